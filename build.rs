@@ -0,0 +1,17 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    compile_proto();
+}
+
+/// Generate the `idt grpc` service from the bundled `.proto`, with a vendored
+/// `protoc` so no system install is needed
+#[cfg(feature = "grpc")]
+fn compile_proto() {
+    let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc is available");
+    let mut config = tonic_prost_build::Config::new();
+    config.protoc_executable(protoc);
+    tonic_prost_build::configure()
+        .compile_with_config(config, &["proto/idt/v1/idt.proto"], &["proto"])
+        .expect("proto/idt/v1/idt.proto compiles");
+}
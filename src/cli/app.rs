@@ -1,8 +1,23 @@
+use crate::cli::commands::completions::{format_candidates, preset_candidates};
+use crate::cli::input::{CsvOptions, InputSource};
+use crate::core::case::CasePolicy;
+use crate::core::db::Database;
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
 use crate::core::id::{GenIdKind, IdKind};
+use crate::core::jsonpath::JsonPath;
+use crate::core::mask::TimestampGranularity;
+use crate::core::predicate::{Op, Predicate};
+use crate::core::rng::RngSource;
+use crate::core::time::BucketSize;
+use crate::ids::{ClockPolicy, LegacyUuidOrder, NodeIdSource, SequencePolicy, UuidStyle};
+use chrono_tz::Tz;
 use clap::builder::ValueHint;
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
+use clap_complete::engine::ArgValueCandidates;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(
@@ -23,11 +38,16 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// Output in JSON format (shorthand for --format json)
+    /// Log detection decisions (-v) and per-ID parse timing (-vv) to stderr.
+    /// Goes before the command: `idt -v inspect ID`
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Output in JSON format (shorthand for --output json)
     #[arg(short, long, global = true)]
     pub json: bool,
 
-    /// Output format (json, yaml, toml)
+    /// Output format
     #[arg(
         short = 'o',
         long = "output",
@@ -35,22 +55,56 @@ pub struct Cli {
         global = true,
         value_enum
     )]
-    pub output_format: Option<OutputFormat>,
+    pub output: Option<OutputMode>,
 
     /// Pretty print JSON output
     #[arg(short, long, global = true)]
     pub pretty: bool,
 
+    /// Write results to this file instead of stdout; it is only replaced
+    /// once the command succeeds
+    #[arg(long, value_name = "PATH", global = true, value_hint = ValueHint::FilePath)]
+    pub output_file: Option<PathBuf>,
+
+    /// Append to --output-file instead of replacing it
+    #[arg(long, global = true, requires = "output_file")]
+    pub append: bool,
+
     /// Disable colored output
     #[arg(long, global = true)]
     pub no_color: bool,
+
+    /// Never show a progress bar for long runs
+    #[arg(long, global = true)]
+    pub no_progress: bool,
+
+    /// Load a custom ID format from a schema file (TOML, YAML, or JSON)
+    #[arg(
+        long,
+        value_name = "PATH",
+        global = true,
+        env = "IDT_SCHEMA",
+        value_hint = ValueHint::FilePath
+    )]
+    pub schema: Option<PathBuf>,
+
+    /// Case of canonical ULIDs and TSIDs: `lower`, `upper`, or per kind
+    /// like `ulid=lower` (comma-separated)
+    #[arg(
+        long,
+        value_name = "[KIND=]CASE",
+        global = true,
+        env = "IDT_CANONICAL_CASE",
+        value_delimiter = ','
+    )]
+    pub canonical_case: Vec<CasePolicy>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Generate new IDs
     #[command(visible_alias = "g")]
-    Gen(GenArgs),
+    Gen(Box<GenArgs>),
 
     /// Analyze and decode ID(s)
     #[command(visible_alias = "i")]
@@ -60,6 +114,9 @@ pub enum Commands {
     #[command(visible_alias = "c")]
     Convert(ConvertArgs),
 
+    /// Rewrite IDs in canonical form, e.g. fix case or add UUID dashes
+    Canon(CanonArgs),
+
     /// Check if input is a valid ID
     #[command(visible_alias = "v")]
     Validate(ValidateArgs),
@@ -71,28 +128,298 @@ pub enum Commands {
     #[command(visible_alias = "s")]
     Sort(SortArgs),
 
+    /// Keep only IDs whose embedded timestamp falls in a time window
+    #[command(visible_alias = "f")]
+    Filter(FilterArgs),
+
+    /// Group IDs into time buckets by their embedded timestamps
+    Bucket(BucketArgs),
+
+    /// Summarize a batch of IDs, and optionally test their random bits
+    Stats(StatsArgs),
+
+    /// Keep only IDs whose decoded components match predicates
+    Grep(GrepArgs),
+
+    /// Remove duplicate IDs, exactly or approximately with a Bloom filter
+    Dedup(DedupArgs),
+
+    /// Merge already-sorted ID files into one sorted stream
+    Merge(MergeArgs),
+
+    /// Union, intersection, or difference of two ID files
+    Set(SetArgs),
+
+    /// Compute the shard or Kafka partition each ID lands on
+    Shard(ShardArgs),
+
+    /// Derive name-based UUIDs (v5/v3) from names or file contents
+    Hash(HashArgs),
+
+    /// Find which candidate name produces a v5/v3 UUID
+    Uuid5Find(Uuid5FindArgs),
+
+    /// Replace IDs with stable, keyed pseudonyms of the same type
+    Mask(MaskArgs),
+
+    /// Replace IDs found in text with placeholders or masked values
+    Redact(RedactArgs),
+
+    /// Walk through an ID field by field, with a bit layout diagram
+    Explain(ExplainArgs),
+
+    /// Explore IDs interactively: decode as you type, and generate
+    Tui(TuiArgs),
+
+    /// Serve Generate, Inspect, and Validate over gRPC, as an ID sidecar
+    Grpc(GrpcArgs),
+
     /// Show information about ID types
     Info(InfoArgs),
 
+    /// Measure generation and parsing throughput on this machine
+    Bench(BenchArgs),
+
+    /// Report what a batch of IDs gives away: MACs, hosts, processes, timing, sequences
+    Audit(AuditArgs),
+
+    /// Check the clock, random source, and process identity that IDs rely on
+    Doctor(DoctorArgs),
+
+    /// Check idt's codecs against published test vectors
+    Conformance(ConformanceArgs),
+
     /// Generate shell completion scripts
     Completions(CompletionsArgs),
 
-    /// Generate man pages
-    Manpage(ManpageArgs),
+    /// Replace this binary with the latest GitHub release
+    SelfUpdate(SelfUpdateArgs),
+
+    /// Generate man pages for idt and each subcommand
+    #[command(hide = true, alias = "manpage")]
+    Man(ManpageArgs),
+}
+
+/// Constraints on auto-detection, shared by commands that parse IDs
+#[derive(Args, Clone, Default)]
+pub struct DetectArgs {
+    /// Only auto-detect these ID types (comma-separated)
+    #[arg(
+        long,
+        value_name = "TYPES",
+        value_delimiter = ',',
+        ignore_case = true,
+        conflicts_with = "id_type"
+    )]
+    pub only: Vec<IdKind>,
+
+    /// Never auto-detect these ID types (comma-separated)
+    #[arg(
+        long,
+        value_name = "TYPES",
+        value_delimiter = ',',
+        ignore_case = true,
+        conflicts_with = "id_type"
+    )]
+    pub exclude: Vec<IdKind>,
+
+    /// Minimum auto-detection confidence (0.0-1.0)
+    #[arg(long, value_name = "CONFIDENCE", default_value_t = 0.0)]
+    pub min_confidence: f32,
+}
+
+impl DetectArgs {
+    pub fn options(&self) -> DetectionOptions {
+        DetectionOptions::default()
+            .with_only(self.only.clone())
+            .with_exclude(self.exclude.clone())
+            .with_min_confidence(self.min_confidence)
+    }
+}
+
+/// How ID input (arguments or stdin) is read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// One ID per argument or line, or per NUL-terminated record
+    #[default]
+    Text,
+    /// JSON or NDJSON files given as arguments, or stdin; IDs are selected with --path
+    Json,
+    /// CSV files given as arguments, or stdin; IDs are taken from --column
+    Csv,
+    /// Parquet files given as arguments; IDs are taken from --column
+    Parquet,
+}
+
+/// Input options shared by commands that read IDs
+#[derive(Args, Clone, Default)]
+pub struct InputArgs {
+    /// Input format (text, json, csv, parquet)
+    #[arg(long = "input", value_name = "FORMAT", value_enum, default_value_t)]
+    pub input_format: InputFormat,
+
+    /// JSONPath to the IDs in JSON input, e.g. '$.items[*].id' (implies --input json)
+    #[arg(long, value_name = "PATH", conflicts_with = "column")]
+    pub path: Option<String>,
+
+    /// CSV or Parquet column holding the IDs, by name or 1-based index (implies --input csv)
+    #[arg(long, value_name = "NAME|INDEX")]
+    pub column: Option<String>,
+
+    /// CSV field delimiter
+    #[arg(long, value_name = "CHAR", default_value_t = ',')]
+    pub delimiter: char,
+
+    /// CSV input has no header row
+    #[arg(long)]
+    pub no_header: bool,
+}
+
+impl InputArgs {
+    /// Resolve where IDs come from
+    pub fn source(&self) -> Result<InputSource> {
+        let format = match (self.input_format, &self.path, &self.column) {
+            (InputFormat::Text, Some(_), _) => InputFormat::Json,
+            (InputFormat::Text, None, Some(_)) => InputFormat::Csv,
+            (format, _, _) => format,
+        };
+        match format {
+            InputFormat::Text => Ok(InputSource::Text),
+            InputFormat::Json => Ok(InputSource::Json(JsonPath::parse(
+                self.path.as_deref().unwrap_or("$"),
+            )?)),
+            InputFormat::Parquet => Ok(InputSource::Parquet(
+                self.column.clone().unwrap_or_else(|| "1".to_string()),
+            )),
+            InputFormat::Csv => {
+                if !self.delimiter.is_ascii() {
+                    return Err(IdtError::InvalidArgument(format!(
+                        "CSV delimiter must be an ASCII character, got '{}'",
+                        self.delimiter
+                    )));
+                }
+                Ok(InputSource::Csv(CsvOptions {
+                    column: self.column.clone().unwrap_or_else(|| "1".to_string()),
+                    delimiter: self.delimiter as u8,
+                    has_header: !self.no_header,
+                }))
+            }
+        }
+    }
+}
+
+/// How plain output ends each record, shared by commands that print one
+/// record per ID
+#[derive(Args, Clone, Default)]
+pub struct RecordArgs {
+    /// End each plain output record with NUL instead of a newline, for `xargs -0`
+    #[arg(long, conflicts_with = "output_delimiter")]
+    pub print0: bool,
+
+    /// End each plain output record with this string instead of a newline
+    #[arg(long, value_name = "STR", allow_hyphen_values = true)]
+    pub output_delimiter: Option<String>,
+}
+
+impl RecordArgs {
+    /// What follows each plain output record
+    pub fn terminator(&self) -> &str {
+        match self.output_delimiter {
+            Some(ref delimiter) => delimiter,
+            None if self.print0 => "\0",
+            None => "\n",
+        }
+    }
+
+    /// Reject `--print0` and `--output-delimiter` alongside structured output
+    pub fn check_plain(&self, output_format: Option<OutputFormat>) -> Result<()> {
+        if output_format.is_some() && (self.print0 || self.output_delimiter.is_some()) {
+            return Err(IdtError::InvalidArgument(
+                "--print0 and --output-delimiter only apply to plain output".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// QR codes for IDs, shared by commands that print one ID per record
+#[derive(Args, Clone, Default)]
+pub struct QrArgs {
+    /// Draw each ID as a QR code in the terminal, below the ID
+    #[arg(long, conflicts_with_all = ["print0", "output_delimiter"])]
+    pub qr: bool,
+
+    /// Also write each ID as a QR code PNG to this file (numbered -1, -2, ...
+    /// when there are several IDs)
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub qr_png: Option<PathBuf>,
+}
+
+impl QrArgs {
+    /// Reject `--qr` alongside structured output
+    pub fn check_plain(&self, output_format: Option<OutputFormat>) -> Result<()> {
+        if output_format.is_some() && self.qr {
+            return Err(IdtError::InvalidArgument(
+                "--qr only applies to plain output; use --qr-png for an image".into(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Parser)]
 pub struct GenArgs {
     /// ID type to generate
-    #[arg(value_name = "TYPE", ignore_case = true)]
-    pub id_type: GenIdKind,
+    #[arg(
+        value_name = "TYPE",
+        ignore_case = true,
+        required_unless_present_any = ["mix", "manifest"]
+    )]
+    pub id_type: Option<GenIdKind>,
+
+    /// Interleave several types by weight, e.g. 'uuidv7:70,ulid:20,ksuid:10'
+    #[arg(
+        long,
+        value_name = "TYPE:WEIGHT,...",
+        value_parser = parse_mix,
+        conflicts_with_all = [
+            "id_type", "format", "bytea", "bound", "state_file", "namespace", "names_file", "with_input",
+            "qr", "qr_png"
+        ]
+    )]
+    pub mix: Option<Mix>,
+
+    /// Print each --mix ID's type before it, separated by a tab
+    #[arg(long)]
+    pub label: bool,
+
+    /// Generate one labelled batch per row of this CSV file (- = stdin), with
+    /// columns type, count, label, and options like prefix or length
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = [
+            "id_type", "mix", "label", "count", "format", "template", "bytea", "db", "copy", "lang",
+            "at", "bound", "state_file", "rate", "uuid_version", "style", "alphabet", "length",
+            "epoch", "preset", "field", "machine_id", "datacenter_id", "prefix", "suffix", "qr",
+            "qr_png"
+        ]
+    )]
+    pub manifest: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub records: RecordArgs,
+
+    #[command(flatten)]
+    pub qr: QrArgs,
 
     /// Number of IDs to generate
     #[arg(short = 'n', long, default_value = "1")]
     pub count: usize,
 
     /// Output format (canonical, hex, base32, base58, base64)
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(format_candidates))]
     pub format: Option<String>,
 
     /// Don't print trailing newline (single ID only)
@@ -103,11 +430,89 @@ pub struct GenArgs {
     #[arg(short = 'T', long)]
     pub template: Option<String>,
 
+    /// Letter case of each generated ID (after --format)
+    #[arg(long, conflicts_with = "bytea")]
+    pub case: Option<Case>,
+
+    /// Append a string to each generated ID (after --format)
+    #[arg(long, allow_hyphen_values = true)]
+    pub suffix: Option<String>,
+
+    /// Frame output as a Postgres `COPY <TABLE> FROM STDIN` block, e.g. 'users (id)'
+    #[arg(long, value_name = "TABLE", conflicts_with_all = ["print0", "output_delimiter", "qr"])]
+    pub copy: Option<String>,
+
+    /// Print IDs as Postgres bytea hex literals (\\x...) escaped for COPY text
+    #[arg(long, conflicts_with = "format")]
+    pub bytea: bool,
+
+    /// Print each ID as a SQL literal for this database's native column type
+    #[arg(long, value_name = "DB", conflicts_with_all = ["format", "bytea", "copy", "case", "style", "mix", "lang"])]
+    pub db: Option<Database>,
+
+    /// Wrap the IDs in a source snippet to paste into code or tests
+    #[arg(long, value_name = "LANG", conflicts_with_all = [
+        "copy", "bytea", "template", "no_newline", "rate", "print0", "output_delimiter", "qr"
+    ])]
+    pub lang: Option<Lang>,
+
+    /// Embed this time instead of now, for any timestamped ID: RFC 3339,
+    /// YYYY-MM-DD, or an offset like -24h
+    #[arg(
+        long,
+        value_name = "TIME",
+        allow_hyphen_values = true,
+        conflicts_with = "state_file"
+    )]
+    pub at: Option<String>,
+
+    /// Emit the smallest or largest ID for --at instead of a random one (ULID, KSUID)
+    #[arg(long, requires = "at")]
+    pub bound: Option<Bound>,
+
+    /// Regenerate any ID already produced in this run (exact up to 1M IDs,
+    /// Bloom filter beyond) and report how many collided
+    #[arg(long, conflicts_with = "bound")]
+    pub check_collisions: bool,
+
+    /// Randomness source: os (system CSPRNG), chacha (fast, OS-seeded), or
+    /// seeded:<n> for reproducible output [default: chacha]
+    #[arg(long, value_name = "SOURCE")]
+    pub rng: Option<RngSource>,
+
+    /// Emit IDs at a steady pace, e.g. 500/s, 30/m, or 2/h
+    #[arg(long, value_name = "N/UNIT", value_parser = parse_rate, conflicts_with_all = ["qr", "qr_png"])]
+    pub rate: Option<Duration>,
+
+    /// Vary each interval of --rate randomly by up to this percentage
+    #[arg(long, value_name = "PERCENT", requires = "rate", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub jitter: Option<u8>,
+
+    /// Keep emitting IDs at --rate for this long (e.g. 30s, 5m) instead of --count
+    #[arg(long = "for", value_name = "DURATION", requires = "rate", conflicts_with = "count", value_parser = parse_for)]
+    pub duration: Option<Duration>,
+
     // UUID-specific options
     /// UUID version (1, 4, 6, 7)
     #[arg(long, value_name = "VERSION")]
     pub uuid_version: Option<u8>,
 
+    /// UUID text style (hyphenated, simple, braced, urn, upper)
+    #[arg(long, value_name = "STYLE", conflicts_with = "format")]
+    pub style: Option<UuidStyle>,
+
+    /// Namespace for uuidv3/uuidv5 (dns, url, oid, x500, or a UUID) [default: dns]
+    #[arg(long, value_name = "NAMESPACE")]
+    pub namespace: Option<String>,
+
+    /// Derive one uuidv3/uuidv5 per line of this file (- = stdin)
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub names_file: Option<PathBuf>,
+
+    /// Print each name before its uuidv3/uuidv5, separated by a tab
+    #[arg(long)]
+    pub with_input: bool,
+
     // NanoID-specific options
     /// Custom alphabet for NanoID
     #[arg(long)]
@@ -123,87 +528,237 @@ pub struct GenArgs {
     pub epoch: Option<String>,
 
     /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
     pub preset: Option<String>,
 
     /// Set a Snowflake field value (e.g., --field shard_id=42)
     #[arg(long, value_name = "NAME=VALUE")]
     pub field: Vec<String>,
 
-    /// Machine/worker ID (0-31)
-    #[arg(long)]
-    pub machine_id: Option<u16>,
+    /// Machine/worker ID: a number (0-31 for Twitter), auto:ip, auto:hostname,
+    /// or env:VAR
+    #[arg(long, value_name = "ID")]
+    pub machine_id: Option<NodeIdSource>,
 
-    /// Datacenter ID (0-31)
-    #[arg(long)]
-    pub datacenter_id: Option<u16>,
+    /// Datacenter ID: a number (0-31), auto:ip, auto:hostname, or env:VAR
+    #[arg(long, value_name = "ID")]
+    pub datacenter_id: Option<NodeIdSource>,
 
-    // TypeID-specific options
-    /// Type prefix for TypeID
-    #[arg(long)]
+    /// Persist Snowflake or ULID generator state across invocations
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub state_file: Option<PathBuf>,
+
+    /// What to do if the clock moves backwards during Snowflake generation
+    #[arg(long, value_name = "POLICY", default_value = "wait")]
+    pub clock_rollback: ClockPolicy,
+
+    /// What to do when a Snowflake timestamp runs out of sequence numbers
+    #[arg(long, value_name = "POLICY", default_value = "wait")]
+    pub sequence_overflow: SequencePolicy,
+
+    /// Prepend a string to each generated ID (after --format); the type
+    /// prefix for TypeID
+    #[arg(long, allow_hyphen_values = true)]
     pub prefix: Option<String>,
 }
 
 #[derive(Parser)]
 pub struct InspectArgs {
-    /// ID(s) to inspect (reads from stdin if omitted)
-    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    /// ID(s) to inspect (reads from stdin if omitted); file paths with --binary
+    #[arg(value_name = "ID", value_hint = ValueHint::Other, allow_negative_numbers = true)]
     pub ids: Vec<String>,
 
     /// Hint the ID type (skip auto-detection)
     #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
     pub id_type: Option<IdKind>,
 
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub records: RecordArgs,
+
+    /// Read raw bytes and decode fixed-width records (16-byte UUIDs unless
+    /// --id-type or --stride say otherwise)
+    #[arg(long, conflicts_with_all = ["input_format", "path", "column"])]
+    pub binary: bool,
+
+    /// Bytes per record for --binary; bytes after the ID in each record are ignored
+    #[arg(long, value_name = "BYTES", requires = "binary")]
+    pub stride: Option<usize>,
+
     /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
     #[arg(long, value_hint = ValueHint::Other)]
     pub epoch: Option<String>,
 
     /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
     pub preset: Option<String>,
 
+    /// Show times in this IANA time zone (e.g. Asia/Seoul) instead of the local zone
+    #[arg(long, value_name = "ZONE", value_hint = ValueHint::Other)]
+    pub tz: Option<Tz>,
+
+    /// Show a hexdump with each byte annotated by the fields it holds
+    #[arg(long)]
+    pub xxd: bool,
+
+    /// Columns for --output table (comma-separated), e.g.
+    /// input,type,timestamp,machine_id [default: type,canonical,timestamp,version]
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Read each input as a base64 BSON binary UUID (subtype 3) stored by this
+    /// legacy MongoDB driver and un-shuffle it
+    #[arg(long, value_name = "DRIVER", conflicts_with_all = ["binary", "preset", "epoch"])]
+    pub uuid_legacy: Option<LegacyUuidOrder>,
+
     /// Only show errors (for validation)
     #[arg(short, long)]
     pub quiet: bool,
 }
 
+#[derive(Parser)]
+pub struct CanonArgs {
+    /// ID(s) to canonicalize (reads from stdin if not provided)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Report each rewritten ID on stderr
+    #[arg(short, long)]
+    pub annotate: bool,
+
+    /// Write nothing; fail if any ID is not already canonical
+    #[arg(long)]
+    pub check: bool,
+
+    /// Pass lines that aren't IDs through unchanged instead of failing
+    #[arg(long)]
+    pub keep_invalid: bool,
+}
+
 #[derive(Parser)]
 pub struct ConvertArgs {
+    // The first ID on its own, so that it may be a negative `--from
+    // java-longs` pair like `-1,-2`. clap only lets a list positional take
+    // hyphenated values by also swallowing every flag that follows it.
+    #[arg(value_name = "ID", hide = true, allow_hyphen_values = true)]
+    pub first_id: Option<String>,
+
     /// ID(s) to convert (reads from stdin if omitted)
-    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    #[arg(value_name = "ID", value_hint = ValueHint::Other, allow_negative_numbers = true)]
     pub ids: Vec<String>,
 
     /// Source ID type (auto-detect if omitted)
     #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
     pub id_type: Option<IdKind>,
 
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub records: RecordArgs,
+
+    #[command(flatten)]
+    pub qr: QrArgs,
+
     /// Target format
-    #[arg(short, long, value_name = "FORMAT", value_hint = ValueHint::Other)]
+    #[arg(
+        short,
+        long,
+        value_name = "FORMAT",
+        add = ArgValueCandidates::new(format_candidates)
+    )]
     pub format: Option<String>,
 
-    /// Convert to different ID type (if compatible)
-    #[arg(long, value_name = "TYPE", value_hint = ValueHint::Other)]
-    pub to: Option<String>,
+    /// UUID text style (hyphenated, simple, braced, urn, upper)
+    #[arg(long, value_name = "STYLE", conflicts_with = "format")]
+    pub style: Option<UuidStyle>,
+
+    /// Read each input as a base64 BSON binary UUID (subtype 3) stored by this
+    /// legacy MongoDB driver and un-shuffle it
+    #[arg(long, value_name = "DRIVER")]
+    pub uuid_legacy: Option<LegacyUuidOrder>,
+
+    /// Read inputs in this form instead of as ID text (java-longs)
+    #[arg(long, value_name = "FORMAT", conflicts_with = "uuid_legacy")]
+    pub from: Option<FromFormat>,
+
+    /// Convert to a different ID type: uuidv7 from a v1 or v6 UUID (lossy)
+    #[arg(long, value_name = "TYPE", ignore_case = true)]
+    pub to: Option<IdKind>,
+
+    /// Secret key that makes `--to` deterministic (HMAC-SHA256 of the input)
+    #[arg(long, requires = "to")]
+    pub key: Option<String>,
+
+    /// Print each ID as a SQL literal for this database's native column type
+    #[arg(long, value_name = "DB", conflicts_with_all = ["format", "style", "case", "uppercase", "lowercase"])]
+    pub db: Option<Database>,
 
-    /// Uppercase output
+    /// Letter case of the output
+    #[arg(long, conflicts_with_all = ["uppercase", "lowercase"])]
+    pub case: Option<Case>,
+
+    /// Uppercase output (same as --case upper)
     #[arg(short = 'U', long)]
     pub uppercase: bool,
 
-    /// Lowercase output
+    /// Lowercase output (same as --case lower)
     #[arg(short = 'L', long)]
     pub lowercase: bool,
 }
 
+impl ConvertArgs {
+    /// Every ID given on the command line, in order
+    pub fn all_ids(&self) -> Vec<String> {
+        self.first_id.iter().chain(&self.ids).cloned().collect()
+    }
+
+    /// The requested output case, from `--case` or `-U`/`-L`
+    pub fn case(&self) -> Option<Case> {
+        if self.uppercase {
+            Some(Case::Upper)
+        } else if self.lowercase {
+            Some(Case::Lower)
+        } else {
+            self.case
+        }
+    }
+}
+
 #[derive(Parser)]
 pub struct ValidateArgs {
     /// ID(s) to validate
-    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    #[arg(value_name = "ID", value_hint = ValueHint::Other, allow_negative_numbers = true)]
     pub ids: Vec<String>,
 
     /// Expected ID type (any valid if omitted)
     #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
     pub id_type: Option<IdKind>,
 
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
     /// No output, only exit code
     #[arg(short, long)]
     pub quiet: bool,
@@ -211,6 +766,31 @@ pub struct ValidateArgs {
     /// Strict validation (reject non-canonical forms)
     #[arg(long)]
     pub strict: bool,
+
+    /// Rule the decoded components must satisfy, e.g. 'timestamp >= 2022-01-01'
+    /// or 'machine_id in 0..8' (repeatable, all must hold)
+    #[arg(long = "assert", value_name = "EXPR")]
+    pub assertions: Vec<Predicate>,
+
+    /// Reject IDs minted longer ago than this, e.g. 30d or 12h
+    #[arg(long, value_name = "DURATION")]
+    pub max_age: Option<String>,
+
+    /// Reject IDs minted before this time (RFC 3339, YYYY-MM-DD, now, or e.g. -7d)
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub not_before: Option<String>,
+
+    /// Reject IDs minted after this time, e.g. now to catch clock skew
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub not_after: Option<String>,
+
+    /// Fail if any ID appears more than once, compared by canonical form
+    #[arg(long)]
+    pub expect_unique: bool,
+
+    /// Fail unless the input holds exactly this many distinct valid IDs
+    #[arg(long, value_name = "N")]
+    pub expect_count: Option<usize>,
 }
 
 #[derive(Parser)]
@@ -226,6 +806,50 @@ pub struct CompareArgs {
     /// ID type (auto-detect if omitted)
     #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
     pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+}
+
+#[derive(Parser)]
+pub struct ExplainArgs {
+    /// ID(s) to explain
+    #[arg(value_name = "ID", required = true, value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Explain as this type instead of the detected one
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct TuiArgs {
+    /// ID to start with
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub id: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct GrpcArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:50051", value_name = "ADDR")]
+    pub listen: std::net::SocketAddr,
+
+    /// Also serve Prometheus metrics over HTTP at this address, under /metrics
+    #[arg(long, value_name = "ADDR")]
+    pub metrics: Option<std::net::SocketAddr>,
+
+    /// Print the bundled .proto and exit, to generate clients from
+    #[arg(long)]
+    pub proto: bool,
 }
 
 #[derive(Parser)]
@@ -233,6 +857,11 @@ pub struct InfoArgs {
     /// ID type to get information about (list all if omitted)
     #[arg(value_name = "TYPE", ignore_case = true)]
     pub id_type: Option<IdKind>,
+
+    /// List well-known UUIDs (RFC namespaces, GPT partition types, ...),
+    /// e.g. `idt info uuid --well-known`
+    #[arg(long, requires = "id_type")]
+    pub well_known: bool,
 }
 
 #[derive(Parser)]
@@ -240,6 +869,30 @@ pub struct CompletionsArgs {
     /// Shell to generate completions for
     #[arg(value_name = "SHELL")]
     pub shell: Shell,
+
+    /// Emit a script that asks idt for candidates on each <TAB>, so ID
+    /// types, formats, and presets complete too
+    #[arg(long)]
+    pub dynamic: bool,
+}
+
+#[derive(Parser)]
+pub struct SelfUpdateArgs {
+    /// Only report whether a newer release exists
+    #[arg(long)]
+    pub check: bool,
+
+    /// Install this release instead of the latest (e.g. 0.1.20)
+    #[arg(long, value_name = "VERSION")]
+    pub version: Option<String>,
+
+    /// Reinstall even if the release is not newer
+    #[arg(long)]
+    pub force: bool,
+
+    /// Also check the release's build provenance with `gh attestation verify`
+    #[arg(long)]
+    pub verify_attestation: bool,
 }
 
 #[derive(Parser)]
@@ -259,6 +912,9 @@ pub struct SortArgs {
     #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
     pub id_type: Option<IdKind>,
 
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
     /// Sort in descending order (newest first)
     #[arg(short, long)]
     pub reverse: bool,
@@ -272,7 +928,7 @@ pub struct SortArgs {
     pub epoch: Option<String>,
 
     /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
     pub preset: Option<String>,
 
     /// Policy for IDs without timestamps: skip (default), error, end
@@ -280,15 +936,807 @@ pub struct SortArgs {
     pub on_unsortable: UnsortablePolicy,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum, Default)]
-pub enum UnsortablePolicy {
-    /// Skip unsortable IDs with a warning to stderr
-    #[default]
-    Skip,
-    /// Fail with an error if any ID is unsortable
-    Error,
-    /// Append unsortable IDs after sorted ones
-    End,
+#[derive(Parser)]
+pub struct FilterArgs {
+    /// ID(s) to filter (reads from stdin if omitted)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    /// Keep IDs at or after this time (RFC 3339, YYYY-MM-DD, now, or e.g. -24h)
+    #[arg(
+        long,
+        value_name = "TIME",
+        allow_hyphen_values = true,
+        required_unless_present = "before"
+    )]
+    pub after: Option<String>,
+
+    /// Keep IDs before this time (RFC 3339, YYYY-MM-DD, now, or e.g. -1h)
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub before: Option<String>,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct BucketArgs {
+    /// ID(s) to bucket (reads from stdin if omitted)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Bucket width: minute, hour, day (default), week, month, or a duration like 15m
+    #[arg(long, value_name = "SIZE", default_value = "day")]
+    pub by: BucketSize,
+
+    /// Print the number of IDs per bucket instead of each ID
+    #[arg(short, long)]
+    pub count: bool,
+
+    /// With --count, include empty buckets between the first and last
+    #[arg(long, requires = "count")]
+    pub fill: bool,
+
+    /// Draw a bar chart of IDs per bucket (implies --count --fill)
+    #[arg(long)]
+    pub histogram: bool,
+
+    /// Width of the longest histogram bar, in columns
+    #[arg(
+        long,
+        value_name = "COLS",
+        default_value_t = 50,
+        requires = "histogram"
+    )]
+    pub width: usize,
+
+    /// Draw histogram bars with `#` instead of Unicode blocks
+    #[arg(long, requires = "histogram")]
+    pub ascii: bool,
+
+    /// Align buckets to this IANA time zone (e.g. Asia/Seoul) instead of UTC
+    #[arg(long, value_name = "ZONE", value_hint = ValueHint::Other)]
+    pub tz: Option<Tz>,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct StatsArgs {
+    /// ID(s) to summarize (reads from stdin if omitted)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Test the random part of ULIDs, UUIDv4/v7, and KSUIDs for bias, repeats,
+    /// and serial correlation
+    #[arg(long)]
+    pub entropy: bool,
+
+    /// Draw a bar chart of ID creation times over the batch
+    #[arg(long)]
+    pub histogram: bool,
+
+    /// Histogram bucket width: minute, hour, day, week, month, or a duration
+    /// like 15m (default: fit to the batch's time range)
+    #[arg(long, value_name = "SIZE", requires = "histogram")]
+    pub by: Option<BucketSize>,
+
+    /// Width of the longest histogram bar, in columns
+    #[arg(
+        long,
+        value_name = "COLS",
+        default_value_t = 50,
+        requires = "histogram"
+    )]
+    pub width: usize,
+
+    /// Draw histogram bars with `#` instead of Unicode blocks
+    #[arg(long, requires = "histogram")]
+    pub ascii: bool,
+
+    /// Align histogram buckets to this IANA time zone (e.g. Asia/Seoul) instead of UTC
+    #[arg(
+        long,
+        value_name = "ZONE",
+        value_hint = ValueHint::Other,
+        requires = "histogram"
+    )]
+    pub tz: Option<Tz>,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct GrepArgs {
+    /// ID(s) to match (reads from stdin if omitted)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(
+        short = 't',
+        long,
+        alias = "type",
+        value_name = "TYPE",
+        ignore_case = true
+    )]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    /// Component predicate, e.g. 'machine_id=7' or 'sequence>=100' (repeatable, all must match)
+    #[arg(short = 'w', long = "where", value_name = "EXPR")]
+    pub predicates: Vec<Predicate>,
+
+    /// Shorthand for --where machine_id=N
+    #[arg(long, value_name = "N")]
+    pub machine_id: Option<u64>,
+
+    /// Shorthand for --where datacenter_id=N
+    #[arg(long, value_name = "N")]
+    pub datacenter_id: Option<u64>,
+
+    /// Shorthand for --where prefix=PREFIX (TypeID)
+    #[arg(long)]
+    pub prefix: Option<String>,
+
+    /// Keep IDs that do not match instead
+    #[arg(short = 'v', long)]
+    pub invert_match: bool,
+
+    /// Print only the number of matching IDs
+    #[arg(short, long)]
+    pub count: bool,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+impl GrepArgs {
+    /// All predicates, with the shorthand flags expanded
+    pub fn all_predicates(&self) -> Vec<Predicate> {
+        let mut predicates = self.predicates.clone();
+        if let Some(id) = self.machine_id {
+            predicates.push(Predicate::new("machine_id", Op::Eq, &id.to_string()));
+        }
+        if let Some(id) = self.datacenter_id {
+            predicates.push(Predicate::new("datacenter_id", Op::Eq, &id.to_string()));
+        }
+        if let Some(ref prefix) = self.prefix {
+            predicates.push(Predicate::new("prefix", Op::Eq, prefix));
+        }
+        predicates
+    }
+}
+
+#[derive(Parser)]
+pub struct HashArgs {
+    /// Name(s) to hash (reads one name per line from stdin if omitted)
+    #[arg(value_name = "NAME", value_hint = ValueHint::Other)]
+    pub names: Vec<String>,
+
+    /// Namespace (dns, url, oid, x500, or a UUID)
+    #[arg(
+        long = "ns",
+        visible_alias = "namespace",
+        value_name = "NAMESPACE",
+        default_value = "dns"
+    )]
+    pub namespace: String,
+
+    /// Hash the contents of a file instead of a name (- = stdin)
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub file: Vec<PathBuf>,
+
+    /// Read names from a file, one per line (- = stdin)
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub names_file: Option<PathBuf>,
+
+    /// Print each input before its UUID, separated by a tab
+    #[arg(long)]
+    pub with_input: bool,
+
+    /// UUID version (3 = MD5, 5 = SHA-1)
+    #[arg(long, value_name = "VERSION", default_value_t = 5)]
+    pub uuid_version: u8,
+
+    /// Output format (canonical, hex, base32, base58, base64)
+    #[arg(short, long, add = ArgValueCandidates::new(format_candidates))]
+    pub format: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct Uuid5FindArgs {
+    /// v5 or v3 UUID(s) to find the name of
+    #[arg(value_name = "UUID", required = true, value_hint = ValueHint::Other)]
+    pub uuids: Vec<String>,
+
+    /// Candidate names, one per line (- = stdin)
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub candidates_file: PathBuf,
+
+    /// Namespace to try (dns, url, oid, x500, or a UUID; repeatable)
+    /// [default: dns, url, oid, and x500]
+    #[arg(long = "ns", visible_alias = "namespace", value_name = "NAMESPACE")]
+    pub namespaces: Vec<String>,
+}
+
+#[derive(Parser)]
+pub struct MaskArgs {
+    /// ID(s) to mask (reads from stdin if omitted)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Secret key for the keyed derivation
+    #[arg(short, long, env = "IDT_MASK_KEY", hide_env_values = true)]
+    pub key: String,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    /// Keep the embedded timestamp, truncated to this granularity
+    #[arg(long, value_name = "GRANULARITY")]
+    pub keep_timestamp: Option<TimestampGranularity>,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct RedactArgs {
+    /// File(s) to redact (reads from stdin if omitted)
+    #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub files: Vec<PathBuf>,
+
+    /// Edit files in place instead of writing to stdout
+    #[arg(short, long, requires = "files")]
+    pub in_place: bool,
+
+    /// Only redact these ID types (repeatable)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Vec<IdKind>,
+
+    /// Never redact these ID types (comma-separated)
+    #[arg(long, value_name = "TYPES", value_delimiter = ',', ignore_case = true)]
+    pub exclude: Vec<IdKind>,
+
+    /// Placeholder text ({} = ID type label)
+    #[arg(long, default_value = "[{}]")]
+    pub placeholder: String,
+
+    /// Replace IDs with masked values instead of placeholders
+    #[arg(short, long)]
+    pub mask: bool,
+
+    /// Secret key for --mask
+    #[arg(short, long, env = "IDT_MASK_KEY", hide_env_values = true)]
+    pub key: Option<String>,
+
+    /// Minimum detection confidence (0.0-1.0)
+    #[arg(long, default_value_t = crate::core::scan::DEFAULT_MIN_CONFIDENCE)]
+    pub min_confidence: f32,
+
+    /// Print a summary of replacements to stderr
+    #[arg(short, long)]
+    pub summary: bool,
+}
+
+#[derive(Parser)]
+pub struct DedupArgs {
+    /// File(s) of IDs, one per line (reads from stdin if omitted)
+    #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub files: Vec<PathBuf>,
+
+    /// Use a fixed-size Bloom filter instead of remembering every ID
+    #[arg(short, long)]
+    pub approximate: bool,
+
+    /// Expected number of distinct IDs, e.g. 500M (sizes the Bloom filter)
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "10M",
+        value_parser = parse_quantity,
+        requires = "approximate"
+    )]
+    pub expected: u64,
+
+    /// Target false-positive rate of the Bloom filter
+    #[arg(
+        long,
+        value_name = "RATE",
+        default_value_t = 1e-6,
+        requires = "approximate"
+    )]
+    pub fp_rate: f64,
+
+    /// Print duplicate counts to stderr
+    #[arg(short, long)]
+    pub stats: bool,
+}
+
+#[derive(Parser)]
+pub struct MergeArgs {
+    /// Sorted file(s) of IDs, one per line (`-` reads stdin)
+    #[arg(value_name = "FILE", required = true, value_hint = ValueHint::FilePath)]
+    pub files: Vec<PathBuf>,
+
+    /// Order the inputs are sorted by: bytes (default) or timestamp
+    #[arg(long, value_name = "KEY", default_value = "bytes")]
+    pub by: MergeKey,
+
+    /// Inputs are sorted in descending order (newest or largest first)
+    #[arg(short, long)]
+    pub reverse: bool,
+
+    /// Drop IDs equal to the previous output line
+    #[arg(short, long)]
+    pub unique: bool,
+
+    /// Hint the ID type when merging by timestamp (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct SetArgs {
+    /// Operation: union, intersect, or diff (IDs in the first file but not the second)
+    #[arg(value_name = "OP")]
+    pub op: SetOp,
+
+    /// First file of IDs, one per line (`-` reads stdin)
+    #[arg(value_name = "A", value_hint = ValueHint::FilePath)]
+    pub a: PathBuf,
+
+    /// Second file of IDs, one per line (`-` reads stdin)
+    #[arg(value_name = "B", value_hint = ValueHint::FilePath)]
+    pub b: PathBuf,
+
+    /// What makes two IDs equal: canonical text (default) or decoded bytes
+    #[arg(long, value_name = "KEY", default_value = "canonical")]
+    pub by: SetKey,
+
+    /// Hash in memory, merge sorted inputs, or pick by file size (default)
+    #[arg(long, value_name = "STRATEGY", default_value = "auto")]
+    pub strategy: SetStrategy,
+
+    /// Print the number of resulting IDs instead of the IDs
+    #[arg(short, long)]
+    pub count: bool,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+}
+
+#[derive(Parser)]
+pub struct ShardArgs {
+    /// ID(s) to assign (reads from stdin if not provided)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Number of shards or partitions
+    #[arg(short = 'n', long, value_name = "N", required = true)]
+    pub shards: u32,
+
+    /// Hash function: murmur3 (default), xxhash, jump, or kafka
+    #[arg(long, value_name = "HASH", default_value = "murmur3")]
+    pub hash: ShardHash,
+
+    /// Bytes to hash: canonical text (default), text as given, or decoded bytes
+    #[arg(long, value_name = "KEY", default_value = "canonical")]
+    pub key: ShardKey,
+
+    /// Hash seed for murmur3, xxhash, and jump (default: 0)
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u32>,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+}
+
+/// Weighted ID types for `gen --mix`
+#[derive(Debug, Clone)]
+pub struct Mix(pub Vec<(GenIdKind, u32)>);
+
+/// Parse `TYPE:WEIGHT,...` for `--mix`; a missing weight counts as 1
+fn parse_mix(s: &str) -> std::result::Result<Mix, String> {
+    let mut parts = Vec::new();
+    for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, weight) = entry.split_once(':').unwrap_or((entry, "1"));
+        let kind = GenIdKind::from_str(name.trim(), true)
+            .map_err(|_| format!("unknown ID type '{}'", name.trim()))?;
+        if matches!(kind, GenIdKind::UuidV3 | GenIdKind::UuidV5) {
+            return Err(format!(
+                "{} is derived from names and can't be mixed",
+                name.trim()
+            ));
+        }
+        if parts.iter().any(|&(k, _)| k == kind) {
+            return Err(format!("{} is listed more than once", name.trim()));
+        }
+        let weight = match weight.trim().parse::<u32>() {
+            Ok(w) if w > 0 => w,
+            _ => {
+                return Err(format!(
+                    "invalid weight '{}' for {}",
+                    weight.trim(),
+                    name.trim()
+                ));
+            }
+        };
+        parts.push((kind, weight));
+    }
+    if parts.is_empty() {
+        return Err("expected TYPE:WEIGHT pairs, e.g. uuidv7:70,ulid:30".to_string());
+    }
+    Ok(Mix(parts))
+}
+
+/// Parse a rate like `500/s` (or plain `500`) into the interval between IDs
+fn parse_rate(s: &str) -> std::result::Result<Duration, String> {
+    let (count, unit) = s.trim().split_once('/').unwrap_or((s.trim(), "s"));
+    let per = match unit {
+        "s" => Duration::from_secs(1),
+        "m" => Duration::from_secs(60),
+        "h" => Duration::from_secs(3600),
+        _ => return Err(format!("unknown unit '{}' (use s, m, or h)", unit)),
+    };
+    match count.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(per / n),
+        _ => Err(format!("invalid rate '{}': expected e.g. 500/s", s)),
+    }
+}
+
+fn parse_for(s: &str) -> std::result::Result<Duration, String> {
+    crate::core::time::parse_duration(s).map_err(|e| e.to_string())
+}
+
+/// Parse a count with an optional K, M, or B (billion) suffix, e.g. `500M`
+fn parse_quantity(s: &str) -> std::result::Result<u64, String> {
+    let s = s.trim().replace('_', "");
+    let (number, scale) = match s.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => {
+            let scale = match c.to_ascii_uppercase() {
+                'K' => 1e3,
+                'M' => 1e6,
+                'B' | 'G' => 1e9,
+                _ => return Err(format!("unknown suffix '{}' (use K, M, or B)", c)),
+            };
+            (&s[..i], scale)
+        }
+        _ => (s.as_str(), 1.0),
+    };
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid count '{}'", s))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("invalid count '{}'", s));
+    }
+    Ok((value * scale).round() as u64)
+}
+
+#[derive(Parser)]
+pub struct BenchArgs {
+    /// ID type(s) to benchmark (all generatable types if omitted)
+    #[arg(value_name = "TYPE", ignore_case = true)]
+    pub id_types: Vec<GenIdKind>,
+
+    /// Operation(s) to measure
+    #[arg(long, default_value = "all")]
+    pub op: BenchOp,
+
+    /// Measurement time per type and operation, in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 1000)]
+    pub duration: u64,
+
+    /// Warmup time before each measurement, in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 200)]
+    pub warmup: u64,
+
+    /// Number of threads running the operation concurrently
+    #[arg(long, default_value_t = 1)]
+    pub threads: usize,
+
+    /// Parse with auto-detection instead of a type hint
+    #[arg(long)]
+    pub detect: bool,
+}
+
+#[derive(Parser)]
+pub struct AuditArgs {
+    /// ID(s) to audit (reads from stdin if omitted)
+    #[arg(value_name = "ID", value_hint = ValueHint::Other)]
+    pub ids: Vec<String>,
+
+    /// Exit with an error if any finding is at least this severe
+    #[arg(long, value_name = "SEVERITY")]
+    pub fail_on: Option<Severity>,
+
+    /// Hint the ID type (skip auto-detection)
+    #[arg(short = 't', long, value_name = "TYPE", ignore_case = true)]
+    pub id_type: Option<IdKind>,
+
+    #[command(flatten)]
+    pub detect: DetectArgs,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Epoch for Snowflake IDs (discord, twitter, or milliseconds since Unix epoch)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub epoch: Option<String>,
+
+    /// Snowflake preset (twitter, discord, instagram, sonyflake, mastodon)
+    #[arg(long, add = ArgValueCandidates::new(preset_candidates))]
+    pub preset: Option<String>,
+}
+
+/// How much an audit finding gives away, least to most
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth knowing, but nothing leaks
+    Info,
+    /// Coarse metadata, such as creation time to the second
+    Low,
+    /// Infrastructure or timing detail an outsider could use
+    Medium,
+    /// Hardware identity, such as a MAC address
+    High,
+}
+
+#[derive(Parser)]
+pub struct DoctorArgs {
+    /// Exit with an error on warnings as well as failures
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Parser)]
+pub struct ConformanceArgs {
+    /// Only check vectors of these types (all if omitted)
+    #[arg(value_name = "TYPE", ignore_case = true)]
+    pub id_types: Vec<IdKind>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum BenchOp {
+    /// Generation and parsing
+    #[default]
+    All,
+    /// Generation only
+    Gen,
+    /// Parsing only
+    Parse,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default)]
+pub enum UnsortablePolicy {
+    /// Skip unsortable IDs with a warning to stderr
+    #[default]
+    Skip,
+    /// Fail with an error if any ID is unsortable
+    Error,
+    /// Append unsortable IDs after sorted ones
+    End,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum MergeKey {
+    /// Compare IDs as raw text, like `sort` with `LC_ALL=C`
+    #[default]
+    Bytes,
+    /// Compare embedded timestamps
+    Timestamp,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum SetOp {
+    /// IDs in either file
+    Union,
+    /// IDs in both files
+    Intersect,
+    /// IDs in the first file but not the second
+    Diff,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum SetKey {
+    /// Canonical text, so case and separators don't matter
+    #[default]
+    Canonical,
+    /// Decoded bytes, so the same value in different ID formats matches
+    Bytes,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum SetStrategy {
+    /// Hash when the held input is small enough, else merge
+    #[default]
+    Auto,
+    /// Hold one or both inputs in memory; any order
+    Hash,
+    /// Stream inputs sorted by canonical form in constant memory
+    Merge,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum ShardHash {
+    /// MurmurHash3 (32-bit), modulo the shard count
+    #[default]
+    Murmur3,
+    /// XXH64, modulo the shard count
+    Xxhash,
+    /// Jump consistent hash of XXH64: adding a shard moves the fewest IDs
+    Jump,
+    /// Kafka's default partitioner: murmur2 of the key bytes
+    Kafka,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum ShardKey {
+    /// The ID's canonical text, so case and separators don't matter
+    #[default]
+    Canonical,
+    /// The input exactly as given; any string works
+    Text,
+    /// The ID's decoded bytes
+    Bytes,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum Case {
+    Upper,
+    Lower,
+}
+
+impl Case {
+    pub fn apply(self, s: &mut str) {
+        match self {
+            Case::Upper => s.make_ascii_uppercase(),
+            Case::Lower => s.make_ascii_lowercase(),
+        }
+    }
+}
+
+/// Representation read by `convert --from` in place of an ID's text form
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum FromFormat {
+    /// `mostSigBits,leastSigBits` of a `java.util.UUID`, as signed decimals
+    JavaLongs,
+}
+
+/// Language of the snippet written by `gen --lang`
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum Lang {
+    Rust,
+    Go,
+    Python,
+    #[value(alias = "typescript")]
+    Ts,
+    Java,
+    Sql,
+}
+
+/// End of the ID range sharing one timestamp
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum Bound {
+    Min,
+    Max,
+}
+
+/// What `--output` selects: a command's own text, or a structured format
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum OutputMode {
+    /// The command's usual text output (the default)
+    #[value(alias = "plain")]
+    Human,
+    Json,
+    Yaml,
+    Toml,
+    Csv,
+    Tsv,
+    Table,
+    Cbor,
+    #[value(alias = "messagepack")]
+    Msgpack,
+}
+
+impl OutputMode {
+    /// The structured format to write, or `None` for the usual text output
+    pub fn structured(self) -> Option<OutputFormat> {
+        match self {
+            OutputMode::Human => None,
+            OutputMode::Json => Some(OutputFormat::Json),
+            OutputMode::Yaml => Some(OutputFormat::Yaml),
+            OutputMode::Toml => Some(OutputFormat::Toml),
+            OutputMode::Csv => Some(OutputFormat::Csv),
+            OutputMode::Tsv => Some(OutputFormat::Tsv),
+            OutputMode::Table => Some(OutputFormat::Table),
+            OutputMode::Cbor => Some(OutputFormat::Cbor),
+            OutputMode::Msgpack => Some(OutputFormat::Msgpack),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
@@ -296,4 +1744,15 @@ pub enum OutputFormat {
     Json,
     Yaml,
     Toml,
+    /// One row per entry, with nested fields as dotted column names
+    Csv,
+    /// Like CSV, separated by tabs
+    Tsv,
+    /// Like CSV, with columns padded to line up in a terminal
+    Table,
+    /// Binary CBOR (RFC 8949) of the same structure as JSON
+    Cbor,
+    /// Binary MessagePack of the same structure as JSON
+    #[value(alias = "messagepack")]
+    Msgpack,
 }
@@ -0,0 +1,173 @@
+//! Output files that only appear once they are complete.
+//!
+//! Everything is written to a hidden temp file next to the target, which is
+//! renamed over the target (or appended to it) on [`AtomicFile::commit`]. A
+//! run that is interrupted or fails leaves the target untouched.
+
+use crate::core::error::Result;
+use std::fs::{OpenOptions, Permissions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+pub struct AtomicFile {
+    target: PathBuf,
+    /// Randomly named and created exclusively, so nothing already at a
+    /// guessable path (such as a symlink) is followed; removed when dropped
+    writer: BufWriter<NamedTempFile>,
+    append: bool,
+}
+
+impl AtomicFile {
+    /// Start writing output destined for `target`
+    pub fn create(target: &Path, append: bool) -> Result<Self> {
+        let name = target
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+        // Same directory as the target so the final rename cannot cross filesystems
+        let dir = match target.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let prefix = format!(".{}.", name.to_string_lossy());
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(&prefix).suffix(".tmp");
+        // Like a newly created file, rather than tempfile's owner-only default
+        #[cfg(unix)]
+        builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+        Ok(Self {
+            target: target.to_path_buf(),
+            writer: BufWriter::new(builder.tempfile_in(dir)?),
+            append,
+        })
+    }
+
+    /// Give the output `permissions`, before it takes the target's place
+    pub fn set_permissions(&self, permissions: Permissions) -> Result<()> {
+        Ok(self
+            .writer
+            .get_ref()
+            .as_file()
+            .set_permissions(permissions)?)
+    }
+
+    /// Publish the output: replace the target, or append to it with `--append`
+    pub fn commit(self) -> Result<()> {
+        let temp = self.writer.into_inner().map_err(|e| e.into_error())?;
+        if self.append {
+            let mut target = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.target)?;
+            io::copy(&mut temp.reopen()?, &mut target)?;
+            target.flush()?;
+        } else {
+            temp.as_file().sync_all()?;
+            temp.persist(&self.target).map_err(|e| e.error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("idt-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_commit_replaces_target() {
+        let dir = temp_dir("atomic-replace");
+        let target = dir.join("ids.txt");
+        fs::write(&target, "old\n").unwrap();
+
+        let mut file = AtomicFile::create(&target, false).unwrap();
+        writeln!(file, "new").unwrap();
+        // Nothing is visible until the commit
+        assert_eq!(fs::read_to_string(&target).unwrap(), "old\n");
+        file.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new\n");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_commit_appends() {
+        let dir = temp_dir("atomic-append");
+        let target = dir.join("ids.txt");
+        for line in ["a", "b"] {
+            let mut file = AtomicFile::create(&target, true).unwrap();
+            writeln!(file, "{}", line).unwrap();
+            file.commit().unwrap();
+        }
+        assert_eq!(fs::read_to_string(&target).unwrap(), "a\nb\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_drop_discards_output() {
+        let dir = temp_dir("atomic-drop");
+        let target = dir.join("ids.txt");
+        let mut file = AtomicFile::create(&target, false).unwrap();
+        writeln!(file, "partial").unwrap();
+        drop(file);
+
+        assert!(!target.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permissions_set_before_commit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("atomic-permissions");
+        let target = dir.join("ids.txt");
+        let mut file = AtomicFile::create(&target, false).unwrap();
+        writeln!(file, "new").unwrap();
+        file.set_permissions(fs::Permissions::from_mode(0o640))
+            .unwrap();
+        file.commit().unwrap();
+
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_does_not_follow_symlinks() {
+        let dir = temp_dir("atomic-symlink");
+        let victim = dir.join("victim");
+        fs::write(&victim, "keep\n").unwrap();
+        // The old fixed temp name for this process
+        let guessed = dir.join(format!(".ids.txt.{}.tmp", std::process::id()));
+        std::os::unix::fs::symlink(&victim, &guessed).unwrap();
+
+        let target = dir.join("ids.txt");
+        let mut file = AtomicFile::create(&target, false).unwrap();
+        writeln!(file, "new").unwrap();
+        file.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "keep\n");
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
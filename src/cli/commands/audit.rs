@@ -0,0 +1,652 @@
+use crate::cli::app::{AuditArgs, OutputFormat, Severity};
+use crate::cli::input::{stream_ids, stream_total};
+use crate::cli::output::{stdout, write_output};
+use crate::cli::progress::Progress;
+use crate::core::components::Components;
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId, Timestamp};
+use crate::ids::snowflake_id::SnowflakeLayout;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// Largest counter step between neighbouring IDs that still counts as
+/// guessable: an attacker trying this many values finds the next ID
+const MAX_STEP: u128 = 16;
+
+/// Values listed per finding before the rest are summarized
+const MAX_EXAMPLES: usize = 5;
+
+pub fn execute(
+    args: &AuditArgs,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    let snowflake_layout = if args.preset.is_some() || args.epoch.is_some() {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+    let parser = Parser {
+        type_hint: args.id_type,
+        detection: args.detect.options(),
+        snowflake_layout,
+    };
+
+    let source = args.input.source()?;
+    let ids = stream_ids(&args.ids, &source)?;
+    let mut progress = Progress::new("audit", stream_total(&ids), false);
+    let mut audit = Audit::default();
+
+    for sourced in ids {
+        let id = sourced?.id;
+        progress.tick(id.len() + 1);
+        match parser.parse(&id) {
+            Ok(parsed) => audit.observe(parsed.as_ref()),
+            Err(e) => {
+                audit.unparsed += 1;
+                progress.clear();
+                eprintln!("Warning: skipping '{}' — failed to parse: {}", id, e);
+            }
+        }
+    }
+    progress.clear();
+
+    if audit.parsed + audit.unparsed == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    let report = audit.report();
+    let mut stdout = stdout();
+    if let Some(fmt) = format {
+        write_output(&mut stdout, &report, fmt, pretty)?;
+    } else {
+        output_plain(&mut stdout, &report, no_color)?;
+    }
+
+    if let Some(threshold) = args.fail_on
+        && let Some(worst) = report.findings.iter().map(|f| f.severity).max()
+        && worst >= threshold
+    {
+        return Err(IdtError::CheckFailed(format!(
+            "Found {} leakage",
+            worst.name()
+        )));
+    }
+    Ok(())
+}
+
+struct Parser {
+    type_hint: Option<IdKind>,
+    detection: DetectionOptions,
+    snowflake_layout: Option<SnowflakeLayout>,
+}
+
+impl Parser {
+    fn parse(&self, id: &str) -> Result<Box<dyn ParsedId>> {
+        match self.snowflake_layout {
+            Some(ref layout) => crate::ids::ParsedSnowflake::parse_with_layout(id, layout.clone())
+                .map(|s| Box::new(s) as Box<dyn ParsedId>),
+            None => crate::ids::parse_id_with(id, self.type_hint, &self.detection),
+        }
+    }
+}
+
+impl Severity {
+    fn name(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+        }
+    }
+}
+
+impl Serialize for Severity {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    ids: usize,
+    unparsed: usize,
+    /// IDs per detected type
+    types: BTreeMap<&'static str, usize>,
+    /// Most severe first
+    findings: Vec<Finding>,
+}
+
+#[derive(Debug, Serialize)]
+struct Finding {
+    severity: Severity,
+    title: &'static str,
+    /// IDs the finding applies to
+    ids: usize,
+    detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    examples: Vec<String>,
+}
+
+/// What the batch gives away, gathered one ID at a time
+#[derive(Default)]
+struct Audit {
+    parsed: usize,
+    unparsed: usize,
+    types: BTreeMap<&'static str, usize>,
+    /// IDs per timestamp resolution, e.g. `1 ms`
+    precisions: BTreeMap<&'static str, usize>,
+    first_ms: Option<u64>,
+    last_ms: Option<u64>,
+    /// Hardware MAC addresses in UUIDv1/v6 node fields
+    macs: BTreeMap<[u8; 6], usize>,
+    /// UUIDv1/v6 whose node has the multicast bit set, i.e. random
+    random_nodes: usize,
+    /// Xid machine ID to the process IDs seen with it
+    xid_hosts: BTreeMap<[u8; 3], BTreeSet<u16>>,
+    xids: usize,
+    /// Snowflake worker fields (everything but the sequence)
+    workers: BTreeMap<String, usize>,
+    /// ObjectId per-process random values
+    objectid_processes: BTreeMap<[u8; 5], usize>,
+    /// CUID host fingerprints
+    fingerprints: BTreeMap<String, usize>,
+    /// v3/v5 UUIDs, whose names can be guessed and checked
+    name_based: usize,
+    /// IDs with nothing embedded but randomness
+    opaque: usize,
+    /// Counter readings per family and producer, for predictability
+    counters: BTreeMap<(&'static str, String), Vec<Reading>>,
+}
+
+/// One ID's position in its producer's sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Reading {
+    time_ms: u64,
+    counter: u128,
+}
+
+impl Audit {
+    fn observe(&mut self, id: &dyn ParsedId) {
+        let kind = id.kind();
+        self.parsed += 1;
+        *self.types.entry(kind.name()).or_default() += 1;
+
+        let timestamp = id.timestamp();
+        if let (Some(ts), Some(precision)) = (timestamp, precision(kind)) {
+            *self.precisions.entry(precision).or_default() += 1;
+            self.first_ms = Some(self.first_ms.map_or(ts.millis, |t| t.min(ts.millis)));
+            self.last_ms = Some(self.last_ms.map_or(ts.millis, |t| t.max(ts.millis)));
+        }
+        let time_ms = timestamp.map_or(0, |ts| ts.millis);
+
+        match (kind, id.components()) {
+            (IdKind::UuidV1 | IdKind::UuidV6, _) => {
+                let bytes = id.as_bytes();
+                let Ok(node) = <[u8; 6]>::try_from(&bytes[10..16]) else {
+                    return;
+                };
+                if node[0] & 0x01 == 0 {
+                    *self.macs.entry(node).or_default() += 1;
+                } else {
+                    self.random_nodes += 1;
+                }
+                // The 60-bit timestamp in 100 ns ticks, in v6 order
+                let ticks = u64::from_be_bytes(bytes[..8].try_into().unwrap_or_default());
+                let ticks = if kind == IdKind::UuidV1 {
+                    (ticks & 0x0fff) << 48 | (ticks >> 16 & 0xffff) << 32 | ticks >> 32
+                } else {
+                    (ticks >> 4 & !0xfff) | (ticks & 0x0fff)
+                };
+                self.count("uuidv1/v6", format_mac(&node), 0, ticks as u128);
+            }
+            (IdKind::UuidV3 | IdKind::UuidV5, _) => self.name_based += 1,
+            (_, Some(Components::Xid(c))) => {
+                self.xids += 1;
+                self.xid_hosts
+                    .entry(c.machine_id)
+                    .or_default()
+                    .insert(c.process_id);
+                let producer = format!("{}/{}", hex::encode(c.machine_id), c.process_id);
+                self.count("xid", producer, 0, c.counter as u128);
+            }
+            (_, Some(Components::ObjectId(c))) => {
+                *self.objectid_processes.entry(c.random).or_default() += 1;
+                self.count("objectid", hex::encode(c.random), 0, c.counter as u128);
+            }
+            (_, Some(Components::Snowflake(c))) => {
+                let worker = c
+                    .fields
+                    .iter()
+                    .filter(|(name, _)| *name != "sequence")
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                *self.workers.entry(worker.clone()).or_default() += 1;
+                if let Some(sequence) = c.sequence() {
+                    self.count("snowflake", worker, time_ms, sequence as u128);
+                }
+            }
+            (_, Some(Components::Ulid(c))) => {
+                self.count("ulid", String::new(), time_ms, c.random);
+            }
+            (IdKind::Cuid, _) => {
+                let components = id.inspect().components.unwrap_or_default();
+                if let Some(fingerprint) = components["fingerprint"].as_str() {
+                    *self
+                        .fingerprints
+                        .entry(fingerprint.to_string())
+                        .or_default() += 1;
+                    if let Some(counter) = components["counter"]
+                        .as_str()
+                        .and_then(|c| u128::from_str_radix(c, 36).ok())
+                    {
+                        self.count("cuid", fingerprint.to_string(), 0, counter);
+                    }
+                }
+            }
+            _ if timestamp.is_none() => self.opaque += 1,
+            _ => {}
+        }
+    }
+
+    /// Record a counter reading. `time_ms` is 0 for counters that keep
+    /// running across timestamps.
+    fn count(&mut self, family: &'static str, producer: String, time_ms: u64, counter: u128) {
+        self.counters
+            .entry((family, producer))
+            .or_default()
+            .push(Reading { time_ms, counter });
+    }
+
+    fn report(mut self) -> Report {
+        let mut findings = Vec::new();
+
+        if !self.macs.is_empty() {
+            let ids = self.macs.values().sum();
+            findings.push(Finding {
+                severity: Severity::High,
+                title: "MAC address in UUIDv1/v6",
+                ids,
+                detail: format!(
+                    "{} network interface(s) identify the machines that made these IDs",
+                    self.macs.len()
+                ),
+                examples: examples(&self.macs, format_mac),
+            });
+        }
+
+        if !self.fingerprints.is_empty() {
+            findings.push(Finding {
+                severity: Severity::Medium,
+                title: "Host fingerprint in CUID",
+                ids: self.fingerprints.values().sum(),
+                detail: format!(
+                    "{} fingerprint(s) derived from hostname and process ID",
+                    self.fingerprints.len()
+                ),
+                examples: examples(&self.fingerprints, String::clone),
+            });
+        }
+
+        if !self.xid_hosts.is_empty() {
+            let processes: usize = self.xid_hosts.values().map(BTreeSet::len).sum();
+            findings.push(Finding {
+                severity: Severity::Medium,
+                title: "Machine and process ID in Xid",
+                ids: self.xids,
+                detail: format!(
+                    "{} machine(s) running {} process(es)",
+                    self.xid_hosts.len(),
+                    processes
+                ),
+                examples: self
+                    .xid_hosts
+                    .iter()
+                    .take(MAX_EXAMPLES)
+                    .map(|(machine, pids)| {
+                        let pids: Vec<String> = pids.iter().map(u16::to_string).collect();
+                        format!("machine {} pid {}", hex::encode(machine), pids.join(", "))
+                    })
+                    .collect(),
+            });
+        }
+
+        if self.workers.len() > 1 || self.workers.keys().any(|w| !w.is_empty()) {
+            findings.push(Finding {
+                severity: Severity::Medium,
+                title: "Worker IDs in Snowflake",
+                ids: self.workers.values().sum(),
+                detail: format!(
+                    "{} distinct worker(s), which hints at fleet size and routing",
+                    self.workers.len()
+                ),
+                examples: examples(&self.workers, String::clone),
+            });
+        }
+
+        if !self.objectid_processes.is_empty() {
+            findings.push(Finding {
+                severity: Severity::Low,
+                title: "Process value in ObjectId",
+                ids: self.objectid_processes.values().sum(),
+                detail: format!(
+                    "{} process value(s) link IDs made by the same process",
+                    self.objectid_processes.len()
+                ),
+                examples: examples(&self.objectid_processes, |p| hex::encode(p)),
+            });
+        }
+
+        findings.extend(self.predictability());
+
+        if let (Some(first), Some(last)) = (self.first_ms, self.last_ms) {
+            let ids = self.precisions.values().sum();
+            let precisions: Vec<String> = self
+                .precisions
+                .iter()
+                .map(|(precision, n)| format!("{} ({} IDs)", precision, n))
+                .collect();
+            let finest_is_sub_ms = self.precisions.contains_key("100 ns");
+            findings.push(Finding {
+                severity: if finest_is_sub_ms {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                title: "Creation time",
+                ids,
+                detail: format!(
+                    "Timestamps to {}, {}{}",
+                    precisions.join(", "),
+                    span(first, last),
+                    rate(ids, last - first)
+                ),
+                examples: vec![],
+            });
+        }
+
+        if self.name_based > 0 {
+            findings.push(Finding {
+                severity: Severity::Low,
+                title: "Name-based UUIDv3/v5",
+                ids: self.name_based,
+                detail: "Whoever can guess the names can confirm them; see `idt uuid5-find`"
+                    .to_string(),
+                examples: vec![],
+            });
+        }
+
+        if self.random_nodes > 0 {
+            findings.push(Finding {
+                severity: Severity::Info,
+                title: "Random node in UUIDv1/v6",
+                ids: self.random_nodes,
+                detail: "The node has the multicast bit set, so it is random, not a MAC"
+                    .to_string(),
+                examples: vec![],
+            });
+        }
+
+        if self.opaque > 0 {
+            findings.push(Finding {
+                severity: Severity::Info,
+                title: "Nothing embedded",
+                ids: self.opaque,
+                detail: "Random or hashed IDs with no timestamp, host, or counter".to_string(),
+                examples: vec![],
+            });
+        }
+
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        Report {
+            ids: self.parsed,
+            unparsed: self.unparsed,
+            types: std::mem::take(&mut self.types),
+            findings,
+        }
+    }
+
+    /// Families whose neighbouring IDs from one producer step by a small,
+    /// guessable amount
+    fn predictability(&mut self) -> Vec<Finding> {
+        // Family -> (guessable steps, neighbouring pairs, IDs)
+        let mut families: BTreeMap<&'static str, (usize, usize, usize)> = BTreeMap::new();
+        for ((family, _), readings) in &mut self.counters {
+            readings.sort_unstable();
+            let totals = families.entry(family).or_default();
+            totals.2 += readings.len();
+            for pair in readings.windows(2) {
+                totals.1 += 1;
+                let step = pair[1].counter.wrapping_sub(pair[0].counter);
+                if pair[0].time_ms == pair[1].time_ms && (1..=MAX_STEP).contains(&step) {
+                    totals.0 += 1;
+                }
+            }
+        }
+
+        families
+            .into_iter()
+            .filter(|&(_, (guessable, _, _))| guessable > 0)
+            .map(|(family, (guessable, pairs, ids))| Finding {
+                severity: if guessable * 2 >= pairs {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                title: "Predictable sequence",
+                ids,
+                detail: format!(
+                    "{}: {} of {} neighbouring IDs from the same source step by at most {}, \
+                     so nearby IDs can be guessed",
+                    family, guessable, pairs, MAX_STEP
+                ),
+                examples: vec![],
+            })
+            .collect()
+    }
+}
+
+/// Resolution of `kind`'s embedded timestamp
+fn precision(kind: IdKind) -> Option<&'static str> {
+    match kind {
+        IdKind::UuidV1 | IdKind::UuidV6 => Some("100 ns"),
+        IdKind::UuidV7
+        | IdKind::Ulid
+        | IdKind::Snowflake
+        | IdKind::Tsid
+        | IdKind::Cuid
+        | IdKind::TypeId
+        | IdKind::Custom => Some("1 ms"),
+        IdKind::Ksuid | IdKind::ObjectId | IdKind::Xid => Some("1 s"),
+        _ => None,
+    }
+}
+
+fn span(first_ms: u64, last_ms: u64) -> String {
+    let first = Timestamp::new(first_ms).to_iso8601();
+    if first_ms == last_ms {
+        format!("at {}", first)
+    } else {
+        format!("from {} to {}", first, Timestamp::new(last_ms).to_iso8601())
+    }
+}
+
+/// `, about 12.5 per hour` for spans long enough to say, per day when
+/// fewer than one an hour, and nothing when too sparse to mean anything
+fn rate(ids: usize, span_ms: u64) -> String {
+    const HOUR_MS: f64 = 3_600_000.0;
+    if ids < 2 || span_ms < 60_000 {
+        return String::new();
+    }
+    let per_hour = ids as f64 * HOUR_MS / span_ms as f64;
+    let (rate, unit) = if per_hour >= 1.0 {
+        (per_hour, "hour")
+    } else {
+        (per_hour * 24.0, "day")
+    };
+    if rate < 0.05 {
+        return String::new();
+    }
+    format!(", about {:.1} per {}, which hints at volume", rate, unit)
+}
+
+fn format_mac(node: &[u8; 6]) -> String {
+    node.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// The most common values first, each with its count
+fn examples<K>(counts: &BTreeMap<K, usize>, show: impl Fn(&K) -> String) -> Vec<String> {
+    let mut sorted: Vec<_> = counts.iter().collect();
+    sorted.sort_by_key(|&(_, &n)| std::cmp::Reverse(n));
+    let mut out: Vec<String> = sorted
+        .iter()
+        .take(MAX_EXAMPLES)
+        .map(|(key, n)| format!("{} ({} IDs)", show(key), n))
+        .collect();
+    if sorted.len() > MAX_EXAMPLES {
+        out.push(format!("and {} more", sorted.len() - MAX_EXAMPLES));
+    }
+    out
+}
+
+fn output_plain(writer: &mut dyn Write, report: &Report, no_color: bool) -> Result<()> {
+    let types: Vec<String> = report
+        .types
+        .iter()
+        .map(|(name, n)| format!("{} {}", name, n))
+        .collect();
+    write!(writer, "Audited {} IDs: {}", report.ids, types.join(", "))?;
+    if report.unparsed > 0 {
+        write!(writer, " ({} unparsed)", report.unparsed)?;
+    }
+    writeln!(writer)?;
+
+    for finding in &report.findings {
+        let label = format!("{:<6}", finding.severity.name().to_uppercase());
+        let label = match (no_color, finding.severity) {
+            (true, _) => label,
+            (false, Severity::High) => label.red().bold().to_string(),
+            (false, Severity::Medium) => label.yellow().to_string(),
+            (false, Severity::Low) => label.blue().to_string(),
+            (false, Severity::Info) => label.dimmed().to_string(),
+        };
+        writeln!(writer)?;
+        writeln!(writer, "{}  {} ({} IDs)", label, finding.title, finding.ids)?;
+        writeln!(writer, "        {}", finding.detail)?;
+        for example in &finding.examples {
+            writeln!(writer, "          {}", example)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::parse_id;
+
+    fn audit(ids: &[&str], kind: Option<IdKind>) -> Report {
+        let mut audit = Audit::default();
+        for id in ids {
+            audit.observe(parse_id(id, kind).unwrap().as_ref());
+        }
+        audit.report()
+    }
+
+    fn titles(report: &Report) -> Vec<(Severity, &'static str)> {
+        report
+            .findings
+            .iter()
+            .map(|f| (f.severity, f.title))
+            .collect()
+    }
+
+    #[test]
+    fn test_uuid_v1_mac() {
+        // RFC 9562 vector: node 9f:6b:de:ce:d8:46 has the multicast bit set
+        let report = audit(&["c232ab00-9414-11ec-b3c8-9f6bdeced846"], None);
+        assert!(titles(&report).contains(&(Severity::Info, "Random node in UUIDv1/v6")));
+
+        // 00:c0:4f:d4:30:c8 is a universal MAC
+        let report = audit(&["6ba7b810-9dad-11d1-80b4-00c04fd430c8"], None);
+        let mac = &report.findings[0];
+        assert_eq!(
+            (mac.severity, mac.title),
+            (Severity::High, "MAC address in UUIDv1/v6")
+        );
+        assert_eq!(mac.examples, ["00:c0:4f:d4:30:c8 (1 IDs)"]);
+        assert!(titles(&report).contains(&(Severity::Medium, "Creation time")));
+    }
+
+    #[test]
+    fn test_objectid_counter() {
+        let report = audit(
+            &[
+                "507f1f77bcf86cd799439011",
+                "507f1f77bcf86cd799439012",
+                "507f1f77bcf86cd799439013",
+            ],
+            Some(IdKind::ObjectId),
+        );
+        assert_eq!(report.findings[0].title, "Predictable sequence");
+        assert_eq!(report.findings[0].severity, Severity::Medium);
+        assert!(report.findings[0].detail.starts_with("objectid: 2 of 2"));
+        let process = report
+            .findings
+            .iter()
+            .find(|f| f.title == "Process value in ObjectId")
+            .unwrap();
+        assert_eq!(process.examples, ["bcf86cd799 (3 IDs)"]);
+    }
+
+    #[test]
+    fn test_snowflake_workers() {
+        let report = audit(&["1234567890123456789"], Some(IdKind::Snowflake));
+        let workers = report
+            .findings
+            .iter()
+            .find(|f| f.title == "Worker IDs in Snowflake")
+            .unwrap();
+        assert_eq!(workers.examples, ["datacenter_id=20 machine_id=24 (1 IDs)"]);
+    }
+
+    #[test]
+    fn test_opaque_ids() {
+        let report = audit(&["550e8400-e29b-41d4-a716-446655440000"], None);
+        assert_eq!(titles(&report), [(Severity::Info, "Nothing embedded")]);
+        assert_eq!(report.types["uuidv4"], 1);
+    }
+
+    #[test]
+    fn test_rate() {
+        assert_eq!(rate(1, 3_600_000), "");
+        assert_eq!(rate(10, 1_000), "");
+        assert_eq!(
+            rate(25, 7_200_000),
+            ", about 12.5 per hour, which hints at volume"
+        );
+        assert_eq!(
+            rate(3, 7_200_000 * 24),
+            ", about 1.5 per day, which hints at volume"
+        );
+        assert_eq!(rate(2, 3_600_000 * 24 * 365), "");
+        assert_eq!(span(0, 0), "at 1970-01-01T00:00:00.000Z");
+    }
+}
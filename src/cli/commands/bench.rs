@@ -0,0 +1,314 @@
+use crate::cli::app::{BenchArgs, BenchOp, OutputFormat};
+use crate::cli::output::{stdout, write_output};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdGenerator, IdKind};
+use colored::Colorize;
+use serde::Serialize;
+use std::hint::black_box;
+use std::io::{self, IsTerminal, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Operations run between deadline checks, so `Instant::now()` stays off the hot path
+const CHECK_EVERY: u64 = 64;
+
+/// Number of distinct IDs cycled through when parsing
+const PARSE_POOL_SIZE: usize = 1024;
+
+#[derive(Debug, Serialize)]
+struct BenchResult {
+    id_type: String,
+    operation: String,
+    threads: usize,
+    iterations: u64,
+    ids_per_sec: f64,
+    /// Average time per operation on a single thread
+    ns_per_op: f64,
+}
+
+pub fn execute(
+    args: &BenchArgs,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    if args.threads == 0 {
+        return Err(IdtError::InvalidArgument(
+            "--threads must be at least 1".to_string(),
+        ));
+    }
+    if args.duration == 0 {
+        return Err(IdtError::InvalidArgument(
+            "--duration must be at least 1 ms".to_string(),
+        ));
+    }
+
+    let kinds: Vec<IdKind> = if args.id_types.is_empty() {
+        default_kinds()
+    } else {
+        args.id_types.iter().map(|&k| k.into()).collect()
+    };
+
+    let progress = format.is_none() && io::stderr().is_terminal();
+    let mut results = Vec::new();
+
+    for kind in kinds {
+        if progress {
+            eprint!("\r\x1b[2KBenchmarking {}...", kind.name());
+        }
+        let generator = crate::ids::create_generator(kind)?;
+
+        if args.op != BenchOp::Parse {
+            results.push(bench_generate(args, kind, generator.as_ref())?);
+        }
+        if args.op != BenchOp::Gen {
+            results.push(bench_parse(args, kind, generator.as_ref())?);
+        }
+    }
+
+    if progress {
+        eprint!("\r\x1b[2K");
+    }
+
+    let mut stdout = stdout();
+    if let Some(fmt) = format {
+        write_output(&mut stdout, &results, fmt, pretty)?;
+    } else {
+        output_table(&mut stdout, args, &results, no_color)?;
+    }
+
+    Ok(())
+}
+
+/// Generatable types, minus the aliases and constants that would only add noise
+fn default_kinds() -> Vec<IdKind> {
+    IdKind::generatable()
+        .iter()
+        .copied()
+        .filter(|k| !matches!(k, IdKind::Uuid | IdKind::UuidNil | IdKind::UuidMax))
+        .collect()
+}
+
+fn bench_generate(
+    args: &BenchArgs,
+    kind: IdKind,
+    generator: &(dyn IdGenerator + Send + Sync),
+) -> Result<BenchResult> {
+    let op = |_: u64| generator.generate().map(|id| drop(black_box(id)));
+    measure(args, kind, "gen", &op)
+}
+
+fn bench_parse(
+    args: &BenchArgs,
+    kind: IdKind,
+    generator: &(dyn IdGenerator + Send + Sync),
+) -> Result<BenchResult> {
+    let pool = generator.generate_many(PARSE_POOL_SIZE)?;
+    let hint = (!args.detect).then_some(kind);
+    let op = |i: u64| {
+        let id = &pool[i as usize % pool.len()];
+        crate::ids::parse_id(black_box(id), hint).map(|parsed| drop(black_box(parsed)))
+    };
+    measure(args, kind, "parse", &op)
+}
+
+fn measure(
+    args: &BenchArgs,
+    kind: IdKind,
+    operation: &str,
+    op: &(dyn Fn(u64) -> Result<()> + Sync),
+) -> Result<BenchResult> {
+    if args.warmup > 0 {
+        run_for(op, args.threads, Duration::from_millis(args.warmup))?;
+    }
+    let (iterations, elapsed) = run_for(op, args.threads, Duration::from_millis(args.duration))?;
+
+    let secs = elapsed.as_secs_f64();
+    Ok(BenchResult {
+        id_type: kind.name().to_string(),
+        operation: operation.to_string(),
+        threads: args.threads,
+        iterations,
+        ids_per_sec: iterations as f64 / secs,
+        ns_per_op: secs * 1e9 * args.threads as f64 / iterations.max(1) as f64,
+    })
+}
+
+/// Run `op` on `threads` threads until `duration` has passed.
+/// Returns the total number of operations and the wall-clock time taken.
+fn run_for(
+    op: &(dyn Fn(u64) -> Result<()> + Sync),
+    threads: usize,
+    duration: Duration,
+) -> Result<(u64, Duration)> {
+    let start = Instant::now();
+    let deadline = start + duration;
+
+    let counts: Vec<Result<u64>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut n = 0u64;
+                    loop {
+                        for _ in 0..CHECK_EVERY {
+                            op(n)?;
+                            n += 1;
+                        }
+                        if Instant::now() >= deadline {
+                            return Ok(n);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| {
+                h.join().unwrap_or_else(|_| {
+                    Err(IdtError::GenerationError(
+                        "benchmark thread panicked".to_string(),
+                    ))
+                })
+            })
+            .collect()
+    });
+    let elapsed = start.elapsed();
+
+    let mut total = 0;
+    for count in counts {
+        total += count?;
+    }
+    Ok((total, elapsed))
+}
+
+fn output_table(
+    writer: &mut dyn Write,
+    args: &BenchArgs,
+    results: &[BenchResult],
+    no_color: bool,
+) -> Result<()> {
+    let title = format!(
+        "{} thread{}, {}ms per measurement",
+        args.threads,
+        if args.threads == 1 { "" } else { "s" },
+        args.duration
+    );
+    let header = format!(
+        "{:<12} {:>12} {:>11} {:>12} {:>11}",
+        "TYPE", "GEN IDS/S", "GEN NS/OP", "PARSE IDS/S", "PARSE NS/OP"
+    );
+    if no_color {
+        writeln!(writer, "{}", title)?;
+        writeln!(writer)?;
+        writeln!(writer, "{}", header)?;
+    } else {
+        writeln!(writer, "{}", title.dimmed())?;
+        writeln!(writer)?;
+        writeln!(writer, "{}", header.bold())?;
+    }
+
+    let mut i = 0;
+    while i < results.len() {
+        let id_type = &results[i].id_type;
+        let mut gen_result = None;
+        let mut parse_result = None;
+        while i < results.len() && results[i].id_type == *id_type {
+            match results[i].operation.as_str() {
+                "gen" => gen_result = Some(&results[i]),
+                _ => parse_result = Some(&results[i]),
+            }
+            i += 1;
+        }
+
+        let (gen_rate, gen_ns) = columns(gen_result);
+        let (parse_rate, parse_ns) = columns(parse_result);
+        writeln!(
+            writer,
+            "{:<12} {:>12} {:>11} {:>12} {:>11}",
+            id_type, gen_rate, gen_ns, parse_rate, parse_ns
+        )?;
+    }
+
+    Ok(())
+}
+
+fn columns(result: Option<&BenchResult>) -> (String, String) {
+    match result {
+        Some(r) => (format_rate(r.ids_per_sec), format!("{:.1}", r.ns_per_op)),
+        None => ("-".to_string(), "-".to_string()),
+    }
+}
+
+fn format_rate(per_sec: f64) -> String {
+    if per_sec >= 1e9 {
+        format!("{:.2}G", per_sec / 1e9)
+    } else if per_sec >= 1e6 {
+        format!("{:.2}M", per_sec / 1e6)
+    } else if per_sec >= 1e3 {
+        format!("{:.2}K", per_sec / 1e3)
+    } else {
+        format!("{:.0}", per_sec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::id::GenIdKind;
+
+    fn make_args(id_types: Vec<GenIdKind>) -> BenchArgs {
+        BenchArgs {
+            id_types,
+            op: BenchOp::All,
+            duration: 5,
+            warmup: 0,
+            threads: 1,
+            detect: false,
+        }
+    }
+
+    #[test]
+    fn test_bench_single_type() {
+        let args = make_args(vec![GenIdKind::Ulid]);
+        assert!(execute(&args, None, false, true).is_ok());
+    }
+
+    #[test]
+    fn test_bench_json_multi_thread() {
+        let mut args = make_args(vec![GenIdKind::UuidV7, GenIdKind::Snowflake]);
+        args.threads = 2;
+        args.detect = true;
+        assert!(execute(&args, Some(OutputFormat::Json), false, true).is_ok());
+    }
+
+    #[test]
+    fn test_bench_zero_threads() {
+        let mut args = make_args(vec![GenIdKind::Ulid]);
+        args.threads = 0;
+        assert!(execute(&args, None, false, true).is_err());
+    }
+
+    #[test]
+    fn test_measure_counts_iterations() {
+        let args = make_args(vec![]);
+        let generator = crate::ids::create_generator(IdKind::Ksuid).unwrap();
+        let result = bench_parse(&args, IdKind::Ksuid, generator.as_ref()).unwrap();
+        assert_eq!(result.operation, "parse");
+        assert!(result.iterations >= CHECK_EVERY);
+        assert!(result.ids_per_sec > 0.0);
+    }
+
+    #[test]
+    fn test_default_kinds_skip_constants() {
+        let kinds = default_kinds();
+        assert!(!kinds.contains(&IdKind::UuidNil));
+        assert!(kinds.contains(&IdKind::Ulid));
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(950.0), "950");
+        assert_eq!(format_rate(12_345_678.0), "12.35M");
+    }
+}
@@ -0,0 +1,332 @@
+use crate::cli::app::{BucketArgs, OutputFormat};
+use crate::cli::input::{stream_ids, stream_total};
+use crate::cli::output::{stdout, write_output};
+use crate::cli::progress::Progress;
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId};
+use crate::core::time::BucketSize;
+use crate::ids::snowflake_id::SnowflakeLayout;
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{BufWriter, Write};
+
+pub fn execute(args: &BucketArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let snowflake_layout = if args.preset.is_some() || args.epoch.is_some() {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+    let bucketer = Bucketer {
+        size: args.by,
+        tz: args.tz.unwrap_or(Tz::UTC),
+        type_hint: args.id_type,
+        detection: args.detect.options(),
+        snowflake_layout,
+    };
+
+    let source = args.input.source()?;
+    let ids = stream_ids(&args.ids, &source)?;
+    let mut writer = BufWriter::new(stdout());
+    let mut progress = Progress::new("bucket", stream_total(&ids), false);
+    let mut entries = Vec::new();
+    let mut counts: BTreeMap<i64, u64> = BTreeMap::new();
+    let mut count = 0usize;
+
+    for sourced in ids {
+        let id = sourced?.id;
+        count += 1;
+        progress.tick(id.len() + 1);
+        let start = match bucketer.bucket(&id) {
+            Ok(start) => start,
+            Err(e) => {
+                progress.clear();
+                eprintln!("Warning: skipping '{}' {}", id, e);
+                continue;
+            }
+        };
+        if args.count || args.histogram {
+            *counts.entry(start).or_default() += 1;
+        } else if output_format.is_some() {
+            entries.push(BucketEntry {
+                bucket: bucketer.label(start)?,
+                id,
+            });
+        } else {
+            writeln!(writer, "{}\t{}", bucketer.label(start)?, id)?;
+        }
+    }
+
+    progress.clear();
+
+    if count == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    if args.count || args.histogram {
+        let rows = count_rows(
+            bucketer.size,
+            bucketer.tz,
+            &counts,
+            args.fill || args.histogram,
+        )?;
+        match output_format {
+            Some(fmt) => write_output(&mut writer, &rows, fmt, pretty)?,
+            None if args.histogram => {
+                for line in histogram(&rows, args.width, args.ascii) {
+                    writeln!(writer, "{}", line)?;
+                }
+            }
+            None => {
+                for row in rows {
+                    writeln!(writer, "{}\t{}", row.bucket, row.count)?;
+                }
+            }
+        }
+    } else if let Some(fmt) = output_format {
+        write_output(&mut writer, &entries, fmt, pretty)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct BucketEntry {
+    bucket: String,
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CountRow {
+    pub(crate) bucket: String,
+    /// First millisecond of the bucket
+    pub(crate) start_ms: i64,
+    pub(crate) count: u64,
+}
+
+struct Bucketer {
+    size: BucketSize,
+    tz: Tz,
+    type_hint: Option<IdKind>,
+    detection: DetectionOptions,
+    snowflake_layout: Option<SnowflakeLayout>,
+}
+
+impl Bucketer {
+    /// Start of the bucket `id`'s embedded timestamp falls in, or why it
+    /// has none
+    fn bucket(&self, id: &str) -> std::result::Result<i64, String> {
+        let parsed: Result<Box<dyn ParsedId>> = match self.snowflake_layout {
+            Some(ref layout) => crate::ids::ParsedSnowflake::parse_with_layout(id, layout.clone())
+                .map(|s| Box::new(s) as Box<dyn ParsedId>),
+            None => crate::ids::parse_id_with(id, self.type_hint, &self.detection),
+        };
+        let parsed = parsed.map_err(|e| format!("— failed to parse: {}", e))?;
+        let ts = parsed
+            .timestamp()
+            .ok_or_else(|| format!("({}) — no embedded timestamp", parsed.kind().name()))?;
+        let ms = i64::try_from(ts.millis).map_err(|_| {
+            format!(
+                "— Timestamp {} ms is outside the range of dates that can be bucketed",
+                ts.millis
+            )
+        })?;
+        self.size.start(ms, self.tz).map_err(|e| format!("— {}", e))
+    }
+
+    fn label(&self, start: i64) -> Result<String> {
+        self.size.label(start, self.tz)
+    }
+}
+
+/// One row per bucket of `counts` (keyed by bucket start) in time order.
+/// With `fill`, empty buckets between the first and last are included with
+/// a count of 0.
+pub(crate) fn count_rows(
+    size: BucketSize,
+    tz: Tz,
+    counts: &BTreeMap<i64, u64>,
+    fill: bool,
+) -> Result<Vec<CountRow>> {
+    let row = |start: i64, count: u64| {
+        Ok(CountRow {
+            bucket: size.label(start, tz)?,
+            start_ms: start,
+            count,
+        })
+    };
+    let (Some((&first, _)), Some((&last, _))) =
+        (counts.first_key_value(), counts.last_key_value())
+    else {
+        return Ok(Vec::new());
+    };
+    if !fill {
+        return counts.iter().map(|(&start, &n)| row(start, n)).collect();
+    }
+    let mut rows = Vec::new();
+    let mut start = first;
+    while start <= last {
+        rows.push(row(start, counts.get(&start).copied().unwrap_or(0))?);
+        // The last bucket on the calendar has no next one to fill
+        match size.next(start, tz) {
+            Ok(next) => start = next,
+            Err(_) => break,
+        }
+    }
+    Ok(rows)
+}
+
+/// One bar per bucket, scaled so the fullest bucket is `width` columns.
+/// Unicode bars use eighth blocks, so small differences still show:
+///
+/// ```text
+/// 2024-05-01T00:00:00Z │██████████████▏    41
+/// 2024-05-01T01:00:00Z │                    0
+/// 2024-05-01T02:00:00Z │██████████████████ 52
+/// ```
+pub(crate) fn histogram(rows: &[CountRow], width: usize, ascii: bool) -> Vec<String> {
+    const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let Some(peak) = rows.iter().map(|r| r.count).max() else {
+        return Vec::new();
+    };
+    let label_width = rows.iter().map(|r| r.bucket.len()).max().unwrap_or(0);
+    let width = width.max(1);
+
+    let mut lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            // Bar length in eighths of a column
+            let eighths = (row.count as u128 * width as u128 * 8).div_ceil(peak.max(1) as u128);
+            let bar = if ascii {
+                "#".repeat(eighths.div_ceil(8) as usize)
+            } else {
+                let mut bar = "█".repeat((eighths / 8) as usize);
+                let partial = (eighths % 8) as usize;
+                if partial > 0 {
+                    bar.push(EIGHTHS[partial]);
+                }
+                bar
+            };
+            let separator = if ascii { '|' } else { '│' };
+            format!(
+                "{:<label_width$} {}{:<width$} {}",
+                row.bucket, separator, bar, row.count
+            )
+        })
+        .collect();
+
+    let total: u64 = rows.iter().map(|r| r.count).sum();
+    let busiest = rows
+        .iter()
+        .find(|r| r.count == peak)
+        .map(|r| r.bucket.as_str());
+    let empty = rows.iter().filter(|r| r.count == 0).count();
+    lines.push(String::new());
+    lines.push(format!(
+        "{} IDs in {} buckets, peak {} at {}, {} empty",
+        total,
+        rows.len(),
+        peak,
+        busiest.unwrap_or_default(),
+        empty
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::snowflake_id::{SnowflakeField, TimestampUnit};
+
+    fn bucketer(size: BucketSize) -> Bucketer {
+        Bucketer {
+            size,
+            tz: Tz::UTC,
+            type_hint: None,
+            detection: DetectionOptions::default(),
+            snowflake_layout: None,
+        }
+    }
+
+    #[test]
+    fn test_bucket_ids() {
+        let b = bucketer(BucketSize::Day);
+        // Minted 2016-07-30T23:54:10.259Z
+        let start = b.bucket("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+        assert_eq!(b.label(start).unwrap(), "2016-07-30");
+        // UUIDv7 from 2022-02-22T19:22:22Z
+        let start = b.bucket("017f22e2-79b0-7cc3-98c4-dc0c0c07398f").unwrap();
+        assert_eq!(b.label(start).unwrap(), "2022-02-22");
+
+        let err = b
+            .bucket("550e8400-e29b-41d4-a716-446655440000")
+            .unwrap_err();
+        assert_eq!(err, "(uuidv4) — no embedded timestamp");
+    }
+
+    #[test]
+    fn test_bucket_out_of_range_timestamp() {
+        // A custom schema whose timestamp runs past any calendar date
+        static WIDE: &[SnowflakeField] = &[SnowflakeField {
+            name: "timestamp",
+            bits: 63,
+        }];
+        let b = Bucketer {
+            snowflake_layout: Some(SnowflakeLayout {
+                name: "wide",
+                epoch: 0,
+                timestamp_unit: TimestampUnit::Millis,
+                fields: WIDE,
+            }),
+            ..bucketer(BucketSize::Day)
+        };
+        let err = b.bucket("9000000000000000000").unwrap_err();
+        assert!(err.contains("outside the range"), "{}", err);
+        assert!(b.bucket("1700000000000").is_ok());
+    }
+
+    #[test]
+    fn test_count_rows_fill() {
+        let hour = 3_600_000;
+        let counts = BTreeMap::from([(0, 2), (3 * hour, 1)]);
+        let count_rows = |counts, fill| count_rows(BucketSize::Hour, Tz::UTC, counts, fill);
+
+        let sparse = count_rows(&counts, false).unwrap();
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse[1].bucket, "1970-01-01T03:00:00Z");
+
+        let filled = count_rows(&counts, true).unwrap();
+        let counts: Vec<u64> = filled.iter().map(|r| r.count).collect();
+        assert_eq!(counts, vec![2, 0, 0, 1]);
+        assert!(count_rows(&BTreeMap::new(), true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_histogram() {
+        let row = |bucket: &str, count| CountRow {
+            bucket: bucket.to_string(),
+            start_ms: 0,
+            count,
+        };
+        let rows = [row("a", 4), row("bb", 0), row("c", 1)];
+
+        let lines = histogram(&rows, 4, true);
+        assert_eq!(lines[0], "a  |#### 4");
+        assert_eq!(lines[1], "bb |     0");
+        assert_eq!(lines[2], "c  |#    1");
+        assert_eq!(lines[4], "5 IDs in 3 buckets, peak 4 at a, 1 empty");
+
+        let lines = histogram(&rows, 4, false);
+        assert_eq!(lines[0], "a  │████ 4");
+        assert_eq!(lines[2], "c  │█    1");
+        let lines = histogram(&[row("a", 8), row("b", 1)], 2, false);
+        assert_eq!(lines[1], "b │▎  1");
+        assert!(histogram(&[], 10, false).is_empty());
+    }
+}
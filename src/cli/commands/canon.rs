@@ -0,0 +1,202 @@
+use crate::cli::app::{CanonArgs, OutputFormat};
+use crate::cli::input::{stream_ids, stream_total};
+use crate::cli::output::{ErrorEntry, stdout, write_output};
+use crate::cli::progress::Progress;
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::IdKind;
+use serde::Serialize;
+use std::io::{BufWriter, Write};
+
+pub fn execute(args: &CanonArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let source = args.input.source()?;
+    let ids = stream_ids(&args.ids, &source)?;
+    let detection = args.detect.options();
+
+    let mut writer = BufWriter::new(stdout());
+    let mut progress = Progress::new("canon", stream_total(&ids), false);
+    let mut entries = Vec::new();
+    let mut count = 0usize;
+    let mut changed = 0usize;
+    let mut invalid = 0usize;
+
+    for sourced in ids {
+        let input = sourced?.id;
+        count += 1;
+        progress.tick(input.len() + 1);
+        match canonicalize(&input, args.id_type, &detection) {
+            Ok(canon) => {
+                if canon.changed {
+                    changed += 1;
+                    if args.annotate || args.check {
+                        progress.clear();
+                        eprintln!("{} -> {} ({})", input, canon.output, canon.id_type);
+                    }
+                }
+                if args.check {
+                    continue;
+                }
+                if output_format.is_some() {
+                    entries.push(CanonEntry::Ok(canon));
+                } else {
+                    writeln!(writer, "{}", canon.output)?;
+                }
+            }
+            Err(e) => {
+                invalid += 1;
+                if output_format.is_some() {
+                    entries.push(CanonEntry::Err(ErrorEntry::new(&input, &e)));
+                } else if args.keep_invalid {
+                    if args.annotate {
+                        progress.clear();
+                        eprintln!("{} kept: {}", input, e);
+                    }
+                    if !args.check {
+                        writeln!(writer, "{}", input)?;
+                    }
+                } else {
+                    progress.clear();
+                    eprintln!("Error canonicalizing '{}': {}", input, e);
+                }
+            }
+        }
+    }
+
+    progress.clear();
+    writer.flush()?;
+
+    if count == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    if let Some(fmt) = output_format {
+        if entries.len() == 1 {
+            write_output(&mut stdout(), &entries[0], fmt, pretty)?;
+        } else {
+            write_output(&mut stdout(), &entries, fmt, pretty)?;
+        }
+    }
+
+    if invalid > 0 && !args.keep_invalid {
+        return Err(IdtError::InvalidArgument(format!(
+            "Failed to canonicalize {} of {} IDs",
+            invalid, count
+        )));
+    }
+    if args.check && changed > 0 {
+        return Err(IdtError::CheckFailed(format!(
+            "{} of {} IDs are not in canonical form",
+            changed, count
+        )));
+    }
+    Ok(())
+}
+
+/// One input in canonical form
+#[derive(Debug, Serialize)]
+struct Canonical {
+    input: String,
+    output: String,
+    id_type: &'static str,
+    /// `output` differs from `input`
+    changed: bool,
+}
+
+/// Canonical form, or an error object when the input isn't an ID
+#[derive(Serialize)]
+#[serde(untagged)]
+enum CanonEntry {
+    Ok(Canonical),
+    Err(ErrorEntry),
+}
+
+fn canonicalize(
+    input: &str,
+    type_hint: Option<IdKind>,
+    detection: &DetectionOptions,
+) -> Result<Canonical> {
+    let parsed = crate::ids::parse_id_with(unquote(input), type_hint, detection)?;
+    let output = parsed.canonical();
+    Ok(Canonical {
+        changed: output != input,
+        input: input.to_string(),
+        output,
+        id_type: parsed.kind().name(),
+    })
+}
+
+/// Strip what IDs pick up when copied out of SQL, JSON, or code: a trailing
+/// comma or semicolon, then one pair of matching quotes or backticks.
+/// `urn:uuid:`, `{...}`, and `ObjectId("...")` are left for the parsers.
+fn unquote(input: &str) -> &str {
+    let s = input.trim();
+    let s = s
+        .strip_suffix(',')
+        .or_else(|| s.strip_suffix(';'))
+        .unwrap_or(s)
+        .trim_end();
+    for quote in ['"', '\'', '`'] {
+        if let Some(inner) = s
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.trim();
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canon(input: &str) -> Result<Canonical> {
+        canonicalize(input, None, &DetectionOptions::default())
+    }
+
+    #[test]
+    fn test_unquote() {
+        assert_eq!(unquote("'abc',"), "abc");
+        assert_eq!(unquote("\"abc\";"), "abc");
+        assert_eq!(unquote("`abc`"), "abc");
+        assert_eq!(unquote("'abc\""), "'abc\"");
+        assert_eq!(unquote("abc"), "abc");
+    }
+
+    #[test]
+    fn test_canonicalize_uuid_forms() {
+        let expected = "550e8400-e29b-41d4-a716-446655440000";
+        for input in [
+            "550E8400E29B41D4A716446655440000",
+            "{550e8400-e29b-41d4-a716-446655440000}",
+            "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+            "'550E8400-E29B-41D4-A716-446655440000',",
+        ] {
+            let c = canon(input).unwrap();
+            assert_eq!(c.output, expected, "{}", input);
+            assert!(c.changed);
+        }
+        assert!(!canon(expected).unwrap().changed);
+    }
+
+    #[test]
+    fn test_canonicalize_ulid_case() {
+        let c = canon("01arz3ndektsv4rrffq69g5fav").unwrap();
+        assert_eq!(c.output, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert_eq!(c.id_type, "ulid");
+        assert!(c.changed);
+    }
+
+    #[test]
+    fn test_canonicalize_objectid_wrapper() {
+        let c = canon("ObjectId(\"507f1f77bcf86cd799439011\")").unwrap();
+        assert_eq!(c.output, "507f1f77bcf86cd799439011");
+    }
+
+    #[test]
+    fn test_canonicalize_invalid() {
+        assert!(canon("not an id!").is_err());
+    }
+}
@@ -1,10 +1,10 @@
 use crate::cli::app::{CompareArgs, OutputFormat};
-use crate::cli::output::format_output;
+use crate::cli::output::{stdout, write_output};
 use crate::core::error::Result;
-use crate::core::id::IdKind;
+use crate::core::id::{IdKind, InspectionResult};
 use colored::Colorize;
 use std::cmp::Ordering;
-use std::io::{self, Write};
+use std::io::Write;
 
 pub fn execute(
     args: &CompareArgs,
@@ -13,9 +13,10 @@ pub fn execute(
     no_color: bool,
 ) -> Result<()> {
     let type_hint: Option<IdKind> = args.id_type;
+    let detection = args.detect.options();
 
-    let parsed1 = crate::ids::parse_id(&args.id1, type_hint)?;
-    let parsed2 = crate::ids::parse_id(&args.id2, type_hint)?;
+    let parsed1 = crate::ids::parse_id_with(&args.id1, type_hint, &detection)?;
+    let parsed2 = crate::ids::parse_id_with(&args.id2, type_hint, &detection)?;
 
     let bytes1 = parsed1.as_bytes();
     let bytes2 = parsed2.as_bytes();
@@ -42,6 +43,10 @@ pub fn execute(
         _ => None,
     };
 
+    let differing_fields = (parsed1.kind() == parsed2.kind())
+        .then(|| differing_fields(&parsed1.inspect(), &parsed2.inspect()))
+        .flatten();
+
     let result = CompareResult {
         id1: args.id1.clone(),
         id2: args.id2.clone(),
@@ -53,13 +58,15 @@ pub fn execute(
         time_diff_ms: time_diff,
         timestamp1: ts1.map(|t| t.millis),
         timestamp2: ts2.map(|t| t.millis),
+        hamming_distance: hamming_distance(&bytes1, &bytes2),
+        shared_prefix_bytes: shared_prefix_len(&bytes1, &bytes2),
+        differing_fields,
     };
 
-    let mut stdout = io::stdout();
+    let mut stdout = stdout();
 
     if let Some(fmt) = format {
-        let output = format_output(&result, fmt, pretty)?;
-        writeln!(stdout, "{}", output)?;
+        write_output(&mut stdout, &result, fmt, pretty)?;
     } else {
         print_human(&mut stdout, &result, no_color)?;
     }
@@ -83,6 +90,35 @@ struct CompareResult {
     timestamp1: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     timestamp2: Option<u64>,
+    /// Number of differing bits, for IDs of the same byte length
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hamming_distance: Option<u32>,
+    shared_prefix_bytes: usize,
+    /// Component fields whose values differ, for IDs of the same type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    differing_fields: Option<Vec<String>>,
+}
+
+fn hamming_distance(a: &[u8], b: &[u8]) -> Option<u32> {
+    (a.len() == b.len()).then(|| a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum())
+}
+
+fn shared_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// Names of the component fields that differ, in the first ID's order, then
+/// fields only the second ID has
+fn differing_fields(a: &InspectionResult, b: &InspectionResult) -> Option<Vec<String>> {
+    let a = a.components.as_ref()?.as_object()?;
+    let b = b.components.as_ref()?.as_object()?;
+    let mut fields: Vec<String> = a
+        .iter()
+        .filter(|(key, value)| b.get(key.as_str()) != Some(value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    fields.extend(b.keys().filter(|key| !a.contains_key(*key)).cloned());
+    Some(fields)
 }
 
 fn ordering_to_string(ord: Ordering) -> String {
@@ -170,6 +206,26 @@ fn print_human(writer: &mut dyn Write, result: &CompareResult, no_color: bool) -
         }
     }
 
+    writeln!(writer)?;
+    writeln!(writer, "Similarity:")?;
+    if let Some(distance) = result.hamming_distance {
+        writeln!(writer, "  {} {} bits", label("Hamming distance:"), distance)?;
+    }
+    writeln!(
+        writer,
+        "  {} {} bytes",
+        label("Shared prefix:"),
+        result.shared_prefix_bytes
+    )?;
+    if let Some(ref fields) = result.differing_fields {
+        let fields = if fields.is_empty() {
+            "none".to_string()
+        } else {
+            fields.join(", ")
+        };
+        writeln!(writer, "  {} {}", label("Differing fields:"), fields)?;
+    }
+
     Ok(())
 }
 
@@ -183,6 +239,7 @@ mod tests {
             id1: id1.to_string(),
             id2: id2.to_string(),
             id_type: None,
+            detect: Default::default(),
         }
     }
 
@@ -232,6 +289,7 @@ mod tests {
             id1: "550e8400-e29b-41d4-a716-446655440000".to_string(),
             id2: "6ba7b810-9dad-11d1-80b4-00c04fd430c8".to_string(),
             id_type: Some(IdKind::Uuid),
+            detect: Default::default(),
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
@@ -251,6 +309,29 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_hamming_and_prefix() {
+        assert_eq!(hamming_distance(&[0xff, 0x00], &[0xff, 0x00]), Some(0));
+        assert_eq!(hamming_distance(&[0xff, 0x00], &[0x0f, 0x01]), Some(5));
+        assert_eq!(hamming_distance(&[0xff], &[0xff, 0x00]), None);
+        assert_eq!(shared_prefix_len(&[1, 2, 3], &[1, 2, 4]), 2);
+        assert_eq!(shared_prefix_len(&[1, 2], &[1, 2, 3]), 2);
+        assert_eq!(shared_prefix_len(&[9], &[1]), 0);
+    }
+
+    #[test]
+    fn test_differing_fields() {
+        let inspect = |id: &str| crate::ids::parse_id(id, None).unwrap().inspect();
+        // Same Snowflake timestamp and datacenter, different machine and sequence
+        let a = inspect("1234567890123456789");
+        let b = inspect("1234567890123358208");
+        let fields = differing_fields(&a, &b).unwrap();
+        assert!(fields.contains(&"machine_id".to_string()));
+        assert!(fields.contains(&"sequence".to_string()));
+        assert!(!fields.contains(&"timestamp".to_string()));
+        assert_eq!(differing_fields(&a, &a).unwrap(), Vec::<String>::new());
+    }
+
     #[test]
     fn test_ordering_to_string() {
         assert_eq!(ordering_to_string(Ordering::Less), "less");
@@ -1,13 +1,100 @@
 use crate::cli::Cli;
 use crate::cli::app::CompletionsArgs;
-use crate::core::error::Result;
+use crate::cli::output::stdout;
+use crate::core::EncodingFormat;
+use crate::core::error::{IdtError, Result};
+use crate::ids::SnowflakeLayout;
 use clap::CommandFactory;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
 use clap_complete::generate;
-use std::io;
+
+/// Environment variable the dynamic completion script sets when it calls back
+/// into idt
+pub const COMPLETE_VAR: &str = "COMPLETE";
 
 pub fn execute(args: &CompletionsArgs) -> Result<()> {
     let mut cmd = Cli::command();
     let bin_name = cmd.get_name().to_string();
-    generate(args.shell, &mut cmd, bin_name, &mut io::stdout());
+    if !args.dynamic {
+        generate(args.shell, &mut cmd, bin_name, &mut stdout());
+        return Ok(());
+    }
+
+    let shell = args.shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells.completer(&shell).ok_or_else(|| {
+        IdtError::InvalidArgument(format!("No dynamic completions for {}", shell))
+    })?;
+    completer.write_registration(COMPLETE_VAR, &bin_name, &bin_name, &bin_name, &mut stdout())?;
     Ok(())
 }
+
+/// `--format` values
+pub fn format_candidates() -> Vec<CompletionCandidate> {
+    EncodingFormat::names()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// `--preset` values, with each preset's bit layout as help
+pub fn preset_candidates() -> Vec<CompletionCandidate> {
+    SnowflakeLayout::PRESETS
+        .iter()
+        .filter_map(|&name| {
+            let layout = SnowflakeLayout::by_name(name)?;
+            let fields = layout
+                .fields
+                .iter()
+                .map(|f| format!("{} {}", f.name, f.bits))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Some(CompletionCandidate::new(name).help(Some(fields.into())))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(candidates: Vec<CompletionCandidate>) -> Vec<String> {
+        candidates
+            .iter()
+            .map(|c| c.get_value().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_candidates_parse() {
+        for name in values(format_candidates()) {
+            assert!(name.parse::<EncodingFormat>().is_ok(), "{}", name);
+        }
+        for name in values(preset_candidates()) {
+            assert!(SnowflakeLayout::by_name(&name).is_some(), "{}", name);
+        }
+        assert_eq!(
+            values(preset_candidates()).len(),
+            SnowflakeLayout::PRESETS.len()
+        );
+    }
+
+    #[test]
+    fn test_dynamic_completion_of_values() {
+        let complete = |line: &[&str]| {
+            let mut cmd = Cli::command();
+            let args = line.iter().map(Into::into).collect();
+            let index = line.len() - 1;
+            values(clap_complete::engine::complete(&mut cmd, args, index, None).unwrap())
+        };
+
+        assert!(complete(&["idt", "gen", "ul"]).contains(&"ulid".to_string()));
+        assert!(
+            complete(&["idt", "gen", "uuid", "--format", "base5"]).contains(&"base58".to_string())
+        );
+        assert_eq!(
+            complete(&["idt", "inspect", "--preset", "dis"]),
+            vec!["discord"]
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crate::cli::app::{ConformanceArgs, OutputFormat};
+use crate::cli::output::{stdout, write_output};
+use crate::conformance::{self, Outcome};
+use crate::core::error::{IdtError, Result};
+use colored::Colorize;
+use std::io::Write;
+
+pub fn execute(
+    args: &ConformanceArgs,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    let outcomes = conformance::run(&args.id_types);
+    if outcomes.is_empty() {
+        return Err(IdtError::InvalidArgument(
+            "No test vectors for the given types".to_string(),
+        ));
+    }
+
+    let mut stdout = stdout();
+    if let Some(fmt) = format {
+        write_output(&mut stdout, &outcomes, fmt, pretty)?;
+    } else {
+        output_plain(&mut stdout, &outcomes, no_color)?;
+    }
+
+    let failed = outcomes.iter().filter(|o| !o.passed).count();
+    if failed > 0 {
+        return Err(IdtError::CheckFailed(format!(
+            "{} of {} vectors failed",
+            failed,
+            outcomes.len()
+        )));
+    }
+    Ok(())
+}
+
+fn output_plain(writer: &mut dyn Write, outcomes: &[Outcome], no_color: bool) -> Result<()> {
+    for outcome in outcomes {
+        let label = match (no_color, outcome.passed) {
+            (true, true) => "ok  ".to_string(),
+            (true, false) => "FAIL".to_string(),
+            (false, true) => "ok  ".green().to_string(),
+            (false, false) => "FAIL".red().bold().to_string(),
+        };
+        let note = if outcome.valid { "" } else { "  (invalid)" };
+        writeln!(
+            writer,
+            "{}  {:<7}  {:<15}  {}{}",
+            label, outcome.id_type, outcome.source, outcome.input, note
+        )?;
+        for mismatch in &outcome.mismatches {
+            writeln!(writer, "      {}", mismatch)?;
+        }
+    }
+    writeln!(
+        writer,
+        "\n{} vectors, {} passed",
+        outcomes.len(),
+        outcomes.iter().filter(|o| o.passed).count()
+    )?;
+    Ok(())
+}
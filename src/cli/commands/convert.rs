@@ -1,24 +1,29 @@
-use crate::cli::app::{ConvertArgs, OutputFormat};
-use crate::cli::output::format_output;
+use crate::cli::app::{ConvertArgs, FromFormat, OutputFormat};
+use crate::cli::input::{stream_ids, stream_total};
+use crate::cli::output::{ErrorEntry, stdout, write_output};
+use crate::cli::progress::Progress;
+use crate::cli::qr;
 use crate::core::EncodingFormat;
+use crate::core::detection::DetectionOptions;
+use crate::core::encoding::decode_java_longs;
 use crate::core::error::{IdtError, Result};
-use crate::core::id::IdKind;
-use std::io::{self, BufRead, Write};
+use crate::core::id::{IdKind, ParsedId};
+use std::io::Write;
+use uuid::Uuid;
 
 pub fn execute(
     args: &ConvertArgs,
     output_format: Option<OutputFormat>,
     pretty: bool,
 ) -> Result<()> {
-    let ids = collect_ids(&args.ids)?;
-
-    if ids.is_empty() {
-        return Err(IdtError::InvalidArgument(
-            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
-        ));
-    }
+    args.records.check_plain(output_format)?;
+    args.qr.check_plain(output_format)?;
+    let source = args.input.source()?;
+    let given = args.all_ids();
+    let ids = stream_ids(&given, &source)?;
 
     let type_hint: Option<IdKind> = args.id_type;
+    let detection = args.detect.options();
 
     let encoding: EncodingFormat = args
         .format
@@ -27,110 +32,196 @@ pub fn execute(
         .transpose()?
         .unwrap_or(EncodingFormat::Canonical);
 
-    let mut results = Vec::new();
-    let mut failed = 0usize;
+    match args.to {
+        None => {}
+        Some(IdKind::UuidV7) => eprintln!(
+            "Warning: converting to UUIDv7 is lossy: sub-millisecond precision, clock sequence, and node are dropped"
+        ),
+        Some(kind) => {
+            return Err(IdtError::InvalidArgument(format!(
+                "Conversion to {} is not supported. Supported targets: uuidv7",
+                kind.name()
+            )));
+        }
+    }
 
-    for id in &ids {
-        match crate::ids::parse_id(id, type_hint) {
-            Ok(parsed) => {
-                let mut converted = parsed.encode(encoding);
+    // Plain output is written as each ID is converted; structured output is
+    // one document, so it is gathered first
+    let mut stdout = stdout();
+    let mut progress = Progress::new("convert", stream_total(&ids), false);
+    let terminator = args.records.terminator();
+    let mut entries = Vec::new();
+    // --qr-png numbers its files only when there are several IDs
+    let mut png_ids = Vec::new();
+    let mut count = 0usize;
+    let mut failed = 0usize;
 
-                // Apply case transformation
-                if args.uppercase {
-                    converted = converted.to_uppercase();
-                } else if args.lowercase {
-                    converted = converted.to_lowercase();
+    for sourced in ids {
+        let id = sourced?.id;
+        count += 1;
+        progress.tick(id.len() + 1);
+        match convert_one(&id, args, type_hint, &detection, encoding) {
+            Ok(converted) => {
+                if args.qr.qr_png.is_some() {
+                    png_ids.push(converted.clone());
+                }
+                if output_format.is_some() {
+                    entries.push(ConvertEntry::Ok(converted));
+                } else if args.qr.qr {
+                    qr::write_terminal(&mut stdout, &converted)?;
+                } else {
+                    write!(stdout, "{}{}", converted, terminator)?;
                 }
-
-                results.push(ConvertResult {
-                    input: id.clone(),
-                    output: converted,
-                    format: encoding.to_string(),
-                });
             }
             Err(e) => {
                 failed += 1;
-                eprintln!("Error converting '{}': {}", id, e);
+                if output_format.is_some() {
+                    // Structured output carries the error in place of the result
+                    entries.push(ConvertEntry::Err(ErrorEntry::new(&id, &e)));
+                } else {
+                    progress.clear();
+                    eprintln!("Error converting '{}': {}", id, e);
+                    if let Some(caret) = e.caret() {
+                        eprintln!("{}", caret);
+                    }
+                }
             }
         }
     }
 
-    // Output
-    let mut stdout = io::stdout();
+    progress.clear();
+
+    if count == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    if let Some(ref path) = args.qr.qr_png
+        && !png_ids.is_empty()
+    {
+        qr::write_pngs(&png_ids, path)?;
+    }
 
     if let Some(fmt) = output_format {
-        let output = if results.len() == 1 {
-            format_output(&results[0].output, fmt, pretty)?
+        if entries.len() == 1 {
+            write_output(&mut stdout, &entries[0], fmt, pretty)?;
         } else {
-            let outputs: Vec<&str> = results.iter().map(|r| r.output.as_str()).collect();
-            format_output(&outputs, fmt, pretty)?
-        };
-        writeln!(stdout, "{}", output)?;
-    } else {
-        output_plain(&mut stdout, &results)?;
+            write_output(&mut stdout, &entries, fmt, pretty)?;
+        }
     }
 
     if failed > 0 {
         return Err(IdtError::InvalidArgument(format!(
             "Failed to convert {} of {} IDs",
-            failed,
-            ids.len()
+            failed, count
         )));
     }
 
     Ok(())
 }
 
-#[derive(serde::Serialize)]
-struct ConvertResult {
-    input: String,
-    output: String,
-    format: String,
-}
-
-fn collect_ids(args: &[String]) -> Result<Vec<String>> {
-    if !args.is_empty() {
-        return Ok(args.to_vec());
+fn convert_one(
+    id: &str,
+    args: &ConvertArgs,
+    type_hint: Option<IdKind>,
+    detection: &DetectionOptions,
+    encoding: EncodingFormat,
+) -> Result<String> {
+    let mut parsed: Box<dyn ParsedId> = match (args.uuid_legacy, args.from) {
+        (Some(order), _) => {
+            let uuid = crate::ids::decode_legacy_uuid(id, order)?;
+            Box::new(crate::ids::ParsedUuid::parse(&uuid.to_string())?)
+        }
+        (None, Some(FromFormat::JavaLongs)) => {
+            let uuid = Uuid::from_bytes(decode_java_longs(id)?);
+            Box::new(crate::ids::ParsedUuid::parse(&uuid.to_string())?)
+        }
+        (None, None) => crate::ids::parse_id_with(id, type_hint, detection)?,
+    };
+    if args.to == Some(IdKind::UuidV7) {
+        let Some(uuid) = uuid_value(parsed.as_ref()) else {
+            return Err(IdtError::InvalidArgument(format!(
+                "--to uuidv7 only applies to UUIDs, not {}",
+                parsed.kind().name()
+            )));
+        };
+        let key = args.key.as_deref().map(str::as_bytes);
+        let v7 = crate::ids::upgrade_to_v7(&uuid, key)?;
+        parsed = Box::new(crate::ids::ParsedUuid::parse(&v7.to_string())?);
     }
-
-    // Don't block on stdin if it's a terminal (no piped input)
-    if std::io::IsTerminal::is_terminal(&io::stdin()) {
-        return Ok(Vec::new());
+    if let Some(db) = args.db {
+        return Ok(db.literal(parsed.as_ref()));
     }
+    if encoding == EncodingFormat::JavaLongs && parsed.as_bytes().len() != 16 {
+        return Err(IdtError::InvalidArgument(format!(
+            "java-longs needs a 128-bit ID, not {} ({} bits)",
+            parsed.kind().name(),
+            parsed.as_bytes().len() * 8
+        )));
+    }
+    if encoding == EncodingFormat::SortKey {
+        crate::core::sortkey::sort_key(parsed.as_ref())?;
+    }
+    let mut converted = match args.style {
+        Some(style) => match uuid_value(parsed.as_ref()) {
+            Some(uuid) => style.format(&uuid),
+            None => {
+                return Err(IdtError::InvalidArgument(format!(
+                    "--style only applies to UUIDs, not {}",
+                    parsed.kind().name()
+                )));
+            }
+        },
+        None => parsed.encode(encoding),
+    };
 
-    // Read from stdin
-    let stdin = io::stdin();
-    let mut ids = Vec::new();
-
-    for line in stdin.lock().lines() {
-        let line = line?;
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            ids.push(trimmed.to_string());
-        }
+    // Apply case transformation
+    if let Some(case) = args.case() {
+        case.apply(&mut converted);
     }
+    Ok(converted)
+}
 
-    Ok(ids)
+/// One entry per input in structured output, in input order: the converted
+/// string, or an error object
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum ConvertEntry {
+    Ok(String),
+    Err(ErrorEntry),
 }
 
-fn output_plain(writer: &mut dyn Write, results: &[ConvertResult]) -> Result<()> {
-    for result in results {
-        writeln!(writer, "{}", result.output)?;
+/// The UUID behind `parsed`, if it is any UUID version
+fn uuid_value(parsed: &dyn ParsedId) -> Option<Uuid> {
+    if !parsed.kind().is_uuid() {
+        return None;
     }
-    Ok(())
+    parsed.as_u128().map(Uuid::from_u128)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::app::OutputFormat;
+    use crate::cli::app::{Case, OutputFormat};
 
     fn make_args(ids: Vec<&str>) -> ConvertArgs {
         ConvertArgs {
+            first_id: None,
             ids: ids.into_iter().map(String::from).collect(),
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            qr: Default::default(),
             format: None,
+            style: None,
+            uuid_legacy: None,
+            from: None,
             to: None,
+            key: None,
+            db: None,
+            case: None,
             uppercase: false,
             lowercase: false,
         }
@@ -153,10 +244,21 @@ mod tests {
     #[test]
     fn test_convert_uuid_to_hex() {
         let args = ConvertArgs {
+            first_id: None,
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            qr: Default::default(),
             format: Some("hex".to_string()),
+            style: None,
+            uuid_legacy: None,
+            from: None,
             to: None,
+            key: None,
+            db: None,
+            case: None,
             uppercase: false,
             lowercase: false,
         };
@@ -167,10 +269,21 @@ mod tests {
     #[test]
     fn test_convert_uuid_to_base64() {
         let args = ConvertArgs {
+            first_id: None,
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            qr: Default::default(),
             format: Some("base64".to_string()),
+            style: None,
+            uuid_legacy: None,
+            from: None,
             to: None,
+            key: None,
+            db: None,
+            case: None,
             uppercase: false,
             lowercase: false,
         };
@@ -181,10 +294,21 @@ mod tests {
     #[test]
     fn test_convert_uppercase() {
         let args = ConvertArgs {
+            first_id: None,
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            qr: Default::default(),
             format: Some("hex".to_string()),
+            style: None,
+            uuid_legacy: None,
+            from: None,
             to: None,
+            key: None,
+            db: None,
+            case: None,
             uppercase: true,
             lowercase: false,
         };
@@ -195,10 +319,21 @@ mod tests {
     #[test]
     fn test_convert_lowercase() {
         let args = ConvertArgs {
+            first_id: None,
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            qr: Default::default(),
             format: Some("hex".to_string()),
+            style: None,
+            uuid_legacy: None,
+            from: None,
             to: None,
+            key: None,
+            db: None,
+            case: None,
             uppercase: false,
             lowercase: true,
         };
@@ -206,6 +341,32 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_convert_case_flags() {
+        let mut args = ConvertArgs {
+            first_id: None,
+            ids: vec![],
+            id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            qr: Default::default(),
+            format: None,
+            style: None,
+            uuid_legacy: None,
+            from: None,
+            to: None,
+            key: None,
+            db: None,
+            case: Some(Case::Lower),
+            uppercase: false,
+            lowercase: false,
+        };
+        assert_eq!(args.case(), Some(Case::Lower));
+        args.uppercase = true;
+        assert_eq!(args.case(), Some(Case::Upper));
+    }
+
     #[test]
     fn test_convert_json_output() {
         let args = make_args(vec!["550e8400-e29b-41d4-a716-446655440000"]);
@@ -223,17 +384,250 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_convert_json_keeps_errors_in_order() {
+        let args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV", "not-an-id"]);
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_err());
+
+        let entries = [
+            ConvertEntry::Ok("01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string()),
+            ConvertEntry::Err(ErrorEntry::new("not-an-id", &IdtError::DetectionFailed)),
+        ];
+        assert_eq!(
+            serde_json::to_value(entries).unwrap(),
+            serde_json::json!([
+                "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+                {
+                    "input": "not-an-id",
+                    "error": "Detection failed: could not determine ID type",
+                    "code": "detection_failed",
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_convert_style_non_uuid_error() {
+        let mut args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]);
+        args.style = Some(crate::ids::UuidStyle::Simple);
+        let detection = DetectionOptions::default();
+        let err = convert_one(
+            &args.ids[0],
+            &args,
+            None,
+            &detection,
+            EncodingFormat::Canonical,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "invalid_argument");
+    }
+
     #[test]
     fn test_convert_with_type_hint() {
         let args = ConvertArgs {
+            first_id: None,
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: Some(IdKind::Uuid),
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            qr: Default::default(),
             format: Some("base58".to_string()),
+            style: None,
+            uuid_legacy: None,
+            from: None,
             to: None,
+            key: None,
+            db: None,
+            case: None,
             uppercase: false,
             lowercase: false,
         };
         let result = execute(&args, None, false);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_convert_uuid_legacy() {
+        let mut args = make_args(vec![]);
+        args.uuid_legacy = Some(crate::ids::LegacyUuidOrder::CSharp);
+        let detection = DetectionOptions::default();
+        let converted = convert_one(
+            "BinData(3, \"MyIRAFVEd2aImaq7zN3u/w==\")",
+            &args,
+            None,
+            &detection,
+            EncodingFormat::Canonical,
+        )
+        .unwrap();
+        assert_eq!(converted, "00112233-4455-6677-8899-aabbccddeeff");
+    }
+
+    #[test]
+    fn test_convert_java_longs() {
+        let detection = DetectionOptions::default();
+        let mut args = make_args(vec![]);
+        let pair = convert_one(
+            "550e8400-e29b-41d4-a716-446655440000",
+            &args,
+            None,
+            &detection,
+            EncodingFormat::JavaLongs,
+        )
+        .unwrap();
+        assert_eq!(pair, "6128981282234515924,-6406858213580079104");
+
+        args.from = Some(FromFormat::JavaLongs);
+        let uuid = convert_one(&pair, &args, None, &detection, EncodingFormat::Canonical).unwrap();
+        assert_eq!(uuid, "550e8400-e29b-41d4-a716-446655440000");
+
+        args.from = None;
+        let err = convert_one(
+            "507f1f77bcf86cd799439011",
+            &args,
+            None,
+            &detection,
+            EncodingFormat::JavaLongs,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "invalid_argument");
+    }
+
+    #[test]
+    fn test_convert_to_uuidv7() {
+        let mut args = make_args(vec![]);
+        args.to = Some(IdKind::UuidV7);
+        args.key = Some("secret".to_string());
+        let detection = DetectionOptions::default();
+        let v1 = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+        let convert = |args: &ConvertArgs, id: &str| {
+            convert_one(id, args, None, &detection, EncodingFormat::Canonical)
+        };
+
+        let v7 = convert(&args, v1).unwrap();
+        assert_eq!(v7, convert(&args, v1).unwrap());
+        let parsed = crate::ids::parse_id(&v7, None).unwrap();
+        assert_eq!(parsed.kind(), IdKind::UuidV7);
+        let millis = |id: &dyn ParsedId| id.timestamp().map(|ts| ts.millis);
+        let original = crate::ids::parse_id(v1, None).unwrap();
+        assert_eq!(millis(parsed.as_ref()), millis(original.as_ref()));
+
+        assert!(convert(&args, "550e8400-e29b-41d4-a716-446655440000").is_err());
+        assert!(convert(&args, "01ARZ3NDEKTSV4RRFFQ69G5FAV").is_err());
+    }
+
+    #[test]
+    fn test_convert_to_unsupported_target() {
+        let mut args = make_args(vec!["6ba7b810-9dad-11d1-80b4-00c04fd430c8"]);
+        args.to = Some(IdKind::Ulid);
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_convert_db_literal() {
+        let mut args = make_args(vec![]);
+        args.db = Some(crate::core::db::Database::Mssql);
+        let converted = convert_one(
+            "550e8400-e29b-41d4-a716-446655440000",
+            &args,
+            None,
+            &DetectionOptions::default(),
+            EncodingFormat::Canonical,
+        )
+        .unwrap();
+        assert_eq!(
+            converted,
+            "CONVERT(uniqueidentifier, 0x00840E559BE2D441A716446655440000)"
+        );
+    }
+
+    #[test]
+    fn test_convert_uuid_style() {
+        let mut args = make_args(vec!["{550E8400-E29B-41D4-A716-446655440000}"]);
+        args.style = Some(crate::ids::UuidStyle::Urn);
+        assert!(execute(&args, None, false).is_ok());
+
+        let mut args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]);
+        args.style = Some(crate::ids::UuidStyle::Simple);
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_qr_needs_plain_output() {
+        let mut args = make_args(vec!["550e8400-e29b-41d4-a716-446655440000"]);
+        args.qr.qr = true;
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_err());
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_qr_png() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut args = make_args(vec![
+            "550e8400-e29b-41d4-a716-446655440000",
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+        ]);
+        args.qr.qr_png = Some(dir.path().join("id.png"));
+        execute(&args, Some(OutputFormat::Json), false).unwrap();
+        assert!(dir.path().join("id-1.png").exists());
+        assert!(dir.path().join("id-2.png").exists());
+    }
+
+    fn parse(args: &[&str]) -> ConvertArgs {
+        use crate::cli::{Cli, Commands};
+        use clap::Parser;
+
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Commands::Convert(args) = cli.command else {
+            panic!("expected convert");
+        };
+        args
+    }
+
+    #[test]
+    fn test_negative_java_longs_argument() {
+        let args = parse(&[
+            "idt",
+            "convert",
+            "--from",
+            "java-longs",
+            "-5403194877577133845,-7865837820844897439",
+        ]);
+        assert_eq!(
+            args.all_ids(),
+            ["-5403194877577133845,-7865837820844897439"]
+        );
+        assert!(matches!(args.from, Some(FromFormat::JavaLongs)));
+        assert!(execute(&args, None, false).is_ok());
+
+        // Flags after the IDs are still flags
+        let args = parse(&[
+            "idt",
+            "convert",
+            "-5403194877577133845,-7865837820844897439",
+            "--from",
+            "java-longs",
+            "-f",
+            "hex",
+        ]);
+        assert_eq!(
+            args.all_ids(),
+            ["-5403194877577133845,-7865837820844897439"]
+        );
+        assert_eq!(args.format.as_deref(), Some("hex"));
+    }
+
+    #[test]
+    fn test_flags_after_ids() {
+        let args = parse(&[
+            "idt",
+            "convert",
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "-123",
+            "-f",
+            "hex",
+        ]);
+        assert_eq!(args.all_ids(), ["01ARZ3NDEKTSV4RRFFQ69G5FAV", "-123"]);
+        assert_eq!(args.format.as_deref(), Some("hex"));
+    }
 }
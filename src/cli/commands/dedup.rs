@@ -0,0 +1,173 @@
+use crate::cli::app::{DedupArgs, OutputFormat};
+use crate::cli::input::{InputSource, SourcedId, stream_ids, text_lines};
+use crate::cli::output::{stdout, write_output};
+use crate::core::bloom::{BloomFilter, Seen};
+use crate::core::error::{IdtError, Result};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+
+pub fn execute(args: &DedupArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let mut seen = if args.approximate {
+        Seen::Approximate(BloomFilter::new(args.expected, args.fp_rate)?)
+    } else {
+        Seen::Exact(HashSet::new())
+    };
+    let mut stats = DedupStats::default();
+    let mut writer = BufWriter::new(stdout());
+
+    if args.files.is_empty() {
+        // Don't block on stdin if it's a terminal (no piped input)
+        if std::io::IsTerminal::is_terminal(&io::stdin()) {
+            return Err(IdtError::InvalidArgument(
+                "No IDs provided. Pass files as arguments or via stdin.".to_string(),
+            ));
+        }
+        let ids = stream_ids(&[], &InputSource::Text)?;
+        dedup_stream(ids, &mut writer, &mut seen, &mut stats)?;
+    } else {
+        for path in &args.files {
+            let ids = text_lines(BufReader::new(File::open(path)?));
+            dedup_stream(ids, &mut writer, &mut seen, &mut stats)?;
+        }
+    }
+    writer.flush()?;
+
+    if args.stats {
+        if let Seen::Approximate(ref filter) = seen {
+            stats.false_positive_rate = Some(filter.current_fp_rate());
+            stats.filter_bytes = Some(filter.memory_bytes());
+        }
+
+        let mut stderr = io::stderr();
+        if let Some(fmt) = output_format {
+            write_output(&mut stderr, &stats, fmt, pretty)?;
+        } else {
+            writeln!(stderr, "{}", stats)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the first occurrence of each of `ids`, one per line
+fn dedup_stream(
+    ids: impl Iterator<Item = Result<SourcedId>>,
+    writer: &mut dyn Write,
+    seen: &mut Seen,
+    stats: &mut DedupStats,
+) -> Result<()> {
+    for sourced in ids {
+        let id = sourced?.id;
+        stats.total += 1;
+        if seen.check_and_insert(&id) {
+            stats.duplicates += 1;
+        } else {
+            stats.unique += 1;
+            writer.write_all(id.as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default, serde::Serialize)]
+struct DedupStats {
+    total: u64,
+    unique: u64,
+    /// With a Bloom filter this is an estimate: false positives count as duplicates
+    duplicates: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    false_positive_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter_bytes: Option<usize>,
+}
+
+impl std::fmt::Display for DedupStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} IDs, {} unique, {} duplicates",
+            self.total, self.unique, self.duplicates
+        )?;
+        if let Some(rate) = self.false_positive_rate {
+            write!(f, " (estimated, false-positive rate {:.1e})", rate)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+
+    fn dedup(input: &str, seen: &mut Seen) -> (String, DedupStats) {
+        let mut output = Vec::new();
+        let mut stats = DedupStats::default();
+        dedup_stream(text_lines(input.as_bytes()), &mut output, seen, &mut stats).unwrap();
+        (String::from_utf8(output).unwrap(), stats)
+    }
+
+    #[test]
+    fn test_dedup_exact() {
+        let (out, stats) = dedup("a\nb\n a\n\nc\nb\n", &mut Seen::Exact(HashSet::new()));
+        assert_eq!(out, "a\nb\nc\n");
+        assert_eq!((stats.total, stats.unique, stats.duplicates), (5, 3, 2));
+    }
+
+    #[test]
+    fn test_dedup_nul_separated() {
+        let (out, stats) = dedup("a\0b\0a\0", &mut Seen::Exact(HashSet::new()));
+        assert_eq!(out, "a\nb\n");
+        assert_eq!(stats.duplicates, 1);
+    }
+
+    #[test]
+    fn test_dedup_approximate() {
+        let filter = BloomFilter::new(1000, 1e-9).unwrap();
+        let input: String = (0..1000).map(|i| format!("id-{}\n", i % 600)).collect();
+        let (out, stats) = dedup(&input, &mut Seen::Approximate(filter));
+        assert_eq!(out.lines().count(), 600);
+        assert_eq!(stats.duplicates, 400);
+    }
+
+    #[test]
+    fn test_stats_display() {
+        let mut stats = DedupStats {
+            total: 10,
+            unique: 7,
+            duplicates: 3,
+            ..Default::default()
+        };
+        assert_eq!(stats.to_string(), "10 IDs, 7 unique, 3 duplicates");
+        stats.false_positive_rate = Some(1.5e-9);
+        assert_eq!(
+            stats.to_string(),
+            "10 IDs, 7 unique, 3 duplicates (estimated, false-positive rate 1.5e-9)"
+        );
+    }
+
+    #[test]
+    fn test_parse_expected_suffixes() {
+        let expected = |value: &str| {
+            let cli = Cli::try_parse_from(["idt", "dedup", "-a", "--expected", value])?;
+            match cli.command {
+                Commands::Dedup(args) => Ok::<_, clap::Error>(args.expected),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(expected("500M").unwrap(), 500_000_000);
+        assert_eq!(expected("1.5k").unwrap(), 1500);
+        assert_eq!(expected("2B").unwrap(), 2_000_000_000);
+        assert_eq!(expected("1_000").unwrap(), 1000);
+        assert!(expected("10X").is_err());
+        assert!(expected("-5").is_err());
+    }
+
+    #[test]
+    fn test_expected_requires_approximate() {
+        assert!(Cli::try_parse_from(["idt", "dedup", "--expected", "5M"]).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+use crate::cli::app::{DoctorArgs, OutputFormat};
+use crate::cli::output::{stdout, write_output};
+use crate::core::components::Components;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdGenerator, ParsedId};
+use crate::ids::{ObjectIdGenerator, ParsedObjectId, ParsedXid, XidGenerator};
+use chrono::Utc;
+use colored::Colorize;
+use rand::TryRng;
+use rand::rngs::SysRng;
+use serde::Serialize;
+use std::io::Write;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Wall-clock reads taken to look for backward steps and the tick size
+const CLOCK_SAMPLES: usize = 200_000;
+
+/// How long the wall clock is compared against the monotonic clock
+const DRIFT_WINDOW: Duration = Duration::from_millis(50);
+
+/// Drift over `DRIFT_WINDOW` beyond which the clock is being adjusted
+const MAX_DRIFT: Duration = Duration::from_millis(5);
+
+/// Clocks reading earlier than this (2020-01-01) were never set
+const MIN_PLAUSIBLE_SECS: i64 = 1_577_836_800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Info,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: String) -> Self {
+        Self {
+            name,
+            status,
+            detail,
+        }
+    }
+}
+
+pub fn execute(
+    args: &DoctorArgs,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    let samples = sample_clock(CLOCK_SAMPLES);
+    let checks = vec![
+        check_clock(),
+        check_clock_order(&samples),
+        check_resolution(&samples),
+        check_rng(),
+        check_pid(std::process::id()),
+        check_machine_ids()?,
+        check_snowflake(),
+    ];
+
+    let mut stdout = stdout();
+    if let Some(fmt) = format {
+        write_output(&mut stdout, &checks, fmt, pretty)?;
+    } else {
+        output_plain(&mut stdout, &checks, no_color)?;
+    }
+
+    let failed = checks
+        .iter()
+        .any(|c| c.status == Status::Fail || (args.strict && c.status == Status::Warn));
+    if failed {
+        return Err(IdtError::CheckFailed("One or more checks failed".into()));
+    }
+    Ok(())
+}
+
+/// The wall clock is set and keeps pace with the monotonic clock
+fn check_clock() -> Check {
+    let now = Utc::now();
+    if now.timestamp() < MIN_PLAUSIBLE_SECS {
+        return Check::new(
+            "clock",
+            Status::Fail,
+            format!(
+                "System clock reads {}; it looks unset, so every timestamped ID will be wrong",
+                now.to_rfc3339()
+            ),
+        );
+    }
+
+    let (wall, mono) = (SystemTime::now(), Instant::now());
+    std::thread::sleep(DRIFT_WINDOW);
+    let wall_elapsed = wall.elapsed().unwrap_or_default();
+    let drift = wall_elapsed.abs_diff(mono.elapsed());
+
+    if drift > MAX_DRIFT {
+        Check::new(
+            "clock",
+            Status::Warn,
+            format!(
+                "Wall clock moved {} against the monotonic clock in {}; it is being adjusted, \
+                 so time-ordered IDs may come out of order",
+                format_duration(drift),
+                format_duration(DRIFT_WINDOW)
+            ),
+        )
+    } else {
+        Check::new(
+            "clock",
+            Status::Ok,
+            format!(
+                "Wall clock reads {} and kept pace with the monotonic clock",
+                now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            ),
+        )
+    }
+}
+
+/// What consecutive wall-clock reads reveal
+#[derive(Debug, Default, PartialEq)]
+struct ClockSamples {
+    reads: usize,
+    backward_steps: usize,
+    max_backward: Duration,
+    /// Smallest forward step seen, if the clock advanced at all
+    resolution: Option<Duration>,
+}
+
+fn sample_clock(count: usize) -> ClockSamples {
+    let reads: Vec<u128> = (0..count)
+        .map(|_| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos())
+        })
+        .collect();
+    analyze_clock(&reads)
+}
+
+/// Summarize wall-clock reads in nanoseconds since the Unix epoch
+fn analyze_clock(reads: &[u128]) -> ClockSamples {
+    let mut samples = ClockSamples {
+        reads: reads.len(),
+        ..Default::default()
+    };
+    for pair in reads.windows(2) {
+        let (prev, next) = (pair[0], pair[1]);
+        if next < prev {
+            samples.backward_steps += 1;
+            let step = Duration::from_nanos((prev - next) as u64);
+            samples.max_backward = samples.max_backward.max(step);
+        } else if next > prev {
+            let step = Duration::from_nanos((next - prev) as u64);
+            samples.resolution = Some(samples.resolution.map_or(step, |r| r.min(step)));
+        }
+    }
+    samples
+}
+
+fn check_clock_order(samples: &ClockSamples) -> Check {
+    if samples.backward_steps == 0 {
+        return Check::new(
+            "clock_order",
+            Status::Ok,
+            format!("No backward steps in {} reads", samples.reads),
+        );
+    }
+    Check::new(
+        "clock_order",
+        Status::Warn,
+        format!(
+            "Wall clock stepped backwards {} time(s) in {} reads, by up to {}; \
+             IDs made across a step sort before earlier ones",
+            samples.backward_steps,
+            samples.reads,
+            format_duration(samples.max_backward)
+        ),
+    )
+}
+
+fn check_resolution(samples: &ClockSamples) -> Check {
+    match samples.resolution {
+        Some(step) if step <= Duration::from_millis(1) => Check::new(
+            "resolution",
+            Status::Ok,
+            format!("System time advances in steps of {}", format_duration(step)),
+        ),
+        Some(step) => Check::new(
+            "resolution",
+            Status::Warn,
+            format!(
+                "System time advances in steps of {}, coarser than the 1 ms most IDs record; \
+                 IDs made within one step share a timestamp",
+                format_duration(step)
+            ),
+        ),
+        None => Check::new(
+            "resolution",
+            Status::Warn,
+            format!("System time did not advance in {} reads", samples.reads),
+        ),
+    }
+}
+
+/// The OS random source answers and does not look stuck
+fn check_rng() -> Check {
+    let mut first = [0u8; 4096];
+    let mut second = [0u8; 32];
+    if let Err(e) = SysRng
+        .try_fill_bytes(&mut first)
+        .and_then(|_| SysRng.try_fill_bytes(&mut second))
+    {
+        return Check::new(
+            "rng",
+            Status::Fail,
+            format!("OS random source failed: {}", e),
+        );
+    }
+
+    let ones: u32 = first.iter().map(|b| b.count_ones()).sum();
+    let ratio = ones as f64 / (first.len() * 8) as f64;
+    // 32768 bits put a healthy source within 0.3% of half; 3% is far outside
+    if !(0.47..=0.53).contains(&ratio) || first[..32] == second {
+        return Check::new(
+            "rng",
+            Status::Fail,
+            format!(
+                "OS random source looks broken ({:.1}% one bits, repeated output); \
+                 random IDs may collide",
+                ratio * 100.0
+            ),
+        );
+    }
+    Check::new(
+        "rng",
+        Status::Ok,
+        format!(
+            "OS random source returned {} bytes, {:.1}% one bits",
+            first.len(),
+            ratio * 100.0
+        ),
+    )
+}
+
+/// The process ID, which Xid embeds as its low 16 bits
+fn check_pid(pid: u32) -> Check {
+    let embedded = pid & 0xFFFF;
+    if embedded != pid {
+        return Check::new(
+            "pid",
+            Status::Warn,
+            format!(
+                "PID {} is truncated to {} in Xids; processes whose PIDs differ by a multiple \
+                 of 65536 share a process field",
+                pid, embedded
+            ),
+        );
+    }
+    Check::new(
+        "pid",
+        Status::Info,
+        format!("PID {}, embedded as-is in Xids", pid),
+    )
+}
+
+/// The per-run values Xid and ObjectId mix in, read back from fresh IDs
+fn check_machine_ids() -> Result<Check> {
+    let xid = ParsedXid::parse(&XidGenerator::new().generate()?)?;
+    let oid = ParsedObjectId::parse(&ObjectIdGenerator::new().generate()?)?;
+    let (Some(Components::Xid(xid)), Some(Components::ObjectId(oid))) =
+        (xid.components(), oid.components())
+    else {
+        unreachable!("Xid and ObjectId always have components");
+    };
+    Ok(Check::new(
+        "machine_id",
+        Status::Info,
+        format!(
+            "Xid machine ID {} and ObjectId random value {} are drawn fresh for every run",
+            hex::encode(xid.machine_id),
+            hex::encode(oid.random)
+        ),
+    ))
+}
+
+fn check_snowflake() -> Check {
+    Check::new(
+        "snowflake",
+        Status::Info,
+        "Snowflake uses machine_id 0 and datacenter_id 0 unless --machine-id, \
+         --datacenter-id, or --state-file is given; concurrent hosts need distinct values \
+         (e.g. --machine-id auto:ip)"
+            .to_string(),
+    )
+}
+
+fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{} ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1} µs", nanos as f64 / 1e3)
+    } else {
+        format!("{:.1} ms", nanos as f64 / 1e6)
+    }
+}
+
+fn output_plain(writer: &mut dyn Write, checks: &[Check], no_color: bool) -> Result<()> {
+    for check in checks {
+        let label = match check.status {
+            Status::Ok => "ok",
+            Status::Info => "info",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        };
+        let label = format!("{:<4}", label);
+        let label = match (no_color, check.status) {
+            (true, _) => label,
+            (false, Status::Ok) => label.green().to_string(),
+            (false, Status::Info) => label.blue().to_string(),
+            (false, Status::Warn) => label.yellow().to_string(),
+            (false, Status::Fail) => label.red().bold().to_string(),
+        };
+        writeln!(writer, "{}  {:<11}  {}", label, check.name, check.detail)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_clock() {
+        let samples = analyze_clock(&[100, 100, 1_100, 900, 2_000, 2_050]);
+        assert_eq!(
+            samples,
+            ClockSamples {
+                reads: 6,
+                backward_steps: 1,
+                max_backward: Duration::from_nanos(200),
+                resolution: Some(Duration::from_nanos(50)),
+            }
+        );
+        assert_eq!(analyze_clock(&[5, 5, 5]).resolution, None);
+    }
+
+    #[test]
+    fn test_clock_checks() {
+        let steady = analyze_clock(&[0, 1_000, 2_000]);
+        assert_eq!(check_clock_order(&steady).status, Status::Ok);
+        assert_eq!(check_resolution(&steady).status, Status::Ok);
+
+        let coarse = analyze_clock(&[0, 15_000_000, 10_000_000]);
+        assert_eq!(check_clock_order(&coarse).status, Status::Warn);
+        assert_eq!(check_resolution(&coarse).status, Status::Warn);
+    }
+
+    #[test]
+    fn test_check_pid() {
+        assert_eq!(check_pid(4242).status, Status::Info);
+        let truncated = check_pid(70_000);
+        assert_eq!(truncated.status, Status::Warn);
+        assert!(truncated.detail.contains("4464"));
+    }
+
+    #[test]
+    fn test_execute_json() {
+        let args = DoctorArgs { strict: false };
+        assert!(execute(&args, Some(OutputFormat::Json), false, true).is_ok());
+    }
+}
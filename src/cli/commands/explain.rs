@@ -0,0 +1,489 @@
+use crate::cli::app::{ExplainArgs, OutputFormat};
+use crate::cli::output::{stdout, write_output};
+use crate::core::detection::{detect_id_type_with, detection_rule};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdEncodings, IdKind, ParsedId, SizeUnit, StructureSegment};
+use crate::ids::snowflake_id::SnowflakeLayout;
+use colored::Colorize;
+use serde::Serialize;
+use std::io::Write;
+
+/// Columns the layout diagram aims for; narrow fields still get room for
+/// their names
+const DIAGRAM_WIDTH: usize = 64;
+
+#[derive(Debug, Serialize)]
+struct Explanation {
+    id_type: &'static str,
+    description: &'static str,
+    input: String,
+    canonical: String,
+    detection: Detection,
+    /// What field ranges count: bits, digits, or chars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unit: Option<SizeUnit>,
+    /// Width of the whole ID in `unit`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u32>,
+    fields: Vec<Field>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp_iso: Option<String>,
+    encodings: IdEncodings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spec_url: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Detection {
+    /// The type came from --id-type rather than detection
+    given: bool,
+    rule: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidence: Option<f32>,
+    /// Other types the input could have been, most likely first
+    alternatives: Vec<Candidate>,
+}
+
+#[derive(Debug, Serialize)]
+struct Candidate {
+    id_type: &'static str,
+    confidence: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Field {
+    name: String,
+    /// First and last position, counted from the most significant end;
+    /// absent when the structure mixes units
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end: Option<u32>,
+    size: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    description: String,
+}
+
+pub fn execute(
+    args: &ExplainArgs,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    let layout = if args.preset.is_some() || args.epoch.is_some() {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    let explanations = args
+        .ids
+        .iter()
+        .map(|id| explain(id, args.id_type, layout.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut stdout = stdout();
+    if let Some(fmt) = format {
+        if let [one] = explanations.as_slice() {
+            write_output(&mut stdout, one, fmt, pretty)?;
+        } else {
+            write_output(&mut stdout, &explanations, fmt, pretty)?;
+        }
+        return Ok(());
+    }
+    for (i, explanation) in explanations.iter().enumerate() {
+        if i > 0 {
+            writeln!(stdout)?;
+        }
+        print_explanation(&mut stdout, explanation, no_color)?;
+    }
+    Ok(())
+}
+
+fn explain(
+    input: &str,
+    type_hint: Option<IdKind>,
+    layout: Option<&SnowflakeLayout>,
+) -> Result<Explanation> {
+    let parsed: Box<dyn ParsedId> = match layout {
+        Some(layout) => Box::new(crate::ids::ParsedSnowflake::parse_with_layout(
+            input,
+            layout.clone(),
+        )?),
+        None => crate::ids::parse_id(input, type_hint)?,
+    };
+    let kind = parsed.kind();
+    let inspection = parsed.inspect();
+
+    let detection = if type_hint.is_some() || layout.is_some() {
+        Detection {
+            given: true,
+            rule: detection_rule(kind),
+            confidence: None,
+            alternatives: Vec::new(),
+        }
+    } else {
+        let candidates = detect_id_type_with(input, &Default::default())
+            .map_err(|_| IdtError::DetectionFailed)?;
+        let chosen = candidates
+            .iter()
+            .find(|c| kind.matches_filter(c.kind) || c.kind.matches_filter(kind));
+        Detection {
+            given: false,
+            rule: detection_rule(chosen.map_or(kind, |c| c.kind)),
+            confidence: chosen.map(|c| c.confidence),
+            alternatives: candidates
+                .iter()
+                .filter(|c| chosen.is_none_or(|chosen| chosen.kind != c.kind))
+                .map(|c| Candidate {
+                    id_type: c.kind.name(),
+                    confidence: c.confidence,
+                })
+                .collect(),
+        }
+    };
+
+    let structure = inspection.structure.unwrap_or_default();
+    let byte_bits = (inspection.encodings.hex.len() / 2 * 8) as u32;
+    let (unit, size, fields) = fields(&structure, byte_bits);
+
+    Ok(Explanation {
+        id_type: kind.name(),
+        description: kind.description(),
+        input: input.trim().to_string(),
+        canonical: inspection.canonical,
+        detection,
+        unit,
+        size,
+        fields,
+        timestamp_iso: inspection.timestamp_iso,
+        encodings: inspection.encodings,
+        spec_url: kind.spec_url(),
+    })
+}
+
+/// Number the structure's fields from the most significant end. Bit
+/// layouts narrower than the ID's bytes (a 63-bit Snowflake) are aligned to
+/// the last bit, with the rest shown as unused.
+fn fields(
+    structure: &[StructureSegment],
+    byte_bits: u32,
+) -> (Option<SizeUnit>, Option<u32>, Vec<Field>) {
+    let Some(unit) = structure.first().map(|s| s.unit) else {
+        return (None, None, Vec::new());
+    };
+    if structure.iter().any(|s| s.unit != unit) {
+        let fields = structure
+            .iter()
+            .map(|s| Field {
+                name: s.name.clone(),
+                start: None,
+                end: None,
+                size: s.size,
+                value: s.value.clone(),
+                description: s.description.clone(),
+            })
+            .collect();
+        return (None, None, fields);
+    }
+
+    let used: u32 = structure.iter().map(|s| s.size).sum();
+    let mut fields = Vec::new();
+    let mut start = 0;
+    if unit == SizeUnit::Bits && used < byte_bits {
+        fields.push(Field {
+            name: "unused".to_string(),
+            start: Some(0),
+            end: Some(byte_bits - used - 1),
+            size: byte_bits - used,
+            value: None,
+            description: "Always zero".to_string(),
+        });
+        start = byte_bits - used;
+    }
+    for s in structure {
+        fields.push(Field {
+            name: s.name.clone(),
+            start: Some(start),
+            end: Some(start + s.size - 1),
+            size: s.size,
+            value: s.value.clone(),
+            description: s.description.clone(),
+        });
+        start += s.size;
+    }
+    (Some(unit), Some(start), fields)
+}
+
+/// A box per field, sized roughly by its share of the ID, with the first
+/// position of each field underneath:
+///
+/// ```text
+/// +------------------+-----------------------------------+
+/// |    timestamp     |              random               |
+/// +------------------+-----------------------------------+
+/// 0                  48                                127
+/// ```
+fn layout_diagram(fields: &[(&str, u32)]) -> Vec<String> {
+    let total: u32 = fields.iter().map(|&(_, size)| size).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+    let widths: Vec<usize> = fields
+        .iter()
+        .map(|&(name, size)| {
+            let share = (size as usize * DIAGRAM_WIDTH).div_ceil(total as usize);
+            share.max(name.len() + 2)
+        })
+        .collect();
+
+    let border = widths.iter().fold("+".to_string(), |mut line, &w| {
+        line.push_str(&"-".repeat(w));
+        line.push('+');
+        line
+    });
+    let names = fields
+        .iter()
+        .zip(&widths)
+        .fold("|".to_string(), |mut line, (&(name, _), &w)| {
+            line.push_str(&format!("{:^w$}|", name));
+            line
+        });
+
+    let mut positions = String::new();
+    let mut column = 0;
+    let mut start = 0;
+    for (&(_, size), &w) in fields.iter().zip(&widths) {
+        let label = start.to_string();
+        if column == 0 || positions.len() < column {
+            positions.push_str(&" ".repeat(column - positions.len()));
+            positions.push_str(&label);
+        }
+        column += w + 1;
+        start += size;
+    }
+    let last = (total - 1).to_string();
+    if positions.len() + 1 < column + 1 - last.len() {
+        positions.push_str(&" ".repeat(column + 1 - last.len() - positions.len()));
+        positions.push_str(&last);
+    }
+
+    vec![border.clone(), names, border, positions]
+}
+
+fn print_explanation(writer: &mut dyn Write, e: &Explanation, no_color: bool) -> Result<()> {
+    let heading = |s: &str| -> String {
+        if no_color {
+            s.to_string()
+        } else {
+            s.bold().to_string()
+        }
+    };
+    let dim = |s: &str| -> String {
+        if no_color {
+            s.to_string()
+        } else {
+            s.dimmed().to_string()
+        }
+    };
+
+    writeln!(
+        writer,
+        "{}  {}",
+        heading(&e.id_type.to_uppercase()),
+        e.description
+    )?;
+    writeln!(writer, "  {}", e.canonical)?;
+
+    writeln!(writer)?;
+    writeln!(writer, "{}", heading(&format!("Why {}", e.id_type)))?;
+    if e.detection.given {
+        writeln!(writer, "  Type given on the command line")?;
+        writeln!(
+            writer,
+            "  {}",
+            dim(&format!("Looks like: {}", e.detection.rule))
+        )?;
+    } else {
+        let confidence = e
+            .detection
+            .confidence
+            .map(|c| format!(" (confidence {:.2})", c))
+            .unwrap_or_default();
+        writeln!(writer, "  Matches {}{}", e.detection.rule, confidence)?;
+        if !e.detection.alternatives.is_empty() {
+            let others = e
+                .detection
+                .alternatives
+                .iter()
+                .map(|c| format!("{} ({:.2})", c.id_type, c.confidence))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(writer, "  {}", dim(&format!("Also possible: {}", others)))?;
+        }
+    }
+
+    if e.fields.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "{}", heading("Layout"))?;
+        writeln!(
+            writer,
+            "  No internal fields: the whole ID is random or opaque"
+        )?;
+    } else {
+        if let (Some(unit), Some(size)) = (e.unit, e.size) {
+            writeln!(writer)?;
+            writeln!(
+                writer,
+                "{}",
+                heading(&format!(
+                    "Layout ({} {}, most significant first)",
+                    size,
+                    unit_name(unit)
+                ))
+            )?;
+            let boxes: Vec<(&str, u32)> =
+                e.fields.iter().map(|f| (f.name.as_str(), f.size)).collect();
+            for line in layout_diagram(&boxes) {
+                writeln!(writer, "  {}", line)?;
+            }
+        }
+
+        writeln!(writer)?;
+        writeln!(writer, "{}", heading("Fields"))?;
+        let ranges: Vec<String> = e
+            .fields
+            .iter()
+            .map(|f| match (f.start, f.end) {
+                (Some(start), Some(end)) if start == end => start.to_string(),
+                (Some(start), Some(end)) => format!("{}-{}", start, end),
+                _ => "-".to_string(),
+            })
+            .collect();
+        let range_width = ranges.iter().map(String::len).max().unwrap_or(0);
+        let name_width = e.fields.iter().map(|f| f.name.len()).max().unwrap_or(0);
+        let value_width = e
+            .fields
+            .iter()
+            .map(|f| f.value.as_deref().unwrap_or("-").len())
+            .max()
+            .unwrap_or(0);
+        for (field, range) in e.fields.iter().zip(&ranges) {
+            let name = format!("{:<name_width$}", field.name);
+            let name = if no_color {
+                name
+            } else {
+                name.cyan().to_string()
+            };
+            writeln!(
+                writer,
+                "  {:<range_width$}  {}  {:<value_width$}  {}",
+                range,
+                name,
+                field.value.as_deref().unwrap_or("-"),
+                dim(&field.description),
+            )?;
+        }
+    }
+
+    if let Some(ref iso) = e.timestamp_iso {
+        writeln!(writer)?;
+        writeln!(writer, "{}", heading("Time"))?;
+        writeln!(writer, "  {} (UTC)", iso)?;
+    }
+
+    writeln!(writer)?;
+    writeln!(writer, "{}", heading("Encodings"))?;
+    writeln!(writer, "  Hex     {}", e.encodings.hex)?;
+    for (label, value) in [
+        ("Base32", &e.encodings.base32),
+        ("Base58", &e.encodings.base58),
+        ("Base64", &e.encodings.base64),
+    ] {
+        if !value.is_empty() {
+            writeln!(writer, "  {:<7} {}", label, value)?;
+        }
+    }
+    if let Some(ref int) = e.encodings.int {
+        writeln!(writer, "  Int     {}", int)?;
+    }
+
+    if let Some(url) = e.spec_url {
+        writeln!(writer)?;
+        writeln!(writer, "{}", heading("Spec"))?;
+        writeln!(writer, "  {}", url)?;
+    }
+    Ok(())
+}
+
+fn unit_name(unit: SizeUnit) -> &'static str {
+    match unit {
+        SizeUnit::Bits => "bits",
+        SizeUnit::Digits => "digits",
+        SizeUnit::Chars => "chars",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_diagram() {
+        let lines = layout_diagram(&[("timestamp", 48), ("random", 80)]);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], lines[2]);
+        assert_eq!(lines[0].len(), lines[1].len());
+        assert!(lines[1].contains("timestamp") && lines[1].contains("random"));
+        assert!(lines[3].starts_with("0 "));
+        assert!(lines[3].contains(" 48 "));
+        assert!(lines[3].ends_with("127"));
+        assert_eq!(lines[3].len(), lines[0].len());
+    }
+
+    #[test]
+    fn test_narrow_fields_keep_their_names() {
+        let lines = layout_diagram(&[("ver", 4), ("rest", 124)]);
+        assert!(lines[1].contains("|ver |") || lines[1].contains("| ver |"));
+    }
+
+    #[test]
+    fn test_explain_ulid() {
+        let e = explain("01ARZ3NDEKTSV4RRFFQ69G5FAV", None, None).unwrap();
+        assert_eq!(e.id_type, "ulid");
+        assert!(!e.detection.given);
+        assert_eq!(e.detection.confidence, Some(0.95));
+        assert_eq!(e.size, Some(128));
+        assert_eq!(e.fields[0].start, Some(0));
+        assert_eq!(e.fields[0].end, Some(47));
+        assert_eq!(e.fields[1].start, Some(48));
+        assert_eq!(e.spec_url, Some("https://github.com/ulid/spec"));
+
+        let mut out = Vec::new();
+        print_explanation(&mut out, &e, true).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Why ulid"));
+        assert!(out.contains("0-47"));
+        assert!(out.contains("https://github.com/ulid/spec"));
+    }
+
+    #[test]
+    fn test_explain_snowflake_marks_unused_bit() {
+        let e = explain("1234567890123456789", None, None).unwrap();
+        assert_eq!(e.fields[0].name, "unused");
+        assert_eq!(e.fields[0].end, Some(0));
+        assert_eq!(e.fields[1].start, Some(1));
+        assert_eq!(e.size, Some(64));
+    }
+
+    #[test]
+    fn test_explain_with_hint() {
+        let e = explain("1234567890123456789", Some(IdKind::Snowflake), None).unwrap();
+        assert!(e.detection.given);
+        assert!(e.detection.alternatives.is_empty());
+    }
+}
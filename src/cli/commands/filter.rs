@@ -0,0 +1,228 @@
+use crate::cli::app::{FilterArgs, OutputFormat};
+use crate::cli::input::{InputSource, stream_ids};
+use crate::cli::output::{stdout, write_output};
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId};
+use crate::core::time::parse_time;
+use crate::ids::snowflake_id::SnowflakeLayout;
+use chrono::Utc;
+use std::io::Write;
+
+pub fn execute(args: &FilterArgs, format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let now = Utc::now();
+    let window = TimeWindow {
+        after: args
+            .after
+            .as_deref()
+            .map(|t| parse_time(t, now))
+            .transpose()?,
+        before: args
+            .before
+            .as_deref()
+            .map(|t| parse_time(t, now))
+            .transpose()?,
+    };
+    if let (Some(after), Some(before)) = (window.after, window.before)
+        && after >= before
+    {
+        return Err(IdtError::InvalidArgument(
+            "--after must be earlier than --before".to_string(),
+        ));
+    }
+
+    let snowflake_layout = if args.preset.is_some() || args.epoch.is_some() {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    let filter = Filter {
+        window,
+        type_hint: args.id_type,
+        detection: args.detect.options(),
+        snowflake_layout,
+    };
+
+    let mut stdout = stdout();
+    let mut kept = Vec::new();
+    let mut keep = |entry: FilterEntry| -> Result<()> {
+        if format.is_some() {
+            kept.push(entry);
+        } else {
+            writeln!(stdout, "{}", entry.id)?;
+        }
+        Ok(())
+    };
+
+    // Streamed so long pipelines see output as it arrives
+    let mut count = 0usize;
+    for sourced in stream_ids(&args.ids, &InputSource::Text)? {
+        let id = sourced?.id;
+        count += 1;
+        if let Some(entry) = filter.check(&id) {
+            keep(entry)?;
+        }
+    }
+
+    if count == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    if let Some(fmt) = format {
+        write_output(&mut stdout, &kept, fmt, pretty)?;
+    }
+
+    Ok(())
+}
+
+/// Half-open window: `after` is inclusive, `before` exclusive (Unix millis)
+#[derive(Debug, Clone, Copy, Default)]
+struct TimeWindow {
+    after: Option<i64>,
+    before: Option<i64>,
+}
+
+impl TimeWindow {
+    fn contains(&self, millis: i64) -> bool {
+        self.after.is_none_or(|after| millis >= after)
+            && self.before.is_none_or(|before| millis < before)
+    }
+}
+
+struct Filter {
+    window: TimeWindow,
+    type_hint: Option<IdKind>,
+    detection: DetectionOptions,
+    snowflake_layout: Option<SnowflakeLayout>,
+}
+
+impl Filter {
+    /// The entry for `id` if its timestamp is inside the window. IDs that
+    /// fail to parse or carry no timestamp are skipped with a warning.
+    fn check(&self, id: &str) -> Option<FilterEntry> {
+        let parsed: Result<Box<dyn ParsedId>> = match self.snowflake_layout {
+            Some(ref layout) => crate::ids::ParsedSnowflake::parse_with_layout(id, layout.clone())
+                .map(|s| Box::new(s) as Box<dyn ParsedId>),
+            None => crate::ids::parse_id_with(id, self.type_hint, &self.detection),
+        };
+
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Warning: skipping '{}' — failed to parse: {}", id, e);
+                return None;
+            }
+        };
+
+        let Some(ts) = parsed.timestamp() else {
+            eprintln!(
+                "Warning: skipping '{}' ({}) — no embedded timestamp",
+                id,
+                parsed.kind().name()
+            );
+            return None;
+        };
+
+        self.window.contains(ts.millis as i64).then(|| FilterEntry {
+            id: id.to_string(),
+            id_type: parsed.kind().name().to_string(),
+            timestamp_ms: ts.millis,
+            timestamp_iso: ts.to_iso8601(),
+        })
+    }
+}
+
+#[derive(serde::Serialize)]
+struct FilterEntry {
+    id: String,
+    id_type: String,
+    timestamp_ms: u64,
+    timestamp_iso: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 01ARZ3NDEKTSV4RRFFQ69G5FAV was minted at 2016-07-30T23:54:10.259Z
+    const ULID: &str = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+    const ULID_MS: i64 = 1_469_922_850_259;
+
+    fn filter(after: Option<i64>, before: Option<i64>) -> Filter {
+        Filter {
+            window: TimeWindow { after, before },
+            type_hint: None,
+            detection: DetectionOptions::default(),
+            snowflake_layout: None,
+        }
+    }
+
+    fn make_args(after: Option<&str>, before: Option<&str>) -> FilterArgs {
+        FilterArgs {
+            ids: vec![ULID.to_string()],
+            id_type: None,
+            detect: Default::default(),
+            after: after.map(String::from),
+            before: before.map(String::from),
+            epoch: None,
+            preset: None,
+        }
+    }
+
+    #[test]
+    fn test_window_bounds() {
+        let window = TimeWindow {
+            after: Some(10),
+            before: Some(20),
+        };
+        assert!(!window.contains(9));
+        assert!(window.contains(10));
+        assert!(window.contains(19));
+        assert!(!window.contains(20));
+        assert!(TimeWindow::default().contains(0));
+    }
+
+    #[test]
+    fn test_check_keeps_ids_in_window() {
+        let entry = filter(Some(ULID_MS), Some(ULID_MS + 1))
+            .check(ULID)
+            .unwrap();
+        assert_eq!(entry.id, ULID);
+        assert_eq!(entry.id_type, "ulid");
+        assert_eq!(entry.timestamp_ms, ULID_MS as u64);
+
+        assert!(filter(Some(ULID_MS + 1), None).check(ULID).is_none());
+        assert!(filter(None, Some(ULID_MS)).check(ULID).is_none());
+    }
+
+    #[test]
+    fn test_check_skips_untimed_and_invalid() {
+        let f = filter(None, Some(i64::MAX));
+        assert!(f.check("550e8400-e29b-41d4-a716-446655440000").is_none());
+        assert!(f.check("not-an-id").is_none());
+    }
+
+    #[test]
+    fn test_execute_relative_and_absolute() {
+        let args = make_args(Some("2016-07-30"), Some("-1h"));
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_ok());
+    }
+
+    #[test]
+    fn test_execute_empty_window_error() {
+        let args = make_args(Some("now"), Some("-24h"));
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_execute_invalid_time() {
+        let args = make_args(Some("yesterday"), None);
+        assert!(execute(&args, None, false).is_err());
+    }
+}
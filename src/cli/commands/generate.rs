@@ -1,11 +1,32 @@
-use crate::cli::app::{GenArgs, OutputFormat};
-use crate::cli::output::format_output;
+use crate::cli::app::{Bound, Case, GenArgs, Lang, Mix, OutputFormat};
+use crate::cli::commands::hash;
+use crate::cli::output::{stdout, write_output};
+use crate::cli::progress::{Progress, Total};
+use crate::cli::qr;
 use crate::core::EncodingFormat;
+use crate::core::bloom::{BloomFilter, Seen};
+use crate::core::db::Database;
 use crate::core::error::{IdtError, Result};
-use crate::core::id::{IdGenerator, IdKind};
-use crate::ids::snowflake_id::SnowflakeLayout;
-use crate::ids::{NanoIdGenerator, SnowflakeGenerator, TypeIdGenerator, UuidGenerator};
+use crate::core::id::{GenIdKind, IdGenerator, IdKind, ParsedId};
+use crate::core::rng::IdRng;
+use crate::core::state::with_locked_state;
+use crate::core::time::parse_time;
+use crate::ids::custom_id::{self, CustomGenerator, CustomSchema};
+use crate::ids::snowflake_id::{SnowflakeLayout, with_state_file};
+use crate::ids::uuid_id::{name_based_uuid, parse_namespace};
+use crate::ids::{
+    GeneratorOptions, ParsedUlid, SnowflakeGenerator, ULID_MAX_RANDOM, UlidState, UuidStyle,
+    create_generator_with, ksuid_max_for_time, ksuid_min_for_time,
+};
+use chrono::Utc;
+use clap::ValueEnum;
+use rand::RngExt;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
 use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 pub fn execute(args: &GenArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
     if output_format.is_some() && args.template.is_some() {
@@ -13,6 +34,19 @@ pub fn execute(args: &GenArgs, output_format: Option<OutputFormat>, pretty: bool
             "--template cannot be used with structured output formats".into(),
         ));
     }
+    if output_format.is_some() && (args.copy.is_some() || args.bytea) {
+        return Err(IdtError::InvalidArgument(
+            "--copy and --bytea cannot be used with structured output formats".into(),
+        ));
+    }
+    if output_format.is_some() && args.lang.is_some() {
+        return Err(IdtError::InvalidArgument(
+            "--lang cannot be used with structured output formats".into(),
+        ));
+    }
+
+    args.records.check_plain(output_format)?;
+    args.qr.check_plain(output_format)?;
 
     if let Some(ref tpl) = args.template
         && !tpl.contains("{}")
@@ -22,22 +56,146 @@ pub fn execute(args: &GenArgs, output_format: Option<OutputFormat>, pretty: bool
         );
     }
 
-    let kind: IdKind = args.id_type.into();
-    let ids = generate_ids(args, kind)?;
+    if let Some(ref path) = args.manifest {
+        return generate_manifest(args, path, output_format, pretty);
+    }
+    if let Some(ref mix) = args.mix {
+        return generate_mix(args, mix, output_format, pretty);
+    }
+    if args.label {
+        return Err(IdtError::InvalidArgument(
+            "--label only applies to --mix".into(),
+        ));
+    }
+    let Some(id_type) = args.id_type else {
+        return Err(IdtError::InvalidArgument(
+            "No ID type given. Pass a TYPE or --mix.".into(),
+        ));
+    };
+    let kind: IdKind = id_type.into();
 
-    let mut writer: Box<dyn Write> = Box::new(io::stdout());
+    match kind {
+        IdKind::UuidV3 | IdKind::UuidV5 if args.qr.qr || args.qr.qr_png.is_some() => {
+            return Err(IdtError::InvalidArgument(
+                "--qr and --qr-png don't apply to uuidv3 and uuidv5; pipe them to `idt convert --qr`"
+                    .into(),
+            ));
+        }
+        IdKind::UuidV3 | IdKind::UuidV5 => {
+            return derive_name_based(args, kind, output_format, pretty);
+        }
+        _ if args.namespace.is_some() || args.names_file.is_some() || args.with_input => {
+            return Err(IdtError::InvalidArgument(
+                "--namespace, --names-file, and --with-input only apply to uuidv3 and uuidv5"
+                    .into(),
+            ));
+        }
+        _ => {}
+    }
 
     // Apply encoding format conversion if specified
     let encoding: Option<EncodingFormat> = args.format.as_ref().map(|f| f.parse()).transpose()?;
 
-    let formatted_ids: Vec<String> = if let Some(enc) = encoding {
+    if args.rate.is_some() && (output_format.is_some() || encoding.is_some()) {
+        return Err(IdtError::InvalidArgument(
+            "--rate streams plain IDs and cannot be used with --output or --format".into(),
+        ));
+    }
+
+    // Plain output streams straight to stdout through a single reused buffer;
+    // QR codes need the finished IDs
+    let qr = args.qr.qr || args.qr.qr_png.is_some();
+    if output_format.is_none() && encoding.is_none() && args.lang.is_none() && !qr {
+        let mut writer = io::BufWriter::new(stdout());
+        write_copy_header(&mut writer, args)?;
+        with_generator(args, kind, |generator| {
+            let bytea;
+            let generator = if args.bytea {
+                bytea = ByteaGenerator {
+                    inner: generator,
+                    kind,
+                };
+                &bytea as &dyn IdGenerator
+            } else {
+                generator
+            };
+            let literal;
+            let generator = match args.db {
+                Some(db) => {
+                    literal = SqlLiteralGenerator {
+                        inner: generator,
+                        kind,
+                        db,
+                    };
+                    &literal as &dyn IdGenerator
+                }
+                None => generator,
+            };
+            let decorated;
+            let generator = match (args.case, decorations(args, kind)) {
+                (None, ("", "")) => generator,
+                (case, (prefix, suffix)) => {
+                    decorated = DecoratedGenerator {
+                        inner: generator,
+                        case,
+                        prefix,
+                        suffix,
+                    };
+                    &decorated as &dyn IdGenerator
+                }
+            };
+            let mut pace = args
+                .rate
+                .map(|period| Pace::new(period, args.jitter, args.duration));
+            // With --for the deadline ends the stream, not the count
+            let count = match args.duration {
+                Some(_) => usize::MAX,
+                None => args.count,
+            };
+            stream_plain(
+                &mut writer,
+                generator,
+                count,
+                args.template.as_deref(),
+                Terminator::new(args),
+                pace.as_mut(),
+                &mut progress(args),
+            )
+        })?;
+        write_copy_trailer(&mut writer, args)?;
+        writer.flush()?;
+        return Ok(());
+    }
+
+    let ids = generate_ids(args, kind)?;
+
+    let mut writer: Box<dyn Write> = Box::new(stdout());
+
+    let mut formatted_ids: Vec<String> = if let Some(enc) = encoding {
         ids.iter()
             .map(|id| format_id(id, &kind, enc))
             .collect::<Result<Vec<_>>>()?
+    } else if let Some(db) = args.db {
+        ids.iter()
+            .map(|id| Ok(db.literal(crate::ids::parse_id(id, Some(kind))?.as_ref())))
+            .collect::<Result<Vec<_>>>()?
     } else {
         ids
     };
 
+    if let Some(case) = args.case {
+        formatted_ids.iter_mut().for_each(|id| case.apply(id));
+    }
+
+    // Decorate after encoding so the prefix and suffix stay literal
+    let formatted_ids: Vec<String> = match decorations(args, kind) {
+        ("", "") => formatted_ids,
+        (prefix, suffix) => formatted_ids
+            .iter()
+            .map(|id| format!("{}{}{}", prefix, id, suffix))
+            .collect(),
+    };
+
     // Apply template if specified
     let final_ids = if let Some(ref tpl) = args.template {
         formatted_ids
@@ -50,190 +208,1185 @@ pub fn execute(args: &GenArgs, output_format: Option<OutputFormat>, pretty: bool
 
     // Output
     if let Some(fmt) = output_format {
-        let output = if final_ids.len() == 1 {
+        if final_ids.len() == 1 {
             let wrapper = serde_json::json!({ "id": final_ids[0] });
-            format_output(&wrapper, fmt, pretty)?
+            write_output(&mut writer, &wrapper, fmt, pretty)?;
         } else {
-            format_output(&final_ids, fmt, pretty)?
-        };
-        writeln!(writer, "{}", output)?;
+            write_output(&mut writer, &final_ids, fmt, pretty)?;
+        }
+    } else if let Some(lang) = args.lang {
+        write_snippet(&mut writer, lang, &final_ids)?;
+    } else if args.qr.qr {
+        for id in &final_ids {
+            qr::write_terminal(&mut writer, id)?;
+        }
     } else {
-        output_plain(&mut writer, &final_ids, args.no_newline && args.count == 1)?;
+        write_copy_header(&mut writer, args)?;
+        output_plain(&mut writer, &final_ids, Terminator::new(args))?;
+        write_copy_trailer(&mut writer, args)?;
+    }
+    if let Some(ref path) = args.qr.qr_png {
+        qr::write_pngs(&final_ids, path)?;
     }
 
     Ok(())
 }
 
-fn generate_ids(args: &GenArgs, kind: IdKind) -> Result<Vec<String>> {
-    let mut ids = Vec::with_capacity(args.count);
+/// Name-based UUIDs have no randomness to generate; derive one per name
+/// from `--names-file` (or stdin) the way `idt hash` does, then encode and
+/// decorate them like any other generated ID
+fn derive_name_based(
+    args: &GenArgs,
+    kind: IdKind,
+    output_format: Option<OutputFormat>,
+    pretty: bool,
+) -> Result<()> {
+    let unsupported = [
+        ("-n", args.count != 1),
+        ("--rate", args.rate.is_some()),
+        ("--at", args.at.is_some()),
+        ("--db", args.db.is_some()),
+        ("--bytea", args.bytea),
+        ("--copy", args.copy.is_some()),
+        ("--lang", args.lang.is_some()),
+    ];
+    if let Some((flag, _)) = unsupported.iter().find(|(_, set)| *set) {
+        return Err(IdtError::InvalidArgument(format!(
+            "{} doesn't apply to uuidv3 and uuidv5, which derive one UUID per name",
+            flag
+        )));
+    }
 
-    match kind {
-        IdKind::Uuid | IdKind::UuidV4 => {
-            let version = args.uuid_version.unwrap_or(4);
-            let generator = match version {
-                1 => UuidGenerator::v1(),
-                4 => UuidGenerator::v4(),
-                6 => UuidGenerator::v6(),
-                7 => UuidGenerator::v7(),
-                _ => {
-                    return Err(IdtError::InvalidArgument(format!(
-                        "UUID version {} not supported for generation. Use 1, 4, 6, or 7.",
-                        version
-                    )));
-                }
+    let names = match args.names_file {
+        Some(ref path) => hash::read_names(path)?,
+        // Don't block on stdin if it's a terminal (no piped input)
+        None if std::io::IsTerminal::is_terminal(&io::stdin()) => vec![],
+        None => hash::read_names(Path::new("-"))?,
+    };
+    if names.is_empty() {
+        return Err(IdtError::InvalidArgument(format!(
+            "No names to derive {} from. Pass --names-file, or pipe names to stdin.",
+            kind
+        )));
+    }
+    let namespace = args.namespace.as_deref().unwrap_or("dns");
+    let ids = derive_ids(args, kind, namespace, &names)?;
+    let version = if kind == IdKind::UuidV3 { 3 } else { 5 };
+
+    let mut writer = stdout();
+    if let Some(fmt) = output_format {
+        let namespace = parse_namespace(namespace)?.to_string();
+        let results: Vec<hash::HashResult> = names
+            .into_iter()
+            .zip(ids)
+            .map(|(input, uuid)| hash::HashResult {
+                input,
+                namespace: namespace.clone(),
+                version,
+                uuid,
+            })
+            .collect();
+        if results.len() == 1 {
+            write_output(&mut writer, &results[0], fmt, pretty)?;
+        } else {
+            write_output(&mut writer, &results, fmt, pretty)?;
+        }
+        return Ok(());
+    }
+
+    let lines: Vec<String> = if args.with_input {
+        names
+            .iter()
+            .zip(&ids)
+            .map(|(name, id)| format!("{}\t{}", name, id))
+            .collect()
+    } else {
+        ids
+    };
+    let mut writer = io::BufWriter::new(writer);
+    output_plain(&mut writer, &lines, Terminator::new(args))?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// The `kind` UUID of each name, with `--format` or `--style`, `--case`,
+/// `--prefix`/`--suffix`, and `--template` applied as for random IDs
+fn derive_ids(
+    args: &GenArgs,
+    kind: IdKind,
+    namespace: &str,
+    names: &[String],
+) -> Result<Vec<String>> {
+    let namespace = parse_namespace(namespace)?;
+    let version = if kind == IdKind::UuidV3 { 3 } else { 5 };
+    let encoding: Option<EncodingFormat> = args.format.as_ref().map(|f| f.parse()).transpose()?;
+    let (prefix, suffix) = decorations(args, kind);
+    names
+        .iter()
+        .map(|name| {
+            let uuid = name_based_uuid(version, &namespace, name.as_bytes())?;
+            let mut id = match encoding {
+                Some(enc) => format_id(&uuid.to_string(), &kind, enc)?,
+                None => args.style.unwrap_or_default().format(&uuid),
             };
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
+            if let Some(case) = args.case {
+                case.apply(&mut id);
             }
+            let id = format!("{}{}{}", prefix, id, suffix);
+            Ok(match args.template {
+                Some(ref tpl) => tpl.replace("{}", &id),
+                None => id,
+            })
+        })
+        .collect()
+}
+
+/// `--mix`: interleave one generator per listed type in proportion to the weights
+fn generate_mix(
+    args: &GenArgs,
+    mix: &Mix,
+    output_format: Option<OutputFormat>,
+    pretty: bool,
+) -> Result<()> {
+    if args.rate.is_some() && output_format.is_some() {
+        return Err(IdtError::InvalidArgument(
+            "--rate streams plain IDs and cannot be used with --output or --format".into(),
+        ));
+    }
+
+    let kinds: Vec<IdKind> = mix.0.iter().map(|&(kind, _)| kind.into()).collect();
+    let weights: Vec<u32> = mix.0.iter().map(|&(_, weight)| weight).collect();
+    // With --for the deadline ends the stream, not the count
+    let count = match args.duration {
+        Some(_) => usize::MAX,
+        None => args.count,
+    };
+
+    with_generators(args, &kinds, &[], &mut |generators| {
+        let decorated: Vec<DecoratedGenerator> = generators
+            .iter()
+            .zip(&kinds)
+            .map(|(&inner, &kind)| {
+                let (prefix, suffix) = decorations(args, kind);
+                DecoratedGenerator {
+                    inner,
+                    case: args.case,
+                    prefix,
+                    suffix,
+                }
+            })
+            .collect();
+        let mixed = MixedGenerator {
+            parts: decorated
+                .iter()
+                .zip(&kinds)
+                .map(|(generator, &kind)| (generator as &dyn IdGenerator, kind))
+                .collect(),
+            picker: RefCell::new(MixPicker::new(
+                &weights,
+                args.duration.is_none().then_some(count),
+            )),
+            label: args.label,
+        };
+
+        let mut writer = io::BufWriter::new(stdout());
+        if let Some(fmt) = output_format {
+            let entries = (0..count)
+                .map(|_| mixed.next_entry())
+                .collect::<Result<Vec<_>>>()?;
+            write_output(&mut writer, &entries, fmt, pretty)?;
+        } else if let Some(lang) = args.lang {
+            write_snippet(&mut writer, lang, &mixed.generate_many(count)?)?;
+        } else {
+            write_copy_header(&mut writer, args)?;
+            let mut pace = args
+                .rate
+                .map(|period| Pace::new(period, args.jitter, args.duration));
+            stream_plain(
+                &mut writer,
+                &mixed,
+                count,
+                args.template.as_deref(),
+                Terminator::new(args),
+                pace.as_mut(),
+                &mut progress(args),
+            )?;
+            write_copy_trailer(&mut writer, args)?;
         }
-        IdKind::UuidV1 => {
-            let generator = UuidGenerator::v1();
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
+        writer.flush()?;
+        Ok(())
+    })
+}
+
+/// Build a generator for each of `kinds` with [`with_generator`], then hand
+/// them all to `f` at once
+fn with_generators(
+    args: &GenArgs,
+    kinds: &[IdKind],
+    built: &[&dyn IdGenerator],
+    f: &mut dyn FnMut(&[&dyn IdGenerator]) -> Result<()>,
+) -> Result<()> {
+    let Some((&kind, rest)) = kinds.split_first() else {
+        return f(built);
+    };
+    with_generator(args, kind, |generator| {
+        let built: Vec<&dyn IdGenerator> = built
+            .iter()
+            .copied()
+            .chain(std::iter::once(generator))
+            .collect();
+        with_generators(args, rest, &built, f)
+    })
+}
+
+/// `--manifest`: one batch per row of a CSV file, each ID labelled with its row
+fn generate_manifest(
+    args: &GenArgs,
+    path: &Path,
+    output_format: Option<OutputFormat>,
+    pretty: bool,
+) -> Result<()> {
+    let rows = if path.as_os_str() == "-" {
+        read_manifest(io::stdin().lock(), args)?
+    } else {
+        let file = File::open(path).map_err(|e| {
+            IdtError::InvalidArgument(format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        read_manifest(file, args)?
+    };
+
+    let mut writer = io::BufWriter::new(stdout());
+    if let Some(fmt) = output_format {
+        let batches = rows
+            .iter()
+            .map(|row| {
+                Ok(ManifestBatch {
+                    label: &row.label,
+                    id_type: row.kind.name(),
+                    ids: row.generator()?.generate_many(row.count)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        write_output(&mut writer, &batches, fmt, pretty)?;
+    } else {
+        let terminator = args.records.terminator();
+        let mut progress = progress(args);
+        let mut line = String::new();
+        for row in &rows {
+            let generator = row.generator()?;
+            for _ in 0..row.count {
+                line.clear();
+                line.push_str(&row.label);
+                line.push('\t');
+                generator.write_to(&mut line)?;
+                line.push_str(terminator);
+                writer.write_all(line.as_bytes())?;
+                progress.tick(1);
             }
         }
-        IdKind::UuidV6 => {
-            let generator = UuidGenerator::v6();
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
-            }
+        progress.clear();
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Columns a `--manifest` file may have; only `type` is required
+const MANIFEST_COLUMNS: [&str; 10] = [
+    "type", "count", "label", "prefix", "length", "alphabet", "at", "style", "preset", "fields",
+];
+
+/// One row of a `--manifest` file
+struct ManifestRow {
+    label: String,
+    kind: IdKind,
+    count: usize,
+    options: GeneratorOptions,
+}
+
+impl ManifestRow {
+    fn generator(&self) -> Result<Box<dyn IdGenerator + Send + Sync>> {
+        create_generator_with(self.kind, &self.options)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ManifestBatch<'a> {
+    label: &'a str,
+    id_type: &'static str,
+    ids: Vec<String>,
+}
+
+/// Parse a manifest: a CSV header naming [`MANIFEST_COLUMNS`], then one row
+/// per batch. Empty cells keep the defaults, `#` starts a comment line, and
+/// `fields` holds Snowflake fields as `NAME=VALUE` pairs separated by `;`.
+fn read_manifest(reader: impl io::Read, args: &GenArgs) -> Result<Vec<ManifestRow>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(manifest_error)?
+        .iter()
+        .map(str::to_ascii_lowercase)
+        .collect();
+    if let Some(unknown) = headers
+        .iter()
+        .find(|h| !MANIFEST_COLUMNS.contains(&h.as_str()))
+    {
+        return Err(IdtError::InvalidArgument(format!(
+            "Unknown manifest column '{}'. Use: {}",
+            unknown,
+            MANIFEST_COLUMNS.join(", ")
+        )));
+    }
+    if !headers.iter().any(|h| h == "type") {
+        return Err(IdtError::InvalidArgument(
+            "The manifest has no 'type' column".into(),
+        ));
+    }
+    // One stream shared by every row, so a seed reproduces the whole manifest
+    let rng = args.rng.map(IdRng::new);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(manifest_error)?;
+        let line = record.position().map_or(0, |p| p.line());
+        let cells: HashMap<&str, &str> = headers
+            .iter()
+            .map(String::as_str)
+            .zip(record.iter())
+            .filter(|(_, cell)| !cell.is_empty())
+            .collect();
+        let row = manifest_row(&cells, args, rng.clone()).map_err(|e| {
+            IdtError::InvalidArgument(format!("Manifest line {}: {}", line, bare_message(e)))
+        })?;
+        rows.push(row);
+    }
+    if rows.is_empty() {
+        return Err(IdtError::InvalidArgument("The manifest has no rows".into()));
+    }
+    Ok(rows)
+}
+
+fn manifest_row(
+    cells: &HashMap<&str, &str>,
+    args: &GenArgs,
+    rng: Option<IdRng>,
+) -> Result<ManifestRow> {
+    let type_name = cells
+        .get("type")
+        .ok_or_else(|| IdtError::InvalidArgument("no type given".into()))?;
+    let kind: IdKind = GenIdKind::from_str(type_name, true)
+        .map_err(|_| IdtError::InvalidArgument(format!("unknown ID type '{}'", type_name)))?
+        .into();
+    if matches!(kind, IdKind::UuidV3 | IdKind::UuidV5) {
+        return Err(IdtError::InvalidArgument(format!(
+            "{} is derived from names; use --names-file",
+            kind.name()
+        )));
+    }
+    let number = |column: &str| -> Result<Option<usize>> {
+        cells
+            .get(column)
+            .map(|cell| {
+                cell.parse().map_err(|_| {
+                    IdtError::InvalidArgument(format!("invalid {} '{}'", column, cell))
+                })
+            })
+            .transpose()
+    };
+
+    let mut options = GeneratorOptions::new();
+    if let Some(rng) = rng {
+        options = options.with_rng(rng);
+    }
+    if let Some(prefix) = cells.get("prefix") {
+        options = options.with_prefix(prefix);
+    }
+    if let Some(length) = number("length")? {
+        options = options.with_length(length);
+    }
+    if let Some(alphabet) = cells.get("alphabet") {
+        options = options.with_alphabet(alphabet);
+    }
+    if let Some(at) = cells.get("at") {
+        options = options.with_timestamp(parse_at(at)?);
+    }
+    if let Some(style) = cells.get("style") {
+        let style = UuidStyle::from_str(style, true)
+            .map_err(|_| IdtError::InvalidArgument(format!("unknown UUID style '{}'", style)))?;
+        options = options.with_uuid_style(style);
+    }
+    if matches!(kind, IdKind::Snowflake | IdKind::Custom) {
+        options = options
+            .with_clock_policy(args.clock_rollback)
+            .with_sequence_policy(args.sequence_overflow);
+    }
+    if let Some(preset) = cells.get("preset") {
+        options = options.with_layout(SnowflakeLayout::resolve(Some(preset), None)?);
+    }
+    for pair in cells.get("fields").into_iter().flat_map(|f| f.split(';')) {
+        let pair = pair.trim();
+        let value = pair
+            .split_once('=')
+            .and_then(|(name, value)| Some((name.trim(), value.trim().parse().ok()?)));
+        let Some((name, value)) = value else {
+            return Err(IdtError::InvalidArgument(format!(
+                "invalid field '{}': expected NAME=VALUE",
+                pair
+            )));
+        };
+        options = options.with_field(name, value);
+    }
+    // Build once now so a bad option fails before any ID is printed
+    create_generator_with(kind, &options)?;
+
+    Ok(ManifestRow {
+        label: cells
+            .get("label")
+            .map_or_else(|| kind.name().to_string(), |label| label.to_string()),
+        kind,
+        count: number("count")?.unwrap_or(1),
+        options,
+    })
+}
+
+fn manifest_error(e: csv::Error) -> IdtError {
+    IdtError::InvalidArgument(format!("Invalid manifest: {}", e))
+}
+
+/// The message of an `InvalidArgument`, without its "Invalid argument: " prefix
+fn bare_message(e: IdtError) -> String {
+    match e {
+        IdtError::InvalidArgument(message) => message,
+        other => other.to_string(),
+    }
+}
+
+/// Draws each ID from one of several generators, chosen by [`MixPicker`]
+struct MixedGenerator<'a> {
+    parts: Vec<(&'a dyn IdGenerator, IdKind)>,
+    picker: RefCell<MixPicker>,
+    /// Prefix each ID with its type and a tab
+    label: bool,
+}
+
+impl MixedGenerator<'_> {
+    fn next_entry(&self) -> Result<MixEntry> {
+        let (generator, kind) = self.parts[self.picker.borrow_mut().pick()];
+        Ok(MixEntry {
+            id_type: kind.name(),
+            id: generator.generate()?,
+        })
+    }
+}
+
+impl IdGenerator for MixedGenerator<'_> {
+    fn generate(&self) -> Result<String> {
+        let mut out = String::new();
+        self.write_to(&mut out)?;
+        Ok(out)
+    }
+
+    fn write_to(&self, writer: &mut dyn std::fmt::Write) -> Result<()> {
+        let (generator, kind) = self.parts[self.picker.borrow_mut().pick()];
+        if self.label {
+            writer.write_str(kind.name())?;
+            writer.write_char('\t')?;
+        }
+        generator.write_to(writer)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct MixEntry {
+    id_type: &'static str,
+    id: String,
+}
+
+/// Chooses the type of each `--mix` ID. For a known count every type gets
+/// its exact share of the count (largest remainder rounding), handed out
+/// in random order; an open-ended stream draws each ID by weight.
+struct MixPicker {
+    weights: Vec<u64>,
+    remaining: Option<Vec<u64>>,
+}
+
+impl MixPicker {
+    fn new(weights: &[u32], count: Option<usize>) -> Self {
+        let weights: Vec<u64> = weights.iter().map(|&w| w as u64).collect();
+        let remaining = count.map(|count| shares(&weights, count as u64));
+        Self { weights, remaining }
+    }
+
+    fn pick(&mut self) -> usize {
+        if let Some(ref mut remaining) = self.remaining
+            && remaining.iter().any(|&n| n > 0)
+        {
+            let index = weighted_index(remaining);
+            remaining[index] -= 1;
+            return index;
+        }
+        weighted_index(&self.weights)
+    }
+}
+
+/// Split `count` across `weights`, rounding so the shares sum to `count`
+fn shares(weights: &[u64], count: u64) -> Vec<u64> {
+    let total: u128 = weights.iter().map(|&w| w as u128).sum();
+    let exact: Vec<u128> = weights.iter().map(|&w| count as u128 * w as u128).collect();
+    let mut shares: Vec<u64> = exact.iter().map(|&e| (e / total) as u64).collect();
+    let mut by_remainder: Vec<usize> = (0..weights.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(exact[i] % total));
+    let leftover = count - shares.iter().sum::<u64>();
+    for &i in by_remainder.iter().take(leftover as usize) {
+        shares[i] += 1;
+    }
+    shares
+}
+
+/// A random index, each chosen with probability proportional to its weight
+fn weighted_index(weights: &[u64]) -> usize {
+    let total: u64 = weights.iter().sum();
+    let mut r = rand::rng().random_range(0..total);
+    for (i, &weight) in weights.iter().enumerate() {
+        if r < weight {
+            return i;
         }
-        IdKind::UuidV7 => {
-            let generator = UuidGenerator::v7();
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
+        r -= weight;
+    }
+    unreachable!("r is below the total weight")
+}
+
+fn generate_ids(args: &GenArgs, kind: IdKind) -> Result<Vec<String>> {
+    with_generator(args, kind, |generator| generator.generate_many(args.count))
+}
+
+/// Build the generator described by `args` and hand it to `f`.
+///
+/// With `--state-file`, `f` runs while the Snowflake state file is locked and
+/// the advanced state is written back afterwards.
+fn with_generator<T>(
+    args: &GenArgs,
+    kind: IdKind,
+    f: impl FnOnce(&dyn IdGenerator) -> Result<T>,
+) -> Result<T> {
+    let f = |generator: &dyn IdGenerator| {
+        if args.check_collisions {
+            check_collisions(args, generator, f)
+        } else {
+            f(generator)
+        }
+    };
+    // `-t uuid --uuid-version N` is the same as `-t uuidvN`
+    let kind = match (kind, args.uuid_version) {
+        (IdKind::Uuid | IdKind::UuidV4, Some(version)) => match version {
+            1 => IdKind::UuidV1,
+            4 => IdKind::UuidV4,
+            6 => IdKind::UuidV6,
+            7 => IdKind::UuidV7,
+            _ => {
+                return Err(IdtError::InvalidArgument(format!(
+                    "UUID version {} not supported for generation. Use 1, 4, 6, or 7.",
+                    version
+                )));
+            }
+        },
+        _ => kind,
+    };
+    if args.style.is_some() && !kind.is_uuid() {
+        return Err(IdtError::InvalidArgument(format!(
+            "--style only applies to UUIDs, not {}",
+            kind.name()
+        )));
+    }
+
+    let at = args.at.as_deref().map(parse_at).transpose()?;
+    if at.is_some() && !GeneratorOptions::TIMESTAMPED.contains(&kind) {
+        return Err(IdtError::InvalidArgument(format!(
+            "--at only applies to timestamped IDs, not {}",
+            kind.name()
+        )));
+    }
+    if args.state_file.is_some()
+        && !matches!(kind, IdKind::Snowflake | IdKind::Custom | IdKind::Ulid)
+    {
+        return Err(IdtError::InvalidArgument(format!(
+            "--state-file only applies to Snowflake, custom, and ULID generation, not {}",
+            kind.name()
+        )));
+    }
+    let uses_rng = matches!(
+        kind,
+        IdKind::Uuid
+            | IdKind::UuidV4
+            | IdKind::UuidV7
+            | IdKind::Ulid
+            | IdKind::NanoId
+            | IdKind::ObjectId
+            | IdKind::Ksuid
+            | IdKind::Xid
+            | IdKind::Tsid
+            | IdKind::Cuid
+            | IdKind::Cuid2
+            | IdKind::TypeId
+            | IdKind::ShortUuid
+    );
+    if args.rng.is_some() && !uses_rng {
+        return Err(IdtError::InvalidArgument(format!(
+            "--rng only applies to random-based IDs, not {}",
+            kind.name()
+        )));
+    }
+
+    if let (Some(bound), Some(ms)) = (args.bound, at) {
+        let id = match (kind, bound) {
+            (IdKind::Ulid, Bound::Min) => ParsedUlid::from_parts(ms, 0)?.canonical(),
+            (IdKind::Ulid, Bound::Max) => ParsedUlid::from_parts(ms, ULID_MAX_RANDOM)?.canonical(),
+            (IdKind::Ksuid, Bound::Min) => ksuid_min_for_time(ms / 1000)?.canonical(),
+            (IdKind::Ksuid, Bound::Max) => ksuid_max_for_time(ms / 1000)?.canonical(),
+            _ => {
+                return Err(IdtError::InvalidArgument(format!(
+                    "--bound only applies to ULID and KSUID, not {}",
+                    kind.name()
+                )));
+            }
+        };
+        return f(&FixedGenerator(id));
+    }
+
+    let options = generator_options(args, kind, at)?;
+    let generator: Box<dyn IdGenerator> = match kind {
+        IdKind::Ulid => {
+            let generator = options.ulid_generator();
+            if let Some(ref path) = args.state_file {
+                return with_locked_state(path, |state: &mut UlidState| {
+                    let generator = generator.with_state(state.clone());
+                    let result = f(&generator);
+                    *state = generator.state().expect("generator was given a state");
+                    result
+                });
             }
+            Box::new(generator)
         }
-        IdKind::UuidNil => {
-            let generator = UuidGenerator::nil();
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
+        IdKind::NanoId => {
+            let generator = options.nanoid_generator()?;
+            if (args.alphabet.is_some() || args.length.is_some())
+                && let Some(warning) = generator.entropy_warning()
+            {
+                eprintln!("Warning: {}", warning);
             }
+            Box::new(generator)
         }
-        IdKind::UuidMax => {
-            let generator = UuidGenerator::max();
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
+        IdKind::Snowflake | IdKind::Custom => {
+            let schema = match kind {
+                IdKind::Custom => Some(custom_id::require_active()?),
+                _ => None,
+            };
+            let mut generator = options.snowflake_generator()?;
+
+            if let Some(ref path) = args.state_file {
+                return with_state_file(path, |state| {
+                    state.bind_layout(&generator.layout)?;
+
+                    if args.machine_id.is_some() {
+                        state.machine_id = generator.field_values.get("machine_id").copied();
+                    }
+
+                    if let Some(machine_id) = state.assign_machine_id(&generator.layout) {
+                        generator = generator.with_field("machine_id", machine_id);
+                    }
+                    generator = generator.with_state(state.clone());
+
+                    let (result, generator) = run_snowflake(generator, schema, f)?;
+                    *state = generator.state().expect("generator was given a state");
+                    result
+                });
             }
+            return run_snowflake(generator, schema, f)?.0;
         }
-        IdKind::Ulid => {
-            let generator = crate::ids::UlidGenerator::new();
-            for _ in 0..args.count {
-                ids.push(crate::core::id::IdGenerator::generate(&generator)?);
+        IdKind::UuidV3 | IdKind::UuidV5 => {
+            return Err(IdtError::InvalidArgument(format!(
+                "{} is derived from names; pass --names-file",
+                kind.name()
+            )));
+        }
+        _ => create_generator_with(kind, &options)?,
+    };
+
+    f(generator.as_ref())
+}
+
+/// The [`GeneratorOptions`] `args` describe for `kind`. Flags that don't
+/// configure `kind` are left out: `--prefix` decorates every other kind.
+fn generator_options(args: &GenArgs, kind: IdKind, at: Option<u64>) -> Result<GeneratorOptions> {
+    let mut options = GeneratorOptions::new();
+    if let Some(seed) = args.rng {
+        options = options.with_rng(IdRng::new(seed));
+    }
+    if let Some(ms) = at {
+        options = options.with_timestamp(ms);
+    }
+    if let Some(style) = args.style {
+        options = options.with_uuid_style(style);
+    }
+    match kind {
+        IdKind::TypeId => {
+            if let Some(ref prefix) = args.prefix {
+                options = options.with_prefix(prefix);
             }
         }
         IdKind::NanoId => {
-            let mut generator = NanoIdGenerator::new();
             if let Some(ref alphabet) = args.alphabet {
-                generator = generator.with_alphabet(alphabet);
+                options = options.with_alphabet(alphabet);
             }
             if let Some(length) = args.length {
-                generator = generator.with_length(length);
-            }
-            for _ in 0..args.count {
-                ids.push(crate::core::id::IdGenerator::generate(&generator)?);
+                options = options.with_length(length);
             }
         }
-        IdKind::Snowflake => {
+        IdKind::Snowflake | IdKind::Custom => options = snowflake_options(args, kind, options)?,
+        _ => {}
+    }
+    Ok(options)
+}
+
+/// Yields the same ID every time, e.g. a `--bound` ID
+struct FixedGenerator(String);
+
+impl IdGenerator for FixedGenerator {
+    fn generate(&self) -> Result<String> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Parse `--at` as Unix milliseconds; IDs cannot predate 1970
+fn parse_at(input: &str) -> Result<u64> {
+    let ms = parse_time(input, Utc::now())?;
+    u64::try_from(ms).map_err(|_| {
+        IdtError::InvalidArgument(format!("--at '{}' is before the Unix epoch", input))
+    })
+}
+
+/// Run `f` on a Snowflake generator, encoding through `schema` if given.
+/// Hands the generator back, even when `f` fails, so its state can be saved.
+fn run_snowflake<T>(
+    generator: SnowflakeGenerator,
+    schema: Option<&'static CustomSchema>,
+    f: impl FnOnce(&dyn IdGenerator) -> Result<T>,
+) -> Result<(Result<T>, SnowflakeGenerator)> {
+    match schema {
+        Some(schema) => {
+            let custom = CustomGenerator::new(schema, generator)?;
+            let result = f(&custom);
+            Ok((result, custom.into_snowflake()))
+        }
+        None => {
+            let result = f(&generator);
+            Ok((result, generator))
+        }
+    }
+}
+
+/// Add the Snowflake flags to `options`. A custom schema fixes the layout,
+/// so `--preset` and `--epoch` only apply to plain Snowflakes.
+fn snowflake_options(
+    args: &GenArgs,
+    kind: IdKind,
+    options: GeneratorOptions,
+) -> Result<GeneratorOptions> {
+    let mut options = options
+        .with_clock_policy(args.clock_rollback)
+        .with_sequence_policy(args.sequence_overflow);
+    let layout = match kind {
+        IdKind::Custom if args.preset.is_some() || args.epoch.is_some() => {
+            return Err(IdtError::InvalidArgument(
+                "--preset and --epoch cannot be used with a custom schema".to_string(),
+            ));
+        }
+        IdKind::Custom => custom_id::require_active()?.layout.clone(),
+        _ => {
             let layout = SnowflakeLayout::resolve(args.preset.as_deref(), args.epoch.as_deref())?;
+            options = options.with_layout(layout.clone());
+            layout
+        }
+    };
 
-            let mut generator = SnowflakeGenerator::new().with_layout(layout);
+    for (name, source) in [
+        ("machine_id", &args.machine_id),
+        ("datacenter_id", &args.datacenter_id),
+    ] {
+        let Some(source) = source else { continue };
+        let Some(bits) = layout.field_bits(name) else {
+            return Err(IdtError::InvalidArgument(format!(
+                "Preset '{}' does not have a {} field",
+                layout.name, name
+            )));
+        };
+        options = options.with_field(name, source.resolve(bits)?);
+    }
 
-            if let Some(machine_id) = args.machine_id {
-                if !generator.layout.has_field("machine_id") {
-                    return Err(IdtError::InvalidArgument(format!(
-                        "Preset '{}' does not have a machine_id field",
-                        generator.layout.name
-                    )));
-                }
-                generator = generator.with_machine_id(machine_id);
-            }
-            if let Some(datacenter_id) = args.datacenter_id {
-                if !generator.layout.has_field("datacenter_id") {
-                    return Err(IdtError::InvalidArgument(format!(
-                        "Preset '{}' does not have a datacenter_id field",
-                        generator.layout.name
-                    )));
-                }
-                generator = generator.with_datacenter_id(datacenter_id);
-            }
+    // Handle --field key=value pairs
+    for field_arg in &args.field {
+        let (name, value) = field_arg.split_once('=').ok_or_else(|| {
+            IdtError::InvalidArgument(format!(
+                "Invalid --field format '{}': expected NAME=VALUE",
+                field_arg
+            ))
+        })?;
+        let val: u64 = value.parse().map_err(|_| {
+            IdtError::InvalidArgument(format!(
+                "Invalid value '{}' for field '{}': expected integer",
+                value, name
+            ))
+        })?;
+        options = options.with_field(name, val);
+    }
 
-            // Handle --field key=value pairs
-            for field_arg in &args.field {
-                let (name, value) = field_arg.split_once('=').ok_or_else(|| {
-                    IdtError::InvalidArgument(format!(
-                        "Invalid --field format '{}': expected NAME=VALUE",
-                        field_arg
-                    ))
-                })?;
-                if !generator.layout.has_field(name) {
-                    return Err(IdtError::InvalidArgument(format!(
-                        "Preset '{}' does not have a '{}' field. Available: {}",
-                        generator.layout.name,
-                        name,
-                        generator
-                            .layout
-                            .fields
-                            .iter()
-                            .filter(|f| f.name != "timestamp" && f.name != "sequence")
-                            .map(|f| f.name)
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    )));
-                }
-                let val: u64 = value.parse().map_err(|_| {
-                    IdtError::InvalidArgument(format!(
-                        "Invalid value '{}' for field '{}': expected integer",
-                        value, name
-                    ))
-                })?;
-                generator = generator.with_field(name, val);
+    Ok(options)
+}
+
+fn write_copy_header(writer: &mut dyn Write, args: &GenArgs) -> Result<()> {
+    if let Some(ref table) = args.copy {
+        writeln!(writer, "COPY {} FROM STDIN;", table)?;
+    }
+    Ok(())
+}
+
+fn write_copy_trailer(writer: &mut dyn Write, args: &GenArgs) -> Result<()> {
+    if args.copy.is_some() {
+        writeln!(writer, "\\.")?;
+    }
+    Ok(())
+}
+
+/// Write `ids` as an array literal in `lang`, ready to paste into source
+fn write_snippet(writer: &mut dyn Write, lang: Lang, ids: &[String]) -> Result<()> {
+    let (open, indent, close) = match lang {
+        Lang::Rust => (
+            format!("const IDS: [&str; {}] = [", ids.len()),
+            "    ",
+            "];",
+        ),
+        Lang::Go => ("var ids = []string{".to_string(), "\t", "}"),
+        Lang::Python => ("IDS = [".to_string(), "    ", "]"),
+        Lang::Ts => ("const ids: string[] = [".to_string(), "  ", "];"),
+        Lang::Java => ("String[] ids = {".to_string(), "    ", "};"),
+        Lang::Sql => ("ARRAY[".to_string(), "    ", "]"),
+    };
+
+    writeln!(writer, "{}", open)?;
+    for (i, id) in ids.iter().enumerate() {
+        let literal = match lang {
+            Lang::Sql => format!("'{}'", id.replace('\'', "''")),
+            _ => format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\"")),
+        };
+        // SQL is the only one of these without trailing commas
+        let comma = if lang == Lang::Sql && i + 1 == ids.len() {
+            ""
+        } else {
+            ","
+        };
+        writeln!(writer, "{}{}{}", indent, literal, comma)?;
+    }
+    writeln!(writer, "{}", close)?;
+    Ok(())
+}
+
+/// Prints each ID from `inner` as a bytea hex literal for COPY text format.
+///
+/// COPY unescapes `\\` to `\`, so `\\x0123...` loads as the bytea `\x0123...`.
+struct ByteaGenerator<'a> {
+    inner: &'a dyn IdGenerator,
+    kind: IdKind,
+}
+
+impl IdGenerator for ByteaGenerator<'_> {
+    fn generate(&self) -> Result<String> {
+        let mut out = String::new();
+        self.write_to(&mut out)?;
+        Ok(out)
+    }
+
+    fn write_to(&self, writer: &mut dyn std::fmt::Write) -> Result<()> {
+        let id = self.inner.generate()?;
+        let bytes = crate::ids::parse_id(&id, Some(self.kind))?.as_bytes();
+        writer.write_str("\\\\x")?;
+        writer.write_str(&hex::encode(bytes))?;
+        Ok(())
+    }
+}
+
+/// Runs up to this many IDs are checked exactly; longer ones use a Bloom filter
+const EXACT_COLLISION_LIMIT: usize = 1_000_000;
+/// Bloom filter capacity for open-ended `--for` streams
+const STREAM_COLLISION_CAPACITY: u64 = 10_000_000;
+/// Consecutive collisions before the ID space is considered exhausted
+const MAX_COLLISION_RETRIES: u32 = 1000;
+
+/// Hand `f` a generator that never repeats an ID from this run, then report
+/// how many were regenerated
+fn check_collisions<T>(
+    args: &GenArgs,
+    inner: &dyn IdGenerator,
+    f: impl FnOnce(&dyn IdGenerator) -> Result<T>,
+) -> Result<T> {
+    let seen = match args.duration {
+        None if args.count <= EXACT_COLLISION_LIMIT => Seen::Exact(HashSet::new()),
+        None => Seen::Approximate(BloomFilter::new(args.count as u64, 1e-6)?),
+        Some(_) => Seen::Approximate(BloomFilter::new(STREAM_COLLISION_CAPACITY, 1e-6)?),
+    };
+    let approximate = matches!(seen, Seen::Approximate(_));
+    let checked = CollisionCheckedGenerator {
+        inner,
+        seen: RefCell::new(seen),
+        regenerated: Cell::new(0),
+    };
+    let result = f(&checked);
+
+    let regenerated = checked.regenerated.get();
+    if regenerated > 0 {
+        eprintln!(
+            "Warning: regenerated {} colliding ID{}{}",
+            regenerated,
+            if regenerated == 1 { "" } else { "s" },
+            if approximate {
+                " (Bloom filter; some may be false positives)"
+            } else {
+                ""
             }
+        );
+    }
+    result
+}
+
+/// Regenerates any ID from `inner` that was already produced
+struct CollisionCheckedGenerator<'a> {
+    inner: &'a dyn IdGenerator,
+    seen: RefCell<Seen>,
+    regenerated: Cell<u64>,
+}
 
-            for _ in 0..args.count {
-                ids.push(crate::core::id::IdGenerator::generate(&generator)?);
+impl IdGenerator for CollisionCheckedGenerator<'_> {
+    fn generate(&self) -> Result<String> {
+        let mut seen = self.seen.borrow_mut();
+        for _ in 0..MAX_COLLISION_RETRIES {
+            let id = self.inner.generate()?;
+            if !seen.check_and_insert(&id) {
+                return Ok(id);
             }
+            self.regenerated.set(self.regenerated.get() + 1);
         }
-        IdKind::ObjectId
-        | IdKind::Ksuid
-        | IdKind::Xid
-        | IdKind::Tsid
-        | IdKind::Cuid
-        | IdKind::Cuid2
-        | IdKind::ShortUuid => {
-            let generator = crate::ids::create_generator(kind)?;
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
+        Err(IdtError::GenerationError(format!(
+            "{} collisions in a row; the ID space is nearly exhausted (try a longer --length or a larger alphabet)",
+            MAX_COLLISION_RETRIES
+        )))
+    }
+}
+
+/// Writes each ID from `inner` as a SQL literal for `db`
+struct SqlLiteralGenerator<'a> {
+    inner: &'a dyn IdGenerator,
+    kind: IdKind,
+    db: Database,
+}
+
+impl IdGenerator for SqlLiteralGenerator<'_> {
+    fn generate(&self) -> Result<String> {
+        let id = self.inner.generate()?;
+        Ok(self
+            .db
+            .literal(crate::ids::parse_id(&id, Some(self.kind))?.as_ref()))
+    }
+}
+
+/// The literal prefix and suffix for each ID. TypeID consumes `--prefix` as
+/// its type prefix, so only the suffix applies there.
+fn decorations(args: &GenArgs, kind: IdKind) -> (&str, &str) {
+    let prefix = match kind {
+        IdKind::TypeId => "",
+        _ => args.prefix.as_deref().unwrap_or(""),
+    };
+    (prefix, args.suffix.as_deref().unwrap_or(""))
+}
+
+/// Recases each ID from `inner` and wraps it in a fixed prefix and suffix
+struct DecoratedGenerator<'a> {
+    inner: &'a dyn IdGenerator,
+    case: Option<Case>,
+    prefix: &'a str,
+    suffix: &'a str,
+}
+
+impl IdGenerator for DecoratedGenerator<'_> {
+    fn generate(&self) -> Result<String> {
+        let mut out = String::new();
+        self.write_to(&mut out)?;
+        Ok(out)
+    }
+
+    fn write_to(&self, writer: &mut dyn std::fmt::Write) -> Result<()> {
+        writer.write_str(self.prefix)?;
+        match self.case {
+            Some(case) => {
+                let mut id = self.inner.generate()?;
+                case.apply(&mut id);
+                writer.write_str(&id)?;
             }
+            None => self.inner.write_to(writer)?,
         }
-        IdKind::TypeId => {
-            let prefix = args.prefix.as_deref().unwrap_or("");
-            let generator = TypeIdGenerator::new(prefix);
-            for _ in 0..args.count {
-                ids.push(generator.generate()?);
+        writer.write_str(self.suffix)?;
+        Ok(())
+    }
+}
+
+/// Progress for a plain stream, measured against `-n` unless `--for` sets
+/// the end
+fn progress(args: &GenArgs) -> Progress {
+    let total = args
+        .duration
+        .is_none()
+        .then_some(Total::Items(args.count as u64));
+    Progress::new("gen", total, false)
+}
+
+/// Write `count` IDs, one per line, reusing one buffer for every ID
+fn stream_plain(
+    writer: &mut dyn Write,
+    generator: &dyn IdGenerator,
+    count: usize,
+    template: Option<&str>,
+    terminator: Terminator,
+    mut pace: Option<&mut Pace>,
+    progress: &mut Progress,
+) -> Result<()> {
+    let parts: Option<Vec<&str>> = template.map(|tpl| tpl.split("{}").collect());
+    let mut buf = String::new();
+
+    for i in 0..count {
+        if let Some(pace) = pace.as_deref_mut()
+            && !pace.wait()
+        {
+            break;
+        }
+        buf.clear();
+        generator.generate_into(&mut buf)?;
+        progress.tick(buf.len() + 1);
+
+        match parts {
+            Some(ref parts) => {
+                for (j, part) in parts.iter().enumerate() {
+                    if j > 0 {
+                        writer.write_all(buf.as_bytes())?;
+                    }
+                    writer.write_all(part.as_bytes())?;
+                }
             }
+            None => writer.write_all(buf.as_bytes())?,
+        }
+
+        writer.write_all(terminator.after(i + 1 == count).as_bytes())?;
+        // Paced IDs must reach the reader as they are made
+        if pace.is_some() {
+            writer.flush()?;
         }
-        _ => unreachable!("GenIdKind only contains generable types"),
     }
+    Ok(())
+}
+
+/// Schedule for `--rate`: one ID per `period`, each interval stretched or
+/// shrunk by up to `jitter`, until the optional `--for` deadline
+struct Pace {
+    period: Duration,
+    jitter: f64,
+    next: Option<Instant>,
+    deadline: Option<Instant>,
+}
+
+impl Pace {
+    fn new(period: Duration, jitter_percent: Option<u8>, duration: Option<Duration>) -> Self {
+        Self {
+            period,
+            jitter: jitter_percent.unwrap_or(0) as f64 / 100.0,
+            next: None,
+            deadline: duration.map(|d| Instant::now() + d),
+        }
+    }
+
+    /// Sleep until the next ID is due; false once the deadline has passed
+    fn wait(&mut self) -> bool {
+        let now = Instant::now();
+        let due = self.next.unwrap_or(now);
+        if self.deadline.is_some_and(|deadline| due >= deadline) {
+            return false;
+        }
+        if due > now {
+            std::thread::sleep(due - now);
+        }
 
-    Ok(ids)
+        // A slow reader delays the schedule instead of causing a burst later
+        let start = due.max(now);
+        let factor = match self.jitter {
+            0.0 => 1.0,
+            jitter => 1.0 + rand::rng().random_range(-jitter..=jitter),
+        };
+        self.next = Some(start + self.period.mul_f64(factor));
+        true
+    }
 }
 
 fn format_id(id: &str, kind: &IdKind, format: EncodingFormat) -> Result<String> {
     // Parse and re-encode
     let parsed = crate::ids::parse_id(id, Some(*kind))?;
+    if format == EncodingFormat::JavaLongs && parsed.as_bytes().len() != 16 {
+        return Err(IdtError::InvalidArgument(format!(
+            "java-longs needs a 128-bit ID, not {}",
+            kind.name()
+        )));
+    }
+    if format == EncodingFormat::SortKey {
+        crate::core::sortkey::sort_key(parsed.as_ref())?;
+    }
     Ok(parsed.encode(format))
 }
 
-fn output_plain(writer: &mut dyn Write, ids: &[String], no_newline: bool) -> Result<()> {
+fn output_plain(writer: &mut dyn Write, ids: &[String], terminator: Terminator) -> Result<()> {
     for (i, id) in ids.iter().enumerate() {
-        if i == ids.len() - 1 && no_newline {
-            write!(writer, "{}", id)?;
+        write!(writer, "{}{}", id, terminator.after(i + 1 == ids.len()))?;
+    }
+    Ok(())
+}
+
+/// What follows each plain ID: a newline, or the `--print0` or
+/// `--output-delimiter` terminator
+#[derive(Clone, Copy)]
+struct Terminator<'a> {
+    text: &'a str,
+    /// `--no-newline`: leave it off after the last ID
+    omit_last: bool,
+}
+
+impl<'a> Terminator<'a> {
+    #[cfg(test)]
+    const NEWLINE: Terminator<'static> = Terminator {
+        text: "\n",
+        omit_last: false,
+    };
+
+    fn new(args: &'a GenArgs) -> Self {
+        Self {
+            text: args.records.terminator(),
+            omit_last: args.no_newline && args.count == 1 && args.copy.is_none(),
+        }
+    }
+
+    fn after(&self, last: bool) -> &'a str {
+        if last && self.omit_last {
+            ""
         } else {
-            writeln!(writer, "{}", id)?;
+            self.text
         }
     }
-    Ok(())
 }
 
 #[cfg(test)]
@@ -271,15 +1424,39 @@ mod tests {
     use super::*;
     use crate::cli::app::GenArgs;
     use crate::core::id::{GenIdKind, IdKind};
+    use crate::core::rng::RngSource;
+    use crate::ids::{NodeIdSource, UuidGenerator};
 
     fn make_gen_args(kind: GenIdKind) -> GenArgs {
         GenArgs {
-            id_type: kind,
+            id_type: Some(kind),
+            mix: None,
+            label: false,
+            manifest: None,
+            records: Default::default(),
+            qr: Default::default(),
             count: 1,
             format: None,
             no_newline: false,
             template: None,
+            case: None,
+            suffix: None,
+            copy: None,
+            bytea: false,
+            db: None,
+            lang: None,
+            at: None,
+            bound: None,
+            check_collisions: false,
+            rng: None,
+            rate: None,
+            jitter: None,
+            duration: None,
             uuid_version: None,
+            style: None,
+            namespace: None,
+            names_file: None,
+            with_input: false,
             alphabet: None,
             length: None,
             epoch: None,
@@ -287,6 +1464,9 @@ mod tests {
             field: vec![],
             machine_id: None,
             datacenter_id: None,
+            state_file: None,
+            clock_rollback: Default::default(),
+            sequence_overflow: Default::default(),
             prefix: None,
         }
     }
@@ -299,6 +1479,19 @@ mod tests {
         assert_eq!(ids[0].len(), 36);
     }
 
+    #[test]
+    fn test_generate_uuid_style() {
+        let mut args = make_gen_args(GenIdKind::UuidV7);
+        args.style = Some(crate::ids::UuidStyle::Braced);
+        let ids = generate_ids(&args, IdKind::UuidV7).unwrap();
+        assert_eq!(ids[0].len(), 38);
+        assert!(ids[0].starts_with('{'));
+
+        let mut args = make_gen_args(GenIdKind::Ulid);
+        args.style = Some(crate::ids::UuidStyle::Urn);
+        assert!(generate_ids(&args, IdKind::Ulid).is_err());
+    }
+
     #[test]
     fn test_generate_uuid_v7() {
         let args = make_gen_args(GenIdKind::UuidV7);
@@ -375,6 +1568,28 @@ mod tests {
         assert!(ids[0].chars().all(|c| "abc".contains(c)));
     }
 
+    #[test]
+    fn test_generate_seeded_rng() {
+        let mut args = make_gen_args(GenIdKind::NanoId);
+        args.count = 5;
+        args.rng = Some(RngSource::Seeded(42));
+        let first = generate_ids(&args, IdKind::NanoId).unwrap();
+        assert_eq!(first, generate_ids(&args, IdKind::NanoId).unwrap());
+        args.rng = Some(RngSource::Seeded(43));
+        assert_ne!(first, generate_ids(&args, IdKind::NanoId).unwrap());
+    }
+
+    #[test]
+    fn test_generate_rng_rejects_non_random() {
+        let mut args = make_gen_args(GenIdKind::Snowflake);
+        args.rng = Some(RngSource::Os);
+        assert!(generate_ids(&args, IdKind::Snowflake).is_err());
+        let mut args = make_gen_args(GenIdKind::Uuid);
+        args.uuid_version = Some(1);
+        args.rng = Some(RngSource::Os);
+        assert!(generate_ids(&args, IdKind::Uuid).is_err());
+    }
+
     #[test]
     fn test_generate_snowflake() {
         let args = make_gen_args(GenIdKind::Snowflake);
@@ -459,7 +1674,7 @@ mod tests {
     fn test_output_plain_single() {
         let mut buf = Vec::new();
         let ids = vec!["test-id".to_string()];
-        output_plain(&mut buf, &ids, false).unwrap();
+        output_plain(&mut buf, &ids, Terminator::NEWLINE).unwrap();
         assert_eq!(String::from_utf8(buf).unwrap(), "test-id\n");
     }
 
@@ -467,7 +1682,11 @@ mod tests {
     fn test_output_plain_no_newline() {
         let mut buf = Vec::new();
         let ids = vec!["test-id".to_string()];
-        output_plain(&mut buf, &ids, true).unwrap();
+        let terminator = Terminator {
+            omit_last: true,
+            ..Terminator::NEWLINE
+        };
+        output_plain(&mut buf, &ids, terminator).unwrap();
         assert_eq!(String::from_utf8(buf).unwrap(), "test-id");
     }
 
@@ -475,10 +1694,367 @@ mod tests {
     fn test_output_plain_multiple() {
         let mut buf = Vec::new();
         let ids = vec!["id1".to_string(), "id2".to_string()];
-        output_plain(&mut buf, &ids, false).unwrap();
+        output_plain(&mut buf, &ids, Terminator::NEWLINE).unwrap();
         assert_eq!(String::from_utf8(buf).unwrap(), "id1\nid2\n");
     }
 
+    #[test]
+    fn test_output_plain_print0() {
+        let mut buf = Vec::new();
+        let ids = vec!["id1".to_string(), "id 2".to_string()];
+        let terminator = Terminator {
+            text: "\0",
+            ..Terminator::NEWLINE
+        };
+        output_plain(&mut buf, &ids, terminator).unwrap();
+        assert_eq!(buf, b"id1\0id 2\0");
+    }
+
+    #[test]
+    fn test_stream_plain_template() {
+        let mut buf = Vec::new();
+        let generator = UuidGenerator::nil();
+        stream_plain(
+            &mut buf,
+            &generator,
+            2,
+            Some("<{}|{}>"),
+            Terminator::NEWLINE,
+            None,
+            &mut Progress::hidden(),
+        )
+        .unwrap();
+        let nil = "00000000-0000-0000-0000-000000000000";
+        let line = format!("<{}|{}>\n", nil, nil);
+        assert_eq!(String::from_utf8(buf).unwrap(), line.repeat(2));
+    }
+
+    #[test]
+    fn test_stream_plain_no_newline() {
+        let mut buf = Vec::new();
+        let generator = UuidGenerator::max();
+        stream_plain(
+            &mut buf,
+            &generator,
+            1,
+            None,
+            Terminator {
+                omit_last: true,
+                ..Terminator::NEWLINE
+            },
+            None,
+            &mut Progress::hidden(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "ffffffff-ffff-ffff-ffff-ffffffffffff"
+        );
+    }
+
+    #[test]
+    fn test_stream_copy_bytea() {
+        let mut args = make_gen_args(GenIdKind::UuidMax);
+        args.copy = Some("users (id)".to_string());
+        let mut buf = Vec::new();
+        write_copy_header(&mut buf, &args).unwrap();
+        let inner = UuidGenerator::max();
+        let generator = ByteaGenerator {
+            inner: &inner,
+            kind: IdKind::UuidMax,
+        };
+        stream_plain(
+            &mut buf,
+            &generator,
+            2,
+            None,
+            Terminator::NEWLINE,
+            None,
+            &mut Progress::hidden(),
+        )
+        .unwrap();
+        write_copy_trailer(&mut buf, &args).unwrap();
+
+        let row = format!("\\\\x{}\n", "ff".repeat(16));
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            format!("COPY users (id) FROM STDIN;\n{}\\.\n", row.repeat(2))
+        );
+    }
+
+    #[test]
+    fn test_stream_decorated_template() {
+        let inner = UuidGenerator::nil();
+        let generator = DecoratedGenerator {
+            inner: &inner,
+            case: None,
+            prefix: "req-",
+            suffix: "-test",
+        };
+        let mut buf = Vec::new();
+        stream_plain(
+            &mut buf,
+            &generator,
+            1,
+            Some("id={}"),
+            Terminator::NEWLINE,
+            None,
+            &mut Progress::hidden(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "id=req-00000000-0000-0000-0000-000000000000-test\n"
+        );
+    }
+
+    #[test]
+    fn test_stream_paced_for_duration() {
+        let inner = UuidGenerator::nil();
+        let mut pace = Pace::new(
+            Duration::from_millis(20),
+            Some(50),
+            Some(Duration::from_millis(100)),
+        );
+        let mut buf = Vec::new();
+        let start = Instant::now();
+        stream_plain(
+            &mut buf,
+            &inner,
+            usize::MAX,
+            None,
+            Terminator::NEWLINE,
+            Some(&mut pace),
+            &mut Progress::hidden(),
+        )
+        .unwrap();
+
+        let lines = String::from_utf8(buf).unwrap().lines().count();
+        // Jitter of 50% keeps every interval at 10ms or more
+        assert!((1..=10).contains(&lines), "{}", lines);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_parse_rate_args() {
+        use crate::cli::{Cli, Commands};
+        use clap::Parser;
+
+        let rate = |value: &str| {
+            let cli = Cli::try_parse_from(["idt", "gen", "ulid", "--rate", value])?;
+            match cli.command {
+                Commands::Gen(args) => Ok::<_, clap::Error>(args.rate.unwrap()),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(rate("500/s").unwrap(), Duration::from_millis(2));
+        assert_eq!(rate("500").unwrap(), Duration::from_millis(2));
+        assert_eq!(rate("30/m").unwrap(), Duration::from_secs(2));
+        assert!(rate("0/s").is_err());
+        assert!(rate("5/d").is_err());
+
+        let parse = |extra: &[&str]| {
+            Cli::try_parse_from(["idt", "gen", "ulid"].iter().chain(extra).copied())
+        };
+        assert!(parse(&["--rate", "10/s", "--for", "1m"]).is_ok());
+        assert!(parse(&["--rate", "10/s", "--for", "1m", "-n", "5"]).is_err());
+        assert!(parse(&["--for", "1m"]).is_err());
+        assert!(parse(&["--rate", "10/s", "--jitter", "101"]).is_err());
+    }
+
+    #[test]
+    fn test_write_snippet() {
+        let ids = ["a1".to_string(), "b\"2".to_string()];
+        let snippet = |lang| {
+            let mut buf = Vec::new();
+            write_snippet(&mut buf, lang, &ids).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+        assert_eq!(
+            snippet(Lang::Rust),
+            "const IDS: [&str; 2] = [\n    \"a1\",\n    \"b\\\"2\",\n];\n"
+        );
+        assert_eq!(
+            snippet(Lang::Go),
+            "var ids = []string{\n\t\"a1\",\n\t\"b\\\"2\",\n}\n"
+        );
+        assert_eq!(snippet(Lang::Sql), "ARRAY[\n    'a1',\n    'b\"2'\n]\n");
+        assert!(snippet(Lang::Java).starts_with("String[] ids = {\n"));
+    }
+
+    #[test]
+    fn test_lang_rejects_structured_output() {
+        let mut args = make_gen_args(GenIdKind::UuidV4);
+        args.lang = Some(Lang::Python);
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_err());
+    }
+
+    #[test]
+    fn test_stream_case_upper() {
+        let inner = UuidGenerator::max();
+        let generator = DecoratedGenerator {
+            inner: &inner,
+            case: Some(Case::Upper),
+            prefix: "id-",
+            suffix: "",
+        };
+        let mut buf = Vec::new();
+        stream_plain(
+            &mut buf,
+            &generator,
+            1,
+            None,
+            Terminator::NEWLINE,
+            None,
+            &mut Progress::hidden(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "id-FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_at() {
+        let mut args = make_gen_args(GenIdKind::UuidV7);
+        args.at = Some("2023-11-14T22:13:20.123Z".to_string());
+        let ids = generate_ids(&args, IdKind::UuidV7).unwrap();
+        let parsed = crate::ids::parse_id(&ids[0], None).unwrap();
+        assert_eq!(parsed.timestamp().unwrap().millis, 1_700_000_000_123);
+
+        args.id_type = Some(GenIdKind::Ulid);
+        let ids = generate_ids(&args, IdKind::Ulid).unwrap();
+        let parsed = crate::ids::parse_id(&ids[0], None).unwrap();
+        assert_eq!(parsed.timestamp().unwrap().millis, 1_700_000_000_123);
+
+        for (gen_kind, kind, millis) in [
+            (GenIdKind::ObjectId, IdKind::ObjectId, 1_700_000_000_000),
+            (GenIdKind::Xid, IdKind::Xid, 1_700_000_000_000),
+            (GenIdKind::Tsid, IdKind::Tsid, 1_700_000_000_123),
+            (GenIdKind::Cuid, IdKind::Cuid, 1_700_000_000_123),
+            (GenIdKind::TypeId, IdKind::TypeId, 1_700_000_000_123),
+        ] {
+            args.id_type = Some(gen_kind);
+            let ids = generate_ids(&args, kind).unwrap();
+            let parsed = crate::ids::parse_id(&ids[0], Some(kind)).unwrap();
+            assert_eq!(parsed.timestamp().unwrap().millis, millis, "{}", kind);
+        }
+    }
+
+    #[test]
+    fn test_generate_bounds() {
+        let mut args = make_gen_args(GenIdKind::Ulid);
+        args.at = Some("2016-07-30T23:54:10.259Z".to_string());
+        args.bound = Some(Bound::Min);
+        assert_eq!(
+            generate_ids(&args, IdKind::Ulid).unwrap(),
+            ["01ARZ3NDEK0000000000000000"]
+        );
+        args.bound = Some(Bound::Max);
+        assert_eq!(
+            generate_ids(&args, IdKind::Ulid).unwrap(),
+            ["01ARZ3NDEKZZZZZZZZZZZZZZZZ"]
+        );
+
+        args.id_type = Some(GenIdKind::Ksuid);
+        args.at = Some("2014-05-13T16:53:20Z".to_string());
+        args.bound = Some(Bound::Min);
+        assert_eq!(
+            generate_ids(&args, IdKind::Ksuid).unwrap(),
+            ["000000000000000000000000000"]
+        );
+
+        args.id_type = Some(GenIdKind::UuidV7);
+        assert!(generate_ids(&args, IdKind::UuidV7).is_err());
+    }
+
+    #[test]
+    fn test_every_generatable_kind_generates() {
+        let args = make_gen_args(GenIdKind::Uuid);
+        for &kind in IdKind::generatable() {
+            let ids = generate_ids(&args, kind).unwrap();
+            assert!(
+                crate::ids::parse_id(&ids[0], Some(kind)).is_ok(),
+                "{}",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_at_rejected() {
+        let mut args = make_gen_args(GenIdKind::UuidV4);
+        args.at = Some("-1h".to_string());
+        assert!(generate_ids(&args, IdKind::UuidV4).is_err());
+
+        let mut args = make_gen_args(GenIdKind::UuidV7);
+        args.at = Some("1969-12-31".to_string());
+        assert!(generate_ids(&args, IdKind::UuidV7).is_err());
+
+        // Past what the 32-bit seconds field holds
+        let mut args = make_gen_args(GenIdKind::ObjectId);
+        args.at = Some("2107-01-01".to_string());
+        assert!(generate_ids(&args, IdKind::ObjectId).is_err());
+    }
+
+    #[test]
+    fn test_execute_prefix_suffix_json() {
+        let mut args = make_gen_args(GenIdKind::Ulid);
+        args.prefix = Some("req-".to_string());
+        args.suffix = Some("-test".to_string());
+        args.format = Some("hex".to_string());
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_ok());
+    }
+
+    #[test]
+    fn test_execute_uuidv5_names_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("domains.txt");
+        std::fs::write(&path, "www.example.com\nexample.org\n").unwrap();
+        let mut args = make_gen_args(GenIdKind::UuidV5);
+        args.namespace = Some("dns".to_string());
+        args.names_file = Some(path);
+        args.with_input = true;
+        assert!(execute(&args, None, false).is_ok());
+
+        // Options that make no sense for derived UUIDs are rejected
+        args.count = 5;
+        let err = execute(&args, None, false).unwrap_err();
+        assert!(err.to_string().contains("-n doesn't apply"));
+
+        // Name-based options are rejected for random kinds
+        let mut args = make_gen_args(GenIdKind::UuidV4);
+        args.namespace = Some("dns".to_string());
+        let err = execute(&args, None, false).unwrap_err();
+        assert!(err.to_string().contains("only apply to uuidv3 and uuidv5"));
+    }
+
+    #[test]
+    fn test_derive_ids_formatting() {
+        let names = ["www.example.com".to_string()];
+        let mut args = make_gen_args(GenIdKind::UuidV5);
+        args.case = Some(Case::Upper);
+        args.style = Some(UuidStyle::Braced);
+        args.suffix = Some("X".to_string());
+        args.template = Some("id={}".to_string());
+        let ids = derive_ids(&args, IdKind::UuidV5, "dns", &names).unwrap();
+        assert_eq!(ids, ["id={2ED6657D-E927-568B-95E1-2665A8AEA6A2}X"]);
+
+        let mut args = make_gen_args(GenIdKind::UuidV3);
+        args.format = Some("hex".to_string());
+        let ids = derive_ids(&args, IdKind::UuidV3, "dns", &names).unwrap();
+        assert_eq!(ids[0].len(), 32);
+        assert!(!ids[0].contains('-'));
+    }
+
+    #[test]
+    fn test_execute_copy_with_format_error() {
+        let mut args = make_gen_args(GenIdKind::UuidV4);
+        args.copy = Some("t".to_string());
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_err());
+    }
+
     #[test]
     fn test_execute_template_with_format_error() {
         let mut args = make_gen_args(GenIdKind::UuidV4);
@@ -549,6 +2125,51 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_collision_check_regenerates() {
+        // 2-character binary NanoIDs: only four distinct values
+        let mut args = make_gen_args(GenIdKind::NanoId);
+        args.alphabet = Some("01".to_string());
+        args.length = Some(2);
+        args.count = 4;
+        args.check_collisions = true;
+        let mut ids = generate_ids(&args, IdKind::NanoId).unwrap();
+        ids.sort();
+        assert_eq!(ids, ["00", "01", "10", "11"]);
+
+        args.count = 5;
+        let err = generate_ids(&args, IdKind::NanoId).unwrap_err();
+        assert!(err.to_string().contains("nearly exhausted"));
+    }
+
+    #[test]
+    fn test_collision_checked_generator_counts_retries() {
+        let inner = FixedGenerator("same".to_string());
+        let checked = CollisionCheckedGenerator {
+            inner: &inner,
+            seen: RefCell::new(Seen::Exact(HashSet::new())),
+            regenerated: Cell::new(0),
+        };
+        assert_eq!(checked.generate().unwrap(), "same");
+        assert!(checked.generate().is_err());
+        assert_eq!(checked.regenerated.get(), MAX_COLLISION_RETRIES as u64);
+    }
+
+    #[test]
+    fn test_execute_cbor_output() {
+        let mut args = make_gen_args(GenIdKind::Ulid);
+        args.count = 3;
+        let result = execute(&args, Some(OutputFormat::Cbor), false);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_execute_msgpack_output() {
+        let args = make_gen_args(GenIdKind::UuidV7);
+        let result = execute(&args, Some(OutputFormat::Msgpack), false);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_execute_json_pretty() {
         let args = make_gen_args(GenIdKind::UuidV4);
@@ -582,7 +2203,7 @@ mod tests {
     fn test_snowflake_with_machine_id() {
         let mut args = make_gen_args(GenIdKind::Snowflake);
         args.preset = Some("twitter".to_string());
-        args.machine_id = Some(1);
+        args.machine_id = Some(NodeIdSource::Value(1));
         let ids = generate_ids(&args, IdKind::Snowflake).unwrap();
         assert_eq!(ids.len(), 1);
     }
@@ -591,7 +2212,7 @@ mod tests {
     fn test_snowflake_machine_id_rejected() {
         let mut args = make_gen_args(GenIdKind::Snowflake);
         args.preset = Some("instagram".to_string());
-        args.machine_id = Some(1);
+        args.machine_id = Some(NodeIdSource::Value(1));
         let err = generate_ids(&args, IdKind::Snowflake).unwrap_err();
         let msg = format!("{}", err);
         assert!(msg.contains("does not have a machine_id field"));
@@ -601,7 +2222,7 @@ mod tests {
     fn test_snowflake_with_datacenter_id() {
         let mut args = make_gen_args(GenIdKind::Snowflake);
         args.preset = Some("twitter".to_string());
-        args.datacenter_id = Some(1);
+        args.datacenter_id = Some(NodeIdSource::Value(1));
         let ids = generate_ids(&args, IdKind::Snowflake).unwrap();
         assert_eq!(ids.len(), 1);
     }
@@ -610,7 +2231,7 @@ mod tests {
     fn test_snowflake_datacenter_id_rejected() {
         let mut args = make_gen_args(GenIdKind::Snowflake);
         args.preset = Some("sonyflake".to_string());
-        args.datacenter_id = Some(1);
+        args.datacenter_id = Some(NodeIdSource::Value(1));
         let err = generate_ids(&args, IdKind::Snowflake).unwrap_err();
         let msg = format!("{}", err);
         assert!(msg.contains("does not have a datacenter_id field"));
@@ -654,4 +2275,223 @@ mod tests {
         let msg = format!("{}", err);
         assert!(msg.contains("expected integer"));
     }
+
+    #[test]
+    fn test_snowflake_state_file_persists_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snowflake.json");
+
+        let mut args = make_gen_args(GenIdKind::Snowflake);
+        args.preset = Some("twitter".to_string());
+        args.count = 3;
+        args.state_file = Some(path.clone());
+
+        let first = generate_ids(&args, IdKind::Snowflake).unwrap();
+        let second = generate_ids(&args, IdKind::Snowflake).unwrap();
+
+        let values: Vec<u64> = first
+            .iter()
+            .chain(second.iter())
+            .map(|id| id.parse().unwrap())
+            .collect();
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+
+        // The assigned machine ID is reused on the second run
+        let machine_ids: std::collections::HashSet<u16> = first
+            .iter()
+            .chain(second.iter())
+            .map(|id| {
+                crate::ids::ParsedSnowflake::parse_twitter(id)
+                    .unwrap()
+                    .machine_id()
+            })
+            .collect();
+        assert_eq!(machine_ids.len(), 1);
+    }
+
+    #[test]
+    fn test_ulid_state_file_is_monotonic_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ulid.json");
+
+        // A fixed time puts every run in the same millisecond
+        let mut args = make_gen_args(GenIdKind::Ulid);
+        args.at = Some("2024-01-01T00:00:00Z".to_string());
+        args.count = 3;
+        args.state_file = Some(path.clone());
+
+        let mut ids = generate_ids(&args, IdKind::Ulid).unwrap();
+        ids.extend(generate_ids(&args, IdKind::Ulid).unwrap());
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+        let state: UlidState =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            ParsedUlid::from_parts(state.last_timestamp, state.last_random)
+                .unwrap()
+                .canonical(),
+            ids[5]
+        );
+    }
+
+    #[test]
+    fn test_state_file_rejects_other_types() {
+        let mut args = make_gen_args(GenIdKind::UuidV4);
+        args.state_file = Some("state.json".into());
+        let err = generate_ids(&args, IdKind::UuidV4).unwrap_err();
+        assert!(err.to_string().contains("--state-file only applies"));
+    }
+
+    #[test]
+    fn test_snowflake_state_file_layout_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snowflake.json");
+
+        let mut args = make_gen_args(GenIdKind::Snowflake);
+        args.preset = Some("twitter".to_string());
+        args.state_file = Some(path.clone());
+        generate_ids(&args, IdKind::Snowflake).unwrap();
+
+        args.preset = Some("discord".to_string());
+        assert!(generate_ids(&args, IdKind::Snowflake).is_err());
+    }
+
+    #[test]
+    fn test_read_manifest() {
+        let manifest = "\
+# fixtures
+label,type,count,prefix,length,preset,fields
+users,typeid,2,user,,,
+,nanoid,3,,8,,
+events,snowflake,1,,,discord,machine_id=3;datacenter_id=1
+";
+        let args = make_gen_args(GenIdKind::Uuid);
+        let rows = read_manifest(manifest.as_bytes(), &args).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!((rows[0].label.as_str(), rows[0].count), ("users", 2));
+        assert_eq!((rows[1].label.as_str(), rows[1].count), ("nanoid", 3));
+
+        let ids = rows[0].generator().unwrap().generate_many(2).unwrap();
+        assert!(ids.iter().all(|id| id.starts_with("user_")));
+        assert_eq!(rows[1].generator().unwrap().generate().unwrap().len(), 8);
+        let id = rows[2].generator().unwrap().generate().unwrap();
+        let parsed = crate::ids::ParsedSnowflake::parse_discord(&id).unwrap();
+        assert_eq!((parsed.machine_id(), parsed.datacenter_id()), (3, 1));
+    }
+
+    #[test]
+    fn test_read_manifest_rejects() {
+        let args = make_gen_args(GenIdKind::Uuid);
+        for manifest in [
+            "type,bogus\nulid,1\n",
+            "count\n1\n",
+            "type\n",
+            "type\nnotatype\n",
+            "type\nuuidv5\n",
+            "type,count\nulid,many\n",
+            "type,prefix\nulid,user\n",
+            "type,alphabet\nnanoid,aab\n",
+            "type,fields\nsnowflake,machine_id\n",
+            "type,fields\nsnowflake,shard=1\n",
+        ] {
+            assert!(
+                read_manifest(manifest.as_bytes(), &args).is_err(),
+                "{}",
+                manifest
+            );
+        }
+        let err = read_manifest("type,at\nulid,now\nuuidv4,now\n".as_bytes(), &args)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("line 3"), "{}", err);
+    }
+
+    #[test]
+    fn test_mix_shares_are_exact() {
+        assert_eq!(shares(&[70, 20, 10], 100_000), vec![70_000, 20_000, 10_000]);
+        assert_eq!(shares(&[1, 1, 1], 10), vec![4, 3, 3]);
+        assert_eq!(shares(&[2, 1], 0), vec![0, 0]);
+
+        let mut picker = MixPicker::new(&[3, 1], Some(400));
+        let mut counts = [0; 2];
+        for _ in 0..400 {
+            counts[picker.pick()] += 1;
+        }
+        assert_eq!(counts, [300, 100]);
+    }
+
+    #[test]
+    fn test_mix_interleaves_labeled_types() {
+        let mut args = make_gen_args(GenIdKind::Ulid);
+        args.id_type = None;
+        let kinds = [IdKind::UuidV7, IdKind::Ulid];
+        let mut lines = Vec::new();
+        with_generators(&args, &kinds, &[], &mut |generators| {
+            let mixed = MixedGenerator {
+                parts: generators.iter().copied().zip(kinds).collect(),
+                picker: RefCell::new(MixPicker::new(&[1, 1], Some(20))),
+                label: true,
+            };
+            lines = mixed.generate_many(20)?;
+            Ok(())
+        })
+        .unwrap();
+
+        for line in &lines {
+            let (label, id) = line.split_once('\t').unwrap();
+            let parsed = crate::ids::parse_id(id, None).unwrap();
+            assert_eq!(parsed.kind().name(), label);
+        }
+        assert_eq!(lines.iter().filter(|l| l.starts_with("ulid\t")).count(), 10);
+    }
+
+    #[test]
+    fn test_label_requires_mix() {
+        let mut args = make_gen_args(GenIdKind::Ulid);
+        args.label = true;
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_parse_mix_args() {
+        use crate::cli::{Cli, Commands};
+        use clap::Parser;
+
+        let parse = |extra: &[&str]| {
+            let cli = Cli::try_parse_from(["idt", "gen"].iter().chain(extra).copied())?;
+            match cli.command {
+                Commands::Gen(args) => Ok::<_, clap::Error>(args.mix.map(|m| m.0)),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(
+            parse(&["--mix", "uuidv7:70, ulid:20,ksuid"]).unwrap(),
+            Some(vec![
+                (GenIdKind::UuidV7, 70),
+                (GenIdKind::Ulid, 20),
+                (GenIdKind::Ksuid, 1)
+            ])
+        );
+        assert!(parse(&["--mix", "uuidv7:0"]).is_err());
+        assert!(parse(&["--mix", "bogus:1"]).is_err());
+        assert!(parse(&["--mix", "uuidv5:1"]).is_err());
+        assert!(parse(&["--mix", "ulid:1,ulid:2"]).is_err());
+        assert!(parse(&["ulid", "--mix", "ksuid:1"]).is_err());
+        assert!(parse(&["--mix", "ulid:1", "-f", "hex"]).is_err());
+        assert!(parse(&[]).is_err());
+    }
+
+    #[test]
+    fn test_sql_literal_generator() {
+        let inner = FixedGenerator("550e8400-e29b-41d4-a716-446655440000".to_string());
+        let generator = SqlLiteralGenerator {
+            inner: &inner,
+            kind: IdKind::UuidV4,
+            db: Database::Mysql,
+        };
+        assert_eq!(
+            generator.generate().unwrap(),
+            "UNHEX('550e8400e29b41d4a716446655440000')"
+        );
+    }
 }
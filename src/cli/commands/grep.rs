@@ -0,0 +1,212 @@
+use crate::cli::app::{GrepArgs, OutputFormat};
+use crate::cli::input::{InputSource, stream_ids};
+use crate::cli::output::{stdout, write_output};
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, InspectionResult, ParsedId};
+use crate::core::predicate::{Predicate, known_fields};
+use crate::ids::snowflake_id::SnowflakeLayout;
+use std::io::Write;
+
+pub fn execute(args: &GrepArgs, format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let predicates = args.all_predicates();
+    if predicates.is_empty() {
+        return Err(IdtError::InvalidArgument(
+            "No predicates given. Use --where, --machine-id, --datacenter-id, or --prefix."
+                .to_string(),
+        ));
+    }
+    let known = known_fields();
+    for predicate in &predicates {
+        predicate.check_field(&known)?;
+    }
+
+    let snowflake_layout = if args.preset.is_some() || args.epoch.is_some() {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    let matcher = Matcher {
+        predicates,
+        invert: args.invert_match,
+        type_hint: args.id_type,
+        detection: args.detect.options(),
+        snowflake_layout,
+    };
+
+    let mut stdout = stdout();
+    let mut matches = Vec::new();
+    let mut count = 0usize;
+    let mut keep = |id: &str, result: InspectionResult| -> Result<()> {
+        count += 1;
+        if args.count {
+            return Ok(());
+        }
+        if format.is_some() {
+            matches.push(GrepMatch {
+                id: id.to_string(),
+                id_type: result.id_type,
+                components: result.components,
+            });
+        } else {
+            writeln!(stdout, "{}", id)?;
+        }
+        Ok(())
+    };
+
+    let mut read = 0usize;
+    for sourced in stream_ids(&args.ids, &InputSource::Text)? {
+        let id = sourced?.id;
+        read += 1;
+        if let Some(result) = matcher.check(&id) {
+            keep(&id, result)?;
+        }
+    }
+
+    if read == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    match (format, args.count) {
+        (Some(fmt), true) => {
+            let output = serde_json::json!({ "count": count });
+            write_output(&mut stdout, &output, fmt, pretty)?;
+        }
+        (Some(fmt), false) => write_output(&mut stdout, &matches, fmt, pretty)?,
+        (None, true) => writeln!(stdout, "{}", count)?,
+        (None, false) => {}
+    }
+
+    Ok(())
+}
+
+struct Matcher {
+    predicates: Vec<Predicate>,
+    invert: bool,
+    type_hint: Option<IdKind>,
+    detection: DetectionOptions,
+    snowflake_layout: Option<SnowflakeLayout>,
+}
+
+impl Matcher {
+    /// The inspection of `id` if it is selected. IDs that fail to parse are
+    /// skipped with a warning, even with `--invert-match`.
+    fn check(&self, id: &str) -> Option<InspectionResult> {
+        let parsed: Result<Box<dyn ParsedId>> = match self.snowflake_layout {
+            Some(ref layout) => crate::ids::ParsedSnowflake::parse_with_layout(id, layout.clone())
+                .map(|s| Box::new(s) as Box<dyn ParsedId>),
+            None => crate::ids::parse_id_with(id, self.type_hint, &self.detection),
+        };
+
+        let result = match parsed {
+            Ok(parsed) => parsed.inspect(),
+            Err(e) => {
+                eprintln!("Warning: skipping '{}' — failed to parse: {}", id, e);
+                return None;
+            }
+        };
+
+        let matched = self.predicates.iter().all(|p| p.matches(&result));
+        (matched != self.invert).then_some(result)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct GrepMatch {
+    id: String,
+    id_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Default layout: datacenter_id 20, machine_id 24
+    const SNOWFLAKE: &str = "1234567890123456789";
+
+    fn matcher(predicates: &[&str], invert: bool) -> Matcher {
+        Matcher {
+            predicates: predicates.iter().map(|p| p.parse().unwrap()).collect(),
+            invert,
+            type_hint: None,
+            detection: DetectionOptions::default(),
+            snowflake_layout: None,
+        }
+    }
+
+    fn make_args(ids: Vec<&str>) -> GrepArgs {
+        GrepArgs {
+            ids: ids.into_iter().map(String::from).collect(),
+            id_type: None,
+            detect: Default::default(),
+            predicates: vec![],
+            machine_id: None,
+            datacenter_id: None,
+            prefix: None,
+            invert_match: false,
+            count: false,
+            epoch: None,
+            preset: None,
+        }
+    }
+
+    #[test]
+    fn test_all_predicates_must_match() {
+        assert!(
+            matcher(&["machine_id=24", "datacenter_id=20"], false)
+                .check(SNOWFLAKE)
+                .is_some()
+        );
+        assert!(
+            matcher(&["machine_id=24", "datacenter_id=3"], false)
+                .check(SNOWFLAKE)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_invert_match() {
+        assert!(matcher(&["machine_id=7"], true).check(SNOWFLAKE).is_some());
+        assert!(matcher(&["machine_id=24"], true).check(SNOWFLAKE).is_none());
+        // Unparseable input is never selected
+        assert!(
+            matcher(&["machine_id=7"], true)
+                .check("not-an-id")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_shorthand_flags() {
+        let mut args = make_args(vec![]);
+        args.machine_id = Some(7);
+        args.prefix = Some("order".to_string());
+        let predicates = args.all_predicates();
+        assert_eq!(predicates.len(), 2);
+        assert_eq!(predicates[0].field, "machine_id");
+        assert_eq!(predicates[1].value, "order");
+    }
+
+    #[test]
+    fn test_execute_requires_predicate() {
+        let args = make_args(vec![SNOWFLAKE]);
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_execute_typeid_prefix() {
+        let mut args = make_args(vec!["user_01h455vb4pex5vsknk084sn02q"]);
+        args.id_type = Some(IdKind::TypeId);
+        args.prefix = Some("user".to_string());
+        args.count = true;
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_ok());
+    }
+}
@@ -0,0 +1,30 @@
+use crate::cli::app::GrpcArgs;
+use crate::cli::output::stdout;
+use crate::core::error::Result;
+use std::io::Write;
+
+/// The service definition clients are generated from
+const PROTO: &str = include_str!("../../../proto/idt/v1/idt.proto");
+
+pub fn execute(args: &GrpcArgs) -> Result<()> {
+    if args.proto {
+        stdout().write_all(PROTO.as_bytes())?;
+        return Ok(());
+    }
+    serve(args)
+}
+
+#[cfg(feature = "grpc")]
+fn serve(args: &GrpcArgs) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(crate::cli::grpc::serve(args.listen, args.metrics))
+}
+
+#[cfg(not(feature = "grpc"))]
+fn serve(_args: &GrpcArgs) -> Result<()> {
+    Err(crate::core::error::IdtError::InvalidArgument(
+        "The gRPC server requires idt to be built with the `grpc` feature".to_string(),
+    ))
+}
@@ -0,0 +1,204 @@
+use crate::cli::app::{HashArgs, OutputFormat};
+use crate::cli::input::{InputSource, open_text, stream_ids, text_lines};
+use crate::cli::output::{stdout, write_output};
+use crate::core::EncodingFormat;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::ParsedId;
+use crate::ids::ParsedUuid;
+use crate::ids::uuid_id::{name_based_uuid, parse_namespace};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+pub fn execute(args: &HashArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let namespace = parse_namespace(&args.namespace)?;
+
+    let encoding: EncodingFormat = args
+        .format
+        .as_ref()
+        .map(|f| f.parse())
+        .transpose()?
+        .unwrap_or(EncodingFormat::Canonical);
+
+    let inputs = collect_inputs(args)?;
+
+    if inputs.is_empty() {
+        return Err(IdtError::InvalidArgument(
+            "No input provided. Pass names as arguments, --file, --names-file, or via stdin."
+                .to_string(),
+        ));
+    }
+
+    let mut results = Vec::with_capacity(inputs.len());
+    for (label, data) in &inputs {
+        let uuid = name_based_uuid(args.uuid_version, &namespace, data)?;
+        let parsed = ParsedUuid::parse(&uuid.to_string())?;
+        results.push(HashResult {
+            input: label.clone(),
+            namespace: namespace.to_string(),
+            version: args.uuid_version,
+            uuid: parsed.encode(encoding),
+        });
+    }
+
+    let mut stdout = stdout();
+
+    if let Some(fmt) = output_format {
+        if results.len() == 1 {
+            write_output(&mut stdout, &results[0], fmt, pretty)?;
+        } else {
+            write_output(&mut stdout, &results, fmt, pretty)?;
+        }
+    } else {
+        for result in &results {
+            if args.with_input {
+                writeln!(stdout, "{}\t{}", result.input, result.uuid)?;
+            } else {
+                writeln!(stdout, "{}", result.uuid)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+pub(crate) struct HashResult {
+    pub input: String,
+    pub namespace: String,
+    pub version: u8,
+    pub uuid: String,
+}
+
+/// Collect (label, bytes) pairs from names, files, or stdin lines
+fn collect_inputs(args: &HashArgs) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut inputs: Vec<(String, Vec<u8>)> = args
+        .names
+        .iter()
+        .map(|name| (name.clone(), name.as_bytes().to_vec()))
+        .collect();
+
+    for path in &args.file {
+        inputs.push((path.display().to_string(), read_file(path)?));
+    }
+
+    if let Some(ref path) = args.names_file {
+        inputs.extend(
+            read_names(path)?
+                .into_iter()
+                .map(|name| (name.clone(), name.into_bytes())),
+        );
+    }
+
+    if !inputs.is_empty() {
+        return Ok(inputs);
+    }
+
+    for sourced in stream_ids(&[], &InputSource::Text)? {
+        let name = sourced?.id;
+        inputs.push((name.clone(), name.into_bytes()));
+    }
+    Ok(inputs)
+}
+
+/// Non-empty, trimmed lines of `path` (`-` = stdin)
+pub(crate) fn read_names(path: &Path) -> Result<Vec<String>> {
+    text_lines(open_text(path)?)
+        .map(|sourced| sourced.map(|s| s.id))
+        .collect::<Result<_>>()
+        .map_err(|e| match e {
+            IdtError::IoError(e) if e.kind() == io::ErrorKind::InvalidData => {
+                IdtError::InvalidArgument(format!("{} is not valid UTF-8", path.display()))
+            }
+            e => e,
+        })
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    if path.as_os_str() == "-" {
+        let mut buf = Vec::new();
+        io::stdin().read_to_end(&mut buf)?;
+        Ok(buf)
+    } else {
+        Ok(std::fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn make_args(names: Vec<&str>) -> HashArgs {
+        HashArgs {
+            names: names.into_iter().map(String::from).collect(),
+            namespace: "dns".to_string(),
+            file: vec![],
+            names_file: None,
+            with_input: false,
+            uuid_version: 5,
+            format: None,
+        }
+    }
+
+    #[test]
+    fn test_hash_name() {
+        let args = make_args(vec!["www.example.com"]);
+        assert!(execute(&args, None, false).is_ok());
+    }
+
+    #[test]
+    fn test_hash_v3_json() {
+        let mut args = make_args(vec!["www.example.com", "example.org"]);
+        args.uuid_version = 3;
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_ok());
+    }
+
+    #[test]
+    fn test_hash_names_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("domains.txt");
+        std::fs::write(&path, "www.example.com\n\nexample.org\n").unwrap();
+        let mut args = make_args(vec![]);
+        args.names_file = Some(path);
+        let inputs = collect_inputs(&args).unwrap();
+        let names: Vec<&str> = inputs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["www.example.com", "example.org"]);
+
+        args.with_input = true;
+        assert!(execute(&args, None, false).is_ok());
+    }
+
+    #[test]
+    fn test_hash_unsupported_version() {
+        let mut args = make_args(vec!["www.example.com"]);
+        args.uuid_version = 4;
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_hash_invalid_namespace() {
+        let mut args = make_args(vec!["x"]);
+        args.namespace = "nope".to_string();
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_collect_inputs_file_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("payload.bin");
+        std::fs::write(&path, [0u8, 159, 146, 150]).unwrap();
+
+        let mut args = make_args(vec![]);
+        args.file = vec![path.clone()];
+        let inputs = collect_inputs(&args).unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].1, vec![0u8, 159, 146, 150]);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let mut args = make_args(vec![]);
+        args.file = vec![PathBuf::from("/nonexistent/idt-hash-input")];
+        assert!(execute(&args, None, false).is_err());
+    }
+}
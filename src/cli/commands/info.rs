@@ -1,9 +1,10 @@
 use crate::cli::app::{InfoArgs, OutputFormat};
-use crate::cli::output::format_output;
-use crate::core::error::Result;
+use crate::cli::output::{stdout, write_output};
+use crate::core::error::{IdtError, Result};
 use crate::core::id::IdKind;
+use crate::ids::{WELL_KNOWN_UUIDS, WellKnownUuid};
 use colored::Colorize;
-use std::io::{self, Write};
+use std::io::Write;
 
 pub fn execute(
     args: &InfoArgs,
@@ -11,9 +12,16 @@ pub fn execute(
     pretty: bool,
     no_color: bool,
 ) -> Result<()> {
-    let mut stdout = io::stdout();
+    let mut stdout = stdout();
 
-    if let Some(kind) = args.id_type {
+    if args.well_known {
+        if !args.id_type.is_some_and(|kind| kind.is_uuid()) {
+            return Err(IdtError::InvalidArgument(
+                "--well-known lists UUIDs. Use 'idt info uuid --well-known'.".to_string(),
+            ));
+        }
+        list_well_known(&mut stdout, format, pretty, no_color)?;
+    } else if let Some(kind) = args.id_type {
         // Show detailed info about specific type
         show_type_detail(&mut stdout, kind, format, pretty, no_color)?;
     } else {
@@ -42,7 +50,7 @@ fn list_all_types(
             })
             .collect();
 
-        writeln!(writer, "{}", format_output(&types, fmt, pretty)?)?;
+        write_output(writer, &types, fmt, pretty)?;
     } else {
         let title = if no_color {
             "Supported ID Types".to_string()
@@ -126,12 +134,12 @@ fn show_type_detail(
         is_sortable: kind.is_sortable(),
         bit_length: kind.bit_length(),
         example: generate_example(kind)?,
-        spec_url: get_spec_url(kind),
+        spec_url: kind.spec_url().map(String::from),
         notes: get_notes(kind),
     };
 
     if let Some(fmt) = format {
-        writeln!(writer, "{}", format_output(&info, fmt, pretty)?)?;
+        write_output(writer, &info, fmt, pretty)?;
     } else {
         print_type_detail(writer, &info, no_color)?;
     }
@@ -139,6 +147,41 @@ fn show_type_detail(
     Ok(())
 }
 
+fn list_well_known(
+    writer: &mut dyn Write,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    if let Some(fmt) = format {
+        write_output(writer, &WELL_KNOWN_UUIDS, fmt, pretty)?;
+        return Ok(());
+    }
+
+    let mut category = "";
+    for &WellKnownUuid {
+        uuid,
+        name,
+        category: this,
+    } in WELL_KNOWN_UUIDS
+    {
+        if this != category {
+            if !category.is_empty() {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "{}:", format_category(this, no_color))?;
+            category = this;
+        }
+        let uuid = if no_color {
+            uuid.to_string()
+        } else {
+            uuid.cyan().to_string()
+        };
+        writeln!(writer, "  {}  {}", uuid, name)?;
+    }
+    Ok(())
+}
+
 fn print_type_summary(writer: &mut dyn Write, kind: IdKind, no_color: bool) -> Result<()> {
     let name = if no_color {
         format!("{:12}", kind.name())
@@ -250,31 +293,6 @@ fn generate_example(kind: IdKind) -> Result<String> {
     generator.generate()
 }
 
-fn get_spec_url(kind: IdKind) -> Option<String> {
-    match kind {
-        IdKind::Uuid | IdKind::UuidV1 | IdKind::UuidV3 | IdKind::UuidV4 | IdKind::UuidV5 => {
-            Some("https://datatracker.ietf.org/doc/html/rfc4122".to_string())
-        }
-        IdKind::UuidV6 | IdKind::UuidV7 => {
-            Some("https://datatracker.ietf.org/doc/html/rfc9562".to_string())
-        }
-        IdKind::Ulid => Some("https://github.com/ulid/spec".to_string()),
-        IdKind::Snowflake => Some("https://en.wikipedia.org/wiki/Snowflake_ID".to_string()),
-        IdKind::NanoId => Some("https://github.com/ai/nanoid".to_string()),
-        IdKind::Ksuid => Some("https://github.com/segmentio/ksuid".to_string()),
-        IdKind::ObjectId => {
-            Some("https://www.mongodb.com/docs/manual/reference/method/objectid/".to_string())
-        }
-        IdKind::TypeId => Some("https://github.com/jetify-com/typeid".to_string()),
-        IdKind::Xid => Some("https://github.com/rs/xid".to_string()),
-        IdKind::Cuid => Some("https://github.com/paralleldrive/cuid".to_string()),
-        IdKind::Cuid2 => Some("https://github.com/paralleldrive/cuid2".to_string()),
-        IdKind::Tsid => Some("https://github.com/f4b6a3/tsid-creator".to_string()),
-        IdKind::ShortUuid => Some("https://github.com/skorokithakis/shortuuid".to_string()),
-        _ => None,
-    }
-}
-
 fn get_notes(kind: IdKind) -> Vec<String> {
     match kind {
         IdKind::UuidV4 => vec![
@@ -376,28 +394,40 @@ mod tests {
 
     #[test]
     fn test_list_all_types_human() {
-        let args = InfoArgs { id_type: None };
+        let args = InfoArgs {
+            id_type: None,
+            well_known: false,
+        };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_list_all_types_json() {
-        let args = InfoArgs { id_type: None };
+        let args = InfoArgs {
+            id_type: None,
+            well_known: false,
+        };
         let result = execute(&args, Some(OutputFormat::Json), false, true);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_list_all_types_json_pretty() {
-        let args = InfoArgs { id_type: None };
+        let args = InfoArgs {
+            id_type: None,
+            well_known: false,
+        };
         let result = execute(&args, Some(OutputFormat::Json), true, true);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_list_all_types_yaml() {
-        let args = InfoArgs { id_type: None };
+        let args = InfoArgs {
+            id_type: None,
+            well_known: false,
+        };
         let result = execute(&args, Some(OutputFormat::Yaml), false, true);
         assert!(result.is_ok());
     }
@@ -406,15 +436,33 @@ mod tests {
     fn test_show_detail_uuid_v4() {
         let args = InfoArgs {
             id_type: Some(IdKind::UuidV4),
+            well_known: false,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_well_known() {
+        let args = InfoArgs {
+            id_type: Some(IdKind::Uuid),
+            well_known: true,
+        };
+        assert!(execute(&args, None, false, true).is_ok());
+        assert!(execute(&args, Some(OutputFormat::Json), false, true).is_ok());
+
+        let args = InfoArgs {
+            id_type: Some(IdKind::Ulid),
+            well_known: true,
+        };
+        assert!(execute(&args, None, false, true).is_err());
+    }
+
     #[test]
     fn test_show_detail_uuid_v7() {
         let args = InfoArgs {
             id_type: Some(IdKind::UuidV7),
+            well_known: false,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
@@ -424,6 +472,7 @@ mod tests {
     fn test_show_detail_ulid() {
         let args = InfoArgs {
             id_type: Some(IdKind::Ulid),
+            well_known: false,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
@@ -433,6 +482,7 @@ mod tests {
     fn test_show_detail_snowflake() {
         let args = InfoArgs {
             id_type: Some(IdKind::Snowflake),
+            well_known: false,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
@@ -442,6 +492,7 @@ mod tests {
     fn test_show_detail_json() {
         let args = InfoArgs {
             id_type: Some(IdKind::UuidV4),
+            well_known: false,
         };
         let result = execute(&args, Some(OutputFormat::Json), false, true);
         assert!(result.is_ok());
@@ -461,6 +512,7 @@ mod tests {
         ] {
             let args = InfoArgs {
                 id_type: Some(*kind),
+                well_known: false,
             };
             let result = execute(&args, None, false, true);
             assert!(result.is_ok(), "info failed for {:?}", kind);
@@ -471,6 +523,7 @@ mod tests {
     fn test_show_detail_with_color() {
         let args = InfoArgs {
             id_type: Some(IdKind::UuidV4),
+            well_known: false,
         };
         let result = execute(&args, None, false, false);
         assert!(result.is_ok());
@@ -478,20 +531,14 @@ mod tests {
 
     #[test]
     fn test_list_all_types_with_color() {
-        let args = InfoArgs { id_type: None };
+        let args = InfoArgs {
+            id_type: None,
+            well_known: false,
+        };
         let result = execute(&args, None, false, false);
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_get_spec_url() {
-        assert!(get_spec_url(IdKind::UuidV4).is_some());
-        assert!(get_spec_url(IdKind::UuidV7).is_some());
-        assert!(get_spec_url(IdKind::Ulid).is_some());
-        assert!(get_spec_url(IdKind::Snowflake).is_some());
-        assert!(get_spec_url(IdKind::UuidNil).is_none());
-    }
-
     #[test]
     fn test_get_notes() {
         assert!(!get_notes(IdKind::UuidV4).is_empty());
@@ -1,10 +1,15 @@
 use crate::cli::app::{InspectArgs, OutputFormat};
-use crate::cli::output::format_output;
+use crate::cli::input::{BinaryLayout, IdStream, SourcedId, stream_ids, stream_total};
+use crate::cli::output::{ErrorEntry, stdout, write_output, write_rows};
+use crate::cli::progress::Progress;
 use crate::core::error::{IdtError, Result};
-use crate::core::id::{IdKind, InspectionResult, ParsedId};
+use crate::core::id::{IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment};
+use crate::core::time::humanize_age;
 use crate::ids::snowflake_id::SnowflakeLayout;
+use chrono::Utc;
+use chrono_tz::Tz;
 use colored::Colorize;
-use std::io::{self, BufRead, Write};
+use std::io::{self, Read, Write};
 
 pub fn execute(
     args: &InspectArgs,
@@ -12,15 +17,31 @@ pub fn execute(
     pretty: bool,
     no_color: bool,
 ) -> Result<()> {
-    let ids = collect_ids(&args.ids)?;
-
-    if ids.is_empty() {
+    args.records.check_plain(format)?;
+    if args.xxd && format.is_some() {
         return Err(IdtError::InvalidArgument(
-            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+            "--xxd cannot be used with structured output formats".to_string(),
+        ));
+    }
+    let table = format == Some(OutputFormat::Table);
+    if !args.columns.is_empty() && !table {
+        return Err(IdtError::InvalidArgument(
+            "--columns only applies to --output table".to_string(),
         ));
     }
 
-    let type_hint: Option<IdKind> = args.id_type;
+    let mut type_hint: Option<IdKind> = args.id_type;
+    let source = args.input.source()?;
+    let ids: IdStream<'_> = if args.binary {
+        let layout = BinaryLayout::resolve(args.id_type, args.stride)?;
+        type_hint = Some(layout.kind);
+        let ids = collect_binary_ids(&args.ids, &layout)?;
+        Box::new(ids.into_iter().map(|id| Ok(SourcedId { id, row: None })))
+    } else {
+        stream_ids(&args.ids, &source)?
+    };
+
+    let detection = args.detect.options();
     let has_snowflake_opts = args.preset.is_some() || args.epoch.is_some();
     let snowflake_layout = if has_snowflake_opts {
         Some(SnowflakeLayout::resolve(
@@ -31,114 +52,341 @@ pub fn execute(
         None
     };
 
-    let mut results = Vec::new();
+    // Text output is written as each ID is inspected; structured output is
+    // one document, so it is gathered first
+    let mut stdout = stdout();
+    let mut progress = Progress::new("inspect", stream_total(&ids), args.quiet);
+    let now_ms = Utc::now().timestamp_millis();
+    let mut entries = Vec::new();
     let mut failed_ids = Vec::new();
-
-    for id in &ids {
-        let parse_result: Result<Box<dyn ParsedId>> = if let Some(ref layout) = snowflake_layout {
-            crate::ids::ParsedSnowflake::parse_with_layout(id, layout.clone())
+    let mut count = 0usize;
+    let mut shown = 0usize;
+    // Reports are separated by a blank line, unless --print0 or
+    // --output-delimiter asks for a terminator after each
+    let terminator = args.records.terminator();
+    let blank_line = terminator == "\n";
+
+    for sourced in ids {
+        let id = sourced?.id;
+        count += 1;
+        progress.tick(id.len() + 1);
+        let url = crate::core::url::extract_id(&id);
+        let parse_result: Result<Box<dyn ParsedId>> = if let Some(order) = args.uuid_legacy {
+            crate::ids::decode_legacy_uuid(&id, order)
+                .and_then(|uuid| crate::ids::ParsedUuid::parse(&uuid.to_string()))
+                .map(|u| Box::new(u) as Box<dyn ParsedId>)
+        } else if let Some(ref layout) = snowflake_layout {
+            let text = url.as_ref().map_or(id.as_str(), |url| url.id);
+            crate::ids::ParsedSnowflake::parse_with_layout(text, layout.clone())
                 .map(|s| Box::new(s) as Box<dyn ParsedId>)
+        } else if let Some(ref url) = url {
+            crate::ids::parse_url_id(url, type_hint, &detection)
         } else {
-            crate::ids::parse_id(id, type_hint)
+            crate::ids::parse_id_or_url(&id, type_hint, &detection)
         };
 
         match parse_result {
             Ok(parsed) => {
                 let mut inspection = parsed.inspect();
+                if let Some(order) = args.uuid_legacy {
+                    inspection.input = id.clone();
+                    inspection.source = Some(format!("uuid-legacy-{}", order.name()));
+                } else if let Some(ref url) = url {
+                    inspection.input = id.clone();
+                    inspection.source = Some(url.source.to_string());
+                }
                 if let Some(ref ts) = inspection.timestamp {
-                    inspection.timestamp_local_iso = Some(ts.to_local_iso8601());
+                    inspection.timestamp_local_iso = Some(match args.tz {
+                        Some(tz) => ts.to_tz_iso8601(tz),
+                        None => ts.to_local_iso8601(),
+                    });
+                    let age_ms = now_ms - ts.millis as i64;
+                    inspection.age_ms = Some(age_ms);
+                    inspection.age_human = Some(humanize_age(age_ms));
+                }
+                if args.quiet {
+                    continue;
+                }
+                if format.is_some() {
+                    entries.push(InspectEntry::Ok(Box::new(inspection)));
+                    continue;
+                }
+                if shown > 0 && blank_line {
+                    writeln!(stdout)?;
+                }
+                shown += 1;
+                if args.xxd {
+                    print_xxd(&mut stdout, &inspection, no_color)?;
+                } else {
+                    print_inspection(&mut stdout, &inspection, args.tz, no_color)?;
+                }
+                if !blank_line {
+                    write!(stdout, "{}", terminator)?;
                 }
-                results.push(inspection);
             }
             Err(e) => {
-                failed_ids.push(id.clone());
-                if !args.quiet {
+                if format.is_some() && !table {
+                    // Structured output carries the error in place of the result
+                    entries.push(InspectEntry::Err(ErrorEntry::new(&id, &e)));
+                } else if !args.quiet {
+                    progress.clear();
                     eprintln!("Error parsing '{}': {}", id, e);
+                    if let Some(caret) = e.caret() {
+                        eprintln!("{}", caret);
+                    }
                 }
+                failed_ids.push(id);
             }
         }
     }
 
+    progress.clear();
+
+    if count == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
     if args.quiet {
         // In quiet mode, just return success/failure
         if !failed_ids.is_empty() {
-            return Err(crate::core::error::IdtError::ValidationError(format!(
+            return Err(crate::core::error::IdtError::CheckFailed(format!(
                 "Failed to parse {} of {} IDs: {}",
                 failed_ids.len(),
-                ids.len(),
+                count,
                 failed_ids.join(", ")
             )));
         }
         return Ok(());
     }
 
-    // Output results
-    let mut stdout = io::stdout();
+    if table {
+        let inspections: Vec<&InspectionResult> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                InspectEntry::Ok(inspection) => Some(&**inspection),
+                InspectEntry::Err(_) => None,
+            })
+            .collect();
+        return write_columns(&mut stdout, &inspections, &args.columns);
+    }
 
     if let Some(fmt) = format {
-        let output = if results.len() == 1 {
-            format_output(&results[0], fmt, pretty)?
+        if entries.len() == 1 {
+            write_output(&mut stdout, &entries[0], fmt, pretty)?;
         } else {
-            format_output(&results, fmt, pretty)?
-        };
-        writeln!(stdout, "{}", output)?;
-    } else {
-        output_human(&mut stdout, &results, no_color)?;
+            write_output(&mut stdout, &entries, fmt, pretty)?;
+        }
     }
 
     Ok(())
 }
 
-fn collect_ids(args: &[String]) -> Result<Vec<String>> {
-    if !args.is_empty() {
-        return Ok(args.to_vec());
+/// One entry per input in structured output, in input order
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum InspectEntry {
+    Ok(Box<InspectionResult>),
+    Err(ErrorEntry),
+}
+
+/// What `--output table` shows without `--columns`
+const DEFAULT_COLUMNS: [&str; 4] = ["type", "canonical", "timestamp", "version"];
+
+/// Columns every ID has; anything else names a decoded component
+const COLUMNS: [&str; 15] = [
+    "input",
+    "type",
+    "canonical",
+    "timestamp",
+    "local",
+    "age",
+    "version",
+    "variant",
+    "random_bits",
+    "source",
+    "hex",
+    "base32",
+    "base58",
+    "base64",
+    "int",
+];
+
+/// One row per inspected ID, in `columns` or the default ones
+fn write_columns(
+    writer: &mut dyn Write,
+    inspections: &[&InspectionResult],
+    columns: &[String],
+) -> Result<()> {
+    let columns: Vec<&str> = if columns.is_empty() {
+        DEFAULT_COLUMNS.to_vec()
+    } else {
+        columns.iter().map(|c| c.trim()).collect()
+    };
+    // A component name is only known once some ID has it
+    for column in &columns {
+        let known = COLUMNS.contains(column)
+            || inspections
+                .iter()
+                .any(|inspection| component(inspection, column).is_some());
+        if !known && !inspections.is_empty() {
+            return Err(IdtError::InvalidArgument(format!(
+                "Unknown column '{}'. Available: {}, or a component such as machine_id",
+                column,
+                COLUMNS.join(", ")
+            )));
+        }
+    }
+    let rows: Vec<Vec<String>> = inspections
+        .iter()
+        .map(|inspection| {
+            columns
+                .iter()
+                .map(|column| column_value(inspection, column).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    write_rows(writer, &columns, &rows, OutputFormat::Table)
+}
+
+fn column_value(inspection: &InspectionResult, column: &str) -> Option<String> {
+    let text = |value: &Option<String>| value.clone();
+    match column {
+        "input" => Some(inspection.input.clone()),
+        "type" => Some(inspection.id_type.clone()),
+        "canonical" => Some(inspection.canonical.clone()),
+        "timestamp" => text(&inspection.timestamp_iso),
+        "local" => text(&inspection.timestamp_local_iso),
+        "age" => text(&inspection.age_human),
+        "version" => text(&inspection.version),
+        "variant" => text(&inspection.variant),
+        "random_bits" => inspection.random_bits.map(|bits| bits.to_string()),
+        "source" => text(&inspection.source),
+        "hex" => Some(inspection.encodings.hex.clone()),
+        "base32" => Some(inspection.encodings.base32.clone()),
+        "base58" => Some(inspection.encodings.base58.clone()),
+        "base64" => Some(inspection.encodings.base64.clone()),
+        "int" => text(&inspection.encodings.int),
+        _ => component(inspection, column),
     }
+}
 
-    // Don't block on stdin if it's a terminal (no piped input)
-    if std::io::IsTerminal::is_terminal(&io::stdin()) {
-        return Ok(Vec::new());
+/// A decoded component by name, as text
+fn component(inspection: &InspectionResult, name: &str) -> Option<String> {
+    match inspection.components.as_ref()?.get(name)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
     }
+}
 
-    // Read from stdin
-    let stdin = io::stdin();
+/// Decode raw records from the files in `paths`, or from stdin
+fn collect_binary_ids(paths: &[String], layout: &BinaryLayout) -> Result<Vec<String>> {
     let mut ids = Vec::new();
+    let mut decode = |name: &str, data: Vec<u8>| {
+        let trailing = data.len() % layout.stride;
+        if trailing > 0 {
+            eprintln!(
+                "Warning: ignoring {} trailing bytes in {} (not a whole {}-byte record)",
+                trailing, name, layout.stride
+            );
+        }
+        ids.extend(layout.decode(&data));
+    };
 
-    for line in stdin.lock().lines() {
-        let line = line?;
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            ids.push(trimmed.to_string());
+    if paths.is_empty() {
+        // Don't block on stdin if it's a terminal (no piped input)
+        if std::io::IsTerminal::is_terminal(&io::stdin()) {
+            return Ok(ids);
+        }
+        let mut data = Vec::new();
+        io::stdin().lock().read_to_end(&mut data)?;
+        decode("stdin", data);
+    } else {
+        for path in paths {
+            decode(path, std::fs::read(path)?);
         }
     }
-
     Ok(ids)
 }
 
-fn output_human(
-    writer: &mut dyn Write,
-    results: &[InspectionResult],
-    no_color: bool,
-) -> Result<()> {
-    for (i, result) in results.iter().enumerate() {
-        if i > 0 {
-            writeln!(writer)?;
-        }
-        print_inspection(writer, result, no_color)?;
+/// One row per byte: offset, hex, bits, ASCII, and the fields the byte holds
+fn print_xxd(writer: &mut dyn Write, result: &InspectionResult, no_color: bool) -> Result<()> {
+    let bytes = hex::decode(&result.encodings.hex).unwrap_or_default();
+    let fields = byte_fields(bytes.len(), result.structure.as_deref().unwrap_or(&[]));
+
+    writeln!(writer, "{} ({})", result.canonical, result.id_type)?;
+    for (offset, byte) in bytes.iter().enumerate() {
+        let ascii = if byte.is_ascii_graphic() {
+            *byte as char
+        } else {
+            '.'
+        };
+        let names = fields[offset].join(", ");
+        let names = if no_color {
+            names
+        } else {
+            names.cyan().to_string()
+        };
+        let row = format!(
+            "{:08x}: {:02x}  {:08b}  {}  {}",
+            offset, byte, byte, ascii, names
+        );
+        writeln!(writer, "{}", row.trim_end())?;
     }
     Ok(())
 }
 
+/// The fields covering each of `len` bytes, e.g. `ver` or `random_b[11:8]`
+/// for a byte holding part of a field. Only bit-sized structures map onto
+/// bytes; they are aligned to the last byte, so a 63-bit Snowflake layout
+/// leaves the top bit unnamed.
+fn byte_fields(len: usize, structure: &[StructureSegment]) -> Vec<Vec<String>> {
+    let mut fields = vec![Vec::new(); len];
+    let total_bits = len * 8;
+    let used: usize = structure.iter().map(|s| s.size as usize).sum();
+    if structure.iter().any(|s| !matches!(s.unit, SizeUnit::Bits)) || used > total_bits {
+        return fields;
+    }
+
+    let mut start = total_bits - used;
+    for segment in structure {
+        let end = start + segment.size as usize;
+        for (byte, names) in fields.iter_mut().enumerate() {
+            let lo = start.max(byte * 8);
+            let hi = end.min(byte * 8 + 8);
+            if lo >= hi {
+                continue;
+            }
+            // Field bits are numbered from its most significant bit down to 0
+            let (top, bottom) = (end - 1 - lo, end - hi);
+            names.push(match (lo == start && hi == end, top == bottom) {
+                (true, _) => segment.name.clone(),
+                (false, true) => format!("{}[{}]", segment.name, top),
+                (false, false) => format!("{}[{}:{}]", segment.name, top, bottom),
+            });
+        }
+        start = end;
+    }
+    fields
+}
+
 fn print_inspection(
     writer: &mut dyn Write,
     result: &InspectionResult,
+    tz: Option<Tz>,
     no_color: bool,
 ) -> Result<()> {
+    let zone_abbr = result.timestamp.map(|ts| match tz {
+        Some(tz) => ts.timezone_abbr(tz),
+        None => ts.local_timezone_abbr(),
+    });
+
     // Compute label width based on longest label present
-    let label_width = if let Some(ref ts) = result.timestamp {
-        let local_label = format!("Local Time ({})", ts.local_timezone_abbr());
-        local_label.len().max(12)
-    } else {
-        12
+    let label_width = match zone_abbr {
+        Some(ref abbr) => format!("Local Time ({})", abbr).len().max(12),
+        None => 12,
     };
 
     // Helper for coloring
@@ -178,16 +426,17 @@ fn print_inspection(
             writeln!(writer, "  {} {}", label("Time (UTC)"), iso)?;
         }
 
-        if let Some(ref ts) = result.timestamp {
-            let abbr = ts.local_timezone_abbr();
-            if let Some(ref local_iso) = result.timestamp_local_iso {
-                writeln!(
-                    writer,
-                    "  {} {}",
-                    label(&format!("Local Time ({})", abbr)),
-                    local_iso
-                )?;
-            }
+        if let (Some(abbr), Some(local_iso)) = (&zone_abbr, &result.timestamp_local_iso) {
+            writeln!(
+                writer,
+                "  {} {}",
+                label(&format!("Local Time ({})", abbr)),
+                local_iso
+            )?;
+        }
+
+        if let Some(ref age) = result.age_human {
+            writeln!(writer, "  {} {}", label("Age"), age)?;
         }
 
         if let Some(ref version) = result.version {
@@ -198,9 +447,42 @@ fn print_inspection(
             writeln!(writer, "  {} {}", label("Variant"), variant)?;
         }
 
+        if let Some(known) = result.components.as_ref().and_then(|c| c.get("well_known")) {
+            writeln!(
+                writer,
+                "  {} {} ({})",
+                label("Well-known"),
+                known["name"].as_str().unwrap_or_default(),
+                known["category"].as_str().unwrap_or_default()
+            )?;
+        }
+
+        if let Some(short) = result.components.as_ref().and_then(|c| c.get("bluetooth")) {
+            writeln!(
+                writer,
+                "  {} {} ({}-bit assigned number)",
+                label("Bluetooth"),
+                short["short"].as_str().unwrap_or_default(),
+                short["bits"]
+            )?;
+        }
+
+        if let Some(ref source) = result.source {
+            writeln!(writer, "  {} {}", label("Source"), source)?;
+        }
+
         if let Some(bits) = result.random_bits {
             writeln!(writer, "  {} {} bits", label("Random"), bits)?;
         }
+
+        if let Some(note) = result
+            .components
+            .as_ref()
+            .and_then(|c| c.get("note"))
+            .and_then(|n| n.as_str())
+        {
+            writeln!(writer, "  {} {}", label("Note"), note)?;
+        }
     }
 
     // Structure (if available)
@@ -308,8 +590,17 @@ mod tests {
         InspectArgs {
             ids: ids.into_iter().map(String::from).collect(),
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            binary: false,
+            stride: None,
             epoch: None,
             preset: None,
+            tz: None,
+            xxd: false,
+            columns: vec![],
+            uuid_legacy: None,
             quiet: false,
         }
     }
@@ -321,6 +612,66 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_inspect_binary_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ids.bin");
+        let uuid = uuid::Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        std::fs::write(&path, uuid.as_bytes().repeat(3)).unwrap();
+
+        let layout = BinaryLayout::resolve(None, None).unwrap();
+        let ids = collect_binary_ids(&[path.display().to_string()], &layout).unwrap();
+        assert_eq!(ids, vec![uuid.to_string(); 3]);
+
+        let mut args = make_args(vec![path.to_str().unwrap()]);
+        args.binary = true;
+        assert!(execute(&args, Some(OutputFormat::Json), false, false).is_ok());
+        args.stride = Some(24);
+        assert!(execute(&args, None, false, false).is_err());
+    }
+
+    #[test]
+    fn test_byte_fields() {
+        let parsed = crate::ids::parse_id("550e8400-e29b-41d4-a716-446655440000", None).unwrap();
+        let inspection = parsed.inspect();
+        let fields = byte_fields(16, inspection.structure.as_deref().unwrap());
+        assert_eq!(fields[0], ["random_a[47:40]"]);
+        assert_eq!(fields[6], ["ver", "random_b[11:8]"]);
+        assert_eq!(fields[8], ["var", "random_c[61:56]"]);
+        assert_eq!(fields[15], ["random_c[7:0]"]);
+
+        // 63 bits in 8 bytes: the sign bit is unnamed
+        let parsed = crate::ids::parse_id("1234567890123456789", Some(IdKind::Snowflake)).unwrap();
+        let inspection = parsed.inspect();
+        let fields = byte_fields(8, inspection.structure.as_deref().unwrap());
+        assert_eq!(fields[0], ["timestamp[40:34]"]);
+        assert_eq!(fields[7], ["sequence[7:0]"]);
+    }
+
+    #[test]
+    fn test_inspect_binary_output_formats() {
+        let args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]);
+        assert!(execute(&args, Some(OutputFormat::Cbor), false, true).is_ok());
+        assert!(execute(&args, Some(OutputFormat::Msgpack), false, true).is_ok());
+    }
+
+    #[test]
+    fn test_xxd_output() {
+        let parsed = crate::ids::parse_id("01ARZ3NDEKTSV4RRFFQ69G5FAV", None).unwrap();
+        let mut out = Vec::new();
+        print_xxd(&mut out, &parsed.inspect(), true).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "01ARZ3NDEKTSV4RRFFQ69G5FAV (ulid)");
+        assert_eq!(lines[1], "00000000: 01  00000001  .  Timestamp[47:40]");
+        assert_eq!(lines[3], "00000002: 3e  00111110  >  Timestamp[31:24]");
+        assert_eq!(lines.len(), 17);
+
+        let mut args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]);
+        args.xxd = true;
+        assert!(execute(&args, Some(OutputFormat::Json), false, true).is_err());
+    }
+
     #[test]
     fn test_inspect_uuid_v4() {
         let args = make_args(vec!["550e8400-e29b-41d4-a716-446655440000"]);
@@ -374,8 +725,17 @@ mod tests {
         let args = InspectArgs {
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: Some(IdKind::Uuid),
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            binary: false,
+            stride: None,
             epoch: None,
             preset: None,
+            tz: None,
+            xxd: false,
+            columns: vec![],
+            uuid_legacy: None,
             quiet: false,
         };
         let result = execute(&args, None, false, true);
@@ -387,8 +747,17 @@ mod tests {
         let args = InspectArgs {
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            binary: false,
+            stride: None,
             epoch: None,
             preset: None,
+            tz: None,
+            xxd: false,
+            columns: vec![],
+            uuid_legacy: None,
             quiet: true,
         };
         let result = execute(&args, None, false, true);
@@ -400,14 +769,37 @@ mod tests {
         let args = InspectArgs {
             ids: vec!["invalid-id-string".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            binary: false,
+            stride: None,
             epoch: None,
             preset: None,
+            tz: None,
+            xxd: false,
+            columns: vec![],
+            uuid_legacy: None,
             quiet: true,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_inspect_json_keeps_errors() {
+        let args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV", "invalid-id-string"]);
+        assert!(execute(&args, Some(OutputFormat::Json), false, true).is_ok());
+
+        let entry = InspectEntry::Err(ErrorEntry::new(
+            "invalid-id-string",
+            &IdtError::DetectionFailed,
+        ));
+        let value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(value["code"], "detection_failed");
+        assert_eq!(value["input"], "invalid-id-string");
+    }
+
     #[test]
     fn test_inspect_invalid_id_non_quiet() {
         let args = make_args(vec!["invalid-id-string"]);
@@ -422,16 +814,87 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_inspect_with_tz() {
+        let mut args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]);
+        args.tz = Some(chrono_tz::Asia::Seoul);
+        let result = execute(&args, None, false, true);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_inspect_with_snowflake_preset() {
         let args = InspectArgs {
             ids: vec!["1234567890123456789".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
+            records: Default::default(),
+            binary: false,
+            stride: None,
             epoch: None,
             preset: Some("twitter".to_string()),
+            tz: None,
+            xxd: false,
+            columns: vec![],
+            uuid_legacy: None,
             quiet: false,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_inspect_url() {
+        let args = make_args(vec![
+            "https://discord.com/channels/81384788765712384/381870553235193857/1143646939598774322",
+        ]);
+        assert!(execute(&args, Some(OutputFormat::Json), false, true).is_ok());
+
+        let mut args = make_args(vec!["https://example.com/about"]);
+        args.quiet = true;
+        assert!(execute(&args, None, false, true).is_err());
+    }
+
+    #[test]
+    fn test_write_columns() {
+        let snowflake = crate::ids::parse_id("1234567890123456789", Some(IdKind::Snowflake))
+            .unwrap()
+            .inspect();
+        let ulid = crate::ids::parse_id("01ARZ3NDEKTSV4RRFFQ69G5FAV", None)
+            .unwrap()
+            .inspect();
+        let columns = ["type", "machine_id", "timestamp"].map(String::from);
+        let mut out = Vec::new();
+        write_columns(&mut out, &[&snowflake, &ulid], &columns).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "type       machine_id  timestamp");
+        assert!(lines[1].starts_with("snowflake  24          "));
+        assert_eq!(lines[2], "ulid       -           2016-07-30T23:54:10.259Z");
+
+        let columns = ["type", "nope"].map(String::from);
+        assert!(write_columns(&mut Vec::new(), &[&ulid], &columns).is_err());
+    }
+
+    #[test]
+    fn test_columns_need_table() {
+        let mut args = make_args(vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]);
+        args.columns = vec!["type".to_string()];
+        assert!(execute(&args, Some(OutputFormat::Table), false, true).is_ok());
+        assert!(execute(&args, None, false, true).is_err());
+    }
+
+    #[test]
+    fn test_negative_id_argument() {
+        use crate::cli::{Cli, Commands};
+        use clap::Parser;
+
+        let cli = Cli::try_parse_from(["idt", "inspect", "-5403194877577133845"]).unwrap();
+        let Commands::Inspect(args) = cli.command else {
+            panic!("expected inspect");
+        };
+        assert_eq!(args.ids, ["-5403194877577133845"]);
+        assert!(execute(&args, None, false, true).is_ok());
+    }
 }
@@ -1,10 +1,10 @@
 use crate::cli::Cli;
 use crate::cli::app::ManpageArgs;
+use crate::cli::output::stdout;
 use crate::core::error::Result;
 use clap::CommandFactory;
 use clap_mangen::Man;
 use std::fs;
-use std::io;
 
 pub fn execute(args: &ManpageArgs) -> Result<()> {
     let cmd = Cli::command();
@@ -12,7 +12,7 @@ pub fn execute(args: &ManpageArgs) -> Result<()> {
     match &args.dir {
         None => {
             let man = Man::new(cmd);
-            man.render(&mut io::stdout())?;
+            man.render(&mut stdout())?;
         }
         Some(dir) => {
             fs::create_dir_all(dir)?;
@@ -22,9 +22,10 @@ pub fn execute(args: &ManpageArgs) -> Result<()> {
             let mut file = fs::File::create(path)?;
             man.render(&mut file)?;
 
-            for subcommand in cmd.get_subcommands() {
+            for subcommand in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+                // Named as `man` looks them up, e.g. `man idt-gen`
                 let name = format!("idt-{}", subcommand.get_name());
-                let man = Man::new(subcommand.clone());
+                let man = Man::new(subcommand.clone().name(name.clone()));
                 let path = dir.join(format!("{name}.1"));
                 let mut file = fs::File::create(path)?;
                 man.render(&mut file)?;
@@ -34,3 +35,24 @@ pub fn execute(args: &ManpageArgs) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_a_page_per_subcommand() {
+        let dir = tempfile::tempdir().unwrap();
+        execute(&ManpageArgs {
+            dir: Some(dir.path().to_path_buf()),
+        })
+        .unwrap();
+
+        let top = fs::read_to_string(dir.path().join("idt.1")).unwrap();
+        assert!(top.starts_with(".ie"), "not roff: {}", &top[..40]);
+        let generate = fs::read_to_string(dir.path().join("idt-gen.1")).unwrap();
+        assert!(generate.contains(".TH idt-gen"));
+        assert!(generate.contains("\\-\\-count"));
+        assert!(!dir.path().join("idt-man.1").exists());
+    }
+}
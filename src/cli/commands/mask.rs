@@ -0,0 +1,166 @@
+use crate::cli::app::{MaskArgs, OutputFormat};
+use crate::cli::input::{InputSource, stream_ids};
+use crate::cli::output::{stdout, write_output};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId};
+use crate::core::mask::Masker;
+use crate::ids::snowflake_id::SnowflakeLayout;
+use std::io::Write;
+
+pub fn execute(args: &MaskArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let ids = stream_ids(&args.ids, &InputSource::Text)?
+        .map(|sourced| sourced.map(|s| s.id))
+        .collect::<Result<Vec<_>>>()?;
+
+    if ids.is_empty() {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    if args.key.is_empty() {
+        return Err(IdtError::InvalidArgument(
+            "Mask key must not be empty".to_string(),
+        ));
+    }
+
+    let type_hint: Option<IdKind> = args.id_type;
+    let detection = args.detect.options();
+    let has_snowflake_opts = args.preset.is_some() || args.epoch.is_some();
+    let snowflake_layout = if has_snowflake_opts {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    let mut masker = Masker::new(args.key.as_bytes());
+    if let Some(granularity) = args.keep_timestamp {
+        masker = masker.with_keep_timestamp(granularity);
+    }
+    if let Some(ref layout) = snowflake_layout {
+        masker = masker.with_snowflake_layout(layout.clone());
+    }
+
+    let mut results = Vec::new();
+    let mut failed = 0usize;
+
+    for id in &ids {
+        let parse_result: Result<Box<dyn ParsedId>> = if let Some(ref layout) = snowflake_layout {
+            crate::ids::ParsedSnowflake::parse_with_layout(id, layout.clone())
+                .map(|s| Box::new(s) as Box<dyn ParsedId>)
+        } else {
+            crate::ids::parse_id_with(id, type_hint, &detection)
+        };
+
+        match parse_result.and_then(|parsed| {
+            let masked = masker.mask(parsed.as_ref())?;
+            Ok((parsed.kind(), masked))
+        }) {
+            Ok((kind, masked)) => results.push(MaskResult {
+                input: id.clone(),
+                masked,
+                id_type: kind.name().to_string(),
+            }),
+            Err(e) => {
+                failed += 1;
+                eprintln!("Error masking '{}': {}", id, e);
+            }
+        }
+    }
+
+    let mut stdout = stdout();
+
+    if let Some(fmt) = output_format {
+        if results.len() == 1 {
+            write_output(&mut stdout, &results[0], fmt, pretty)?;
+        } else {
+            write_output(&mut stdout, &results, fmt, pretty)?;
+        }
+    } else {
+        for result in &results {
+            writeln!(stdout, "{}", result.masked)?;
+        }
+    }
+
+    if failed > 0 {
+        return Err(IdtError::InvalidArgument(format!(
+            "Failed to mask {} of {} IDs",
+            failed,
+            ids.len()
+        )));
+    }
+
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct MaskResult {
+    input: String,
+    masked: String,
+    id_type: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mask::TimestampGranularity;
+
+    fn make_args(ids: Vec<&str>) -> MaskArgs {
+        MaskArgs {
+            ids: ids.into_iter().map(String::from).collect(),
+            key: "secret".to_string(),
+            id_type: None,
+            detect: Default::default(),
+            keep_timestamp: None,
+            epoch: None,
+            preset: None,
+        }
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let args = make_args(vec![]);
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_empty_key() {
+        let mut args = make_args(vec!["550e8400-e29b-41d4-a716-446655440000"]);
+        args.key = String::new();
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_mask_uuid() {
+        let args = make_args(vec!["550e8400-e29b-41d4-a716-446655440000"]);
+        assert!(execute(&args, None, false).is_ok());
+    }
+
+    #[test]
+    fn test_mask_keep_timestamp_json() {
+        let mut args = make_args(vec![
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "507f1f77bcf86cd799439011",
+        ]);
+        args.keep_timestamp = Some(TimestampGranularity::Day);
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_ok());
+    }
+
+    #[test]
+    fn test_mask_snowflake_preset() {
+        let mut args = make_args(vec!["1234567890123456789"]);
+        args.preset = Some("twitter".to_string());
+        args.keep_timestamp = Some(TimestampGranularity::Exact);
+        assert!(execute(&args, None, false).is_ok());
+    }
+
+    #[test]
+    fn test_mask_unsupported_type_fails() {
+        let mut args = make_args(vec!["9780306406157"]);
+        args.id_type = Some(IdKind::Isbn13);
+        assert!(execute(&args, None, false).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::cli::app::{MergeArgs, MergeKey};
+use crate::cli::input::{numbered_lines, open_text};
+use crate::cli::output::stdout;
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId};
+use crate::ids::snowflake_id::SnowflakeLayout;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::io::{BufRead, BufWriter, Write};
+
+pub fn execute(args: &MergeArgs) -> Result<()> {
+    let snowflake_layout = if args.preset.is_some() || args.epoch.is_some() {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+    let order = MergeOrder {
+        by: args.by,
+        descending: args.reverse,
+        type_hint: args.id_type,
+        detection: args.detect.options(),
+        snowflake_layout,
+    };
+
+    let mut sources = Vec::with_capacity(args.files.len());
+    for path in &args.files {
+        sources.push(Source::new(path.display().to_string(), open_text(path)?));
+    }
+
+    let mut writer = BufWriter::new(stdout());
+    merge(sources, &order, args.unique, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// How heads from different inputs are compared
+struct MergeOrder {
+    by: MergeKey,
+    descending: bool,
+    type_hint: Option<IdKind>,
+    detection: DetectionOptions,
+    snowflake_layout: Option<SnowflakeLayout>,
+}
+
+impl MergeOrder {
+    /// The embedded timestamp of `id` when merging by timestamp, else `None`
+    fn millis(&self, id: &str) -> Result<Option<u64>> {
+        if self.by == MergeKey::Bytes {
+            return Ok(None);
+        }
+        let parsed: Box<dyn ParsedId> = match self.snowflake_layout {
+            Some(ref layout) => Box::new(crate::ids::ParsedSnowflake::parse_with_layout(
+                id,
+                layout.clone(),
+            )?),
+            None => crate::ids::parse_id_with(id, self.type_hint, &self.detection)?,
+        };
+        match parsed.timestamp() {
+            Some(ts) => Ok(Some(ts.millis)),
+            None => Err(IdtError::InvalidArgument(format!(
+                "'{}' ({}) has no embedded timestamp",
+                id,
+                parsed.kind().name()
+            ))),
+        }
+    }
+}
+
+/// One sorted input, read a line at a time
+struct Source<'a> {
+    name: String,
+    lines: Box<dyn Iterator<Item = Result<(usize, String)>> + 'a>,
+    line_no: usize,
+}
+
+impl<'a> Source<'a> {
+    fn new(name: String, reader: Box<dyn BufRead + 'a>) -> Self {
+        Self {
+            name,
+            lines: Box::new(numbered_lines(reader)),
+            line_no: 0,
+        }
+    }
+
+    /// The next non-blank, trimmed line, or `None` at end of input
+    fn next_id(&mut self) -> Result<Option<String>> {
+        let Some((line_no, id)) = self.lines.next().transpose()? else {
+            return Ok(None);
+        };
+        self.line_no = line_no;
+        Ok(Some(id))
+    }
+}
+
+/// The current line of one input, ordered so `BinaryHeap` pops the next
+/// ID to write. Ties go to the earlier input.
+struct Head {
+    millis: Option<u64>,
+    id: String,
+    source: usize,
+    descending: bool,
+}
+
+impl Head {
+    fn key(&self) -> (Option<u64>, &str) {
+        (self.millis, &self.id)
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap pops the greatest element, so ascending merges invert
+        let ord = self.key().cmp(&other.key());
+        let ord = if self.descending { ord } else { ord.reverse() };
+        ord.then_with(|| other.source.cmp(&self.source))
+    }
+}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+/// K-way merge of `sources` into `writer`. Only one line per input is held
+/// at a time, so memory stays constant however large the inputs are.
+fn merge(
+    mut sources: Vec<Source<'_>>,
+    order: &MergeOrder,
+    unique: bool,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let mut heap = BinaryHeap::with_capacity(sources.len());
+    for (index, source) in sources.iter_mut().enumerate() {
+        if let Some(head) = read_head(source, index, order)? {
+            heap.push(head);
+        }
+    }
+
+    let mut last: Option<String> = None;
+    while let Some(head) = heap.pop() {
+        if let Some(next) = read_head(&mut sources[head.source], head.source, order)? {
+            let out_of_order = match next.key().cmp(&head.key()) {
+                Ordering::Less => !order.descending,
+                Ordering::Greater => order.descending,
+                Ordering::Equal => false,
+            };
+            if out_of_order {
+                let source = &sources[head.source];
+                return Err(IdtError::InvalidArgument(format!(
+                    "{} is not sorted: line {} ('{}') belongs before '{}'",
+                    source.name, source.line_no, next.id, head.id
+                )));
+            }
+            heap.push(next);
+        }
+
+        if unique && last.as_deref() == Some(head.id.as_str()) {
+            continue;
+        }
+        writer.write_all(head.id.as_bytes())?;
+        writer.write_all(b"\n")?;
+        if unique {
+            last = Some(head.id);
+        }
+    }
+    Ok(())
+}
+
+fn read_head(source: &mut Source<'_>, index: usize, order: &MergeOrder) -> Result<Option<Head>> {
+    let Some(id) = source.next_id()? else {
+        return Ok(None);
+    };
+    let millis = order.millis(&id).map_err(|e| {
+        IdtError::InvalidArgument(format!("{}:{}: {}", source.name, source.line_no, e))
+    })?;
+    Ok(Some(Head {
+        millis,
+        id,
+        source: index,
+        descending: order.descending,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(by: MergeKey, descending: bool) -> MergeOrder {
+        MergeOrder {
+            by,
+            descending,
+            type_hint: None,
+            detection: DetectionOptions::default(),
+            snowflake_layout: None,
+        }
+    }
+
+    fn run(inputs: &[&str], order: &MergeOrder, unique: bool) -> Result<String> {
+        let sources = inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| Source::new(format!("in{}", i), Box::new(input.as_bytes())))
+            .collect();
+        let mut output = Vec::new();
+        merge(sources, order, unique, &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_merge_bytes() {
+        let out = run(
+            &["a\nc\ne\n", "b\n\nd\n", "", "f\n"],
+            &order(MergeKey::Bytes, false),
+            false,
+        );
+        assert_eq!(out.unwrap(), "a\nb\nc\nd\ne\nf\n");
+    }
+
+    #[test]
+    fn test_merge_descending() {
+        let out = run(
+            &["e\nc\na\n", "d\nb\n"],
+            &order(MergeKey::Bytes, true),
+            false,
+        );
+        assert_eq!(out.unwrap(), "e\nd\nc\nb\na\n");
+    }
+
+    #[test]
+    fn test_merge_unique() {
+        let out = run(
+            &["a\nb\nb\n", "b\nc\n"],
+            &order(MergeKey::Bytes, false),
+            true,
+        );
+        assert_eq!(out.unwrap(), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn test_merge_by_timestamp() {
+        // Text order and time order differ: UUIDv7 hex vs ULID Crockford base32
+        let uuid_2023 = "0188c0a8-a000-7000-8000-000000000000";
+        let ulid_2016 = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+        let ulid_2024 = "01HQ0000000000000000000000";
+        let uuids = format!("{}\n", uuid_2023);
+        let ulids = format!("{}\n{}\n", ulid_2016, ulid_2024);
+
+        let out = run(&[&uuids, &ulids], &order(MergeKey::Timestamp, false), false).unwrap();
+        assert_eq!(
+            out,
+            format!("{}\n{}\n{}\n", ulid_2016, uuid_2023, ulid_2024)
+        );
+    }
+
+    #[test]
+    fn test_merge_rejects_unsorted_input() {
+        let err = run(&["a\nc\nb\n"], &order(MergeKey::Bytes, false), false).unwrap_err();
+        assert!(err.to_string().contains("in0 is not sorted: line 3 ('b')"));
+    }
+
+    #[test]
+    fn test_merge_rejects_ids_without_timestamp() {
+        let err = run(
+            &["550e8400-e29b-41d4-a716-446655440000\n"],
+            &order(MergeKey::Timestamp, false),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("in0:1:"));
+    }
+}
@@ -1,9 +1,30 @@
+pub mod audit;
+pub mod bench;
+pub mod bucket;
+pub mod canon;
 pub mod compare;
 pub mod completions;
+pub mod conformance;
 pub mod convert;
+pub mod dedup;
+pub mod doctor;
+pub mod explain;
+pub mod filter;
 pub mod generate;
+pub mod grep;
+pub mod grpc;
+pub mod hash;
 pub mod info;
 pub mod inspect;
 pub mod manpage;
+pub mod mask;
+pub mod merge;
+pub mod redact;
+pub mod self_update;
+pub mod set;
+pub mod shard;
 pub mod sort;
+pub mod stats;
+pub mod tui;
+pub mod uuid5_find;
 pub mod validate;
@@ -0,0 +1,296 @@
+use crate::cli::app::{OutputFormat, RedactArgs};
+use crate::cli::atomic_file::AtomicFile;
+use crate::cli::output::{stdout, write_output};
+use crate::core::error::{IdtError, Result};
+use crate::core::mask::Masker;
+use crate::core::scan::{ScanOptions, find_ids, kind_label};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+
+pub fn execute(args: &RedactArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    if !(0.0..=1.0).contains(&args.min_confidence) {
+        return Err(IdtError::InvalidArgument(
+            "--min-confidence must be between 0.0 and 1.0".to_string(),
+        ));
+    }
+
+    let masker = if args.mask {
+        match args.key.as_deref() {
+            Some(key) if !key.is_empty() => Some(Masker::new(key.as_bytes())),
+            _ => {
+                return Err(IdtError::InvalidArgument(
+                    "--mask requires --key or IDT_MASK_KEY".to_string(),
+                ));
+            }
+        }
+    } else {
+        None
+    };
+
+    let redactor = Redactor {
+        options: ScanOptions {
+            min_confidence: args.min_confidence,
+            kinds: args.id_type.clone(),
+            exclude: args.exclude.clone(),
+        },
+        placeholder: args.placeholder.clone(),
+        masker,
+    };
+
+    let mut summaries = Vec::new();
+
+    if args.files.is_empty() {
+        let stdin = io::stdin();
+        let mut stdout = stdout();
+        let mut summary = RedactSummary::new("-");
+        redactor.redact_stream(&mut stdin.lock(), &mut stdout, &mut summary)?;
+        summaries.push(summary);
+    } else {
+        let mut stdout = stdout();
+        for path in &args.files {
+            let content = std::fs::read_to_string(path)?;
+            let mut summary = RedactSummary::new(&path.display().to_string());
+            let redacted = redactor.redact_text(&content, &mut summary);
+
+            if args.in_place {
+                // Replace the file only once the redacted copy is complete,
+                // keeping its permissions
+                if summary.replacements > 0 {
+                    let mut file = AtomicFile::create(path, false)?;
+                    file.write_all(redacted.as_bytes())?;
+                    file.set_permissions(std::fs::metadata(path)?.permissions())?;
+                    file.commit()?;
+                }
+            } else {
+                stdout.write_all(redacted.as_bytes())?;
+            }
+            summaries.push(summary);
+        }
+    }
+
+    if args.summary {
+        let mut stderr = io::stderr();
+        if let Some(fmt) = output_format {
+            if summaries.len() == 1 {
+                write_output(&mut stderr, &summaries[0], fmt, pretty)?;
+            } else {
+                write_output(&mut stderr, &summaries, fmt, pretty)?;
+            }
+        } else {
+            for summary in &summaries {
+                writeln!(stderr, "{}", summary)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Redactor {
+    options: ScanOptions,
+    placeholder: String,
+    masker: Option<Masker>,
+}
+
+impl Redactor {
+    /// Redact line by line so piped streams (e.g. `tail -f`) are flushed as they arrive
+    fn redact_stream(
+        &self,
+        reader: &mut dyn BufRead,
+        writer: &mut dyn Write,
+        summary: &mut RedactSummary,
+    ) -> Result<()> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            writer.write_all(self.redact_text(&line, summary).as_bytes())?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn redact_text(&self, text: &str, summary: &mut RedactSummary) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for m in find_ids(text, &self.options) {
+            out.push_str(&text[last..m.start]);
+            out.push_str(&self.replacement(&text[m.start..m.end], m.kind));
+            last = m.end;
+            summary.record(m.kind.name());
+        }
+
+        out.push_str(&text[last..]);
+        out
+    }
+
+    fn replacement(&self, id: &str, kind: crate::core::id::IdKind) -> String {
+        // Kinds that cannot be masked fall back to the placeholder so nothing leaks
+        if let Some(ref masker) = self.masker
+            && let Ok(parsed) = crate::ids::parse_id(id, Some(kind))
+            && let Ok(masked) = masker.mask(parsed.as_ref())
+        {
+            return masked;
+        }
+        self.placeholder.replace("{}", &kind_label(kind))
+    }
+}
+
+#[derive(serde::Serialize)]
+struct RedactSummary {
+    source: String,
+    replacements: usize,
+    by_type: BTreeMap<String, usize>,
+}
+
+impl RedactSummary {
+    fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            replacements: 0,
+            by_type: BTreeMap::new(),
+        }
+    }
+
+    fn record(&mut self, kind: &str) {
+        self.replacements += 1;
+        *self.by_type.entry(kind.to_string()).or_insert(0) += 1;
+    }
+}
+
+impl std::fmt::Display for RedactSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} redacted", self.source, self.replacements)?;
+        if !self.by_type.is_empty() {
+            let parts: Vec<String> = self
+                .by_type
+                .iter()
+                .map(|(kind, count)| format!("{} {}", kind, count))
+                .collect();
+            write!(f, " ({})", parts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn make_args(files: Vec<PathBuf>) -> RedactArgs {
+        RedactArgs {
+            files,
+            in_place: false,
+            id_type: vec![],
+            exclude: vec![],
+            placeholder: "[{}]".to_string(),
+            mask: false,
+            key: None,
+            min_confidence: crate::core::scan::DEFAULT_MIN_CONFIDENCE,
+            summary: false,
+        }
+    }
+
+    fn redactor(masker: Option<Masker>) -> Redactor {
+        Redactor {
+            options: ScanOptions::default(),
+            placeholder: "[{}]".to_string(),
+            masker,
+        }
+    }
+
+    #[test]
+    fn test_redact_text_placeholders() {
+        let mut summary = RedactSummary::new("-");
+        let out = redactor(None).redact_text(
+            "user 550e8400-e29b-41d4-a716-446655440000 placed 01ARZ3NDEKTSV4RRFFQ69G5FAV\n",
+            &mut summary,
+        );
+        assert_eq!(out, "user [UUID] placed [ULID]\n");
+        assert_eq!(summary.replacements, 2);
+        assert_eq!(summary.by_type["uuidv4"], 1);
+        assert_eq!(summary.by_type["ulid"], 1);
+    }
+
+    #[test]
+    fn test_redact_text_masked() {
+        let mut summary = RedactSummary::new("-");
+        let input = "order=01ARZ3NDEKTSV4RRFFQ69G5FAV";
+        let out = redactor(Some(Masker::new(b"k"))).redact_text(input, &mut summary);
+        assert!(out.starts_with("order="));
+        assert_ne!(out, input);
+        assert!(crate::ids::is_ulid(&out["order=".len()..]));
+    }
+
+    #[test]
+    fn test_redact_stream_preserves_lines() {
+        let input = b"a 507f1f77bcf86cd799439011\nno ids here\r\n";
+        let mut output = Vec::new();
+        let mut summary = RedactSummary::new("-");
+        redactor(None)
+            .redact_stream(&mut &input[..], &mut output, &mut summary)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "a [OBJECTID]\nno ids here\r\n"
+        );
+    }
+
+    #[test]
+    fn test_summary_display() {
+        let mut summary = RedactSummary::new("app.log");
+        summary.record("ulid");
+        summary.record("ulid");
+        assert_eq!(summary.to_string(), "app.log: 2 redacted (ulid 2)");
+    }
+
+    #[test]
+    fn test_mask_requires_key() {
+        let mut args = make_args(vec![PathBuf::from("unused.log")]);
+        args.mask = true;
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_invalid_min_confidence() {
+        let mut args = make_args(vec![PathBuf::from("unused.log")]);
+        args.min_confidence = 1.5;
+        assert!(execute(&args, None, false).is_err());
+    }
+
+    #[test]
+    fn test_redact_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "id=550e8400-e29b-41d4-a716-446655440000\n").unwrap();
+
+        let mut args = make_args(vec![path.clone()]);
+        args.in_place = true;
+        execute(&args, None, false).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "id=[UUID]\n");
+        // The temp copy was renamed over the log, not left beside it
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_redact_in_place_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "id=550e8400-e29b-41d4-a716-446655440000\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        let mut args = make_args(vec![path.clone()]);
+        args.in_place = true;
+        execute(&args, None, false).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+}
@@ -0,0 +1,349 @@
+//! Replace the running binary with a build from a GitHub release.
+//!
+//! Downloads go through `curl` and archives are unpacked with `tar`, both of
+//! which ship with current Linux, macOS, and Windows. Every archive is checked
+//! against the `.sha256` file published next to it before anything is
+//! replaced; `--verify-attestation` additionally checks the build provenance
+//! with `gh attestation verify`.
+
+use crate::cli::app::{OutputFormat, SelfUpdateArgs};
+use crate::cli::atomic_file::AtomicFile;
+use crate::cli::output::{stdout, write_output};
+use crate::core::error::{IdtError, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+const REPO: &str = "sh-cho/idt";
+
+const BIN_NAME: &str = if cfg!(windows) { "idt.exe" } else { "idt" };
+
+/// Installs that belong to a package manager, and how to update them instead
+const MANAGED_PATHS: &[(&str, &str)] = &[
+    ("/Cellar/", "brew upgrade idt"),
+    ("/nix/store/", "nix profile upgrade"),
+];
+
+#[derive(Debug, Serialize)]
+struct UpdateReport {
+    current_version: String,
+    target_version: String,
+    asset: String,
+    update_available: bool,
+    updated: bool,
+}
+
+pub fn execute(args: &SelfUpdateArgs, format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let target = match &args.version {
+        Some(v) => v.trim_start_matches('v').to_string(),
+        None => latest_version()?,
+    };
+    let asset = asset_name(std::env::consts::OS, std::env::consts::ARCH).ok_or_else(|| {
+        update_failed(format!(
+            "no release build for {}-{}; install with `cargo install idt`",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+
+    let update_available = match args.version {
+        Some(_) => target != current,
+        None => compare_versions(&target, current) == Some(Ordering::Greater),
+    };
+    let mut report = UpdateReport {
+        current_version: current.to_string(),
+        target_version: target.clone(),
+        asset: asset.clone(),
+        update_available,
+        updated: false,
+    };
+
+    if !args.check && (update_available || args.force) {
+        let exe = std::env::current_exe()?;
+        let exe = fs::canonicalize(&exe).unwrap_or(exe);
+        check_not_managed(&exe)?;
+        install(&target, &asset, &exe, args.verify_attestation)?;
+        report.updated = true;
+    }
+
+    let mut stdout = stdout();
+    if let Some(fmt) = format {
+        write_output(&mut stdout, &report, fmt, pretty)?;
+    } else if report.updated {
+        writeln!(stdout, "Updated idt {} -> {}", current, target)?;
+    } else if update_available {
+        writeln!(
+            stdout,
+            "idt {} is available (installed: {}); run `idt self-update` to install it",
+            target, current
+        )?;
+    } else {
+        writeln!(stdout, "idt {} is up to date", current)?;
+    }
+    Ok(())
+}
+
+fn update_failed(msg: impl Into<String>) -> IdtError {
+    IdtError::UpdateFailed(msg.into())
+}
+
+/// Download, verify, unpack, and swap in `version` over `exe`
+fn install(version: &str, asset: &str, exe: &Path, verify_attestation: bool) -> Result<()> {
+    let dir = tempfile::Builder::new().prefix("idt-update-").tempdir()?;
+    let base = format!("https://github.com/{}/releases/download/{}", REPO, version);
+
+    let archive = dir.path().join(asset);
+    tracing::debug!(url = %format!("{}/{}", base, asset), "downloading release");
+    curl(&format!("{}/{}", base, asset), Some(&archive))?;
+    let checksum = curl(&format!("{}/{}.sha256", base, asset), None)?;
+    let expected = parse_checksum(&String::from_utf8_lossy(&checksum))
+        .ok_or_else(|| update_failed(format!("{}.sha256 holds no SHA-256 digest", asset)))?;
+    verify_checksum(&fs::read(&archive)?, &expected)?;
+    if verify_attestation {
+        run(
+            Command::new("gh")
+                .args(["attestation", "verify", "--repo", REPO])
+                .arg(&archive),
+            "gh",
+        )?;
+    }
+
+    let unpacked = dir.path().join("unpacked");
+    fs::create_dir(&unpacked)?;
+    run(
+        Command::new("tar")
+            .arg("-xf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&unpacked),
+        "tar",
+    )?;
+    let new = unpacked.join(BIN_NAME);
+    if !new.is_file() {
+        return Err(update_failed(format!("{} has no {}", asset, BIN_NAME)));
+    }
+
+    // Refuse a binary that does not run here or reports another version
+    let output = run(Command::new(&new).arg("--version"), BIN_NAME)?;
+    if !String::from_utf8_lossy(&output).contains(version) {
+        return Err(update_failed(format!(
+            "downloaded binary does not report version {}",
+            version
+        )));
+    }
+
+    replace_executable(&new, exe)
+}
+
+/// Tag of the newest published release
+fn latest_version() -> Result<String> {
+    let body = curl(
+        &format!("https://api.github.com/repos/{}/releases/latest", REPO),
+        None,
+    )?;
+    let release: serde_json::Value = serde_json::from_slice(&body)?;
+    release["tag_name"]
+        .as_str()
+        .map(|tag| tag.trim_start_matches('v').to_string())
+        .ok_or_else(|| update_failed("GitHub returned a release without a tag"))
+}
+
+/// Release archive built for `os` and `arch` (as in `std::env::consts`)
+fn asset_name(os: &str, arch: &str) -> Option<String> {
+    let os = match os {
+        "linux" => "linux",
+        "macos" => "darwin",
+        "windows" => "windows",
+        _ => return None,
+    };
+    let arch = match arch {
+        "x86_64" => "x86_64",
+        "aarch64" => "arm64",
+        _ => return None,
+    };
+    let ext = if os == "windows" { "zip" } else { "tar.gz" };
+    Some(format!("idt_{}_{}.{}", os, arch, ext))
+}
+
+/// Order `a` and `b` as `MAJOR.MINOR.PATCH[-PRE]`; a pre-release sorts
+/// before its release
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    fn key(v: &str) -> Option<(u64, u64, u64, bool)> {
+        let (core, pre) = match v.split_once('-') {
+            Some((core, _)) => (core, true),
+            None => (v, false),
+        };
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        let key = (parts.next()??, parts.next()??, parts.next()??, !pre);
+        parts.next().is_none().then_some(key)
+    }
+    Some(key(a)?.cmp(&key(b)?))
+}
+
+/// Digest from a `.sha256` file: the hex digest, optionally followed by the
+/// file name as `sha256sum` writes it
+fn parse_checksum(text: &str) -> Option<String> {
+    let digest = text.split_whitespace().next()?;
+    (digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
+}
+
+fn verify_checksum(data: &[u8], expected: &str) -> Result<()> {
+    let actual = hex::encode(Sha256::digest(data));
+    if actual != expected {
+        return Err(update_failed(format!(
+            "checksum mismatch: expected {}, downloaded file has {}",
+            expected, actual
+        )));
+    }
+    Ok(())
+}
+
+fn check_not_managed(exe: &Path) -> Result<()> {
+    let path = exe.to_string_lossy().replace('\\', "/");
+    match MANAGED_PATHS.iter().find(|(dir, _)| path.contains(dir)) {
+        Some((_, hint)) => Err(update_failed(format!(
+            "{} is managed by a package manager; update it with `{}`",
+            exe.display(),
+            hint
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Put `new` in place of `exe`, keeping `exe`'s permissions
+fn replace_executable(new: &Path, exe: &Path) -> Result<()> {
+    let name = exe
+        .file_name()
+        .ok_or_else(|| update_failed(format!("{} is not a file", exe.display())))?
+        .to_string_lossy()
+        .into_owned();
+    let mut staged = AtomicFile::create(exe, false)?;
+    io::copy(&mut File::open(new)?, &mut staged)?;
+    staged.set_permissions(fs::metadata(exe)?.permissions())?;
+
+    // Windows cannot overwrite a running executable, but it can rename one
+    let old = exe.with_file_name(format!("{}.old", name));
+    if cfg!(windows) {
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old)?;
+    }
+    staged.commit().inspect_err(|_| {
+        if cfg!(windows) {
+            let _ = fs::rename(&old, exe);
+        }
+    })
+}
+
+/// Fetch `url`, into `output` if given, otherwise returning the body
+fn curl(url: &str, output: Option<&Path>) -> Result<Vec<u8>> {
+    let mut cmd = Command::new("curl");
+    cmd.args([
+        "--fail",
+        "--silent",
+        "--show-error",
+        "--location",
+        "--proto",
+        "=https",
+        "--tlsv1.2",
+    ]);
+    if let Some(path) = output {
+        cmd.arg("--output").arg(path);
+    }
+    run(cmd.arg(url), "curl")
+}
+
+/// Run `cmd`, returning its stdout, or its stderr as the error
+fn run(cmd: &mut Command, name: &str) -> Result<Vec<u8>> {
+    let output = cmd
+        .output()
+        .map_err(|e| update_failed(format!("cannot run {}: {}", name, e)))?;
+    if !output.status.success() {
+        return Err(update_failed(format!(
+            "{} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_name() {
+        assert_eq!(
+            asset_name("linux", "x86_64").as_deref(),
+            Some("idt_linux_x86_64.tar.gz")
+        );
+        assert_eq!(
+            asset_name("macos", "aarch64").as_deref(),
+            Some("idt_darwin_arm64.tar.gz")
+        );
+        assert_eq!(
+            asset_name("windows", "x86_64").as_deref(),
+            Some("idt_windows_x86_64.zip")
+        );
+        assert_eq!(asset_name("freebsd", "x86_64"), None);
+        assert_eq!(asset_name("linux", "riscv64"), None);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(
+            compare_versions("0.1.24", "0.1.23"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare_versions("0.1.9", "0.1.10"), Some(Ordering::Less));
+        assert_eq!(compare_versions("1.0.0", "1.0.0"), Some(Ordering::Equal));
+        assert_eq!(
+            compare_versions("1.0.0-rc.1", "1.0.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare_versions("1.0", "1.0.0"), None);
+        assert_eq!(compare_versions("nightly", "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let digest = hex::encode(Sha256::digest(b"idt"));
+        assert_eq!(parse_checksum(&digest).as_deref(), Some(digest.as_str()));
+        let line = format!("{}  idt_linux_x86_64.tar.gz\n", digest.to_uppercase());
+        assert_eq!(parse_checksum(&line).as_deref(), Some(digest.as_str()));
+        assert_eq!(parse_checksum("not a digest"), None);
+
+        assert!(verify_checksum(b"idt", &digest).is_ok());
+        assert!(matches!(
+            verify_checksum(b"tampered", &digest),
+            Err(IdtError::UpdateFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_not_managed() {
+        assert!(check_not_managed(Path::new("/opt/homebrew/Cellar/idt/0.1.23/bin/idt")).is_err());
+        assert!(check_not_managed(Path::new("/nix/store/abc-idt-0.1.23/bin/idt")).is_err());
+        assert!(check_not_managed(Path::new("/usr/local/bin/idt")).is_ok());
+    }
+
+    #[test]
+    fn test_replace_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("idt");
+        let new = dir.path().join("download");
+        fs::write(&exe, b"old").unwrap();
+        fs::write(&new, b"new").unwrap();
+
+        replace_executable(&new, &exe).unwrap();
+        assert_eq!(fs::read(&exe).unwrap(), b"new");
+        // Nothing staged is left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,441 @@
+use crate::cli::app::{SetArgs, SetKey, SetOp, SetStrategy};
+use crate::cli::input::{numbered_lines, open_text};
+use crate::cli::output::stdout;
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::IdKind;
+use std::cmp::Ordering;
+use std::collections::hash_map;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufWriter, Write};
+use std::path::Path;
+
+/// Largest amount of input `--strategy auto` holds in memory; past this it
+/// streams sorted inputs instead
+const HASH_LIMIT_BYTES: u64 = 256 * 1024 * 1024;
+
+pub fn execute(args: &SetArgs) -> Result<()> {
+    if is_stdin(&args.a) && is_stdin(&args.b) {
+        return Err(IdtError::InvalidArgument(
+            "Only one of the two inputs can be stdin ('-')".to_string(),
+        ));
+    }
+    let keys = Canonicalizer {
+        by: args.by,
+        type_hint: args.id_type,
+        detection: args.detect.options(),
+    };
+    let sizes = (file_size(&args.a), file_size(&args.b));
+    let strategy = match args.strategy {
+        SetStrategy::Auto => auto_strategy(args.op, sizes),
+        strategy => strategy,
+    };
+
+    let a = Input::open(&args.a, &keys)?;
+    let b = Input::open(&args.b, &keys)?;
+    let mut sink = Sink {
+        writer: BufWriter::new(stdout()),
+        count_only: args.count,
+        count: 0,
+    };
+    match strategy {
+        SetStrategy::Merge => merge_op(args.op, a, b, &mut sink)?,
+        _ => hash_op(args.op, a, b, sizes, &mut sink)?,
+    }
+    if args.count {
+        writeln!(sink.writer, "{}", sink.count)?;
+    }
+    sink.writer.flush()?;
+    Ok(())
+}
+
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Size of the input on disk, or `None` for stdin
+fn file_size(path: &Path) -> Option<u64> {
+    if is_stdin(path) {
+        return None;
+    }
+    std::fs::metadata(path).ok().map(|m| m.len())
+}
+
+/// Hash when what has to be held in memory is known to be small enough.
+/// Stdin's size is unknown, and a pipe is rarely sorted, so it hashes.
+fn auto_strategy(op: SetOp, (a, b): (Option<u64>, Option<u64>)) -> SetStrategy {
+    let (Some(a), Some(b)) = (a, b) else {
+        return SetStrategy::Hash;
+    };
+    let held = match op {
+        SetOp::Union => a + b,
+        SetOp::Intersect => a.min(b),
+        SetOp::Diff => b,
+    };
+    if held <= HASH_LIMIT_BYTES {
+        SetStrategy::Hash
+    } else {
+        SetStrategy::Merge
+    }
+}
+
+/// Turns each line into the form IDs are compared and written in
+struct Canonicalizer {
+    by: SetKey,
+    type_hint: Option<IdKind>,
+    detection: DetectionOptions,
+}
+
+/// A parsed line: `key` decides equality, `id` is what gets written
+struct Entry {
+    key: String,
+    id: String,
+}
+
+impl Canonicalizer {
+    fn entry(&self, line: &str) -> Result<Entry> {
+        let parsed = crate::ids::parse_id_with(line, self.type_hint, &self.detection)?;
+        let id = parsed.canonical();
+        let key = match self.by {
+            SetKey::Canonical => id.clone(),
+            SetKey::Bytes => hex::encode(parsed.as_bytes()),
+        };
+        Ok(Entry { key, id })
+    }
+}
+
+/// One input file, read a line at a time
+struct Input<'a> {
+    name: String,
+    lines: Box<dyn Iterator<Item = Result<(usize, String)>> + 'a>,
+    keys: &'a Canonicalizer,
+    line_no: usize,
+    /// Key of the previous entry, tracked when reading sorted input
+    last: Option<String>,
+}
+
+impl<'a> Input<'a> {
+    fn open(path: &Path, keys: &'a Canonicalizer) -> Result<Self> {
+        Ok(Self::new(path.display().to_string(), open_text(path)?, keys))
+    }
+
+    fn new(name: String, reader: Box<dyn BufRead + 'a>, keys: &'a Canonicalizer) -> Self {
+        Self {
+            name,
+            lines: Box::new(numbered_lines(reader)),
+            keys,
+            line_no: 0,
+            last: None,
+        }
+    }
+
+    /// The next non-blank line, canonicalized, or `None` at end of input
+    fn next_entry(&mut self) -> Result<Option<Entry>> {
+        let Some((line_no, id)) = self.lines.next().transpose()? else {
+            return Ok(None);
+        };
+        self.line_no = line_no;
+        self.keys.entry(&id).map(Some).map_err(|e| {
+            IdtError::InvalidArgument(format!("{}:{}: {}", self.name, self.line_no, e))
+        })
+    }
+
+    /// The next entry with a key greater than the last one. Repeats are
+    /// skipped; a smaller key means the input isn't sorted.
+    fn next_sorted(&mut self) -> Result<Option<Entry>> {
+        while let Some(entry) = self.next_entry()? {
+            match self
+                .last
+                .as_deref()
+                .map(|last| entry.key.as_str().cmp(last))
+            {
+                Some(Ordering::Equal) => continue,
+                Some(Ordering::Less) => {
+                    return Err(IdtError::InvalidArgument(format!(
+                        "{} is not sorted: line {} ('{}') belongs before '{}'",
+                        self.name,
+                        self.line_no,
+                        entry.id,
+                        self.last.as_deref().unwrap_or_default()
+                    )));
+                }
+                _ => {}
+            }
+            self.last = Some(entry.key.clone());
+            return Ok(Some(entry));
+        }
+        Ok(None)
+    }
+}
+
+struct Sink<W: Write> {
+    writer: W,
+    count_only: bool,
+    count: u64,
+}
+
+impl<W: Write> Sink<W> {
+    fn emit(&mut self, entry: &Entry) -> Result<()> {
+        self.count += 1;
+        if !self.count_only {
+            self.writer.write_all(entry.id.as_bytes())?;
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Set operation with the needed side(s) held in a hash set. Output follows
+/// input order: the first file, then (for union) the second.
+fn hash_op<'a, W: Write>(
+    op: SetOp,
+    mut a: Input<'a>,
+    mut b: Input<'a>,
+    (a_size, b_size): (Option<u64>, Option<u64>),
+    sink: &mut Sink<W>,
+) -> Result<()> {
+    match op {
+        SetOp::Union => {
+            let mut seen = HashSet::new();
+            for input in [&mut a, &mut b] {
+                while let Some(entry) = input.next_entry()? {
+                    if seen.insert(entry.key.clone()) {
+                        sink.emit(&entry)?;
+                    }
+                }
+            }
+        }
+        SetOp::Diff => {
+            // Keys written so far join the second file's, so repeats in the
+            // first file are dropped too
+            let mut skip = HashSet::new();
+            while let Some(entry) = b.next_entry()? {
+                skip.insert(entry.key);
+            }
+            while let Some(entry) = a.next_entry()? {
+                if skip.insert(entry.key.clone()) {
+                    sink.emit(&entry)?;
+                }
+            }
+        }
+        SetOp::Intersect => {
+            // Hold the smaller file; results keep the first file's spelling
+            let hold_first = matches!((a_size, b_size), (Some(a), Some(b)) if a < b);
+            if hold_first {
+                let mut held = HashMap::new();
+                let mut order = Vec::new();
+                while let Some(entry) = a.next_entry()? {
+                    if let hash_map::Entry::Vacant(slot) = held.entry(entry.key.clone()) {
+                        order.push(entry.key);
+                        slot.insert((entry.id, false));
+                    }
+                }
+                while let Some(entry) = b.next_entry()? {
+                    if let Some((_, found)) = held.get_mut(&entry.key) {
+                        *found = true;
+                    }
+                }
+                for key in order {
+                    if let Some((id, true)) = held.remove(&key) {
+                        sink.emit(&Entry { key, id })?;
+                    }
+                }
+            } else {
+                let mut held = HashSet::new();
+                while let Some(entry) = b.next_entry()? {
+                    held.insert(entry.key);
+                }
+                while let Some(entry) = a.next_entry()? {
+                    if held.remove(&entry.key) {
+                        sink.emit(&entry)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Set operation over two inputs sorted by key, holding one entry from
+/// each at a time
+fn merge_op<W: Write>(
+    op: SetOp,
+    mut a: Input<'_>,
+    mut b: Input<'_>,
+    sink: &mut Sink<W>,
+) -> Result<()> {
+    let mut next_a = a.next_sorted()?;
+    let mut next_b = b.next_sorted()?;
+    loop {
+        let ord = match (&next_a, &next_b) {
+            (Some(x), Some(y)) => x.key.cmp(&y.key),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+        match ord {
+            Ordering::Less => {
+                if let (Some(entry), SetOp::Union | SetOp::Diff) = (&next_a, op) {
+                    sink.emit(entry)?;
+                }
+                next_a = a.next_sorted()?;
+            }
+            Ordering::Greater => {
+                if let (Some(entry), SetOp::Union) = (&next_b, op) {
+                    sink.emit(entry)?;
+                }
+                next_b = b.next_sorted()?;
+            }
+            Ordering::Equal => {
+                if let (Some(entry), SetOp::Union | SetOp::Intersect) = (&next_a, op) {
+                    sink.emit(entry)?;
+                }
+                next_a = a.next_sorted()?;
+                next_b = b.next_sorted()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const ULID_A: &str = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+    const ULID_B: &str = "01HQ0000000000000000000000";
+
+    fn keys(by: SetKey) -> Canonicalizer {
+        Canonicalizer {
+            by,
+            type_hint: None,
+            detection: DetectionOptions::default(),
+        }
+    }
+
+    fn run(op: SetOp, strategy: SetStrategy, by: SetKey, a: &str, b: &str) -> Result<String> {
+        let keys = keys(by);
+        let a = Input::new("a".to_string(), Box::new(a.as_bytes()), &keys);
+        let b = Input::new("b".to_string(), Box::new(b.as_bytes()), &keys);
+        let mut sink = Sink {
+            writer: Vec::new(),
+            count_only: false,
+            count: 0,
+        };
+        match strategy {
+            SetStrategy::Merge => merge_op(op, a, b, &mut sink)?,
+            _ => hash_op(op, a, b, (None, None), &mut sink)?,
+        }
+        Ok(String::from_utf8(sink.writer).unwrap())
+    }
+
+    #[test]
+    fn test_ops_agree_across_strategies() {
+        // Sorted, with case and dash differences between the files
+        let a = format!("{}\n{}\n{}\n", ULID_A, ULID_A, UUID);
+        let b = format!(
+            "{}\n\n{}\n",
+            ULID_B.to_lowercase(),
+            UUID.to_uppercase().replace('-', "")
+        );
+        for strategy in [SetStrategy::Hash, SetStrategy::Merge] {
+            let run = |op| run(op, strategy, SetKey::Canonical, &a, &b).unwrap();
+            assert_eq!(run(SetOp::Intersect), format!("{}\n", UUID));
+            assert_eq!(run(SetOp::Diff), format!("{}\n", ULID_A));
+            let union = run(SetOp::Union);
+            assert_eq!(union.lines().count(), 3, "{:?}", strategy);
+            assert!(union.contains(ULID_B));
+        }
+    }
+
+    #[test]
+    fn test_hash_keeps_input_order() {
+        let out = run(
+            SetOp::Union,
+            SetStrategy::Hash,
+            SetKey::Canonical,
+            &format!("{}\n{}\n", ULID_B, ULID_A),
+            &format!("{}\n", UUID),
+        );
+        assert_eq!(out.unwrap(), format!("{}\n{}\n{}\n", ULID_B, ULID_A, UUID));
+    }
+
+    #[test]
+    fn test_match_by_bytes() {
+        // The same 128 bits as a UUID and as a ULID
+        let ulid = "01BX5ZZKBKACTAV9WEVGEMMVRZ";
+        let uuid = "015f4bff-cd73-5334-ada7-8edc1d4a6f1f";
+        let text = run(
+            SetOp::Intersect,
+            SetStrategy::Hash,
+            SetKey::Canonical,
+            ulid,
+            uuid,
+        );
+        assert_eq!(text.unwrap(), "");
+        let bytes = run(
+            SetOp::Intersect,
+            SetStrategy::Hash,
+            SetKey::Bytes,
+            ulid,
+            uuid,
+        );
+        assert_eq!(bytes.unwrap(), format!("{}\n", ulid));
+    }
+
+    #[test]
+    fn test_merge_rejects_unsorted_input() {
+        let err = run(
+            SetOp::Union,
+            SetStrategy::Merge,
+            SetKey::Canonical,
+            &format!("{}\n{}\n", ULID_B, ULID_A),
+            "",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("a is not sorted: line 2"));
+    }
+
+    #[test]
+    fn test_invalid_line_reports_position() {
+        let err = run(
+            SetOp::Diff,
+            SetStrategy::Hash,
+            SetKey::Canonical,
+            &format!("{}\nnot an id!\n", UUID),
+            "",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().starts_with("Invalid argument: a:2:"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_auto_strategy() {
+        let big = HASH_LIMIT_BYTES + 1;
+        assert_eq!(
+            auto_strategy(SetOp::Diff, (Some(big), Some(10))),
+            SetStrategy::Hash
+        );
+        assert_eq!(
+            auto_strategy(SetOp::Diff, (Some(10), Some(big))),
+            SetStrategy::Merge
+        );
+        assert_eq!(
+            auto_strategy(SetOp::Intersect, (Some(big), Some(10))),
+            SetStrategy::Hash
+        );
+        assert_eq!(
+            auto_strategy(SetOp::Union, (Some(big), Some(10))),
+            SetStrategy::Merge
+        );
+        assert_eq!(
+            auto_strategy(SetOp::Union, (None, Some(big))),
+            SetStrategy::Hash
+        );
+    }
+}
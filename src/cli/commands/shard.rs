@@ -0,0 +1,260 @@
+use crate::cli::app::{OutputFormat, ShardArgs, ShardHash, ShardKey};
+use crate::cli::input::{stream_ids, stream_total};
+use crate::cli::output::{ErrorEntry, stdout, write_output};
+use crate::cli::progress::Progress;
+use crate::core::detection::DetectionOptions;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::IdKind;
+use serde::Serialize;
+use std::io::{BufWriter, Write};
+
+pub fn execute(args: &ShardArgs, output_format: Option<OutputFormat>, pretty: bool) -> Result<()> {
+    if args.shards == 0 {
+        return Err(IdtError::InvalidArgument(
+            "--shards must be at least 1".to_string(),
+        ));
+    }
+    if args.seed.is_some() && args.hash == ShardHash::Kafka {
+        return Err(IdtError::InvalidArgument(
+            "--seed does not apply to --hash kafka, which always uses Kafka's seed".to_string(),
+        ));
+    }
+    let sharder = Sharder {
+        hash: args.hash,
+        shards: args.shards,
+        seed: args.seed.unwrap_or(0),
+        key: args.key,
+        type_hint: args.id_type,
+        detection: args.detect.options(),
+    };
+
+    let source = args.input.source()?;
+    let ids = stream_ids(&args.ids, &source)?;
+    let mut writer = BufWriter::new(stdout());
+    let mut progress = Progress::new("shard", stream_total(&ids), false);
+    let mut entries = Vec::new();
+    let mut count = 0usize;
+    let mut failed = 0usize;
+
+    for sourced in ids {
+        let id = sourced?.id;
+        count += 1;
+        progress.tick(id.len() + 1);
+        match sharder.shard(&id) {
+            Ok(shard) => {
+                if output_format.is_some() {
+                    entries.push(ShardEntry::Ok(Assignment { id, shard }));
+                } else {
+                    writeln!(writer, "{}\t{}", id, shard)?;
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                if output_format.is_some() {
+                    entries.push(ShardEntry::Err(ErrorEntry::new(&id, &e)));
+                } else {
+                    progress.clear();
+                    eprintln!("Error sharding '{}': {}", id, e);
+                }
+            }
+        }
+    }
+
+    progress.clear();
+    writer.flush()?;
+
+    if count == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    if let Some(fmt) = output_format {
+        if entries.len() == 1 {
+            write_output(&mut stdout(), &entries[0], fmt, pretty)?;
+        } else {
+            write_output(&mut stdout(), &entries, fmt, pretty)?;
+        }
+    }
+
+    if failed > 0 {
+        return Err(IdtError::InvalidArgument(format!(
+            "Failed to shard {} of {} IDs",
+            failed, count
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct Assignment {
+    id: String,
+    shard: u32,
+}
+
+/// An assignment, or an error object when the input isn't an ID
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ShardEntry {
+    Ok(Assignment),
+    Err(ErrorEntry),
+}
+
+struct Sharder {
+    hash: ShardHash,
+    shards: u32,
+    seed: u32,
+    key: ShardKey,
+    type_hint: Option<IdKind>,
+    detection: DetectionOptions,
+}
+
+impl Sharder {
+    fn shard(&self, id: &str) -> Result<u32> {
+        let key = self.key_bytes(id)?;
+        let n = self.shards;
+        Ok(match self.hash {
+            ShardHash::Murmur3 => {
+                let hash = murmur3::murmur3_32(&mut key.as_slice(), self.seed)?;
+                hash % n
+            }
+            ShardHash::Xxhash => {
+                let hash = xxhash_rust::xxh64::xxh64(&key, self.seed as u64);
+                (hash % n as u64) as u32
+            }
+            ShardHash::Jump => {
+                jump_consistent_hash(xxhash_rust::xxh64::xxh64(&key, self.seed as u64), n)
+            }
+            ShardHash::Kafka => (kafka_murmur2(&key) & 0x7fff_ffff) % n,
+        })
+    }
+
+    /// The bytes that get hashed
+    fn key_bytes(&self, id: &str) -> Result<Vec<u8>> {
+        if self.key == ShardKey::Text {
+            return Ok(id.as_bytes().to_vec());
+        }
+        let parsed = crate::ids::parse_id_with(id, self.type_hint, &self.detection)?;
+        Ok(match self.key {
+            ShardKey::Bytes => parsed.as_bytes(),
+            _ => parsed.canonical().into_bytes(),
+        })
+    }
+}
+
+/// Lamping and Veach's jump consistent hash: growing from `n` to `n + 1`
+/// buckets moves only the keys that land in the new bucket
+fn jump_consistent_hash(mut key: u64, buckets: u32) -> u32 {
+    let mut b: i64 = -1;
+    let mut j: i64 = 0;
+    while j < buckets as i64 {
+        b = j;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    b as u32
+}
+
+/// MurmurHash2 as Kafka's default partitioner computes it
+/// (`org.apache.kafka.common.utils.Utils.murmur2`)
+fn kafka_murmur2(data: &[u8]) -> u32 {
+    const SEED: u32 = 0x9747_b28c;
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let mut h = SEED ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, &byte) in tail.iter().enumerate() {
+            h ^= (byte as u32) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sharder(hash: ShardHash, shards: u32, key: ShardKey) -> Sharder {
+        Sharder {
+            hash,
+            shards,
+            seed: 0,
+            key,
+            type_hint: None,
+            detection: DetectionOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_kafka_murmur2() {
+        // Values from Kafka's UtilsTest, as Java ints
+        assert_eq!(kafka_murmur2(b"21") as i32, -973932308);
+        assert_eq!(kafka_murmur2(b"foobar") as i32, -790332482);
+        assert_eq!(
+            kafka_murmur2(b"a-little-bit-long-string") as i32,
+            -985981536
+        );
+        assert_eq!(
+            kafka_murmur2(b"a-little-bit-longer-string") as i32,
+            -1486304829
+        );
+        assert_eq!(kafka_murmur2(b"abc") as i32, 479470107);
+    }
+
+    #[test]
+    fn test_jump_moves_keys_only_to_new_bucket() {
+        for key in 0..1000u64 {
+            let key = key.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            assert_eq!(jump_consistent_hash(key, 1), 0);
+            for n in 1..20 {
+                let before = jump_consistent_hash(key, n);
+                let after = jump_consistent_hash(key, n + 1);
+                assert!(before < n);
+                assert!(after == before || after == n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_canonicalizes() {
+        let s = sharder(ShardHash::Murmur3, 16, ShardKey::Canonical);
+        assert_eq!(
+            s.shard("550E8400E29B41D4A716446655440000").unwrap(),
+            s.shard("550e8400-e29b-41d4-a716-446655440000").unwrap()
+        );
+        let text = sharder(ShardHash::Murmur3, 1 << 20, ShardKey::Text);
+        assert_ne!(
+            text.shard("550E8400E29B41D4A716446655440000").unwrap(),
+            text.shard("550e8400-e29b-41d4-a716-446655440000").unwrap()
+        );
+        assert!(s.shard("not an id!").is_err());
+        assert!(text.shard("not an id!").is_ok());
+    }
+
+    #[test]
+    fn test_hashes_match_references() {
+        // murmur3_32("hello", 0) = 0x248bfa47, xxh64("hello", 0) = 0x26c7827d889f6da3
+        let murmur = sharder(ShardHash::Murmur3, u32::MAX, ShardKey::Text);
+        assert_eq!(murmur.shard("hello").unwrap(), 0x248b_fa47);
+        let xx = sharder(ShardHash::Xxhash, u32::MAX, ShardKey::Text);
+        assert_eq!(
+            xx.shard("hello").unwrap() as u64,
+            0x26c7_827d_889f_6da3 % u32::MAX as u64
+        );
+    }
+}
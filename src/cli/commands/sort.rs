@@ -1,9 +1,10 @@
 use crate::cli::app::{OutputFormat, SortArgs, UnsortablePolicy};
-use crate::cli::output::format_output;
+use crate::cli::input::{InputSource, stream_ids};
+use crate::cli::output::{stdout, write_output};
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{IdKind, ParsedId, Timestamp};
 use crate::ids::snowflake_id::SnowflakeLayout;
-use std::io::{self, BufRead, Write};
+use std::io::Write;
 
 struct SortEntry {
     input: String,
@@ -17,7 +18,9 @@ pub fn execute(
     pretty: bool,
     _no_color: bool,
 ) -> Result<()> {
-    let ids = collect_ids(&args.ids)?;
+    let ids = stream_ids(&args.ids, &InputSource::Text)?
+        .map(|sourced| sourced.map(|s| s.id))
+        .collect::<Result<Vec<_>>>()?;
 
     if ids.is_empty() {
         return Err(IdtError::InvalidArgument(
@@ -26,6 +29,7 @@ pub fn execute(
     }
 
     let type_hint: Option<IdKind> = args.id_type;
+    let detection = args.detect.options();
     let has_snowflake_opts = args.preset.is_some() || args.epoch.is_some();
     let snowflake_layout = if has_snowflake_opts {
         Some(SnowflakeLayout::resolve(
@@ -44,7 +48,7 @@ pub fn execute(
             crate::ids::ParsedSnowflake::parse_with_layout(id, layout.clone())
                 .map(|s| Box::new(s) as Box<dyn ParsedId>)
         } else {
-            crate::ids::parse_id(id, type_hint)
+            crate::ids::parse_id_with(id, type_hint, &detection)
         };
 
         match parse_result {
@@ -109,7 +113,7 @@ pub fn execute(
         sortable.reverse();
     }
 
-    let mut stdout = io::stdout();
+    let mut stdout = stdout();
 
     if let Some(fmt) = format {
         let sorted_items: Vec<serde_json::Value> = sortable
@@ -143,8 +147,7 @@ pub fn execute(
             "count": sortable.len() + unsortable.len(),
         });
 
-        let output = format_output(&output_val, fmt, pretty)?;
-        writeln!(stdout, "{}", output)?;
+        write_output(&mut stdout, &output_val, fmt, pretty)?;
     } else {
         output_plain(&mut stdout, &sortable, &unsortable, args.show_time)?;
     }
@@ -152,30 +155,6 @@ pub fn execute(
     Ok(())
 }
 
-fn collect_ids(args: &[String]) -> Result<Vec<String>> {
-    if !args.is_empty() {
-        return Ok(args.to_vec());
-    }
-
-    // Don't block on stdin if it's a terminal (no piped input)
-    if std::io::IsTerminal::is_terminal(&io::stdin()) {
-        return Ok(Vec::new());
-    }
-
-    let stdin = io::stdin();
-    let mut ids = Vec::new();
-
-    for line in stdin.lock().lines() {
-        let line = line?;
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            ids.push(trimmed.to_string());
-        }
-    }
-
-    Ok(ids)
-}
-
 fn output_plain(
     writer: &mut dyn Write,
     sortable: &[SortEntry],
@@ -214,6 +193,7 @@ mod tests {
         SortArgs {
             ids: ids.into_iter().map(String::from).collect(),
             id_type: None,
+            detect: Default::default(),
             reverse: false,
             show_time: false,
             epoch: None,
@@ -249,6 +229,7 @@ mod tests {
         let args = SortArgs {
             ids: vec!["not-a-real-id-format-xyz".to_string()],
             id_type: None,
+            detect: Default::default(),
             reverse: false,
             show_time: false,
             epoch: None,
@@ -293,6 +274,7 @@ mod tests {
         let args = SortArgs {
             ids: vec!["not-a-real-id-format-xyz".to_string()],
             id_type: None,
+            detect: Default::default(),
             reverse: false,
             show_time: false,
             epoch: None,
@@ -308,6 +290,7 @@ mod tests {
         let args = SortArgs {
             ids: vec!["not-a-real-id-format-xyz".to_string()],
             id_type: None,
+            detect: Default::default(),
             reverse: false,
             show_time: false,
             epoch: None,
@@ -356,13 +339,6 @@ mod tests {
         assert_eq!(String::from_utf8(buf).unwrap(), "test-id\n");
     }
 
-    #[test]
-    fn test_collect_ids_from_args() {
-        let args = vec!["id1".to_string(), "id2".to_string()];
-        let ids = collect_ids(&args).unwrap();
-        assert_eq!(ids, vec!["id1", "id2"]);
-    }
-
     #[test]
     fn test_sort_json_with_unsortable_end() {
         let args = SortArgs {
@@ -371,6 +347,7 @@ mod tests {
                 "not-a-real-id".to_string(),
             ],
             id_type: None,
+            detect: Default::default(),
             reverse: false,
             show_time: false,
             epoch: None,
@@ -0,0 +1,872 @@
+use crate::cli::app::{OutputFormat, StatsArgs};
+use crate::cli::commands::bucket::{CountRow, count_rows, histogram};
+use crate::cli::input::{stream_ids, stream_total};
+use crate::cli::output::{stdout, write_output};
+use crate::cli::progress::Progress;
+use crate::core::components::{Components, SnowflakeComponents};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId, Timestamp};
+use crate::core::time::BucketSize;
+use crate::ids::snowflake_id::SnowflakeLayout;
+use chrono_tz::Tz;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+/// Standard deviations from the expected value before a test fails. With
+/// 128 bit positions tested, a healthy source crosses this about once in
+/// ten thousand batches.
+const MAX_Z: f64 = 5.0;
+
+/// Fewest samples the per-position and serial tests need to say anything
+const MIN_SAMPLES: usize = 32;
+
+/// Most bars a histogram gets when it picks its own bucket width
+const MAX_BARS: u64 = 100;
+
+pub fn execute(
+    args: &StatsArgs,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    no_color: bool,
+) -> Result<()> {
+    let snowflake_layout = if args.preset.is_some() || args.epoch.is_some() {
+        Some(SnowflakeLayout::resolve(
+            args.preset.as_deref(),
+            args.epoch.as_deref(),
+        )?)
+    } else {
+        None
+    };
+    let detection = args.detect.options();
+    let source = args.input.source()?;
+    let ids = stream_ids(&args.ids, &source)?;
+    let mut progress = Progress::new("stats", stream_total(&ids), false);
+    let mut tally = Tally::default();
+    // Creation times, kept only for --histogram
+    let mut times = Vec::new();
+
+    for sourced in ids {
+        let id = sourced?.id;
+        progress.tick(id.len() + 1);
+        let parsed: Result<Box<dyn ParsedId>> = match snowflake_layout {
+            Some(ref layout) => crate::ids::ParsedSnowflake::parse_with_layout(&id, layout.clone())
+                .map(|s| Box::new(s) as Box<dyn ParsedId>),
+            None => crate::ids::parse_id_with(&id, args.id_type, &detection),
+        };
+        match parsed {
+            Ok(parsed) => {
+                if let (true, Some(ts)) = (args.histogram, parsed.timestamp()) {
+                    times.push(ts.millis);
+                }
+                tally.observe(parsed.as_ref());
+            }
+            Err(e) => {
+                tally.unparsed += 1;
+                progress.clear();
+                eprintln!("Warning: skipping '{}' — failed to parse: {}", id, e);
+            }
+        }
+    }
+    progress.clear();
+
+    if tally.distinct.is_empty() && tally.unparsed == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    let mut report = tally.report(args.entropy);
+    if args.histogram {
+        report.timeline = Some(timeline(&times, args.by, args.tz.unwrap_or(Tz::UTC))?);
+    }
+    let mut stdout = stdout();
+    if let Some(fmt) = format {
+        write_output(&mut stdout, &report, fmt, pretty)?;
+    } else {
+        output_plain(&mut stdout, &report, no_color)?;
+        match report.timeline.as_deref() {
+            Some([]) => {
+                writeln!(stdout)?;
+                writeln!(stdout, "No embedded timestamps to chart")?;
+            }
+            Some(rows) => {
+                writeln!(stdout)?;
+                for line in histogram(rows, args.width, args.ascii) {
+                    writeln!(stdout, "{}", line)?;
+                }
+            }
+            None => {}
+        }
+    }
+
+    let failed = report
+        .entropy
+        .iter()
+        .flatten()
+        .flat_map(|family| &family.checks)
+        .any(|check| check.status == Status::Fail);
+    if failed {
+        return Err(IdtError::CheckFailed(
+            "Random bits look weak; IDs may collide".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Skip,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: String) -> Self {
+        Self {
+            name,
+            status,
+            detail,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    ids: usize,
+    distinct: usize,
+    unparsed: usize,
+    /// IDs per detected type
+    types: BTreeMap<&'static str, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snowflake: Option<FleetReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entropy: Option<Vec<Entropy>>,
+    /// IDs per bucket of creation time, with `--histogram`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeline: Option<Vec<CountRow>>,
+}
+
+/// How Snowflake IDs spread over the workers that made them
+#[derive(Debug, Serialize)]
+struct FleetReport {
+    /// IDs per value of each worker field, e.g. `machine_id`
+    fields: BTreeMap<&'static str, BTreeMap<u64, usize>>,
+    workers: Vec<WorkerRow>,
+    /// Patterns that suggest misconfigured worker IDs
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WorkerRow {
+    #[serde(flatten)]
+    fields: BTreeMap<&'static str, u64>,
+    ids: usize,
+    first: String,
+    last: String,
+}
+
+/// Test results for the random part of one ID type
+#[derive(Debug, Serialize)]
+struct Entropy {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    /// Distinct IDs tested
+    ids: usize,
+    /// Random bits per ID
+    bits: u32,
+    /// IDs skipped as steps of a monotonic counter
+    counter_steps: usize,
+    checks: Vec<Check>,
+}
+
+#[derive(Default)]
+struct Tally {
+    ids: usize,
+    unparsed: usize,
+    distinct: HashSet<String>,
+    types: BTreeMap<&'static str, usize>,
+    first_ms: Option<u64>,
+    last_ms: Option<u64>,
+    samples: BTreeMap<&'static str, Samples>,
+    fleet: Fleet,
+}
+
+/// Distinct Snowflake IDs per worker, keyed by every layout field but the
+/// sequence
+#[derive(Default)]
+struct Fleet {
+    workers: BTreeMap<Vec<(&'static str, u64)>, WorkerTally>,
+}
+
+struct WorkerTally {
+    ids: usize,
+    first_ms: u64,
+    last_ms: u64,
+}
+
+impl Fleet {
+    fn observe(&mut self, id: &SnowflakeComponents) {
+        let worker: Vec<_> = id
+            .fields
+            .iter()
+            .copied()
+            .filter(|&(name, _)| name != "sequence")
+            .collect();
+        let ms = id.timestamp_ms;
+        let tally = self.workers.entry(worker).or_insert(WorkerTally {
+            ids: 0,
+            first_ms: ms,
+            last_ms: ms,
+        });
+        tally.ids += 1;
+        tally.first_ms = tally.first_ms.min(ms);
+        tally.last_ms = tally.last_ms.max(ms);
+    }
+
+    /// `None` without Snowflakes, or for layouts with no worker fields
+    fn report(&self) -> Option<FleetReport> {
+        if self.workers.keys().all(Vec::is_empty) {
+            return None;
+        }
+        let mut fields: BTreeMap<&'static str, BTreeMap<u64, usize>> = BTreeMap::new();
+        for (worker, tally) in &self.workers {
+            for &(name, value) in worker {
+                *fields.entry(name).or_default().entry(value).or_default() += tally.ids;
+            }
+        }
+        let workers = self
+            .workers
+            .iter()
+            .map(|(worker, tally)| WorkerRow {
+                fields: worker.iter().copied().collect(),
+                ids: tally.ids,
+                first: Timestamp::new(tally.first_ms).to_iso8601(),
+                last: Timestamp::new(tally.last_ms).to_iso8601(),
+            })
+            .collect();
+        Some(FleetReport {
+            fields,
+            workers,
+            warnings: self.warnings(),
+        })
+    }
+
+    /// Worker 0 is what most generators fall back to when none is
+    /// configured, so hosts left at the default share it and collide
+    fn warnings(&self) -> Vec<String> {
+        let default = self
+            .workers
+            .iter()
+            .find(|(worker, _)| worker.iter().all(|&(_, value)| value == 0));
+        let Some((worker, tally)) = default else {
+            return vec![];
+        };
+        let label = worker_label(worker);
+        if self.workers.len() == 1 {
+            vec![format!(
+                "Every ID comes from {}, the default; if more than one host generates \
+                 these IDs, they share a worker ID and can collide",
+                label
+            )]
+        } else {
+            vec![format!(
+                "{} IDs come from {}, the default, alongside {} other workers; \
+                 a host may be running without its worker ID set",
+                tally.ids,
+                label,
+                self.workers.len() - 1
+            )]
+        }
+    }
+}
+
+/// `datacenter_id=1 machine_id=7`
+fn worker_label(worker: &[(&'static str, u64)]) -> String {
+    worker
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Random parts of one type's distinct IDs, in input order
+struct Samples {
+    bits: u32,
+    values: Vec<u128>,
+    /// Timestamp and random part of the previous ID
+    last: Option<(u64, u128)>,
+    /// IDs left out because they step a monotonic counter
+    counter_steps: usize,
+}
+
+impl Samples {
+    /// Monotonic ULID and UUIDv7 generators increment the random part of
+    /// IDs made within one millisecond. Those steps aren't random, so only
+    /// the value each run starts from is kept.
+    fn push(&mut self, time_ms: Option<u64>, value: u128) {
+        let step_limit = 1u128 << (self.bits - 16);
+        let is_step = match (time_ms, self.last) {
+            (Some(time_ms), Some((last_ms, last))) => {
+                time_ms == last_ms && value > last && value - last <= step_limit
+            }
+            _ => false,
+        };
+        if is_step {
+            self.counter_steps += 1;
+        } else {
+            self.values.push(value);
+        }
+        self.last = time_ms.map(|ms| (ms, value));
+    }
+}
+
+impl Tally {
+    fn observe(&mut self, id: &dyn ParsedId) {
+        self.ids += 1;
+        let kind = id.kind();
+        *self.types.entry(kind.name()).or_default() += 1;
+        if let Some(ts) = id.timestamp() {
+            self.first_ms = Some(self.first_ms.map_or(ts.millis, |t| t.min(ts.millis)));
+            self.last_ms = Some(self.last_ms.map_or(ts.millis, |t| t.max(ts.millis)));
+        }
+        // A repeated ID says nothing about the generator, only about the input
+        if !self.distinct.insert(id.canonical()) {
+            return;
+        }
+        if let Some(Components::Snowflake(c)) = id.components() {
+            self.fleet.observe(&c);
+        }
+        if let Some((bits, value)) = random_part(id) {
+            self.samples
+                .entry(kind.name())
+                .or_insert(Samples {
+                    bits,
+                    values: vec![],
+                    last: None,
+                    counter_steps: 0,
+                })
+                .push(id.timestamp().map(|ts| ts.millis), value);
+        }
+    }
+
+    fn report(self, entropy: bool) -> Report {
+        Report {
+            ids: self.ids,
+            distinct: self.distinct.len(),
+            unparsed: self.unparsed,
+            types: self.types,
+            first: self.first_ms.map(|ms| Timestamp::new(ms).to_iso8601()),
+            last: self.last_ms.map(|ms| Timestamp::new(ms).to_iso8601()),
+            snowflake: self.fleet.report(),
+            entropy: entropy.then(|| {
+                self.samples
+                    .iter()
+                    .map(|(&kind, samples)| Entropy {
+                        kind,
+                        ids: samples.values.len(),
+                        bits: samples.bits,
+                        counter_steps: samples.counter_steps,
+                        checks: vec![
+                            check_balance(&samples.values, samples.bits),
+                            check_repeats(&samples.values, samples.bits),
+                            check_serial(&samples.values, samples.bits),
+                        ],
+                    })
+                    .collect()
+            }),
+            timeline: None,
+        }
+    }
+}
+
+/// IDs per bucket of creation time, empty buckets included. Without `by`,
+/// the bucket width is picked to fit the range the times cover.
+fn timeline(times: &[u64], by: Option<BucketSize>, tz: Tz) -> Result<Vec<CountRow>> {
+    let (Some(&first), Some(&last)) = (times.iter().min(), times.iter().max()) else {
+        return Ok(Vec::new());
+    };
+    let size = by.unwrap_or_else(|| fit_bucket(last - first));
+    let mut counts: BTreeMap<i64, u64> = BTreeMap::new();
+    let mut skipped = 0;
+    for &ms in times {
+        match i64::try_from(ms).map(|ms| size.start(ms, tz)) {
+            Ok(Ok(start)) => *counts.entry(start).or_default() += 1,
+            _ => skipped += 1,
+        }
+    }
+    if skipped > 0 {
+        eprintln!(
+            "Warning: {} IDs left out of the histogram; their timestamps are outside the range of dates that can be bucketed",
+            skipped
+        );
+    }
+    count_rows(size, tz, &counts, true)
+}
+
+/// The narrowest common bucket width that charts `span_ms` in at most
+/// `MAX_BARS` bars
+fn fit_bucket(span_ms: u64) -> BucketSize {
+    const SECOND: u64 = 1_000;
+    const MINUTE: u64 = 60 * SECOND;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    const WEEK: u64 = 7 * DAY;
+    match span_ms {
+        s if s < MAX_BARS * SECOND => BucketSize::Fixed(SECOND),
+        s if s < MAX_BARS * MINUTE => BucketSize::Minute,
+        s if s < MAX_BARS * HOUR => BucketSize::Hour,
+        s if s < MAX_BARS * DAY => BucketSize::Day,
+        s if s < MAX_BARS * WEEK => BucketSize::Week,
+        _ => BucketSize::Month,
+    }
+}
+
+/// The bits of `id` its generator filled from a random source, packed into
+/// the low bits, and how many there are
+fn random_part(id: &dyn ParsedId) -> Option<(u32, u128)> {
+    let bytes = id.as_bytes();
+    let as_u128 = || <[u8; 16]>::try_from(bytes.as_slice()).map(u128::from_be_bytes);
+    match (id.kind(), id.components()) {
+        // Everything but the 4 version and 2 variant bits
+        (IdKind::UuidV4, _) => {
+            let v = as_u128().ok()?;
+            let value = (v >> 80) << 74 | (v >> 64 & 0xfff) << 62 | v & ((1 << 62) - 1);
+            Some((122, value))
+        }
+        // rand_b only: RFC 9562 lets rand_a hold a counter or finer time
+        (IdKind::UuidV7, _) => Some((62, as_u128().ok()? & ((1 << 62) - 1))),
+        (_, Some(Components::Ulid(c))) => Some((80, c.random)),
+        (_, Some(Components::Ksuid(c))) => Some((128, u128::from_be_bytes(c.payload))),
+        _ => None,
+    }
+}
+
+/// Ones should make up half of all random bits, and half of each bit
+/// position across IDs
+fn check_balance(values: &[u128], bits: u32) -> Check {
+    let n = values.len() as f64;
+    let ones: Vec<u32> = (0..bits)
+        .map(|bit| values.iter().filter(|&&v| v >> bit & 1 == 1).count() as u32)
+        .collect();
+    let total: u32 = ones.iter().sum();
+    let ratio = total as f64 / (n * bits as f64);
+    let overall_z = z_score(total as f64, n * bits as f64);
+    let half = values.len() as u32 / 2;
+
+    let per_position = values.len() >= MIN_SAMPLES;
+    let biased = if per_position {
+        ones.iter()
+            .filter(|&&c| z_score(c as f64, n).abs() > MAX_Z)
+            .count()
+    } else {
+        0
+    };
+
+    if overall_z.abs() > MAX_Z || biased > 0 {
+        let mut detail = format!("{:.2}% one bits", ratio * 100.0);
+        // The most lopsided position, counted from the most significant end
+        let worst = ones
+            .iter()
+            .enumerate()
+            .max_by(|a, b| (a.1.abs_diff(half)).cmp(&b.1.abs_diff(half)));
+        if let (true, Some((bit, &count))) = (biased > 0, worst) {
+            detail.push_str(&format!(
+                "; {} of {} bit positions are biased, e.g. random bit {} is 1 in {:.0}% of IDs",
+                biased,
+                bits,
+                bits as usize - 1 - bit,
+                count as f64 / n * 100.0
+            ));
+        }
+        return Check::new("balance", Status::Fail, detail);
+    }
+    let detail = if per_position {
+        format!("{:.2}% one bits; no bit position biased", ratio * 100.0)
+    } else {
+        format!(
+            "{:.2}% one bits; too few IDs to test each bit position",
+            ratio * 100.0
+        )
+    };
+    Check::new("balance", Status::Ok, detail)
+}
+
+/// Distinct IDs should never share their random part
+fn check_repeats(values: &[u128], bits: u32) -> Check {
+    let mut seen = HashSet::with_capacity(values.len());
+    let repeats = values.iter().filter(|&&v| !seen.insert(v)).count();
+    if repeats > 0 {
+        return Check::new(
+            "repeats",
+            Status::Fail,
+            format!(
+                "{} IDs reuse the {}-bit random part of another ID; a healthy source \
+                 practically never repeats",
+                repeats, bits
+            ),
+        );
+    }
+    Check::new("repeats", Status::Ok, "No random part repeats".to_string())
+}
+
+/// Consecutive IDs' random parts should be uncorrelated. Compares the top
+/// 32 random bits of each ID with the next one's.
+fn check_serial(values: &[u128], bits: u32) -> Check {
+    if values.len() < MIN_SAMPLES {
+        return Check::new(
+            "serial",
+            Status::Skip,
+            format!("Needs at least {} distinct IDs", MIN_SAMPLES),
+        );
+    }
+    let top: Vec<f64> = values
+        .iter()
+        .map(|v| (v >> bits.saturating_sub(32)) as u32 as f64)
+        .collect();
+    let r = correlation(&top[..top.len() - 1], &top[1..]);
+    // The coefficient of independent samples has a standard error of 1/sqrt(n)
+    let limit = MAX_Z / ((top.len() - 1) as f64).sqrt();
+    if r.abs() > limit {
+        return Check::new(
+            "serial",
+            Status::Fail,
+            format!(
+                "Correlation {:.3} between consecutive IDs, beyond ±{:.3}; the random part \
+                 follows from the previous ID (a counter, or a monotonic generator)",
+                r, limit
+            ),
+        );
+    }
+    Check::new(
+        "serial",
+        Status::Ok,
+        format!("Correlation {:.3} between consecutive IDs", r),
+    )
+}
+
+/// How many standard deviations `ones` is from half of `trials` fair bits
+fn z_score(ones: f64, trials: f64) -> f64 {
+    if trials == 0.0 {
+        return 0.0;
+    }
+    (ones - trials / 2.0) / (trials / 4.0).sqrt()
+}
+
+/// Pearson correlation coefficient; 0 when either side is constant
+fn correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return 0.0;
+    }
+    cov / (var_x * var_y).sqrt()
+}
+
+fn output_plain(writer: &mut dyn Write, report: &Report, no_color: bool) -> Result<()> {
+    writeln!(writer, "IDs:       {}", report.ids)?;
+    writeln!(
+        writer,
+        "Distinct:  {} ({} duplicates)",
+        report.distinct,
+        report.ids - report.distinct
+    )?;
+    if report.unparsed > 0 {
+        writeln!(writer, "Unparsed:  {}", report.unparsed)?;
+    }
+    let types: Vec<String> = report
+        .types
+        .iter()
+        .map(|(name, n)| format!("{} {}", name, n))
+        .collect();
+    writeln!(writer, "Types:     {}", types.join(", "))?;
+    if let (Some(first), Some(last)) = (&report.first, &report.last) {
+        writeln!(writer, "First:     {}", first)?;
+        writeln!(writer, "Last:      {}", last)?;
+    }
+
+    if let Some(fleet) = &report.snowflake {
+        output_fleet(writer, fleet, no_color)?;
+    }
+
+    let Some(entropy) = &report.entropy else {
+        return Ok(());
+    };
+    if entropy.is_empty() {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "No ULID, UUIDv4, UUIDv7, or KSUID to test for randomness"
+        )?;
+    }
+    for family in entropy {
+        writeln!(writer)?;
+        write!(
+            writer,
+            "{} ({} IDs, {} random bits each",
+            family.kind, family.ids, family.bits
+        )?;
+        if family.counter_steps > 0 {
+            write!(writer, "; {} counter steps skipped", family.counter_steps)?;
+        }
+        writeln!(writer, ")")?;
+        for check in &family.checks {
+            let label = match check.status {
+                Status::Ok => "ok",
+                Status::Skip => "skip",
+                Status::Fail => "FAIL",
+            };
+            let label = format!("{:<4}", label);
+            let label = match (no_color, check.status) {
+                (true, _) => label,
+                (false, Status::Ok) => label.green().to_string(),
+                (false, Status::Skip) => label.dimmed().to_string(),
+                (false, Status::Fail) => label.red().bold().to_string(),
+            };
+            writeln!(writer, "  {}  {:<8}  {}", label, check.name, check.detail)?;
+        }
+    }
+    Ok(())
+}
+
+fn output_fleet(writer: &mut dyn Write, fleet: &FleetReport, no_color: bool) -> Result<()> {
+    writeln!(writer)?;
+    writeln!(writer, "Snowflake workers: {}", fleet.workers.len())?;
+    for (name, values) in &fleet.fields {
+        let values: Vec<String> = values
+            .iter()
+            .map(|(value, n)| format!("{} ({})", value, n))
+            .collect();
+        writeln!(writer, "  {:<14} {}", name, values.join(", "))?;
+    }
+
+    let labels: Vec<String> = fleet
+        .workers
+        .iter()
+        .map(|w| {
+            let fields: Vec<_> = w.fields.iter().map(|(&k, &v)| (k, v)).collect();
+            worker_label(&fields)
+        })
+        .collect();
+    let width = labels.iter().map(String::len).max().unwrap_or(0).max(6);
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "  {:<width$}  {:>8}  {:<24}  LAST",
+        "WORKER", "IDS", "FIRST"
+    )?;
+    for (label, worker) in labels.iter().zip(&fleet.workers) {
+        writeln!(
+            writer,
+            "  {:<width$}  {:>8}  {:<24}  {}",
+            label, worker.ids, worker.first, worker.last
+        )?;
+    }
+
+    for warning in &fleet.warnings {
+        let label = if no_color {
+            "warn".to_string()
+        } else {
+            "warn".yellow().to_string()
+        };
+        writeln!(writer)?;
+        writeln!(writer, "  {}  {}", label, warning)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::parse_id;
+
+    /// A fixed xorshift stream, so the healthy-source tests are repeatable
+    fn pseudo_random(count: usize) -> Vec<u128> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..count)
+            .map(|_| (next() as u128) << 64 | next() as u128)
+            .collect()
+    }
+
+    #[test]
+    fn test_random_part() {
+        let v4 = parse_id("550e8400-e29b-41d4-a716-446655440000", None).unwrap();
+        let (bits, value) = random_part(v4.as_ref()).unwrap();
+        assert_eq!(bits, 122);
+        assert!(value < 1 << 122);
+        assert_eq!(value & 0xffff_ffff, 0x5544_0000);
+
+        let ulid = parse_id("01ARZ3NDEKTSV4RRFFQ69G5FAV", None).unwrap();
+        assert_eq!(random_part(ulid.as_ref()).unwrap().0, 80);
+        let v1 = parse_id("6ba7b810-9dad-11d1-80b4-00c04fd430c8", None).unwrap();
+        assert!(random_part(v1.as_ref()).is_none());
+    }
+
+    #[test]
+    fn test_healthy_source_passes() {
+        let values: Vec<u128> = pseudo_random(2000).iter().map(|v| v >> 48).collect();
+        assert_eq!(check_balance(&values, 80).status, Status::Ok);
+        assert_eq!(check_repeats(&values, 80).status, Status::Ok);
+        assert_eq!(check_serial(&values, 80).status, Status::Ok);
+    }
+
+    #[test]
+    fn test_weak_sources_fail() {
+        // The top 16 of 80 bits never set, as from a truncated source
+        let truncated: Vec<u128> = pseudo_random(500).iter().map(|v| v >> 64).collect();
+        let check = check_balance(&truncated, 80);
+        assert_eq!(check.status, Status::Fail);
+        assert!(
+            check.detail.contains("16 of 80 bit positions"),
+            "{}",
+            check.detail
+        );
+
+        let mut repeated = pseudo_random(100);
+        repeated[50] = repeated[10];
+        assert_eq!(check_repeats(&repeated, 128).status, Status::Fail);
+
+        // A monotonic generator incrementing the random part
+        let counter: Vec<u128> = (0..100u128).map(|i| (i * 3) << 90).collect();
+        assert_eq!(check_serial(&counter, 128).status, Status::Fail);
+        assert_eq!(check_serial(&counter[..10], 128).status, Status::Skip);
+    }
+
+    #[test]
+    fn test_counter_steps_skipped() {
+        let mut samples = Samples {
+            bits: 80,
+            values: vec![],
+            last: None,
+            counter_steps: 0,
+        };
+        samples.push(Some(1), 1 << 70);
+        samples.push(Some(1), (1 << 70) + 1);
+        samples.push(Some(1), (1 << 70) + 2);
+        // A new millisecond, and a value far from the last
+        samples.push(Some(2), (1 << 70) + 3);
+        samples.push(Some(2), 5);
+        assert_eq!(samples.values, [1 << 70, (1 << 70) + 3, 5]);
+        assert_eq!(samples.counter_steps, 2);
+    }
+
+    fn snowflake(timestamp_ms: u64, machine_id: u64, sequence: u64) -> SnowflakeComponents {
+        SnowflakeComponents {
+            timestamp_ms,
+            epoch: 0,
+            fields: vec![
+                ("datacenter_id", 0),
+                ("machine_id", machine_id),
+                ("sequence", sequence),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_fleet() {
+        let mut fleet = Fleet::default();
+        fleet.observe(&snowflake(2_000, 3, 0));
+        fleet.observe(&snowflake(1_000, 3, 1));
+        fleet.observe(&snowflake(5_000, 7, 0));
+        let report = fleet.report().unwrap();
+        assert_eq!(
+            report.fields["machine_id"],
+            BTreeMap::from([(3, 2), (7, 1)])
+        );
+        assert_eq!(report.fields["datacenter_id"], BTreeMap::from([(0, 3)]));
+        assert_eq!(report.workers.len(), 2);
+        assert_eq!(report.workers[0].ids, 2);
+        assert_eq!(report.workers[0].first, "1970-01-01T00:00:01.000Z");
+        assert!(report.warnings.is_empty());
+
+        fleet.observe(&snowflake(6_000, 0, 0));
+        assert_eq!(
+            fleet.report().unwrap().warnings,
+            [
+                "1 IDs come from datacenter_id=0 machine_id=0, the default, alongside 2 other \
+              workers; a host may be running without its worker ID set"
+            ]
+        );
+
+        let mut fleet = Fleet::default();
+        assert!(fleet.report().is_none());
+        fleet.observe(&snowflake(1_000, 0, 0));
+        assert!(fleet.report().unwrap().warnings[0].starts_with("Every ID comes from"));
+    }
+
+    #[test]
+    fn test_report() {
+        let mut tally = Tally::default();
+        for id in [
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "01arz3ndektsv4rrffq69g5fav",
+            "550e8400-e29b-41d4-a716-446655440000",
+        ] {
+            tally.observe(parse_id(id, None).unwrap().as_ref());
+        }
+        let report = tally.report(true);
+        assert_eq!((report.ids, report.distinct), (3, 2));
+        assert_eq!(report.first.as_deref(), Some("2016-07-30T23:54:10.259Z"));
+        let entropy = report.entropy.unwrap();
+        assert_eq!(entropy.len(), 2);
+        assert_eq!((entropy[0].kind, entropy[0].ids), ("ulid", 1));
+    }
+
+    #[test]
+    fn test_timeline() {
+        let hour = 3_600_000;
+        // 2024-05-01T00:00:00Z
+        let t = 1_714_521_600_000;
+        let times = [t, t + 10, t + 3 * hour, t + 3 * hour + 1];
+
+        let rows = timeline(&times, None, Tz::UTC).unwrap();
+        let counts: Vec<u64> = rows.iter().map(|r| r.count).collect();
+        assert_eq!(rows[0].bucket, "2024-05-01T00:00:00Z");
+        assert_eq!(counts, vec![2, 0, 0, 2]);
+
+        let rows = timeline(&times, Some(BucketSize::Day), Tz::UTC).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!((rows[0].bucket.as_str(), rows[0].count), ("2024-05-01", 4));
+
+        // Out-of-range times are left out, not fatal
+        let rows = timeline(&[t, u64::MAX], Some(BucketSize::Day), Tz::UTC).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert!(timeline(&[], None, Tz::UTC).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fit_bucket() {
+        assert_eq!(fit_bucket(13), BucketSize::Fixed(1_000));
+        assert_eq!(fit_bucket(30 * 60_000), BucketSize::Minute);
+        assert_eq!(fit_bucket(3 * 86_400_000), BucketSize::Hour);
+        assert_eq!(fit_bucket(30 * 86_400_000), BucketSize::Day);
+        assert_eq!(fit_bucket(365 * 86_400_000), BucketSize::Week);
+        assert_eq!(fit_bucket(10 * 365 * 86_400_000), BucketSize::Month);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::cli::app::TuiArgs;
+use crate::core::error::{IdtError, Result};
+
+#[cfg(feature = "tui")]
+pub fn execute(args: &TuiArgs) -> Result<()> {
+    use std::io::IsTerminal;
+    if !crate::cli::output::stdout_is_terminal() || !std::io::stdin().is_terminal() {
+        return Err(IdtError::InvalidArgument(
+            "idt tui needs an interactive terminal".to_string(),
+        ));
+    }
+    crate::cli::tui::run(args.id.as_deref())
+}
+
+#[cfg(not(feature = "tui"))]
+pub fn execute(_args: &TuiArgs) -> Result<()> {
+    Err(IdtError::InvalidArgument(
+        "The TUI requires idt to be built with the `tui` feature".to_string(),
+    ))
+}
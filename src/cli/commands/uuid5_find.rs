@@ -0,0 +1,252 @@
+use crate::cli::app::{OutputFormat, Uuid5FindArgs};
+use crate::cli::output::{stdout, write_output};
+use crate::cli::progress::{Progress, Total, stdin_size};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::ParsedId;
+use crate::ids::uuid_id::{name_based_uuid, parse_namespace};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use uuid::Uuid;
+
+/// Tried when no `--ns` is given
+const STANDARD_NAMESPACES: [Uuid; 4] = [
+    Uuid::NAMESPACE_DNS,
+    Uuid::NAMESPACE_URL,
+    Uuid::NAMESPACE_OID,
+    Uuid::NAMESPACE_X500,
+];
+
+pub fn execute(
+    args: &Uuid5FindArgs,
+    output_format: Option<OutputFormat>,
+    pretty: bool,
+) -> Result<()> {
+    let namespaces = if args.namespaces.is_empty() {
+        STANDARD_NAMESPACES.to_vec()
+    } else {
+        args.namespaces
+            .iter()
+            .map(|ns| parse_namespace(ns))
+            .collect::<Result<Vec<_>>>()?
+    };
+    let targets = args
+        .uuids
+        .iter()
+        .map(|input| name_based_target(input))
+        .collect::<Result<Vec<_>>>()?;
+    let mut finder = Finder::new(&targets, namespaces);
+
+    let path = &args.candidates_file;
+    let (reader, total): (Box<dyn BufRead>, _) = if path.as_os_str() == "-" {
+        (Box::new(io::stdin().lock()), stdin_size())
+    } else {
+        let file = File::open(path).map_err(|e| {
+            IdtError::InvalidArgument(format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        let len = file.metadata().ok().map(|m| m.len());
+        (Box::new(BufReader::new(file)), len)
+    };
+
+    let mut progress = Progress::new("uuid5-find", total.map(Total::Bytes), false);
+    let mut tried = 0usize;
+    for line in reader.lines() {
+        let line = line?;
+        let name = line.trim();
+        if name.is_empty() {
+            continue;
+        }
+        tried += 1;
+        progress.tick(name.len() + 1);
+        if finder.try_name(name)? {
+            break;
+        }
+    }
+    progress.clear();
+
+    let results: Vec<FindResult> = targets.iter().map(|uuid| finder.result(uuid)).collect();
+    let missing = results.iter().filter(|r| r.name.is_none()).count();
+
+    let mut stdout = stdout();
+    if let Some(fmt) = output_format {
+        if results.len() == 1 {
+            write_output(&mut stdout, &results[0], fmt, pretty)?;
+        } else {
+            write_output(&mut stdout, &results, fmt, pretty)?;
+        }
+    } else {
+        for result in &results {
+            match (&result.namespace, &result.name) {
+                (Some(namespace), Some(name)) => {
+                    writeln!(stdout, "{}\t{}\t{}", result.uuid, namespace, name)?
+                }
+                _ => eprintln!("No candidate produces {}", result.uuid),
+            }
+        }
+    }
+
+    if missing > 0 {
+        return Err(IdtError::CheckFailed(format!(
+            "{} of {} UUIDs matched none of {} candidates",
+            missing,
+            results.len(),
+            tried
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct FindResult {
+    uuid: String,
+    found: bool,
+    /// `dns`, `url`, `oid`, `x500`, or the namespace UUID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+/// A v3 or v5 UUID; other versions aren't derived from a name
+fn name_based_target(input: &str) -> Result<Uuid> {
+    let parsed = crate::ids::ParsedUuid::parse(input)?;
+    let uuid = Uuid::from_u128(parsed.as_u128().unwrap_or_default());
+    match uuid.get_version_num() {
+        3 | 5 => Ok(uuid),
+        version => Err(IdtError::InvalidArgument(format!(
+            "{} is a version {} UUID; only v3 and v5 UUIDs are derived from a name",
+            input, version
+        ))),
+    }
+}
+
+/// The namespace as `--ns` would take it
+fn namespace_label(namespace: &Uuid) -> String {
+    match *namespace {
+        Uuid::NAMESPACE_DNS => "dns".to_string(),
+        Uuid::NAMESPACE_URL => "url".to_string(),
+        Uuid::NAMESPACE_OID => "oid".to_string(),
+        Uuid::NAMESPACE_X500 => "x500".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Hashes candidate names until every target UUID has a name
+struct Finder {
+    namespaces: Vec<Uuid>,
+    /// Versions among the targets, so v3 isn't hashed when all are v5
+    versions: Vec<u8>,
+    /// Target UUID to the namespace and name that produce it
+    matches: HashMap<Uuid, Option<(Uuid, String)>>,
+    remaining: usize,
+}
+
+impl Finder {
+    fn new(targets: &[Uuid], namespaces: Vec<Uuid>) -> Self {
+        let matches: HashMap<_, _> = targets.iter().map(|&uuid| (uuid, None)).collect();
+        let mut versions: Vec<u8> = targets
+            .iter()
+            .map(|uuid| uuid.get_version_num() as u8)
+            .collect();
+        versions.sort_unstable();
+        versions.dedup();
+        Self {
+            namespaces,
+            versions,
+            remaining: matches.len(),
+            matches,
+        }
+    }
+
+    /// Hash `name` in every namespace. Returns true once all targets are found.
+    fn try_name(&mut self, name: &str) -> Result<bool> {
+        for namespace in &self.namespaces {
+            for &version in &self.versions {
+                let uuid = name_based_uuid(version, namespace, name.as_bytes())?;
+                if let Some(slot @ None) = self.matches.get_mut(&uuid) {
+                    *slot = Some((*namespace, name.to_string()));
+                    self.remaining -= 1;
+                }
+            }
+        }
+        Ok(self.remaining == 0)
+    }
+
+    fn result(&self, uuid: &Uuid) -> FindResult {
+        let found = self.matches.get(uuid).and_then(Option::as_ref);
+        FindResult {
+            uuid: uuid.to_string(),
+            found: found.is_some(),
+            namespace: found.map(|(namespace, _)| namespace_label(namespace)),
+            name: found.map(|(_, name)| name.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // uuid5(NAMESPACE_DNS, "www.example.com") and uuid3 of the same
+    const V5_EXAMPLE: &str = "2ed6657d-e927-568b-95e1-2665a8aea6a2";
+    const V3_EXAMPLE: &str = "5df41881-3aed-3515-88a7-2f4a814cf09e";
+
+    fn make_args(uuids: Vec<&str>, candidates: PathBuf) -> Uuid5FindArgs {
+        Uuid5FindArgs {
+            uuids: uuids.into_iter().map(String::from).collect(),
+            candidates_file: candidates,
+            namespaces: vec![],
+        }
+    }
+
+    #[test]
+    fn test_finder() {
+        let targets = [
+            Uuid::parse_str(V5_EXAMPLE).unwrap(),
+            Uuid::parse_str(V3_EXAMPLE).unwrap(),
+        ];
+        let mut finder = Finder::new(&targets, STANDARD_NAMESPACES.to_vec());
+        assert_eq!(finder.versions, [3, 5]);
+        assert!(!finder.try_name("example.org").unwrap());
+        assert!(finder.try_name("www.example.com").unwrap());
+
+        let result = finder.result(&targets[0]);
+        assert!(result.found);
+        assert_eq!(result.namespace.as_deref(), Some("dns"));
+        assert_eq!(result.name.as_deref(), Some("www.example.com"));
+    }
+
+    #[test]
+    fn test_finder_other_namespace() {
+        let target = Uuid::parse_str(V5_EXAMPLE).unwrap();
+        let mut finder = Finder::new(&[target], vec![Uuid::NAMESPACE_URL]);
+        assert!(!finder.try_name("www.example.com").unwrap());
+        assert!(!finder.result(&target).found);
+    }
+
+    #[test]
+    fn test_rejects_non_name_based() {
+        assert!(name_based_target(V5_EXAMPLE).is_ok());
+        assert!(name_based_target("550e8400-e29b-41d4-a716-446655440000").is_err());
+    }
+
+    #[test]
+    fn test_execute() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names.txt");
+        std::fs::write(&path, "example.org\n\nwww.example.com\n").unwrap();
+
+        let args = make_args(vec![V5_EXAMPLE], path.clone());
+        assert!(execute(&args, None, false).is_ok());
+        assert!(execute(&args, Some(OutputFormat::Json), false).is_ok());
+
+        let mut args = make_args(vec![V5_EXAMPLE], path);
+        args.namespaces = vec!["oid".to_string()];
+        assert!(execute(&args, None, false).is_err());
+
+        let args = make_args(vec![V5_EXAMPLE], PathBuf::from("/nonexistent/idt-names"));
+        assert!(execute(&args, None, false).is_err());
+    }
+}
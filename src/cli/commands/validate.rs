@@ -1,9 +1,17 @@
 use crate::cli::app::{OutputFormat, ValidateArgs};
-use crate::cli::output::format_output;
+use crate::cli::input::{SourcedId, stream_ids, stream_total};
+use crate::cli::output::{stdout, write_output};
+use crate::cli::progress::Progress;
+use crate::core::detection::DetectionOptions;
 use crate::core::error::{IdtError, Result};
-use crate::core::id::{IdKind, ValidationResult};
+use crate::core::id::{IdKind, Timestamp, ValidationResult};
+use crate::core::predicate::{Predicate, known_fields};
+use crate::core::time::{parse_duration, parse_time};
+use chrono::Utc;
 use colored::Colorize;
-use std::io::{self, BufRead, Write};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io::Write;
 
 pub fn execute(
     args: &ValidateArgs,
@@ -11,78 +19,231 @@ pub fn execute(
     pretty: bool,
     no_color: bool,
 ) -> Result<()> {
-    let ids = collect_ids(&args.ids)?;
-
-    if ids.is_empty() {
-        return Err(IdtError::InvalidArgument(
-            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
-        ));
+    if !args.assertions.is_empty() {
+        let known = known_fields();
+        for assertion in &args.assertions {
+            assertion.check_field(&known)?;
+        }
     }
 
+    let source = args.input.source()?;
+    let ids = stream_ids(&args.ids, &source)?;
+
     let type_hint: Option<IdKind> = args.id_type;
+    let detection = args.detect.options();
+    let freshness = Freshness::from_args(args)?;
 
+    // Plain output is written as each ID is checked; structured output is
+    // one document, so it is gathered first
+    let mut stdout = stdout();
+    let mut progress = Progress::new("validate", stream_total(&ids), args.quiet);
     let mut results = Vec::new();
+    let mut count = 0usize;
     let mut all_valid = true;
-
-    for id in &ids {
-        let result = validate_id(id, type_hint, args.strict);
+    let mut seen = (args.expect_unique || args.expect_count.is_some()).then(Seen::default);
+
+    for sourced in ids {
+        let SourcedId { id, row } = sourced?;
+        count += 1;
+        progress.tick(id.len() + 1);
+        let mut result = validate_id(
+            &id,
+            type_hint,
+            &detection,
+            args.strict,
+            &args.assertions,
+            &freshness,
+        );
         if !result.valid {
             all_valid = false;
+        } else if let Some(ref mut seen) = seen
+            && let Ok(parsed) = crate::ids::parse_id_or_url(&id, type_hint, &detection)
+        {
+            let canonical = parsed.canonical();
+            if seen.insert(&canonical) && args.expect_unique {
+                result.valid = false;
+                result.error = Some(format!("Duplicate: {} appeared earlier", canonical));
+            }
         }
-        results.push(ValidateOutput {
-            input: id.clone(),
+        let output = ValidateOutput {
+            source: crate::core::url::extract_id(&id).map(|url| url.source),
+            input: id,
+            row,
             result,
-        });
+        };
+        if args.quiet {
+            continue;
+        }
+        if format.is_some() {
+            results.push(output);
+        } else {
+            output_plain(&mut stdout, &output, no_color)?;
+        }
     }
 
-    // Output
-    if !args.quiet {
-        let mut stdout = io::stdout();
+    progress.clear();
 
-        if let Some(fmt) = format {
-            let output = if results.len() == 1 {
-                format_output(&results[0], fmt, pretty)?
-            } else {
-                format_output(&results, fmt, pretty)?
-            };
-            writeln!(stdout, "{}", output)?;
+    if count == 0 {
+        return Err(IdtError::InvalidArgument(
+            "No IDs provided. Pass IDs as arguments or via stdin.".to_string(),
+        ));
+    }
+
+    if let Some(fmt) = format
+        && !args.quiet
+    {
+        if results.len() == 1 {
+            write_output(&mut stdout, &results[0], fmt, pretty)?;
         } else {
-            output_plain(&mut stdout, &results, no_color)?;
+            write_output(&mut stdout, &results, fmt, pretty)?;
         }
     }
 
-    // Return result
-    if all_valid {
+    // Duplicates are reported on their own rather than as invalid IDs
+    let mut failures = Vec::new();
+    if !all_valid {
+        failures.push("One or more IDs are invalid".to_string());
+    }
+    if let Some(ref seen) = seen {
+        if args.expect_unique && !seen.repeated.is_empty() {
+            failures.push(seen.offenders());
+        }
+        if let Some(expected) = args.expect_count
+            && seen.distinct() != expected
+        {
+            failures.push(format!(
+                "Expected {} distinct IDs, found {}",
+                expected,
+                seen.distinct()
+            ));
+        }
+    }
+
+    if failures.is_empty() {
         Ok(())
     } else {
-        Err(IdtError::ValidationError(
-            "One or more IDs are invalid".into(),
-        ))
+        Err(IdtError::CheckFailed(failures.join("; ")))
+    }
+}
+
+/// Duplicates listed in the final error before the rest are summarized
+const MAX_OFFENDERS: usize = 10;
+
+/// Canonical forms of the valid IDs so far, for `--expect-unique` and
+/// `--expect-count`
+#[derive(Default)]
+struct Seen {
+    counts: HashMap<String, usize>,
+    /// Canonical forms that appeared more than once, in order of first repeat
+    repeated: Vec<String>,
+}
+
+impl Seen {
+    /// Count one occurrence. Returns true if it appeared before.
+    fn insert(&mut self, canonical: &str) -> bool {
+        match self.counts.entry(canonical.to_string()) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() += 1;
+                if *entry.get() == 2 {
+                    self.repeated.push(canonical.to_string());
+                }
+                true
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(1);
+                false
+            }
+        }
+    }
+
+    fn distinct(&self) -> usize {
+        self.counts.len()
     }
+
+    /// The repeated IDs with how often each appeared
+    fn offenders(&self) -> String {
+        let mut listed: Vec<String> = self
+            .repeated
+            .iter()
+            .take(MAX_OFFENDERS)
+            .map(|canonical| format!("{} ({} times)", canonical, self.counts[canonical]))
+            .collect();
+        if self.repeated.len() > MAX_OFFENDERS {
+            listed.push(format!("and {} more", self.repeated.len() - MAX_OFFENDERS));
+        }
+        let noun = if self.repeated.len() == 1 {
+            "ID"
+        } else {
+            "IDs"
+        };
+        format!(
+            "{} {} appeared more than once: {}",
+            self.repeated.len(),
+            noun,
+            listed.join(", ")
+        )
+    }
+}
+
+/// Check one ID as `idt validate` does without extra flags
+#[cfg(feature = "grpc")]
+pub(crate) fn check(id: &str, type_hint: Option<IdKind>) -> ValidationResult {
+    let detection = DetectionOptions::default();
+    validate_id(id, type_hint, &detection, false, &[], &Freshness::default())
 }
 
-fn validate_id(id: &str, type_hint: Option<IdKind>, strict: bool) -> ValidationResult {
-    match crate::ids::parse_id(id, type_hint) {
+fn validate_id(
+    id: &str,
+    type_hint: Option<IdKind>,
+    detection: &DetectionOptions,
+    strict: bool,
+    assertions: &[Predicate],
+    freshness: &Freshness,
+) -> ValidationResult {
+    match crate::ids::parse_id_or_url(id, type_hint, detection) {
         Ok(parsed) => {
             let mut result = parsed.validate();
 
             // Strict mode: check canonical form
             if strict && result.valid {
                 let canonical = parsed.canonical();
-                if canonical != id {
+                let text = crate::core::url::extract_id(id).map_or(id, |url| url.id);
+                if canonical != text {
                     result.valid = false;
                     result.error = Some("Non-canonical form".to_string());
                     result.hint = Some(format!("Canonical form: {}", canonical));
                 }
             }
 
+            if result.valid && !assertions.is_empty() {
+                let inspection = parsed.inspect();
+                let failed: Vec<String> = assertions
+                    .iter()
+                    .filter(|assertion| !assertion.matches(&inspection))
+                    .map(|assertion| assertion.to_string())
+                    .collect();
+                if !failed.is_empty() {
+                    result.valid = false;
+                    result.error = Some(format!("Assertion failed: {}", failed.join(", ")));
+                }
+            }
+
+            if result.valid
+                && freshness.is_set()
+                && let Err(error) = freshness.check(parsed.timestamp())
+            {
+                result.valid = false;
+                result.error = Some(error);
+            }
+
             result
         }
         Err(e) => {
             let mut result = ValidationResult::invalid(&e.to_string());
 
-            // Add hints for common mistakes
-            if id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            // Add hints for common mistakes; a kind mismatch already says what was found
+            if matches!(e, IdtError::ValidationError(_)) {
+            } else if id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()) {
                 result.hint =
                     Some("Looks like UUID without dashes. Try adding dashes.".to_string());
             } else if id.len() == 36 && id.contains('-') {
@@ -94,78 +255,141 @@ fn validate_id(id: &str, type_hint: Option<IdKind>, strict: bool) -> ValidationR
     }
 }
 
-#[derive(serde::Serialize)]
-struct ValidateOutput {
-    input: String,
-    #[serde(flatten)]
-    result: ValidationResult,
+/// Inclusive bounds on when an ID may have been minted, each with the flag
+/// it came from for error messages
+#[derive(Default)]
+struct Freshness {
+    not_before: Option<(i64, String)>,
+    not_after: Option<(i64, String)>,
 }
 
-fn collect_ids(args: &[String]) -> Result<Vec<String>> {
-    if !args.is_empty() {
-        return Ok(args.to_vec());
+impl Freshness {
+    fn from_args(args: &ValidateArgs) -> Result<Self> {
+        let now = Utc::now();
+        let mut freshness = Freshness::default();
+        if let Some(ref t) = args.not_before {
+            freshness.not_before = Some((parse_time(t, now)?, format!("--not-before {}", t)));
+        }
+        if let Some(ref age) = args.max_age {
+            let oldest = now.timestamp_millis() - parse_duration(age)?.as_millis() as i64;
+            // The later of the two lower bounds is the one that applies
+            if freshness
+                .not_before
+                .as_ref()
+                .is_none_or(|&(t, _)| oldest > t)
+            {
+                freshness.not_before = Some((oldest, format!("--max-age {}", age)));
+            }
+        }
+        if let Some(ref t) = args.not_after {
+            freshness.not_after = Some((parse_time(t, now)?, format!("--not-after {}", t)));
+        }
+        if let (Some((lo, _)), Some((hi, _))) = (&freshness.not_before, &freshness.not_after)
+            && lo > hi
+        {
+            return Err(IdtError::InvalidArgument(
+                "--not-before and --max-age must be earlier than --not-after".to_string(),
+            ));
+        }
+        Ok(freshness)
     }
 
-    // Don't block on stdin if it's a terminal (no piped input)
-    if std::io::IsTerminal::is_terminal(&io::stdin()) {
-        return Ok(Vec::new());
+    fn is_set(&self) -> bool {
+        self.not_before.is_some() || self.not_after.is_some()
     }
 
-    // Read from stdin
-    let stdin = io::stdin();
-    let mut ids = Vec::new();
-
-    for line in stdin.lock().lines() {
-        let line = line?;
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            ids.push(trimmed.to_string());
+    fn check(&self, timestamp: Option<Timestamp>) -> std::result::Result<(), String> {
+        let Some(timestamp) = timestamp else {
+            return Err("No embedded timestamp to check freshness against".to_string());
+        };
+        let millis = timestamp.millis as i64;
+        if let Some((bound, ref flag)) = self.not_before
+            && millis < bound
+        {
+            return Err(format!(
+                "Too old: minted {}, outside {}",
+                timestamp.to_iso8601(),
+                flag
+            ));
+        }
+        if let Some((bound, ref flag)) = self.not_after
+            && millis > bound
+        {
+            return Err(format!(
+                "Too new: minted {}, outside {}",
+                timestamp.to_iso8601(),
+                flag
+            ));
         }
+        Ok(())
     }
+}
 
-    Ok(ids)
+#[derive(serde::Serialize)]
+struct ValidateOutput {
+    input: String,
+    /// Data row the ID came from (CSV and Parquet input)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    row: Option<usize>,
+    /// URL pattern the ID was extracted from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<&'static str>,
+    #[serde(flatten)]
+    result: ValidationResult,
 }
 
-fn output_plain(writer: &mut dyn Write, results: &[ValidateOutput], no_color: bool) -> Result<()> {
-    for result in results {
-        let status = if result.result.valid {
-            if no_color {
-                "valid".to_string()
-            } else {
-                "valid".green().to_string()
-            }
-        } else if no_color {
-            "invalid".to_string()
+fn output_plain(writer: &mut dyn Write, result: &ValidateOutput, no_color: bool) -> Result<()> {
+    let status = if result.result.valid {
+        if no_color {
+            "valid".to_string()
         } else {
-            "invalid".red().to_string()
-        };
-
-        let type_info = result
-            .result
-            .id_type
-            .as_ref()
-            .map(|t| format!(" ({})", t))
-            .unwrap_or_default();
-
-        writeln!(writer, "{}: {}{}", result.input, status, type_info)?;
-
-        if let Some(ref error) = result.result.error {
-            let error_msg = if no_color {
-                format!("  Error: {}", error)
-            } else {
-                format!("  {}: {}", "Error".red(), error)
-            };
-            writeln!(writer, "{}", error_msg)?;
+            "valid".green().to_string()
         }
+    } else if no_color {
+        "invalid".to_string()
+    } else {
+        "invalid".red().to_string()
+    };
+
+    let type_info = result
+        .result
+        .id_type
+        .as_ref()
+        .map(|t| format!(" ({})", t))
+        .unwrap_or_default();
+
+    let row = result
+        .row
+        .map(|r| format!("row {}: ", r))
+        .unwrap_or_default();
+
+    let source = result
+        .source
+        .map(|source| format!(" [{}]", source))
+        .unwrap_or_default();
+
+    writeln!(
+        writer,
+        "{}{}: {}{}{}",
+        row, result.input, status, type_info, source
+    )?;
+
+    if let Some(ref error) = result.result.error {
+        let error_msg = if no_color {
+            format!("  Error: {}", error)
+        } else {
+            format!("  {}: {}", "Error".red(), error)
+        };
+        writeln!(writer, "{}", error_msg)?;
+    }
 
-        if let Some(ref hint) = result.result.hint {
-            let hint_msg = if no_color {
-                format!("  Hint: {}", hint)
-            } else {
-                format!("  {}: {}", "Hint".yellow(), hint)
-            };
-            writeln!(writer, "{}", hint_msg)?;
-        }
+    if let Some(ref hint) = result.result.hint {
+        let hint_msg = if no_color {
+            format!("  Hint: {}", hint)
+        } else {
+            format!("  {}: {}", "Hint".yellow(), hint)
+        };
+        writeln!(writer, "{}", hint_msg)?;
     }
     Ok(())
 }
@@ -179,8 +403,16 @@ mod tests {
         ValidateArgs {
             ids: ids.into_iter().map(String::from).collect(),
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
             quiet: false,
             strict: false,
+            assertions: vec![],
+            max_age: None,
+            not_before: None,
+            not_after: None,
+            expect_unique: false,
+            expect_count: None,
         }
     }
 
@@ -217,8 +449,16 @@ mod tests {
         let args = ValidateArgs {
             ids: vec!["550E8400-E29B-41D4-A716-446655440000".to_string()],
             id_type: Some(IdKind::Uuid),
+            detect: Default::default(),
+            input: Default::default(),
             quiet: false,
             strict: true,
+            assertions: vec![],
+            max_age: None,
+            not_before: None,
+            not_after: None,
+            expect_unique: false,
+            expect_count: None,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_err());
@@ -229,8 +469,16 @@ mod tests {
         let args = ValidateArgs {
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: None,
+            detect: Default::default(),
+            input: Default::default(),
             quiet: true,
             strict: false,
+            assertions: vec![],
+            max_age: None,
+            not_before: None,
+            not_after: None,
+            expect_unique: false,
+            expect_count: None,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
@@ -243,6 +491,15 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_url() {
+        let detection = DetectionOptions::default();
+        let tweet = "https://twitter.com/jack/status/1234567890123456789";
+        assert!(validate_id(tweet, None, &detection, true, &[], &Freshness::default()).valid);
+        let page = "https://example.com/about";
+        assert!(!validate_id(page, None, &detection, false, &[], &Freshness::default()).valid);
+    }
+
     #[test]
     fn test_multiple_ids() {
         let args = make_args(vec![
@@ -258,17 +515,250 @@ mod tests {
         let args = ValidateArgs {
             ids: vec!["550e8400-e29b-41d4-a716-446655440000".to_string()],
             id_type: Some(IdKind::Uuid),
+            detect: Default::default(),
+            input: Default::default(),
             quiet: false,
             strict: false,
+            assertions: vec![],
+            max_age: None,
+            not_before: None,
+            not_after: None,
+            expect_unique: false,
+            expect_count: None,
         };
         let result = execute(&args, None, false, true);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_id_assertions() {
+        let detection = DetectionOptions::default();
+        // datacenter_id 20, machine_id 24
+        let id = "1234567890123456789";
+        let assertions: Vec<Predicate> = vec![
+            "machine_id in 0..32".parse().unwrap(),
+            "datacenter_id=20".parse().unwrap(),
+        ];
+        assert!(
+            validate_id(
+                id,
+                Some(IdKind::Snowflake),
+                &detection,
+                false,
+                &assertions,
+                &Freshness::default()
+            )
+            .valid
+        );
+
+        let assertions: Vec<Predicate> = vec![
+            "machine_id in 0..8".parse().unwrap(),
+            "datacenter_id=20".parse().unwrap(),
+        ];
+        let result = validate_id(
+            id,
+            Some(IdKind::Snowflake),
+            &detection,
+            false,
+            &assertions,
+            &Freshness::default(),
+        );
+        assert!(!result.valid);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Assertion failed: machine_id in 0..8")
+        );
+    }
+
+    #[test]
+    fn test_validate_id_enforces_uuid_kind() {
+        let detection = DetectionOptions::default();
+        let v4 = "550e8400-e29b-41d4-a716-446655440000";
+        assert!(
+            validate_id(
+                v4,
+                Some(IdKind::UuidV4),
+                &detection,
+                false,
+                &[],
+                &Freshness::default()
+            )
+            .valid
+        );
+        assert!(
+            validate_id(
+                v4,
+                Some(IdKind::Uuid),
+                &detection,
+                false,
+                &[],
+                &Freshness::default()
+            )
+            .valid
+        );
+
+        let result = validate_id(
+            v4,
+            Some(IdKind::UuidV7),
+            &detection,
+            false,
+            &[],
+            &Freshness::default(),
+        );
+        assert!(!result.valid);
+        assert!(
+            result
+                .error
+                .unwrap()
+                .contains("Expected uuidv7, got uuidv4")
+        );
+        assert!(result.hint.is_none());
+
+        let nil = "00000000-0000-0000-0000-000000000000";
+        assert!(
+            validate_id(
+                nil,
+                Some(IdKind::UuidNil),
+                &detection,
+                false,
+                &[],
+                &Freshness::default()
+            )
+            .valid
+        );
+        assert!(
+            !validate_id(
+                nil,
+                Some(IdKind::UuidV4),
+                &detection,
+                false,
+                &[],
+                &Freshness::default()
+            )
+            .valid
+        );
+
+        // Version nibble 4 but NCS variant
+        let ncs = "550e8400-e29b-41d4-0716-446655440000";
+        let result = validate_id(
+            ncs,
+            Some(IdKind::UuidV4),
+            &detection,
+            false,
+            &[],
+            &Freshness::default(),
+        );
+        assert!(result.error.unwrap().contains("variant is NCS"));
+    }
+
     #[test]
     fn test_validate_id_hint_uuid_without_dashes() {
         // 32 hex chars that don't parse as any known ID type get a helpful hint
-        let result = validate_id("zz0e8400e29b41d4a716446655440zzz", None, false);
+        let result = validate_id(
+            "zz0e8400e29b41d4a716446655440zzz",
+            None,
+            &DetectionOptions::default(),
+            false,
+            &[],
+            &Freshness::default(),
+        );
+        assert!(!result.valid);
+    }
+
+    #[test]
+    fn test_validate_id_freshness() {
+        let detection = DetectionOptions::default();
+        // ULID minted 2016-07-30T23:54:10.259Z
+        let ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+        let day = 86_400_000;
+        let minted = 1_469_922_850_259;
+        let window = |lo: i64, hi: i64| Freshness {
+            not_before: Some((lo, "--not-before X".to_string())),
+            not_after: Some((hi, "--not-after Y".to_string())),
+        };
+
+        let check =
+            |freshness: &Freshness| validate_id(ulid, None, &detection, false, &[], freshness);
+        assert!(check(&window(minted, minted)).valid);
+        let result = check(&window(minted + 1, minted + day));
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Too old: minted 2016-07-30T23:54:10.259Z, outside --not-before X")
+        );
+        let result = check(&window(minted - day, minted - 1));
+        assert!(result.error.unwrap().starts_with("Too new"));
+
+        // No timestamp to check
+        let v4 = "550e8400-e29b-41d4-a716-446655440000";
+        let result = validate_id(v4, None, &detection, false, &[], &window(0, i64::MAX));
         assert!(!result.valid);
+        assert!(validate_id(v4, None, &detection, false, &[], &Freshness::default()).valid);
+    }
+
+    #[test]
+    fn test_freshness_from_args() {
+        let mut args = make_args(vec![]);
+        args.max_age = Some("30d".to_string());
+        args.not_before = Some("2023-01-01".to_string());
+        let freshness = Freshness::from_args(&args).unwrap();
+        // Thirty days ago is later than 2023, so --max-age applies
+        assert_eq!(freshness.not_before.unwrap().1, "--max-age 30d");
+
+        args.not_after = Some("2022-01-01".to_string());
+        assert!(Freshness::from_args(&args).is_err());
+        args.max_age = Some("soon".to_string());
+        assert!(Freshness::from_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_seen() {
+        let mut seen = Seen::default();
+        assert!(!seen.insert("a"));
+        assert!(!seen.insert("b"));
+        assert!(seen.insert("a"));
+        assert!(seen.insert("a"));
+        assert_eq!(seen.distinct(), 2);
+        assert_eq!(
+            seen.offenders(),
+            "1 ID appeared more than once: a (3 times)"
+        );
+
+        for i in 0..12 {
+            let id = i.to_string();
+            seen.insert(&id);
+            seen.insert(&id);
+        }
+        assert!(
+            seen.offenders()
+                .starts_with("13 IDs appeared more than once: a (3 times), 0 (2 times)")
+        );
+        assert!(seen.offenders().ends_with("8 (2 times), and 3 more"));
+    }
+
+    #[test]
+    fn test_expect_unique() {
+        let ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+        let mut args = make_args(vec![ulid, "01arz3ndektsv4rrffq69g5fav"]);
+        assert!(execute(&args, None, false, true).is_ok());
+        args.expect_unique = true;
+        let error = execute(&args, None, false, true).unwrap_err().to_string();
+        assert!(
+            error.contains("1 ID appeared more than once: 01ARZ3NDEKTSV4RRFFQ69G5FAV (2 times)")
+        );
+        assert!(!error.contains("invalid"));
+
+        args.ids.truncate(1);
+        assert!(execute(&args, None, false, true).is_ok());
+    }
+
+    #[test]
+    fn test_expect_count() {
+        let ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+        let mut args = make_args(vec![ulid, ulid, "550e8400-e29b-41d4-a716-446655440000"]);
+        args.expect_count = Some(2);
+        assert!(execute(&args, None, false, true).is_ok());
+        args.expect_count = Some(3);
+        let error = execute(&args, None, false, true).unwrap_err().to_string();
+        assert!(error.contains("Expected 3 distinct IDs, found 2"));
     }
 }
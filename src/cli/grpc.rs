@@ -0,0 +1,363 @@
+//! `idt grpc`: Generate, Inspect, and Validate as a gRPC service, defined by
+//! the bundled `proto/idt/v1/idt.proto`.
+//!
+//! Each RPC does what the matching command does for a list of IDs. Requests
+//! for a bad type or option fail with `INVALID_ARGUMENT`; IDs that don't
+//! parse are reported per ID instead, so one bad ID doesn't fail the batch.
+//! With `--metrics`, every RPC also feeds the counters in [`Metrics`].
+
+use crate::cli::metrics::{self, Metrics};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdGenerator, IdKind, InspectionResult};
+use crate::ids::{GeneratorOptions, create_generator_with, parse_id_or_url};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Messages and the client and server generated from the `.proto`
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("idt.v1");
+}
+
+use proto::idt_server::{Idt, IdtServer};
+
+/// Most IDs one Generate call may ask for
+const MAX_COUNT: u32 = 10_000;
+
+/// Serve until Ctrl-C, with Prometheus metrics on `metrics_addr` if given
+pub async fn serve(addr: SocketAddr, metrics_addr: Option<SocketAddr>) -> Result<()> {
+    let service = IdtService::default();
+    if let Some(metrics_addr) = metrics_addr {
+        let listener = tokio::net::TcpListener::bind(metrics_addr).await?;
+        eprintln!("idt grpc metrics at http://{}/metrics", metrics_addr);
+        tokio::spawn(metrics::serve(listener, service.metrics.clone()));
+    }
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    eprintln!("idt grpc listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(IdtServer::new(service))
+        .serve_with_shutdown(addr, shutdown)
+        .await
+        .map_err(|e| IdtError::IoError(std::io::Error::other(e)))
+}
+
+#[derive(Debug, Default)]
+pub struct IdtService {
+    metrics: Arc<Metrics>,
+}
+
+#[tonic::async_trait]
+impl Idt for IdtService {
+    async fn generate(
+        &self,
+        request: Request<proto::GenerateRequest>,
+    ) -> std::result::Result<Response<proto::GenerateResponse>, Status> {
+        let _timer = self.metrics.timer("Generate");
+        let request = request.into_inner();
+        let kind = match request.r#type.as_str() {
+            "" => IdKind::UuidV4,
+            name => name.parse().map_err(status)?,
+        };
+        let count = match request.count {
+            0 => 1,
+            n if n > MAX_COUNT => {
+                return Err(Status::invalid_argument(format!(
+                    "count must be at most {}",
+                    MAX_COUNT
+                )));
+            }
+            n => n,
+        };
+
+        let mut options = GeneratorOptions::new();
+        if let Some(length) = request.length {
+            options = options.with_length(length as usize);
+        }
+        if let Some(ref prefix) = request.prefix {
+            options = options.with_prefix(prefix);
+        }
+        let generator: Arc<dyn IdGenerator + Send + Sync> = create_generator_with(kind, &options)
+            .map_err(status)?
+            .into();
+        let ids = crate::stream::generate_many(generator, count as usize)
+            .await
+            .map_err(status)?;
+        self.metrics.generated(kind.name(), ids.len());
+        Ok(Response::new(proto::GenerateResponse { ids }))
+    }
+
+    async fn inspect(
+        &self,
+        request: Request<proto::InspectRequest>,
+    ) -> std::result::Result<Response<proto::InspectResponse>, Status> {
+        let _timer = self.metrics.timer("Inspect");
+        let request = request.into_inner();
+        let hint = type_hint(request.r#type.as_deref())?;
+        let options = Default::default();
+        let results = request
+            .ids
+            .into_iter()
+            .map(|id| match parse_id_or_url(&id, hint, &options) {
+                Ok(parsed) => inspection(parsed.inspect()),
+                Err(e) => {
+                    self.metrics.parse_error();
+                    proto::Inspection {
+                        input: id,
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            })
+            .collect();
+        Ok(Response::new(proto::InspectResponse { results }))
+    }
+
+    async fn validate(
+        &self,
+        request: Request<proto::ValidateRequest>,
+    ) -> std::result::Result<Response<proto::ValidateResponse>, Status> {
+        let _timer = self.metrics.timer("Validate");
+        let request = request.into_inner();
+        let hint = type_hint(request.r#type.as_deref())?;
+        let results: Vec<proto::Validation> = request
+            .ids
+            .into_iter()
+            .map(|id| {
+                let result = crate::cli::commands::validate::check(&id, hint);
+                if !result.valid {
+                    self.metrics.parse_error();
+                }
+                proto::Validation {
+                    input: id,
+                    valid: result.valid,
+                    r#type: result.id_type,
+                    error: result.error,
+                    hint: result.hint,
+                }
+            })
+            .collect();
+        let all_valid = results.iter().all(|r| r.valid);
+        Ok(Response::new(proto::ValidateResponse {
+            results,
+            all_valid,
+        }))
+    }
+}
+
+fn type_hint(name: Option<&str>) -> std::result::Result<Option<IdKind>, Status> {
+    match name {
+        None | Some("") => Ok(None),
+        Some(name) => name.parse().map(Some).map_err(status),
+    }
+}
+
+fn inspection(result: InspectionResult) -> proto::Inspection {
+    let components: HashMap<String, String> = match result.components {
+        Some(Value::Object(fields)) => fields
+            .into_iter()
+            .map(|(name, value)| match value {
+                Value::String(s) => (name, s),
+                other => (name, other.to_string()),
+            })
+            .collect(),
+        _ => HashMap::new(),
+    };
+    let encodings = result.encodings;
+    proto::Inspection {
+        input: result.input,
+        error: None,
+        r#type: result.id_type,
+        canonical: result.canonical,
+        timestamp_ms: result.timestamp.map(|t| t.millis),
+        timestamp_iso: result.timestamp_iso,
+        version: result.version,
+        variant: result.variant,
+        random_bits: result.random_bits,
+        components,
+        encodings: Some(proto::Encodings {
+            hex: encodings.hex,
+            base32: encodings.base32,
+            base58: encodings.base58,
+            base64: encodings.base64,
+            int: encodings.int,
+        }),
+    }
+}
+
+/// Bad requests are the caller's to fix; a clock or sequence problem may
+/// clear up on retry; anything else is on our side
+fn status(error: IdtError) -> Status {
+    match error {
+        IdtError::InvalidArgument(_) | IdtError::UnknownType(_) => {
+            Status::invalid_argument(error.to_string())
+        }
+        IdtError::ClockMovedBackwards { .. } | IdtError::SequenceExhausted { .. } => {
+            Status::unavailable(error.to_string())
+        }
+        _ => Status::internal(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_over_the_wire() {
+        use proto::idt_client::IdtClient;
+        use tonic::transport::server::TcpIncoming;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(IdtServer::new(IdtService::default()))
+                .serve_with_incoming(TcpIncoming::from(listener)),
+        );
+
+        let mut client = IdtClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        let request = proto::GenerateRequest {
+            r#type: "uuidv7".to_string(),
+            count: 2,
+            ..Default::default()
+        };
+        let ids = client.generate(request).await.unwrap().into_inner().ids;
+        assert_eq!(ids.len(), 2);
+
+        let request = proto::ValidateRequest {
+            ids,
+            r#type: Some("uuidv7".to_string()),
+        };
+        assert!(
+            client
+                .validate(request)
+                .await
+                .unwrap()
+                .into_inner()
+                .all_valid
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate() {
+        let request = proto::GenerateRequest {
+            r#type: "ulid".to_string(),
+            count: 3,
+            ..Default::default()
+        };
+        let ids = IdtService::default()
+            .generate(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner()
+            .ids;
+        assert_eq!(ids.len(), 3);
+        assert!(ids.iter().all(|id| id.len() == 26));
+
+        let request = proto::GenerateRequest {
+            r#type: "nanoid".to_string(),
+            length: Some(10),
+            ..Default::default()
+        };
+        let ids = IdtService::default()
+            .generate(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner()
+            .ids;
+        assert_eq!(ids.len(), 1);
+        assert_eq!(ids[0].len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_generate_rejects_bad_requests() {
+        for request in [
+            proto::GenerateRequest {
+                r#type: "nope".to_string(),
+                ..Default::default()
+            },
+            proto::GenerateRequest {
+                count: MAX_COUNT + 1,
+                ..Default::default()
+            },
+        ] {
+            let err = IdtService::default()
+                .generate(Request::new(request))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics() {
+        let service = IdtService::default();
+        let request = proto::GenerateRequest {
+            r#type: "ulid".to_string(),
+            count: 3,
+            ..Default::default()
+        };
+        service.generate(Request::new(request)).await.unwrap();
+        let request = proto::InspectRequest {
+            ids: vec!["notanid".to_string()],
+            r#type: None,
+        };
+        service.inspect(Request::new(request)).await.unwrap();
+
+        let text = service.metrics.render();
+        assert!(text.contains("idt_generated_ids_total{type=\"ulid\"} 3\n"));
+        assert!(text.contains("idt_parse_errors_total 1\n"));
+        assert!(text.contains("idt_request_duration_seconds_count{method=\"Generate\"} 1\n"));
+        assert!(text.contains("idt_request_duration_seconds_count{method=\"Inspect\"} 1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_inspect() {
+        let request = proto::InspectRequest {
+            ids: vec![
+                "01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string(),
+                "notanid".to_string(),
+            ],
+            r#type: None,
+        };
+        let results = IdtService::default()
+            .inspect(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner()
+            .results;
+        assert_eq!(results[0].r#type, "ulid");
+        assert_eq!(results[0].timestamp_ms, Some(1469922850259));
+        assert_eq!(
+            results[0].components.get("random_hex").map(String::as_str),
+            Some("d6764c61efb99302bd5b")
+        );
+        assert!(results[1].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_validate() {
+        let request = proto::ValidateRequest {
+            ids: vec![
+                "550e8400-e29b-41d4-a716-446655440000".to_string(),
+                "01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string(),
+            ],
+            r#type: Some("uuid".to_string()),
+        };
+        let response = IdtService::default()
+            .validate(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.results[0].valid);
+        assert!(!response.results[1].valid);
+        assert!(!response.all_valid);
+    }
+}
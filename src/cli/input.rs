@@ -0,0 +1,750 @@
+use crate::cli::progress::{Total, stdin_size};
+use crate::core::encoding::{encode_hex, encode_shortuuid};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::IdKind;
+use crate::core::jsonpath::JsonPath;
+use serde_json::Value;
+use std::io::{self, BufRead, Read};
+
+/// Where IDs are read from, resolved from the `--input` options
+pub enum InputSource {
+    /// One ID per argument or line
+    Text,
+    /// JSON or NDJSON, selecting IDs at a path
+    Json(JsonPath),
+    /// CSV, taking IDs from one column
+    Csv(CsvOptions),
+    /// Parquet files, taking IDs from one column (header name or 1-based index)
+    Parquet(String),
+}
+
+/// How to read IDs from CSV input
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Header name or 1-based index
+    pub column: String,
+    pub delimiter: u8,
+    pub has_header: bool,
+}
+
+/// An ID read from structured input
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourcedId {
+    pub id: String,
+    /// 1-based data row for CSV and Parquet input, not counting the header
+    pub row: Option<usize>,
+}
+
+/// Pull IDs out of JSON, CSV, or Parquet input.
+///
+/// Arguments are file paths; JSON and CSV read stdin without them. JSON may
+/// be one document, concatenated documents, or NDJSON; string and number
+/// values matched by the path become IDs and `null` matches are skipped.
+/// Empty CSV and Parquet cells are skipped.
+pub fn collect_structured(args: &[String], source: &InputSource) -> Result<Vec<SourcedId>> {
+    let InputSource::Parquet(column) = source else {
+        return stream_ids(args, source)?.collect();
+    };
+    if args.is_empty() {
+        return Err(IdtError::InvalidArgument(
+            "Parquet input must be given as file paths".to_string(),
+        ));
+    }
+    let mut ids = Vec::new();
+    for path in args {
+        extract_parquet_ids(std::path::Path::new(path), column, &mut ids)?;
+    }
+    Ok(ids)
+}
+
+/// IDs read lazily, one at a time
+pub type IdStream<'a> = Box<dyn Iterator<Item = Result<SourcedId>> + 'a>;
+
+/// IDs from the arguments, or from stdin without arguments.
+///
+/// Plain text is read a line at a time, CSV a row at a time, and JSON a
+/// document (or NDJSON line) at a time, so results can be written as each ID
+/// is processed and memory stays flat however long the input is. Parquet is
+/// collected first, as in [`collect_structured`].
+pub fn stream_ids<'a>(args: &'a [String], source: &InputSource) -> Result<IdStream<'a>> {
+    match source {
+        InputSource::Text => {}
+        InputSource::Json(path) => {
+            let path = path.clone();
+            return file_stream(args, move |reader| {
+                Ok(Box::new(json_ids(reader, path.clone())))
+            });
+        }
+        InputSource::Csv(options) => {
+            let options = options.clone();
+            return file_stream(args, move |reader| {
+                Ok(Box::new(csv_rows(reader, &options)?))
+            });
+        }
+        InputSource::Parquet(_) => {
+            return Ok(Box::new(
+                collect_structured(args, source)?.into_iter().map(Ok),
+            ));
+        }
+    }
+    if !args.is_empty() {
+        return Ok(Box::new(args.iter().map(|id| {
+            Ok(SourcedId {
+                id: id.clone(),
+                row: None,
+            })
+        })));
+    }
+
+    // Don't block on stdin if it's a terminal (no piped input)
+    if std::io::IsTerminal::is_terminal(&io::stdin()) {
+        return Ok(Box::new(std::iter::empty()));
+    }
+    Ok(Box::new(text_lines(io::stdin().lock())))
+}
+
+/// What a run over `ids` can measure its progress against: the count when
+/// it is known up front, else the size of a file redirected to stdin
+pub fn stream_total(ids: &IdStream<'_>) -> Option<Total> {
+    match ids.size_hint() {
+        (low, Some(high)) if low == high => Some(Total::Items(low as u64)),
+        _ => stdin_size().map(Total::Bytes),
+    }
+}
+
+/// Trimmed, non-blank lines of `reader`, or NUL-terminated records (as from
+/// `find -print0` or `idt gen --print0`) when the first block read holds a NUL
+pub fn text_lines<'a>(reader: impl BufRead + 'a) -> impl Iterator<Item = Result<SourcedId>> + 'a {
+    numbered_lines(reader).map(|record| record.map(|(_, id)| SourcedId { id, row: None }))
+}
+
+/// Like [`text_lines`], with each record's 1-based line number
+pub fn numbered_lines<'a>(
+    mut reader: impl BufRead + 'a,
+) -> impl Iterator<Item = Result<(usize, String)>> + 'a {
+    let separator = match reader.fill_buf() {
+        Ok(buf) if buf.contains(&0) => 0,
+        _ => b'\n',
+    };
+    reader
+        .split(separator)
+        .enumerate()
+        .filter_map(|(i, record)| match record {
+            Ok(bytes) => {
+                let line = match String::from_utf8(bytes) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e).into()));
+                    }
+                };
+                let id = line.trim();
+                (!id.is_empty()).then(|| Ok((i + 1, id.to_string())))
+            }
+            Err(e) => Some(Err(e.into())),
+        })
+}
+
+/// The text file at `path` (`-` = stdin), for [`text_lines`] or
+/// [`numbered_lines`]
+pub fn open_text(path: &std::path::Path) -> Result<Box<dyn BufRead>> {
+    if path.as_os_str() == "-" {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    Ok(Box::new(io::BufReader::new(std::fs::File::open(path)?)))
+}
+
+/// Like [`collect_structured`], without row numbers
+pub fn collect_structured_ids(args: &[String], source: &InputSource) -> Result<Vec<String>> {
+    Ok(collect_structured(args, source)?
+        .into_iter()
+        .map(|s| s.id)
+        .collect())
+}
+
+/// Resolve a column by header name, falling back to a 1-based index
+fn resolve_column<'a>(
+    column: &str,
+    mut names: impl Iterator<Item = &'a str>,
+    what: &str,
+) -> Result<usize> {
+    if let Some(index) = names.position(|name| name.trim() == column) {
+        return Ok(index);
+    }
+    match column.parse::<usize>() {
+        Ok(n) if n >= 1 => Ok(n - 1),
+        _ => Err(IdtError::InvalidArgument(format!(
+            "{} column '{}' not found",
+            what, column
+        ))),
+    }
+}
+
+/// IDs read by `ids` from each file in `args`, one after another and each
+/// opened once reached, or from stdin without arguments
+fn file_stream<'a>(
+    args: &'a [String],
+    ids: impl Fn(Box<dyn Read>) -> Result<IdStream<'static>> + 'a,
+) -> Result<IdStream<'a>> {
+    if args.is_empty() {
+        // Don't block on stdin if it's a terminal (no piped input)
+        if std::io::IsTerminal::is_terminal(&io::stdin()) {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        return ids(Box::new(io::stdin().lock()));
+    }
+
+    Ok(Box::new(args.iter().flat_map(move |path| {
+        match std::fs::File::open(path)
+            .map_err(IdtError::from)
+            .and_then(|file| ids(Box::new(file)))
+        {
+            Ok(ids) => ids,
+            Err(e) => Box::new(std::iter::once(Err(e))),
+        }
+    })))
+}
+
+/// IDs in one column of `reader`, read a row at a time
+fn csv_rows(
+    reader: impl Read + 'static,
+    options: &CsvOptions,
+) -> Result<impl Iterator<Item = Result<SourcedId>> + 'static> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(options.has_header)
+        .flexible(true)
+        .from_reader(reader);
+
+    let index = if options.has_header {
+        let headers = reader.headers().map_err(csv_error)?;
+        resolve_column(&options.column, headers.iter(), "CSV")?
+    } else {
+        resolve_column(&options.column, std::iter::empty(), "CSV").map_err(|_| {
+            IdtError::InvalidArgument(format!(
+                "CSV column '{}' not found (use a 1-based index with --no-header)",
+                options.column
+            ))
+        })?
+    };
+
+    Ok(reader
+        .into_records()
+        .enumerate()
+        .filter_map(move |(i, record)| {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => return Some(Err(csv_error(e))),
+            };
+            let cell = record.get(index).map(str::trim).filter(|c| !c.is_empty())?;
+            Some(Ok(SourcedId {
+                id: cell.to_string(),
+                row: Some(i + 1),
+            }))
+        }))
+}
+
+fn csv_error(e: csv::Error) -> IdtError {
+    IdtError::InvalidArgument(format!("Invalid CSV input: {}", e))
+}
+
+#[cfg(feature = "parquet")]
+fn extract_parquet_ids(
+    path: &std::path::Path,
+    column: &str,
+    ids: &mut Vec<SourcedId>,
+) -> Result<()> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+    use parquet::schema::types::Type;
+    use std::sync::Arc;
+
+    let invalid = |e: parquet::errors::ParquetError| {
+        IdtError::InvalidArgument(format!("Invalid Parquet file {}: {}", path.display(), e))
+    };
+
+    let reader = SerializedFileReader::new(std::fs::File::open(path)?).map_err(invalid)?;
+    let schema = reader.metadata().file_metadata().schema();
+    let fields = schema.get_fields();
+    let index = resolve_column(column, fields.iter().map(|f| f.name()), "Parquet")?;
+    let field = fields.get(index).ok_or_else(|| {
+        IdtError::InvalidArgument(format!(
+            "Parquet column {} is out of range ({} columns)",
+            index + 1,
+            fields.len()
+        ))
+    })?;
+    if !field.is_primitive() {
+        return Err(IdtError::InvalidArgument(format!(
+            "Parquet column '{}' is not a primitive column",
+            field.name()
+        )));
+    }
+
+    // Only decode the one column
+    let projection = Type::group_type_builder(schema.name())
+        .with_fields(vec![Arc::clone(field)])
+        .build()
+        .map_err(invalid)?;
+
+    for (row, record) in reader
+        .get_row_iter(Some(projection))
+        .map_err(invalid)?
+        .enumerate()
+    {
+        let record = record.map_err(invalid)?;
+        let Some((_, value)) = record.get_column_iter().next() else {
+            continue;
+        };
+        let id = match value {
+            Field::Null => continue,
+            Field::Str(s) => s.trim().to_string(),
+            Field::Bytes(bytes) => match std::str::from_utf8(bytes.data()) {
+                Ok(s) => s.trim().to_string(),
+                Err(_) => hex::encode(bytes.data()),
+            },
+            Field::Int(v) => v.to_string(),
+            Field::Long(v) => v.to_string(),
+            Field::UInt(v) => v.to_string(),
+            Field::ULong(v) => v.to_string(),
+            other => {
+                return Err(IdtError::InvalidArgument(format!(
+                    "Parquet column '{}' holds {} values, not IDs",
+                    field.name(),
+                    other
+                )));
+            }
+        };
+        if !id.is_empty() {
+            ids.push(SourcedId {
+                id,
+                row: Some(row + 1),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn extract_parquet_ids(
+    _path: &std::path::Path,
+    _column: &str,
+    _ids: &mut Vec<SourcedId>,
+) -> Result<()> {
+    Err(IdtError::InvalidArgument(
+        "Parquet input requires idt to be built with the `parquet` feature".to_string(),
+    ))
+}
+
+/// How to cut raw bytes into IDs: fixed-width `kind` values, one every
+/// `stride` bytes. Bytes past the value in each record are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinaryLayout {
+    pub kind: IdKind,
+    pub stride: usize,
+}
+
+impl BinaryLayout {
+    /// Resolve the layout from `--id-type` and `--stride`. Without a type,
+    /// 16-byte records are UUIDs, 12-byte ObjectIds, and 20-byte KSUIDs.
+    pub fn resolve(hint: Option<IdKind>, stride: Option<usize>) -> Result<Self> {
+        let kind = match (hint, stride.unwrap_or(16)) {
+            (Some(kind), _) => kind,
+            (None, 16) => IdKind::Uuid,
+            (None, 12) => IdKind::ObjectId,
+            (None, 20) => IdKind::Ksuid,
+            (None, n) => {
+                return Err(IdtError::InvalidArgument(format!(
+                    "No default ID type for {}-byte records; pass --id-type",
+                    n
+                )));
+            }
+        };
+        let width = binary_width(kind).ok_or_else(|| {
+            IdtError::InvalidArgument(format!(
+                "{} has no fixed binary form. --binary reads UUID, ULID, ShortUUID, \
+                 ObjectId, XID, KSUID, Snowflake, and TSID",
+                kind.name()
+            ))
+        })?;
+        let stride = stride.unwrap_or(width);
+        if stride < width {
+            return Err(IdtError::InvalidArgument(format!(
+                "--stride {} is shorter than a {} ({} bytes)",
+                stride,
+                kind.name(),
+                width
+            )));
+        }
+        Ok(Self { kind, stride })
+    }
+
+    /// The canonical text of each whole record in `data`. A trailing
+    /// partial record is left out.
+    pub fn decode(&self, data: &[u8]) -> Vec<String> {
+        let width = binary_width(self.kind).expect("resolve checked the width");
+        data.chunks_exact(self.stride)
+            .map(|record| decode_record(self.kind, &record[..width]))
+            .collect()
+    }
+}
+
+/// Bytes in the raw form of `kind`, for kinds that have a fixed one
+fn binary_width(kind: IdKind) -> Option<usize> {
+    match kind {
+        _ if kind.is_uuid() => Some(16),
+        IdKind::Ulid | IdKind::ShortUuid => Some(16),
+        IdKind::ObjectId | IdKind::Xid => Some(12),
+        IdKind::Ksuid => Some(20),
+        IdKind::Snowflake | IdKind::Tsid => Some(8),
+        _ => None,
+    }
+}
+
+/// The canonical text of one raw, big-endian `kind` value
+fn decode_record(kind: IdKind, bytes: &[u8]) -> String {
+    fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+        bytes.try_into().expect("record has the kind's width")
+    }
+    match kind {
+        IdKind::Ulid => ulid::Ulid::from_bytes(array(bytes)).to_string(),
+        IdKind::ShortUuid => encode_shortuuid(bytes),
+        IdKind::ObjectId => encode_hex(bytes),
+        IdKind::Xid => crate::ids::xid_id::xid_encode(&array(bytes)),
+        IdKind::Ksuid => crate::ids::ksuid_id::encode_base62(&array(bytes)),
+        IdKind::Snowflake => u64::from_be_bytes(array(bytes)).to_string(),
+        IdKind::Tsid => crate::ids::tsid_id::tsid_encode(u64::from_be_bytes(array(bytes))),
+        _ => uuid::Uuid::from_bytes(array(bytes)).to_string(),
+    }
+}
+
+/// IDs at `path` in each JSON document of `reader`, parsing one document (or
+/// NDJSON line) at a time and stopping at the first malformed one
+fn json_ids(
+    reader: impl Read + 'static,
+    path: JsonPath,
+) -> impl Iterator<Item = Result<SourcedId>> + 'static {
+    let mut failed = false;
+    serde_json::Deserializer::from_reader(io::BufReader::new(reader))
+        .into_iter::<Value>()
+        .map_while(move |doc| {
+            if failed {
+                return None;
+            }
+            failed = doc.is_err();
+            Some(doc)
+        })
+        .flat_map(move |doc| {
+            let ids: Vec<Result<SourcedId>> = match doc {
+                Ok(doc) => path.select(&doc).into_iter().filter_map(json_id).collect(),
+                Err(e) => vec![Err(e.into())],
+            };
+            ids
+        })
+}
+
+fn json_id(value: &Value) -> Option<Result<SourcedId>> {
+    let id = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Null => return None,
+        other => {
+            return Some(Err(IdtError::InvalidArgument(format!(
+                "Path matched a non-ID value: {}",
+                other
+            ))));
+        }
+    };
+    Some(Ok(SourcedId { id, row: None }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn just_ids(ids: Vec<SourcedId>) -> Vec<String> {
+        ids.into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn test_text_lines() {
+        let ids: Vec<String> = text_lines("  a \n\n\tb\nc".as_bytes())
+            .map(|id| id.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+
+        let ids: Vec<String> = text_lines("a\0b c\0\n\0".as_bytes())
+            .map(|id| id.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a", "b c"]);
+        assert!(text_lines(&b"\xff\n"[..]).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_numbered_lines() {
+        let lines: Vec<(usize, String)> = numbered_lines("a\n\n b\n".as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, [(1, "a".to_string()), (3, "b".to_string())]);
+
+        let lines: Vec<(usize, String)> = numbered_lines("a\0\0b\0".as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, [(1, "a".to_string()), (3, "b".to_string())]);
+    }
+
+    #[test]
+    fn test_stream_ids_from_args() {
+        let args = vec!["x".to_string(), "y".to_string()];
+        let ids: Vec<String> = stream_ids(&args, &InputSource::Text)
+            .unwrap()
+            .map(|id| id.unwrap().id)
+            .collect();
+        assert_eq!(ids, args);
+
+        let ids = stream_ids(&args, &InputSource::Text).unwrap();
+        assert_eq!(stream_total(&ids), Some(Total::Items(2)));
+    }
+
+    fn extract_ids(text: &str, path: &str) -> Result<Vec<SourcedId>> {
+        let path = JsonPath::parse(path).unwrap();
+        json_ids(std::io::Cursor::new(text.to_string()), path).collect()
+    }
+
+    #[test]
+    fn test_extract_from_document() {
+        let ids = extract_ids(
+            r#"{"items": [{"id": "01ARZ3NDEKTSV4RRFFQ69G5FAV"}, {"id": 1234567890123456789}, {"id": null}]}"#,
+            "$.items[*].id",
+        )
+        .unwrap();
+        assert_eq!(
+            just_ids(ids),
+            ["01ARZ3NDEKTSV4RRFFQ69G5FAV", "1234567890123456789"]
+        );
+    }
+
+    #[test]
+    fn test_extract_from_ndjson() {
+        let ids = extract_ids(
+            "{\"request_id\": \"a\"}\n{\"request_id\": \"b\"}\n{\"other\": 1}\n",
+            "request_id",
+        )
+        .unwrap();
+        assert_eq!(just_ids(ids), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_extract_errors() {
+        assert!(extract_ids(r#"{"items": [1, 2]}"#, "$.items").is_err());
+        assert!(extract_ids(r#"{"items": "#, "$.items").is_err());
+
+        // NDJSON is read a line at a time: IDs before a bad line still arrive
+        let path = JsonPath::parse("id").unwrap();
+        let mut ids = json_ids(
+            std::io::Cursor::new("{\"id\": \"a\"}\n{\"id\"\n{\"id\": \"b\"}\n"),
+            path,
+        );
+        assert_eq!(ids.next().unwrap().unwrap().id, "a");
+        assert!(ids.next().unwrap().is_err());
+        assert!(ids.next().is_none());
+    }
+
+    #[test]
+    fn test_json_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.json");
+        let second = dir.path().join("b.ndjson");
+        std::fs::write(&first, r#"{"items": [{"id": "x"}, {"id": "y"}]}"#).unwrap();
+        std::fs::write(&second, "{\"items\": [{\"id\": \"z\"}]}\n").unwrap();
+        let args = vec![
+            first.to_string_lossy().into_owned(),
+            second.to_string_lossy().into_owned(),
+        ];
+        let source = InputSource::Json(JsonPath::parse("$.items[*].id").unwrap());
+        let ids = collect_structured(&args, &source).unwrap();
+        assert_eq!(just_ids(ids), ["x", "y", "z"]);
+    }
+
+    fn csv_ids(input: &str, column: &str, has_header: bool) -> Result<Vec<SourcedId>> {
+        let options = CsvOptions {
+            column: column.to_string(),
+            delimiter: b',',
+            has_header,
+        };
+        csv_rows(std::io::Cursor::new(input.to_string()), &options)?.collect()
+    }
+
+    #[test]
+    fn test_csv_by_name_and_index() {
+        let input = "name,user_id\nalice,01ARZ3NDEKTSV4RRFFQ69G5FAV\nbob,\n\"c, d\",507f1f77bcf86cd799439011\n";
+        let expected = ["01ARZ3NDEKTSV4RRFFQ69G5FAV", "507f1f77bcf86cd799439011"];
+        assert_eq!(just_ids(csv_ids(input, "user_id", true).unwrap()), expected);
+        let ids = csv_ids(input, "2", true).unwrap();
+        assert_eq!(ids[1].row, Some(3));
+        assert_eq!(just_ids(ids), expected);
+    }
+
+    #[test]
+    fn test_csv_without_header() {
+        let input = "a,1\nb,2\n";
+        assert_eq!(just_ids(csv_ids(input, "1", false).unwrap()), ["a", "b"]);
+        assert!(csv_ids(input, "name", false).is_err());
+    }
+
+    #[test]
+    fn test_csv_unknown_column() {
+        assert!(csv_ids("id\nx\n", "user_id", true).is_err());
+        assert!(csv_ids("id\nx\n", "0", true).is_err());
+    }
+
+    #[test]
+    fn test_csv_delimiter() {
+        let options = CsvOptions {
+            column: "id".to_string(),
+            delimiter: b'\t',
+            has_header: true,
+        };
+        let ids = csv_rows(&b"n\tid\nx\tabc\n"[..], &options)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(just_ids(ids), ["abc"]);
+    }
+
+    #[test]
+    fn test_csv_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<String> = ["a.csv", "b.csv"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, format!("id\n{}-1\n{}-2\n", name, name)).unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .collect();
+        let source = InputSource::Csv(CsvOptions {
+            column: "id".to_string(),
+            delimiter: b',',
+            has_header: true,
+        });
+        let ids: Vec<SourcedId> = stream_ids(&paths, &source)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            ids.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            ["a.csv-1", "a.csv-2", "b.csv-1", "b.csv-2"]
+        );
+        assert_eq!(ids[3].row, Some(2));
+
+        let missing = vec![dir.path().join("nope.csv").to_string_lossy().into_owned()];
+        assert!(
+            stream_ids(&missing, &source)
+                .unwrap()
+                .next()
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_column() {
+        use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::parser::parse_message_type;
+        use std::sync::Arc;
+
+        let schema = Arc::new(
+            parse_message_type(
+                "message schema { REQUIRED INT64 n; OPTIONAL BYTE_ARRAY id (UTF8); }",
+            )
+            .unwrap(),
+        );
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut writer =
+            SerializedFileWriter::new(file.reopen().unwrap(), schema, Default::default()).unwrap();
+        let mut group = writer.next_row_group().unwrap();
+
+        let mut col = group.next_column().unwrap().unwrap();
+        col.typed::<Int64Type>()
+            .write_batch(&[1, 2, 3], None, None)
+            .unwrap();
+        col.close().unwrap();
+
+        let values = [
+            ByteArray::from("01ARZ3NDEKTSV4RRFFQ69G5FAV"),
+            ByteArray::from("not-an-id"),
+        ];
+        let mut col = group.next_column().unwrap().unwrap();
+        col.typed::<ByteArrayType>()
+            .write_batch(&values, Some(&[1, 0, 1]), None)
+            .unwrap();
+        col.close().unwrap();
+        group.close().unwrap();
+        writer.close().unwrap();
+
+        let mut ids = Vec::new();
+        extract_parquet_ids(file.path(), "id", &mut ids).unwrap();
+        assert_eq!(
+            ids,
+            [
+                SourcedId {
+                    id: "01ARZ3NDEKTSV4RRFFQ69G5FAV".to_string(),
+                    row: Some(1)
+                },
+                SourcedId {
+                    id: "not-an-id".to_string(),
+                    row: Some(3)
+                },
+            ]
+        );
+
+        let mut ids = Vec::new();
+        extract_parquet_ids(file.path(), "1", &mut ids).unwrap();
+        assert_eq!(just_ids(ids), ["1", "2", "3"]);
+
+        assert!(extract_parquet_ids(file.path(), "missing", &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_binary_layout_defaults() {
+        let layout = |hint, stride| BinaryLayout::resolve(hint, stride);
+        assert_eq!(layout(None, None).unwrap().kind, IdKind::Uuid);
+        assert_eq!(layout(None, Some(12)).unwrap().kind, IdKind::ObjectId);
+        assert_eq!(layout(None, Some(20)).unwrap().kind, IdKind::Ksuid);
+        assert!(layout(None, Some(24)).is_err());
+        assert_eq!(
+            layout(Some(IdKind::Ulid), Some(24)).unwrap(),
+            BinaryLayout {
+                kind: IdKind::Ulid,
+                stride: 24
+            }
+        );
+        assert!(layout(Some(IdKind::Ksuid), Some(16)).is_err());
+        assert!(layout(Some(IdKind::NanoId), None).is_err());
+    }
+
+    #[test]
+    fn test_binary_decode() {
+        let uuid = uuid::Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let mut data = uuid.as_bytes().to_vec();
+        data.extend_from_slice(uuid.as_bytes());
+        data.extend_from_slice(&[0xAA; 5]);
+
+        let layout = BinaryLayout::resolve(None, None).unwrap();
+        assert_eq!(layout.decode(&data), [uuid.to_string(), uuid.to_string()]);
+
+        // 18-byte records: a ULID followed by two bytes of padding
+        let layout = BinaryLayout::resolve(Some(IdKind::Ulid), Some(18)).unwrap();
+        let padded: Vec<u8> = [uuid.as_bytes().as_slice(), &[0, 0]].concat().repeat(2);
+        let ulid = ulid::Ulid::from_bytes(*uuid.as_bytes()).to_string();
+        assert_eq!(layout.decode(&padded), [ulid.clone(), ulid]);
+
+        let layout = BinaryLayout::resolve(Some(IdKind::Snowflake), None).unwrap();
+        assert_eq!(
+            layout.decode(&1234567890123456789u64.to_be_bytes()),
+            ["1234567890123456789"]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Diagnostic logging on stderr.
+//!
+//! idt reports what it decides through `tracing`: detection candidates and
+//! the type chosen (debug), Snowflake layouts (debug), and per-ID parse
+//! timing (trace). `-v` and `-vv` turn these on for the CLI; otherwise
+//! `RUST_LOG` is honored with the usual filter syntax, e.g.
+//! `RUST_LOG=idt::core::detection=debug`. Programs embedding idt as a
+//! library receive the same events through their own subscriber.
+
+use std::io::{self, IsTerminal};
+use tracing_subscriber::EnvFilter;
+
+/// Install a stderr logger for `verbose` (the number of `-v` flags)
+pub fn init(verbose: u8, no_color: bool) {
+    let directives = filter(verbose, std::env::var("RUST_LOG").ok().as_deref());
+    let filter = EnvFilter::try_new(&directives).unwrap_or_else(|e| {
+        eprintln!("Warning: ignoring invalid RUST_LOG: {}", e);
+        EnvFilter::new(filter(verbose, None))
+    });
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(!no_color && io::stderr().is_terminal())
+        .without_time()
+        .try_init();
+}
+
+/// `-v` flags win over `RUST_LOG`; with neither, only warnings show
+fn filter(verbose: u8, rust_log: Option<&str>) -> String {
+    match (verbose, rust_log) {
+        (0, Some(env)) if !env.trim().is_empty() => env.to_string(),
+        (0, _) => "warn".to_string(),
+        (1, _) => "warn,idt=debug".to_string(),
+        _ => "warn,idt=trace".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        assert_eq!(filter(0, None), "warn");
+        assert_eq!(filter(0, Some("")), "warn");
+        assert_eq!(filter(0, Some("idt=trace")), "idt=trace");
+        assert_eq!(filter(1, Some("idt=trace")), "warn,idt=debug");
+        assert_eq!(filter(3, None), "warn,idt=trace");
+        for verbose in 0..3 {
+            assert!(EnvFilter::try_new(filter(verbose, None)).is_ok());
+        }
+    }
+}
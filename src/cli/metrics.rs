@@ -0,0 +1,258 @@
+//! Prometheus metrics for `idt grpc --metrics`, served as plain text at
+//! `GET /metrics` on their own HTTP listener.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 9] = [0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0];
+
+/// Most bytes of request head read before answering
+const MAX_REQUEST: usize = 8192;
+
+/// Counters shared by every RPC of one server
+#[derive(Debug, Default)]
+pub struct Metrics {
+    generated: Mutex<BTreeMap<&'static str, u64>>,
+    parse_errors: AtomicU64,
+    latency: Mutex<BTreeMap<&'static str, Histogram>>,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Cumulative, one per entry of `LATENCY_BUCKETS`
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+/// Records how long an RPC took when dropped
+pub struct Timer<'a> {
+    metrics: &'a Metrics,
+    method: &'static str,
+    started: Instant,
+}
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        self.metrics.observe(self.method, self.started.elapsed());
+    }
+}
+
+impl Metrics {
+    pub fn generated(&self, type_name: &'static str, count: usize) {
+        *self
+            .generated
+            .lock()
+            .expect("metrics lock poisoned")
+            .entry(type_name)
+            .or_default() += count as u64;
+    }
+
+    pub fn parse_error(&self) {
+        self.parse_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Time `method` until the returned guard is dropped
+    pub fn timer(&self, method: &'static str) -> Timer<'_> {
+        Timer {
+            metrics: self,
+            method,
+            started: Instant::now(),
+        }
+    }
+
+    fn observe(&self, method: &'static str, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let mut latency = self.latency.lock().expect("metrics lock poisoned");
+        let histogram = latency.entry(method).or_default();
+        for (count, &bound) in histogram.buckets.iter_mut().zip(&LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *count += 1;
+            }
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    /// The Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP idt_generated_ids_total IDs generated, by type\n");
+        out.push_str("# TYPE idt_generated_ids_total counter\n");
+        for (type_name, count) in self.generated.lock().expect("metrics lock poisoned").iter() {
+            let _ = writeln!(
+                out,
+                "idt_generated_ids_total{{type=\"{}\"}} {}",
+                type_name, count
+            );
+        }
+
+        out.push_str(
+            "# HELP idt_parse_errors_total IDs that Inspect could not parse or Validate rejected\n",
+        );
+        out.push_str("# TYPE idt_parse_errors_total counter\n");
+        let _ = writeln!(
+            out,
+            "idt_parse_errors_total {}",
+            self.parse_errors.load(Ordering::Relaxed)
+        );
+
+        out.push_str(
+            "# HELP idt_sequence_exhausted_total Snowflake IDs that found their sequence used up\n",
+        );
+        out.push_str("# TYPE idt_sequence_exhausted_total counter\n");
+        let _ = writeln!(
+            out,
+            "idt_sequence_exhausted_total {}",
+            crate::ids::sequence_exhaustions()
+        );
+
+        out.push_str("# HELP idt_request_duration_seconds Time to answer an RPC\n");
+        out.push_str("# TYPE idt_request_duration_seconds histogram\n");
+        for (method, histogram) in self.latency.lock().expect("metrics lock poisoned").iter() {
+            for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "idt_request_duration_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    method, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "idt_request_duration_seconds_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                method, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "idt_request_duration_seconds_sum{{method=\"{}\"}} {}",
+                method, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "idt_request_duration_seconds_count{{method=\"{}\"}} {}",
+                method, histogram.count
+            );
+        }
+        out
+    }
+}
+
+/// Answer `GET /metrics` on `listener` until the task is dropped
+pub async fn serve(listener: TcpListener, metrics: std::sync::Arc<Metrics>) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &metrics).await {
+                tracing::debug!(error = %e, "metrics request failed");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line
+        .next()
+        .unwrap_or_default()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+    let (status, content_type, body) = if method == "GET" && path == "/metrics" {
+        ("200 OK", "text/plain; version=0.0.4", metrics.render())
+    } else {
+        (
+            "404 Not Found",
+            "text/plain",
+            "Not found; try /metrics\n".to_string(),
+        )
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        metrics.generated("ulid", 3);
+        metrics.generated("ulid", 2);
+        metrics.parse_error();
+        metrics.observe("Generate", Duration::from_millis(2));
+
+        let text = metrics.render();
+        assert!(text.contains("idt_generated_ids_total{type=\"ulid\"} 5\n"));
+        assert!(text.contains("idt_parse_errors_total 1\n"));
+        assert!(text.contains("idt_sequence_exhausted_total "));
+        assert!(
+            text.contains(
+                "idt_request_duration_seconds_bucket{method=\"Generate\",le=\"0.001\"} 0\n"
+            )
+        );
+        assert!(
+            text.contains(
+                "idt_request_duration_seconds_bucket{method=\"Generate\",le=\"0.005\"} 1\n"
+            )
+        );
+        assert!(text.contains("idt_request_duration_seconds_count{method=\"Generate\"} 1\n"));
+    }
+
+    async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let metrics = Arc::new(Metrics::default());
+        metrics.generated("uuidv7", 1);
+        tokio::spawn(serve(listener, metrics));
+
+        let response = get(addr, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("idt_generated_ids_total{type=\"uuidv7\"} 1\n"));
+        assert!(
+            get(addr, "/")
+                .await
+                .starts_with("HTTP/1.1 404 Not Found\r\n")
+        );
+    }
+}
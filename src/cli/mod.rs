@@ -1,5 +1,16 @@
 pub mod app;
+pub mod atomic_file;
 pub mod commands;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod input;
+pub mod logging;
+#[cfg(feature = "grpc")]
+pub mod metrics;
 pub mod output;
+pub mod progress;
+pub mod qr;
+#[cfg(feature = "tui")]
+pub mod tui;
 
 pub use app::{Cli, Commands, OutputFormat};
@@ -1,7 +1,105 @@
 use crate::cli::app::OutputFormat;
-use crate::core::error::Result;
+use crate::cli::atomic_file::AtomicFile;
+use crate::core::error::{IdtError, Result};
+use clap::ValueEnum;
 use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Mutex, MutexGuard};
 
+/// The `--output-file` that replaces stdout, if any
+static OUTPUT_FILE: Mutex<Option<AtomicFile>> = Mutex::new(None);
+
+/// Send everything commands print to [`stdout`] into `file` instead
+pub fn redirect_stdout(file: AtomicFile) {
+    *lock_output_file() = Some(file);
+}
+
+/// Publish the `--output-file` if the command ran to completion, or discard
+/// it. Returns whether there was one.
+pub fn finish_output_file(completed: bool) -> Result<bool> {
+    match lock_output_file().take() {
+        Some(file) if completed => file.commit().map(|()| true),
+        Some(_) => Ok(true),
+        None => Ok(false),
+    }
+}
+
+fn lock_output_file() -> MutexGuard<'static, Option<AtomicFile>> {
+    OUTPUT_FILE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Where commands write their results: stdout, or the `--output-file`
+pub fn stdout() -> Stdout {
+    let file = lock_output_file();
+    if file.is_some() {
+        Stdout::File(file)
+    } else {
+        Stdout::Terminal(io::stdout().lock())
+    }
+}
+
+/// Whether results are shown on a terminal, rather than written to a pipe,
+/// a redirected file, or the `--output-file`
+pub fn stdout_is_terminal() -> bool {
+    lock_output_file().is_none() && io::stdout().is_terminal()
+}
+
+pub enum Stdout {
+    Terminal(io::StdoutLock<'static>),
+    File(MutexGuard<'static, Option<AtomicFile>>),
+}
+
+impl Stdout {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Stdout::Terminal(lock) => lock,
+            Stdout::File(file) => file.as_mut().expect("checked in stdout()"),
+        }
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.writer().write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer().flush()
+    }
+}
+
+/// Serialize `value` to `writer` in `format`. Text formats end with a
+/// newline; binary formats are written as-is.
+pub fn write_output<T: Serialize>(
+    writer: &mut dyn Write,
+    value: &T,
+    format: OutputFormat,
+    pretty: bool,
+) -> Result<()> {
+    match format {
+        OutputFormat::Cbor => ciborium::into_writer(value, writer)
+            .map_err(|e| IdtError::SerializationError(e.to_string())),
+        // Named fields keep structs as maps, matching the JSON shape
+        OutputFormat::Msgpack => rmp_serde::encode::write_named(writer, value)
+            .map_err(|e| IdtError::SerializationError(e.to_string())),
+        OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Table => {
+            write_table(writer, value, format)
+        }
+        _ => Ok(writeln!(
+            writer,
+            "{}",
+            format_output(value, format, pretty)?
+        )?),
+    }
+}
+
+/// `value` as text in `format`. Binary formats have no text form; use
+/// [`write_output`] for those.
 pub fn format_output<T: Serialize>(
     value: &T,
     format: OutputFormat,
@@ -22,6 +120,169 @@ pub fn format_output<T: Serialize>(
             Ok(toml::to_string_pretty(&toml_value)
                 .map_err(|e| crate::core::error::IdtError::SerializationError(e.to_string()))?)
         }
+        OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Table => {
+            let mut out = Vec::new();
+            write_table(&mut out, value, format)?;
+            String::from_utf8(out).map_err(|e| IdtError::SerializationError(e.to_string()))
+        }
+        OutputFormat::Cbor | OutputFormat::Msgpack => {
+            let name = format.to_possible_value().expect("no skipped variants");
+            Err(IdtError::SerializationError(format!(
+                "{} is a binary format with no text form",
+                name.get_name()
+            )))
+        }
+    }
+}
+
+/// `value` as a CSV, TSV, or aligned table: a row per element of a top-level
+/// array, or a single row. Nested objects become dotted column names
+/// (`timestamp.iso`), arrays stay JSON text, and a scalar row is one `value`
+/// column.
+fn write_table<T: Serialize>(
+    writer: &mut dyn Write,
+    value: &T,
+    format: OutputFormat,
+) -> Result<()> {
+    let rows: Vec<Vec<(String, String)>> = match serde_json::to_value(value)? {
+        Value::Array(items) => items.into_iter().map(table_row).collect(),
+        other => vec![table_row(other)],
+    };
+    let mut columns: Vec<&str> = Vec::new();
+    for (column, _) in rows.iter().flatten() {
+        if !columns.contains(&column.as_str()) {
+            columns.push(column);
+        }
+    }
+    let cells: Vec<Vec<&str>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|&column| {
+                    row.iter()
+                        .find(|(name, _)| name == column)
+                        .map_or("", |(_, cell)| cell.as_str())
+                })
+                .collect()
+        })
+        .collect();
+    write_rows(writer, &columns, &cells, format)
+}
+
+/// Write a header and `rows` of cells under it, as CSV, TSV, or a table
+/// with each column as wide as its widest cell. Empty table cells show `-`.
+pub fn write_rows<S: AsRef<str>>(
+    writer: &mut dyn Write,
+    columns: &[&str],
+    rows: &[Vec<S>],
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Table {
+        let shown = |cell: &str| if cell.is_empty() { "-" } else { cell }.to_string();
+        let lines: Vec<Vec<String>> =
+            std::iter::once(columns.iter().map(|c| c.to_string()).collect())
+                .chain(
+                    rows.iter()
+                        .map(|row| row.iter().map(|cell| shown(cell.as_ref())).collect()),
+                )
+                .collect();
+        let mut widths = vec![0; columns.len()];
+        for line in &lines {
+            for (width, cell) in widths.iter_mut().zip(line) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        for line in &lines {
+            let mut text = String::new();
+            for (i, (cell, width)) in line.iter().zip(&widths).enumerate() {
+                if i > 0 {
+                    text.push_str("  ");
+                }
+                text.push_str(cell);
+                if i + 1 < line.len() {
+                    text.extend(std::iter::repeat_n(' ', width - cell.chars().count()));
+                }
+            }
+            writeln!(writer, "{}", text)?;
+        }
+        return Ok(());
+    }
+
+    let delimiter = if format == OutputFormat::Tsv {
+        b'\t'
+    } else {
+        b','
+    };
+    let mut table = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(writer);
+    let table_error = |e: csv::Error| IdtError::SerializationError(e.to_string());
+    table.write_record(columns).map_err(table_error)?;
+    for row in rows {
+        table
+            .write_record(row.iter().map(AsRef::as_ref))
+            .map_err(table_error)?;
+    }
+    Ok(table.flush()?)
+}
+
+fn table_row(value: Value) -> Vec<(String, String)> {
+    let mut cells = Vec::new();
+    match value {
+        Value::Object(_) => flatten_cells(String::new(), value, &mut cells),
+        // A bare ID, as in `gen -n 5`; same column as the single-ID `{"id": ...}`
+        other => cells.push(("id".to_string(), table_cell(other))),
+    }
+    cells
+}
+
+fn flatten_cells(prefix: String, value: Value, cells: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields {
+                let column = if prefix.is_empty() {
+                    name
+                } else {
+                    format!("{}.{}", prefix, name)
+                };
+                flatten_cells(column, field, cells);
+            }
+        }
+        other => cells.push((prefix, table_cell(other))),
+    }
+}
+
+fn table_cell(value: Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+/// Stand-in for an input that failed, so batch output keeps one entry per input
+#[derive(Debug, Serialize)]
+pub struct ErrorEntry {
+    pub input: String,
+    pub error: String,
+    pub code: &'static str,
+    /// Byte offset of the offending character, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+}
+
+impl ErrorEntry {
+    pub fn new(input: &str, error: &IdtError) -> Self {
+        Self {
+            input: input.to_string(),
+            error: error.to_string(),
+            code: error.code(),
+            offset: match error {
+                IdtError::InvalidCharacter { offset, .. } => Some(*offset),
+                _ => None,
+            },
+        }
     }
 }
 
@@ -53,6 +314,76 @@ mod tests {
         assert!(result.contains("value"));
     }
 
+    #[test]
+    fn test_cbor() {
+        let value = json!({"key": "value"});
+        let mut out = Vec::new();
+        write_output(&mut out, &value, OutputFormat::Cbor, false).unwrap();
+        // map(1), text(3) "key", text(5) "value"
+        assert_eq!(out, b"\xa1\x63key\x65value");
+        let decoded: serde_json::Value = ciborium::from_reader(out.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+        assert!(format_output(&value, OutputFormat::Cbor, false).is_err());
+    }
+
+    #[test]
+    fn test_msgpack() {
+        let value = json!({"key": "value"});
+        let mut out = Vec::new();
+        write_output(&mut out, &value, OutputFormat::Msgpack, false).unwrap();
+        // fixmap(1), fixstr(3) "key", fixstr(5) "value"
+        assert_eq!(out, b"\x81\xa3key\xa5value");
+        assert!(format_output(&value, OutputFormat::Msgpack, false).is_err());
+    }
+
+    #[test]
+    fn test_msgpack_keeps_field_names() {
+        let entry = ErrorEntry::new("x", &IdtError::InvalidArgument("bad".to_string()));
+        let mut out = Vec::new();
+        write_output(&mut out, &entry, OutputFormat::Msgpack, false).unwrap();
+        let decoded: serde_json::Value = rmp_serde::from_slice(&out).unwrap();
+        assert_eq!(decoded["input"], "x");
+        assert_eq!(decoded["code"], "invalid_argument");
+    }
+
+    #[test]
+    fn test_write_output_text_ends_with_newline() {
+        let mut out = Vec::new();
+        write_output(&mut out, &json!([1]), OutputFormat::Json, false).unwrap();
+        assert_eq!(out, b"[1]\n");
+    }
+
+    #[test]
+    fn test_csv_table() {
+        let value = json!([
+            {"id": "a", "timestamp": {"millis": 1}, "tags": ["x"]},
+            {"id": "b,c", "error": null, "extra": true},
+        ]);
+        let result = format_output(&value, OutputFormat::Csv, false).unwrap();
+        assert_eq!(
+            result,
+            "id,tags,timestamp.millis,error,extra\n\
+             a,\"[\"\"x\"\"]\",1,,\n\
+             \"b,c\",,,,true\n"
+        );
+    }
+
+    #[test]
+    fn test_tsv_scalars() {
+        let mut out = Vec::new();
+        write_output(&mut out, &json!(["a", "b"]), OutputFormat::Tsv, false).unwrap();
+        assert_eq!(out, b"id\na\nb\n");
+        let result = format_output(&json!({"a": 1, "b": "x"}), OutputFormat::Tsv, false).unwrap();
+        assert_eq!(result, "a\tb\n1\tx\n");
+    }
+
+    #[test]
+    fn test_aligned_table() {
+        let value = json!([{"id": "a", "kind": "ulid"}, {"id": "bcd"}]);
+        let result = format_output(&value, OutputFormat::Table, false).unwrap();
+        assert_eq!(result, "id   kind\na    ulid\nbcd  -\n");
+    }
+
     #[test]
     fn test_toml() {
         let value = json!({"key": "value"});
@@ -60,4 +391,19 @@ mod tests {
         assert!(result.contains("key"));
         assert!(result.contains("value"));
     }
+
+    #[test]
+    fn test_error_entry() {
+        let entry = ErrorEntry::new("0AWE5HZP3SKUK", &IdtError::DetectionFailed);
+        assert_eq!(
+            serde_json::to_value(&entry).unwrap(),
+            json!({
+                "input": "0AWE5HZP3SKUK",
+                "error": "Detection failed: could not determine ID type",
+                "code": "detection_failed",
+            })
+        );
+        let error = IdtError::invalid_character("0AWE5HZP3SKUK", 11, "Crockford Base32");
+        assert_eq!(ErrorEntry::new("0AWE5HZP3SKUK", &error).offset, Some(11));
+    }
 }
@@ -0,0 +1,249 @@
+//! A progress line on stderr for long batch runs.
+//!
+//! The line only appears when stderr is a terminal and results are going
+//! somewhere else (a pipe or file), so it never mixes with output on screen.
+//! Short runs finish before the first draw and show nothing.
+
+use crate::cli::output::stdout_is_terminal;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Set by `--no-progress`
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// How long a run must take before the line first appears
+const DELAY: Duration = Duration::from_millis(500);
+/// Minimum time between redraws
+const INTERVAL: Duration = Duration::from_millis(100);
+const BAR_WIDTH: usize = 24;
+
+/// Turn progress off for the rest of the process
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// What the run is measured against, when the end is known
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Total {
+    /// A fixed number of items, e.g. `gen -n`
+    Items(u64),
+    /// Input bytes, e.g. a file redirected to stdin. Lines are counted as
+    /// their trimmed length plus a newline, so this is an estimate.
+    Bytes(u64),
+}
+
+pub struct Progress {
+    label: &'static str,
+    total: Option<Total>,
+    items: u64,
+    bytes: u64,
+    start: Instant,
+    next_draw: Instant,
+    enabled: bool,
+    drawn: bool,
+}
+
+impl Progress {
+    /// Progress for `label`, shown only if the terminal setup allows it and
+    /// `quiet` is off
+    pub fn new(label: &'static str, total: Option<Total>, quiet: bool) -> Self {
+        let enabled = !quiet
+            && !DISABLED.load(Ordering::Relaxed)
+            && io::stderr().is_terminal()
+            && !stdout_is_terminal();
+        Self::with_enabled(label, total, enabled)
+    }
+
+    /// Progress that never draws
+    pub fn hidden() -> Self {
+        Self::with_enabled("", None, false)
+    }
+
+    fn with_enabled(label: &'static str, total: Option<Total>, enabled: bool) -> Self {
+        let start = Instant::now();
+        Self {
+            label,
+            total,
+            items: 0,
+            bytes: 0,
+            start,
+            next_draw: start + DELAY,
+            enabled,
+            drawn: false,
+        }
+    }
+
+    /// Count one item that took `bytes` of input
+    pub fn tick(&mut self, bytes: usize) {
+        self.items += 1;
+        self.bytes += bytes as u64;
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        if now >= self.next_draw {
+            self.next_draw = now + INTERVAL;
+            let line = self.render(now - self.start);
+            let mut stderr = io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K{}", line);
+            let _ = stderr.flush();
+            self.drawn = true;
+        }
+    }
+
+    /// Erase the line, if it is showing, e.g. before printing a message.
+    /// The next tick draws it again.
+    pub fn clear(&mut self) {
+        if self.drawn {
+            eprint!("\r\x1b[2K");
+            self.drawn = false;
+        }
+    }
+
+    /// Fraction done, when the total is known
+    fn fraction(&self) -> Option<f64> {
+        let (done, total) = match self.total? {
+            Total::Items(total) => (self.items, total),
+            Total::Bytes(total) => (self.bytes, total),
+        };
+        (total > 0).then(|| (done as f64 / total as f64).min(1.0))
+    }
+
+    fn render(&self, elapsed: Duration) -> String {
+        let secs = elapsed.as_secs_f64();
+        let rate = if secs > 0.0 {
+            self.items as f64 / secs
+        } else {
+            0.0
+        };
+        let counts = format!("{} IDs  {}/s", group_digits(self.items), si(rate));
+
+        match self.fraction() {
+            Some(fraction) => {
+                let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+                let eta = if fraction > 0.0 {
+                    clock(secs * (1.0 - fraction) / fraction)
+                } else {
+                    "?".to_string()
+                };
+                format!(
+                    "{} [{}{}] {:>3.0}%  {}  ETA {}",
+                    self.label,
+                    "#".repeat(filled),
+                    "-".repeat(BAR_WIDTH - filled),
+                    fraction * 100.0,
+                    counts,
+                    eta
+                )
+            }
+            None => format!("{} {}  {}", self.label, counts, clock(secs)),
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// The size of stdin when it is a regular file (`idt inspect < ids.txt`)
+pub fn stdin_size() -> Option<u64> {
+    #[cfg(unix)]
+    {
+        let metadata = std::fs::metadata("/dev/stdin").ok()?;
+        metadata.is_file().then_some(metadata.len())
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// `1234567` as `1,234,567`
+fn group_digits(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// A rate with a k/M suffix, e.g. `350k`
+fn si(value: f64) -> String {
+    if value >= 1e6 {
+        format!("{:.1}M", value / 1e6)
+    } else if value >= 1e3 {
+        format!("{:.0}k", value / 1e3)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+/// Seconds as `m:ss`, or `h:mm:ss` past an hour
+fn clock(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(total: Option<Total>, items: u64, bytes: u64) -> Progress {
+        let mut progress = Progress::with_enabled("gen", total, false);
+        progress.items = items;
+        progress.bytes = bytes;
+        progress
+    }
+
+    #[test]
+    fn test_render_with_item_total() {
+        let line = progress(Some(Total::Items(1000)), 250, 0).render(Duration::from_secs(2));
+        assert_eq!(
+            line,
+            "gen [######------------------]  25%  250 IDs  125/s  ETA 0:06"
+        );
+    }
+
+    #[test]
+    fn test_render_with_byte_total() {
+        let line = progress(Some(Total::Bytes(2700)), 50, 1350).render(Duration::from_secs(1));
+        assert!(line.contains(" 50%  50 IDs  50/s  ETA 0:01"), "{}", line);
+    }
+
+    #[test]
+    fn test_render_open_ended() {
+        let line = progress(None, 1_234_567, 0).render(Duration::from_secs(3725));
+        assert_eq!(line, "gen 1,234,567 IDs  331/s  1:02:05");
+    }
+
+    #[test]
+    fn test_hidden_progress_counts_without_drawing() {
+        let mut progress = Progress::hidden();
+        progress.tick(27);
+        progress.tick(27);
+        assert_eq!((progress.items, progress.bytes), (2, 54));
+        assert!(!progress.drawn);
+    }
+
+    #[test]
+    fn test_formatting_helpers() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1000), "1,000");
+        assert_eq!(si(350_400.0), "350k");
+        assert_eq!(si(2_500_000.0), "2.5M");
+        assert_eq!(clock(59.6), "1:00");
+    }
+}
@@ -0,0 +1,149 @@
+//! QR codes for `--qr` and `--qr-png`, to move an ID to a phone by camera.
+//!
+//! Terminal codes follow `qrencode -t UTF8`: light modules are drawn with
+//! block characters, so they scan on a dark background. PNG files are black
+//! on white, and written whole or not at all.
+
+use crate::core::error::{IdtError, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Pixels per module in a PNG
+#[cfg(feature = "qr")]
+const PNG_SCALE: usize = 8;
+
+/// Modules of light border around a PNG code, as the spec asks for
+#[cfg(feature = "qr")]
+const QUIET_ZONE: usize = 4;
+
+/// Write `id` followed by its QR code drawn in block characters
+#[cfg(feature = "qr")]
+pub fn write_terminal(writer: &mut dyn Write, id: &str) -> Result<()> {
+    use qrcode::render::unicode::Dense1x2;
+    let code = encode(id)?
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build();
+    writeln!(writer, "{}", id)?;
+    writeln!(writer, "{}", code)?;
+    Ok(())
+}
+
+/// Write `id` as a black-on-white PNG at `path`
+#[cfg(feature = "qr")]
+pub fn write_png(id: &str, path: &Path) -> Result<()> {
+    use crate::cli::atomic_file::AtomicFile;
+    let code = encode(id)?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + 2 * QUIET_ZONE) * PNG_SCALE;
+    let mut pixels = vec![u8::MAX; size * size];
+    for (i, color) in colors.iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let (x, y) = (i % modules + QUIET_ZONE, i / modules + QUIET_ZONE);
+        for row in y * PNG_SCALE..(y + 1) * PNG_SCALE {
+            pixels[row * size + x * PNG_SCALE..row * size + (x + 1) * PNG_SCALE].fill(0);
+        }
+    }
+
+    let png_error = |e: png::EncodingError| IdtError::SerializationError(e.to_string());
+    let mut file = AtomicFile::create(path, false)?;
+    let mut encoder = png::Encoder::new(&mut file, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut png = encoder.write_header().map_err(png_error)?;
+    png.write_image_data(&pixels).map_err(png_error)?;
+    png.finish().map_err(png_error)?;
+    file.commit()
+}
+
+#[cfg(feature = "qr")]
+fn encode(id: &str) -> Result<qrcode::QrCode> {
+    qrcode::QrCode::new(id.as_bytes())
+        .map_err(|e| IdtError::InvalidArgument(format!("Cannot draw '{}' as a QR code: {}", id, e)))
+}
+
+#[cfg(not(feature = "qr"))]
+pub fn write_terminal(_writer: &mut dyn Write, _id: &str) -> Result<()> {
+    Err(not_built())
+}
+
+#[cfg(not(feature = "qr"))]
+pub fn write_png(_id: &str, _path: &Path) -> Result<()> {
+    Err(not_built())
+}
+
+#[cfg(not(feature = "qr"))]
+fn not_built() -> IdtError {
+    IdtError::InvalidArgument("QR codes require idt to be built with the `qr` feature".to_string())
+}
+
+/// Write one PNG per ID: `path` itself for a single ID, else `path` with
+/// `-1`, `-2`, ... before the extension
+pub fn write_pngs(ids: &[String], path: &Path) -> Result<()> {
+    if ids.len() == 1 {
+        return write_png(&ids[0], path);
+    }
+    for (i, id) in ids.iter().enumerate() {
+        write_png(id, &numbered(path, i + 1))?;
+    }
+    Ok(())
+}
+
+/// `codes/device.png` → `codes/device-3.png`
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, n, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, n),
+    };
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbered() {
+        let path = Path::new("codes/device.png");
+        assert_eq!(numbered(path, 3), Path::new("codes/device-3.png"));
+        assert_eq!(numbered(Path::new("device"), 1), Path::new("device-1"));
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_terminal() {
+        let mut out = Vec::new();
+        write_terminal(&mut out, "01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+        // Version 2 is 25 modules, plus the quiet zone on both sides
+        assert!(lines.all(|line| line.chars().count() == 33));
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_pngs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.png");
+        write_pngs(&["550e8400-e29b-41d4-a716-446655440000".to_string()], &path).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        assert!(data.starts_with(b"\x89PNG"));
+
+        let ids = ["a".to_string(), "b".to_string()];
+        write_pngs(&ids, &path).unwrap();
+        assert!(dir.path().join("id-1.png").exists());
+        assert!(dir.path().join("id-2.png").exists());
+    }
+
+    #[cfg(not(feature = "qr"))]
+    #[test]
+    fn test_needs_feature() {
+        assert!(write_png("a", Path::new("a.png")).is_err());
+    }
+}
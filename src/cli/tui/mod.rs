@@ -0,0 +1,37 @@
+//! `idt tui`: an interactive explorer that decodes an ID as it is typed,
+//! with detection candidates, the bit layout, encodings, a history list,
+//! and a generator panel. [`state`] holds everything on screen and reacts
+//! to keys; [`ui`] draws it.
+
+pub mod state;
+pub mod ui;
+
+use crate::core::error::Result;
+use ratatui::crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyEventKind,
+};
+use ratatui::crossterm::execute;
+use state::App;
+use std::io;
+
+/// Take over the terminal until the user quits, starting with `input`
+pub fn run(input: Option<&str>) -> Result<()> {
+    let mut terminal = ratatui::init();
+    execute!(io::stdout(), EnableBracketedPaste)?;
+    let mut app = App::new(input);
+    let result = (|| -> Result<()> {
+        while !app.quit {
+            terminal.draw(|frame| ui::draw(frame, &app))?;
+            match event::read()? {
+                // Windows also reports releases
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key),
+                Event::Paste(text) => app.paste(&text),
+                _ => {}
+            }
+        }
+        Ok(())
+    })();
+    let _ = execute!(io::stdout(), DisableBracketedPaste);
+    ratatui::restore();
+    result
+}
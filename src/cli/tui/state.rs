@@ -0,0 +1,420 @@
+use crate::core::detection::{DetectionOptions, DetectionResult, detect_id_type_with};
+use crate::core::id::{IdKind, InspectionResult};
+use crate::ids::{GeneratorOptions, create_generator_with, parse_id_with};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Inputs kept in the history list, most recent first
+const HISTORY_LIMIT: usize = 50;
+
+/// Largest batch the generator panel makes at once
+const MAX_COUNT: usize = 20;
+
+/// Which pane keystrokes go to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Explore,
+    Generate,
+}
+
+/// A generator option the panel can adjust
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenField {
+    Kind,
+    Count,
+    Length,
+    Prefix,
+}
+
+impl GenField {
+    pub fn label(self) -> &'static str {
+        match self {
+            GenField::Kind => "Type",
+            GenField::Count => "Count",
+            GenField::Length => "Length",
+            GenField::Prefix => "Prefix",
+        }
+    }
+}
+
+/// Everything the explorer shows, updated as keys arrive
+pub struct App {
+    pub focus: Focus,
+    pub input: String,
+    /// Types the input could be, most likely first
+    pub candidates: Vec<DetectionResult>,
+    pub inspection: Option<InspectionResult>,
+    /// Why the input didn't parse, when it is not empty
+    pub error: Option<String>,
+    pub history: Vec<String>,
+    /// History entry shown in the input, while browsing with Up and Down
+    pub history_index: Option<usize>,
+    pub generator: Generator,
+    pub quit: bool,
+}
+
+/// The generator panel's options and its last batch
+pub struct Generator {
+    pub field: GenField,
+    pub kind: IdKind,
+    pub count: usize,
+    /// NanoID length
+    pub length: usize,
+    /// TypeID prefix
+    pub prefix: String,
+    pub generated: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            field: GenField::Kind,
+            kind: IdKind::UuidV7,
+            count: 5,
+            length: 21,
+            prefix: "user".to_string(),
+            generated: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+impl Generator {
+    /// The options that apply to the selected type
+    pub fn fields(&self) -> Vec<GenField> {
+        let mut fields = vec![GenField::Kind, GenField::Count];
+        match self.kind {
+            IdKind::NanoId => fields.push(GenField::Length),
+            IdKind::TypeId => fields.push(GenField::Prefix),
+            _ => {}
+        }
+        fields
+    }
+
+    pub fn value(&self, field: GenField) -> String {
+        match field {
+            GenField::Kind => self.kind.name().to_string(),
+            GenField::Count => self.count.to_string(),
+            GenField::Length => self.length.to_string(),
+            GenField::Prefix => self.prefix.clone(),
+        }
+    }
+
+    /// Mint a batch with the current options
+    pub fn generate(&mut self) {
+        let mut options = GeneratorOptions::new();
+        match self.kind {
+            IdKind::NanoId => options = options.with_length(self.length),
+            IdKind::TypeId => options = options.with_prefix(&self.prefix),
+            _ => {}
+        }
+        let batch = create_generator_with(self.kind, &options)
+            .and_then(|generator| (0..self.count).map(|_| generator.generate()).collect());
+        match batch {
+            Ok(ids) => {
+                self.generated = ids;
+                self.error = None;
+            }
+            Err(e) => {
+                self.generated.clear();
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn select(&mut self, step: isize) {
+        let fields = self.fields();
+        let at = fields.iter().position(|&f| f == self.field).unwrap_or(0);
+        self.field = fields[(at as isize + step).rem_euclid(fields.len() as isize) as usize];
+    }
+
+    fn adjust(&mut self, step: isize) {
+        match self.field {
+            GenField::Kind => {
+                let kinds: Vec<IdKind> = IdKind::generatable()
+                    .iter()
+                    .copied()
+                    .filter(IdKind::is_enabled)
+                    .collect();
+                let at = kinds.iter().position(|&k| k == self.kind).unwrap_or(0);
+                self.kind = kinds[(at as isize + step).rem_euclid(kinds.len() as isize) as usize];
+            }
+            GenField::Count => {
+                self.count = self.count.saturating_add_signed(step).clamp(1, MAX_COUNT);
+            }
+            GenField::Length => {
+                self.length = self.length.saturating_add_signed(step).clamp(2, 64);
+            }
+            GenField::Prefix => {}
+        }
+    }
+}
+
+impl App {
+    pub fn new(input: Option<&str>) -> Self {
+        let mut app = Self {
+            focus: Focus::Explore,
+            input: String::new(),
+            candidates: Vec::new(),
+            inspection: None,
+            error: None,
+            history: Vec::new(),
+            history_index: None,
+            generator: Generator::default(),
+            quit: false,
+        };
+        if let Some(input) = input {
+            app.set_input(input.trim().to_string());
+            app.remember();
+        }
+        app
+    }
+
+    /// Replace the input and decode it again
+    pub fn set_input(&mut self, input: String) {
+        self.input = input;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let input = self.input.trim();
+        self.candidates.clear();
+        self.inspection = None;
+        self.error = None;
+        if input.is_empty() {
+            return;
+        }
+        let options = DetectionOptions::default();
+        self.candidates = detect_id_type_with(input, &options).unwrap_or_default();
+        match parse_id_with(input, None, &options) {
+            Ok(parsed) => self.inspection = Some(parsed.inspect()),
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// Put the input at the top of the history
+    fn remember(&mut self) {
+        let input = self.input.trim().to_string();
+        if input.is_empty() {
+            return;
+        }
+        self.history.retain(|entry| *entry != input);
+        self.history.insert(0, input);
+        self.history.truncate(HISTORY_LIMIT);
+        self.history_index = None;
+    }
+
+    /// Text from a bracketed paste, without surrounding whitespace
+    pub fn paste(&mut self, text: &str) {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        match self.focus {
+            Focus::Explore => {
+                let input = format!("{}{}", self.input, text.trim());
+                self.history_index = None;
+                self.set_input(input);
+            }
+            Focus::Generate if self.generator.field == GenField::Prefix => {
+                self.generator.prefix.push_str(&text);
+            }
+            Focus::Generate => {}
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Explore => Focus::Generate,
+                    Focus::Generate => Focus::Explore,
+                }
+            }
+            _ => match self.focus {
+                Focus::Explore => self.explore_key(key.code, ctrl),
+                Focus::Generate => self.generate_key(key.code),
+            },
+        }
+    }
+
+    fn explore_key(&mut self, code: KeyCode, ctrl: bool) {
+        match code {
+            KeyCode::Char('u') if ctrl => self.set_input(String::new()),
+            KeyCode::Char(c) if !ctrl => {
+                let mut input = self.input.clone();
+                input.push(c);
+                self.history_index = None;
+                self.set_input(input);
+            }
+            KeyCode::Backspace => {
+                let mut input = self.input.clone();
+                input.pop();
+                self.history_index = None;
+                self.set_input(input);
+            }
+            KeyCode::Enter => self.remember(),
+            KeyCode::Up | KeyCode::Down if !self.history.is_empty() => {
+                let last = self.history.len() - 1;
+                let index = match (code, self.history_index) {
+                    (KeyCode::Up, None) => Some(0),
+                    (KeyCode::Up, Some(i)) => Some((i + 1).min(last)),
+                    (_, Some(0) | None) => None,
+                    (_, Some(i)) => Some(i - 1),
+                };
+                self.history_index = index;
+                let input = index.map_or_else(String::new, |i| self.history[i].clone());
+                self.set_input(input);
+            }
+            _ => {}
+        }
+    }
+
+    fn generate_key(&mut self, code: KeyCode) {
+        let generator = &mut self.generator;
+        match code {
+            KeyCode::Up => generator.select(-1),
+            KeyCode::Down => generator.select(1),
+            KeyCode::Left | KeyCode::Char('-') if generator.field != GenField::Prefix => {
+                generator.adjust(-1)
+            }
+            KeyCode::Right | KeyCode::Char('+') if generator.field != GenField::Prefix => {
+                generator.adjust(1)
+            }
+            KeyCode::Char(c) if generator.field == GenField::Prefix => generator.prefix.push(c),
+            KeyCode::Backspace if generator.field == GenField::Prefix => {
+                generator.prefix.pop();
+            }
+            KeyCode::Enter => {
+                generator.generate();
+                // Open the first of the batch in the explorer
+                if let Some(first) = generator.generated.first().cloned() {
+                    self.set_input(first);
+                    self.remember();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_typing_decodes() {
+        let mut app = App::new(None);
+        type_text(&mut app, "01ARZ3NDEKTSV4RRFFQ69G5FA");
+        assert!(app.inspection.is_none());
+        assert!(app.error.is_some());
+        type_text(&mut app, "V");
+        assert_eq!(app.inspection.as_ref().unwrap().id_type, "ulid");
+        assert_eq!(app.candidates[0].kind, IdKind::Ulid);
+        assert!(app.error.is_none());
+
+        press(&mut app, KeyCode::Backspace);
+        assert!(app.inspection.is_none());
+        app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert!(app.input.is_empty() && app.error.is_none());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut app = App::new(Some("550e8400-e29b-41d4-a716-446655440000"));
+        app.set_input(String::new());
+        type_text(&mut app, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.history.len(), 2);
+
+        app.set_input(String::new());
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.input, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.input, "550e8400-e29b-41d4-a716-446655440000");
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert!(app.input.is_empty());
+
+        // Entering a remembered ID again moves it to the top
+        app.set_input("550e8400-e29b-41d4-a716-446655440000".to_string());
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.history[0], "550e8400-e29b-41d4-a716-446655440000");
+        assert_eq!(app.history.len(), 2);
+    }
+
+    #[test]
+    fn test_paste_trims() {
+        let mut app = App::new(None);
+        app.paste("  01ARZ3NDEKTSV4RRFFQ69G5FAV\n");
+        assert_eq!(app.input, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert!(app.inspection.is_some());
+    }
+
+    #[test]
+    fn test_generator_panel() {
+        let mut app = App::new(None);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Generate);
+
+        // Move to NanoID, which has a length option
+        while app.generator.kind != IdKind::NanoId {
+            press(&mut app, KeyCode::Right);
+        }
+        assert_eq!(
+            app.generator.fields(),
+            [GenField::Kind, GenField::Count, GenField::Length]
+        );
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.generator.count, 3);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!(app.generator.length, 20);
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.generator.generated.len(), 3);
+        assert!(app.generator.generated.iter().all(|id| id.len() == 20));
+        assert_eq!(app.input, app.generator.generated[0]);
+        assert_eq!(app.history.len(), 1);
+
+        // Count stays in range, and the field list wraps
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.generator.field, GenField::Kind);
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.generator.field, GenField::Length);
+    }
+
+    #[test]
+    fn test_generator_prefix() {
+        let mut generator = Generator {
+            kind: IdKind::TypeId,
+            field: GenField::Prefix,
+            count: 1,
+            ..Default::default()
+        };
+        generator.prefix = "order".to_string();
+        generator.generate();
+        assert!(generator.generated[0].starts_with("order_"));
+        assert!(generator.error.is_none());
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut app = App::new(None);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.quit);
+    }
+}
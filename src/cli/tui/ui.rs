@@ -0,0 +1,312 @@
+use super::state::{App, Focus};
+use crate::core::id::{InspectionResult, SizeUnit, StructureSegment};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+
+/// Segment colors in the layout bar, in field order
+const PALETTE: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+];
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [input, body, generator, help] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(10),
+        Constraint::Length(8),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [left, right] =
+        Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(body);
+    let [detection, history] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(left);
+    let [layout, encodings] =
+        Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(right);
+
+    draw_input(frame, app, input);
+    draw_detection(frame, app, detection);
+    draw_history(frame, app, history);
+    draw_layout(frame, app, layout);
+    draw_encodings(frame, app, encodings);
+    draw_generator(frame, app, generator);
+
+    let keys = match app.focus {
+        Focus::Explore => "Tab generator · Enter save · ↑↓ history · Ctrl-U clear · Esc quit",
+        Focus::Generate => "Tab explorer · ↑↓ option · ←→ adjust · Enter generate · Esc quit",
+    };
+    frame.render_widget(Paragraph::new(keys).dim(), help);
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(format!(" {} ", title));
+    if focused {
+        block.border_style(Style::new().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.focus == Focus::Explore;
+    let status = match (&app.inspection, &app.error) {
+        (Some(inspection), _) => Span::styled(
+            format!(" {} ", inspection.id_type),
+            Style::new().fg(Color::Green),
+        ),
+        (None, Some(error)) => Span::styled(format!(" {} ", error), Style::new().fg(Color::Red)),
+        (None, None) => Span::raw(" type or paste an ID "),
+    };
+    let block = pane("ID", focused).title_bottom(Line::from(status).right_aligned());
+    frame.render_widget(Paragraph::new(app.input.as_str()).block(block), area);
+    if focused {
+        let width = app.input.chars().count() as u16;
+        frame.set_cursor_position((
+            area.x + 1 + width.min(area.width.saturating_sub(3)),
+            area.y + 1,
+        ));
+    }
+}
+
+fn draw_detection(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .candidates
+        .iter()
+        .map(|candidate| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<12}", candidate.kind.name())),
+                Span::raw(format!("{:>4.0}%", candidate.confidence * 100.0)).dim(),
+            ]))
+        })
+        .collect();
+    frame.render_widget(List::new(items).block(pane("Detection", false)), area);
+}
+
+fn draw_history(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .history
+        .iter()
+        .map(|entry| ListItem::new(entry.as_str()))
+        .collect();
+    let list = List::new(items)
+        .block(pane("History", false))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(app.history_index);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_layout(frame: &mut Frame, app: &App, area: Rect) {
+    let structure = app
+        .inspection
+        .as_ref()
+        .and_then(|inspection| inspection.structure.as_deref())
+        .unwrap_or(&[]);
+    let title = app
+        .inspection
+        .as_ref()
+        .map_or("Layout".to_string(), |i| format!("Layout: {}", i.id_type));
+    let block = pane(&title, false);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if structure.is_empty() {
+        return;
+    }
+
+    let mut lines = vec![layout_bar(structure, inner.width as usize), Line::raw("")];
+    let name_width = structure.iter().map(|s| s.name.len()).max().unwrap_or(0);
+    for (i, segment) in structure.iter().enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<width$}", segment.name, width = name_width),
+                Style::new().fg(color),
+            ),
+            Span::raw(format!(
+                "  {:>3} {:<6}",
+                segment.size,
+                unit_name(segment.unit)
+            ))
+            .dim(),
+            Span::raw(segment.value.clone().unwrap_or_default()),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
+/// One colored block per field, as wide as its share of the ID
+fn layout_bar(structure: &[StructureSegment], width: usize) -> Line<'static> {
+    let total: u32 = structure.iter().map(|s| s.size).sum();
+    if total == 0 || width == 0 {
+        return Line::raw("");
+    }
+    let mut spans = Vec::new();
+    let mut used = 0;
+    let mut covered = 0;
+    for (i, segment) in structure.iter().enumerate() {
+        covered += segment.size;
+        // Round the running edge so widths add up to the whole bar
+        let end = (covered as usize * width)
+            .div_ceil(total as usize)
+            .max(used + 1);
+        let cells = end.min(width).saturating_sub(used);
+        if cells == 0 {
+            continue;
+        }
+        used += cells;
+        let label: String = segment.name.chars().take(cells).collect();
+        spans.push(Span::styled(
+            format!("{:<cells$}", label),
+            Style::new().fg(Color::Black).bg(PALETTE[i % PALETTE.len()]),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn unit_name(unit: SizeUnit) -> &'static str {
+    match unit {
+        SizeUnit::Bits => "bits",
+        SizeUnit::Digits => "digits",
+        SizeUnit::Chars => "chars",
+    }
+}
+
+fn draw_encodings(frame: &mut Frame, app: &App, area: Rect) {
+    let lines = app
+        .inspection
+        .as_ref()
+        .map(encoding_lines)
+        .unwrap_or_default();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(pane("Encodings", false))
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn encoding_lines(inspection: &InspectionResult) -> Vec<Line<'static>> {
+    let encodings = &inspection.encodings;
+    let rows = [
+        ("canonical", Some(&inspection.canonical)),
+        ("timestamp", inspection.timestamp_iso.as_ref()),
+        ("hex", Some(&encodings.hex)),
+        ("base32", Some(&encodings.base32)),
+        ("base58", Some(&encodings.base58)),
+        ("base64", Some(&encodings.base64)),
+        ("int", encodings.int.as_ref()),
+    ];
+    rows.into_iter()
+        .filter_map(|(name, value)| {
+            let value = value.filter(|v| !v.is_empty())?;
+            Some(Line::from(vec![
+                Span::raw(format!("{:<10}", name)).dim(),
+                Span::raw(value.clone()),
+            ]))
+        })
+        .collect()
+}
+
+fn draw_generator(frame: &mut Frame, app: &App, area: Rect) {
+    let generator = &app.generator;
+    let focused = app.focus == Focus::Generate;
+    let [options, output] =
+        Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(area);
+
+    let lines: Vec<Line> = generator
+        .fields()
+        .into_iter()
+        .map(|field| {
+            let selected = focused && field == generator.field;
+            let marker = if selected { "> " } else { "  " };
+            let line = Line::from(vec![
+                Span::raw(format!("{}{:<8}", marker, field.label())),
+                Span::raw(generator.value(field)),
+            ]);
+            if selected { line.bold() } else { line }
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(pane("Generator", focused)),
+        options,
+    );
+
+    let lines: Vec<Line> = match generator.error {
+        Some(ref error) => vec![Line::styled(error.clone(), Style::new().fg(Color::Red))],
+        None => generator
+            .generated
+            .iter()
+            .map(|id| Line::raw(id.clone()))
+            .collect(),
+    };
+    frame.render_widget(
+        Paragraph::new(lines).block(pane("Generated", false)),
+        output,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_draw() {
+        let app = App::new(Some("01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+        let screen = render(&app);
+        assert!(screen.contains("Layout: ulid"));
+        assert!(screen.contains("timestamp"));
+        assert!(screen.contains("2016-07-30T23:54:10.259Z"));
+        assert!(screen.contains("01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+
+        let screen = render(&App::new(Some("not an id")));
+        assert!(screen.contains("type or paste") || screen.contains("Detection failed"));
+    }
+
+    #[test]
+    fn test_layout_bar() {
+        let segment = |name: &str, size| StructureSegment {
+            name: name.to_string(),
+            size,
+            unit: SizeUnit::Bits,
+            value: None,
+            description: String::new(),
+        };
+        let structure = [segment("timestamp", 48), segment("random", 80)];
+        let bar = layout_bar(&structure, 64);
+        let widths: Vec<usize> = bar
+            .spans
+            .iter()
+            .map(|s| s.content.chars().count())
+            .collect();
+        assert_eq!(widths, [24, 40]);
+
+        // A 1-bit field still gets a cell
+        let structure = [segment("sign", 1), segment("rest", 127)];
+        let widths: Vec<usize> = layout_bar(&structure, 10)
+            .spans
+            .iter()
+            .map(|s| s.content.chars().count())
+            .collect();
+        assert_eq!(widths, [1, 9]);
+    }
+}
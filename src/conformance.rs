@@ -0,0 +1,481 @@
+//! Published test vectors, checked against idt's own codecs.
+//!
+//! ULID, TypeID, KSUID, and Xid use base32 and base62 codecs written for idt
+//! rather than taken from their reference implementations. Each vector here
+//! comes from a spec or a reference implementation's test suite, and
+//! [`run`] checks that idt decodes it to the same bytes and timestamp, and
+//! that encoding those bytes gives back the same text. Invalid vectors must
+//! be rejected.
+//!
+//! Vectors for families left out of the build are skipped.
+
+use crate::core::error::Result;
+use crate::core::id::IdKind;
+use crate::ids::parse_id;
+use serde::Serialize;
+
+/// What a vector must decode to
+#[derive(Debug, Clone, Copy)]
+pub enum Expected {
+    /// Valid, with these bytes (hex) and, if the kind has one, this Unix
+    /// timestamp in milliseconds
+    Valid {
+        hex: &'static str,
+        timestamp_ms: Option<u64>,
+    },
+    /// Must fail to parse
+    Invalid,
+}
+
+/// One known-good (or known-bad) ID
+#[derive(Debug, Clone, Copy)]
+pub struct Vector {
+    pub kind: IdKind,
+    /// Where the vector comes from
+    pub source: &'static str,
+    pub input: &'static str,
+    pub expected: Expected,
+    /// For name-based UUIDs: the name hashed in the DNS namespace
+    pub dns_name: Option<&'static str>,
+}
+
+const fn valid(
+    kind: IdKind,
+    source: &'static str,
+    input: &'static str,
+    hex: &'static str,
+    timestamp_ms: Option<u64>,
+) -> Vector {
+    Vector {
+        kind,
+        source,
+        input,
+        expected: Expected::Valid { hex, timestamp_ms },
+        dns_name: None,
+    }
+}
+
+const fn invalid(kind: IdKind, source: &'static str, input: &'static str) -> Vector {
+    Vector {
+        kind,
+        source,
+        input,
+        expected: Expected::Invalid,
+        dns_name: None,
+    }
+}
+
+const fn name_based(
+    kind: IdKind,
+    source: &'static str,
+    input: &'static str,
+    hex: &'static str,
+    name: &'static str,
+) -> Vector {
+    Vector {
+        kind,
+        source,
+        input,
+        expected: Expected::Valid {
+            hex,
+            timestamp_ms: None,
+        },
+        dns_name: Some(name),
+    }
+}
+
+const ULID_SPEC: &str = "ULID spec";
+const TYPEID_SPEC: &str = "TypeID spec";
+const KSUID_REF: &str = "segmentio/ksuid";
+const XID_REF: &str = "rs/xid";
+const RFC_9562: &str = "RFC 9562";
+
+/// Every vector idt knows, including those of families not compiled in
+pub const VECTORS: &[Vector] = &[
+    // https://github.com/ulid/spec
+    valid(
+        IdKind::Ulid,
+        ULID_SPEC,
+        "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+        "01563e3ab5d3d6764c61efb99302bd5b",
+        Some(1_469_922_850_259),
+    ),
+    valid(
+        IdKind::Ulid,
+        ULID_SPEC,
+        "00000000000000000000000000",
+        "00000000000000000000000000000000",
+        Some(0),
+    ),
+    valid(
+        IdKind::Ulid,
+        ULID_SPEC,
+        "7ZZZZZZZZZZZZZZZZZZZZZZZZZ",
+        "ffffffffffffffffffffffffffffffff",
+        Some(281_474_976_710_655),
+    ),
+    invalid(IdKind::Ulid, ULID_SPEC, "80000000000000000000000000"),
+    invalid(IdKind::Ulid, ULID_SPEC, "01ARZ3NDEKTSV4RRFFQ69G5FA"),
+    // https://github.com/jetify-com/typeid/tree/main/spec (valid.yml, invalid.yml)
+    valid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "00000000000000000000000000",
+        "00000000000000000000000000000000",
+        None,
+    ),
+    valid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "0000000000000000000000000g",
+        "00000000000000000000000000000010",
+        None,
+    ),
+    valid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "7zzzzzzzzzzzzzzzzzzzzzzzzz",
+        "ffffffffffffffffffffffffffffffff",
+        None,
+    ),
+    valid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "prefix_0123456789abcdefghjkmnpqrs",
+        "0110c8531d0952d8d73e1194e95b5f19",
+        None,
+    ),
+    valid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "prefix_01h455vb4pex5vsknk084sn02q",
+        "01890a5dac96774bbcceb302099a8057",
+        Some(1_688_096_058_518),
+    ),
+    invalid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "PREFIX_00000000000000000000000000",
+    ),
+    invalid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "prefix_8zzzzzzzzzzzzzzzzzzzzzzzzz",
+    ),
+    invalid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "prefix_0123456789ABCDEFGHJKMNPQRS",
+    ),
+    invalid(IdKind::TypeId, TYPEID_SPEC, "prefix_"),
+    invalid(
+        IdKind::TypeId,
+        TYPEID_SPEC,
+        "prefix_ooooooooooooooooooooooooo",
+    ),
+    // https://github.com/segmentio/ksuid
+    valid(
+        IdKind::Ksuid,
+        KSUID_REF,
+        "0ujtsYcgvSTl8PAuAdqWYSMnLOv",
+        "0669f7efb5a1cd34b5f99d1154fb6853345c9735",
+        Some(1_507_608_047_000),
+    ),
+    valid(
+        IdKind::Ksuid,
+        KSUID_REF,
+        "000000000000000000000000000",
+        "0000000000000000000000000000000000000000",
+        Some(1_400_000_000_000),
+    ),
+    valid(
+        IdKind::Ksuid,
+        KSUID_REF,
+        "aWgEPTl1tmebfsQzFP4bxwgy80V",
+        "ffffffffffffffffffffffffffffffffffffffff",
+        Some(5_694_967_295_000),
+    ),
+    invalid(IdKind::Ksuid, KSUID_REF, "aWgEPTl1tmebfsQzFP4bxwgy80W"),
+    // https://github.com/rs/xid (xid_test.go)
+    valid(
+        IdKind::Xid,
+        XID_REF,
+        "9m4e2mr0ui3e8a215n4g",
+        "4d88e15b60f486e428412dc9",
+        Some(1_300_816_219_000),
+    ),
+    valid(
+        IdKind::Xid,
+        XID_REF,
+        "0000005anf6drrg0000g",
+        "00000000aabbccddee000001",
+        Some(0),
+    ),
+    invalid(IdKind::Xid, XID_REF, "9m4e2mr0ui3e8a215n4"),
+    invalid(IdKind::Xid, XID_REF, "9z4e2mr0ui3e8a215n4g"),
+    invalid(IdKind::Xid, XID_REF, "9m4e2mr0ui3e8a215n4h"),
+    // RFC 9562, Appendix A
+    valid(
+        IdKind::UuidV1,
+        RFC_9562,
+        "c232ab00-9414-11ec-b3c8-9f6bdeced846",
+        "c232ab00941411ecb3c89f6bdeced846",
+        Some(1_645_557_742_000),
+    ),
+    name_based(
+        IdKind::UuidV3,
+        RFC_9562,
+        "5df41881-3aed-3515-88a7-2f4a814cf09e",
+        "5df418813aed351588a72f4a814cf09e",
+        "www.example.com",
+    ),
+    valid(
+        IdKind::UuidV4,
+        RFC_9562,
+        "919108f7-52d1-4320-9bac-f847db4148a8",
+        "919108f752d143209bacf847db4148a8",
+        None,
+    ),
+    name_based(
+        IdKind::UuidV5,
+        RFC_9562,
+        "2ed6657d-e927-568b-95e1-2665a8aea6a2",
+        "2ed6657de927568b95e12665a8aea6a2",
+        "www.example.com",
+    ),
+    valid(
+        IdKind::UuidV6,
+        RFC_9562,
+        "1ec9414c-232a-6b00-b3c8-9f6bdeced846",
+        "1ec9414c232a6b00b3c89f6bdeced846",
+        Some(1_645_557_742_000),
+    ),
+    valid(
+        IdKind::UuidV7,
+        RFC_9562,
+        "017f22e2-79b0-7cc3-98c4-dc0c0c07398f",
+        "017f22e279b07cc398c4dc0c0c07398f",
+        Some(1_645_557_742_000),
+    ),
+];
+
+/// Result of checking one vector
+#[derive(Debug, Clone, Serialize)]
+pub struct Outcome {
+    pub id_type: &'static str,
+    pub source: &'static str,
+    pub input: &'static str,
+    /// Whether the vector is a valid ID, as opposed to one idt must reject
+    pub valid: bool,
+    pub passed: bool,
+    /// What differed from the vector; empty when it passed
+    pub mismatches: Vec<String>,
+}
+
+/// Vectors for compiled-in families matching any of `kinds` (all if empty)
+pub fn vectors(kinds: &[IdKind]) -> impl Iterator<Item = &'static Vector> {
+    VECTORS.iter().filter(move |v| {
+        v.kind.is_enabled() && (kinds.is_empty() || kinds.iter().any(|&k| v.kind.matches_filter(k)))
+    })
+}
+
+/// Check every vector of `kinds` (all if empty)
+pub fn run(kinds: &[IdKind]) -> Vec<Outcome> {
+    vectors(kinds).map(check).collect()
+}
+
+/// Check one vector against idt's decoder and encoder
+pub fn check(vector: &Vector) -> Outcome {
+    let mismatches = match (vector.expected, parse_id(vector.input, Some(vector.kind))) {
+        (Expected::Invalid, Ok(parsed)) => {
+            vec![format!("accepted as {}", parsed.kind().name())]
+        }
+        (Expected::Invalid, Err(_)) => Vec::new(),
+        (Expected::Valid { .. }, Err(e)) => vec![format!("rejected: {}", e)],
+        (Expected::Valid { hex, timestamp_ms }, Ok(parsed)) => {
+            let mut mismatches = Vec::new();
+            if parsed.kind() != vector.kind {
+                mismatches.push(format!("parsed as {}", parsed.kind().name()));
+            }
+            let bytes = parsed.as_bytes();
+            if hex::encode(&bytes) != hex {
+                mismatches.push(format!(
+                    "decoded to {}, expected {}",
+                    hex::encode(&bytes),
+                    hex
+                ));
+            }
+            let actual_ms = parsed.timestamp().map(|t| t.millis);
+            if timestamp_ms.is_some() && actual_ms != timestamp_ms {
+                mismatches.push(format!(
+                    "timestamp {:?} ms, expected {:?}",
+                    actual_ms, timestamp_ms
+                ));
+            }
+            match encode(vector, &bytes) {
+                Ok(Some(text)) if !same_text(vector.kind, &text, vector.input) => {
+                    mismatches.push(format!("encoded back to {}", text));
+                }
+                Err(e) => mismatches.push(format!("encoding failed: {}", e)),
+                _ => {}
+            }
+            if let Some(name) = vector.dns_name {
+                match name_based_uuid(vector.kind, name) {
+                    Ok(Some(text)) if text != vector.input => {
+                        mismatches.push(format!("hashing {:?} gave {}", name, text));
+                    }
+                    Err(e) => mismatches.push(format!("hashing failed: {}", e)),
+                    _ => {}
+                }
+            }
+            mismatches
+        }
+    };
+
+    Outcome {
+        id_type: vector.kind.name(),
+        source: vector.source,
+        input: vector.input,
+        valid: matches!(vector.expected, Expected::Valid { .. }),
+        passed: mismatches.is_empty(),
+        mismatches,
+    }
+}
+
+/// Case only matters where the alphabet is case-sensitive or the spec
+/// mandates lowercase
+fn same_text(kind: IdKind, a: &str, b: &str) -> bool {
+    if kind.is_uuid() || crate::core::case::CASE_INSENSITIVE_KINDS.contains(&kind) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Text idt's encoder produces for the vector's bytes, for kinds whose codec
+/// is idt's own
+#[cfg_attr(
+    not(any(
+        feature = "ulid",
+        feature = "ksuid",
+        feature = "xid",
+        feature = "typeid",
+        feature = "uuid"
+    )),
+    allow(unused_variables)
+)]
+fn encode(vector: &Vector, bytes: &[u8]) -> Result<Option<String>> {
+    #[cfg(any(feature = "ulid", feature = "ksuid"))]
+    use crate::core::id::ParsedId;
+
+    Ok(match vector.kind {
+        #[cfg(feature = "ulid")]
+        IdKind::Ulid => {
+            let value = u128::from_be_bytes(fixed(bytes)?);
+            let ulid = crate::ids::ParsedUlid::from_parts(
+                (value >> 80) as u64,
+                value & crate::ids::ULID_MAX_RANDOM,
+            )?;
+            Some(ulid.canonical())
+        }
+        #[cfg(feature = "ksuid")]
+        IdKind::Ksuid => {
+            let bytes: [u8; 20] = fixed(bytes)?;
+            let offset = u32::from_be_bytes(fixed(&bytes[..4])?) as u64;
+            let ksuid = crate::ids::ParsedKsuid::from_parts(
+                offset + crate::ids::ksuid_id::KSUID_EPOCH,
+                fixed(&bytes[4..])?,
+            )?;
+            Some(ksuid.canonical())
+        }
+        #[cfg(feature = "xid")]
+        IdKind::Xid => Some(crate::ids::xid_id::xid_encode(&fixed(bytes)?)),
+        #[cfg(feature = "typeid")]
+        IdKind::TypeId => {
+            let suffix = crate::ids::typeid_id::typeid_base32_encode(&fixed(bytes)?);
+            Some(match vector.input.rsplit_once('_') {
+                Some((prefix, _)) => format!("{}_{}", prefix, suffix),
+                None => suffix,
+            })
+        }
+        #[cfg(feature = "uuid")]
+        kind if kind.is_uuid() => Some(uuid::Uuid::from_bytes(fixed(bytes)?).to_string()),
+        _ => None,
+    })
+}
+
+#[cfg_attr(not(feature = "uuid"), allow(unused_variables))]
+fn name_based_uuid(kind: IdKind, name: &str) -> Result<Option<String>> {
+    #[cfg(feature = "uuid")]
+    {
+        use crate::core::id::IdGenerator;
+        let version = match kind {
+            IdKind::UuidV3 => 3,
+            IdKind::UuidV5 => 5,
+            _ => return Ok(None),
+        };
+        crate::ids::UuidGenerator::new(version)
+            .with_namespace(uuid::Uuid::NAMESPACE_DNS)
+            .with_name(name.to_string())
+            .generate()
+            .map(Some)
+    }
+    #[cfg(not(feature = "uuid"))]
+    Ok(None)
+}
+
+#[cfg(any(
+    feature = "ulid",
+    feature = "ksuid",
+    feature = "xid",
+    feature = "typeid",
+    feature = "uuid"
+))]
+fn fixed<const N: usize>(bytes: &[u8]) -> Result<[u8; N]> {
+    bytes.try_into().map_err(|_| {
+        crate::core::error::IdtError::EncodingError(format!(
+            "expected {} bytes, got {}",
+            N,
+            bytes.len()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_vectors_pass() {
+        let failures: Vec<_> = run(&[]).into_iter().filter(|o| !o.passed).collect();
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+
+    #[test]
+    fn test_filter_by_kind() {
+        assert!(vectors(&[IdKind::Xid]).all(|v| v.kind == IdKind::Xid));
+        assert!(vectors(&[IdKind::Uuid]).all(|v| v.kind.is_uuid()));
+        assert_eq!(
+            vectors(&[]).count(),
+            VECTORS.iter().filter(|v| v.kind.is_enabled()).count()
+        );
+    }
+
+    #[test]
+    fn test_mismatch_is_reported() {
+        let wrong = valid(
+            IdKind::Ulid,
+            "test",
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "00000000000000000000000000000000",
+            Some(1),
+        );
+        let outcome = check(&wrong);
+        assert!(!outcome.passed);
+        assert_eq!(outcome.mismatches.len(), 2);
+
+        let accepted = check(&invalid(IdKind::Ulid, "test", "01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+        assert_eq!(outcome.id_type, "ulid");
+        assert_eq!(accepted.mismatches, vec!["accepted as ulid"]);
+    }
+}
@@ -0,0 +1,175 @@
+//! Probabilistic set membership for inputs too large to hold in memory.
+//!
+//! [`BloomFilter`] answers "seen before?" with no false negatives and a
+//! configurable false-positive rate, using a fixed bit array sized up front
+//! from the expected number of items. [`Seen`] puts an exact set and a
+//! filter behind one interface.
+
+use crate::core::error::{IdtError, Result};
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// A Bloom filter over byte strings
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    inserted: u64,
+}
+
+impl BloomFilter {
+    /// Size a filter for `expected` items at false-positive rate `fp_rate`
+    pub fn new(expected: u64, fp_rate: f64) -> Result<Self> {
+        if expected == 0 {
+            return Err(IdtError::InvalidArgument(
+                "expected item count must be greater than 0".to_string(),
+            ));
+        }
+        if !(fp_rate > 0.0 && fp_rate < 1.0) {
+            return Err(IdtError::InvalidArgument(
+                "false-positive rate must be between 0 and 1 (exclusive)".to_string(),
+            ));
+        }
+
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-(expected as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let num_bits = num_bits.max(64);
+        let num_hashes = ((num_bits as f64 / expected as f64) * ln2).round().max(1.0) as u32;
+        let words = usize::try_from(num_bits.div_ceil(64)).map_err(|_| {
+            IdtError::InvalidArgument("Bloom filter too large for this platform".to_string())
+        })?;
+
+        Ok(Self {
+            bits: vec![0; words],
+            num_bits,
+            num_hashes,
+            inserted: 0,
+        })
+    }
+
+    /// Size of the bit array in bytes
+    pub fn memory_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Whether `item` may have been inserted (never false for inserted items)
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.positions(item).all(|bit| self.get(bit))
+    }
+
+    /// Insert `item`, returning whether it may already have been present
+    pub fn check_and_insert(&mut self, item: &[u8]) -> bool {
+        let mut present = true;
+        for bit in self.positions(item) {
+            if !self.get(bit) {
+                present = false;
+                self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+        }
+        if !present {
+            self.inserted += 1;
+        }
+        present
+    }
+
+    /// Expected false-positive rate at the current fill level
+    pub fn current_fp_rate(&self) -> f64 {
+        let k = self.num_hashes as f64;
+        (1.0 - (-k * self.inserted as f64 / self.num_bits as f64).exp()).powf(k)
+    }
+
+    fn get(&self, bit: u64) -> bool {
+        self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+    }
+
+    /// Bit positions for `item` by double hashing (Kirsch-Mitzenmacher)
+    fn positions(&self, item: &[u8]) -> impl Iterator<Item = u64> + use<> {
+        let h1 = hash_with(item, 0);
+        let h2 = hash_with(item, 0x9e37_79b9_7f4a_7c15) | 1;
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+/// IDs seen so far: every ID exactly, or a Bloom filter that may report a
+/// never-seen ID as a duplicate at its false-positive rate
+pub enum Seen {
+    Exact(HashSet<String>),
+    Approximate(BloomFilter),
+}
+
+impl Seen {
+    /// Record `id`, returning whether it was (probably) seen before
+    pub fn check_and_insert(&mut self, id: &str) -> bool {
+        match self {
+            Seen::Exact(set) => !set.insert(id.to_string()),
+            Seen::Approximate(filter) => filter.check_and_insert(id.as_bytes()),
+        }
+    }
+}
+
+fn hash_with(item: &[u8], seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizing() {
+        // ~9.6 bits and 7 hashes per item at 1%
+        let filter = BloomFilter::new(1000, 0.01).unwrap();
+        assert_eq!(filter.num_bits, 9586);
+        assert_eq!(filter.num_hashes(), 7);
+        assert_eq!(filter.memory_bytes(), 150 * 8);
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(BloomFilter::new(0, 0.01).is_err());
+        assert!(BloomFilter::new(10, 0.0).is_err());
+        assert!(BloomFilter::new(10, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_no_false_negatives() {
+        let mut filter = BloomFilter::new(1000, 0.001).unwrap();
+        for i in 0..1000 {
+            filter.check_and_insert(format!("id-{}", i).as_bytes());
+        }
+        for i in 0..1000 {
+            assert!(filter.contains(format!("id-{}", i).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_check_and_insert() {
+        let mut filter = BloomFilter::new(100, 1e-6).unwrap();
+        assert!(!filter.check_and_insert(b"a"));
+        assert!(filter.check_and_insert(b"a"));
+        assert!(!filter.check_and_insert(b"b"));
+        assert_eq!(filter.inserted, 2);
+    }
+
+    #[test]
+    fn test_false_positive_rate_near_target() {
+        let mut filter = BloomFilter::new(10_000, 0.01).unwrap();
+        for i in 0..10_000 {
+            filter.check_and_insert(format!("in-{}", i).as_bytes());
+        }
+        let false_positives = (0..10_000)
+            .filter(|i| filter.contains(format!("out-{}", i).as_bytes()))
+            .count();
+        assert!(false_positives < 200, "{}", false_positives);
+        assert!((filter.current_fp_rate() - 0.01).abs() < 0.005);
+    }
+}
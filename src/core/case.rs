@@ -0,0 +1,174 @@
+//! Letter case of the canonical form for kinds whose text is case-insensitive.
+//!
+//! ULID and TSID are Crockford Base32, which decodes either case. idt writes
+//! them uppercase, as their specs do, but a database may hold them lowercase.
+//! The policy here is process-wide: `canonical()`, generated IDs, `convert`,
+//! and `validate --strict` all follow it.
+
+use crate::core::error::{IdtError, Result};
+use crate::core::id::IdKind;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Letter case of a canonical ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanonicalCase {
+    Upper,
+    Lower,
+}
+
+impl CanonicalCase {
+    pub fn apply(self, s: &mut str) {
+        match self {
+            CanonicalCase::Upper => s.make_ascii_uppercase(),
+            CanonicalCase::Lower => s.make_ascii_lowercase(),
+        }
+    }
+}
+
+impl fmt::Display for CanonicalCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanonicalCase::Upper => write!(f, "upper"),
+            CanonicalCase::Lower => write!(f, "lower"),
+        }
+    }
+}
+
+impl FromStr for CanonicalCase {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "upper" => Ok(CanonicalCase::Upper),
+            "lower" => Ok(CanonicalCase::Lower),
+            _ => Err(IdtError::InvalidArgument(format!(
+                "Unknown case '{}': expected upper or lower",
+                s
+            ))),
+        }
+    }
+}
+
+/// Kinds with a case policy. Their default is uppercase.
+pub const CASE_INSENSITIVE_KINDS: &[IdKind] = &[IdKind::Ulid, IdKind::Tsid];
+
+static ULID_LOWER: AtomicBool = AtomicBool::new(false);
+static TSID_LOWER: AtomicBool = AtomicBool::new(false);
+
+fn flag(kind: IdKind) -> Option<&'static AtomicBool> {
+    match kind {
+        IdKind::Ulid => Some(&ULID_LOWER),
+        IdKind::Tsid => Some(&TSID_LOWER),
+        _ => None,
+    }
+}
+
+/// Case `kind` is written in. Kinds without a policy report uppercase.
+pub fn canonical_case(kind: IdKind) -> CanonicalCase {
+    match flag(kind) {
+        Some(lower) if lower.load(Ordering::Relaxed) => CanonicalCase::Lower,
+        _ => CanonicalCase::Upper,
+    }
+}
+
+fn fixed_case(kind: IdKind) -> IdtError {
+    IdtError::InvalidArgument(format!(
+        "{} has a fixed case; --canonical-case applies to {}",
+        kind.name(),
+        CASE_INSENSITIVE_KINDS
+            .iter()
+            .map(|k| k.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+/// Write `kind` in `case` for the rest of the process
+pub fn set_canonical_case(kind: IdKind, case: CanonicalCase) -> Result<()> {
+    let lower = flag(kind).ok_or_else(|| fixed_case(kind))?;
+    lower.store(case == CanonicalCase::Lower, Ordering::Relaxed);
+    Ok(())
+}
+
+/// One `--canonical-case` entry: `lower` for every kind, or `ulid=lower`
+/// for one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CasePolicy {
+    pub kind: Option<IdKind>,
+    pub case: CanonicalCase,
+}
+
+impl CasePolicy {
+    /// Make this policy the process-wide setting
+    pub fn apply(self) -> Result<()> {
+        match self.kind {
+            Some(kind) => set_canonical_case(kind, self.case),
+            None => CASE_INSENSITIVE_KINDS
+                .iter()
+                .try_for_each(|&kind| set_canonical_case(kind, self.case)),
+        }
+    }
+}
+
+impl FromStr for CasePolicy {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, case) = match s.split_once('=') {
+            Some((kind, case)) => (Some(kind.trim().parse::<IdKind>()?), case),
+            None => (None, s),
+        };
+        if let Some(kind) = kind
+            && flag(kind).is_none()
+        {
+            return Err(fixed_case(kind));
+        }
+        Ok(Self {
+            kind,
+            case: case.trim().parse()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_policy_from_str() {
+        assert_eq!(
+            "lower".parse::<CasePolicy>().unwrap(),
+            CasePolicy {
+                kind: None,
+                case: CanonicalCase::Lower
+            }
+        );
+        assert_eq!(
+            "ULID=upper".parse::<CasePolicy>().unwrap(),
+            CasePolicy {
+                kind: Some(IdKind::Ulid),
+                case: CanonicalCase::Upper
+            }
+        );
+        assert!("uuid=lower".parse::<CasePolicy>().is_err());
+        assert!("ulid=title".parse::<CasePolicy>().is_err());
+        assert!("sideways".parse::<CasePolicy>().is_err());
+    }
+
+    #[test]
+    fn test_fixed_case_kinds() {
+        assert_eq!(canonical_case(IdKind::Uuid), CanonicalCase::Upper);
+        assert!(set_canonical_case(IdKind::TypeId, CanonicalCase::Lower).is_err());
+    }
+
+    #[test]
+    fn test_canonical_case_apply() {
+        let mut s = String::from("01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        CanonicalCase::Lower.apply(&mut s);
+        assert_eq!(s, "01arz3ndektsv4rrffq69g5fav");
+        CanonicalCase::Upper.apply(&mut s);
+        assert_eq!(s, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+    }
+}
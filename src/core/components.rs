@@ -0,0 +1,197 @@
+//! Typed views of the fields packed into an ID.
+//!
+//! [`ParsedId::components`](crate::core::id::ParsedId::components) returns
+//! these for library users; the CLI keeps printing the JSON `components` of
+//! [`InspectionResult`](crate::core::id::InspectionResult).
+
+use uuid::Uuid;
+
+/// Decoded fields of a parsed ID, one variant per structured ID family
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Components {
+    Uuid(UuidComponents),
+    Ulid(UlidComponents),
+    Snowflake(SnowflakeComponents),
+    Ksuid(KsuidComponents),
+    ObjectId(ObjectIdComponents),
+    Xid(XidComponents),
+    Tsid(TsidComponents),
+    TypeId(TypeIdComponents),
+}
+
+impl Components {
+    /// Embedded Unix time in milliseconds, if the ID carries one
+    pub fn timestamp_ms(&self) -> Option<u64> {
+        match self {
+            Components::Uuid(c) => c.timestamp_ms,
+            Components::Ulid(c) => Some(c.timestamp_ms),
+            Components::Snowflake(c) => Some(c.timestamp_ms),
+            Components::Ksuid(c) => Some(c.timestamp_secs * 1000),
+            Components::ObjectId(c) => Some(c.timestamp_secs as u64 * 1000),
+            Components::Xid(c) => Some(c.timestamp_secs as u64 * 1000),
+            Components::Tsid(c) => Some(c.timestamp_ms),
+            Components::TypeId(c) => c.timestamp_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UuidComponents {
+    pub version: Option<u8>,
+    pub variant: &'static str,
+    /// Present for v1, v6, and v7
+    pub timestamp_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UlidComponents {
+    pub timestamp_ms: u64,
+    /// The low 80 bits
+    pub random: u128,
+}
+
+/// Fields of a Snowflake, laid out by its preset or custom schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnowflakeComponents {
+    pub timestamp_ms: u64,
+    /// Epoch the timestamp counts from (Unix milliseconds)
+    pub epoch: u64,
+    /// Every non-timestamp field in layout order, e.g. `("machine_id", 24)`
+    pub fields: Vec<(&'static str, u64)>,
+}
+
+impl SnowflakeComponents {
+    /// Value of the layout field `name`
+    pub fn field(&self, name: &str) -> Option<u64> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|&(_, value)| value)
+    }
+
+    pub fn datacenter_id(&self) -> Option<u64> {
+        self.field("datacenter_id")
+    }
+
+    pub fn machine_id(&self) -> Option<u64> {
+        self.field("machine_id")
+    }
+
+    pub fn sequence(&self) -> Option<u64> {
+        self.field("sequence")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KsuidComponents {
+    /// Unix seconds (not the KSUID epoch offset)
+    pub timestamp_secs: u64,
+    pub payload: [u8; 16],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectIdComponents {
+    pub timestamp_secs: u32,
+    pub random: [u8; 5],
+    pub counter: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XidComponents {
+    pub timestamp_secs: u32,
+    pub machine_id: [u8; 3],
+    pub process_id: u16,
+    pub counter: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TsidComponents {
+    pub timestamp_ms: u64,
+    /// The low 22 bits: node and counter, or randomness
+    pub random_bits: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeIdComponents {
+    pub prefix: String,
+    pub uuid: Uuid,
+    /// Present when the suffix is a UUIDv7
+    pub timestamp_ms: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::id::IdKind;
+    use crate::ids::parse_id;
+
+    fn components(id: &str, kind: IdKind) -> Components {
+        parse_id(id, Some(kind)).unwrap().components().unwrap()
+    }
+
+    #[test]
+    fn test_snowflake() {
+        let Components::Snowflake(c) = components("1234567890123456789", IdKind::Snowflake) else {
+            panic!("expected Snowflake components");
+        };
+        assert_eq!(c.datacenter_id(), Some(20));
+        assert_eq!(c.machine_id(), Some(24));
+        assert_eq!(c.sequence(), Some(277));
+        assert_eq!(c.field("shard_id"), None);
+    }
+
+    #[test]
+    fn test_ulid() {
+        let c = components("01ARZ3NDEKTSV4RRFFQ69G5FAV", IdKind::Ulid);
+        assert_eq!(c.timestamp_ms(), Some(1_469_922_850_259));
+        let Components::Ulid(ulid) = c else {
+            panic!("expected ULID components");
+        };
+        assert!(ulid.random < 1 << 80);
+    }
+
+    #[test]
+    fn test_uuid_and_typeid() {
+        let c = components("550e8400-e29b-41d4-a716-446655440000", IdKind::Uuid);
+        assert_eq!(
+            c,
+            Components::Uuid(UuidComponents {
+                version: Some(4),
+                variant: "RFC4122",
+                timestamp_ms: None,
+            })
+        );
+
+        let Components::TypeId(c) = components("user_01h455vb4pex5vsknk084sn02q", IdKind::TypeId)
+        else {
+            panic!("expected TypeID components");
+        };
+        assert_eq!(c.prefix, "user");
+        assert_eq!(c.uuid.get_version_num(), 7);
+        assert!(c.timestamp_ms.is_some());
+    }
+
+    #[test]
+    fn test_matches_inspection_timestamp() {
+        for (id, kind) in [
+            ("0ujtsYcgvSTl8PAuAdqWYSMnLOv", IdKind::Ksuid),
+            ("507f1f77bcf86cd799439011", IdKind::ObjectId),
+            ("9m4e2mr0ui3e8a215n4g", IdKind::Xid),
+            ("0AWE5HZP3SKTK", IdKind::Tsid),
+        ] {
+            let parsed = parse_id(id, Some(kind)).unwrap();
+            assert_eq!(
+                parsed.components().unwrap().timestamp_ms(),
+                parsed.timestamp().map(|ts| ts.millis),
+                "{}",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_unstructured_ids_have_none() {
+        let parsed = parse_id("V1StGXR8_Z5jdHi6B-myT", Some(IdKind::NanoId)).unwrap();
+        assert!(parsed.components().is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! SQL literals that store an ID in a database's idiomatic column type.
+//!
+//! UUIDs and ULIDs go into native UUID or 16-byte binary columns, Snowflake
+//! and TSID values into 64-bit integers, and every other ID into text. The
+//! byte order for SQL Server's `uniqueidentifier` is handled here so callers
+//! never have to think about it.
+
+use crate::core::id::{IdKind, ParsedId};
+use uuid::Uuid;
+
+/// Target database for `--db`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Database {
+    /// PostgreSQL: `'…'::uuid`
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "postgres", alias = "postgresql", alias = "pg")
+    )]
+    Postgres,
+    /// MySQL and MariaDB: `UNHEX('…')` for BINARY(16)
+    #[cfg_attr(feature = "cli", value(name = "mysql", alias = "mariadb"))]
+    Mysql,
+    /// SQL Server: `CONVERT(uniqueidentifier, 0x…)` in its mixed-endian byte order
+    #[cfg_attr(feature = "cli", value(name = "mssql", alias = "sqlserver"))]
+    Mssql,
+    /// Oracle: `HEXTORAW('…')` for RAW(16)
+    #[cfg_attr(feature = "cli", value(name = "oracle"))]
+    Oracle,
+}
+
+impl Database {
+    /// The SQL literal for `id`
+    pub fn literal(self, id: &dyn ParsedId) -> String {
+        let kind = id.kind();
+        if kind.is_uuid() || kind == IdKind::Ulid {
+            let bytes = id.as_bytes();
+            if let Ok(uuid) = Uuid::from_slice(&bytes) {
+                return self.uuid_literal(&uuid);
+            }
+        }
+        if matches!(kind, IdKind::Snowflake | IdKind::Tsid)
+            && let Some(value) = id.as_u64()
+        {
+            return value.to_string();
+        }
+        quote(&id.canonical())
+    }
+
+    fn uuid_literal(self, uuid: &Uuid) -> String {
+        match self {
+            Database::Postgres => format!("'{}'::uuid", uuid.hyphenated()),
+            Database::Mysql => format!("UNHEX('{}')", uuid.simple()),
+            // uniqueidentifier stores the first three groups little-endian,
+            // so a binary literal must be swapped to display as `uuid`
+            Database::Mssql => format!(
+                "CONVERT(uniqueidentifier, 0x{})",
+                hex::encode_upper(uuid.to_bytes_le())
+            ),
+            Database::Oracle => format!("HEXTORAW('{}')", hex::encode_upper(uuid.as_bytes())),
+        }
+    }
+}
+
+/// A single-quoted SQL string literal
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::parse_id;
+
+    const UUID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+    fn literal(id: &str, db: Database) -> String {
+        db.literal(parse_id(id, None).unwrap().as_ref())
+    }
+
+    #[test]
+    fn test_uuid_literals() {
+        assert_eq!(
+            literal(UUID, Database::Postgres),
+            "'550e8400-e29b-41d4-a716-446655440000'::uuid"
+        );
+        assert_eq!(
+            literal(UUID, Database::Mysql),
+            "UNHEX('550e8400e29b41d4a716446655440000')"
+        );
+        assert_eq!(
+            literal(UUID, Database::Mssql),
+            "CONVERT(uniqueidentifier, 0x00840E559BE2D441A716446655440000)"
+        );
+        assert_eq!(
+            literal(UUID, Database::Oracle),
+            "HEXTORAW('550E8400E29B41D4A716446655440000')"
+        );
+    }
+
+    #[test]
+    fn test_ulid_stored_as_uuid() {
+        assert_eq!(
+            literal("01ARZ3NDEKTSV4RRFFQ69G5FAV", Database::Postgres),
+            "'01563e3a-b5d3-d676-4c61-efb99302bd5b'::uuid"
+        );
+    }
+
+    #[test]
+    fn test_integer_and_text_literals() {
+        let snowflake = parse_id("1234567890123456789", Some(IdKind::Snowflake)).unwrap();
+        assert_eq!(
+            Database::Mysql.literal(snowflake.as_ref()),
+            "1234567890123456789"
+        );
+        let ksuid = parse_id("aWgEPTl1tmebfsQzFP4bxwgy80V", Some(IdKind::Ksuid)).unwrap();
+        assert_eq!(
+            Database::Oracle.literal(ksuid.as_ref()),
+            "'aWgEPTl1tmebfsQzFP4bxwgy80V'"
+        );
+        assert_eq!(quote("it's"), "'it''s'");
+    }
+}
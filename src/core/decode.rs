@@ -0,0 +1,313 @@
+//! Table-driven decoders for the parse hot path.
+//!
+//! Each alphabet is a 256-entry lookup table in which bytes outside the
+//! alphabet map to [`INVALID`]. Decoders OR every looked-up value together and
+//! test the high bit once at the end, so the inner loops have no
+//! data-dependent branches and decode into fixed-size arrays without
+//! allocating.
+
+/// Table value for bytes outside the alphabet
+pub const INVALID: u8 = 0xFF;
+
+/// Lowercase and uppercase hexadecimal digits
+pub static HEX: [u8; 256] = build_table(b"0123456789abcdef", true);
+
+/// RFC 4648 base32hex, lowercase (as used by Xid)
+pub static BASE32_HEX: [u8; 256] = build_table(b"0123456789abcdefghijklmnopqrstuv", false);
+
+/// Base62 in `0-9A-Za-z` order (as used by KSUID)
+pub static BASE62: [u8; 256] = build_table(
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+    false,
+);
+
+/// Base62 digits per chunk: 62^5 is the largest power of 62 that fits in a u32
+const BASE62_CHUNK_DIGITS: usize = 5;
+
+/// Output sizes up to 32 bytes are supported by [`decode_base62`]
+const BASE62_MAX_LIMBS: usize = 8;
+
+const fn build_table(alphabet: &[u8], case_insensitive: bool) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < alphabet.len() {
+        let c = alphabet[i];
+        table[c as usize] = i as u8;
+        if case_insensitive {
+            table[c.to_ascii_uppercase() as usize] = i as u8;
+        }
+        i += 1;
+    }
+    table
+}
+
+/// Map each byte of `src` through `table`, or `None` if any byte is invalid
+pub fn lookup<const N: usize>(src: &[u8], table: &[u8; 256]) -> Option<[u8; N]> {
+    if src.len() != N {
+        return None;
+    }
+    let mut out = [0u8; N];
+    let mut bad = 0u8;
+    for (value, &c) in out.iter_mut().zip(src) {
+        *value = table[c as usize];
+        bad |= *value;
+    }
+    (bad & 0x80 == 0).then_some(out)
+}
+
+/// Decode exactly `2 * N` hex digits (either case) into `N` bytes
+pub fn decode_hex<const N: usize>(src: &[u8]) -> Option<[u8; N]> {
+    if src.len() != N * 2 {
+        return None;
+    }
+    let mut out = [0u8; N];
+    let mut bad = 0u8;
+    for (byte, pair) in out.iter_mut().zip(src.chunks_exact(2)) {
+        let hi = HEX[pair[0] as usize];
+        let lo = HEX[pair[1] as usize];
+        bad |= hi | lo;
+        *byte = (hi << 4) | (lo & 0x0F);
+    }
+    (bad & 0x80 == 0).then_some(out)
+}
+
+/// Decode a big-endian base62 number into `N` bytes.
+///
+/// Returns `None` on invalid characters or if the value does not fit in `N`
+/// bytes. Digits are consumed five at a time (62^5 fits in a u32), so the
+/// big-number multiply runs once per chunk rather than once per character.
+pub fn decode_base62<const N: usize>(src: &[u8]) -> Option<[u8; N]> {
+    const { assert!(N <= BASE62_MAX_LIMBS * 4) };
+    let limb_count = N.div_ceil(4);
+    let mut limbs = [0u32; BASE62_MAX_LIMBS];
+    let limbs = &mut limbs[BASE62_MAX_LIMBS - limb_count..];
+
+    let mut bad = 0u8;
+    let mut overflow = 0u64;
+    for chunk in src.chunks(BASE62_CHUNK_DIGITS) {
+        let mut value = 0u64;
+        let mut multiplier = 1u64;
+        for &c in chunk {
+            let digit = BASE62[c as usize];
+            bad |= digit;
+            value = value * 62 + (digit & 0x3F) as u64;
+            multiplier *= 62;
+        }
+
+        let mut carry = value;
+        for limb in limbs.iter_mut().rev() {
+            let acc = *limb as u64 * multiplier + carry;
+            *limb = acc as u32;
+            carry = acc >> 32;
+        }
+        overflow |= carry;
+    }
+
+    let mut wide = [0u8; BASE62_MAX_LIMBS * 4];
+    for (bytes, limb) in wide.chunks_exact_mut(4).zip(limbs.iter()) {
+        bytes.copy_from_slice(&limb.to_be_bytes());
+    }
+    let (high, low) = wide[..limb_count * 4].split_at(limb_count * 4 - N);
+    for &b in high {
+        overflow |= b as u64;
+    }
+
+    if bad & 0x80 != 0 || overflow != 0 {
+        return None;
+    }
+    let mut out = [0u8; N];
+    out.copy_from_slice(low);
+    Some(out)
+}
+
+/// First character of `s` that is not in `table`, for error messages
+pub fn find_invalid(s: &str, table: &[u8; 256]) -> Option<(usize, char)> {
+    s.char_indices()
+        .find(|&(_, c)| !c.is_ascii() || table[c as usize] == INVALID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scalar reference: multiply the whole number by 62 for every digit
+    fn reference_base62<const N: usize>(src: &[u8]) -> Option<[u8; N]> {
+        let mut num = [0u8; N];
+        for &c in src {
+            let v = (c as char)
+                .to_digit(36)
+                .map(|d| if c.is_ascii_lowercase() { d + 26 } else { d })?;
+            let mut carry = v as u16;
+            for byte in num.iter_mut().rev() {
+                let acc = *byte as u16 * 62 + carry;
+                *byte = acc as u8;
+                carry = acc >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(num)
+    }
+
+    fn encode_base62(bytes: &[u8], len: usize) -> Vec<u8> {
+        let alphabet = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+        let mut num = bytes.to_vec();
+        let mut out = vec![b'0'; len];
+        for slot in out.iter_mut().rev() {
+            let mut rem = 0u16;
+            for byte in num.iter_mut() {
+                let acc = (rem << 8) | *byte as u16;
+                *byte = (acc / 62) as u8;
+                rem = acc % 62;
+            }
+            *slot = alphabet[rem as usize];
+        }
+        out
+    }
+
+    #[test]
+    fn test_hex_table_exhaustive() {
+        for b in 0..=255u8 {
+            let expected = (b as char).to_digit(16).map(|d| d as u8);
+            let actual = (HEX[b as usize] != INVALID).then_some(HEX[b as usize]);
+            assert_eq!(actual, expected, "byte {:#04x}", b);
+        }
+    }
+
+    #[test]
+    fn test_base32_hex_table_exhaustive() {
+        for b in 0..=255u8 {
+            let expected = match b {
+                b'0'..=b'9' => Some(b - b'0'),
+                b'a'..=b'v' => Some(b - b'a' + 10),
+                _ => None,
+            };
+            let actual = (BASE32_HEX[b as usize] != INVALID).then_some(BASE32_HEX[b as usize]);
+            assert_eq!(actual, expected, "byte {:#04x}", b);
+        }
+    }
+
+    #[test]
+    fn test_base62_table_exhaustive() {
+        for b in 0..=255u8 {
+            let expected = match b {
+                b'0'..=b'9' => Some(b - b'0'),
+                b'A'..=b'Z' => Some(b - b'A' + 10),
+                b'a'..=b'z' => Some(b - b'a' + 36),
+                _ => None,
+            };
+            let actual = (BASE62[b as usize] != INVALID).then_some(BASE62[b as usize]);
+            assert_eq!(actual, expected, "byte {:#04x}", b);
+        }
+    }
+
+    #[test]
+    fn test_hex_all_byte_pairs() {
+        for hi in 0..=255u8 {
+            for lo in 0..=255u8 {
+                let expected = match ((hi as char).to_digit(16), (lo as char).to_digit(16)) {
+                    (Some(h), Some(l)) => Some([(h * 16 + l) as u8]),
+                    _ => None,
+                };
+                assert_eq!(decode_hex::<1>(&[hi, lo]), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_round_trip_every_byte_every_position() {
+        for pos in 0..12 {
+            for b in 0..=255u8 {
+                let mut bytes = [0x5Au8; 12];
+                bytes[pos] = b;
+                let lower = hex::encode(bytes);
+                let upper = lower.to_uppercase();
+                assert_eq!(decode_hex::<12>(lower.as_bytes()), Some(bytes));
+                assert_eq!(decode_hex::<12>(upper.as_bytes()), Some(bytes));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_rejects_wrong_length() {
+        assert_eq!(decode_hex::<2>(b"abc"), None);
+        assert_eq!(decode_hex::<2>(b"abcdef"), None);
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup::<3>(b"09v", &BASE32_HEX), Some([0, 9, 31]));
+        assert_eq!(lookup::<3>(b"09w", &BASE32_HEX), None);
+        assert_eq!(lookup::<3>(b"09", &BASE32_HEX), None);
+    }
+
+    #[test]
+    fn test_base62_every_digit_every_position() {
+        for pos in 0..27 {
+            for d in 0..62u8 {
+                let mut src = [b'0'; 27];
+                src[pos] = BASE62.iter().position(|&v| v == d).unwrap() as u8;
+                assert_eq!(
+                    decode_base62::<20>(&src),
+                    reference_base62::<20>(&src),
+                    "{}",
+                    String::from_utf8_lossy(&src)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_base62_round_trip_random() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..10_000 {
+            let mut bytes = [0u8; 20];
+            rng.fill(&mut bytes);
+            let encoded = encode_base62(&bytes, 27);
+            assert_eq!(decode_base62::<20>(&encoded), Some(bytes));
+        }
+    }
+
+    #[test]
+    fn test_base62_other_sizes() {
+        for len in [1usize, 3, 4, 5, 16, 32] {
+            let bytes: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(37) | 1).collect();
+            let encoded = encode_base62(&bytes, (len * 8).div_ceil(5) + 1);
+            let decoded: Vec<u8> = match len {
+                1 => decode_base62::<1>(&encoded).map(|a| a.to_vec()),
+                3 => decode_base62::<3>(&encoded).map(|a| a.to_vec()),
+                4 => decode_base62::<4>(&encoded).map(|a| a.to_vec()),
+                5 => decode_base62::<5>(&encoded).map(|a| a.to_vec()),
+                16 => decode_base62::<16>(&encoded).map(|a| a.to_vec()),
+                _ => decode_base62::<32>(&encoded).map(|a| a.to_vec()),
+            }
+            .unwrap();
+            assert_eq!(decoded, bytes, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_base62_overflow() {
+        // Largest KSUID, then one more
+        assert_eq!(
+            decode_base62::<20>(b"aWgEPTl1tmebfsQzFP4bxwgy80V"),
+            Some([0xFF; 20])
+        );
+        assert_eq!(decode_base62::<20>(b"aWgEPTl1tmebfsQzFP4bxwgy80W"), None);
+        assert_eq!(decode_base62::<20>(b"zzzzzzzzzzzzzzzzzzzzzzzzzzz"), None);
+    }
+
+    #[test]
+    fn test_base62_invalid_char() {
+        assert_eq!(decode_base62::<20>(b"0ujtsYcgvSTl8PAuAdqWYSMnLO!"), None);
+    }
+
+    #[test]
+    fn test_find_invalid() {
+        assert_eq!(find_invalid("abc-def", &HEX), Some((3, '-')));
+        assert_eq!(find_invalid("abcé", &HEX), Some((3, 'é')));
+        assert_eq!(find_invalid("abcdef", &HEX), None);
+    }
+}
@@ -14,14 +14,76 @@ impl DetectionResult {
     }
 }
 
+/// Constraints applied to auto-detection
+#[derive(Debug, Clone, Default)]
+pub struct DetectionOptions {
+    /// Only consider these kinds (all kinds if empty)
+    pub only: Vec<IdKind>,
+    /// Never consider these kinds
+    pub exclude: Vec<IdKind>,
+    /// Drop candidates below this confidence
+    pub min_confidence: f32,
+}
+
+impl DetectionOptions {
+    pub fn with_only(mut self, kinds: Vec<IdKind>) -> Self {
+        self.only = kinds;
+        self
+    }
+
+    pub fn with_exclude(mut self, kinds: Vec<IdKind>) -> Self {
+        self.exclude = kinds;
+        self
+    }
+
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    /// Whether a candidate survives these constraints
+    pub fn accepts(&self, result: &DetectionResult) -> bool {
+        result.confidence >= self.min_confidence
+            && (self.only.is_empty() || self.only.iter().any(|&k| result.kind.matches_filter(k)))
+            && !self.exclude.iter().any(|&k| result.kind.matches_filter(k))
+    }
+}
+
+/// `uuidv4 (1.00), ulid (0.95)`, for logs
+fn candidate_list(results: &[DetectionResult]) -> String {
+    results
+        .iter()
+        .map(|r| format!("{} ({:.2})", r.kind.name(), r.confidence))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Detect the ID type from a string
 pub fn detect_id_type(input: &str) -> Result<Vec<DetectionResult>> {
+    detect_id_type_with(input, &DetectionOptions::default())
+}
+
+/// Detect the ID type from a string, keeping only candidates `options` accepts
+pub fn detect_id_type_with(
+    input: &str,
+    options: &DetectionOptions,
+) -> Result<Vec<DetectionResult>> {
     let input = input.trim();
     let mut results = Vec::new();
 
-    // Check UUID format (with dashes)
-    if is_uuid_format(input) {
-        if let Some(version) = detect_uuid_version(input) {
+    // A loaded schema describes exactly this deployment's IDs, so it wins
+    #[cfg(feature = "custom")]
+    if crate::ids::custom_id::active().is_some_and(|schema| schema.matches(input)) {
+        results.push(DetectionResult::new(IdKind::Custom, 0.97));
+    }
+
+    // Check UUID format (with dashes), also inside `urn:uuid:` or `{...}`
+    #[cfg(feature = "uuid")]
+    let bare_uuid = crate::ids::uuid_id::strip_uuid_wrapper(input);
+    #[cfg(not(feature = "uuid"))]
+    let bare_uuid = input;
+    if is_uuid_format(bare_uuid) {
+        if let Some(version) = detect_uuid_version(bare_uuid) {
             results.push(DetectionResult::new(version, 1.0));
         } else {
             results.push(DetectionResult::new(IdKind::Uuid, 0.9));
@@ -29,10 +91,16 @@ pub fn detect_id_type(input: &str) -> Result<Vec<DetectionResult>> {
     }
 
     // Check UUID format (without dashes - 32 hex chars)
-    if input.len() == 32 && input.chars().all(|c| c.is_ascii_hexdigit()) {
+    if bare_uuid.len() == 32 && bare_uuid.chars().all(|c| c.is_ascii_hexdigit()) {
         results.push(DetectionResult::new(IdKind::Uuid, 0.7));
     }
 
+    // Check Bluetooth short UUID (0x and 4 or 8 hex digits)
+    #[cfg(feature = "uuid")]
+    if crate::ids::expand_bluetooth_short(input).is_some() {
+        results.push(DetectionResult::new(IdKind::Uuid, 0.9));
+    }
+
     // Check ULID format (26 chars, Crockford Base32)
     if is_ulid_format(input) {
         results.push(DetectionResult::new(IdKind::Ulid, 0.95));
@@ -43,8 +111,17 @@ pub fn detect_id_type(input: &str) -> Result<Vec<DetectionResult>> {
         results.push(DetectionResult::new(IdKind::TypeId, 0.95));
     }
 
-    // Check ObjectId format (24 hex chars)
-    if is_objectid_format(input) {
+    // Check ObjectId format (24 hex chars); a mongosh or Extended JSON
+    // wrapper makes it unambiguous
+    #[cfg(feature = "objectid")]
+    let wrapped = crate::ids::objectid_id::strip_objectid_wrapper(input);
+    #[cfg(not(feature = "objectid"))]
+    let wrapped = None;
+    if let Some(hex) = wrapped {
+        if is_objectid_format(hex) {
+            results.push(DetectionResult::new(IdKind::ObjectId, 1.0));
+        }
+    } else if is_objectid_format(input) {
         results.push(DetectionResult::new(IdKind::ObjectId, 0.85));
     }
 
@@ -58,7 +135,7 @@ pub fn detect_id_type(input: &str) -> Result<Vec<DetectionResult>> {
         results.push(DetectionResult::new(IdKind::Xid, 0.8));
     }
 
-    // Check Snowflake (numeric, 15-19 digits)
+    // Check Snowflake (numeric, 15-19 digits, optionally negative)
     if is_snowflake_format(input) {
         results.push(DetectionResult::new(IdKind::Snowflake, 0.8));
     }
@@ -147,8 +224,22 @@ pub fn detect_id_type(input: &str) -> Result<Vec<DetectionResult>> {
         results.push(DetectionResult::new(IdKind::Asin, 0.60));
     }
 
+    // Families left out of the build can't be parsed
+    results.retain(|r| r.kind.is_enabled());
+
+    let before = results.len();
+    results.retain(|r| options.accepts(r));
+    if results.len() < before {
+        tracing::debug!(
+            input,
+            dropped = before - results.len(),
+            "candidates excluded by detection options"
+        );
+    }
+
     // Sort by confidence descending
     results.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    tracing::debug!(input, candidates = %candidate_list(&results), "detected");
 
     if results.is_empty() {
         Err(crate::core::error::IdtError::DetectionFailed)
@@ -157,6 +248,48 @@ pub fn detect_id_type(input: &str) -> Result<Vec<DetectionResult>> {
     }
 }
 
+/// The shape auto-detection matches for `kind`, in words
+pub fn detection_rule(kind: IdKind) -> &'static str {
+    match kind {
+        IdKind::Uuid => {
+            "32 hex digits, with or without 8-4-4-4-12 hyphens, or a Bluetooth short UUID (0x180D)"
+        }
+        IdKind::UuidNil => "8-4-4-4-12 hex digits, all zero",
+        IdKind::UuidMax => "8-4-4-4-12 hex digits, all f",
+        IdKind::UuidV1
+        | IdKind::UuidV3
+        | IdKind::UuidV4
+        | IdKind::UuidV5
+        | IdKind::UuidV6
+        | IdKind::UuidV7 => {
+            "8-4-4-4-12 hex digits; the first digit of the third group is the version"
+        }
+        IdKind::Ulid => "26 Crockford Base32 characters, the first 0-7",
+        IdKind::TypeId => "a lowercase prefix, an underscore, and 26 base32 characters",
+        IdKind::ObjectId => "24 hex digits",
+        IdKind::Ksuid => "27 alphanumeric (base62) characters",
+        IdKind::Xid => "20 base32hex characters (0-9, a-v) ending in 0 or g",
+        IdKind::Snowflake => "an integer of 15-19 digits",
+        IdKind::Tsid => "13 Crockford Base32 characters",
+        IdKind::Cuid => "25 lowercase alphanumerics starting with c",
+        IdKind::NanoId => "21 URL-safe characters (A-Z, a-z, 0-9, _ and -)",
+        IdKind::Cuid2 => "24 lowercase alphanumerics starting with a letter",
+        IdKind::ShortUuid => "22 characters of the base57 shortuuid alphabet",
+        IdKind::Isin => "2 letters, 9 alphanumerics, and a Luhn check digit",
+        IdKind::Ismn => "13 digits starting 979-0 with a valid check digit",
+        IdKind::Isbn13 => "13 digits starting 978 or 979 with a valid check digit",
+        IdKind::Ean13 => "13 digits with a valid check digit",
+        IdKind::Gtin14 => "14 digits with a valid check digit",
+        IdKind::UpcA => "12 digits with a valid check digit",
+        IdKind::Isni => "15 digits and a MOD 11-2 check character",
+        IdKind::Ean8 => "8 digits with a valid check digit",
+        IdKind::Isbn10 => "9 digits and a MOD 11 check character",
+        IdKind::Issn => "7 digits and a MOD 11 check character",
+        IdKind::Asin => "10 alphanumerics starting with B or a digit",
+        IdKind::Custom => "the pattern of the schema loaded with --schema",
+    }
+}
+
 /// Check if input matches UUID format with dashes
 fn is_uuid_format(input: &str) -> bool {
     if input.len() != 36 {
@@ -240,20 +373,22 @@ fn is_ulid_format(input: &str) -> bool {
     input_upper.chars().all(|c| CROCKFORD.contains(c))
 }
 
-/// Check if input matches Snowflake format (numeric, 15-19 digits)
+/// Check if input matches Snowflake format (numeric, 15-19 digits). A
+/// leading `-` is a signed 64-bit value, as logged by Java services.
 fn is_snowflake_format(input: &str) -> bool {
-    if input.is_empty() {
+    let digits = input.strip_prefix('-').unwrap_or(input);
+    if digits.is_empty() {
         return false;
     }
 
     // Must be all digits
-    if !input.chars().all(|c| c.is_ascii_digit()) {
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
         return false;
     }
 
     // Snowflake IDs are typically 15-19 digits
     // (depends on epoch and timestamp)
-    let len = input.len();
+    let len = digits.len();
     (15..=19).contains(&len)
 }
 
@@ -280,9 +415,12 @@ fn is_ksuid_format(input: &str) -> bool {
     input.len() == 27 && input.chars().all(|c| c.is_ascii_alphanumeric())
 }
 
-/// Check if input matches Xid format (20 chars, all in [0-9a-v])
+/// Check if input matches Xid format (20 chars, all in [0-9a-v], ending in
+/// 0 or g since the last character is mostly padding)
 fn is_xid_format(input: &str) -> bool {
-    input.len() == 20 && input.chars().all(|c| matches!(c, '0'..='9' | 'a'..='v'))
+    input.len() == 20
+        && input.chars().all(|c| matches!(c, '0'..='9' | 'a'..='v'))
+        && matches!(input.as_bytes()[19], b'0' | b'g')
 }
 
 /// Check if input matches TSID format (13 Crockford Base32 chars)
@@ -511,6 +649,13 @@ mod tests {
         assert_eq!(results[0].kind, IdKind::UuidV4);
     }
 
+    #[test]
+    fn test_detect_bluetooth_short_uuid() {
+        let results = detect_id_type("0x180D").unwrap();
+        assert_eq!(results[0].kind, IdKind::Uuid);
+        assert!(detect_id_type("0x180").is_err());
+    }
+
     #[test]
     fn test_detect_ulid() {
         let results = detect_id_type("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
@@ -525,6 +670,13 @@ mod tests {
         assert_eq!(results[0].kind, IdKind::Snowflake);
     }
 
+    #[test]
+    fn test_detect_signed_snowflake() {
+        let results = detect_id_type("-1407709484554567680").unwrap();
+        assert_eq!(results[0].kind, IdKind::Snowflake);
+        assert!(detect_id_type("-").is_err());
+    }
+
     #[test]
     fn test_detect_objectid() {
         let results = detect_id_type("507f1f77bcf86cd799439011").unwrap();
@@ -691,6 +843,29 @@ mod tests {
         assert!(results.iter().any(|r| r.kind == IdKind::Uuid));
     }
 
+    #[test]
+    fn test_detect_uuid_urn_and_braced() {
+        let results = detect_id_type("urn:uuid:01932c07-209c-7e5b-bb11-4852c227e1f0").unwrap();
+        assert_eq!(results[0].kind, IdKind::UuidV7);
+        let results = detect_id_type("{6BA7B810-9DAD-11D1-80B4-00C04FD430C8}").unwrap();
+        assert_eq!(results[0].kind, IdKind::UuidV1);
+        let results = detect_id_type("{550e8400e29b41d4a716446655440000}").unwrap();
+        assert_eq!(results[0].kind, IdKind::Uuid);
+    }
+
+    #[test]
+    fn test_detect_objectid_wrappers() {
+        for input in [
+            r#"ObjectId("507f1f77bcf86cd799439011")"#,
+            r#"{"$oid": "507f1f77bcf86cd799439011"}"#,
+        ] {
+            let results = detect_id_type(input).unwrap();
+            assert_eq!(results[0].kind, IdKind::ObjectId, "{}", input);
+            assert_eq!(results[0].confidence, 1.0);
+        }
+        assert!(detect_id_type(r#"ObjectId("507f1f77")"#).is_err());
+    }
+
     #[test]
     fn test_detect_uuidv1() {
         let results = detect_id_type("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
@@ -716,4 +891,39 @@ mod tests {
         let result = detect_id_type("not-a-valid-id-at-all-xyz");
         assert!(result.is_err());
     }
+
+    // --- Detection options ---
+
+    #[test]
+    fn test_detect_with_only() {
+        // Hex that starts with a letter is both a valid ObjectId and CUID2
+        let options = DetectionOptions::default().with_only(vec![IdKind::Cuid2]);
+        let results = detect_id_type_with("f07f1f77bcf86cd799439011", &options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, IdKind::Cuid2);
+
+        // A bare `uuid` filter keeps every UUID version
+        let options = DetectionOptions::default().with_only(vec![IdKind::Uuid]);
+        let results =
+            detect_id_type_with("01932c07-209c-7e5b-bb11-4852c227e1f0", &options).unwrap();
+        assert_eq!(results[0].kind, IdKind::UuidV7);
+    }
+
+    #[test]
+    fn test_detect_with_exclude() {
+        let options = DetectionOptions::default().with_exclude(vec![IdKind::ObjectId]);
+        let results = detect_id_type_with("f07f1f77bcf86cd799439011", &options).unwrap();
+        assert_eq!(results[0].kind, IdKind::Cuid2);
+
+        let options = DetectionOptions::default().with_exclude(vec![IdKind::Uuid]);
+        assert!(detect_id_type_with("01932c07-209c-7e5b-bb11-4852c227e1f0", &options).is_err());
+    }
+
+    #[test]
+    fn test_detect_with_min_confidence() {
+        let options = DetectionOptions::default().with_min_confidence(0.9);
+        assert!(detect_id_type_with("V1StGXR8_Z5jdHi6B-myT", &options).is_err());
+        let results = detect_id_type_with("01ARZ3NDEKTSV4RRFFQ69G5FAV", &options).unwrap();
+        assert!(results.iter().all(|r| r.confidence >= 0.9));
+    }
 }
@@ -1,5 +1,8 @@
 use crate::core::error::{IdtError, Result};
-use base64::{Engine, engine::general_purpose};
+use crate::utils::did_you_mean;
+use base64::Engine;
+use base64::alphabet::Alphabet;
+use base64::engine::{GeneralPurpose, general_purpose};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -13,12 +16,19 @@ pub enum EncodingFormat {
     Base32,
     Base32Hex,
     Base58,
+    Base58Check,
     Base64,
     Base64Url,
+    #[serde(rename = "base64-ordered")]
+    Base64Ordered,
     Binary,
     Bits,
     Int,
     Bytes,
+    #[serde(rename = "java-longs")]
+    JavaLongs,
+    #[serde(rename = "sortkey")]
+    SortKey,
 }
 
 impl fmt::Display for EncodingFormat {
@@ -30,36 +40,71 @@ impl fmt::Display for EncodingFormat {
             EncodingFormat::Base32 => write!(f, "base32"),
             EncodingFormat::Base32Hex => write!(f, "base32hex"),
             EncodingFormat::Base58 => write!(f, "base58"),
+            EncodingFormat::Base58Check => write!(f, "base58check"),
             EncodingFormat::Base64 => write!(f, "base64"),
             EncodingFormat::Base64Url => write!(f, "base64url"),
+            EncodingFormat::Base64Ordered => write!(f, "base64-ordered"),
             EncodingFormat::Binary => write!(f, "binary"),
             EncodingFormat::Bits => write!(f, "bits"),
             EncodingFormat::Int => write!(f, "int"),
             EncodingFormat::Bytes => write!(f, "bytes"),
+            EncodingFormat::JavaLongs => write!(f, "java-longs"),
+            EncodingFormat::SortKey => write!(f, "sortkey"),
         }
     }
 }
 
+impl EncodingFormat {
+    /// Every name `FromStr` accepts, lowercase
+    const ALIASES: &'static [(&'static str, EncodingFormat)] = &[
+        ("canonical", EncodingFormat::Canonical),
+        ("hex", EncodingFormat::Hex),
+        ("hexupper", EncodingFormat::HexUpper),
+        ("hex-upper", EncodingFormat::HexUpper),
+        ("base32", EncodingFormat::Base32),
+        ("base32hex", EncodingFormat::Base32Hex),
+        ("base32-hex", EncodingFormat::Base32Hex),
+        ("base58", EncodingFormat::Base58),
+        ("base58check", EncodingFormat::Base58Check),
+        ("base58-check", EncodingFormat::Base58Check),
+        ("base64", EncodingFormat::Base64),
+        ("base64url", EncodingFormat::Base64Url),
+        ("base64-url", EncodingFormat::Base64Url),
+        ("base64-ordered", EncodingFormat::Base64Ordered),
+        ("base64ordered", EncodingFormat::Base64Ordered),
+        ("binary", EncodingFormat::Binary),
+        ("bin", EncodingFormat::Binary),
+        ("bits", EncodingFormat::Bits),
+        ("int", EncodingFormat::Int),
+        ("integer", EncodingFormat::Int),
+        ("bytes", EncodingFormat::Bytes),
+        ("java-longs", EncodingFormat::JavaLongs),
+        ("javalongs", EncodingFormat::JavaLongs),
+        ("sortkey", EncodingFormat::SortKey),
+        ("sort-key", EncodingFormat::SortKey),
+    ];
+
+    /// Preferred spelling of each format, for help text and completion
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        Self::ALIASES
+            .iter()
+            .enumerate()
+            .filter(|&(i, (_, format))| !Self::ALIASES[..i].iter().any(|(_, f)| f == format))
+            .map(|(_, &(name, _))| name)
+    }
+}
+
 impl FromStr for EncodingFormat {
     type Err = IdtError;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "canonical" => Ok(EncodingFormat::Canonical),
-            "hex" => Ok(EncodingFormat::Hex),
-            "hexupper" | "hex-upper" | "HEX" => Ok(EncodingFormat::HexUpper),
-            "base32" => Ok(EncodingFormat::Base32),
-            "base32hex" | "base32-hex" => Ok(EncodingFormat::Base32Hex),
-            "base58" => Ok(EncodingFormat::Base58),
-            "base64" => Ok(EncodingFormat::Base64),
-            "base64url" | "base64-url" => Ok(EncodingFormat::Base64Url),
-            "binary" | "bin" => Ok(EncodingFormat::Binary),
-            "bits" => Ok(EncodingFormat::Bits),
-            "int" | "integer" => Ok(EncodingFormat::Int),
-            "bytes" => Ok(EncodingFormat::Bytes),
-            _ => Err(IdtError::InvalidArgument(format!(
-                "Unknown encoding format: {}",
-                s
+        let lower = s.to_lowercase();
+        match Self::ALIASES.iter().find(|(name, _)| *name == lower) {
+            Some(&(_, format)) => Ok(format),
+            None => Err(IdtError::InvalidArgument(format!(
+                "Unknown encoding format: {}{}",
+                s,
+                did_you_mean(&lower, Self::ALIASES.iter().map(|&(name, _)| name))
             ))),
         }
     }
@@ -97,6 +142,39 @@ pub fn decode_base58(s: &str) -> Result<Vec<u8>> {
         .map_err(|e| IdtError::EncodingError(e.to_string()))
 }
 
+/// First four bytes of SHA-256(SHA-256(`payload`))
+fn base58check_checksum(payload: &[u8]) -> [u8; 4] {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(Sha256::digest(payload));
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+/// Base58 with a 4-byte double-SHA256 checksum appended, as used by Bitcoin
+pub fn encode_base58check(bytes: &[u8]) -> String {
+    let mut data = bytes.to_vec();
+    data.extend_from_slice(&base58check_checksum(bytes));
+    encode_base58(&data)
+}
+
+/// Decode Base58Check and verify its checksum, returning the payload
+pub fn decode_base58check(s: &str) -> Result<Vec<u8>> {
+    let mut data = decode_base58(s)?;
+    if data.len() < 4 {
+        return Err(IdtError::EncodingError(format!(
+            "Base58Check input '{}' is too short for a checksum",
+            s
+        )));
+    }
+    let checksum = data.split_off(data.len() - 4);
+    if checksum != base58check_checksum(&data) {
+        return Err(IdtError::EncodingError(format!(
+            "Base58Check checksum mismatch in '{}'",
+            s
+        )));
+    }
+    Ok(data)
+}
+
 pub fn encode_base64(bytes: &[u8]) -> String {
     general_purpose::STANDARD.encode(bytes)
 }
@@ -117,6 +195,31 @@ pub fn decode_base64_url(s: &str) -> Result<Vec<u8>> {
         .map_err(|e| IdtError::EncodingError(e.to_string()))
 }
 
+/// Base64 alphabet in ASCII order, so that encodings of equal-length inputs
+/// sort the same way as the bytes themselves. URL- and filename-safe.
+pub const BASE64_ORDERED_ALPHABET: &str =
+    "-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz";
+
+const BASE64_ORDERED: GeneralPurpose = GeneralPurpose::new(
+    &match Alphabet::new(BASE64_ORDERED_ALPHABET) {
+        Ok(alphabet) => alphabet,
+        Err(_) => panic!("ordered alphabet has 64 distinct printable characters"),
+    },
+    general_purpose::NO_PAD,
+);
+
+/// Unpadded Base64 that preserves byte order under plain string sorting,
+/// for sort keys and object-store prefixes
+pub fn encode_base64_ordered(bytes: &[u8]) -> String {
+    BASE64_ORDERED.encode(bytes)
+}
+
+pub fn decode_base64_ordered(s: &str) -> Result<Vec<u8>> {
+    BASE64_ORDERED
+        .decode(s)
+        .map_err(|e| IdtError::EncodingError(e.to_string()))
+}
+
 pub fn encode_bits(bytes: &[u8]) -> String {
     bytes
         .iter()
@@ -125,6 +228,42 @@ pub fn encode_bits(bytes: &[u8]) -> String {
         .join("")
 }
 
+/// The `mostSigBits,leastSigBits` pair a `java.util.UUID` holds, as signed
+/// decimals. Only 128-bit values have this form.
+pub fn encode_java_longs(bytes: &[u8]) -> String {
+    match <[u8; 16]>::try_from(bytes) {
+        Ok(bytes) => {
+            let (msb, lsb) = bytes.split_at(8);
+            format!(
+                "{},{}",
+                i64::from_be_bytes(msb.try_into().unwrap()),
+                i64::from_be_bytes(lsb.try_into().unwrap())
+            )
+        }
+        Err(_) => format!("n/a ({} bytes, need 16)", bytes.len()),
+    }
+}
+
+/// Parse a `mostSigBits,leastSigBits` pair back into 16 bytes
+pub fn decode_java_longs(s: &str) -> Result<[u8; 16]> {
+    let invalid = || {
+        IdtError::EncodingError(format!(
+            "Invalid Java longs '{}': expected 'mostSigBits,leastSigBits'",
+            s
+        ))
+    };
+    let s = s.trim().trim_start_matches('(').trim_end_matches(')');
+    let (msb, lsb) = s.split_once(',').ok_or_else(invalid)?;
+    let parse = |half: &str| {
+        let half = half.trim().trim_end_matches(['L', 'l']);
+        half.parse::<i64>().map_err(|_| invalid())
+    };
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&parse(msb)?.to_be_bytes());
+    bytes[8..].copy_from_slice(&parse(lsb)?.to_be_bytes());
+    Ok(bytes)
+}
+
 pub fn encode_bytes_spaced(bytes: &[u8]) -> String {
     bytes
         .iter()
@@ -170,12 +309,9 @@ pub fn decode_shortuuid(s: &str) -> Result<Vec<u8>> {
     }
     let base = SHORTUUID_ALPHABET.len() as u128;
     let mut n: u128 = 0;
-    for c in s.chars() {
+    for (offset, c) in s.char_indices() {
         let Some(idx) = SHORTUUID_ALPHABET.iter().position(|&a| a == c as u8) else {
-            return Err(IdtError::EncodingError(format!(
-                "Invalid shortuuid character '{}'",
-                c
-            )));
+            return Err(IdtError::invalid_character(s, offset, "shortuuid"));
         };
         n = n
             .checked_mul(base)
@@ -195,6 +331,30 @@ pub fn bytes_to_u128(bytes: &[u8]) -> Option<u128> {
     Some(u128::from_be_bytes(arr))
 }
 
+/// Decimal string of a big-endian unsigned integer of any width
+pub fn encode_decimal(bytes: &[u8]) -> String {
+    if let Some(n) = bytes_to_u128(bytes) {
+        return n.to_string();
+    }
+
+    let mut num = bytes.to_vec();
+    let mut digits = Vec::new();
+    while num.iter().any(|&b| b != 0) {
+        let mut rem = 0u16;
+        for byte in num.iter_mut() {
+            let acc = (rem << 8) | *byte as u16;
+            *byte = (acc / 10) as u8;
+            rem = acc % 10;
+        }
+        digits.push(b'0' + rem as u8);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("decimal digits are ASCII")
+}
+
 pub fn encode_bytes(bytes: &[u8], format: EncodingFormat) -> String {
     match format {
         EncodingFormat::Canonical => encode_hex(bytes), // Default fallback
@@ -203,14 +363,19 @@ pub fn encode_bytes(bytes: &[u8], format: EncodingFormat) -> String {
         EncodingFormat::Base32 => encode_base32(bytes),
         EncodingFormat::Base32Hex => encode_base32(bytes), // Simplified
         EncodingFormat::Base58 => encode_base58(bytes),
+        EncodingFormat::Base58Check => encode_base58check(bytes),
         EncodingFormat::Base64 => encode_base64(bytes),
         EncodingFormat::Base64Url => encode_base64_url(bytes),
+        EncodingFormat::Base64Ordered => encode_base64_ordered(bytes),
         EncodingFormat::Binary => String::from_utf8_lossy(bytes).to_string(),
         EncodingFormat::Bits => encode_bits(bytes),
         EncodingFormat::Int => bytes_to_u128(bytes)
             .map(|n| n.to_string())
             .unwrap_or_else(|| format!("overflow ({} bytes, max 16)", bytes.len())),
         EncodingFormat::Bytes => encode_bytes_spaced(bytes),
+        EncodingFormat::JavaLongs => encode_java_longs(bytes),
+        // Needs the ID type for its tag and byte order
+        EncodingFormat::SortKey => "n/a (needs an ID type)".to_string(),
     }
 }
 
@@ -218,6 +383,15 @@ pub fn encode_bytes(bytes: &[u8], format: EncodingFormat) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_encoding_format_from_str_suggests() {
+        let err = EncodingFormat::from_str("base46").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument: Unknown encoding format: base46 (did you mean 'base64'?)"
+        );
+    }
+
     #[test]
     fn test_encoding_format_from_str() {
         assert_eq!(
@@ -248,6 +422,10 @@ mod tests {
             EncodingFormat::from_str("base58").unwrap(),
             EncodingFormat::Base58
         );
+        assert_eq!(
+            EncodingFormat::from_str("base58check").unwrap(),
+            EncodingFormat::Base58Check
+        );
         assert_eq!(
             EncodingFormat::from_str("base64").unwrap(),
             EncodingFormat::Base64
@@ -291,6 +469,7 @@ mod tests {
         assert_eq!(EncodingFormat::Base32.to_string(), "base32");
         assert_eq!(EncodingFormat::Base32Hex.to_string(), "base32hex");
         assert_eq!(EncodingFormat::Base58.to_string(), "base58");
+        assert_eq!(EncodingFormat::Base58Check.to_string(), "base58check");
         assert_eq!(EncodingFormat::Base64.to_string(), "base64");
         assert_eq!(EncodingFormat::Base64Url.to_string(), "base64url");
         assert_eq!(EncodingFormat::Binary.to_string(), "binary");
@@ -380,6 +559,20 @@ mod tests {
         assert!(decode_base58("0OIl").is_err());
     }
 
+    #[test]
+    fn test_encode_decode_base58check() {
+        assert_eq!(encode_base58check(&[0; 21]), "1111111111111111111114oLvT2");
+        assert_eq!(encode_base58check(b"hello"), "2L5B5yqsVG8Vt");
+        assert_eq!(decode_base58check("2L5B5yqsVG8Vt").unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_decode_base58check_verifies_checksum() {
+        let err = decode_base58check("2L5B5yqsVG8Vu").unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert!(decode_base58check("2L5").is_err());
+    }
+
     #[test]
     fn test_encode_decode_base64() {
         let data = b"hello";
@@ -407,6 +600,65 @@ mod tests {
         assert!(decode_base64_url("===").is_err());
     }
 
+    #[test]
+    fn test_encode_decode_base64_ordered() {
+        let data = b"\xff\xfe\xfd\x00";
+        let encoded = encode_base64_ordered(data);
+        assert_eq!(decode_base64_ordered(&encoded).unwrap(), data);
+        assert_eq!(encode_base64_ordered(&[0; 16]), "-".repeat(22));
+        assert!(decode_base64_ordered("+/").is_err());
+        assert_eq!(
+            EncodingFormat::from_str("base64-ordered").unwrap(),
+            EncodingFormat::Base64Ordered
+        );
+        assert_eq!(EncodingFormat::Base64Ordered.to_string(), "base64-ordered");
+    }
+
+    #[test]
+    fn test_java_longs_roundtrip() {
+        let bytes = decode_java_longs("-5403194877577133845,-7865837820844897439").unwrap();
+        assert_eq!(
+            encode_java_longs(&bytes),
+            "-5403194877577133845,-7865837820844897439"
+        );
+        assert_eq!(
+            decode_java_longs("(1L, 2L)").unwrap(),
+            decode_java_longs("1,2").unwrap()
+        );
+        assert_eq!(encode_java_longs(&[0; 12]), "n/a (12 bytes, need 16)");
+        assert!(decode_java_longs("12345").is_err());
+        assert!(decode_java_longs("1,99999999999999999999").is_err());
+        assert_eq!(
+            EncodingFormat::from_str("java-longs").unwrap(),
+            EncodingFormat::JavaLongs
+        );
+    }
+
+    #[test]
+    fn test_base64_ordered_preserves_byte_order() {
+        let alphabet: Vec<u8> = BASE64_ORDERED_ALPHABET.bytes().collect();
+        assert!(alphabet.windows(2).all(|w| w[0] < w[1]));
+
+        let mut values: Vec<[u8; 5]> = (0u32..2000)
+            .map(|i| {
+                let n = i.wrapping_mul(2_654_435_761);
+                [
+                    (n >> 24) as u8,
+                    (n >> 16) as u8,
+                    (n >> 8) as u8,
+                    n as u8,
+                    i as u8,
+                ]
+            })
+            .collect();
+        values.sort();
+        let encoded: Vec<String> = values.iter().map(|v| encode_base64_ordered(v)).collect();
+        assert!(encoded.windows(2).all(|w| w[0] <= w[1]));
+        // Standard base64 does not sort this way
+        let standard: Vec<String> = values.iter().map(|v| encode_base64(v)).collect();
+        assert!(!standard.windows(2).all(|w| w[0] <= w[1]));
+    }
+
     #[test]
     fn test_encode_bits() {
         assert_eq!(encode_bits(&[0xff, 0x00]), "1111111100000000");
@@ -463,6 +715,16 @@ mod tests {
         assert!(!int_result.is_empty());
     }
 
+    #[test]
+    fn test_encode_decimal() {
+        assert_eq!(encode_decimal(&[0u8; 20]), "0");
+        assert_eq!(encode_decimal(&[0x01, 0x00]), "256");
+        assert_eq!(
+            encode_decimal(&[0xFF; 20]),
+            "1461501637330902918203684832716283019655932542975"
+        );
+    }
+
     #[test]
     fn test_encode_bytes_int_overflow() {
         let data = &[0xff; 17]; // > 16 bytes
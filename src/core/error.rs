@@ -11,12 +11,31 @@ pub enum IdtError {
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    /// A character outside the ID's alphabet, `offset` bytes into `input`
+    #[error("Parse error: Invalid {expected} character '{character}' at offset {offset}")]
+    InvalidCharacter {
+        input: String,
+        offset: usize,
+        character: char,
+        expected: &'static str,
+    },
+
     #[error("Encoding error: {0}")]
     EncodingError(String),
 
     #[error("Generation error: {0}")]
     GenerationError(String),
 
+    /// The clock reads earlier than the last timestamp a generator issued
+    #[error(
+        "Clock moved backwards: timestamp {timestamp} is before the last issued {last_timestamp}"
+    )]
+    ClockMovedBackwards { last_timestamp: u64, timestamp: u64 },
+
+    /// Every sequence number for `timestamp` has already been issued
+    #[error("Sequence exhausted: no sequence numbers left for timestamp {timestamp}")]
+    SequenceExhausted { timestamp: u64 },
+
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
@@ -32,14 +51,129 @@ pub enum IdtError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    /// The command ran to completion, but the IDs failed what it checks
+    #[error("Check failed: {0}")]
+    CheckFailed(String),
+
     #[error("Conversion not supported: {from} -> {to}")]
     ConversionNotSupported { from: String, to: String },
 
     #[error("Serialization error: {0}")]
     SerializationError(String),
 
+    #[error("Format error: {0}")]
+    FmtError(#[from] std::fmt::Error),
+
+    #[error("Update failed: {0}")]
+    UpdateFailed(String),
+
+    #[cfg(any(feature = "cli", feature = "custom"))]
     #[error("YAML error: {0}")]
     YamlError(#[from] serde_yaml_ng::Error),
 }
 
 pub type Result<T> = std::result::Result<T, IdtError>;
+
+impl IdtError {
+    /// Stable snake_case name of the error kind, for machine-readable output
+    pub fn code(&self) -> &'static str {
+        match self {
+            IdtError::InvalidFormat(_) => "invalid_format",
+            IdtError::UnknownType(_) => "unknown_type",
+            IdtError::ParseError(_) => "parse_error",
+            IdtError::InvalidCharacter { .. } => "invalid_character",
+            IdtError::EncodingError(_) => "encoding_error",
+            IdtError::GenerationError(_) => "generation_error",
+            IdtError::ClockMovedBackwards { .. } => "clock_moved_backwards",
+            IdtError::SequenceExhausted { .. } => "sequence_exhausted",
+            IdtError::InvalidArgument(_) => "invalid_argument",
+            IdtError::IoError(_) => "io_error",
+            IdtError::JsonError(_) => "json_error",
+            IdtError::DetectionFailed => "detection_failed",
+            IdtError::ValidationError(_) => "validation_error",
+            IdtError::CheckFailed(_) => "check_failed",
+            IdtError::ConversionNotSupported { .. } => "conversion_not_supported",
+            IdtError::SerializationError(_) => "serialization_error",
+            IdtError::FmtError(_) => "fmt_error",
+            IdtError::UpdateFailed(_) => "update_failed",
+            #[cfg(any(feature = "cli", feature = "custom"))]
+            IdtError::YamlError(_) => "yaml_error",
+        }
+    }
+
+    /// Error for the character of `input` starting at byte `offset`
+    pub fn invalid_character(input: &str, offset: usize, expected: &'static str) -> Self {
+        IdtError::InvalidCharacter {
+            input: input.to_string(),
+            offset,
+            character: input[offset..].chars().next().unwrap_or('?'),
+            expected,
+        }
+    }
+
+    /// Re-anchor an [`InvalidCharacter`](IdtError::InvalidCharacter) found in
+    /// a slice that starts `start` bytes into `input`
+    pub fn in_input(self, input: &str, start: usize) -> Self {
+        match self {
+            IdtError::InvalidCharacter {
+                offset,
+                character,
+                expected,
+                ..
+            } => IdtError::InvalidCharacter {
+                input: input.to_string(),
+                offset: start + offset,
+                character,
+                expected,
+            },
+            other => other,
+        }
+    }
+
+    /// The input with a caret under the offending character, for errors
+    /// that know where they happened
+    pub fn caret(&self) -> Option<String> {
+        let IdtError::InvalidCharacter { input, offset, .. } = self else {
+            return None;
+        };
+        let column = input.get(..*offset)?.chars().count();
+        Some(format!("  {}\n  {}^", input, " ".repeat(column)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_character() {
+        let err = IdtError::invalid_character("01AU", 3, "Crockford Base32");
+        assert_eq!(
+            err.to_string(),
+            "Parse error: Invalid Crockford Base32 character 'U' at offset 3"
+        );
+        assert_eq!(err.caret().unwrap(), "  01AU\n     ^");
+    }
+
+    #[test]
+    fn test_code() {
+        assert_eq!(IdtError::DetectionFailed.code(), "detection_failed");
+        assert_eq!(
+            IdtError::invalid_character("x", 0, "hex").code(),
+            "invalid_character"
+        );
+    }
+
+    #[test]
+    fn test_in_input() {
+        let err = IdtError::invalid_character("01au", 2, "TypeID Base32").in_input("user_01au", 5);
+        assert_eq!(err.caret().unwrap(), "  user_01au\n         ^");
+        assert!(IdtError::ParseError("x".into()).caret().is_none());
+    }
+
+    #[test]
+    fn test_caret_counts_chars() {
+        let err = IdtError::invalid_character("ééx-", 5, "hex");
+        assert_eq!(err.caret().unwrap(), "  ééx-\n     ^");
+    }
+}
@@ -1,6 +1,9 @@
+use crate::core::components::Components;
 use crate::core::encoding::EncodingFormat;
 use crate::core::error::Result;
+use crate::utils::did_you_mean;
 use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -40,6 +43,24 @@ impl Timestamp {
             .unwrap_or_else(|| format!("invalid timestamp ({}ms)", self.millis))
     }
 
+    /// ISO 8601 in the IANA time zone `tz`, e.g. `Asia/Seoul`
+    pub fn to_tz_iso8601(&self, tz: Tz) -> String {
+        self.to_datetime()
+            .map(|dt| {
+                dt.with_timezone(&tz)
+                    .format("%Y-%m-%dT%H:%M:%S%.3f%:z")
+                    .to_string()
+            })
+            .unwrap_or_else(|| format!("invalid timestamp ({}ms)", self.millis))
+    }
+
+    /// Abbreviation of `tz` in effect at this timestamp, e.g. `KST` or `PDT`
+    pub fn timezone_abbr(&self, tz: Tz) -> String {
+        self.to_datetime()
+            .map(|dt| dt.with_timezone(&tz).format("%Z").to_string())
+            .unwrap_or_else(|| tz.name().to_string())
+    }
+
     pub fn local_timezone_abbr(&self) -> String {
         self.to_datetime()
             .map(|dt| {
@@ -51,7 +72,7 @@ impl Timestamp {
 }
 
 /// Unit of measurement for a structure segment's size
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SizeUnit {
     Bits,
@@ -83,6 +104,14 @@ pub struct InspectionResult {
     pub timestamp_iso: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp_local_iso: Option<String>,
+    /// Milliseconds between the timestamp and now (negative if in the future)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age_human: Option<String>,
+    /// URL pattern the ID was extracted from, e.g. `twitter-status`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -143,112 +172,192 @@ impl ValidationResult {
 }
 
 /// Supported ID types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 #[serde(rename_all = "lowercase")]
 pub enum IdKind {
-    #[value(name = "uuid")]
+    #[cfg_attr(feature = "cli", value(name = "uuid"))]
     Uuid,
-    #[value(name = "uuidv1", alias = "uuid-v1", alias = "uuid1")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv1", alias = "uuid-v1", alias = "uuid1")
+    )]
     UuidV1,
-    #[value(name = "uuidv3", alias = "uuid-v3", alias = "uuid3")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv3", alias = "uuid-v3", alias = "uuid3")
+    )]
     UuidV3,
-    #[value(name = "uuidv4", alias = "uuid-v4", alias = "uuid4")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv4", alias = "uuid-v4", alias = "uuid4")
+    )]
     UuidV4,
-    #[value(name = "uuidv5", alias = "uuid-v5", alias = "uuid5")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv5", alias = "uuid-v5", alias = "uuid5")
+    )]
     UuidV5,
-    #[value(name = "uuidv6", alias = "uuid-v6", alias = "uuid6")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv6", alias = "uuid-v6", alias = "uuid6")
+    )]
     UuidV6,
-    #[value(name = "uuidv7", alias = "uuid-v7", alias = "uuid7")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv7", alias = "uuid-v7", alias = "uuid7")
+    )]
     UuidV7,
-    #[value(name = "uuid-nil", alias = "uuidnil", alias = "nil")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuid-nil", alias = "uuidnil", alias = "nil")
+    )]
     UuidNil,
-    #[value(name = "uuid-max", alias = "uuidmax", alias = "max")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuid-max", alias = "uuidmax", alias = "max")
+    )]
     UuidMax,
-    #[value(name = "ulid")]
+    #[cfg_attr(feature = "cli", value(name = "ulid"))]
     Ulid,
-    #[value(name = "nanoid", alias = "nano")]
+    #[cfg_attr(feature = "cli", value(name = "nanoid", alias = "nano"))]
     NanoId,
-    #[value(name = "ksuid")]
+    #[cfg_attr(feature = "cli", value(name = "ksuid"))]
     Ksuid,
-    #[value(name = "snowflake", alias = "snow")]
+    #[cfg_attr(feature = "cli", value(name = "snowflake", alias = "snow"))]
     Snowflake,
-    #[value(name = "objectid", alias = "oid", alias = "mongoid")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "objectid", alias = "oid", alias = "mongoid")
+    )]
     ObjectId,
-    #[value(name = "typeid")]
+    #[cfg_attr(feature = "cli", value(name = "typeid"))]
     TypeId,
-    #[value(name = "xid")]
+    #[cfg_attr(feature = "cli", value(name = "xid"))]
     Xid,
-    #[value(name = "cuid")]
+    #[cfg_attr(feature = "cli", value(name = "cuid"))]
     Cuid,
-    #[value(name = "cuid2")]
+    #[cfg_attr(feature = "cli", value(name = "cuid2"))]
     Cuid2,
-    #[value(name = "tsid")]
+    #[cfg_attr(feature = "cli", value(name = "tsid"))]
     Tsid,
-    #[value(name = "shortuuid", alias = "short-uuid", alias = "suuid")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "shortuuid", alias = "short-uuid", alias = "suuid")
+    )]
     ShortUuid,
-    #[value(name = "ean13", alias = "ean-13")]
+    #[cfg_attr(feature = "cli", value(name = "ean13", alias = "ean-13"))]
     Ean13,
-    #[value(name = "isbn13", alias = "isbn-13", alias = "isbn")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "isbn13", alias = "isbn-13", alias = "isbn")
+    )]
     Isbn13,
-    #[value(name = "isbn10", alias = "isbn-10")]
+    #[cfg_attr(feature = "cli", value(name = "isbn10", alias = "isbn-10"))]
     Isbn10,
-    #[value(name = "isin")]
+    #[cfg_attr(feature = "cli", value(name = "isin"))]
     Isin,
-    #[value(name = "ean8", alias = "ean-8")]
+    #[cfg_attr(feature = "cli", value(name = "ean8", alias = "ean-8"))]
     Ean8,
-    #[value(name = "upca", alias = "upc-a", alias = "upc")]
+    #[cfg_attr(feature = "cli", value(name = "upca", alias = "upc-a", alias = "upc"))]
     UpcA,
-    #[value(name = "issn")]
+    #[cfg_attr(feature = "cli", value(name = "issn"))]
     Issn,
-    #[value(name = "ismn")]
+    #[cfg_attr(feature = "cli", value(name = "ismn"))]
     Ismn,
-    #[value(name = "isni")]
+    #[cfg_attr(feature = "cli", value(name = "isni"))]
     Isni,
-    #[value(name = "gtin14", alias = "gtin-14", alias = "gtin")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "gtin14", alias = "gtin-14", alias = "gtin")
+    )]
     Gtin14,
-    #[value(name = "asin")]
+    #[cfg_attr(feature = "cli", value(name = "asin"))]
     Asin,
+    /// User-defined format loaded with `--schema`
+    #[cfg_attr(feature = "cli", value(name = "custom"))]
+    Custom,
 }
 
 /// ID types that support generation. Keep in sync with [`IdKind::generatable()`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum GenIdKind {
-    #[value(name = "uuid")]
+    #[cfg_attr(feature = "cli", value(name = "uuid"))]
     Uuid,
-    #[value(name = "uuidv1", alias = "uuid-v1", alias = "uuid1")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv1", alias = "uuid-v1", alias = "uuid1")
+    )]
     UuidV1,
-    #[value(name = "uuidv4", alias = "uuid-v4", alias = "uuid4")]
+    // Derived from names (`--names-file`), so not listed in `IdKind::generatable()`
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv3", alias = "uuid-v3", alias = "uuid3")
+    )]
+    UuidV3,
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv4", alias = "uuid-v4", alias = "uuid4")
+    )]
     UuidV4,
-    #[value(name = "uuidv6", alias = "uuid-v6", alias = "uuid6")]
+    // Derived from names (`--names-file`), so not listed in `IdKind::generatable()`
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv5", alias = "uuid-v5", alias = "uuid5")
+    )]
+    UuidV5,
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv6", alias = "uuid-v6", alias = "uuid6")
+    )]
     UuidV6,
-    #[value(name = "uuidv7", alias = "uuid-v7", alias = "uuid7")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuidv7", alias = "uuid-v7", alias = "uuid7")
+    )]
     UuidV7,
-    #[value(name = "uuid-nil", alias = "uuidnil", alias = "nil")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuid-nil", alias = "uuidnil", alias = "nil")
+    )]
     UuidNil,
-    #[value(name = "uuid-max", alias = "uuidmax", alias = "max")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "uuid-max", alias = "uuidmax", alias = "max")
+    )]
     UuidMax,
-    #[value(name = "ulid")]
+    #[cfg_attr(feature = "cli", value(name = "ulid"))]
     Ulid,
-    #[value(name = "nanoid", alias = "nano")]
+    #[cfg_attr(feature = "cli", value(name = "nanoid", alias = "nano"))]
     NanoId,
-    #[value(name = "ksuid")]
+    #[cfg_attr(feature = "cli", value(name = "ksuid"))]
     Ksuid,
-    #[value(name = "snowflake", alias = "snow")]
+    #[cfg_attr(feature = "cli", value(name = "snowflake", alias = "snow"))]
     Snowflake,
-    #[value(name = "objectid", alias = "oid", alias = "mongoid")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "objectid", alias = "oid", alias = "mongoid")
+    )]
     ObjectId,
-    #[value(name = "typeid")]
+    #[cfg_attr(feature = "cli", value(name = "typeid"))]
     TypeId,
-    #[value(name = "xid")]
+    #[cfg_attr(feature = "cli", value(name = "xid"))]
     Xid,
-    #[value(name = "cuid")]
+    #[cfg_attr(feature = "cli", value(name = "cuid"))]
     Cuid,
-    #[value(name = "cuid2")]
+    #[cfg_attr(feature = "cli", value(name = "cuid2"))]
     Cuid2,
-    #[value(name = "tsid")]
+    #[cfg_attr(feature = "cli", value(name = "tsid"))]
     Tsid,
-    #[value(name = "shortuuid", alias = "short-uuid", alias = "suuid")]
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "shortuuid", alias = "short-uuid", alias = "suuid")
+    )]
     ShortUuid,
+    // Requires `--schema`, so not listed in `IdKind::generatable()`
+    #[cfg_attr(feature = "cli", value(name = "custom"))]
+    Custom,
 }
 
 impl From<GenIdKind> for IdKind {
@@ -256,7 +365,9 @@ impl From<GenIdKind> for IdKind {
         match g {
             GenIdKind::Uuid => IdKind::Uuid,
             GenIdKind::UuidV1 => IdKind::UuidV1,
+            GenIdKind::UuidV3 => IdKind::UuidV3,
             GenIdKind::UuidV4 => IdKind::UuidV4,
+            GenIdKind::UuidV5 => IdKind::UuidV5,
             GenIdKind::UuidV6 => IdKind::UuidV6,
             GenIdKind::UuidV7 => IdKind::UuidV7,
             GenIdKind::UuidNil => IdKind::UuidNil,
@@ -272,6 +383,7 @@ impl From<GenIdKind> for IdKind {
             GenIdKind::Cuid2 => IdKind::Cuid2,
             GenIdKind::Tsid => IdKind::Tsid,
             GenIdKind::ShortUuid => IdKind::ShortUuid,
+            GenIdKind::Custom => IdKind::Custom,
         }
     }
 }
@@ -310,6 +422,7 @@ impl IdKind {
             IdKind::Isni => "isni",
             IdKind::Gtin14 => "gtin14",
             IdKind::Asin => "asin",
+            IdKind::Custom => "custom",
         }
     }
 
@@ -346,10 +459,82 @@ impl IdKind {
             IdKind::Isni => "ISNI (International Standard Name Identifier)",
             IdKind::Gtin14 => "GTIN-14 (Global Trade Item Number)",
             IdKind::Asin => "ASIN (Amazon Standard Identification Number)",
+            IdKind::Custom => "Custom ID format loaded from a schema file (--schema)",
+        }
+    }
+
+    /// Where the format is specified
+    pub fn spec_url(&self) -> Option<&'static str> {
+        match self {
+            IdKind::Uuid | IdKind::UuidV1 | IdKind::UuidV3 | IdKind::UuidV4 | IdKind::UuidV5 => {
+                Some("https://datatracker.ietf.org/doc/html/rfc4122")
+            }
+            IdKind::UuidV6 | IdKind::UuidV7 => {
+                Some("https://datatracker.ietf.org/doc/html/rfc9562")
+            }
+            IdKind::Ulid => Some("https://github.com/ulid/spec"),
+            IdKind::Snowflake => Some("https://en.wikipedia.org/wiki/Snowflake_ID"),
+            IdKind::NanoId => Some("https://github.com/ai/nanoid"),
+            IdKind::Ksuid => Some("https://github.com/segmentio/ksuid"),
+            IdKind::ObjectId => {
+                Some("https://www.mongodb.com/docs/manual/reference/method/objectid/")
+            }
+            IdKind::TypeId => Some("https://github.com/jetify-com/typeid"),
+            IdKind::Xid => Some("https://github.com/rs/xid"),
+            IdKind::Cuid => Some("https://github.com/paralleldrive/cuid"),
+            IdKind::Cuid2 => Some("https://github.com/paralleldrive/cuid2"),
+            IdKind::Tsid => Some("https://github.com/f4b6a3/tsid-creator"),
+            IdKind::ShortUuid => Some("https://github.com/skorokithakis/shortuuid"),
+            _ => None,
         }
     }
 
+    /// Whether this is any UUID version (including nil and max)
+    pub fn is_uuid(&self) -> bool {
+        matches!(
+            self,
+            IdKind::Uuid
+                | IdKind::UuidV1
+                | IdKind::UuidV3
+                | IdKind::UuidV4
+                | IdKind::UuidV5
+                | IdKind::UuidV6
+                | IdKind::UuidV7
+                | IdKind::UuidNil
+                | IdKind::UuidMax
+        )
+    }
+
+    /// Whether the feature for this kind's family was compiled in
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            kind if kind.is_uuid() => cfg!(feature = "uuid"),
+            IdKind::Ulid => cfg!(feature = "ulid"),
+            IdKind::NanoId => cfg!(feature = "nanoid"),
+            IdKind::Ksuid => cfg!(feature = "ksuid"),
+            IdKind::Snowflake => cfg!(feature = "snowflake"),
+            IdKind::ObjectId => cfg!(feature = "objectid"),
+            IdKind::TypeId => cfg!(feature = "typeid"),
+            IdKind::Xid => cfg!(feature = "xid"),
+            IdKind::Cuid => cfg!(feature = "cuid"),
+            IdKind::Cuid2 => cfg!(feature = "cuid2"),
+            IdKind::Tsid => cfg!(feature = "tsid"),
+            IdKind::ShortUuid => cfg!(feature = "shortuuid"),
+            IdKind::Custom => cfg!(feature = "custom"),
+            _ => cfg!(feature = "product-codes"),
+        }
+    }
+
+    /// Whether this kind satisfies a user-supplied type filter.
+    /// A bare `uuid` filter matches every UUID version.
+    pub fn matches_filter(&self, filter: IdKind) -> bool {
+        *self == filter || (filter == IdKind::Uuid && self.is_uuid())
+    }
+
     pub fn has_timestamp(&self) -> bool {
+        if *self == IdKind::Custom {
+            return true;
+        }
         matches!(
             self,
             IdKind::UuidV1
@@ -367,6 +552,10 @@ impl IdKind {
     }
 
     pub fn is_sortable(&self) -> bool {
+        #[cfg(feature = "custom")]
+        if *self == IdKind::Custom {
+            return crate::ids::custom_id::active().is_some_and(|s| s.is_sortable());
+        }
         matches!(
             self,
             IdKind::UuidV6
@@ -413,6 +602,10 @@ impl IdKind {
             IdKind::Isni => 0,
             IdKind::Gtin14 => 0,
             IdKind::Asin => 0,
+            #[cfg(feature = "custom")]
+            IdKind::Custom => crate::ids::custom_id::active().map_or(64, |s| s.bits as usize),
+            #[cfg(not(feature = "custom"))]
+            IdKind::Custom => 64,
         }
     }
 
@@ -482,44 +675,93 @@ impl fmt::Display for IdKind {
     }
 }
 
+impl IdKind {
+    /// Every name `FromStr` accepts, lowercase
+    const ALIASES: &'static [(&'static str, IdKind)] = &[
+        ("uuid", IdKind::Uuid),
+        ("uuidv1", IdKind::UuidV1),
+        ("uuid-v1", IdKind::UuidV1),
+        ("uuid1", IdKind::UuidV1),
+        ("uuidv3", IdKind::UuidV3),
+        ("uuid-v3", IdKind::UuidV3),
+        ("uuid3", IdKind::UuidV3),
+        ("uuidv4", IdKind::UuidV4),
+        ("uuid-v4", IdKind::UuidV4),
+        ("uuid4", IdKind::UuidV4),
+        ("uuidv5", IdKind::UuidV5),
+        ("uuid-v5", IdKind::UuidV5),
+        ("uuid5", IdKind::UuidV5),
+        ("uuidv6", IdKind::UuidV6),
+        ("uuid-v6", IdKind::UuidV6),
+        ("uuid6", IdKind::UuidV6),
+        ("uuidv7", IdKind::UuidV7),
+        ("uuid-v7", IdKind::UuidV7),
+        ("uuid7", IdKind::UuidV7),
+        ("uuid-nil", IdKind::UuidNil),
+        ("uuidnil", IdKind::UuidNil),
+        ("nil", IdKind::UuidNil),
+        ("uuid-max", IdKind::UuidMax),
+        ("uuidmax", IdKind::UuidMax),
+        ("max", IdKind::UuidMax),
+        ("ulid", IdKind::Ulid),
+        ("nanoid", IdKind::NanoId),
+        ("nano", IdKind::NanoId),
+        ("ksuid", IdKind::Ksuid),
+        ("snowflake", IdKind::Snowflake),
+        ("snow", IdKind::Snowflake),
+        ("objectid", IdKind::ObjectId),
+        ("oid", IdKind::ObjectId),
+        ("mongoid", IdKind::ObjectId),
+        ("typeid", IdKind::TypeId),
+        ("xid", IdKind::Xid),
+        ("cuid", IdKind::Cuid),
+        ("cuid2", IdKind::Cuid2),
+        ("tsid", IdKind::Tsid),
+        ("shortuuid", IdKind::ShortUuid),
+        ("short-uuid", IdKind::ShortUuid),
+        ("suuid", IdKind::ShortUuid),
+        ("ean13", IdKind::Ean13),
+        ("ean-13", IdKind::Ean13),
+        ("isbn13", IdKind::Isbn13),
+        ("isbn-13", IdKind::Isbn13),
+        ("isbn", IdKind::Isbn13),
+        ("isbn10", IdKind::Isbn10),
+        ("isbn-10", IdKind::Isbn10),
+        ("isin", IdKind::Isin),
+        ("ean8", IdKind::Ean8),
+        ("ean-8", IdKind::Ean8),
+        ("upca", IdKind::UpcA),
+        ("upc-a", IdKind::UpcA),
+        ("upc", IdKind::UpcA),
+        ("issn", IdKind::Issn),
+        ("ismn", IdKind::Ismn),
+        ("isni", IdKind::Isni),
+        ("gtin14", IdKind::Gtin14),
+        ("gtin-14", IdKind::Gtin14),
+        ("gtin", IdKind::Gtin14),
+        ("asin", IdKind::Asin),
+        ("custom", IdKind::Custom),
+    ];
+}
+
 impl std::str::FromStr for IdKind {
     type Err = crate::core::error::IdtError;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "uuid" => Ok(IdKind::Uuid),
-            "uuidv1" | "uuid-v1" | "uuid1" => Ok(IdKind::UuidV1),
-            "uuidv3" | "uuid-v3" | "uuid3" => Ok(IdKind::UuidV3),
-            "uuidv4" | "uuid-v4" | "uuid4" => Ok(IdKind::UuidV4),
-            "uuidv5" | "uuid-v5" | "uuid5" => Ok(IdKind::UuidV5),
-            "uuidv6" | "uuid-v6" | "uuid6" => Ok(IdKind::UuidV6),
-            "uuidv7" | "uuid-v7" | "uuid7" => Ok(IdKind::UuidV7),
-            "uuid-nil" | "uuidnil" | "nil" => Ok(IdKind::UuidNil),
-            "uuid-max" | "uuidmax" | "max" => Ok(IdKind::UuidMax),
-            "ulid" => Ok(IdKind::Ulid),
-            "nanoid" | "nano" => Ok(IdKind::NanoId),
-            "ksuid" => Ok(IdKind::Ksuid),
-            "snowflake" | "snow" => Ok(IdKind::Snowflake),
-            "objectid" | "oid" | "mongoid" => Ok(IdKind::ObjectId),
-            "typeid" => Ok(IdKind::TypeId),
-            "xid" => Ok(IdKind::Xid),
-            "cuid" => Ok(IdKind::Cuid),
-            "cuid2" => Ok(IdKind::Cuid2),
-            "tsid" => Ok(IdKind::Tsid),
-            "shortuuid" | "short-uuid" | "suuid" => Ok(IdKind::ShortUuid),
-            "ean13" | "ean-13" => Ok(IdKind::Ean13),
-            "isbn13" | "isbn-13" | "isbn" => Ok(IdKind::Isbn13),
-            "isbn10" | "isbn-10" => Ok(IdKind::Isbn10),
-            "isin" => Ok(IdKind::Isin),
-            "ean8" | "ean-8" => Ok(IdKind::Ean8),
-            "upca" | "upc-a" | "upc" => Ok(IdKind::UpcA),
-            "issn" => Ok(IdKind::Issn),
-            "ismn" => Ok(IdKind::Ismn),
-            "isni" => Ok(IdKind::Isni),
-            "gtin14" | "gtin-14" | "gtin" => Ok(IdKind::Gtin14),
-            "asin" => Ok(IdKind::Asin),
-            _ => Err(crate::core::error::IdtError::UnknownType(s.to_string())),
+        let lower = s.to_lowercase();
+        if let Some(&(_, kind)) = Self::ALIASES.iter().find(|(name, _)| *name == lower) {
+            return Ok(kind);
+        }
+        #[cfg(feature = "custom")]
+        if crate::ids::custom_id::active().is_some_and(|c| c.name == lower) {
+            return Ok(IdKind::Custom);
         }
+        let names = Self::ALIASES.iter().map(|&(name, _)| name);
+        Err(crate::core::error::IdtError::UnknownType(format!(
+            "{}{}",
+            s,
+            did_you_mean(&lower, names)
+        )))
     }
 }
 
@@ -529,6 +771,20 @@ pub trait IdGenerator {
     fn generate_many(&self, count: usize) -> Result<Vec<String>> {
         (0..count).map(|_| self.generate()).collect()
     }
+
+    /// Append a new ID to `buf`, reusing its allocation
+    fn generate_into(&self, buf: &mut String) -> Result<()> {
+        self.write_to(buf)
+    }
+
+    /// Write a new ID to `writer`.
+    ///
+    /// The default goes through [`generate`](Self::generate); generators on
+    /// the bulk hot path override this to format without allocating.
+    fn write_to(&self, writer: &mut dyn fmt::Write) -> Result<()> {
+        writer.write_str(&self.generate()?)?;
+        Ok(())
+    }
 }
 
 /// Trait for ID types that can be parsed and inspected
@@ -546,6 +802,31 @@ pub trait ParsedId: Send + Sync {
     fn inspect(&self) -> InspectionResult;
     fn validate(&self) -> ValidationResult;
     fn encode(&self, format: EncodingFormat) -> String;
+
+    /// Typed fields of the ID, mirroring the JSON `components` of
+    /// [`inspect`](ParsedId::inspect). `None` for IDs without internal
+    /// structure (NanoID, CUID, assigned IDs).
+    fn components(&self) -> Option<Components> {
+        None
+    }
+
+    /// Integer value of the ID, for types that are at most 128 bits wide.
+    ///
+    /// 96-bit types (ObjectId, Xid) are zero-extended. Returns `None` for
+    /// wider types (KSUID) and for IDs that are strings rather than numbers
+    /// (NanoID, CUID, assigned IDs).
+    fn as_u128(&self) -> Option<u128> {
+        None
+    }
+
+    /// Integer value of the ID, for types that are at most 64 bits wide
+    /// (Snowflake, TSID). Returns `None` for wider types, whatever the value.
+    fn as_u64(&self) -> Option<u64> {
+        if self.kind().bit_length() > 64 {
+            return None;
+        }
+        self.as_u128().and_then(|n| u64::try_from(n).ok())
+    }
 }
 
 #[cfg(test)]
@@ -592,6 +873,21 @@ mod tests {
         assert!(!abbr.is_empty());
     }
 
+    #[test]
+    fn test_timestamp_in_named_zone() {
+        // 2023-11-14T22:13:20Z
+        let ts = Timestamp::new(1_700_000_000_000);
+        assert_eq!(
+            ts.to_tz_iso8601(chrono_tz::Asia::Seoul),
+            "2023-11-15T07:13:20.000+09:00"
+        );
+        assert_eq!(ts.timezone_abbr(chrono_tz::Asia::Seoul), "KST");
+        // DST: PDT in summer, PST in winter
+        let summer = Timestamp::new(1_690_000_000_000);
+        assert_eq!(summer.timezone_abbr(chrono_tz::America::Los_Angeles), "PDT");
+        assert_eq!(ts.timezone_abbr(chrono_tz::America::Los_Angeles), "PST");
+    }
+
     #[test]
     fn test_validation_result_valid() {
         let r = ValidationResult::valid("uuid");
@@ -637,6 +933,15 @@ mod tests {
         assert!(desc.contains("random"));
     }
 
+    #[test]
+    fn test_id_kind_spec_url() {
+        assert!(IdKind::UuidV4.spec_url().is_some());
+        assert!(IdKind::UuidV7.spec_url().is_some());
+        assert!(IdKind::Ulid.spec_url().is_some());
+        assert!(IdKind::Snowflake.spec_url().is_some());
+        assert!(IdKind::UuidNil.spec_url().is_none());
+    }
+
     #[test]
     fn test_id_kind_has_timestamp() {
         assert!(IdKind::UuidV7.has_timestamp());
@@ -672,6 +977,12 @@ mod tests {
         assert!(all.contains(&IdKind::Tsid));
     }
 
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_id_kind_all_enabled_with_cli() {
+        assert!(IdKind::all().iter().all(IdKind::is_enabled));
+    }
+
     #[test]
     fn test_id_kind_generatable() {
         let generatable = IdKind::generatable();
@@ -703,4 +1014,17 @@ mod tests {
         assert_eq!("max".parse::<IdKind>().unwrap(), IdKind::UuidMax);
         assert!("unknown_type".parse::<IdKind>().is_err());
     }
+
+    #[test]
+    fn test_id_kind_from_str_suggests() {
+        let err = "ulids".parse::<IdKind>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown ID type: ulids (did you mean 'ulid'?)"
+        );
+        let err = "SNOWFLAK".parse::<IdKind>().unwrap_err();
+        assert!(err.to_string().contains("'snowflake'"));
+        let err = "zzzzzz".parse::<IdKind>().unwrap_err();
+        assert_eq!(err.to_string(), "Unknown ID type: zzzzzz");
+    }
 }
@@ -0,0 +1,255 @@
+//! A small JSONPath subset for pulling IDs out of JSON documents.
+//!
+//! Supported syntax: `$` (root, optional), `.name`, `['name']`, `[n]`
+//! (negative indexes count from the end), `[*]` and `.*` (every element or
+//! value), and `..name` / `..*` (recursive descent). A path without a leading
+//! `$` is read as a dotted path, so `items[*].id` equals `$.items[*].id`.
+
+use crate::core::error::{IdtError, Result};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Key(String),
+    Index(i64),
+    Wildcard,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    recursive: bool,
+    selector: Selector,
+}
+
+/// A compiled path expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    steps: Vec<Step>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let invalid =
+            |msg: &str| IdtError::InvalidArgument(format!("Invalid path '{}': {}", path, msg));
+
+        let trimmed = path.trim();
+        let rest = match trimmed.strip_prefix('$') {
+            Some(rest) => rest,
+            // Dotted paths start with a bare key
+            None if !trimmed.is_empty() && !trimmed.starts_with(['.', '[']) => {
+                return Self::parse(&format!("$.{}", trimmed));
+            }
+            None => trimmed,
+        };
+
+        let chars: Vec<char> = rest.chars().collect();
+        let mut steps = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    let recursive = chars.get(i + 1) == Some(&'.');
+                    i += if recursive { 2 } else { 1 };
+                    if chars.get(i) == Some(&'[') {
+                        if !recursive {
+                            return Err(invalid("unexpected '[' after '.'"));
+                        }
+                        let (selector, next) = parse_bracket(&chars, i).map_err(|m| invalid(&m))?;
+                        steps.push(Step {
+                            recursive,
+                            selector,
+                        });
+                        i = next;
+                        continue;
+                    }
+                    let start = i;
+                    while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                        i += 1;
+                    }
+                    let name: String = chars[start..i].iter().collect();
+                    let selector = match name.as_str() {
+                        "" => return Err(invalid("empty key")),
+                        "*" => Selector::Wildcard,
+                        _ => Selector::Key(name),
+                    };
+                    steps.push(Step {
+                        recursive,
+                        selector,
+                    });
+                }
+                '[' => {
+                    let (selector, next) = parse_bracket(&chars, i).map_err(|m| invalid(&m))?;
+                    steps.push(Step {
+                        recursive: false,
+                        selector,
+                    });
+                    i = next;
+                }
+                c => return Err(invalid(&format!("unexpected '{}'", c))),
+            }
+        }
+
+        Ok(Self { steps })
+    }
+
+    /// Every value in `root` matched by this path, in document order
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for step in &self.steps {
+            let mut next = Vec::new();
+            for value in current {
+                if step.recursive {
+                    let mut descendants = Vec::new();
+                    collect_descendants(value, &mut descendants);
+                    for v in descendants {
+                        apply(&step.selector, v, &mut next);
+                    }
+                } else {
+                    apply(&step.selector, value, &mut next);
+                }
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+/// Parse `[...]` starting at `chars[start] == '['`; returns the selector and
+/// the index just past `]`
+fn parse_bracket(chars: &[char], start: usize) -> std::result::Result<(Selector, usize), String> {
+    let end = (start..chars.len())
+        .find(|&j| chars[j] == ']' && !in_quotes(&chars[start + 1..j]))
+        .ok_or("unclosed '['")?;
+    let inner: String = chars[start + 1..end].iter().collect();
+    let inner = inner.trim();
+
+    let selector = if inner == "*" {
+        Selector::Wildcard
+    } else if let Some(key) = inner
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+    {
+        Selector::Key(key.to_string())
+    } else {
+        Selector::Index(
+            inner
+                .parse()
+                .map_err(|_| format!("invalid index '{}'", inner))?,
+        )
+    };
+    Ok((selector, end + 1))
+}
+
+/// Whether an unterminated quote is open at the end of `chars`
+fn in_quotes(chars: &[char]) -> bool {
+    let mut quote = None;
+    for &c in chars {
+        match quote {
+            Some(q) if c == q => quote = None,
+            None if c == '\'' || c == '"' => quote = Some(c),
+            _ => {}
+        }
+    }
+    quote.is_some()
+}
+
+fn apply<'a>(selector: &Selector, value: &'a Value, out: &mut Vec<&'a Value>) {
+    match (selector, value) {
+        (Selector::Key(key), Value::Object(map)) => out.extend(map.get(key)),
+        (Selector::Index(index), Value::Array(items)) => {
+            let index = if *index < 0 {
+                items.len() as i64 + index
+            } else {
+                *index
+            };
+            if index >= 0 {
+                out.extend(items.get(index as usize));
+            }
+        }
+        (Selector::Wildcard, Value::Array(items)) => out.extend(items),
+        (Selector::Wildcard, Value::Object(map)) => out.extend(map.values()),
+        _ => {}
+    }
+}
+
+fn collect_descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(value);
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| collect_descendants(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_descendants(v, out)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select(path: &str, doc: &Value) -> Vec<Value> {
+        JsonPath::parse(path)
+            .unwrap()
+            .select(doc)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_root() {
+        let doc = json!("abc");
+        assert_eq!(select("$", &doc), [json!("abc")]);
+        assert_eq!(select("", &doc), [json!("abc")]);
+    }
+
+    #[test]
+    fn test_keys_and_indexes() {
+        let doc = json!({"items": [{"id": "a"}, {"id": "b"}, {"id": "c"}]});
+        assert_eq!(select("$.items[0].id", &doc), [json!("a")]);
+        assert_eq!(select("$.items[-1].id", &doc), [json!("c")]);
+        assert_eq!(select("$['items'][1][\"id\"]", &doc), [json!("b")]);
+        assert!(select("$.items[5].id", &doc).is_empty());
+        assert!(select("$.missing", &doc).is_empty());
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let doc = json!({"items": [{"id": "a"}, {"id": "b"}], "meta": {"x": 1, "y": 2}});
+        assert_eq!(select("$.items[*].id", &doc), [json!("a"), json!("b")]);
+        assert_eq!(select("$.items.*.id", &doc), [json!("a"), json!("b")]);
+        assert_eq!(select("$.meta.*", &doc).len(), 2);
+    }
+
+    #[test]
+    fn test_dotted_path() {
+        let doc = json!({"data": {"user": {"id": 42}}});
+        assert_eq!(select("data.user.id", &doc), [json!(42)]);
+        let doc = json!({"items": [{"id": "a"}]});
+        assert_eq!(select("items[*].id", &doc), [json!("a")]);
+    }
+
+    #[test]
+    fn test_recursive_descent() {
+        let doc = json!({"id": "root", "children": [{"id": "a", "children": [{"id": "b"}]}]});
+        assert_eq!(
+            select("$..id", &doc),
+            [json!("root"), json!("a"), json!("b")]
+        );
+        assert_eq!(select("$..children[0].id", &doc), [json!("a"), json!("b")]);
+    }
+
+    #[test]
+    fn test_quoted_keys_with_special_chars() {
+        let doc = json!({"a.b": {"c]": "x"}});
+        assert_eq!(select("$['a.b']['c]']", &doc), [json!("x")]);
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        for path in ["$.", "$[", "$[abc]", "$x", "$.a..", "$.[0]"] {
+            assert!(JsonPath::parse(path).is_err(), "{}", path);
+        }
+    }
+}
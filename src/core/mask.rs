@@ -0,0 +1,495 @@
+use crate::core::encoding::{encode_hex, encode_shortuuid};
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId};
+use crate::ids::snowflake_id::{SnowflakeLayout, TimestampUnit};
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Offset between the Gregorian epoch (1582-10-15) and the Unix epoch, in 100ns ticks
+const GREGORIAN_OFFSET: u64 = 0x01B2_1DD2_1381_4000;
+
+/// Base36 alphabet used by CUID and CUID2
+const BASE36: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// How much of the original timestamp survives masking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum TimestampGranularity {
+    /// Keep the exact timestamp (at the ID's native resolution)
+    #[cfg_attr(feature = "cli", value(name = "exact", alias = "ms"))]
+    Exact,
+    /// Truncate to the start of the second
+    #[cfg_attr(feature = "cli", value(name = "second", alias = "sec"))]
+    Second,
+    /// Truncate to the start of the minute
+    #[cfg_attr(feature = "cli", value(name = "minute", alias = "min"))]
+    Minute,
+    /// Truncate to the start of the hour
+    #[cfg_attr(feature = "cli", value(name = "hour"))]
+    Hour,
+    /// Truncate to the start of the day (UTC)
+    #[cfg_attr(feature = "cli", value(name = "day"))]
+    Day,
+}
+
+impl TimestampGranularity {
+    /// Bucket width in milliseconds
+    pub fn millis(&self) -> u64 {
+        match self {
+            TimestampGranularity::Exact => 1,
+            TimestampGranularity::Second => 1_000,
+            TimestampGranularity::Minute => 60_000,
+            TimestampGranularity::Hour => 3_600_000,
+            TimestampGranularity::Day => 86_400_000,
+        }
+    }
+
+    /// Truncate a millisecond timestamp to the start of its bucket
+    pub fn truncate_ms(&self, ms: u64) -> u64 {
+        ms - ms % self.millis()
+    }
+
+    /// Truncate a second-resolution timestamp to the start of its bucket
+    pub fn truncate_secs(&self, secs: u64) -> u64 {
+        let width = (self.millis() / 1000).max(1);
+        secs - secs % width
+    }
+}
+
+/// Deterministic, keyed pseudonymization of IDs.
+///
+/// The same key and input always produce the same masked ID of the same kind.
+/// Random, machine, and counter bits are replaced with HMAC-SHA256 derived
+/// bits; version and variant markers are preserved so the result still parses
+/// as the original kind. Timestamps are derived as well unless a
+/// [`TimestampGranularity`] is requested, in which case they are kept
+/// truncated to that bucket.
+pub struct Masker {
+    key: Vec<u8>,
+    keep_timestamp: Option<TimestampGranularity>,
+    snowflake_layout: SnowflakeLayout,
+}
+
+impl Masker {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: key.to_vec(),
+            keep_timestamp: None,
+            snowflake_layout: SnowflakeLayout::default_layout(),
+        }
+    }
+
+    pub fn with_keep_timestamp(mut self, granularity: TimestampGranularity) -> Self {
+        self.keep_timestamp = Some(granularity);
+        self
+    }
+
+    pub fn with_snowflake_layout(mut self, layout: SnowflakeLayout) -> Self {
+        self.snowflake_layout = layout;
+        self
+    }
+
+    /// Mask a parsed ID, returning a pseudonymous ID of the same kind
+    pub fn mask(&self, parsed: &dyn ParsedId) -> Result<String> {
+        let kind = parsed.kind();
+        let bytes = parsed.as_bytes();
+
+        match kind {
+            IdKind::UuidNil | IdKind::UuidMax => Ok(parsed.canonical()),
+            IdKind::Uuid
+            | IdKind::UuidV1
+            | IdKind::UuidV3
+            | IdKind::UuidV4
+            | IdKind::UuidV5
+            | IdKind::UuidV6
+            | IdKind::UuidV7 => {
+                let masked =
+                    self.mask_uuid_bytes(kind, &bytes, parsed.timestamp().map(|t| t.millis));
+                Ok(uuid::Uuid::from_bytes(masked).to_string())
+            }
+            IdKind::ShortUuid => {
+                let inner = uuid_kind(&bytes);
+                let ts = parsed.timestamp().map(|t| t.millis);
+                let masked = self.mask_uuid_bytes(inner, &bytes, ts);
+                Ok(encode_shortuuid(&masked))
+            }
+            IdKind::TypeId => {
+                let canonical = parsed.canonical();
+                let prefix = canonical.rfind('_').map(|pos| &canonical[..pos]);
+                let inner = uuid_kind(&bytes);
+                let ts = parsed.timestamp().map(|t| t.millis);
+                let masked = self.mask_uuid_bytes(inner, &bytes, ts);
+                let suffix = crate::ids::typeid_id::typeid_base32_encode(&masked);
+                Ok(match prefix {
+                    Some(p) => format!("{}_{}", p, suffix),
+                    None => suffix,
+                })
+            }
+            IdKind::Ulid => {
+                let derived = self.derive(kind, &bytes, 16);
+                let mut out = [0u8; 16];
+                out.copy_from_slice(&derived);
+                let ms = u64::from_be_bytes([
+                    0, 0, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5],
+                ]);
+                if let Some(ms) = self.kept_ms(ms) {
+                    out[0..6].copy_from_slice(&ms.to_be_bytes()[2..8]);
+                }
+                Ok(ulid::Ulid::from_bytes(out).to_string())
+            }
+            IdKind::Ksuid => {
+                let derived = self.derive(kind, &bytes, 20);
+                let mut out = [0u8; 20];
+                out.copy_from_slice(&derived);
+                let offset = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64;
+                let epoch = crate::ids::ksuid_id::KSUID_EPOCH;
+                if let Some(secs) = self.kept_secs(offset + epoch) {
+                    let kept = secs.saturating_sub(epoch) as u32;
+                    out[0..4].copy_from_slice(&kept.to_be_bytes());
+                }
+                Ok(crate::ids::ksuid_id::encode_base62(&out))
+            }
+            IdKind::ObjectId | IdKind::Xid => {
+                let derived = self.derive(kind, &bytes, 12);
+                let mut out = [0u8; 12];
+                out.copy_from_slice(&derived);
+                let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64;
+                if let Some(secs) = self.kept_secs(secs) {
+                    out[0..4].copy_from_slice(&(secs as u32).to_be_bytes());
+                }
+                if kind == IdKind::Xid {
+                    Ok(crate::ids::xid_id::xid_encode(&out))
+                } else {
+                    Ok(encode_hex(&out))
+                }
+            }
+            IdKind::Tsid => {
+                let value = u64::from_be_bytes(to_array(&bytes)?);
+                let derived = u64::from_be_bytes(to_array(&self.derive(kind, &bytes, 8))?);
+                let ts = match self.kept_ms(value >> 22) {
+                    Some(ms) => ms,
+                    None => derived >> 22,
+                };
+                Ok(crate::ids::tsid_id::tsid_encode(
+                    (ts << 22) | (derived & 0x3F_FFFF),
+                ))
+            }
+            IdKind::Snowflake => {
+                let value = u64::from_be_bytes(to_array(&bytes)?);
+                let derived = u64::from_be_bytes(to_array(&self.derive(kind, &bytes, 8))?);
+                Ok(self.mask_snowflake(value, derived).to_string())
+            }
+            IdKind::Cuid => {
+                let canonical = parsed.canonical();
+                let derived = self.derive(kind, &bytes, canonical.len());
+                let mut out: Vec<u8> = derived.iter().map(|b| BASE36[(*b % 36) as usize]).collect();
+                out[0] = b'c';
+                if let Some(ms) = parsed.timestamp().and_then(|t| self.kept_ms(t.millis)) {
+                    out[1..9].copy_from_slice(crate::ids::cuid_id::pad_base36(ms, 8).as_bytes());
+                }
+                Ok(String::from_utf8(out).expect("BASE36 alphabet is valid UTF-8"))
+            }
+            IdKind::Cuid2 => {
+                let derived = self.derive(kind, &bytes, bytes.len());
+                let mut out: Vec<u8> = derived.iter().map(|b| BASE36[(*b % 36) as usize]).collect();
+                out[0] = b'a' + derived[0] % 26;
+                Ok(String::from_utf8(out).expect("BASE36 alphabet is valid UTF-8"))
+            }
+            IdKind::NanoId => {
+                let alphabet = crate::ids::nanoid_id::DEFAULT_ALPHABET.as_bytes();
+                let derived = self.derive(kind, &bytes, bytes.len());
+                let out: Vec<u8> = derived
+                    .iter()
+                    .map(|b| alphabet[(*b as usize) % alphabet.len()])
+                    .collect();
+                Ok(String::from_utf8(out).expect("NanoID alphabet is valid UTF-8"))
+            }
+            _ => Err(IdtError::InvalidArgument(format!(
+                "Masking not supported for {}",
+                kind.name()
+            ))),
+        }
+    }
+
+    /// Mask the 16 bytes of a UUID-shaped value, keeping its version and variant
+    fn mask_uuid_bytes(&self, kind: IdKind, bytes: &[u8], timestamp_ms: Option<u64>) -> [u8; 16] {
+        let derived = self.derive(kind, bytes, 16);
+        let mut out = [0u8; 16];
+        out.copy_from_slice(&derived);
+
+        // Preserve version nibble and variant bits
+        out[6] = (out[6] & 0x0F) | (bytes[6] & 0xF0);
+        out[8] = (out[8] & 0x3F) | (bytes[8] & 0xC0);
+
+        let kept = timestamp_ms.and_then(|ms| self.kept_ms(ms));
+        match kind {
+            IdKind::UuidV7 => {
+                if let Some(ms) = kept {
+                    out[0..6].copy_from_slice(&ms.to_be_bytes()[2..8]);
+                }
+            }
+            IdKind::UuidV1 | IdKind::UuidV6 => {
+                // Random node IDs must have the multicast bit set (RFC 9562 §6.10)
+                out[10] |= 0x01;
+                if let Some(ms) = kept {
+                    let ticks = ms * 10_000 + GREGORIAN_OFFSET;
+                    if kind == IdKind::UuidV1 {
+                        out[0..4].copy_from_slice(&((ticks & 0xFFFF_FFFF) as u32).to_be_bytes());
+                        out[4..6].copy_from_slice(&(((ticks >> 32) & 0xFFFF) as u16).to_be_bytes());
+                        let hi = ((ticks >> 48) & 0x0FFF) as u16 | 0x1000;
+                        out[6..8].copy_from_slice(&hi.to_be_bytes());
+                    } else {
+                        out[0..4].copy_from_slice(&((ticks >> 28) as u32).to_be_bytes());
+                        out[4..6].copy_from_slice(&(((ticks >> 12) & 0xFFFF) as u16).to_be_bytes());
+                        let lo = (ticks & 0x0FFF) as u16 | 0x6000;
+                        out[6..8].copy_from_slice(&lo.to_be_bytes());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        out
+    }
+
+    /// Rebuild a Snowflake from derived bits, optionally keeping the timestamp bucket
+    fn mask_snowflake(&self, value: u64, derived: u64) -> u64 {
+        let layout = &self.snowflake_layout;
+        let total_bits: u8 = layout.fields.iter().map(|f| f.bits).sum();
+        let mut shift = total_bits;
+        let mut id: u64 = 0;
+
+        for field in layout.fields {
+            shift -= field.bits;
+            let mask = if field.bits >= 64 {
+                u64::MAX
+            } else {
+                (1u64 << field.bits) - 1
+            };
+            let mut field_value = (derived >> shift) & mask;
+
+            if field.name == "timestamp" {
+                let unit_ms = match layout.timestamp_unit {
+                    TimestampUnit::Millis => 1,
+                    TimestampUnit::TenMillis => 10,
+                    TimestampUnit::Seconds => 1000,
+                };
+                let raw = (value >> shift) & mask;
+                let ms = raw * unit_ms + layout.epoch;
+                if let Some(kept) = self.kept_ms(ms) {
+                    field_value = (kept.saturating_sub(layout.epoch) / unit_ms) & mask;
+                }
+            }
+
+            id |= field_value << shift;
+        }
+
+        id
+    }
+
+    fn kept_ms(&self, ms: u64) -> Option<u64> {
+        self.keep_timestamp.map(|g| g.truncate_ms(ms))
+    }
+
+    fn kept_secs(&self, secs: u64) -> Option<u64> {
+        self.keep_timestamp.map(|g| g.truncate_secs(secs))
+    }
+
+    /// Derive `len` pseudorandom bytes from the key, the kind, and the original bytes
+    fn derive(&self, kind: IdKind, input: &[u8], len: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(len);
+        let mut block: u32 = 0;
+        while out.len() < len {
+            let mut mac =
+                HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+            mac.update(kind.name().as_bytes());
+            mac.update(&[0]);
+            mac.update(input);
+            mac.update(&block.to_be_bytes());
+            out.extend_from_slice(&mac.finalize().into_bytes());
+            block += 1;
+        }
+        out.truncate(len);
+        out
+    }
+}
+
+/// UUID kind implied by the version nibble of a 16-byte value
+fn uuid_kind(bytes: &[u8]) -> IdKind {
+    match bytes[6] >> 4 {
+        1 => IdKind::UuidV1,
+        3 => IdKind::UuidV3,
+        4 => IdKind::UuidV4,
+        5 => IdKind::UuidV5,
+        6 => IdKind::UuidV6,
+        7 => IdKind::UuidV7,
+        _ => IdKind::Uuid,
+    }
+}
+
+fn to_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N]> {
+    bytes
+        .try_into()
+        .map_err(|_| IdtError::InvalidFormat(format!("expected {} bytes, got {}", N, bytes.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::parse_id;
+
+    fn mask(masker: &Masker, input: &str) -> String {
+        let parsed = parse_id(input, None).unwrap();
+        masker.mask(parsed.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn test_mask_is_deterministic() {
+        let masker = Masker::new(b"secret");
+        let a = mask(&masker, "550e8400-e29b-41d4-a716-446655440000");
+        let b = mask(&masker, "550e8400-e29b-41d4-a716-446655440000");
+        assert_eq!(a, b);
+        assert_ne!(a, "550e8400-e29b-41d4-a716-446655440000");
+    }
+
+    #[test]
+    fn test_mask_depends_on_key() {
+        let a = mask(&Masker::new(b"one"), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        let b = mask(&Masker::new(b"two"), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_mask_case_insensitive_input() {
+        let masker = Masker::new(b"k");
+        let a = mask(&masker, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        let b = mask(&masker, "01arz3ndektsv4rrffq69g5fav");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_mask_preserves_kind() {
+        let masker = Masker::new(b"k");
+        let inputs = [
+            "550e8400-e29b-41d4-a716-446655440000",
+            "019c04e5-6118-7b22-95cb-a10e84dad469",
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+            "507f1f77bcf86cd799439011",
+            "9m4e2mr0ui3e8a215n4g",
+            "0ujtsYcgvSTl8PAuAdqWYSMnLOv",
+        ];
+        for input in inputs {
+            let original = parse_id(input, None).unwrap();
+            let masked = masker.mask(original.as_ref()).unwrap();
+            let reparsed = parse_id(&masked, Some(original.kind())).unwrap();
+            assert_eq!(
+                reparsed.kind(),
+                original.kind(),
+                "kind changed for {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_mask_keep_timestamp_exact() {
+        let masker = Masker::new(b"k").with_keep_timestamp(TimestampGranularity::Exact);
+        let input = "019c04e5-6118-7b22-95cb-a10e84dad469";
+        let original = parse_id(input, None).unwrap();
+        let masked = masker.mask(original.as_ref()).unwrap();
+        let reparsed = parse_id(&masked, None).unwrap();
+        assert_eq!(
+            reparsed.timestamp().unwrap().millis,
+            original.timestamp().unwrap().millis
+        );
+        assert_ne!(masked, input);
+    }
+
+    #[test]
+    fn test_mask_keep_timestamp_bucket() {
+        let masker = Masker::new(b"k").with_keep_timestamp(TimestampGranularity::Hour);
+        let original = parse_id("01ARZ3NDEKTSV4RRFFQ69G5FAV", None).unwrap();
+        let masked = masker.mask(original.as_ref()).unwrap();
+        let reparsed = parse_id(&masked, None).unwrap();
+        let ts = reparsed.timestamp().unwrap().millis;
+        assert_eq!(ts % 3_600_000, 0);
+        assert_eq!(
+            ts,
+            TimestampGranularity::Hour.truncate_ms(original.timestamp().unwrap().millis)
+        );
+    }
+
+    #[test]
+    fn test_mask_uuid_v1_keep_timestamp() {
+        let masker = Masker::new(b"k").with_keep_timestamp(TimestampGranularity::Second);
+        let input = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+        let original = parse_id(input, None).unwrap();
+        let masked = masker.mask(original.as_ref()).unwrap();
+        let reparsed = parse_id(&masked, None).unwrap();
+        assert_eq!(reparsed.kind(), IdKind::UuidV1);
+        assert_eq!(
+            reparsed.timestamp().unwrap().millis,
+            TimestampGranularity::Second.truncate_ms(original.timestamp().unwrap().millis)
+        );
+        // Node no longer leaks the original MAC address
+        assert!(!masked.ends_with("00c04fd430c8"));
+    }
+
+    #[test]
+    fn test_mask_snowflake_keep_timestamp() {
+        let layout = SnowflakeLayout::twitter();
+        let masker = Masker::new(b"k")
+            .with_snowflake_layout(layout.clone())
+            .with_keep_timestamp(TimestampGranularity::Exact);
+        let original =
+            crate::ids::ParsedSnowflake::parse_with_layout("1234567890123456789", layout.clone())
+                .unwrap();
+        let masked = masker.mask(&original).unwrap();
+        let reparsed = crate::ids::ParsedSnowflake::parse_with_layout(&masked, layout).unwrap();
+        assert_eq!(reparsed.timestamp_ms(), original.timestamp_ms());
+    }
+
+    #[test]
+    fn test_mask_typeid_keeps_prefix() {
+        let masker = Masker::new(b"k");
+        let masked = mask(&masker, "user_01h455vb4pex5vsknk084sn02q");
+        assert!(masked.starts_with("user_"));
+        assert_eq!(masked.len(), "user_01h455vb4pex5vsknk084sn02q".len());
+    }
+
+    #[test]
+    fn test_mask_string_kinds_keep_length() {
+        let masker = Masker::new(b"k");
+        let cuid = parse_id("cjld2cjxh0000qzrmn831i7rn", Some(IdKind::Cuid)).unwrap();
+        let masked = masker.mask(cuid.as_ref()).unwrap();
+        assert_eq!(masked.len(), 25);
+        assert!(masked.starts_with('c'));
+
+        let nano = parse_id("V1StGXR8_Z5jdHi6B-myT", Some(IdKind::NanoId)).unwrap();
+        let masked = masker.mask(nano.as_ref()).unwrap();
+        assert_eq!(masked.len(), 21);
+    }
+
+    #[test]
+    fn test_mask_nil_unchanged() {
+        let masker = Masker::new(b"k");
+        let nil = "00000000-0000-0000-0000-000000000000";
+        assert_eq!(mask(&masker, nil), nil);
+    }
+
+    #[test]
+    fn test_mask_assigned_id_unsupported() {
+        let masker = Masker::new(b"k");
+        let isbn = parse_id("9780306406157", Some(IdKind::Isbn13)).unwrap();
+        assert!(masker.mask(isbn.as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_granularity_truncate() {
+        assert_eq!(TimestampGranularity::Minute.truncate_ms(61_234), 60_000);
+        assert_eq!(TimestampGranularity::Day.truncate_secs(86_401), 86_400);
+        assert_eq!(TimestampGranularity::Exact.truncate_secs(7), 7);
+    }
+}
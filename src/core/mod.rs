@@ -1,9 +1,26 @@
+pub mod bloom;
+pub mod case;
+pub mod components;
+pub mod db;
+pub mod decode;
 pub mod detection;
 pub mod encoding;
 pub mod error;
 pub mod id;
+pub mod jsonpath;
+#[cfg(feature = "cli")]
+pub mod mask;
+pub mod predicate;
+pub mod rng;
+pub mod scan;
+pub mod sortkey;
+pub mod state;
+pub mod time;
+pub mod url;
 
-pub use detection::{DetectionResult, detect_id_type};
+pub use case::{CanonicalCase, set_canonical_case};
+pub use components::Components;
+pub use detection::{DetectionOptions, DetectionResult, detect_id_type, detect_id_type_with};
 pub use encoding::EncodingFormat;
 pub use error::{IdtError, Result};
 pub use id::{
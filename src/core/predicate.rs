@@ -0,0 +1,468 @@
+//! Predicates over decoded ID components, e.g. `machine_id=7` or `sequence>=100`.
+//!
+//! A predicate is `FIELD OP VALUE` with `OP` one of `=`, `!=`, `<`, `<=`, `>`,
+//! `>=`, `~` (substring), or `in` with a range `LO..HI` (or `LO..=HI`).
+//! `FIELD` names a key in the ID's `components` (dotted for nested objects),
+//! or one of the top-level inspection fields `id_type`, `version`, `variant`,
+//! and `timestamp`. Numbers compare numerically, integers exactly however
+//! wide; `timestamp` compares against times such as `2022-01-01` or `-24h`,
+//! resolved once when the predicate is parsed; anything else compares as text.
+
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, InspectionResult};
+use crate::core::time::parse_time;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    In,
+}
+
+impl Op {
+    /// Longest operators first so `<=` is not read as `<`
+    const TOKENS: [(&'static str, Op); 8] = [
+        ("!=", Op::Ne),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        (" in ", Op::In),
+        ("=", Op::Eq),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+        ("~", Op::Contains),
+    ];
+
+    fn token(self) -> &'static str {
+        Self::TOKENS
+            .iter()
+            .find(|&&(_, op)| op == self)
+            .map(|&(token, _)| token)
+            .expect("every operator has a token")
+    }
+}
+
+/// Split `LO..HI` or `LO..=HI` into its bounds and whether `HI` is included
+fn split_range(value: &str) -> Option<(&str, &str, bool)> {
+    if let Some((lo, hi)) = value.split_once("..=") {
+        return Some((lo.trim(), hi.trim(), true));
+    }
+    value
+        .split_once("..")
+        .map(|(lo, hi)| (lo.trim(), hi.trim(), false))
+}
+
+/// A numeric operand. Integers stay integers, so 64-bit fields such as a
+/// TSID's `numeric_value` compare exactly rather than through `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Number {
+    fn parse(value: &str) -> Option<Self> {
+        match value.parse() {
+            Ok(n) => Some(Number::Int(n)),
+            Err(_) => value.parse().ok().map(Number::Float),
+        }
+    }
+
+    fn of(value: &Value) -> Option<Self> {
+        if let Some(n) = value.as_i64() {
+            return Some(Number::Int(n.into()));
+        }
+        if let Some(n) = value.as_u64() {
+            return Some(Number::Int(n.into()));
+        }
+        value.as_f64().map(Number::Float)
+    }
+
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(n) => n as f64,
+            Number::Float(n) => n,
+        }
+    }
+
+    fn compare(self, other: Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Int(a), Number::Int(b)) => Some(a.cmp(&b)),
+            (a, b) => a.as_f64().partial_cmp(&b.as_f64()),
+        }
+    }
+}
+
+/// The operand of a predicate on `field`, read as a number, or as a time in
+/// Unix milliseconds for `timestamp`
+fn operand(field: &str, value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Number> {
+    if field == "timestamp" {
+        parse_time(value, now).ok().map(|ms| Number::Int(ms.into()))
+    } else {
+        Number::parse(value)
+    }
+}
+
+/// Top-level inspection fields a predicate may name besides components
+const TOP_LEVEL_FIELDS: [&str; 4] = ["id_type", "version", "variant", "timestamp"];
+
+/// IDs of types `idt gen` can't make, to learn their component names from
+const SAMPLE_IDS: &[(IdKind, &str)] = &[
+    (IdKind::Isbn13, "9780306406157"),
+    (IdKind::Isbn10, "0306406152"),
+    (IdKind::Ean13, "4006381333931"),
+    (IdKind::Ean8, "96385074"),
+    (IdKind::UpcA, "036000291452"),
+    (IdKind::Issn, "0317-8471"),
+    (IdKind::Ismn, "979-0-2600-0043-8"),
+    (IdKind::Isni, "0000000121032683"),
+    (IdKind::Isin, "US0378331005"),
+    (IdKind::Gtin14, "00012345600012"),
+    (IdKind::Asin, "B07PGL2ZSL"),
+];
+
+/// Every field some ID type decodes, dotted for nested objects: the
+/// top-level fields, the components of a fresh ID of each generatable type
+/// (and of the registered custom schema), those of the types in
+/// [`SAMPLE_IDS`], and every Snowflake preset's fields. Types left out of
+/// this build contribute nothing.
+pub fn known_fields() -> BTreeSet<String> {
+    fn collect(prefix: &str, value: &Value, fields: &mut BTreeSet<String>) {
+        let Value::Object(map) = value else {
+            return;
+        };
+        for (key, value) in map {
+            let field = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            collect(&field, value, fields);
+            fields.insert(field);
+        }
+    }
+
+    let mut fields: BTreeSet<String> = TOP_LEVEL_FIELDS.iter().map(|f| f.to_string()).collect();
+    let generated = IdKind::generatable()
+        .iter()
+        .chain(&[IdKind::Custom])
+        .filter_map(|&kind| {
+            let id = crate::ids::create_generator(kind).ok()?.generate().ok()?;
+            Some((kind, id))
+        });
+    let sampled = SAMPLE_IDS.iter().map(|&(kind, id)| (kind, id.to_string()));
+    for (kind, id) in generated.chain(sampled) {
+        if let Ok(parsed) = crate::ids::parse_id(&id, Some(kind))
+            && let Some(components) = parsed.inspect().components
+        {
+            collect("", &components, &mut fields);
+        }
+    }
+    #[cfg(feature = "snowflake")]
+    for name in crate::ids::SnowflakeLayout::PRESETS {
+        if let Some(layout) = crate::ids::SnowflakeLayout::by_name(name) {
+            fields.extend(layout.fields.iter().map(|f| f.name.to_string()));
+        }
+    }
+    fields
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate {
+    pub field: String,
+    pub op: Op,
+    pub value: String,
+    /// `value` as a number or time, resolved once so that `-24h` is the
+    /// same instant for every ID
+    number: Option<Number>,
+    /// The bounds of an `in` range, resolved the same way, and whether the
+    /// upper one is included
+    range: Option<(Number, Number, bool)>,
+}
+
+impl Predicate {
+    pub fn new(field: &str, op: Op, value: &str) -> Self {
+        let now = chrono::Utc::now();
+        let range = match op {
+            Op::In => split_range(value).and_then(|(lo, hi, inclusive)| {
+                Some((
+                    operand(field, lo, now)?,
+                    operand(field, hi, now)?,
+                    inclusive,
+                ))
+            }),
+            _ => None,
+        };
+        Self {
+            field: field.to_string(),
+            op,
+            value: value.to_string(),
+            number: operand(field, value, now),
+            range,
+        }
+    }
+
+    /// Fail unless some ID type has this predicate's field, so a typo is an
+    /// argument error rather than a failure for every ID
+    pub fn check_field(&self, known: &BTreeSet<String>) -> Result<()> {
+        if known.contains(&self.field) {
+            return Ok(());
+        }
+        Err(IdtError::InvalidArgument(format!(
+            "Unknown field '{}' in '{}'. Fields are: {}",
+            self.field,
+            self,
+            known
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))
+    }
+
+    /// Whether `result` satisfies this predicate; a missing field never matches
+    pub fn matches(&self, result: &InspectionResult) -> bool {
+        if self.field == "timestamp" {
+            return result
+                .timestamp
+                .as_ref()
+                .is_some_and(|ts| self.compare(&Value::from(ts.millis)));
+        }
+        let top_level = match self.field.as_str() {
+            "id_type" => Some(Value::from(result.id_type.as_str())),
+            "version" => result.version.as_deref().map(Value::from),
+            "variant" => result.variant.as_deref().map(Value::from),
+            _ => None,
+        };
+        let field = top_level.as_ref().or_else(|| {
+            self.field
+                .split('.')
+                .try_fold(result.components.as_ref()?, |value, key| value.get(key))
+        });
+        field.is_some_and(|value| self.compare(value))
+    }
+
+    /// Compare `value` against this predicate
+    fn compare(&self, value: &Value) -> bool {
+        let text = match value {
+            Value::String(s) => s.clone(),
+            Value::Null | Value::Array(_) | Value::Object(_) => return false,
+            other => other.to_string(),
+        };
+
+        if self.op == Op::Contains {
+            return text.contains(&self.value);
+        }
+
+        if self.op == Op::In {
+            let (Some(actual), Some((lo, hi, inclusive))) = (Number::of(value), self.range) else {
+                return false;
+            };
+            let above_lo = actual.compare(lo).is_some_and(Ordering::is_ge);
+            let below_hi = match actual.compare(hi) {
+                Some(Ordering::Less) => true,
+                Some(Ordering::Equal) => inclusive,
+                _ => false,
+            };
+            return above_lo && below_hi;
+        }
+
+        let ordering = match (Number::of(value), self.number) {
+            (Some(actual), Some(expected)) if value.is_number() => actual.compare(expected),
+            _ => Some(text.as_str().cmp(self.value.as_str())),
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+
+        match self.op {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Contains | Op::In => unreachable!(),
+        }
+    }
+}
+
+impl FromStr for Predicate {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid =
+            |msg: &str| IdtError::InvalidArgument(format!("Invalid predicate '{}': {}", s, msg));
+
+        let (start, token, op) = Op::TOKENS
+            .iter()
+            .filter_map(|&(token, op)| s.find(token).map(|i| (i, token, op)))
+            .min_by_key(|&(i, token, _)| (i, std::cmp::Reverse(token.len())))
+            .ok_or_else(|| {
+                invalid("expected FIELD=VALUE, or an operator !=, <, <=, >, >=, ~, in")
+            })?;
+
+        let field = s[..start].trim();
+        let value = s[start + token.len()..].trim();
+        if field.is_empty() {
+            return Err(invalid("missing field name"));
+        }
+
+        let predicate = Self::new(field, op, value);
+        if op == Op::In {
+            split_range(value)
+                .ok_or_else(|| invalid("expected a range LO..HI or LO..=HI after 'in'"))?;
+            if predicate.range.is_none() {
+                return Err(invalid(
+                    "range bounds must be numbers, or times for timestamp",
+                ));
+            }
+        } else if field == "timestamp" && op != Op::Contains && predicate.number.is_none() {
+            return Err(invalid(
+                "expected a time such as 2022-01-01, RFC 3339, or an offset like -24h",
+            ));
+        }
+        Ok(predicate)
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.field, self.op.token(), self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inspect(id: &str) -> InspectionResult {
+        crate::ids::parse_id(id, None).unwrap().inspect()
+    }
+
+    fn matches(predicate: &str, id: &str) -> bool {
+        predicate
+            .parse::<Predicate>()
+            .unwrap()
+            .matches(&inspect(id))
+    }
+
+    #[test]
+    fn test_parse() {
+        let p: Predicate = "machine_id >= 7".parse().unwrap();
+        assert_eq!(p, Predicate::new("machine_id", Op::Ge, "7"));
+        let p: Predicate = "prefix=a=b".parse().unwrap();
+        assert_eq!(p, Predicate::new("prefix", Op::Eq, "a=b"));
+        let p: Predicate = "seq!=0".parse().unwrap();
+        assert_eq!(p.op, Op::Ne);
+        assert!("machine_id".parse::<Predicate>().is_err());
+        assert!("=7".parse::<Predicate>().is_err());
+    }
+
+    #[test]
+    fn test_snowflake_components() {
+        // datacenter_id 20, machine_id 24, sequence 277
+        let id = "1234567890123456789";
+        assert!(matches("machine_id=24", id));
+        assert!(!matches("machine_id=7", id));
+        assert!(matches("datacenter_id<21", id));
+        assert!(matches("sequence>=277", id));
+        assert!(!matches("sequence>277", id));
+        // Numeric, not lexicographic: 24 > 3
+        assert!(matches("machine_id>3", id));
+    }
+
+    #[test]
+    fn test_text_and_top_level_fields() {
+        let id = "user_01h455vb4pex5vsknk084sn02q";
+        assert!(matches("prefix=user", id));
+        assert!(matches("prefix~us", id));
+        assert!(!matches("prefix=order", id));
+        assert!(matches("id_type=typeid", id));
+        assert!(matches("uuid_version=7", id));
+    }
+
+    #[test]
+    fn test_in_range() {
+        let id = "1234567890123456789";
+        assert!(matches("machine_id in 0..32", id));
+        assert!(!matches("machine_id in 0..24", id));
+        assert!(matches("machine_id in 0..=24", id));
+        assert!(!matches("machine_id in 25..32", id));
+
+        let p: Predicate = "machine_id in 0..8".parse().unwrap();
+        assert_eq!(p, Predicate::new("machine_id", Op::In, "0..8"));
+        assert_eq!(p.to_string(), "machine_id in 0..8");
+        assert!("machine_id in 8".parse::<Predicate>().is_err());
+        assert!("machine_id in a..b".parse::<Predicate>().is_err());
+    }
+
+    #[test]
+    fn test_timestamp() {
+        // UUIDv7 from 2023-07-04
+        let id = "01893726-efee-7f02-8fbf-9f2b7bc2f910";
+        assert!(matches("timestamp >= 2022-01-01", id));
+        assert!(!matches("timestamp < 2023-01-01", id));
+        assert!(matches("timestamp in 2023-07-01..2023-08-01", id));
+        assert!(matches("timestamp < now", id));
+        assert!("timestamp > yesterday".parse::<Predicate>().is_err());
+    }
+
+    #[test]
+    fn test_relative_time_resolved_once() {
+        let p: Predicate = "timestamp > -1h".parse().unwrap();
+        let Some(Number::Int(resolved)) = p.number else {
+            panic!("expected a resolved time");
+        };
+        let now = chrono::Utc::now().timestamp_millis() as i128;
+        assert!((now - 3_600_000 - resolved).abs() < 60_000);
+    }
+
+    #[test]
+    fn test_wide_integers_compare_exactly() {
+        // TSID numeric_value 2^53 + 1, which f64 rounds to 2^53
+        let value = Value::from(9_007_199_254_740_993u64);
+        let p: Predicate = "numeric_value > 9007199254740992".parse().unwrap();
+        assert!(p.compare(&value));
+        let p: Predicate = "numeric_value = 9007199254740992".parse().unwrap();
+        assert!(!p.compare(&value));
+        let p: Predicate = "numeric_value in 9007199254740993..=9007199254740993"
+            .parse()
+            .unwrap();
+        assert!(p.compare(&value));
+    }
+
+    #[test]
+    fn test_check_field() {
+        let known = known_fields();
+        for field in [
+            "machine_id",
+            "timestamp",
+            "id_type",
+            "prefix",
+            "shard_id",
+            "check_digit",
+        ] {
+            let p: Predicate = format!("{}~1", field).parse().unwrap();
+            assert!(p.check_field(&known).is_ok(), "{}", field);
+        }
+        let p: Predicate = "bogus > 3".parse().unwrap();
+        let err = p.check_field(&known).unwrap_err();
+        assert!(err.to_string().contains("Unknown field 'bogus'"));
+    }
+
+    #[test]
+    fn test_missing_field_never_matches() {
+        assert!(!matches("machine_id=1", "01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+        assert!(!matches("machine_id!=1", "01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+    }
+}
@@ -0,0 +1,249 @@
+//! The randomness source behind every random-based generator.
+//!
+//! Generators draw from an [`IdRng`] rather than calling `rand::rng()`
+//! directly, so `--rng` can swap the source for a whole run: the operating
+//! system's CSPRNG, a fast thread-local ChaCha stream, or a fixed seed that
+//! makes output reproducible.
+
+use crate::core::error::{IdtError, Result};
+use rand::rand_core::UnwrapErr;
+use rand::rngs::{ChaCha12Rng, SysRng};
+use rand::{Rng, RngExt, SeedableRng};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Where random bits come from, as given to `--rng`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RngSource {
+    /// Read every value from the operating system (`getrandom`)
+    Os,
+    /// A ChaCha12 stream per thread, seeded and periodically reseeded from
+    /// the OS. Cryptographically secure and much faster than `Os`.
+    #[default]
+    ChaCha,
+    /// A ChaCha12 stream from a fixed seed: the same seed gives the same IDs.
+    /// Not for anything that must be unpredictable.
+    Seeded(u64),
+}
+
+impl FromStr for RngSource {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "os" => Ok(RngSource::Os),
+            "chacha" => Ok(RngSource::ChaCha),
+            lower => match lower.strip_prefix("seeded:") {
+                Some(seed) => seed.trim().parse().map(RngSource::Seeded).map_err(|_| {
+                    IdtError::InvalidArgument(format!(
+                        "Invalid seed '{}'. Use a non-negative integer.",
+                        seed.trim()
+                    ))
+                }),
+                None => Err(IdtError::InvalidArgument(format!(
+                    "Unknown RNG '{}'. Use os, chacha, or seeded:<n>.",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
+impl fmt::Display for RngSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RngSource::Os => write!(f, "os"),
+            RngSource::ChaCha => write!(f, "chacha"),
+            RngSource::Seeded(seed) => write!(f, "seeded:{}", seed),
+        }
+    }
+}
+
+/// A handle to a randomness source.
+///
+/// Clones share the same stream, so a seeded source handed to several
+/// generators still produces one reproducible sequence.
+#[derive(Clone, Default)]
+pub struct IdRng {
+    source: RngSource,
+    seeded: Option<Arc<Mutex<ChaCha12Rng>>>,
+}
+
+impl IdRng {
+    pub fn new(source: RngSource) -> Self {
+        let seeded = match source {
+            RngSource::Seeded(seed) => Some(Arc::new(Mutex::new(ChaCha12Rng::seed_from_u64(seed)))),
+            _ => None,
+        };
+        Self { source, seeded }
+    }
+
+    pub fn os() -> Self {
+        Self::new(RngSource::Os)
+    }
+
+    pub fn chacha() -> Self {
+        Self::new(RngSource::ChaCha)
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self::new(RngSource::Seeded(seed))
+    }
+
+    pub fn source(&self) -> RngSource {
+        self.source
+    }
+
+    /// Run `f` with the underlying generator
+    pub fn with<T>(&self, f: impl FnOnce(&mut dyn Rng) -> T) -> T {
+        match self.seeded {
+            Some(ref rng) => f(&mut *rng.lock().unwrap_or_else(|e| e.into_inner())),
+            None if self.source == RngSource::Os => f(&mut UnwrapErr(SysRng)),
+            None => f(&mut rand::rng()),
+        }
+    }
+
+    /// Fill `dest` with random bytes
+    pub fn fill(&self, dest: &mut [u8]) {
+        self.with(|rng| rng.fill_bytes(dest))
+    }
+
+    pub fn u64(&self) -> u64 {
+        self.with(|rng| rng.next_u64())
+    }
+
+    pub fn u128(&self) -> u128 {
+        let mut bytes = [0u8; 16];
+        self.fill(&mut bytes);
+        u128::from_be_bytes(bytes)
+    }
+
+    /// A uniform value in `0..n`
+    pub fn below(&self, n: u64) -> u64 {
+        self.with(|rng| rng.random_range(0..n))
+    }
+}
+
+impl fmt::Debug for IdRng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IdRng").field(&self.source).finish()
+    }
+}
+
+/// Bytes drawn from the RNG per refill of a [`RandomPool`]
+pub const POOL_CHUNK_SIZE: usize = 4096;
+
+/// Random bytes drawn from an [`IdRng`] a chunk at a time.
+///
+/// Bulk generators take each ID's random bits from here, so a run of a
+/// million UUIDs makes a few thousand RNG calls instead of a million.
+#[derive(Default)]
+pub struct RandomPool {
+    rng: IdRng,
+    chunk: Mutex<Chunk>,
+}
+
+#[derive(Default)]
+struct Chunk {
+    bytes: Vec<u8>,
+    pos: usize,
+}
+
+impl RandomPool {
+    pub fn new(rng: IdRng) -> Self {
+        Self {
+            rng,
+            chunk: Mutex::default(),
+        }
+    }
+
+    pub fn rng(&self) -> &IdRng {
+        &self.rng
+    }
+
+    /// The next `N` random bytes, refilling the pool when it runs short
+    pub fn take<const N: usize>(&self) -> [u8; N] {
+        const { assert!(N <= POOL_CHUNK_SIZE) };
+        let mut chunk = self.chunk.lock().unwrap_or_else(|e| e.into_inner());
+        if chunk.pos + N > chunk.bytes.len() {
+            chunk.bytes.resize(POOL_CHUNK_SIZE, 0);
+            self.rng.fill(&mut chunk.bytes);
+            chunk.pos = 0;
+        }
+        let mut out = [0u8; N];
+        out.copy_from_slice(&chunk.bytes[chunk.pos..chunk.pos + N]);
+        chunk.pos += N;
+        out
+    }
+}
+
+impl fmt::Debug for RandomPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RandomPool").field(&self.rng).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!("os".parse::<RngSource>().unwrap(), RngSource::Os);
+        assert_eq!("ChaCha".parse::<RngSource>().unwrap(), RngSource::ChaCha);
+        assert_eq!(
+            "seeded:42".parse::<RngSource>().unwrap(),
+            RngSource::Seeded(42)
+        );
+        assert!("seeded:-1".parse::<RngSource>().is_err());
+        assert!("mt19937".parse::<RngSource>().is_err());
+        assert_eq!(RngSource::Seeded(7).to_string(), "seeded:7");
+    }
+
+    #[test]
+    fn test_seeded_is_reproducible() {
+        let a = IdRng::seeded(42);
+        let b = IdRng::seeded(42);
+        assert_eq!(
+            (a.u64(), a.u128(), a.below(1000)),
+            (b.u64(), b.u128(), b.below(1000))
+        );
+        assert_ne!(IdRng::seeded(43).u64(), IdRng::seeded(42).u64());
+    }
+
+    #[test]
+    fn test_clones_share_a_stream() {
+        let a = IdRng::seeded(1);
+        let b = a.clone();
+        let fresh = IdRng::seeded(1);
+        assert_eq!(a.u64(), fresh.u64());
+        assert_eq!(b.u64(), fresh.u64());
+    }
+
+    #[test]
+    fn test_pool_refills() {
+        let pool = RandomPool::new(IdRng::seeded(5));
+        let mut stream = vec![0u8; POOL_CHUNK_SIZE];
+        IdRng::seeded(5).fill(&mut stream);
+
+        // Whole takes come from the chunk in order; a take that doesn't fit
+        // in what's left starts a fresh chunk
+        let takes: Vec<[u8; 10]> = (0..POOL_CHUNK_SIZE / 10 + 1).map(|_| pool.take()).collect();
+        assert_eq!(takes[0], stream[..10]);
+        assert_eq!(takes[1], stream[10..20]);
+        assert_ne!(takes[POOL_CHUNK_SIZE / 10], [0u8; 10]);
+        assert_ne!(takes[POOL_CHUNK_SIZE / 10], stream[..10]);
+    }
+
+    #[test]
+    fn test_unseeded_sources() {
+        for rng in [IdRng::os(), IdRng::chacha()] {
+            let mut bytes = [0u8; 32];
+            rng.fill(&mut bytes);
+            assert_ne!(bytes, [0u8; 32]);
+            assert!(rng.below(10) < 10);
+        }
+    }
+}
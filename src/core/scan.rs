@@ -0,0 +1,183 @@
+use crate::core::detection::detect_id_type;
+use crate::core::id::IdKind;
+
+/// Minimum detection confidence used when scanning free text.
+///
+/// Low-confidence kinds (NanoID, CUID2, ShortUUID, ASIN) match too many
+/// ordinary words to be useful in logs, so they are excluded by default.
+pub const DEFAULT_MIN_CONFIDENCE: f32 = 0.75;
+
+/// An ID found inside a larger piece of text
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdMatch {
+    /// Byte offset of the first character
+    pub start: usize,
+    /// Byte offset one past the last character
+    pub end: usize,
+    pub kind: IdKind,
+    pub confidence: f32,
+}
+
+/// Options controlling which tokens count as IDs
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub min_confidence: f32,
+    /// Only report these kinds (all kinds if empty)
+    pub kinds: Vec<IdKind>,
+    /// Never report these kinds
+    pub exclude: Vec<IdKind>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+            kinds: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl ScanOptions {
+    fn accepts(&self, kind: IdKind) -> bool {
+        (self.kinds.is_empty() || self.kinds.iter().any(|&k| kind.matches_filter(k)))
+            && !self.exclude.iter().any(|&k| kind.matches_filter(k))
+    }
+}
+
+/// Find every ID in `text`.
+///
+/// Text is split into tokens of ASCII alphanumerics, `-`, and `_`. Each token
+/// is run through detection and kept only if its best accepted detection
+/// parses as that kind.
+pub fn find_ids(text: &str, options: &ScanOptions) -> Vec<IdMatch> {
+    let mut matches = Vec::new();
+    let mut token_start: Option<usize> = None;
+
+    for (i, c) in text.char_indices() {
+        if is_token_char(c) {
+            if token_start.is_none() {
+                token_start = Some(i);
+            }
+        } else if let Some(start) = token_start.take()
+            && let Some(m) = match_token(text, start, i, options)
+        {
+            matches.push(m);
+        }
+    }
+
+    if let Some(start) = token_start
+        && let Some(m) = match_token(text, start, text.len(), options)
+    {
+        matches.push(m);
+    }
+
+    matches
+}
+
+/// Short upper-case label for a kind, used in redaction placeholders
+pub fn kind_label(kind: IdKind) -> String {
+    if kind.is_uuid() {
+        "UUID".to_string()
+    } else {
+        kind.name().to_uppercase()
+    }
+}
+
+fn match_token(text: &str, start: usize, end: usize, options: &ScanOptions) -> Option<IdMatch> {
+    let token = &text[start..end];
+    let detections = detect_id_type(token).ok()?;
+
+    detections
+        .into_iter()
+        .filter(|d| d.confidence >= options.min_confidence && options.accepts(d.kind))
+        .find(|d| crate::ids::parse_id(token, Some(d.kind)).is_ok())
+        .map(|d| IdMatch {
+            start,
+            end,
+            kind: d.kind,
+            confidence: d.confidence,
+        })
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_ids_in_log_line() {
+        let line = "2024-01-01 INFO user=550e8400-e29b-41d4-a716-446655440000 order=01ARZ3NDEKTSV4RRFFQ69G5FAV done";
+        let matches = find_ids(line, &ScanOptions::default());
+        assert_eq!(matches.len(), 2);
+        assert_eq!(
+            &line[matches[0].start..matches[0].end],
+            "550e8400-e29b-41d4-a716-446655440000"
+        );
+        assert_eq!(matches[0].kind, IdKind::UuidV4);
+        assert_eq!(
+            &line[matches[1].start..matches[1].end],
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV"
+        );
+        assert_eq!(matches[1].kind, IdKind::Ulid);
+    }
+
+    #[test]
+    fn test_find_ids_at_text_edges() {
+        let text = "507f1f77bcf86cd799439011";
+        let matches = find_ids(text, &ScanOptions::default());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].start, 0);
+        assert_eq!(matches[0].end, text.len());
+    }
+
+    #[test]
+    fn test_find_ids_ignores_plain_words() {
+        let text = "the quick brown fox jumps over the lazy dog, well-known_words";
+        assert!(find_ids(text, &ScanOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_find_ids_kind_filter() {
+        let text = "550e8400-e29b-41d4-a716-446655440000 01ARZ3NDEKTSV4RRFFQ69G5FAV";
+        let options = ScanOptions {
+            kinds: vec![IdKind::Uuid],
+            ..Default::default()
+        };
+        let matches = find_ids(text, &options);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].kind, IdKind::UuidV4);
+    }
+
+    #[test]
+    fn test_find_ids_min_confidence() {
+        let text = "V1StGXR8_Z5jdHi6B-myT";
+        assert!(find_ids(text, &ScanOptions::default()).is_empty());
+        let options = ScanOptions {
+            min_confidence: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(find_ids(text, &options)[0].kind, IdKind::NanoId);
+    }
+
+    #[test]
+    fn test_find_ids_non_ascii_text() {
+        let text = "ключ→01ARZ3NDEKTSV4RRFFQ69G5FAV←";
+        let matches = find_ids(text, &ScanOptions::default());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            &text[matches[0].start..matches[0].end],
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV"
+        );
+    }
+
+    #[test]
+    fn test_kind_label() {
+        assert_eq!(kind_label(IdKind::UuidV7), "UUID");
+        assert_eq!(kind_label(IdKind::Ulid), "ULID");
+        assert_eq!(kind_label(IdKind::ObjectId), "OBJECTID");
+    }
+}
@@ -0,0 +1,152 @@
+//! Byte keys whose order is the IDs' chronological order, for range scans in
+//! key-value stores such as RocksDB, LevelDB, or DynamoDB sort keys.
+//!
+//! A key is one type tag byte followed by a fixed-width, big-endian payload,
+//! so keys of one type sort by time and keys of different types never
+//! interleave. Most time-ordered IDs already sort by their bytes; UUIDv1
+//! stores its timestamp low bits first, so it is rewritten in UUIDv6 order.
+
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId};
+
+/// Type tag and payload width in bytes of each supported kind. Tags are part
+/// of stored keys, so they never change.
+const LAYOUTS: &[(IdKind, u8, usize)] = &[
+    (IdKind::UuidV1, 0x01, 16),
+    (IdKind::UuidV6, 0x06, 16),
+    (IdKind::UuidV7, 0x07, 16),
+    (IdKind::Ulid, 0x10, 16),
+    (IdKind::Ksuid, 0x11, 20),
+    (IdKind::ObjectId, 0x12, 12),
+    (IdKind::Snowflake, 0x13, 8),
+    (IdKind::Tsid, 0x14, 8),
+    (IdKind::Xid, 0x15, 12),
+    (IdKind::Cuid, 0x16, 25),
+    (IdKind::TypeId, 0x17, 16),
+];
+
+/// Tag byte and payload width for `kind`, or `None` if its bytes don't
+/// follow time
+pub fn layout(kind: IdKind) -> Option<(u8, usize)> {
+    LAYOUTS
+        .iter()
+        .find(|&&(k, _, _)| k == kind)
+        .map(|&(_, tag, width)| (tag, width))
+}
+
+/// The sort key of `id`: its tag byte, then its payload
+pub fn sort_key(id: &dyn ParsedId) -> Result<Vec<u8>> {
+    let kind = id.kind();
+    let (tag, width) = layout(kind).ok_or_else(|| {
+        IdtError::InvalidArgument(format!(
+            "sortkey needs a time-ordered ID, not {}",
+            kind.name()
+        ))
+    })?;
+
+    let mut payload = id.as_bytes();
+    if kind == IdKind::UuidV1 {
+        payload = v1_in_v6_order(&payload);
+    }
+    if payload.len() > width {
+        return Err(IdtError::InvalidArgument(format!(
+            "{} is {} bytes, more than the {} a sortkey holds",
+            kind.name(),
+            payload.len(),
+            width
+        )));
+    }
+
+    // Left-pad so shorter payloads of a numeric type still compare by value
+    let mut key = Vec::with_capacity(1 + width);
+    key.push(tag);
+    key.resize(1 + width - payload.len(), 0);
+    key.extend_from_slice(&payload);
+    Ok(key)
+}
+
+/// The sort key of `id` as lowercase hex, or `n/a (...)` for kinds without
+/// one, like [`encode_java_longs`](crate::core::encoding::encode_java_longs)
+pub fn encode_sort_key(id: &dyn ParsedId) -> String {
+    match sort_key(id) {
+        Ok(key) => hex::encode(key),
+        Err(_) => format!("n/a ({} is not time-ordered)", id.kind().name()),
+    }
+}
+
+/// Move a UUIDv1's 60-bit timestamp to the front, most significant bits
+/// first, as UUIDv6 lays it out. The version nibble becomes 6.
+fn v1_in_v6_order(bytes: &[u8]) -> Vec<u8> {
+    let Ok(bytes) = <[u8; 16]>::try_from(bytes) else {
+        return bytes.to_vec();
+    };
+    let time_low = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64;
+    let time_mid = u16::from_be_bytes([bytes[4], bytes[5]]) as u64;
+    let time_hi = (u16::from_be_bytes([bytes[6], bytes[7]]) & 0x0fff) as u64;
+    let ts = time_hi << 48 | time_mid << 32 | time_low;
+
+    let mut out = bytes;
+    out[0..4].copy_from_slice(&((ts >> 28) as u32).to_be_bytes());
+    out[4..6].copy_from_slice(&(((ts >> 12) & 0xffff) as u16).to_be_bytes());
+    out[6..8].copy_from_slice(&(0x6000 | (ts & 0x0fff) as u16).to_be_bytes());
+    out.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::parse_id;
+
+    fn key(input: &str) -> Vec<u8> {
+        sort_key(parse_id(input, None).unwrap().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn test_tags_are_unique() {
+        for (i, &(kind, tag, _)) in LAYOUTS.iter().enumerate() {
+            assert!(
+                LAYOUTS[..i].iter().all(|&(k, t, _)| k != kind && t != tag),
+                "{}",
+                kind.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fixed_width() {
+        let short = parse_id("175928847299117063", Some(IdKind::Snowflake)).unwrap();
+        let long = parse_id("1234567890123456789", Some(IdKind::Snowflake)).unwrap();
+        let short = sort_key(short.as_ref()).unwrap();
+        let long = sort_key(long.as_ref()).unwrap();
+        assert_eq!(short.len(), 9);
+        assert_eq!(long.len(), 9);
+        assert_eq!(short[0], 0x13);
+        // Text order puts "17..." after "12..."; key order follows the value
+        assert!(short < long);
+    }
+
+    #[test]
+    fn test_uuid_v1_orders_by_time() {
+        // RFC 9562 test vector (2022-02-22T19:22:22Z), and one minute later
+        let earlier = key("c232ab00-9414-11ec-b3c8-9f6bdeced846");
+        let later = key("fdf27900-9414-11ec-b3c8-9f6bdeced846");
+        assert!(earlier < later);
+        // Same instant as the RFC's UUIDv6 vector, under the v1 tag
+        assert_eq!(hex::encode(&earlier[1..9]), "1ec9414c232a6b00");
+        assert_eq!(earlier[0], 0x01);
+
+        // The low time bits wrap: text order disagrees with time order
+        let wrapped = key("00000000-9415-11ec-b3c8-9f6bdeced846");
+        assert!(later < wrapped);
+    }
+
+    #[test]
+    fn test_rejects_unordered_kinds() {
+        let v4 = parse_id("550e8400-e29b-41d4-a716-446655440000", None).unwrap();
+        assert!(sort_key(v4.as_ref()).is_err());
+        assert_eq!(
+            encode_sort_key(v4.as_ref()),
+            "n/a (uuidv4 is not time-ordered)"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Generator state kept in a JSON file between invocations.
+//!
+//! Each `idt` run is a new process, so generators that promise ordering or
+//! uniqueness across runs (Snowflake sequences, monotonic ULIDs) store their
+//! last output on disk. The file is locked for the whole run, so concurrent
+//! invocations queue up instead of reading the same state.
+
+use crate::core::error::{IdtError, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+/// Run `f` against the state stored at `path`, holding an exclusive file lock.
+///
+/// A missing or empty file starts from `S::default()`. The state is written
+/// back even if `f` fails, so IDs issued before an error such as a closed
+/// pipe stay recorded; `f` should leave the state it reached in place.
+pub fn with_locked_state<S, T>(path: &Path, f: impl FnOnce(&mut S) -> Result<T>) -> Result<T>
+where
+    S: Default + Serialize + DeserializeOwned,
+{
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    file.lock()?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    let mut state: S = if contents.trim().is_empty() {
+        S::default()
+    } else {
+        serde_json::from_str(&contents).map_err(|e| {
+            IdtError::ParseError(format!("Invalid state file {}: {}", path.display(), e))
+        })?
+    };
+
+    let result = f(&mut state);
+
+    // Overwrite in place before trimming, so a crash leaves a file that fails
+    // to parse rather than an empty one that reads as a fresh state
+    let mut serialized = serde_json::to_string_pretty(&state)?;
+    serialized.push('\n');
+    file.rewind()?;
+    file.write_all(serialized.as_bytes())?;
+    file.set_len(serialized.len() as u64)?;
+    file.sync_all()?;
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_kept_when_f_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        with_locked_state(&path, |state: &mut Vec<u64>| {
+            state.extend([1, 2, 3]);
+            Ok(())
+        })
+        .unwrap();
+
+        let err = with_locked_state(&path, |state: &mut Vec<u64>| {
+            state.truncate(1);
+            Err::<(), _>(IdtError::GenerationError("broken pipe".into()))
+        });
+        assert!(err.is_err());
+
+        // The shorter state replaced the longer one entirely
+        let state: Vec<u64> =
+            with_locked_state(&path, |state: &mut Vec<u64>| Ok(state.clone())).unwrap();
+        assert_eq!(state, [1]);
+    }
+}
@@ -0,0 +1,433 @@
+//! Parsing of user-supplied points in time, such as `--after` and `--before`,
+//! of durations such as `gen --for`, and of time buckets such as
+//! `bucket --by`.
+
+use crate::core::error::{IdtError, Result};
+use chrono::{
+    DateTime, Datelike, Months, NaiveDate, NaiveDateTime, SecondsFormat, TimeDelta, TimeZone,
+    Timelike, Utc,
+};
+use chrono_tz::Tz;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Parse a point in time as milliseconds since the Unix epoch.
+///
+/// Accepts RFC 3339 (`2024-05-01T12:00:00Z`), a UTC date (`2024-05-01`),
+/// `now`, or an offset from `now` such as `-24h`, `+30m`, or `-7d` (units
+/// `ms`, `s`, `m`, `h`, `d`, `w`).
+pub fn parse_time(input: &str, now: DateTime<Utc>) -> Result<i64> {
+    let input = input.trim();
+    let invalid = || {
+        IdtError::InvalidArgument(format!(
+            "Invalid time '{}': expected RFC 3339, YYYY-MM-DD, now, or an offset like -24h",
+            input
+        ))
+    };
+
+    if input.eq_ignore_ascii_case("now") {
+        return Ok(now.timestamp_millis());
+    }
+
+    if let Some(offset) = input.strip_prefix(['-', '+']) {
+        let delta = parse_delta(offset).ok_or_else(invalid)?;
+        let time = if input.starts_with('-') {
+            now.checked_sub_signed(delta)
+        } else {
+            now.checked_add_signed(delta)
+        };
+        return time.map(|t| t.timestamp_millis()).ok_or_else(invalid);
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.timestamp_millis());
+    }
+
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+        .ok_or_else(invalid)
+}
+
+/// Parse a positive duration such as `30s`, `5m`, or `500ms` (units `ms`,
+/// `s`, `m`, `h`, `d`, `w`)
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    parse_delta(input)
+        .and_then(|delta| delta.to_std().ok())
+        .filter(|duration| !duration.is_zero())
+        .ok_or_else(|| {
+            IdtError::InvalidArgument(format!(
+                "Invalid duration '{}': expected a number and unit, like 30s or 5m",
+                input
+            ))
+        })
+}
+
+/// `<digits><unit>`, e.g. `24h`
+fn parse_delta(input: &str) -> Option<TimeDelta> {
+    let split = input.find(|c: char| !c.is_ascii_digit())?;
+    let amount: i64 = input[..split].parse().ok()?;
+    match &input[split..] {
+        "ms" => TimeDelta::try_milliseconds(amount),
+        "s" => TimeDelta::try_seconds(amount),
+        "m" => TimeDelta::try_minutes(amount),
+        "h" => TimeDelta::try_hours(amount),
+        "d" => TimeDelta::try_days(amount),
+        "w" => TimeDelta::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// Describe an age in milliseconds (positive = past) with its two largest
+/// units, e.g. `3 days 4 hours ago` or `in 2 minutes`
+pub fn humanize_age(age_ms: i64) -> String {
+    const UNITS: [(&str, u64); 5] = [
+        ("year", 365 * 86_400_000),
+        ("day", 86_400_000),
+        ("hour", 3_600_000),
+        ("minute", 60_000),
+        ("second", 1_000),
+    ];
+
+    let mut remaining = age_ms.unsigned_abs();
+    if remaining < 1_000 {
+        return "just now".to_string();
+    }
+
+    let mut parts = Vec::new();
+    for (name, size) in UNITS {
+        let count = remaining / size;
+        if count > 0 {
+            parts.push(format!(
+                "{} {}{}",
+                count,
+                name,
+                if count == 1 { "" } else { "s" }
+            ));
+            remaining %= size;
+        } else if !parts.is_empty() {
+            break;
+        }
+        if parts.len() == 2 {
+            break;
+        }
+    }
+
+    let span = parts.join(" ");
+    if age_ms >= 0 {
+        format!("{} ago", span)
+    } else {
+        format!("in {}", span)
+    }
+}
+
+/// Width of a time bucket. Calendar units follow the wall clock of a time
+/// zone, so a day bucket in `Asia/Seoul` starts at local midnight and DST
+/// days are 23 or 25 hours long. Fixed durations are counted from the Unix
+/// epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketSize {
+    Minute,
+    Hour,
+    Day,
+    /// ISO week, starting Monday
+    Week,
+    Month,
+    /// A fixed width in milliseconds, such as `15m` or `6h`
+    Fixed(u64),
+}
+
+impl BucketSize {
+    /// Start of the bucket holding `ms`, in Unix milliseconds
+    pub fn start(&self, ms: i64, tz: Tz) -> Result<i64> {
+        let width = match self {
+            BucketSize::Fixed(width) => *width as i64,
+            _ => {
+                let local = local_time(ms, tz)?.naive_local();
+                let start = self.truncate(local).ok_or_else(|| out_of_range(ms))?;
+                return resolve_local(start, tz);
+            }
+        };
+        Ok(ms - ms.rem_euclid(width))
+    }
+
+    /// Start of the bucket after the one starting at `start`
+    pub fn next(&self, start: i64, tz: Tz) -> Result<i64> {
+        let local = local_time(start, tz)?.naive_local();
+        let next = match self {
+            BucketSize::Fixed(width) => {
+                return start
+                    .checked_add(*width as i64)
+                    .ok_or_else(|| out_of_range(start));
+            }
+            BucketSize::Minute => local.checked_add_signed(TimeDelta::minutes(1)),
+            BucketSize::Hour => local.checked_add_signed(TimeDelta::hours(1)),
+            BucketSize::Day => local.checked_add_signed(TimeDelta::days(1)),
+            BucketSize::Week => local.checked_add_signed(TimeDelta::weeks(1)),
+            BucketSize::Month => local.checked_add_months(Months::new(1)),
+        };
+        let next = next
+            .and_then(|next| self.truncate(next))
+            .ok_or_else(|| out_of_range(start))?;
+        resolve_local(next, tz)
+    }
+
+    /// Name of the bucket starting at `start`: `2024-05` for a month,
+    /// `2024-W18` for a week, `2024-05-01` for a day, else the start time
+    pub fn label(&self, start: i64, tz: Tz) -> Result<String> {
+        let dt = local_time(start, tz)?;
+        Ok(match self {
+            BucketSize::Month => dt.format("%Y-%m").to_string(),
+            BucketSize::Week => dt.format("%G-W%V").to_string(),
+            BucketSize::Day => dt.format("%Y-%m-%d").to_string(),
+            BucketSize::Fixed(width) if width % 1000 != 0 => {
+                dt.to_rfc3339_opts(SecondsFormat::Millis, true)
+            }
+            _ => dt.to_rfc3339_opts(SecondsFormat::Secs, true),
+        })
+    }
+
+    /// Start of the bucket holding `local`, or `None` at the edge of the
+    /// calendar
+    fn truncate(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        let date = local.date();
+        let midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).unwrap();
+        Some(match self {
+            BucketSize::Minute => local.with_second(0).unwrap().with_nanosecond(0).unwrap(),
+            BucketSize::Hour => date.and_hms_opt(local.hour(), 0, 0).unwrap(),
+            BucketSize::Day => midnight(date),
+            BucketSize::Week => midnight(date.checked_sub_signed(TimeDelta::days(
+                date.weekday().num_days_from_monday() as i64,
+            ))?),
+            BucketSize::Month => midnight(date.with_day(1).unwrap()),
+            BucketSize::Fixed(_) => local,
+        })
+    }
+}
+
+/// `ms` on the wall clock of `tz`
+fn local_time(ms: i64, tz: Tz) -> Result<DateTime<Tz>> {
+    tz.timestamp_millis_opt(ms)
+        .single()
+        .ok_or_else(|| out_of_range(ms))
+}
+
+fn out_of_range(ms: i64) -> IdtError {
+    IdtError::InvalidArgument(format!(
+        "Timestamp {} ms is outside the range of dates that can be bucketed",
+        ms
+    ))
+}
+
+/// The instant a local wall-clock time names. A time skipped by a DST jump
+/// resolves to the first instant after the gap.
+fn resolve_local(local: NaiveDateTime, tz: Tz) -> Result<i64> {
+    let mut probe = local;
+    loop {
+        if let Some(dt) = tz.from_local_datetime(&probe).earliest() {
+            return Ok(dt.timestamp_millis());
+        }
+        probe = probe
+            .checked_add_signed(TimeDelta::minutes(15))
+            .ok_or_else(|| out_of_range(local.and_utc().timestamp_millis()))?;
+    }
+}
+
+impl FromStr for BucketSize {
+    type Err = IdtError;
+
+    /// `minute`, `hour`, `day`, `week`, `month`, or a duration such as `15m`
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "minute" | "min" => BucketSize::Minute,
+            "hour" | "hourly" => BucketSize::Hour,
+            "day" | "daily" => BucketSize::Day,
+            "week" | "weekly" => BucketSize::Week,
+            "month" | "monthly" => BucketSize::Month,
+            other => {
+                let width = parse_duration(other).map_err(|_| {
+                    IdtError::InvalidArgument(format!(
+                        "Invalid bucket '{}': expected minute, hour, day, week, month, or a duration like 15m",
+                        s
+                    ))
+                })?;
+                BucketSize::Fixed(width.as_millis() as u64)
+            }
+        })
+    }
+}
+
+impl fmt::Display for BucketSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BucketSize::Minute => write!(f, "minute"),
+            BucketSize::Hour => write!(f, "hour"),
+            BucketSize::Day => write!(f, "day"),
+            BucketSize::Week => write!(f, "week"),
+            BucketSize::Month => write!(f, "month"),
+            BucketSize::Fixed(width) => write!(f, "{}ms", width),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-05-02T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_absolute() {
+        let noon = now().timestamp_millis();
+        assert_eq!(parse_time("2024-05-02T12:00:00Z", now()).unwrap(), noon);
+        assert_eq!(
+            parse_time("2024-05-02T14:00:00+02:00", now()).unwrap(),
+            noon
+        );
+        assert_eq!(
+            parse_time("2024-05-02", now()).unwrap(),
+            noon - 12 * 3_600_000
+        );
+    }
+
+    #[test]
+    fn test_relative() {
+        let noon = now().timestamp_millis();
+        assert_eq!(parse_time("now", now()).unwrap(), noon);
+        assert_eq!(parse_time("-24h", now()).unwrap(), noon - 86_400_000);
+        assert_eq!(parse_time("+30m", now()).unwrap(), noon + 1_800_000);
+        assert_eq!(parse_time("-1w", now()).unwrap(), noon - 7 * 86_400_000);
+        assert_eq!(parse_time("-500ms", now()).unwrap(), noon - 500);
+    }
+
+    #[test]
+    fn test_invalid() {
+        for input in ["", "yesterday", "-24", "-h", "-3y", "2024-13-01", "24h"] {
+            assert!(parse_time(input, now()).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+        for input in ["", "30", "0s", "-5s", "5y", "s"] {
+            assert!(parse_duration(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_humanize_age() {
+        let day = 86_400_000;
+        assert_eq!(humanize_age(0), "just now");
+        assert_eq!(humanize_age(-999), "just now");
+        assert_eq!(humanize_age(1_000), "1 second ago");
+        assert_eq!(
+            humanize_age(3 * day + 4 * 3_600_000 + 59_000),
+            "3 days 4 hours ago"
+        );
+        assert_eq!(humanize_age(-120_000), "in 2 minutes");
+        // The second unit must be adjacent: 1 hour 0 minutes 5 seconds
+        assert_eq!(humanize_age(3_605_000), "1 hour ago");
+        assert_eq!(humanize_age(400 * day), "1 year 35 days ago");
+    }
+
+    fn ms(rfc3339: &str) -> i64 {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn test_bucket_utc() {
+        let t = ms("2024-05-01T13:45:12.345Z");
+        let utc = Tz::UTC;
+        let cases = [
+            (
+                BucketSize::Minute,
+                "2024-05-01T13:45:00Z",
+                "2024-05-01T13:45:00Z",
+            ),
+            (
+                BucketSize::Hour,
+                "2024-05-01T13:00:00Z",
+                "2024-05-01T13:00:00Z",
+            ),
+            (BucketSize::Day, "2024-05-01T00:00:00Z", "2024-05-01"),
+            (BucketSize::Week, "2024-04-29T00:00:00Z", "2024-W18"),
+            (BucketSize::Month, "2024-05-01T00:00:00Z", "2024-05"),
+            (
+                BucketSize::Fixed(900_000),
+                "2024-05-01T13:45:00Z",
+                "2024-05-01T13:45:00Z",
+            ),
+        ];
+        for (size, start, label) in cases {
+            let bucket = size.start(t, utc).unwrap();
+            assert_eq!(bucket, ms(start), "{}", size);
+            assert_eq!(size.label(bucket, utc).unwrap(), label, "{}", size);
+        }
+        let may = BucketSize::Month.start(t, utc).unwrap();
+        assert_eq!(BucketSize::Month.next(may, utc).unwrap(), ms("2024-06-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_bucket_time_zone() {
+        let seoul: Tz = "Asia/Seoul".parse().unwrap();
+        // 2024-05-01T20:00Z is already May 2 in Seoul (UTC+9)
+        let t = ms("2024-05-01T20:00:00Z");
+        let start = BucketSize::Day.start(t, seoul).unwrap();
+        assert_eq!(start, ms("2024-05-01T15:00:00Z"));
+        assert_eq!(BucketSize::Day.label(start, seoul).unwrap(), "2024-05-02");
+
+        // The US spring-forward day is 23 hours long
+        let ny: Tz = "America/New_York".parse().unwrap();
+        let day = BucketSize::Day.start(ms("2024-03-10T12:00:00Z"), ny).unwrap();
+        assert_eq!(BucketSize::Day.next(day, ny).unwrap() - day, 23 * 3_600_000);
+    }
+
+    #[test]
+    fn test_bucket_out_of_range() {
+        // Far past the last date chrono can represent
+        let t = i64::MAX / 2;
+        let seoul: Tz = "Asia/Seoul".parse().unwrap();
+        for size in [BucketSize::Day, BucketSize::Week, BucketSize::Month] {
+            let err = size.start(t, seoul).unwrap_err();
+            assert!(err.to_string().contains("outside the range"), "{}", err);
+            assert!(size.label(t, seoul).is_err());
+            assert!(size.next(t, seoul).is_err());
+        }
+        assert!(BucketSize::Fixed(60_000).next(i64::MAX - 1, seoul).is_err());
+
+        // The last month on the calendar has no successor
+        let max = NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let last = BucketSize::Month
+            .start(max.timestamp_millis(), Tz::UTC)
+            .unwrap();
+        assert_eq!(
+            BucketSize::Month.label(last, Tz::UTC).unwrap(),
+            max.format("%Y-%m").to_string()
+        );
+        assert!(BucketSize::Month.next(last, Tz::UTC).is_err());
+    }
+
+    #[test]
+    fn test_parse_bucket_size() {
+        assert_eq!("day".parse::<BucketSize>().unwrap(), BucketSize::Day);
+        assert_eq!("Week".parse::<BucketSize>().unwrap(), BucketSize::Week);
+        assert_eq!(
+            "15m".parse::<BucketSize>().unwrap(),
+            BucketSize::Fixed(900_000)
+        );
+        assert!("fortnight".parse::<BucketSize>().is_err());
+        assert!("0s".parse::<BucketSize>().is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Pull an ID out of a URL pasted from a browser.
+//!
+//! Known sites map to a specific ID kind: a tweet's status ID is a Twitter
+//! Snowflake, a Discord message link ends in a Discord Snowflake, and MongoDB
+//! Atlas puts project and organization ObjectIds in its paths. Any other URL
+//! is searched for a path segment or query value that looks like an ID.
+
+use crate::core::detection::detect_id_type;
+use crate::core::id::IdKind;
+use crate::core::scan::DEFAULT_MIN_CONFIDENCE;
+
+/// An ID found inside a URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlId<'a> {
+    /// The ID, as it appears in the URL
+    pub id: &'a str,
+    /// Which pattern matched, e.g. `twitter-status` or `url-path`
+    pub source: &'static str,
+    /// Kind the site implies, if any
+    pub kind: Option<IdKind>,
+    /// Snowflake preset the site implies, if any
+    pub preset: Option<&'static str>,
+}
+
+impl<'a> UrlId<'a> {
+    fn site(id: &'a str, source: &'static str, kind: IdKind, preset: Option<&'static str>) -> Self {
+        Self {
+            id,
+            source,
+            kind: Some(kind),
+            preset,
+        }
+    }
+}
+
+/// The parts of an `http(s)://` URL that can carry an ID
+struct Url<'a> {
+    /// Lowercase host without `www.` or a port
+    host: String,
+    segments: Vec<&'a str>,
+    query: Vec<&'a str>,
+    fragment: Vec<&'a str>,
+}
+
+impl<'a> Url<'a> {
+    fn parse(input: &'a str) -> Option<Self> {
+        let (scheme, rest) = input.split_once("://")?;
+        if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+            return None;
+        }
+        let (rest, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        let host = host.split(':').next().unwrap_or(host).to_ascii_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+        if host.is_empty() {
+            return None;
+        }
+        let split = |s: &'a str, sep: char| s.split(sep).filter(|p| !p.is_empty()).collect();
+        Some(Self {
+            host,
+            segments: split(path, '/'),
+            query: query
+                .split('&')
+                .filter_map(|pair| pair.split_once('=').map(|(_, value)| value))
+                .filter(|value| !value.is_empty())
+                .collect(),
+            fragment: split(fragment, '/'),
+        })
+    }
+
+    fn host_is(&self, domains: &[&str]) -> bool {
+        domains.iter().any(|domain| {
+            self.host == *domain
+                || self
+                    .host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+    }
+}
+
+/// Whether `input` is an `http(s)://` URL
+pub fn is_url(input: &str) -> bool {
+    Url::parse(input.trim()).is_some()
+}
+
+/// Find the ID in a URL. Returns `None` if `input` is not a URL or no part
+/// of it looks like an ID.
+pub fn extract_id(input: &str) -> Option<UrlId<'_>> {
+    let url = Url::parse(input.trim())?;
+    site_id(&url).or_else(|| generic_id(&url))
+}
+
+/// The ID in a URL from a site whose link format is known
+fn site_id<'a>(url: &Url<'a>) -> Option<UrlId<'a>> {
+    let digits = |s: &&str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let hex24 = |s: &&str| s.len() == 24 && s.bytes().all(|b| b.is_ascii_hexdigit());
+    let segment = |i: usize| url.segments.get(i).copied();
+
+    if url.host_is(&["twitter.com", "x.com"]) {
+        // /<user>/status/<id> and /i/web/status/<id>
+        let pos = url.segments.iter().position(|s| *s == "status")?;
+        let id = segment(pos + 1).filter(digits)?;
+        return Some(UrlId::site(
+            id,
+            "twitter-status",
+            IdKind::Snowflake,
+            Some("twitter"),
+        ));
+    }
+
+    if url.host_is(&["discord.com", "discordapp.com"]) {
+        // /channels/<guild or @me>/<channel>[/<message>]
+        if url.segments.first() != Some(&"channels") {
+            return None;
+        }
+        let (id, source) = match segment(3).filter(digits) {
+            Some(message) => (message, "discord-message"),
+            None => (segment(2).filter(digits)?, "discord-channel"),
+        };
+        return Some(UrlId::site(id, source, IdKind::Snowflake, Some("discord")));
+    }
+
+    if url.host_is(&["cloud.mongodb.com"]) {
+        // Atlas keeps project and organization ObjectIds in the path
+        // (/v2/<project>#/clusters) or the fragment (#/org/<org>/projects)
+        let id = url
+            .segments
+            .iter()
+            .chain(&url.fragment)
+            .copied()
+            .find(hex24)?;
+        return Some(UrlId::site(id, "mongodb-atlas", IdKind::ObjectId, None));
+    }
+
+    None
+}
+
+/// The last path segment, or else the first query value, that detection
+/// recognizes with at least scan-level confidence
+fn generic_id<'a>(url: &Url<'a>) -> Option<UrlId<'a>> {
+    let looks_like_id = |s: &&str| {
+        detect_id_type(s)
+            .ok()
+            .and_then(|results| results.first().map(|r| r.confidence))
+            .is_some_and(|confidence| confidence >= DEFAULT_MIN_CONFIDENCE)
+    };
+    let (id, source) = match url.segments.iter().rev().copied().find(looks_like_id) {
+        Some(id) => (id, "url-path"),
+        None => (url.query.iter().copied().find(looks_like_id)?, "url-query"),
+    };
+    Some(UrlId {
+        id,
+        source,
+        kind: None,
+        preset: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_twitter_status() {
+        for input in [
+            "https://twitter.com/jack/status/20",
+            "https://x.com/jack/status/20?s=20",
+            "https://mobile.twitter.com/i/web/status/20",
+        ] {
+            let url = extract_id(input).unwrap();
+            assert_eq!(url.id, "20");
+            assert_eq!(url.source, "twitter-status");
+            assert_eq!(url.preset, Some("twitter"));
+        }
+        assert!(extract_id("https://twitter.com/jack").is_none());
+        assert!(extract_id("https://nottwitter.com/jack/status/20").is_none());
+    }
+
+    #[test]
+    fn test_discord_links() {
+        let url = extract_id(
+            "https://discord.com/channels/81384788765712384/381870553235193857/1143646939598774322",
+        )
+        .unwrap();
+        assert_eq!(url.id, "1143646939598774322");
+        assert_eq!(url.source, "discord-message");
+        assert_eq!(url.preset, Some("discord"));
+
+        let url = extract_id("https://ptb.discord.com/channels/@me/381870553235193857").unwrap();
+        assert_eq!(url.id, "381870553235193857");
+        assert_eq!(url.source, "discord-channel");
+    }
+
+    #[test]
+    fn test_mongodb_atlas() {
+        let url =
+            extract_id("https://cloud.mongodb.com/v2/507f1f77bcf86cd799439011#/clusters").unwrap();
+        assert_eq!(url.id, "507f1f77bcf86cd799439011");
+        assert_eq!(url.kind, Some(IdKind::ObjectId));
+
+        let url = extract_id("https://cloud.mongodb.com/v2#/org/507f191e810c19729de860ea/projects")
+            .unwrap();
+        assert_eq!(url.id, "507f191e810c19729de860ea");
+    }
+
+    #[test]
+    fn test_generic_url() {
+        let url = extract_id(
+            "https://api.example.com:8443/v1/orders/550e8400-e29b-41d4-a716-446655440000/items",
+        )
+        .unwrap();
+        assert_eq!(url.id, "550e8400-e29b-41d4-a716-446655440000");
+        assert_eq!(url.source, "url-path");
+        assert_eq!(url.kind, None);
+
+        let url =
+            extract_id("http://example.com/search?id=01ARZ3NDEKTSV4RRFFQ69G5FAV&q=x").unwrap();
+        assert_eq!(url.id, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert_eq!(url.source, "url-query");
+
+        assert!(extract_id("https://example.com/about/team").is_none());
+    }
+
+    #[test]
+    fn test_not_a_url() {
+        assert!(!is_url("550e8400-e29b-41d4-a716-446655440000"));
+        assert!(!is_url("ftp://example.com/file"));
+        assert!(!is_url("https://"));
+        assert!(is_url("HTTPS://Example.com"));
+        assert!(extract_id("1234567890123456789").is_none());
+    }
+}
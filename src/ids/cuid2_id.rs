@@ -3,29 +3,53 @@ use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, Timestamp, ValidationResult,
 };
-use rand::RngExt;
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 
 /// Default CUID2 length
 const DEFAULT_LENGTH: usize = 24;
 
-/// CUID2 counter
-static CUID2_COUNTER: AtomicU64 = AtomicU64::new(0);
-
 /// Base36 alphabet
 const BASE36: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
 
+/// Counter hashed into generated CUID2s.
+///
+/// Generators created with [`Cuid2Generator::new`] share one process-wide
+/// counter; use [`Cuid2Generator::with_state`] to give one its own.
+#[derive(Debug, Default)]
+pub struct Cuid2State {
+    counter: AtomicU64,
+}
+
+impl Cuid2State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process-wide state shared by default generators
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<Cuid2State>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+}
+
 /// CUID2 generator
 pub struct Cuid2Generator {
     length: usize,
+    state: Arc<Cuid2State>,
+    rng: IdRng,
 }
 
 impl Default for Cuid2Generator {
     fn default() -> Self {
         Self {
             length: DEFAULT_LENGTH,
+            state: Cuid2State::shared(),
+            rng: IdRng::default(),
         }
     }
 }
@@ -34,25 +58,35 @@ impl Cuid2Generator {
     pub fn new() -> Self {
         Self::default()
     }
+
+    pub fn with_state(mut self, state: Arc<Cuid2State>) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = rng;
+        self
+    }
 }
 
 impl IdGenerator for Cuid2Generator {
     fn generate(&self) -> Result<String> {
-        let mut rng = rand::rng();
+        let rng = &self.rng;
 
         // Gather entropy sources
         let timestamp = chrono::Utc::now().timestamp_millis() as u64;
-        let counter = CUID2_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let counter = self.state.counter.fetch_add(1, Ordering::SeqCst);
 
         // Generate random salt
-        let salt: u64 = rng.random();
+        let salt = rng.u64();
 
         // Fingerprint from pid
         let pid = std::process::id() as u64;
 
         // Additional random data
-        let random1: u64 = rng.random();
-        let random2: u64 = rng.random();
+        let random1 = rng.u64();
+        let random2 = rng.u64();
 
         // Hash all entropy together
         let mut hasher = Sha256::new();
@@ -80,8 +114,7 @@ impl IdGenerator for Cuid2Generator {
 
         // Pad if needed
         while result.len() < self.length {
-            let extra: u8 = rng.random();
-            result.push(BASE36[(extra % 36) as usize] as char);
+            result.push(BASE36[rng.below(36) as usize] as char);
         }
 
         Ok(result)
@@ -156,6 +189,14 @@ impl ParsedCuid2 {
     }
 }
 
+impl std::str::FromStr for ParsedCuid2 {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedCuid2 {
     fn kind(&self) -> IdKind {
         IdKind::Cuid2
@@ -190,6 +231,9 @@ impl ParsedId for ParsedCuid2 {
             timestamp: None,
             timestamp_iso: None,
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: Some("2".to_string()),
             variant: None,
             random_bits: None,
@@ -221,6 +265,7 @@ impl ParsedId for ParsedCuid2 {
         let bytes = self.as_bytes();
         match format {
             EncodingFormat::Canonical => self.canonical(),
+            EncodingFormat::SortKey => encode_sort_key(self),
             EncodingFormat::Hex => encode_hex(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             _ => self.canonical(),
@@ -370,4 +415,12 @@ mod tests {
         let result = bytes_to_base36(&bytes);
         assert!(!result.is_empty());
     }
+
+    #[test]
+    fn test_with_state() {
+        let state = Arc::new(Cuid2State::new());
+        let generator = Cuid2Generator::new().with_state(state.clone());
+        generator.generate_many(3).unwrap();
+        assert_eq!(state.counter.load(Ordering::SeqCst), 3);
+    }
 }
@@ -4,37 +4,81 @@ use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
-use rand::RngExt;
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
 use serde_json::json;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, OnceLock};
 
 /// Base36 alphabet
 const BASE36: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
 
-/// CUID counter
-static CUID_COUNTER: AtomicU32 = AtomicU32::new(0);
+/// Counter mixed into generated CUIDs.
+///
+/// Generators created with [`CuidGenerator::new`] share one process-wide
+/// counter; use [`CuidGenerator::with_state`] to give one its own.
+#[derive(Debug, Default)]
+pub struct CuidState {
+    counter: AtomicU32,
+}
+
+impl CuidState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process-wide state shared by default generators
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<CuidState>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+}
 
 /// CUID v1 generator
-pub struct CuidGenerator;
+pub struct CuidGenerator {
+    state: Arc<CuidState>,
+    timestamp_ms: Option<u64>,
+    rng: IdRng,
+}
 
 impl Default for CuidGenerator {
     fn default() -> Self {
-        Self
+        Self {
+            state: CuidState::shared(),
+            timestamp_ms: None,
+            rng: IdRng::default(),
+        }
     }
 }
 
 impl CuidGenerator {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_state(mut self, state: Arc<CuidState>) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Stamp every CUID with `ms` (Unix milliseconds) instead of the current time
+    pub fn with_timestamp(mut self, ms: u64) -> Self {
+        self.timestamp_ms = Some(ms);
+        self
     }
 }
 
 impl IdGenerator for CuidGenerator {
     fn generate(&self) -> Result<String> {
-        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
-        let counter = CUID_COUNTER.fetch_add(1, Ordering::SeqCst);
-
-        let mut rng = rand::rng();
+        let now_ms = self
+            .timestamp_ms
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64);
+        let counter = self.state.counter.fetch_add(1, Ordering::SeqCst);
 
         // CUID v1: c + base36(timestamp, 8) + base36(counter, 4) + base36(fingerprint, 4) + base36(random, 8)
         let ts_str = pad_base36(now_ms, 8);
@@ -52,7 +96,7 @@ impl IdGenerator for CuidGenerator {
         };
         let fingerprint = pad_base36(pid.wrapping_add(hostname_hash), 4);
 
-        let random_val: u64 = rng.random::<u64>() % 36u64.pow(8);
+        let random_val = self.rng.below(36u64.pow(8));
         let random_str = pad_base36(random_val, 8);
 
         Ok(format!(
@@ -63,7 +107,7 @@ impl IdGenerator for CuidGenerator {
 }
 
 /// Encode u64 as base36 string, left-padded to `width` characters
-fn pad_base36(mut value: u64, width: usize) -> String {
+pub(crate) fn pad_base36(mut value: u64, width: usize) -> String {
     if value == 0 {
         return "0".repeat(width);
     }
@@ -148,6 +192,14 @@ impl ParsedCuid {
     }
 }
 
+impl std::str::FromStr for ParsedCuid {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedCuid {
     fn kind(&self) -> IdKind {
         IdKind::Cuid
@@ -184,6 +236,9 @@ impl ParsedId for ParsedCuid {
             timestamp,
             timestamp_iso: timestamp.as_ref().map(|ts| ts.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: Some("1".to_string()),
             variant: None,
             random_bits: None,
@@ -243,6 +298,7 @@ impl ParsedId for ParsedCuid {
         let bytes = self.as_bytes();
         match format {
             EncodingFormat::Canonical => self.canonical(),
+            EncodingFormat::SortKey => encode_sort_key(self),
             EncodingFormat::Hex => encode_hex(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             _ => self.canonical(),
@@ -391,4 +447,14 @@ mod tests {
         assert_eq!(parsed.fingerprint_str().len(), 4);
         assert_eq!(parsed.random_str().len(), 8);
     }
+
+    #[test]
+    fn test_independent_counter() {
+        let generator = CuidGenerator::new().with_state(Arc::new(CuidState::new()));
+        let first = generator.generate().unwrap();
+        let second = generator.generate().unwrap();
+        // Counter occupies chars 9..13 and starts at zero for a fresh state
+        assert_eq!(&first[9..13], "0000");
+        assert_eq!(&second[9..13], "0001");
+    }
 }
@@ -0,0 +1,540 @@
+//! User-defined ID formats loaded from a schema file (`--schema`).
+//!
+//! A schema describes a Snowflake-style integer ID: total width, field
+//! layout, epoch, timestamp resolution, and the alphabet used to print it.
+//! Once registered, the schema is available as the `custom` ID type.
+
+use crate::core::components::Components;
+use crate::core::encoding::EncodingFormat;
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{
+    IdGenerator, IdKind, InspectionResult, ParsedId, Timestamp, ValidationResult,
+};
+use crate::core::sortkey::encode_sort_key;
+use crate::ids::snowflake_id::{
+    ParsedSnowflake, SnowflakeField, SnowflakeGenerator, SnowflakeLayout, TimestampUnit,
+};
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+static ACTIVE: OnceLock<CustomSchema> = OnceLock::new();
+
+/// Register `schema` as the process-wide `custom` ID type
+pub fn register(schema: CustomSchema) -> Result<&'static CustomSchema> {
+    let name = schema.name;
+    ACTIVE.set(schema).map_err(|_| {
+        IdtError::InvalidArgument(format!(
+            "Cannot load schema '{}': a schema is already loaded",
+            name
+        ))
+    })?;
+    Ok(ACTIVE.get().expect("schema was just registered"))
+}
+
+/// The registered schema, if any
+pub fn active() -> Option<&'static CustomSchema> {
+    ACTIVE.get()
+}
+
+/// The registered schema, or an error pointing at `--schema`
+pub fn require_active() -> Result<&'static CustomSchema> {
+    active().ok_or_else(|| {
+        IdtError::InvalidArgument(
+            "The custom ID type requires a schema file (--schema PATH)".to_string(),
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    bits: u8,
+    #[serde(default)]
+    epoch: u64,
+    #[serde(default = "default_unit")]
+    timestamp_unit: String,
+    #[serde(default = "default_alphabet")]
+    alphabet: String,
+    #[serde(default)]
+    length: Option<usize>,
+    fields: Vec<FieldSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldSpec {
+    name: String,
+    bits: u8,
+}
+
+fn default_unit() -> String {
+    "ms".to_string()
+}
+
+fn default_alphabet() -> String {
+    "decimal".to_string()
+}
+
+/// A validated custom ID format
+#[derive(Debug)]
+pub struct CustomSchema {
+    pub name: &'static str,
+    pub description: String,
+    pub bits: u8,
+    pub layout: SnowflakeLayout,
+    alphabet: Vec<u8>,
+    lookup: [u8; 256],
+    length: Option<usize>,
+}
+
+impl CustomSchema {
+    /// Load a schema from a TOML, YAML, or JSON file (chosen by extension)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            IdtError::InvalidArgument(format!("Cannot read schema {}: {}", path.display(), e))
+        })?;
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        let file: SchemaFile = match extension.as_str() {
+            "yaml" | "yml" => serde_yaml_ng::from_str(&content)?,
+            "json" => serde_json::from_str(&content)?,
+            _ => toml::from_str(&content).map_err(|e| {
+                IdtError::InvalidArgument(format!("Invalid schema {}: {}", path.display(), e))
+            })?,
+        };
+        Self::from_file(file)
+    }
+
+    /// Parse a schema from TOML text
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: SchemaFile = toml::from_str(content)
+            .map_err(|e| IdtError::InvalidArgument(format!("Invalid schema: {}", e)))?;
+        Self::from_file(file)
+    }
+
+    fn from_file(file: SchemaFile) -> Result<Self> {
+        let invalid =
+            |msg: String| IdtError::InvalidArgument(format!("Schema '{}': {}", file.name, msg));
+
+        if file.name.trim().is_empty() {
+            return Err(IdtError::InvalidArgument(
+                "Schema name must not be empty".to_string(),
+            ));
+        }
+        if file.bits == 0 || file.bits > 64 {
+            return Err(invalid(format!("bits must be 1-64, got {}", file.bits)));
+        }
+
+        let total: u32 = file.fields.iter().map(|f| f.bits as u32).sum();
+        if total != file.bits as u32 {
+            return Err(invalid(format!(
+                "field widths add up to {} bits, expected {}",
+                total, file.bits
+            )));
+        }
+        for (i, field) in file.fields.iter().enumerate() {
+            if field.bits == 0 {
+                return Err(invalid(format!("field '{}' has zero width", field.name)));
+            }
+            if file.fields[..i].iter().any(|f| f.name == field.name) {
+                return Err(invalid(format!("duplicate field '{}'", field.name)));
+            }
+        }
+        if !file.fields.iter().any(|f| f.name == "timestamp") {
+            return Err(invalid("a 'timestamp' field is required".to_string()));
+        }
+
+        let timestamp_unit = match file.timestamp_unit.as_str() {
+            "ms" => TimestampUnit::Millis,
+            "10ms" => TimestampUnit::TenMillis,
+            "s" => TimestampUnit::Seconds,
+            other => {
+                return Err(invalid(format!(
+                    "unknown timestamp_unit '{}' (use ms, 10ms, or s)",
+                    other
+                )));
+            }
+        };
+
+        let alphabet = named_alphabet(&file.alphabet)
+            .unwrap_or(&file.alphabet)
+            .as_bytes()
+            .to_vec();
+        if alphabet.len() < 2 || !alphabet.is_ascii() {
+            return Err(invalid(
+                "alphabet must have at least 2 ASCII characters".to_string(),
+            ));
+        }
+        let mut lookup = [crate::core::decode::INVALID; 256];
+        for (i, &c) in alphabet.iter().enumerate() {
+            if lookup[c as usize] != crate::core::decode::INVALID {
+                return Err(invalid(format!(
+                    "alphabet repeats the character '{}'",
+                    c as char
+                )));
+            }
+            lookup[c as usize] = i as u8;
+        }
+
+        if let Some(length) = file.length {
+            let needed = digits_needed(file.bits, alphabet.len());
+            if length < needed {
+                return Err(invalid(format!(
+                    "length {} is too short; {} bits need {} characters",
+                    length, file.bits, needed
+                )));
+            }
+        }
+
+        // Layouts borrow their names for 'static; a schema is loaded once per process
+        let fields: Vec<SnowflakeField> = file
+            .fields
+            .into_iter()
+            .map(|f| SnowflakeField {
+                name: Box::leak(f.name.into_boxed_str()),
+                bits: f.bits,
+            })
+            .collect();
+        let name: &'static str = Box::leak(file.name.into_boxed_str());
+        let layout = SnowflakeLayout {
+            name,
+            epoch: file.epoch,
+            timestamp_unit,
+            fields: Box::leak(fields.into_boxed_slice()),
+        };
+
+        Ok(Self {
+            name,
+            description: file
+                .description
+                .unwrap_or_else(|| format!("Custom ID format '{}'", name)),
+            bits: file.bits,
+            layout,
+            alphabet,
+            lookup,
+            length: file.length,
+        })
+    }
+
+    /// Whether IDs sort by time (timestamp is the most significant field)
+    pub fn is_sortable(&self) -> bool {
+        self.layout
+            .fields
+            .first()
+            .is_some_and(|f| f.name == "timestamp")
+    }
+
+    /// Encode an integer value in the schema's alphabet
+    pub fn encode(&self, value: u64) -> String {
+        let base = self.alphabet.len() as u64;
+        let mut digits = Vec::new();
+        let mut v = value;
+        loop {
+            digits.push(self.alphabet[(v % base) as usize]);
+            v /= base;
+            if v == 0 {
+                break;
+            }
+        }
+        if let Some(length) = self.length {
+            digits.resize(length.max(digits.len()), self.alphabet[0]);
+        }
+        digits.reverse();
+        String::from_utf8(digits).expect("alphabet is ASCII")
+    }
+
+    /// Decode a string in the schema's alphabet to its integer value
+    pub fn decode(&self, input: &str) -> Result<u64> {
+        let parse_error =
+            |msg: String| IdtError::ParseError(format!("Invalid {}: {}", self.name, msg));
+
+        if input.is_empty() {
+            return Err(parse_error("empty input".to_string()));
+        }
+        if let Some(length) = self.length
+            && input.len() != length
+        {
+            return Err(parse_error(format!("expected {} characters", length)));
+        }
+
+        let base = self.alphabet.len() as u64;
+        let mut value: u64 = 0;
+        for c in input.chars() {
+            let digit = if c.is_ascii() {
+                self.lookup[c as usize]
+            } else {
+                crate::core::decode::INVALID
+            };
+            if digit == crate::core::decode::INVALID {
+                return Err(parse_error(format!("unexpected character '{}'", c)));
+            }
+            value = value
+                .checked_mul(base)
+                .and_then(|v| v.checked_add(digit as u64))
+                .ok_or_else(|| parse_error("value exceeds 64 bits".to_string()))?;
+        }
+
+        if self.bits < 64 && value >> self.bits != 0 {
+            return Err(parse_error(format!("value exceeds {} bits", self.bits)));
+        }
+        Ok(value)
+    }
+
+    /// Whether `input` decodes under this schema
+    pub fn matches(&self, input: &str) -> bool {
+        self.decode(input).is_ok()
+    }
+}
+
+fn named_alphabet(name: &str) -> Option<&'static str> {
+    match name {
+        "decimal" => Some("0123456789"),
+        "hex" => Some("0123456789abcdef"),
+        "base32" => Some("0123456789ABCDEFGHJKMNPQRSTVWXYZ"),
+        "base36" => Some("0123456789abcdefghijklmnopqrstuvwxyz"),
+        "base62" => Some("0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"),
+        _ => None,
+    }
+}
+
+/// Characters needed to print any `bits`-bit value in base `base`
+fn digits_needed(bits: u8, base: usize) -> usize {
+    let max = if bits == 64 {
+        u64::MAX
+    } else {
+        (1u64 << bits) - 1
+    };
+    let mut digits = 1;
+    let mut v = max / base as u64;
+    while v > 0 {
+        digits += 1;
+        v /= base as u64;
+    }
+    digits
+}
+
+/// Generator for a custom schema, built on the Snowflake generator
+pub struct CustomGenerator {
+    schema: &'static CustomSchema,
+    generator: SnowflakeGenerator,
+}
+
+impl CustomGenerator {
+    /// `generator` must use `schema.layout`; field values and state carry over
+    pub fn new(schema: &'static CustomSchema, generator: SnowflakeGenerator) -> Result<Self> {
+        if !schema.layout.has_field("sequence") {
+            return Err(IdtError::GenerationError(format!(
+                "Schema '{}' has no 'sequence' field and can only be inspected",
+                schema.name
+            )));
+        }
+        Ok(Self { schema, generator })
+    }
+
+    /// The underlying Snowflake generator (for reading back state)
+    pub fn into_snowflake(self) -> SnowflakeGenerator {
+        self.generator
+    }
+}
+
+impl IdGenerator for CustomGenerator {
+    fn generate(&self) -> Result<String> {
+        Ok(self.schema.encode(self.generator.next_id()?))
+    }
+
+    fn write_to(&self, writer: &mut dyn fmt::Write) -> Result<()> {
+        writer.write_str(&self.generate()?)?;
+        Ok(())
+    }
+}
+
+/// Parsed custom ID
+pub struct ParsedCustom {
+    schema: &'static CustomSchema,
+    inner: ParsedSnowflake,
+    input: String,
+}
+
+impl ParsedCustom {
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with_schema(input, require_active()?)
+    }
+
+    pub fn parse_with_schema(input: &str, schema: &'static CustomSchema) -> Result<Self> {
+        let input_trimmed = input.trim();
+        let id = schema.decode(input_trimmed)?;
+        Ok(Self {
+            schema,
+            inner: ParsedSnowflake::from_id(id, schema.layout.clone()),
+            input: input_trimmed.to_string(),
+        })
+    }
+}
+
+impl std::str::FromStr for ParsedCustom {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl ParsedId for ParsedCustom {
+    fn kind(&self) -> IdKind {
+        IdKind::Custom
+    }
+
+    fn canonical(&self) -> String {
+        self.schema.encode(self.inner.id())
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.inner.as_bytes()
+    }
+
+    fn timestamp(&self) -> Option<Timestamp> {
+        self.inner.timestamp()
+    }
+
+    fn components(&self) -> Option<Components> {
+        self.inner.components()
+    }
+
+    fn inspect(&self) -> InspectionResult {
+        let mut result = self.inner.inspect();
+        result.id_type = self.schema.name.to_string();
+        result.input = self.input.clone();
+        result.canonical = self.canonical();
+        result.variant = Some(self.schema.description.clone());
+        result
+    }
+
+    fn validate(&self) -> ValidationResult {
+        let result = self.inner.validate();
+        if result.valid {
+            ValidationResult::valid(self.schema.name)
+        } else {
+            result
+        }
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        self.inner.as_u128()
+    }
+
+    fn encode(&self, format: EncodingFormat) -> String {
+        match format {
+            EncodingFormat::Canonical => self.canonical(),
+            EncodingFormat::SortKey => encode_sort_key(self),
+            _ => self.inner.encode(format),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACME: &str = r#"
+        name = "acme"
+        bits = 48
+        epoch = 1577836800000
+        alphabet = "base62"
+        length = 9
+
+        [[fields]]
+        name = "timestamp"
+        bits = 34
+
+        [[fields]]
+        name = "region"
+        bits = 4
+
+        [[fields]]
+        name = "sequence"
+        bits = 10
+    "#;
+
+    fn acme() -> &'static CustomSchema {
+        Box::leak(Box::new(CustomSchema::from_toml(ACME).unwrap()))
+    }
+
+    #[test]
+    fn test_load_schema() {
+        let schema = acme();
+        assert_eq!(schema.name, "acme");
+        assert_eq!(schema.layout.field_bits("region"), Some(4));
+        assert!(schema.is_sortable());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let schema = acme();
+        for value in [0u64, 1, 61, 62, (1 << 48) - 1] {
+            let encoded = schema.encode(value);
+            assert_eq!(encoded.len(), 9);
+            assert_eq!(schema.decode(&encoded).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_out_of_range() {
+        let schema = acme();
+        assert!(schema.decode("zzzzzzzzz").is_err());
+        assert!(schema.decode("0000").is_err());
+        assert!(schema.decode("00000000-").is_err());
+    }
+
+    #[test]
+    fn test_generate_and_parse() {
+        let schema = acme();
+        let generator = SnowflakeGenerator::new()
+            .with_layout(schema.layout.clone())
+            .with_field("region", 3);
+        let generator = CustomGenerator::new(schema, generator).unwrap();
+        let id = generator.generate().unwrap();
+
+        let parsed = ParsedCustom::parse_with_schema(&id, schema).unwrap();
+        assert_eq!(parsed.canonical(), id);
+        let inspection = parsed.inspect();
+        assert_eq!(inspection.id_type, "acme");
+        assert_eq!(inspection.components.unwrap()["region"], 3);
+        assert!(parsed.validate().valid);
+    }
+
+    #[test]
+    fn test_invalid_schemas() {
+        let bad_width = ACME.replace("bits = 48", "bits = 50");
+        assert!(CustomSchema::from_toml(&bad_width).is_err());
+
+        let no_timestamp = ACME.replace("\"timestamp\"", "\"time\"");
+        assert!(CustomSchema::from_toml(&no_timestamp).is_err());
+
+        let short = ACME.replace("length = 9", "length = 8");
+        assert!(CustomSchema::from_toml(&short).is_err());
+
+        let repeated = ACME.replace("\"base62\"", "\"0120\"");
+        assert!(CustomSchema::from_toml(&repeated).is_err());
+    }
+
+    #[test]
+    fn test_inspect_only_schema() {
+        let schema = ACME
+            .replace("\"sequence\"", "\"counter\"")
+            .replace("length = 9", "");
+        let schema: &'static CustomSchema =
+            Box::leak(Box::new(CustomSchema::from_toml(&schema).unwrap()));
+        let generator = SnowflakeGenerator::new().with_layout(schema.layout.clone());
+        assert!(CustomGenerator::new(schema, generator).is_err());
+        assert!(ParsedCustom::parse_with_schema("1000000", schema).is_ok());
+    }
+}
@@ -1,11 +1,13 @@
 use crate::core::encoding::{
-    EncodingFormat, encode_base32, encode_base58, encode_base64, encode_base64_url, encode_bits,
-    encode_bytes_spaced, encode_hex, encode_hex_upper,
+    EncodingFormat, encode_base32, encode_base58, encode_base58check, encode_base64,
+    encode_base64_ordered, encode_base64_url, encode_bits, encode_bytes_spaced, encode_hex,
+    encode_hex_upper, encode_java_longs,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment, ValidationResult,
 };
+use crate::core::sortkey::encode_sort_key;
 use crate::utils::check_digit::{parse_digits, strip_formatting, validate_mod10};
 use serde_json::json;
 
@@ -47,6 +49,14 @@ impl ParsedGtin14 {
     }
 }
 
+impl std::str::FromStr for ParsedGtin14 {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedGtin14 {
     fn kind(&self) -> IdKind {
         IdKind::Gtin14
@@ -81,6 +91,9 @@ impl ParsedId for ParsedGtin14 {
             timestamp: None,
             timestamp_iso: None,
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: None,
             random_bits: None,
@@ -136,12 +149,16 @@ impl ParsedId for ParsedGtin14 {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
             EncodingFormat::Int => self.canonical(),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
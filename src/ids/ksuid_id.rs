@@ -1,53 +1,84 @@
+use crate::core::components::{Components, KsuidComponents};
+use crate::core::decode;
 use crate::core::encoding::{
-    EncodingFormat, encode_base32, encode_base58, encode_base64, encode_base64_url, encode_bits,
-    encode_bytes_spaced, encode_hex, encode_hex_upper,
+    EncodingFormat, encode_base32, encode_base58, encode_base58check, encode_base64,
+    encode_base64_ordered, encode_base64_url, encode_bits, encode_bytes_spaced, encode_decimal,
+    encode_hex, encode_hex_upper, encode_java_longs,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
-use rand::RngExt;
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
 use serde_json::json;
 
 /// KSUID epoch offset: 14e8 seconds (2014-05-13T16:53:20Z)
-const KSUID_EPOCH: u64 = 1_400_000_000;
+pub(crate) const KSUID_EPOCH: u64 = 1_400_000_000;
 
 /// Base62 alphabet
 const BASE62: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
 
-/// KSUID generator
-pub struct KsuidGenerator;
+/// Largest Unix time (seconds) a KSUID can hold
+pub const KSUID_MAX_TIMESTAMP: u64 = KSUID_EPOCH + u32::MAX as u64;
 
-impl Default for KsuidGenerator {
-    fn default() -> Self {
-        Self
-    }
+/// KSUID generator
+#[derive(Default)]
+pub struct KsuidGenerator {
+    timestamp_secs: Option<u64>,
+    rng: IdRng,
 }
 
 impl KsuidGenerator {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Stamp every KSUID with `secs` (Unix seconds) instead of the current time
+    pub fn with_timestamp(mut self, secs: u64) -> Self {
+        self.timestamp_secs = Some(secs);
+        self
+    }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = rng;
+        self
     }
 }
 
 impl IdGenerator for KsuidGenerator {
     fn generate(&self) -> Result<String> {
-        let now = chrono::Utc::now().timestamp() as u64;
-        let offset = now.saturating_sub(KSUID_EPOCH);
-
-        let mut bytes = [0u8; 20];
-        bytes[0..4].copy_from_slice(&(offset as u32).to_be_bytes());
+        let mut payload = [0u8; 16];
+        self.rng.fill(&mut payload);
+
+        match self.timestamp_secs {
+            Some(secs) => Ok(ParsedKsuid::from_parts(secs, payload)?.canonical()),
+            None => {
+                let now = chrono::Utc::now().timestamp() as u64;
+                let offset = now.saturating_sub(KSUID_EPOCH);
+
+                let mut bytes = [0u8; 20];
+                bytes[0..4].copy_from_slice(&(offset as u32).to_be_bytes());
+                bytes[4..20].copy_from_slice(&payload);
+                Ok(encode_base62(&bytes))
+            }
+        }
+    }
+}
 
-        let mut rng = rand::rng();
-        rng.fill(&mut bytes[4..20]);
+/// Smallest KSUID for the second `secs` (Unix seconds), for range scans
+pub fn ksuid_min_for_time(secs: u64) -> Result<ParsedKsuid> {
+    ParsedKsuid::from_parts(secs, [0x00; 16])
+}
 
-        Ok(encode_base62(&bytes))
-    }
+/// Largest KSUID for the second `secs` (Unix seconds), for range scans
+pub fn ksuid_max_for_time(secs: u64) -> Result<ParsedKsuid> {
+    ParsedKsuid::from_parts(secs, [0xff; 16])
 }
 
 /// Encode 20 bytes as 27-char base62 string
-fn encode_base62(bytes: &[u8; 20]) -> String {
+pub(crate) fn encode_base62(bytes: &[u8; 20]) -> String {
     // Convert bytes to a big integer (as a Vec<u8> for divmod)
     let mut num = bytes.to_vec();
     let mut result = Vec::with_capacity(27);
@@ -81,28 +112,16 @@ fn decode_base62(s: &str) -> Result<[u8; 20]> {
         ));
     }
 
-    // Convert base62 string to big integer bytes
-    let mut num = vec![0u8; 20];
-
-    for ch in s.chars() {
-        let val = base62_char_value(ch)
-            .ok_or_else(|| IdtError::ParseError(format!("Invalid base62 character: '{}'", ch)))?;
-
-        // Multiply num by 62 and add val
-        let mut carry: u16 = val as u16;
-        for byte in num.iter_mut().rev() {
-            let acc = (*byte as u16) * 62 + carry;
-            *byte = (acc & 0xFF) as u8;
-            carry = acc >> 8;
+    decode::decode_base62(s.as_bytes()).ok_or_else(|| {
+        match decode::find_invalid(s, &decode::BASE62) {
+            Some((offset, _)) => IdtError::invalid_character(s, offset, "base62"),
+            None => IdtError::ParseError("KSUID value out of range".to_string()),
         }
-    }
-
-    let result: [u8; 20] = num
-        .try_into()
-        .map_err(|_| IdtError::ParseError("Failed to decode KSUID bytes".to_string()))?;
-    Ok(result)
+    })
 }
 
+/// Scalar reference for [`decode::BASE62`]
+#[cfg(test)]
 fn base62_char_value(c: char) -> Option<u8> {
     match c {
         '0'..='9' => Some(c as u8 - b'0'),
@@ -128,6 +147,22 @@ impl ParsedKsuid {
         })
     }
 
+    /// Build a KSUID from a Unix timestamp in seconds and a 16-byte payload
+    pub fn from_parts(secs: u64, payload: [u8; 16]) -> Result<Self> {
+        if !(KSUID_EPOCH..=KSUID_MAX_TIMESTAMP).contains(&secs) {
+            return Err(IdtError::InvalidArgument(format!(
+                "KSUID timestamp {} is outside {}..={}",
+                secs, KSUID_EPOCH, KSUID_MAX_TIMESTAMP
+            )));
+        }
+
+        let mut bytes = [0u8; 20];
+        bytes[0..4].copy_from_slice(&((secs - KSUID_EPOCH) as u32).to_be_bytes());
+        bytes[4..20].copy_from_slice(&payload);
+        let input = encode_base62(&bytes);
+        Ok(Self { bytes, input })
+    }
+
     fn timestamp_offset(&self) -> u32 {
         u32::from_be_bytes([self.bytes[0], self.bytes[1], self.bytes[2], self.bytes[3]])
     }
@@ -141,6 +176,14 @@ impl ParsedKsuid {
     }
 }
 
+impl std::str::FromStr for ParsedKsuid {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedKsuid {
     fn kind(&self) -> IdKind {
         IdKind::Ksuid
@@ -158,6 +201,15 @@ impl ParsedId for ParsedKsuid {
         Some(Timestamp::from_secs(self.unix_timestamp_secs()))
     }
 
+    fn components(&self) -> Option<Components> {
+        Some(Components::Ksuid(KsuidComponents {
+            timestamp_secs: self.unix_timestamp_secs(),
+            payload: self.bytes[4..20]
+                .try_into()
+                .expect("KSUID payload is 16 bytes"),
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let timestamp = self.timestamp().expect("KSUID always has a timestamp");
@@ -176,6 +228,9 @@ impl ParsedId for ParsedKsuid {
             timestamp: Some(timestamp),
             timestamp_iso: Some(timestamp.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: None,
             random_bits: Some(128),
@@ -220,18 +275,16 @@ impl ParsedId for ParsedKsuid {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => {
-                let mut val: u128 = 0;
-                for &b in bytes.iter() {
-                    val = (val << 8) | b as u128;
-                }
-                val.to_string()
-            }
+            EncodingFormat::Int => encode_decimal(&bytes),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
@@ -272,6 +325,37 @@ mod tests {
         assert!((now * 1000).abs_diff(ts.millis) < 10_000);
     }
 
+    #[test]
+    fn test_with_timestamp() {
+        let generator = KsuidGenerator::new().with_timestamp(1_700_000_000);
+        let id = generator.generate().unwrap();
+        let parsed = ParsedKsuid::parse(&id).unwrap();
+        assert_eq!(parsed.unix_timestamp_secs(), 1_700_000_000);
+        assert_ne!(id, generator.generate().unwrap());
+
+        let early = KsuidGenerator::new().with_timestamp(KSUID_EPOCH - 1);
+        assert!(early.generate().is_err());
+    }
+
+    #[test]
+    fn test_from_parts() {
+        let min = ksuid_min_for_time(KSUID_EPOCH).unwrap();
+        assert_eq!(min.canonical(), "000000000000000000000000000");
+        let max = ksuid_max_for_time(KSUID_MAX_TIMESTAMP).unwrap();
+        assert_eq!(max.canonical(), "aWgEPTl1tmebfsQzFP4bxwgy80V");
+
+        let secs = 1_700_000_000;
+        let mid = ParsedKsuid::from_parts(secs, [0x42; 16]).unwrap();
+        assert_eq!(mid.payload(), &[0x42; 16]);
+        let (lo, hi) = (
+            ksuid_min_for_time(secs).unwrap().canonical(),
+            ksuid_max_for_time(secs).unwrap().canonical(),
+        );
+        assert!(lo < mid.canonical() && mid.canonical() < hi);
+
+        assert!(ParsedKsuid::from_parts(KSUID_MAX_TIMESTAMP + 1, [0; 16]).is_err());
+    }
+
     #[test]
     fn test_base62_encode_decode() {
         let mut bytes = [0u8; 20];
@@ -390,4 +474,22 @@ mod tests {
         assert_eq!(base62_char_value('z'), Some(61));
         assert_eq!(base62_char_value('!'), None);
     }
+
+    #[test]
+    fn test_base62_table_matches_scalar() {
+        for b in 0..=255u8 {
+            let table = decode::BASE62[b as usize];
+            let expected = base62_char_value(b as char).unwrap_or(decode::INVALID);
+            assert_eq!(table, expected, "byte {:#04x}", b);
+        }
+    }
+
+    #[test]
+    fn test_parse_out_of_range() {
+        assert!(ParsedKsuid::parse("aWgEPTl1tmebfsQzFP4bxwgy80V").is_ok());
+        let err = ParsedKsuid::parse("zzzzzzzzzzzzzzzzzzzzzzzzzzz")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("out of range"));
+    }
 }
@@ -1,98 +1,241 @@
+#[cfg(feature = "product-codes")]
 pub mod asin_id;
+#[cfg(feature = "cuid2")]
 pub mod cuid2_id;
+#[cfg(feature = "cuid")]
 pub mod cuid_id;
+#[cfg(feature = "custom")]
+pub mod custom_id;
+#[cfg(feature = "product-codes")]
 pub mod ean13_id;
+#[cfg(feature = "product-codes")]
 pub mod ean8_id;
+#[cfg(feature = "product-codes")]
 pub mod gtin14_id;
+#[cfg(feature = "product-codes")]
 pub mod isbn10_id;
+#[cfg(feature = "product-codes")]
 pub mod isbn13_id;
+#[cfg(feature = "product-codes")]
 pub mod isin_id;
+#[cfg(feature = "product-codes")]
 pub mod ismn_id;
+#[cfg(feature = "product-codes")]
 pub mod isni_id;
+#[cfg(feature = "product-codes")]
 pub mod issn_id;
+#[cfg(feature = "ksuid")]
 pub mod ksuid_id;
+#[cfg(feature = "nanoid")]
 pub mod nanoid_id;
+#[cfg(feature = "objectid")]
 pub mod objectid_id;
+pub mod options;
+#[cfg(feature = "shortuuid")]
 pub mod shortuuid_id;
+#[cfg(feature = "snowflake")]
 pub mod snowflake_id;
+#[cfg(feature = "tsid")]
 pub mod tsid_id;
+#[cfg(feature = "typeid")]
 pub mod typeid_id;
+#[cfg(feature = "ulid")]
 pub mod ulid_id;
+#[cfg(feature = "product-codes")]
 pub mod upca_id;
+#[cfg(feature = "uuid")]
 pub mod uuid_id;
+#[cfg(feature = "uuid")]
+pub mod well_known;
+#[cfg(feature = "xid")]
 pub mod xid_id;
 
+#[cfg(feature = "product-codes")]
 pub use asin_id::{ParsedAsin, is_asin};
-pub use cuid_id::{CuidGenerator, ParsedCuid, is_cuid};
-pub use cuid2_id::{Cuid2Generator, ParsedCuid2, is_cuid2};
+#[cfg(feature = "cuid")]
+pub use cuid_id::{CuidGenerator, CuidState, ParsedCuid, is_cuid};
+#[cfg(feature = "cuid2")]
+pub use cuid2_id::{Cuid2Generator, Cuid2State, ParsedCuid2, is_cuid2};
+#[cfg(feature = "custom")]
+pub use custom_id::{CustomGenerator, CustomSchema, ParsedCustom};
+#[cfg(feature = "product-codes")]
 pub use ean8_id::{ParsedEan8, is_ean8};
+#[cfg(feature = "product-codes")]
 pub use ean13_id::{ParsedEan13, is_ean13};
+#[cfg(feature = "product-codes")]
 pub use gtin14_id::{ParsedGtin14, is_gtin14};
+#[cfg(feature = "product-codes")]
 pub use isbn10_id::{ParsedIsbn10, is_isbn10};
+#[cfg(feature = "product-codes")]
 pub use isbn13_id::{ParsedIsbn13, is_isbn13};
+#[cfg(feature = "product-codes")]
 pub use isin_id::{ParsedIsin, is_isin};
+#[cfg(feature = "product-codes")]
 pub use ismn_id::{ParsedIsmn, is_ismn};
+#[cfg(feature = "product-codes")]
 pub use isni_id::{ParsedIsni, is_isni};
+#[cfg(feature = "product-codes")]
 pub use issn_id::{ParsedIssn, is_issn};
-pub use ksuid_id::{KsuidGenerator, ParsedKsuid, is_ksuid};
+#[cfg(feature = "ksuid")]
+pub use ksuid_id::{
+    KSUID_MAX_TIMESTAMP, KsuidGenerator, ParsedKsuid, is_ksuid, ksuid_max_for_time,
+    ksuid_min_for_time,
+};
+#[cfg(feature = "nanoid")]
 pub use nanoid_id::{NanoIdGenerator, ParsedNanoId, is_nanoid};
-pub use objectid_id::{ObjectIdGenerator, ParsedObjectId, is_objectid};
+#[cfg(feature = "objectid")]
+pub use objectid_id::{ObjectIdGenerator, ObjectIdState, ParsedObjectId, is_objectid};
+pub use options::GeneratorOptions;
+#[cfg(feature = "shortuuid")]
 pub use shortuuid_id::{ParsedShortUuid, ShortUuidGenerator, is_shortuuid};
+#[cfg(feature = "snowflake")]
 pub use snowflake_id::{
-    DISCORD_EPOCH, INSTAGRAM_EPOCH, ParsedSnowflake, SONYFLAKE_EPOCH, SnowflakeField,
-    SnowflakeGenerator, SnowflakeLayout, TWITTER_EPOCH, TimestampUnit, is_snowflake,
+    ClockPolicy, DISCORD_EPOCH, INSTAGRAM_EPOCH, NodeIdSource, ParsedSnowflake, SONYFLAKE_EPOCH,
+    SequencePolicy, SnowflakeField, SnowflakeGenerator, SnowflakeLayout, SnowflakeSequence,
+    SnowflakeState, TWITTER_EPOCH, TimestampUnit, is_snowflake, sequence_exhaustions,
 };
+#[cfg(feature = "tsid")]
 pub use tsid_id::{ParsedTsid, TsidGenerator, is_tsid};
+#[cfg(feature = "typeid")]
 pub use typeid_id::{ParsedTypeId, TypeIdGenerator, is_typeid};
-pub use ulid_id::{ParsedUlid, UlidGenerator, is_ulid};
+#[cfg(feature = "ulid")]
+pub use ulid_id::{
+    ParsedUlid, ULID_MAX_RANDOM, ULID_MAX_TIMESTAMP_MS, UlidGenerator, UlidState, is_ulid,
+};
+#[cfg(feature = "product-codes")]
 pub use upca_id::{ParsedUpcA, is_upca};
-pub use uuid_id::{ParsedUuid, UuidGenerator, is_uuid};
-pub use xid_id::{ParsedXid, XidGenerator, is_xid};
+#[cfg(feature = "uuid")]
+pub use uuid_id::{
+    LegacyUuidOrder, ParsedUuid, UuidGenerator, UuidStyle, decode_legacy_uuid, is_uuid,
+    upgrade_to_v7,
+};
+#[cfg(feature = "uuid")]
+pub use well_known::{
+    WELL_KNOWN_UUIDS, WellKnownUuid, bluetooth_short, expand_bluetooth_short,
+    format_bluetooth_short, well_known_uuid,
+};
+#[cfg(feature = "xid")]
+pub use xid_id::{ParsedXid, XidGenerator, XidState, is_xid};
 
+use crate::core::detection::DetectionOptions;
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{IdGenerator, IdKind, ParsedId};
+use crate::core::rng::IdRng;
+use crate::core::url::{UrlId, extract_id, is_url};
+use std::time::Instant;
 
 /// Create a generator for the given ID kind
-pub fn create_generator(kind: IdKind) -> Result<Box<dyn IdGenerator>> {
-    match kind {
-        IdKind::Uuid | IdKind::UuidV4 => Ok(Box::new(UuidGenerator::v4())),
-        IdKind::UuidV1 => Ok(Box::new(UuidGenerator::v1())),
-        IdKind::UuidV6 => Ok(Box::new(UuidGenerator::v6())),
-        IdKind::UuidV7 => Ok(Box::new(UuidGenerator::v7())),
-        IdKind::UuidNil => Ok(Box::new(UuidGenerator::nil())),
-        IdKind::UuidMax => Ok(Box::new(UuidGenerator::max())),
-        IdKind::Ulid => Ok(Box::new(UlidGenerator::new())),
-        IdKind::NanoId => Ok(Box::new(NanoIdGenerator::new())),
-        IdKind::Snowflake => Ok(Box::new(SnowflakeGenerator::new())),
-        IdKind::ObjectId => Ok(Box::new(ObjectIdGenerator::new())),
-        IdKind::Ksuid => Ok(Box::new(KsuidGenerator::new())),
-        IdKind::Xid => Ok(Box::new(XidGenerator::new())),
-        IdKind::Tsid => Ok(Box::new(TsidGenerator::new())),
-        IdKind::Cuid => Ok(Box::new(CuidGenerator::new())),
-        IdKind::Cuid2 => Ok(Box::new(Cuid2Generator::new())),
-        IdKind::TypeId => Ok(Box::new(TypeIdGenerator::new(""))),
-        IdKind::ShortUuid => Ok(Box::new(ShortUuidGenerator::new())),
-        _ => Err(IdtError::GenerationError(format!(
-            "Generation not supported for: {}",
-            kind.name()
-        ))),
-    }
+pub fn create_generator(kind: IdKind) -> Result<Box<dyn IdGenerator + Send + Sync>> {
+    create_generator_with(kind, &GeneratorOptions::default())
+}
+
+/// Create a generator for the given ID kind that draws its randomness from `rng`.
+///
+/// Kinds without random bits (nil/max UUIDs, Snowflake, custom) are built as
+/// by [`create_generator`].
+pub fn create_generator_with_rng(
+    kind: IdKind,
+    rng: IdRng,
+) -> Result<Box<dyn IdGenerator + Send + Sync>> {
+    create_generator_with(kind, &GeneratorOptions::new().with_rng(rng))
+}
+
+/// Create a generator for the given ID kind configured by `options`.
+///
+/// Fails if `options` sets anything `kind` has no use for, such as a prefix
+/// for a ULID, rather than silently dropping it.
+pub fn create_generator_with(
+    kind: IdKind,
+    options: &GeneratorOptions,
+) -> Result<Box<dyn IdGenerator + Send + Sync>> {
+    options.build(kind)
 }
 
 /// Parse an ID string into a ParsedId, optionally with a type hint
 pub fn parse_id(input: &str, type_hint: Option<IdKind>) -> Result<Box<dyn ParsedId>> {
+    parse_id_with(input, type_hint, &DetectionOptions::default())
+}
+
+/// Parse an ID, constraining auto-detection with `options`
+pub fn parse_id_with(
+    input: &str,
+    type_hint: Option<IdKind>,
+    options: &DetectionOptions,
+) -> Result<Box<dyn ParsedId>> {
     let input = input.trim();
+    let start = tracing::enabled!(tracing::Level::TRACE).then(Instant::now);
+    let result = detect_and_parse(input, type_hint, options);
+    if let Some(start) = start {
+        tracing::trace!(
+            input,
+            elapsed_us = start.elapsed().as_secs_f64() * 1e6,
+            ok = result.is_ok(),
+            "parsed"
+        );
+    }
+    result
+}
+
+/// Parse an ID, or the ID inside a URL such as a tweet, a Discord message
+/// link, or a URL whose path holds an ID
+pub fn parse_id_or_url(
+    input: &str,
+    type_hint: Option<IdKind>,
+    options: &DetectionOptions,
+) -> Result<Box<dyn ParsedId>> {
+    match extract_id(input) {
+        Some(url) => parse_url_id(&url, type_hint, options),
+        None if is_url(input) => Err(IdtError::ParseError(format!(
+            "No ID found in URL '{}'",
+            input.trim()
+        ))),
+        None => parse_id_with(input, type_hint, options),
+    }
+}
+
+/// Parse the ID found in a URL. The kind and Snowflake preset the site
+/// implies apply unless `type_hint` names another kind.
+pub fn parse_url_id(
+    url: &UrlId<'_>,
+    type_hint: Option<IdKind>,
+    options: &DetectionOptions,
+) -> Result<Box<dyn ParsedId>> {
+    tracing::debug!(id = url.id, source = url.source, "extracted ID from URL");
+    let kind = type_hint.or(url.kind);
+    match url.preset {
+        #[cfg(feature = "snowflake")]
+        Some(preset) if kind == Some(IdKind::Snowflake) => {
+            let layout = SnowflakeLayout::resolve(Some(preset), None)?;
+            Ok(Box::new(ParsedSnowflake::parse_with_layout(
+                url.id, layout,
+            )?))
+        }
+        _ => parse_id_with(url.id, kind, options),
+    }
+}
 
+fn detect_and_parse(
+    input: &str,
+    type_hint: Option<IdKind>,
+    options: &DetectionOptions,
+) -> Result<Box<dyn ParsedId>> {
     if let Some(kind) = type_hint {
+        tracing::debug!(input, kind = kind.name(), "parsing with type hint");
         return parse_as_type(input, kind);
     }
 
     // Auto-detect
-    let detections = crate::core::detect_id_type(input)?;
+    let detections = crate::core::detect_id_type_with(input, options)?;
 
     for detection in detections {
-        if let Ok(parsed) = parse_as_type(input, detection.kind) {
-            return Ok(parsed);
+        match parse_as_type(input, detection.kind) {
+            Ok(parsed) => {
+                tracing::debug!(input, kind = detection.kind.name(), "chose");
+                return Ok(parsed);
+            }
+            Err(e) => {
+                tracing::debug!(input, kind = detection.kind.name(), error = %e, "candidate failed to parse");
+            }
         }
     }
 
@@ -100,8 +243,11 @@ pub fn parse_id(input: &str, type_hint: Option<IdKind>) -> Result<Box<dyn Parsed
 }
 
 /// Parse input as a specific ID type
+#[cfg_attr(not(feature = "cli"), allow(unused_variables))]
 fn parse_as_type(input: &str, kind: IdKind) -> Result<Box<dyn ParsedId>> {
     match kind {
+        kind if !kind.is_enabled() => Err(not_compiled(kind)),
+        #[cfg(feature = "uuid")]
         IdKind::Uuid
         | IdKind::UuidV1
         | IdKind::UuidV3
@@ -110,39 +256,73 @@ fn parse_as_type(input: &str, kind: IdKind) -> Result<Box<dyn ParsedId>> {
         | IdKind::UuidV6
         | IdKind::UuidV7
         | IdKind::UuidNil
-        | IdKind::UuidMax => Ok(Box::new(ParsedUuid::parse(input)?)),
+        | IdKind::UuidMax => Ok(Box::new(ParsedUuid::parse_kind(input, kind)?)),
+        #[cfg(feature = "ulid")]
         IdKind::Ulid => Ok(Box::new(ParsedUlid::parse(input)?)),
+        #[cfg(feature = "nanoid")]
         IdKind::NanoId => Ok(Box::new(ParsedNanoId::parse(input)?)),
+        #[cfg(feature = "snowflake")]
         IdKind::Snowflake => Ok(Box::new(ParsedSnowflake::parse(input)?)),
+        #[cfg(feature = "objectid")]
         IdKind::ObjectId => Ok(Box::new(ParsedObjectId::parse(input)?)),
+        #[cfg(feature = "ksuid")]
         IdKind::Ksuid => Ok(Box::new(ParsedKsuid::parse(input)?)),
+        #[cfg(feature = "xid")]
         IdKind::Xid => Ok(Box::new(ParsedXid::parse(input)?)),
+        #[cfg(feature = "tsid")]
         IdKind::Tsid => Ok(Box::new(ParsedTsid::parse(input)?)),
+        #[cfg(feature = "cuid")]
         IdKind::Cuid => Ok(Box::new(ParsedCuid::parse(input)?)),
+        #[cfg(feature = "cuid2")]
         IdKind::Cuid2 => Ok(Box::new(ParsedCuid2::parse(input)?)),
+        #[cfg(feature = "typeid")]
         IdKind::TypeId => Ok(Box::new(ParsedTypeId::parse(input)?)),
+        #[cfg(feature = "shortuuid")]
         IdKind::ShortUuid => Ok(Box::new(ParsedShortUuid::parse(input)?)),
+        #[cfg(feature = "custom")]
+        IdKind::Custom => Ok(Box::new(ParsedCustom::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Ean13 => Ok(Box::new(ParsedEan13::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Isbn13 => Ok(Box::new(ParsedIsbn13::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Isbn10 => Ok(Box::new(ParsedIsbn10::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Isin => Ok(Box::new(ParsedIsin::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Ean8 => Ok(Box::new(ParsedEan8::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::UpcA => Ok(Box::new(ParsedUpcA::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Issn => Ok(Box::new(ParsedIssn::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Ismn => Ok(Box::new(ParsedIsmn::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Isni => Ok(Box::new(ParsedIsni::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Gtin14 => Ok(Box::new(ParsedGtin14::parse(input)?)),
+        #[cfg(feature = "product-codes")]
         IdKind::Asin => Ok(Box::new(ParsedAsin::parse(input)?)),
+        #[allow(unreachable_patterns)]
+        _ => Err(not_compiled(kind)),
     }
 }
 
+/// Error for a kind whose family feature was left out of the build
+fn not_compiled(kind: IdKind) -> IdtError {
+    IdtError::InvalidArgument(format!(
+        "{} support is not compiled in; enable its cargo feature",
+        kind.name()
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_create_generator_all_generatable() {
-        for kind in IdKind::generatable() {
+        for kind in IdKind::generatable().iter().filter(|k| k.is_enabled()) {
             let generator = create_generator(*kind);
             assert!(generator.is_ok(), "create_generator failed for {:?}", kind);
             let id = generator.unwrap().generate();
@@ -150,6 +330,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_generator_with_seeded_rng() {
+        for kind in [IdKind::UuidV4, IdKind::NanoId, IdKind::ShortUuid] {
+            if !kind.is_enabled() {
+                continue;
+            }
+            let a = create_generator_with_rng(kind, IdRng::seeded(42)).unwrap();
+            let b = create_generator_with_rng(kind, IdRng::seeded(42)).unwrap();
+            assert_eq!(
+                a.generate_many(3).unwrap(),
+                b.generate_many(3).unwrap(),
+                "{:?} is not reproducible",
+                kind
+            );
+        }
+    }
+
     #[test]
     fn test_create_generator_unsupported() {
         assert!(create_generator(IdKind::UuidV3).is_err());
@@ -157,18 +354,48 @@ mod tests {
     }
 
     #[test]
+    #[cfg(all(feature = "snowflake", feature = "uuid"))]
+    fn test_parse_id_or_url() {
+        let options = DetectionOptions::default();
+        let tweet = "https://x.com/jack/status/1234567890123456789";
+        let parsed = parse_id_or_url(tweet, None, &options).unwrap();
+        let twitter = ParsedSnowflake::parse_twitter("1234567890123456789").unwrap();
+        assert_eq!(
+            parsed.timestamp().unwrap().millis,
+            twitter.timestamp().unwrap().millis
+        );
+
+        let parsed = parse_id_or_url(
+            "https://example.com/users/550e8400-e29b-41d4-a716-446655440000",
+            None,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(parsed.kind(), IdKind::UuidV4);
+
+        let err = parse_id_or_url("https://example.com/about", None, &options)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("No ID found in URL"));
+        assert!(parse_id_or_url("1234567890123456789", None, &options).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
     fn test_parse_id_uuid() {
         let parsed = parse_id("550e8400-e29b-41d4-a716-446655440000", Some(IdKind::Uuid));
         assert!(parsed.is_ok());
     }
 
     #[test]
+    #[cfg(feature = "ulid")]
     fn test_parse_id_ulid() {
         let parsed = parse_id("01ARZ3NDEKTSV4RRFFQ69G5FAV", Some(IdKind::Ulid));
         assert!(parsed.is_ok());
     }
 
     #[test]
+    #[cfg(feature = "uuid")]
     fn test_parse_id_auto_detect_uuid() {
         let parsed = parse_id("550e8400-e29b-41d4-a716-446655440000", None);
         assert!(parsed.is_ok());
@@ -181,12 +408,14 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "snowflake")]
     fn test_parse_id_snowflake() {
         let parsed = parse_id("1234567890123456789", Some(IdKind::Snowflake));
         assert!(parsed.is_ok());
     }
 
     #[test]
+    #[cfg(feature = "cli")]
     fn test_parse_id_with_hint_each_type() {
         let uuid = create_generator(IdKind::UuidV4)
             .unwrap()
@@ -224,4 +453,42 @@ mod tests {
             .unwrap();
         assert!(parse_id(&typeid, Some(IdKind::TypeId)).is_ok());
     }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_numeric_accessors() {
+        let uuid = parse_id("550e8400-e29b-41d4-a716-446655440000", None).unwrap();
+        assert_eq!(uuid.as_u128(), Some(0x550e8400_e29b_41d4_a716_446655440000));
+        assert_eq!(uuid.as_u64(), None);
+
+        let oid = parse_id("507f1f77bcf86cd799439011", Some(IdKind::ObjectId)).unwrap();
+        assert_eq!(oid.as_u128(), Some(0x507f1f77bcf86cd799439011));
+        assert_eq!(oid.as_u64(), None);
+
+        let snowflake = parse_id("1234567890123456789", Some(IdKind::Snowflake)).unwrap();
+        assert_eq!(snowflake.as_u64(), Some(1234567890123456789));
+        assert_eq!(snowflake.as_u128(), Some(1234567890123456789));
+
+        let ksuid = parse_id("0ujtsYcgvSTl8PAuAdqWYSMnLOv", Some(IdKind::Ksuid)).unwrap();
+        assert_eq!(ksuid.as_u128(), None);
+        assert_eq!(ksuid.as_u64(), None);
+
+        let nanoid = parse_id("V1StGXR8_Z5jdHi6B-myT", Some(IdKind::NanoId)).unwrap();
+        assert_eq!(nanoid.as_u128(), None);
+    }
+
+    #[test]
+    fn test_int_encoding_matches_as_u128() {
+        for kind in [IdKind::UuidV7, IdKind::Ulid, IdKind::Xid, IdKind::Tsid] {
+            if !kind.is_enabled() {
+                continue;
+            }
+            let id = create_generator(kind).unwrap().generate().unwrap();
+            let parsed = parse_id(&id, Some(kind)).unwrap();
+            assert_eq!(
+                parsed.encode(crate::core::EncodingFormat::Int),
+                parsed.as_u128().unwrap().to_string()
+            );
+        }
+    }
 }
@@ -1,8 +1,10 @@
 use crate::core::encoding::{EncodingFormat, encode_base64, encode_hex};
-use crate::core::error::Result;
+use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, Timestamp, ValidationResult,
 };
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
 use serde_json::json;
 
 /// Default NanoID alphabet (URL-safe)
@@ -12,10 +14,14 @@ pub const DEFAULT_ALPHABET: &str =
 /// Default NanoID length
 pub const DEFAULT_LENGTH: usize = 21;
 
+/// Entropy below which a custom alphabet and length are considered weak
+pub const MIN_ENTROPY_BITS: f64 = 64.0;
+
 /// NanoID generator with configurable alphabet and length
 pub struct NanoIdGenerator {
     pub alphabet: String,
     pub length: usize,
+    pub rng: IdRng,
 }
 
 impl Default for NanoIdGenerator {
@@ -23,6 +29,7 @@ impl Default for NanoIdGenerator {
         Self {
             alphabet: DEFAULT_ALPHABET.to_string(),
             length: DEFAULT_LENGTH,
+            rng: IdRng::default(),
         }
     }
 }
@@ -41,16 +48,98 @@ impl NanoIdGenerator {
         self.alphabet = alphabet.to_string();
         self
     }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Check that the alphabet has at least two characters, none repeated
+    /// and all printable. Whitespace is rejected too, since parsing trims it.
+    pub fn validate(&self) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for c in self.alphabet.chars() {
+            if c.is_control() || c.is_whitespace() {
+                return Err(IdtError::InvalidArgument(format!(
+                    "NanoID alphabet contains a non-printable character {:?}",
+                    c
+                )));
+            }
+            if !seen.insert(c) {
+                return Err(IdtError::InvalidArgument(format!(
+                    "NanoID alphabet contains '{}' more than once",
+                    c
+                )));
+            }
+        }
+        if seen.len() < 2 {
+            return Err(IdtError::InvalidArgument(
+                "NanoID alphabet needs at least 2 characters".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Random bits per ID: `length * log2(alphabet size)`
+    pub fn entropy_bits(&self) -> f64 {
+        let size = self.alphabet.chars().count();
+        if size < 2 {
+            return 0.0;
+        }
+        self.length as f64 * (size as f64).log2()
+    }
+
+    /// How many IDs can be generated before a collision has probability
+    /// `p`, by the birthday approximation `sqrt(2 * N * ln(1 / (1 - p)))`
+    pub fn ids_until_collision(&self, p: f64) -> f64 {
+        let space = self.entropy_bits().exp2();
+        (2.0 * space * (1.0 / (1.0 - p)).ln()).sqrt()
+    }
+
+    /// A warning for configurations under [`MIN_ENTROPY_BITS`], with the
+    /// number of IDs at which a collision becomes 1% likely
+    pub fn entropy_warning(&self) -> Option<String> {
+        let bits = self.entropy_bits();
+        if bits >= MIN_ENTROPY_BITS {
+            return None;
+        }
+        Some(format!(
+            "NanoID with {} characters from a {}-character alphabet has {:.1} bits of entropy \
+             (below {}); a collision is 1% likely after about {} IDs",
+            self.length,
+            self.alphabet.chars().count(),
+            bits,
+            MIN_ENTROPY_BITS,
+            approx_count(self.ids_until_collision(0.01))
+        ))
+    }
+}
+
+/// A count rounded to two significant figures, e.g. `4,300` or `1.2e12`
+fn approx_count(n: f64) -> String {
+    if n >= 1e9 {
+        return format!("{:.1e}", n);
+    }
+    let n = n.max(1.0);
+    let scale = 10f64.powi((n.log10().floor() as i32 - 1).max(0));
+    let rounded = ((n / scale).round() * scale) as u64;
+    let digits = rounded.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
 }
 
 impl IdGenerator for NanoIdGenerator {
     fn generate(&self) -> Result<String> {
         let alphabet: Vec<char> = self.alphabet.chars().collect();
-        Ok(nanoid::format(
-            nanoid::rngs::default,
-            &alphabet,
-            self.length,
-        ))
+        Ok((0..self.length)
+            .map(|_| alphabet[self.rng.below(alphabet.len() as u64) as usize])
+            .collect())
     }
 }
 
@@ -67,9 +156,7 @@ impl ParsedNanoId {
         // NanoID validation is lenient since it can have custom alphabets
         // We just check that it's not empty
         if input_trimmed.is_empty() {
-            return Err(crate::core::error::IdtError::ParseError(
-                "Empty NanoID".to_string(),
-            ));
+            return Err(IdtError::ParseError("Empty NanoID".to_string()));
         }
 
         Ok(Self {
@@ -89,6 +176,14 @@ impl ParsedNanoId {
     }
 }
 
+impl std::str::FromStr for ParsedNanoId {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedNanoId {
     fn kind(&self) -> IdKind {
         IdKind::NanoId
@@ -124,6 +219,9 @@ impl ParsedId for ParsedNanoId {
             timestamp: None,
             timestamp_iso: None,
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: None,
             random_bits: Some(entropy_bits),
@@ -151,6 +249,7 @@ impl ParsedId for ParsedNanoId {
         let bytes = self.as_bytes();
         match format {
             EncodingFormat::Canonical => self.canonical(),
+            EncodingFormat::SortKey => encode_sort_key(self),
             EncodingFormat::Hex => encode_hex(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             _ => self.canonical(), // Most encodings don't make sense for NanoID
@@ -191,6 +290,59 @@ mod tests {
         assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
+    #[test]
+    fn test_validate_alphabet() {
+        assert!(NanoIdGenerator::new().validate().is_ok());
+        assert!(
+            NanoIdGenerator::new()
+                .with_alphabet("01")
+                .validate()
+                .is_ok()
+        );
+
+        let err = |alphabet: &str| {
+            NanoIdGenerator::new()
+                .with_alphabet(alphabet)
+                .validate()
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(err("abca").contains("'a' more than once"));
+        assert!(err("ab c").contains("non-printable"));
+        assert!(err("ab\u{7}").contains("non-printable"));
+        assert!(err("a").contains("at least 2"));
+        assert!(err("").contains("at least 2"));
+    }
+
+    #[test]
+    fn test_entropy() {
+        let default = NanoIdGenerator::new();
+        assert!((default.entropy_bits() - 126.0).abs() < 1e-9);
+        assert!(default.entropy_warning().is_none());
+
+        let hex = NanoIdGenerator::new()
+            .with_alphabet("0123456789abcdef")
+            .with_length(16);
+        assert!((hex.entropy_bits() - 64.0).abs() < 1e-9);
+        assert!(hex.entropy_warning().is_none());
+
+        // 16^8 = 2^32 IDs: 1% collision chance after about 9,300
+        let short = hex.with_length(8);
+        let n = short.ids_until_collision(0.01);
+        assert!((9_200.0..9_400.0).contains(&n), "{}", n);
+        let warning = short.entropy_warning().unwrap();
+        assert!(warning.contains("32.0 bits"), "{}", warning);
+        assert!(warning.contains("about 9,300 IDs"), "{}", warning);
+    }
+
+    #[test]
+    fn test_approx_count() {
+        assert_eq!(approx_count(0.4), "1");
+        assert_eq!(approx_count(87.0), "87");
+        assert_eq!(approx_count(9_268.0), "9,300");
+        assert_eq!(approx_count(6.1e9), "6.1e9");
+    }
+
     #[test]
     fn test_is_default_format() {
         assert!(ParsedNanoId::is_default_format("V1StGXR8_Z5jdHi6B-myT"));
@@ -1,61 +1,112 @@
+use crate::core::components::{Components, ObjectIdComponents};
+use crate::core::decode;
 use crate::core::encoding::{
-    EncodingFormat, encode_base32, encode_base58, encode_base64, encode_base64_url, encode_bits,
-    encode_bytes_spaced, encode_hex, encode_hex_upper,
+    EncodingFormat, bytes_to_u128, encode_base32, encode_base58, encode_base58check, encode_base64,
+    encode_base64_ordered, encode_base64_url, encode_bits, encode_bytes_spaced, encode_hex,
+    encode_hex_upper, encode_java_longs,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
-use rand::RngExt;
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
 use serde_json::json;
-use std::sync::OnceLock;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Random value and counter mixed into generated ObjectIds.
+///
+/// Generators created with [`ObjectIdGenerator::new`] share one process-wide
+/// state, matching the MongoDB driver. Give a generator its own state with
+/// [`ObjectIdGenerator::with_state`] for isolation (e.g. in tests).
+#[derive(Debug)]
+pub struct ObjectIdState {
+    /// 5-byte random value, unique per state
+    process_random: [u8; 5],
+    /// 3-byte counter, starting from a random value
+    counter: AtomicU32,
+}
 
-/// 5-byte random value, unique per process
-static PROCESS_RANDOM: OnceLock<[u8; 5]> = OnceLock::new();
+impl Default for ObjectIdState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-/// 3-byte counter, starting from a random value
-static COUNTER: AtomicU32 = AtomicU32::new(0);
-static COUNTER_INIT: OnceLock<()> = OnceLock::new();
+impl ObjectIdState {
+    pub fn new() -> Self {
+        Self::from_rng(&IdRng::default())
+    }
 
-fn process_random() -> &'static [u8; 5] {
-    PROCESS_RANDOM.get_or_init(|| {
-        let mut rng = rand::rng();
-        let mut buf = [0u8; 5];
-        rng.fill(&mut buf);
-        buf
-    })
-}
+    /// State with its random value and counter start drawn from `rng`
+    pub fn from_rng(rng: &IdRng) -> Self {
+        let mut process_random = [0u8; 5];
+        rng.fill(&mut process_random);
+        Self {
+            process_random,
+            counter: AtomicU32::new(rng.u64() as u32 & 0xFF_FFFF),
+        }
+    }
+
+    /// Process-wide state shared by default generators
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<ObjectIdState>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Self::new())).clone()
+    }
 
-fn next_counter() -> u32 {
-    COUNTER_INIT.get_or_init(|| {
-        let mut rng = rand::rng();
-        COUNTER.store(rng.random::<u32>() & 0xFF_FFFF, Ordering::SeqCst);
-    });
-    COUNTER.fetch_add(1, Ordering::SeqCst) & 0xFF_FFFF
+    fn next_counter(&self) -> u32 {
+        self.counter.fetch_add(1, Ordering::SeqCst) & 0xFF_FFFF
+    }
 }
 
 /// MongoDB ObjectId generator
-pub struct ObjectIdGenerator;
+pub struct ObjectIdGenerator {
+    state: Arc<ObjectIdState>,
+    timestamp_secs: Option<u32>,
+}
 
 impl Default for ObjectIdGenerator {
     fn default() -> Self {
-        Self
+        Self {
+            state: ObjectIdState::shared(),
+            timestamp_secs: None,
+        }
     }
 }
 
 impl ObjectIdGenerator {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_state(mut self, state: Arc<ObjectIdState>) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Use a fresh state drawn from `rng` instead of the process-wide one
+    pub fn with_rng(self, rng: IdRng) -> Self {
+        self.with_state(Arc::new(ObjectIdState::from_rng(&rng)))
+    }
+
+    /// Stamp every ObjectId with `secs` (Unix seconds) instead of the current time
+    pub fn with_timestamp(mut self, secs: u64) -> Result<Self> {
+        self.timestamp_secs = Some(u32::try_from(secs).map_err(|_| {
+            IdtError::InvalidArgument(format!("ObjectId timestamp {} exceeds 32 bits", secs))
+        })?);
+        Ok(self)
     }
 }
 
 impl IdGenerator for ObjectIdGenerator {
     fn generate(&self) -> Result<String> {
-        let now = chrono::Utc::now().timestamp() as u32;
-        let random = process_random();
-        let counter = next_counter();
+        let now = self
+            .timestamp_secs
+            .unwrap_or_else(|| chrono::Utc::now().timestamp() as u32);
+        let random = &self.state.process_random;
+        let counter = self.state.next_counter();
 
         let mut bytes = [0u8; 12];
         bytes[0..4].copy_from_slice(&now.to_be_bytes());
@@ -74,26 +125,37 @@ pub struct ParsedObjectId {
     input: String,
 }
 
+/// Strip a mongosh `ObjectId("...")` or Extended JSON `{"$oid": "..."}`
+/// wrapper. Returns `None` if `input` has neither wrapper.
+pub fn strip_objectid_wrapper(input: &str) -> Option<&str> {
+    let input = input.trim();
+    let inner = if let Some(rest) = input.strip_prefix("ObjectId(") {
+        rest.strip_suffix(')')?
+    } else {
+        let body = input.strip_prefix('{')?.strip_suffix('}')?.trim();
+        body.strip_prefix("\"$oid\"")?
+            .trim_start()
+            .strip_prefix(':')?
+    };
+    let inner = inner.trim();
+    inner
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+}
+
 impl ParsedObjectId {
     pub fn parse(input: &str) -> Result<Self> {
         let input_trimmed = input.trim();
-        if input_trimmed.len() != 24 {
+        let hex = strip_objectid_wrapper(input_trimmed).unwrap_or(input_trimmed);
+        if hex.len() != 24 {
             return Err(IdtError::ParseError(
                 "ObjectId must be 24 hex characters".to_string(),
             ));
         }
-        if !input_trimmed.chars().all(|c| c.is_ascii_hexdigit()) {
-            return Err(IdtError::ParseError(
-                "ObjectId must contain only hex characters".to_string(),
-            ));
-        }
-
-        let decoded = hex::decode(input_trimmed.to_lowercase())
-            .map_err(|e| IdtError::ParseError(format!("Invalid ObjectId hex: {}", e)))?;
-
-        let bytes: [u8; 12] = decoded
-            .try_into()
-            .map_err(|_| IdtError::ParseError("ObjectId must be 12 bytes".to_string()))?;
+        let bytes = decode::decode_hex::<12>(hex.as_bytes()).ok_or_else(|| {
+            IdtError::ParseError("ObjectId must contain only hex characters".to_string())
+        })?;
 
         Ok(Self {
             bytes,
@@ -114,6 +176,14 @@ impl ParsedObjectId {
     }
 }
 
+impl std::str::FromStr for ParsedObjectId {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedObjectId {
     fn kind(&self) -> IdKind {
         IdKind::ObjectId
@@ -131,6 +201,16 @@ impl ParsedId for ParsedObjectId {
         Some(Timestamp::from_secs(self.timestamp_secs() as u64))
     }
 
+    fn components(&self) -> Option<Components> {
+        Some(Components::ObjectId(ObjectIdComponents {
+            timestamp_secs: self.timestamp_secs(),
+            random: self.bytes[4..9]
+                .try_into()
+                .expect("ObjectId random is 5 bytes"),
+            counter: self.counter(),
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let timestamp = self.timestamp().expect("ObjectID always has a timestamp");
@@ -149,6 +229,9 @@ impl ParsedId for ParsedObjectId {
             timestamp: Some(timestamp),
             timestamp_iso: Some(timestamp.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: None,
             random_bits: Some(40),
@@ -196,6 +279,10 @@ impl ParsedId for ParsedObjectId {
         }
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        bytes_to_u128(&self.bytes)
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
@@ -205,19 +292,16 @@ impl ParsedId for ParsedObjectId {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => {
-                // 96-bit value
-                let mut val: u128 = 0;
-                for &b in &bytes {
-                    val = (val << 8) | b as u128;
-                }
-                val.to_string()
-            }
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
@@ -239,6 +323,23 @@ mod tests {
         assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
+    #[test]
+    fn test_parse_wrapped() {
+        for input in [
+            r#"ObjectId("507f1f77bcf86cd799439011")"#,
+            "ObjectId('507f1f77bcf86cd799439011')",
+            r#"{"$oid": "507f1f77bcf86cd799439011"}"#,
+            r#"{ "$oid" : "507F1F77BCF86CD799439011" }"#,
+            r#"{"$oid":"507f1f77bcf86cd799439011"}"#,
+        ] {
+            let parsed = ParsedObjectId::parse(input).unwrap();
+            assert_eq!(parsed.canonical(), "507f1f77bcf86cd799439011", "{}", input);
+        }
+        assert!(ParsedObjectId::parse(r#"ObjectId("507f1f77bcf86cd799439011""#).is_err());
+        assert!(ParsedObjectId::parse(r#"ObjectId(507f1f77bcf86cd799439011)"#).is_err());
+        assert!(ParsedObjectId::parse(r#"{"$id": "507f1f77bcf86cd799439011"}"#).is_err());
+    }
+
     #[test]
     fn test_parse_known() {
         let parsed = ParsedObjectId::parse("507f1f77bcf86cd799439011").unwrap();
@@ -344,4 +445,18 @@ mod tests {
         assert_eq!(parsed.random_bytes().len(), 5);
         let _ = parsed.counter(); // verify no panic
     }
+
+    #[test]
+    fn test_independent_state() {
+        let shared_a = ObjectIdGenerator::new().generate().unwrap();
+        let shared_b = ObjectIdGenerator::new().generate().unwrap();
+        // Default generators share the per-process random value
+        assert_eq!(shared_a[8..18], shared_b[8..18]);
+
+        let isolated = ObjectIdGenerator::new()
+            .with_state(Arc::new(ObjectIdState::new()))
+            .generate()
+            .unwrap();
+        assert_ne!(shared_a[8..18], isolated[8..18]);
+    }
 }
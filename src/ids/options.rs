@@ -0,0 +1,456 @@
+//! Settings for [`create_generator_with`](super::create_generator_with), so
+//! any generator can be configured without naming its concrete type.
+//!
+//! ```rust
+//! use idt::IdKind;
+//! use idt::ids::{GeneratorOptions, create_generator_with};
+//!
+//! let options = GeneratorOptions::new().with_prefix("user");
+//! let id = create_generator_with(IdKind::TypeId, &options)?.generate()?;
+//! assert!(id.starts_with("user_"));
+//! # Ok::<(), idt::IdtError>(())
+//! ```
+
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdGenerator, IdKind};
+use crate::core::rng::IdRng;
+#[cfg(feature = "snowflake")]
+use crate::ids::{ClockPolicy, SequencePolicy, SnowflakeGenerator, SnowflakeLayout};
+#[cfg(feature = "uuid")]
+use crate::ids::{UuidGenerator, UuidStyle};
+
+/// How to build a generator. Unset options keep the generator's defaults.
+///
+/// The random source is ignored by kinds without random bits; any other
+/// option a kind has no use for is an error when the generator is built.
+#[derive(Clone, Default)]
+pub struct GeneratorOptions {
+    rng: Option<IdRng>,
+    timestamp_ms: Option<u64>,
+    prefix: Option<String>,
+    length: Option<usize>,
+    alphabet: Option<String>,
+    #[cfg(feature = "uuid")]
+    uuid_style: Option<UuidStyle>,
+    #[cfg(feature = "snowflake")]
+    layout: Option<SnowflakeLayout>,
+    fields: Vec<(String, u64)>,
+    #[cfg(feature = "snowflake")]
+    clock_policy: ClockPolicy,
+    #[cfg(feature = "snowflake")]
+    sequence_policy: SequencePolicy,
+}
+
+impl GeneratorOptions {
+    /// Kinds that embed a creation time [`with_timestamp`](Self::with_timestamp) can set
+    pub const TIMESTAMPED: &'static [IdKind] = &[
+        IdKind::UuidV1,
+        IdKind::UuidV6,
+        IdKind::UuidV7,
+        IdKind::Ulid,
+        IdKind::Ksuid,
+        IdKind::ObjectId,
+        IdKind::Xid,
+        IdKind::Tsid,
+        IdKind::Cuid,
+        IdKind::TypeId,
+        IdKind::Snowflake,
+        IdKind::Custom,
+    ];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw random bits from `rng` instead of the default ChaCha source
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = Some(rng);
+        self
+    }
+
+    /// Embed `ms` (Unix milliseconds) instead of the current time. Kinds
+    /// that store seconds drop the milliseconds.
+    pub fn with_timestamp(mut self, ms: u64) -> Self {
+        self.timestamp_ms = Some(ms);
+        self
+    }
+
+    /// TypeID type prefix, e.g. `user`
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// NanoID length in characters
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// NanoID alphabet
+    pub fn with_alphabet(mut self, alphabet: &str) -> Self {
+        self.alphabet = Some(alphabet.to_string());
+        self
+    }
+
+    /// Text form of generated UUIDs
+    #[cfg(feature = "uuid")]
+    pub fn with_uuid_style(mut self, style: UuidStyle) -> Self {
+        self.uuid_style = Some(style);
+        self
+    }
+
+    /// Snowflake layout (preset and epoch)
+    #[cfg(feature = "snowflake")]
+    pub fn with_layout(mut self, layout: SnowflakeLayout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// Value of a Snowflake layout field such as `machine_id`
+    pub fn with_field(mut self, name: &str, value: u64) -> Self {
+        self.fields.push((name.to_string(), value));
+        self
+    }
+
+    #[cfg(feature = "snowflake")]
+    pub fn with_clock_policy(mut self, policy: ClockPolicy) -> Self {
+        self.clock_policy = policy;
+        self
+    }
+
+    #[cfg(feature = "snowflake")]
+    pub fn with_sequence_policy(mut self, policy: SequencePolicy) -> Self {
+        self.sequence_policy = policy;
+        self
+    }
+
+    /// A Snowflake generator with these options' layout, fields, timestamp,
+    /// and policies
+    #[cfg(feature = "snowflake")]
+    pub fn snowflake_generator(&self) -> Result<SnowflakeGenerator> {
+        let layout = self
+            .layout
+            .clone()
+            .unwrap_or_else(SnowflakeLayout::default_layout);
+        let mut generator = SnowflakeGenerator::new()
+            .with_layout(layout)
+            .with_clock_policy(self.clock_policy)
+            .with_sequence_policy(self.sequence_policy);
+        for (name, value) in &self.fields {
+            let layout = &generator.layout;
+            if !layout.has_field(name) {
+                return Err(IdtError::InvalidArgument(format!(
+                    "Preset '{}' does not have a '{}' field. Available: {}",
+                    layout.name,
+                    name,
+                    layout
+                        .fields
+                        .iter()
+                        .filter(|f| f.name != "timestamp" && f.name != "sequence")
+                        .map(|f| f.name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+            generator = generator.with_field(name, *value);
+        }
+        match self.timestamp_ms {
+            Some(ms) => generator.with_timestamp(ms),
+            None => Ok(generator),
+        }
+    }
+
+    /// A ULID generator with these options' timestamp and random source
+    #[cfg(feature = "ulid")]
+    pub fn ulid_generator(&self) -> crate::ids::UlidGenerator {
+        let generator = self.seed(crate::ids::UlidGenerator::new(), |g, rng| g.with_rng(rng));
+        match self.timestamp_ms {
+            Some(ms) => generator.with_timestamp(ms),
+            None => generator,
+        }
+    }
+
+    /// A NanoID generator with these options' length, alphabet, and random
+    /// source, after checking the alphabet
+    #[cfg(feature = "nanoid")]
+    pub fn nanoid_generator(&self) -> Result<crate::ids::NanoIdGenerator> {
+        let mut generator = self.seed(crate::ids::NanoIdGenerator::new(), |g, rng| g.with_rng(rng));
+        if let Some(ref alphabet) = self.alphabet {
+            generator = generator.with_alphabet(alphabet);
+        }
+        if let Some(length) = self.length {
+            generator = generator.with_length(length);
+        }
+        generator.validate()?;
+        Ok(generator)
+    }
+
+    /// Reject options `kind` has no use for
+    fn check(&self, kind: IdKind) -> Result<()> {
+        let reject = |option: &str, applies_to: &str| {
+            Err(IdtError::InvalidArgument(format!(
+                "A {} only applies to {}, not {}",
+                option,
+                applies_to,
+                kind.name()
+            )))
+        };
+        if self.timestamp_ms.is_some() && !Self::TIMESTAMPED.contains(&kind) {
+            return reject("timestamp", "timestamped IDs");
+        }
+        if self.prefix.is_some() && kind != IdKind::TypeId {
+            return reject("prefix", "TypeIDs");
+        }
+        if (self.length.is_some() || self.alphabet.is_some()) && kind != IdKind::NanoId {
+            return reject("length or alphabet", "NanoIDs");
+        }
+        #[cfg(feature = "uuid")]
+        if self.uuid_style.is_some() && !kind.is_uuid() {
+            return reject("UUID style", "UUIDs");
+        }
+        let is_snowflake = matches!(kind, IdKind::Snowflake | IdKind::Custom);
+        if !self.fields.is_empty() && !is_snowflake {
+            return reject("layout field", "Snowflake and custom IDs");
+        }
+        #[cfg(feature = "snowflake")]
+        if self.layout.is_some() && kind != IdKind::Snowflake {
+            return reject("Snowflake layout", "Snowflake IDs");
+        }
+        Ok(())
+    }
+
+    /// Hand `generator` the random source, if one is set
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    fn seed<G>(&self, generator: G, apply: fn(G, IdRng) -> G) -> G {
+        match self.rng {
+            Some(ref rng) => apply(generator, rng.clone()),
+            None => generator,
+        }
+    }
+
+    /// Nil, max, v1, and v6 UUIDs have no random bits, so they ignore the
+    /// random source
+    #[cfg(feature = "uuid")]
+    fn uuid_generator(&self, generator: UuidGenerator) -> UuidGenerator {
+        let generator = generator.with_style(self.uuid_style.unwrap_or_default());
+        let generator = match self.timestamp_ms {
+            Some(ms) => generator.with_timestamp(ms),
+            None => generator,
+        };
+        self.seed(generator, UuidGenerator::with_rng)
+    }
+
+    /// Build the generator for `kind`
+    #[cfg_attr(not(feature = "cli"), allow(unreachable_code))]
+    pub(crate) fn build(&self, kind: IdKind) -> Result<Box<dyn IdGenerator + Send + Sync>> {
+        if !kind.is_enabled() {
+            return Err(super::not_compiled(kind));
+        }
+        self.check(kind)?;
+        Ok(match kind {
+            #[cfg(feature = "uuid")]
+            IdKind::Uuid | IdKind::UuidV4 => Box::new(self.uuid_generator(UuidGenerator::v4())),
+            #[cfg(feature = "uuid")]
+            IdKind::UuidV1 => Box::new(self.uuid_generator(UuidGenerator::v1())),
+            #[cfg(feature = "uuid")]
+            IdKind::UuidV6 => Box::new(self.uuid_generator(UuidGenerator::v6())),
+            #[cfg(feature = "uuid")]
+            IdKind::UuidV7 => Box::new(self.uuid_generator(UuidGenerator::v7())),
+            #[cfg(feature = "uuid")]
+            IdKind::UuidNil => Box::new(self.uuid_generator(UuidGenerator::nil())),
+            #[cfg(feature = "uuid")]
+            IdKind::UuidMax => Box::new(self.uuid_generator(UuidGenerator::max())),
+            #[cfg(feature = "ulid")]
+            IdKind::Ulid => Box::new(self.ulid_generator()),
+            #[cfg(feature = "nanoid")]
+            IdKind::NanoId => Box::new(self.nanoid_generator()?),
+            #[cfg(feature = "snowflake")]
+            IdKind::Snowflake => Box::new(self.snowflake_generator()?),
+            #[cfg(feature = "objectid")]
+            IdKind::ObjectId => {
+                let generator = self.seed(crate::ids::ObjectIdGenerator::new(), |g, rng| {
+                    g.with_rng(rng)
+                });
+                match self.timestamp_ms {
+                    Some(ms) => Box::new(generator.with_timestamp(ms / 1000)?),
+                    None => Box::new(generator),
+                }
+            }
+            #[cfg(feature = "ksuid")]
+            IdKind::Ksuid => {
+                let generator =
+                    self.seed(crate::ids::KsuidGenerator::new(), |g, rng| g.with_rng(rng));
+                match self.timestamp_ms {
+                    Some(ms) => Box::new(generator.with_timestamp(ms / 1000)),
+                    None => Box::new(generator),
+                }
+            }
+            #[cfg(feature = "xid")]
+            IdKind::Xid => {
+                let generator =
+                    self.seed(crate::ids::XidGenerator::new(), |g, rng| g.with_rng(rng));
+                match self.timestamp_ms {
+                    Some(ms) => Box::new(generator.with_timestamp(ms / 1000)?),
+                    None => Box::new(generator),
+                }
+            }
+            #[cfg(feature = "tsid")]
+            IdKind::Tsid => {
+                let generator =
+                    self.seed(crate::ids::TsidGenerator::new(), |g, rng| g.with_rng(rng));
+                match self.timestamp_ms {
+                    Some(ms) => Box::new(generator.with_timestamp(ms)?),
+                    None => Box::new(generator),
+                }
+            }
+            #[cfg(feature = "cuid")]
+            IdKind::Cuid => {
+                let generator =
+                    self.seed(crate::ids::CuidGenerator::new(), |g, rng| g.with_rng(rng));
+                match self.timestamp_ms {
+                    Some(ms) => Box::new(generator.with_timestamp(ms)),
+                    None => Box::new(generator),
+                }
+            }
+            #[cfg(feature = "cuid2")]
+            IdKind::Cuid2 => {
+                Box::new(self.seed(crate::ids::Cuid2Generator::new(), |g, rng| g.with_rng(rng)))
+            }
+            #[cfg(feature = "typeid")]
+            IdKind::TypeId => {
+                let prefix = self.prefix.as_deref().unwrap_or("");
+                let generator = self.seed(crate::ids::TypeIdGenerator::new(prefix), |g, rng| {
+                    g.with_rng(rng)
+                });
+                match self.timestamp_ms {
+                    Some(ms) => Box::new(generator.with_timestamp(ms)?),
+                    None => Box::new(generator),
+                }
+            }
+            #[cfg(feature = "shortuuid")]
+            IdKind::ShortUuid => {
+                Box::new(self.seed(crate::ids::ShortUuidGenerator::new(), |g, rng| {
+                    g.with_rng(rng)
+                }))
+            }
+            #[cfg(feature = "custom")]
+            IdKind::Custom => {
+                let schema = crate::ids::custom_id::require_active()?;
+                let mut options = self.clone();
+                options.layout = Some(schema.layout.clone());
+                Box::new(crate::ids::CustomGenerator::new(
+                    schema,
+                    options.snowflake_generator()?,
+                )?)
+            }
+            _ => {
+                return Err(IdtError::GenerationError(format!(
+                    "Generation not supported for: {}",
+                    kind.name()
+                )));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::parse_id;
+
+    #[test]
+    fn test_options_apply() {
+        let options = GeneratorOptions::new().with_timestamp(1_700_000_000_123);
+        // Custom IDs need a schema loaded
+        for &kind in GeneratorOptions::TIMESTAMPED {
+            if kind == IdKind::Custom || !kind.is_enabled() {
+                continue;
+            }
+            let id = options.build(kind).unwrap().generate().unwrap();
+            let millis = parse_id(&id, Some(kind))
+                .unwrap()
+                .timestamp()
+                .unwrap()
+                .millis;
+            assert!(millis / 1000 == 1_700_000_000, "{}: {}", kind, millis);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "nanoid")]
+    fn test_options_apply_nanoid() {
+        let id = GeneratorOptions::new()
+            .with_length(8)
+            .with_alphabet("ab")
+            .build(IdKind::NanoId)
+            .unwrap()
+            .generate()
+            .unwrap();
+        assert_eq!(id.len(), 8);
+        assert!(id.chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[test]
+    #[cfg(feature = "snowflake")]
+    fn test_options_apply_snowflake() {
+        let id = GeneratorOptions::new()
+            .with_layout(SnowflakeLayout::twitter())
+            .with_field("machine_id", 7)
+            .build(IdKind::Snowflake)
+            .unwrap()
+            .generate()
+            .unwrap();
+        let parsed = crate::ids::ParsedSnowflake::parse_twitter(&id).unwrap();
+        assert_eq!(parsed.machine_id(), 7);
+    }
+
+    #[test]
+    #[cfg(feature = "typeid")]
+    fn test_options_apply_typeid() {
+        let id = GeneratorOptions::new()
+            .with_prefix("user")
+            .build(IdKind::TypeId)
+            .unwrap()
+            .generate()
+            .unwrap();
+        assert!(id.starts_with("user_"));
+    }
+
+    #[test]
+    fn test_options_rejected() {
+        let timestamp = GeneratorOptions::new().with_timestamp(0);
+        assert!(timestamp.build(IdKind::UuidV4).is_err());
+        assert!(
+            GeneratorOptions::new()
+                .with_prefix("user")
+                .build(IdKind::Ulid)
+                .is_err()
+        );
+        assert!(
+            GeneratorOptions::new()
+                .with_length(8)
+                .build(IdKind::Cuid2)
+                .is_err()
+        );
+        assert!(
+            GeneratorOptions::new()
+                .with_field("no_such_field", 1)
+                .build(IdKind::Snowflake)
+                .is_err()
+        );
+        assert!(
+            GeneratorOptions::new()
+                .with_alphabet("aa")
+                .build(IdKind::NanoId)
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn test_rng_ignored_without_random_bits() {
+        let seeded = GeneratorOptions::new().with_rng(IdRng::seeded(1));
+        assert!(seeded.build(IdKind::UuidNil).is_ok());
+    }
+}
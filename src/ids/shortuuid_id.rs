@@ -1,29 +1,39 @@
 use crate::core::encoding::{
     EncodingFormat, SHORTUUID_ALPHABET, decode_shortuuid, encode_base32, encode_base58,
-    encode_base64, encode_base64_url, encode_bits, encode_bytes_spaced, encode_hex,
-    encode_hex_upper, encode_shortuuid,
+    encode_base58check, encode_base64, encode_base64_ordered, encode_base64_url, encode_bits,
+    encode_bytes_spaced, encode_hex, encode_hex_upper, encode_java_longs, encode_shortuuid,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
+use crate::ids::uuid_id::random_v4;
 use serde_json::json;
 use uuid::Uuid;
 
 /// ShortUUID generator — always v4 UUID encoded in base57.
 #[derive(Default)]
-pub struct ShortUuidGenerator;
+pub struct ShortUuidGenerator {
+    rng: IdRng,
+}
 
 impl ShortUuidGenerator {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = rng;
+        self
     }
 }
 
 impl IdGenerator for ShortUuidGenerator {
     fn generate(&self) -> Result<String> {
-        let uuid = Uuid::new_v4();
+        let uuid = random_v4(&self.rng);
         Ok(encode_shortuuid(uuid.as_bytes()))
     }
 }
@@ -57,6 +67,14 @@ impl ParsedShortUuid {
     }
 }
 
+impl std::str::FromStr for ParsedShortUuid {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedShortUuid {
     fn kind(&self) -> IdKind {
         IdKind::ShortUuid
@@ -94,6 +112,9 @@ impl ParsedId for ParsedShortUuid {
             timestamp: None,
             timestamp_iso: None,
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: Some(version.to_string()),
             variant: Some(variant.clone()),
             random_bits: None,
@@ -133,6 +154,10 @@ impl ParsedId for ParsedShortUuid {
         ValidationResult::valid("shortuuid")
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        Some(self.uuid.as_u128())
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
@@ -142,14 +167,16 @@ impl ParsedId for ParsedShortUuid {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => {
-                u128::from_be_bytes(bytes.try_into().expect("UUID is 16 bytes")).to_string()
-            }
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
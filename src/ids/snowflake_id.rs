@@ -1,11 +1,18 @@
+use crate::core::components::{Components, SnowflakeComponents};
 use crate::core::encoding::{EncodingFormat, encode_base64, encode_bits, encode_hex};
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
+use crate::core::sortkey::encode_sort_key;
+use crate::core::state::with_locked_state;
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::fmt;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Compute a bitmask for `bits` bits, safe for 0..=64.
 fn bitmask(bits: u8) -> u64 {
@@ -16,6 +23,15 @@ fn bitmask(bits: u8) -> u64 {
     }
 }
 
+/// IDs that found their sequence used up, across every generator in the process
+static SEQUENCE_EXHAUSTED: AtomicU64 = AtomicU64::new(0);
+
+/// How many IDs in this process found every sequence number for their
+/// timestamp already issued, whether the generator then waited or failed
+pub fn sequence_exhaustions() -> u64 {
+    SEQUENCE_EXHAUSTED.load(Ordering::Relaxed)
+}
+
 /// Twitter Snowflake epoch (Nov 04, 2010 01:42:54 UTC) in milliseconds
 pub const TWITTER_EPOCH: u64 = 1288834974657;
 
@@ -31,9 +47,6 @@ pub const SONYFLAKE_EPOCH: u64 = 1409529600000;
 /// Default epoch (Unix epoch)
 pub const DEFAULT_EPOCH: u64 = 0;
 
-static SEQUENCE: AtomicU64 = AtomicU64::new(0);
-static LAST_TIMESTAMP: AtomicU64 = AtomicU64::new(0);
-
 /// Timestamp resolution unit
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimestampUnit {
@@ -175,6 +188,10 @@ impl SnowflakeLayout {
         }
     }
 
+    /// Names accepted by `--preset`
+    pub const PRESETS: &'static [&'static str] =
+        &["twitter", "discord", "instagram", "sonyflake", "mastodon"];
+
     /// Look up a preset by name
     pub fn by_name(name: &str) -> Option<Self> {
         match name.to_lowercase().as_str() {
@@ -189,14 +206,26 @@ impl SnowflakeLayout {
 
     /// Resolve layout from --preset and --epoch flags
     pub fn resolve(preset: Option<&str>, epoch: Option<&str>) -> Result<Self> {
+        let layout = Self::resolve_inner(preset, epoch)?;
+        tracing::debug!(
+            preset = layout.name,
+            epoch = layout.epoch,
+            unit = ?layout.timestamp_unit,
+            "snowflake layout"
+        );
+        Ok(layout)
+    }
+
+    fn resolve_inner(preset: Option<&str>, epoch: Option<&str>) -> Result<Self> {
         match (preset, epoch) {
             (Some(_), Some(_)) => Err(IdtError::InvalidArgument(
                 "Cannot use both --preset and --epoch".to_string(),
             )),
             (Some(name), None) => Self::by_name(name).ok_or_else(|| {
                 IdtError::InvalidArgument(format!(
-                    "Unknown preset '{}'. Available: twitter, discord, instagram, sonyflake, mastodon",
-                    name
+                    "Unknown preset '{}'. Available: {}",
+                    name,
+                    Self::PRESETS.join(", ")
                 ))
             }),
             (None, Some(e)) => {
@@ -257,10 +286,270 @@ impl SnowflakeLayout {
     }
 }
 
+/// Generator state persisted between invocations (see [`with_state_file`])
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnowflakeState {
+    /// Layout name the state was recorded with
+    pub layout: String,
+    /// Epoch (ms since Unix epoch) the state was recorded with
+    pub epoch: u64,
+    /// Last timestamp issued, in layout units since the epoch
+    pub last_timestamp: u64,
+    /// Last sequence issued within `last_timestamp`
+    pub sequence: u64,
+    /// Machine ID assigned to this state file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_id: Option<u64>,
+}
+
+impl SnowflakeState {
+    /// Check that the state belongs to `layout`, claiming it if fresh
+    pub fn bind_layout(&mut self, layout: &SnowflakeLayout) -> Result<()> {
+        if self.layout.is_empty() {
+            self.layout = layout.name.to_string();
+            self.epoch = layout.epoch;
+            return Ok(());
+        }
+        if self.layout != layout.name || self.epoch != layout.epoch {
+            return Err(IdtError::InvalidArgument(format!(
+                "State file was written for layout '{}' (epoch {}), not '{}' (epoch {})",
+                self.layout, self.epoch, layout.name, layout.epoch
+            )));
+        }
+        Ok(())
+    }
+
+    /// Machine ID to use with `layout`, assigning a random one on first use
+    pub fn assign_machine_id(&mut self, layout: &SnowflakeLayout) -> Option<u64> {
+        let bits = layout.field_bits("machine_id")?;
+        let mask = bitmask(bits);
+        let machine_id = *self
+            .machine_id
+            .get_or_insert_with(|| rand::rng().random::<u64>() & mask);
+        Some(machine_id & mask)
+    }
+
+    /// Advance to the next (timestamp, sequence) pair.
+    ///
+    /// The timestamp never moves backwards: if the clock is behind the last
+    /// issued timestamp, generation continues from the stored value. When the
+    /// sequence is exhausted the timestamp is advanced by one unit.
+    fn advance(&mut self, now: u64, seq_bits: u8) -> (u64, u64) {
+        let seq_mask = bitmask(seq_bits);
+        let (timestamp, sequence) = if now > self.last_timestamp {
+            (now, 0)
+        } else if self.sequence < seq_mask {
+            (self.last_timestamp, self.sequence + 1)
+        } else {
+            (self.last_timestamp + 1, 0)
+        };
+        self.last_timestamp = timestamp;
+        self.sequence = sequence;
+        (timestamp, sequence)
+    }
+}
+
+/// Run `f` against the Snowflake state stored at `path`, holding an
+/// exclusive file lock (see [`with_locked_state`])
+pub fn with_state_file<T>(
+    path: &std::path::Path,
+    f: impl FnOnce(&mut SnowflakeState) -> Result<T>,
+) -> Result<T> {
+    with_locked_state(path, f)
+}
+
+/// In-memory sequence counter for Snowflake generation.
+///
+/// Generators created with [`SnowflakeGenerator::new`] share one process-wide
+/// counter. Use [`SnowflakeGenerator::with_sequence`] to give a generator its
+/// own, or to share one between selected generators.
+#[derive(Debug, Default)]
+pub struct SnowflakeSequence {
+    last: Mutex<SequenceClock>,
+}
+
+/// Last timestamp issued by a [`SnowflakeSequence`] and the next sequence
+/// within it. Timestamps are only comparable under the same epoch and unit.
+#[derive(Debug, Default)]
+struct SequenceClock {
+    clock: Option<(u64, TimestampUnit)>,
+    timestamp: u64,
+    sequence: u64,
+}
+
+impl SnowflakeSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process-wide counter shared by default generators
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<SnowflakeSequence>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    /// Sequence number for `timestamp` under `layout`'s clock, or
+    /// [`IdtError::ClockMovedBackwards`] if it is earlier than the last
+    /// timestamp issued, or [`IdtError::SequenceExhausted`] once every
+    /// sequence number for it is used. A different epoch or unit starts over.
+    fn next(&self, layout: &SnowflakeLayout, timestamp: u64, seq_bits: u8) -> Result<u64> {
+        let mut last = self.last.lock().expect("sequence lock poisoned");
+        let clock = Some((layout.epoch, layout.timestamp_unit));
+        let sequence = if last.clock != clock || timestamp > last.timestamp {
+            0
+        } else if timestamp == last.timestamp {
+            if last.sequence > bitmask(seq_bits) {
+                return Err(IdtError::SequenceExhausted { timestamp });
+            }
+            last.sequence
+        } else {
+            return Err(IdtError::ClockMovedBackwards {
+                last_timestamp: last.timestamp,
+                timestamp,
+            });
+        };
+        *last = SequenceClock {
+            clock,
+            timestamp,
+            sequence: sequence + 1,
+        };
+        Ok(sequence)
+    }
+}
+
+/// What a generator does when the clock reads earlier than the last ID it issued
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ClockPolicy {
+    /// Sleep until the clock catches up with the last timestamp
+    #[default]
+    Wait,
+    /// Fail with a `ClockMovedBackwards` error
+    Error,
+}
+
+/// What a generator does when every sequence number for the current
+/// timestamp has been issued
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SequencePolicy {
+    /// Sleep until the next timestamp unit
+    #[default]
+    Wait,
+    /// Fail with a `SequenceExhausted` error
+    Error,
+}
+
+/// Where a machine or datacenter ID comes from: `N`, `auto:ip`,
+/// `auto:hostname`, or `env:VAR`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeIdSource {
+    /// A fixed value
+    Value(u64),
+    /// Low bits of the address the host would use for outbound traffic
+    Ip,
+    /// Low bits of a SHA-256 hash of the hostname
+    Hostname,
+    /// Integer read from the named environment variable
+    Env(String),
+}
+
+impl std::str::FromStr for NodeIdSource {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto:ip" => Ok(Self::Ip),
+            "auto:hostname" => Ok(Self::Hostname),
+            _ => {
+                if let Some(var) = s.strip_prefix("env:").filter(|v| !v.is_empty()) {
+                    return Ok(Self::Env(var.to_string()));
+                }
+                s.parse().map(Self::Value).map_err(|_| {
+                    IdtError::InvalidArgument(format!(
+                        "Invalid node ID '{}': expected an integer, auto:ip, auto:hostname, or env:VAR",
+                        s
+                    ))
+                })
+            }
+        }
+    }
+}
+
+impl NodeIdSource {
+    /// Resolve to a value that fits in a `bits`-wide field. Derived values
+    /// are truncated to their low bits; a fixed value is taken as-is.
+    pub fn resolve(&self, bits: u8) -> Result<u64> {
+        let mask = bitmask(bits);
+        match self {
+            Self::Value(value) => Ok(*value),
+            Self::Ip => {
+                let ip = outbound_ip().ok_or_else(|| {
+                    IdtError::GenerationError("Could not determine the host IP address".into())
+                })?;
+                let low = match ip {
+                    std::net::IpAddr::V4(v4) => u32::from(v4) as u64,
+                    std::net::IpAddr::V6(v6) => u128::from(v6) as u64,
+                };
+                Ok(low & mask)
+            }
+            Self::Hostname => {
+                use sha2::{Digest, Sha256};
+                let name = hostname().ok_or_else(|| {
+                    IdtError::GenerationError("Could not determine the hostname".into())
+                })?;
+                let digest = Sha256::digest(name.as_bytes());
+                let mut low = [0u8; 8];
+                low.copy_from_slice(&digest[..8]);
+                Ok(u64::from_be_bytes(low) & mask)
+            }
+            Self::Env(var) => {
+                let value = std::env::var(var).map_err(|_| {
+                    IdtError::InvalidArgument(format!("Environment variable {} is not set", var))
+                })?;
+                value.trim().parse().map_err(|_| {
+                    IdtError::InvalidArgument(format!(
+                        "Environment variable {} must be an integer, got '{}'",
+                        var, value
+                    ))
+                })
+            }
+        }
+    }
+}
+
+/// Local address of the route to a public host. Connecting a UDP socket
+/// sends nothing; it only selects the interface.
+fn outbound_ip() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:53").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified()).then_some(ip)
+}
+
+fn hostname() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(
+            ["/proc/sys/kernel/hostname", "/etc/hostname"]
+                .iter()
+                .filter_map(|path| std::fs::read_to_string(path).ok()),
+        )
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty())
+}
+
 /// Snowflake generator
 pub struct SnowflakeGenerator {
     pub layout: SnowflakeLayout,
     pub field_values: std::collections::HashMap<String, u64>,
+    sequence: Arc<SnowflakeSequence>,
+    clock_policy: ClockPolicy,
+    sequence_policy: SequencePolicy,
+    state: Option<Mutex<SnowflakeState>>,
+    /// Unix milliseconds to stamp instead of the current time
+    timestamp_ms: Option<u64>,
 }
 
 impl Default for SnowflakeGenerator {
@@ -268,6 +557,11 @@ impl Default for SnowflakeGenerator {
         Self {
             layout: SnowflakeLayout::default_layout(),
             field_values: std::collections::HashMap::new(),
+            sequence: SnowflakeSequence::shared(),
+            clock_policy: ClockPolicy::default(),
+            sequence_policy: SequencePolicy::default(),
+            state: None,
+            timestamp_ms: None,
         }
     }
 }
@@ -280,14 +574,14 @@ impl SnowflakeGenerator {
     pub fn twitter() -> Self {
         Self {
             layout: SnowflakeLayout::twitter(),
-            field_values: std::collections::HashMap::new(),
+            ..Self::default()
         }
     }
 
     pub fn discord() -> Self {
         Self {
             layout: SnowflakeLayout::discord(),
-            field_values: std::collections::HashMap::new(),
+            ..Self::default()
         }
     }
 
@@ -318,37 +612,120 @@ impl SnowflakeGenerator {
         self
     }
 
+    pub fn with_sequence(mut self, sequence: Arc<SnowflakeSequence>) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Set how a clock that moved backwards is handled. Generators with a
+    /// [`SnowflakeState`] instead continue from the stored timestamp.
+    pub fn with_clock_policy(mut self, policy: ClockPolicy) -> Self {
+        self.clock_policy = policy;
+        self
+    }
+
+    /// Set how an exhausted sequence is handled. Generators with a
+    /// [`SnowflakeState`] instead advance the stored timestamp by one unit.
+    pub fn with_sequence_policy(mut self, policy: SequencePolicy) -> Self {
+        self.sequence_policy = policy;
+        self
+    }
+
+    /// Track timestamp/sequence in a persistable `state` instead of the in-memory counter
+    pub fn with_state(mut self, state: SnowflakeState) -> Self {
+        self.state = Some(Mutex::new(state));
+        self
+    }
+
+    /// Stamp every ID with `ms` (Unix milliseconds) instead of the current
+    /// time. Call after setting the layout. The generator gets a sequence of
+    /// its own, and fails instead of waiting once it runs out for that instant.
+    pub fn with_timestamp(mut self, ms: u64) -> Result<Self> {
+        let bits = self
+            .layout
+            .field_bits("timestamp")
+            .expect("Snowflake layout must have a timestamp field");
+        if ms < self.layout.epoch {
+            return Err(IdtError::InvalidArgument(format!(
+                "Timestamp {} is before the {} epoch ({})",
+                ms, self.layout.name, self.layout.epoch
+            )));
+        }
+        if self.units_since_epoch(ms) > bitmask(bits) {
+            return Err(IdtError::InvalidArgument(format!(
+                "Timestamp {} does not fit the {}-bit {} timestamp",
+                ms, bits, self.layout.name
+            )));
+        }
+        self.timestamp_ms = Some(ms);
+        self.sequence = Arc::default();
+        Ok(self)
+    }
+
+    /// Snapshot of the tracked state, if any
+    pub fn state(&self) -> Option<SnowflakeState> {
+        self.state
+            .as_ref()
+            .map(|s| s.lock().expect("state lock poisoned").clone())
+    }
+
     fn current_timestamp(&self) -> u64 {
-        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
-        let elapsed_ms = now_ms - self.layout.epoch;
+        let now_ms = self
+            .timestamp_ms
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64);
+        self.units_since_epoch(now_ms)
+    }
+
+    /// `ms` (Unix milliseconds) in the layout's timestamp units
+    fn units_since_epoch(&self, ms: u64) -> u64 {
+        let elapsed_ms = ms - self.layout.epoch;
         match self.layout.timestamp_unit {
             TimestampUnit::Millis => elapsed_ms,
             TimestampUnit::TenMillis => elapsed_ms / 10,
             TimestampUnit::Seconds => elapsed_ms / 1000,
         }
     }
-
-    fn next_sequence(&self, timestamp: u64, seq_bits: u8) -> u64 {
-        let seq_mask = bitmask(seq_bits);
-        let last = LAST_TIMESTAMP.swap(timestamp, Ordering::SeqCst);
-        if timestamp == last {
-            SEQUENCE.fetch_add(1, Ordering::SeqCst) & seq_mask
-        } else {
-            SEQUENCE.store(1, Ordering::SeqCst);
-            0
-        }
-    }
 }
 
-impl IdGenerator for SnowflakeGenerator {
-    fn generate(&self) -> Result<String> {
-        let timestamp = self.current_timestamp();
-
+impl SnowflakeGenerator {
+    pub(crate) fn next_id(&self) -> Result<u64> {
         let seq_bits = self
             .layout
             .field_bits("sequence")
             .expect("Snowflake layout must have a sequence field");
-        let sequence = self.next_sequence(timestamp, seq_bits);
+
+        let mut exhausted = false;
+        let (timestamp, sequence) = match self.state {
+            Some(ref state) => state
+                .lock()
+                .expect("state lock poisoned")
+                .advance(self.current_timestamp(), seq_bits),
+            None => loop {
+                let timestamp = self.current_timestamp();
+                match self.sequence.next(&self.layout, timestamp, seq_bits) {
+                    Ok(sequence) => break (timestamp, sequence),
+                    Err(IdtError::ClockMovedBackwards { .. })
+                        if self.clock_policy == ClockPolicy::Wait =>
+                    {
+                        std::thread::sleep(std::time::Duration::from_millis(1));
+                    }
+                    Err(e @ IdtError::SequenceExhausted { .. }) => {
+                        if !exhausted {
+                            exhausted = true;
+                            SEQUENCE_EXHAUSTED.fetch_add(1, Ordering::Relaxed);
+                        }
+                        // A fixed timestamp never moves on, so waiting would hang
+                        if self.sequence_policy != SequencePolicy::Wait
+                            || self.timestamp_ms.is_some()
+                        {
+                            return Err(e);
+                        }
+                        std::thread::sleep(std::time::Duration::from_millis(1));
+                    }
+                    Err(e) => return Err(e),
+                }
+            },
+        };
 
         // Build ID by iterating fields MSB→LSB
         let mut id: u64 = 0;
@@ -368,7 +745,18 @@ impl IdGenerator for SnowflakeGenerator {
             id |= value << shift;
         }
 
-        Ok(id.to_string())
+        Ok(id)
+    }
+}
+
+impl IdGenerator for SnowflakeGenerator {
+    fn generate(&self) -> Result<String> {
+        Ok(self.next_id()?.to_string())
+    }
+
+    fn write_to(&self, writer: &mut dyn fmt::Write) -> Result<()> {
+        write!(writer, "{}", self.next_id()?)?;
+        Ok(())
     }
 }
 
@@ -403,12 +791,17 @@ impl ParsedSnowflake {
         Self::parse_with_layout(input, layout)
     }
 
+    /// Parse a decimal Snowflake. A negative value, as logged by Java or
+    /// Kotlin services that store IDs in a signed `long`, is reinterpreted as
+    /// the unsigned ID with the same 64 bits.
     pub fn parse_with_layout(input: &str, layout: SnowflakeLayout) -> Result<Self> {
         let input_trimmed = input.trim();
 
-        let id = input_trimmed
-            .parse::<u64>()
-            .map_err(|e| IdtError::ParseError(format!("Invalid Snowflake ID: {}", e)))?;
+        let id = match input_trimmed.strip_prefix('-') {
+            Some(_) => input_trimmed.parse::<i64>().map(|signed| signed as u64),
+            None => input_trimmed.parse::<u64>(),
+        }
+        .map_err(|e| IdtError::ParseError(format!("Invalid Snowflake ID: {}", e)))?;
 
         Ok(Self {
             id,
@@ -417,6 +810,24 @@ impl ParsedSnowflake {
         })
     }
 
+    /// Wrap an already-decoded ID value
+    pub fn from_id(id: u64, layout: SnowflakeLayout) -> Self {
+        Self {
+            id,
+            layout,
+            input: id.to_string(),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Whether the input was a negative (signed 64-bit) value
+    pub fn is_signed_input(&self) -> bool {
+        self.input.starts_with('-')
+    }
+
     pub fn timestamp_raw(&self) -> u64 {
         self.layout
             .extract_field(self.id, "timestamp")
@@ -450,6 +861,14 @@ impl ParsedSnowflake {
     }
 }
 
+impl std::str::FromStr for ParsedSnowflake {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedSnowflake {
     fn kind(&self) -> IdKind {
         IdKind::Snowflake
@@ -467,6 +886,24 @@ impl ParsedId for ParsedSnowflake {
         Some(Timestamp::new(self.timestamp_ms()))
     }
 
+    fn components(&self) -> Option<Components> {
+        let fields = self
+            .layout
+            .fields
+            .iter()
+            .filter(|field| field.name != "timestamp")
+            .filter_map(|field| {
+                let value = self.layout.extract_field(self.id, field.name)?;
+                Some((field.name, value))
+            })
+            .collect();
+        Some(Components::Snowflake(SnowflakeComponents {
+            timestamp_ms: self.timestamp_ms(),
+            epoch: self.layout.epoch,
+            fields,
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let timestamp = self.timestamp().expect("Snowflake always has a timestamp");
@@ -484,6 +921,15 @@ impl ParsedId for ParsedSnowflake {
                 components.insert(field.name.to_string(), json!(val));
             }
         }
+        if self.is_signed_input() {
+            components.insert(
+                "note".to_string(),
+                json!(format!(
+                    "Signed 64-bit input {} reinterpreted as unsigned {}",
+                    self.input, self.id
+                )),
+            );
+        }
 
         let variant_name = match self.layout.name {
             "twitter" => "Twitter",
@@ -502,6 +948,9 @@ impl ParsedId for ParsedSnowflake {
             timestamp: Some(timestamp),
             timestamp_iso: Some(timestamp.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: Some(variant_name.to_string()),
             random_bits: None,
@@ -556,36 +1005,53 @@ impl ParsedId for ParsedSnowflake {
         }
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        Some(self.id as u128)
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
             EncodingFormat::Canonical => self.canonical(),
+            EncodingFormat::SortKey => encode_sort_key(self),
             EncodingFormat::Hex => encode_hex(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => self.id.to_string(),
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             _ => self.canonical(),
         }
     }
 }
 
-/// Check if a string looks like a Snowflake ID
+/// Check if a string looks like a Snowflake ID, signed or unsigned
 pub fn is_snowflake(input: &str) -> bool {
     let input = input.trim();
-    if input.is_empty() {
+    let digits = input.strip_prefix('-').unwrap_or(input);
+    if digits.is_empty() {
         return false;
     }
-    if !input.chars().all(|c| c.is_ascii_digit()) {
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
         return false;
     }
-    let len = input.len();
-    (15..=19).contains(&len) && input.parse::<u64>().is_ok()
+    let len = digits.len();
+    (15..=19).contains(&len) && ParsedSnowflake::parse(input).is_ok()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_generate_into_reuses_buffer() {
+        let generator = SnowflakeGenerator::new();
+        let mut buf = String::new();
+        generator.generate_into(&mut buf).unwrap();
+        let first: u64 = buf.parse().unwrap();
+        buf.clear();
+        generator.generate_into(&mut buf).unwrap();
+        assert!(buf.parse::<u64>().unwrap() > first);
+    }
+
     #[test]
     fn test_generate() {
         let generator = SnowflakeGenerator::new();
@@ -601,6 +1067,28 @@ mod tests {
         assert_eq!(parsed.machine_id(), 1);
     }
 
+    #[test]
+    fn test_parse_signed() {
+        let unsigned = "17039034589154983936";
+        let parsed = ParsedSnowflake::parse_twitter("-1407709484554567680").unwrap();
+        assert_eq!(parsed.canonical(), unsigned);
+        assert!(parsed.is_signed_input());
+        assert_eq!(
+            parsed.timestamp_ms(),
+            ParsedSnowflake::parse_twitter(unsigned)
+                .unwrap()
+                .timestamp_ms()
+        );
+        let components = parsed.inspect().components.unwrap();
+        assert!(components["note"].as_str().unwrap().contains(unsigned));
+
+        assert!(!ParsedSnowflake::parse(unsigned).unwrap().is_signed_input());
+        assert!(ParsedSnowflake::parse("-9223372036854775809").is_err());
+        assert!(ParsedSnowflake::parse("--1").is_err());
+        assert!(is_snowflake("-1407709484554567680"));
+        assert!(!is_snowflake("-"));
+    }
+
     #[test]
     fn test_parse_components() {
         let id = "1234567890123456789";
@@ -707,4 +1195,228 @@ mod tests {
         let comps = inspection.components.unwrap();
         assert_eq!(comps["shard_id"], 7);
     }
+
+    #[test]
+    fn test_state_advance_monotonic() {
+        let mut state = SnowflakeState::default();
+        assert_eq!(state.advance(100, 12), (100, 0));
+        assert_eq!(state.advance(100, 12), (100, 1));
+        // Clock moved backwards: stay on the last timestamp
+        assert_eq!(state.advance(50, 12), (100, 2));
+        assert_eq!(state.advance(101, 12), (101, 0));
+    }
+
+    #[test]
+    fn test_state_advance_sequence_overflow() {
+        let mut state = SnowflakeState {
+            last_timestamp: 100,
+            sequence: 3,
+            ..Default::default()
+        };
+        // 2 sequence bits: 3 is the last value, so the timestamp moves forward
+        assert_eq!(state.advance(100, 2), (101, 0));
+    }
+
+    #[test]
+    fn test_state_assign_machine_id() {
+        let mut state = SnowflakeState::default();
+        let assigned = state
+            .assign_machine_id(&SnowflakeLayout::twitter())
+            .unwrap();
+        assert!(assigned < 32);
+        assert_eq!(state.machine_id, Some(assigned));
+        assert_eq!(
+            state.assign_machine_id(&SnowflakeLayout::twitter()),
+            Some(assigned)
+        );
+        assert!(
+            state
+                .assign_machine_id(&SnowflakeLayout::mastodon())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_state_bind_layout() {
+        let mut state = SnowflakeState::default();
+        state.bind_layout(&SnowflakeLayout::twitter()).unwrap();
+        assert_eq!(state.layout, "twitter");
+        assert!(state.bind_layout(&SnowflakeLayout::twitter()).is_ok());
+        assert!(state.bind_layout(&SnowflakeLayout::discord()).is_err());
+    }
+
+    #[test]
+    fn test_generator_with_state_is_unique() {
+        let generator = SnowflakeGenerator::twitter().with_state(SnowflakeState::default());
+        let ids = generator.generate_many(5000).unwrap();
+        let values: Vec<u64> = ids.iter().map(|id| id.parse().unwrap()).collect();
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_with_state_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snowflake.json");
+
+        with_state_file(&path, |state| {
+            state.last_timestamp = 42;
+            state.machine_id = Some(7);
+            Ok(())
+        })
+        .unwrap();
+
+        let loaded = with_state_file(&path, |state| Ok(state.clone())).unwrap();
+        assert_eq!(loaded.last_timestamp, 42);
+        assert_eq!(loaded.machine_id, Some(7));
+    }
+
+    #[test]
+    fn test_with_state_file_invalid_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snowflake.json");
+        std::fs::write(&path, "not json").unwrap();
+        assert!(with_state_file(&path, |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_independent_sequences() {
+        let layout = SnowflakeLayout::default_layout();
+        let a = Arc::new(SnowflakeSequence::new());
+        let b = Arc::new(SnowflakeSequence::new());
+        assert_eq!(a.next(&layout, 100, 12).unwrap(), 0);
+        assert_eq!(a.next(&layout, 100, 12).unwrap(), 1);
+        // A separate counter is unaffected by the first one
+        assert_eq!(b.next(&layout, 100, 12).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sequence_detects_clock_rollback() {
+        let layout = SnowflakeLayout::default_layout();
+        let sequence = SnowflakeSequence::new();
+        assert_eq!(sequence.next(&layout, 100, 12).unwrap(), 0);
+        let err = sequence.next(&layout, 99, 12).unwrap_err();
+        assert!(matches!(
+            err,
+            IdtError::ClockMovedBackwards {
+                last_timestamp: 100,
+                timestamp: 99
+            }
+        ));
+        // The rejected timestamp does not disturb the sequence
+        assert_eq!(sequence.next(&layout, 100, 12).unwrap(), 1);
+        assert_eq!(sequence.next(&layout, 101, 12).unwrap(), 0);
+        // Timestamps under another epoch are not compared
+        let discord = SnowflakeLayout::discord();
+        assert_eq!(sequence.next(&discord, 50, 12).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_node_id_source_parse() {
+        assert_eq!("7".parse::<NodeIdSource>().unwrap(), NodeIdSource::Value(7));
+        assert_eq!("auto:ip".parse::<NodeIdSource>().unwrap(), NodeIdSource::Ip);
+        assert_eq!(
+            "auto:hostname".parse::<NodeIdSource>().unwrap(),
+            NodeIdSource::Hostname
+        );
+        assert_eq!(
+            "env:NODE_ID".parse::<NodeIdSource>().unwrap(),
+            NodeIdSource::Env("NODE_ID".to_string())
+        );
+        assert!("auto:mac".parse::<NodeIdSource>().is_err());
+        assert!("env:".parse::<NodeIdSource>().is_err());
+    }
+
+    #[test]
+    fn test_node_id_source_resolve() {
+        assert_eq!(NodeIdSource::Value(40).resolve(5).unwrap(), 40);
+        if let Ok(id) = NodeIdSource::Hostname.resolve(5) {
+            assert!(id < 32);
+            assert_eq!(NodeIdSource::Hostname.resolve(5).unwrap(), id);
+        }
+        let err = NodeIdSource::Env("IDT_TEST_UNSET_NODE_ID".to_string())
+            .resolve(5)
+            .unwrap_err();
+        assert!(err.to_string().contains("IDT_TEST_UNSET_NODE_ID"));
+    }
+
+    #[test]
+    fn test_sequence_exhaustion() {
+        let layout = SnowflakeLayout::default_layout();
+        let sequence = SnowflakeSequence::new();
+        for expected in 0..4 {
+            assert_eq!(sequence.next(&layout, 100, 2).unwrap(), expected);
+        }
+        assert!(matches!(
+            sequence.next(&layout, 100, 2).unwrap_err(),
+            IdtError::SequenceExhausted { timestamp: 100 }
+        ));
+        assert_eq!(sequence.next(&layout, 101, 2).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sequence_policy_wait_keeps_ids_unique() {
+        // Sonyflake allows 256 IDs per 10 ms, so this spans several units
+        let generator = SnowflakeGenerator::new()
+            .with_layout(SnowflakeLayout::sonyflake())
+            .with_sequence(Arc::new(SnowflakeSequence::new()));
+        let ids: std::collections::HashSet<_> =
+            (0..1000).map(|_| generator.generate().unwrap()).collect();
+        assert_eq!(ids.len(), 1000);
+    }
+
+    #[test]
+    fn test_presets_resolve() {
+        for &name in SnowflakeLayout::PRESETS {
+            let layout = SnowflakeLayout::by_name(name).unwrap();
+            assert_eq!(layout.name, name);
+        }
+    }
+
+    #[test]
+    fn test_with_timestamp() {
+        let ms = 1_577_836_800_000;
+        let generator = SnowflakeGenerator::twitter().with_timestamp(ms).unwrap();
+        let first = ParsedSnowflake::parse(&generator.generate().unwrap()).unwrap();
+        let second = ParsedSnowflake::parse(&generator.generate().unwrap()).unwrap();
+        assert_eq!(first.timestamp_raw() + SnowflakeLayout::twitter().epoch, ms);
+        assert_eq!(first.timestamp_raw(), second.timestamp_raw());
+        assert_ne!(first.sequence(), second.sequence());
+
+        // Before the epoch, and with the sequence for that instant used up
+        assert!(SnowflakeGenerator::twitter().with_timestamp(0).is_err());
+        let layout = SnowflakeLayout::sonyflake();
+        let generator = SnowflakeGenerator::new()
+            .with_layout(layout.clone())
+            .with_timestamp(layout.epoch)
+            .unwrap();
+        let before = sequence_exhaustions();
+        let result: Result<Vec<_>> = (0..257).map(|_| generator.generate()).collect();
+        assert_eq!(result.unwrap_err().code(), "sequence_exhausted");
+        assert!(sequence_exhaustions() > before);
+    }
+
+    #[test]
+    fn test_clock_policy() {
+        // A sequence that last issued an ID a second ahead of the clock
+        let sequence = Arc::new(SnowflakeSequence::new());
+        let generator = SnowflakeGenerator::new()
+            .with_sequence(sequence.clone())
+            .with_clock_policy(ClockPolicy::Error);
+        sequence
+            .next(&generator.layout, generator.current_timestamp() + 1000, 12)
+            .unwrap();
+        assert_eq!(
+            generator.generate().unwrap_err().code(),
+            "clock_moved_backwards"
+        );
+
+        // Waiting for a clock 2 ms behind
+        let sequence = Arc::new(SnowflakeSequence::new());
+        let generator = SnowflakeGenerator::new().with_sequence(sequence.clone());
+        let ahead = generator.current_timestamp() + 2;
+        sequence.next(&generator.layout, ahead, 12).unwrap();
+        let id = generator.generate().unwrap();
+        let parsed = ParsedSnowflake::parse(&id).unwrap();
+        assert!(parsed.timestamp_raw() >= ahead);
+    }
 }
@@ -1,50 +1,88 @@
+use crate::core::case::{CanonicalCase, canonical_case};
+use crate::core::components::{Components, TsidComponents};
 use crate::core::encoding::{EncodingFormat, encode_base64, encode_bits, encode_hex};
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
-use rand::RngExt;
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
 use serde_json::json;
+use std::fmt;
 
 /// Crockford Base32 alphabet
 const CROCKFORD: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
 
 /// TSID generator
-pub struct TsidGenerator;
+#[derive(Default)]
+pub struct TsidGenerator {
+    timestamp_ms: Option<u64>,
+    rng: IdRng,
+}
 
-impl Default for TsidGenerator {
-    fn default() -> Self {
-        Self
+impl TsidGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Stamp every TSID with `ms` (Unix milliseconds) instead of the current time
+    pub fn with_timestamp(mut self, ms: u64) -> Result<Self> {
+        if ms >> 42 != 0 {
+            return Err(IdtError::InvalidArgument(format!(
+                "TSID timestamp {} exceeds 42 bits",
+                ms
+            )));
+        }
+        self.timestamp_ms = Some(ms);
+        Ok(self)
     }
 }
 
 impl TsidGenerator {
-    pub fn new() -> Self {
-        Self
+    fn next_value(&self) -> u64 {
+        let now_ms = self
+            .timestamp_ms
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64);
+        let random_bits = self.rng.u64() & 0x3F_FFFF; // 22 bits
+
+        (now_ms << 22) | random_bits
     }
 }
 
 impl IdGenerator for TsidGenerator {
     fn generate(&self) -> Result<String> {
-        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
-        let mut rng = rand::rng();
-        let random_bits: u64 = rng.random::<u64>() & 0x3F_FFFF; // 22 bits
+        Ok(tsid_encode(self.next_value()))
+    }
 
-        let value = (now_ms << 22) | random_bits;
-        Ok(tsid_encode(value))
+    fn write_to(&self, writer: &mut dyn fmt::Write) -> Result<()> {
+        let chars = tsid_encode_array(self.next_value());
+        writer.write_str(std::str::from_utf8(&chars).expect("CROCKFORD alphabet is ASCII"))?;
+        Ok(())
     }
 }
 
 /// Encode u64 as 13-char Crockford Base32
-fn tsid_encode(value: u64) -> String {
+pub(crate) fn tsid_encode(value: u64) -> String {
+    String::from_utf8(tsid_encode_array(value).to_vec()).expect("CROCKFORD alphabet is valid UTF-8")
+}
+
+fn tsid_encode_array(value: u64) -> [u8; 13] {
     let mut result = [0u8; 13];
     let mut v = value;
     for i in (0..13).rev() {
         result[i] = CROCKFORD[(v & 0x1F) as usize];
         v >>= 5;
     }
-    String::from_utf8(result.to_vec()).expect("CROCKFORD alphabet is valid UTF-8")
+    if canonical_case(IdKind::Tsid) == CanonicalCase::Lower {
+        result.make_ascii_lowercase();
+    }
+    result
 }
 
 /// Decode 13-char Crockford Base32 to u64
@@ -56,10 +94,9 @@ fn tsid_decode(s: &str) -> Result<u64> {
     }
 
     let mut value: u64 = 0;
-    for ch in s.chars() {
-        let v = crockford_char_value(ch).ok_or_else(|| {
-            IdtError::ParseError(format!("Invalid Crockford Base32 character: '{}'", ch))
-        })?;
+    for (offset, ch) in s.char_indices() {
+        let v = crockford_char_value(ch)
+            .ok_or_else(|| IdtError::invalid_character(s, offset, "Crockford Base32"))?;
         value = (value << 5) | (v as u64);
     }
     Ok(value)
@@ -128,6 +165,14 @@ impl ParsedTsid {
     }
 }
 
+impl std::str::FromStr for ParsedTsid {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedTsid {
     fn kind(&self) -> IdKind {
         IdKind::Tsid
@@ -145,6 +190,13 @@ impl ParsedId for ParsedTsid {
         Some(Timestamp::new(self.timestamp_ms()))
     }
 
+    fn components(&self) -> Option<Components> {
+        Some(Components::Tsid(TsidComponents {
+            timestamp_ms: self.timestamp_ms(),
+            random_bits: self.random_bits(),
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let timestamp = self.timestamp().expect("TSID always has a timestamp");
@@ -163,6 +215,9 @@ impl ParsedId for ParsedTsid {
             timestamp: Some(timestamp),
             timestamp_iso: Some(timestamp.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: None,
             random_bits: Some(22),
@@ -203,14 +258,19 @@ impl ParsedId for ParsedTsid {
         }
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        Some(self.value as u128)
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
             EncodingFormat::Canonical => self.canonical(),
+            EncodingFormat::SortKey => encode_sort_key(self),
             EncodingFormat::Hex => encode_hex(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => self.value.to_string(),
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             _ => self.canonical(),
         }
     }
@@ -225,6 +285,14 @@ pub fn is_tsid(input: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_write_to() {
+        let mut buf = String::new();
+        TsidGenerator::new().write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), 13);
+        assert!(ParsedTsid::parse(&buf).is_ok());
+    }
+
     #[test]
     fn test_generate() {
         let generator = TsidGenerator::new();
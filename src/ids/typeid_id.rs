@@ -1,12 +1,17 @@
+use crate::core::components::{Components, TypeIdComponents};
 use crate::core::encoding::{
-    EncodingFormat, encode_base32, encode_base58, encode_base64, encode_base64_url, encode_bits,
-    encode_bytes_spaced, encode_hex, encode_hex_upper,
+    EncodingFormat, encode_base32, encode_base58, encode_base58check, encode_base64,
+    encode_base64_ordered, encode_base64_url, encode_bits, encode_bytes_spaced, encode_hex,
+    encode_hex_upper, encode_java_longs,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
+use crate::ids::uuid_id::random_v7;
 use serde_json::json;
 
 /// Modified Crockford Base32 alphabet for TypeID (lowercase, no padding)
@@ -15,20 +20,48 @@ const TYPEID_ALPHABET: &[u8] = b"0123456789abcdefghjkmnpqrstvwxyz";
 /// TypeID generator
 pub struct TypeIdGenerator {
     prefix: String,
+    timestamp_ms: Option<u64>,
+    rng: Option<IdRng>,
 }
 
 impl TypeIdGenerator {
     pub fn new(prefix: &str) -> Self {
         Self {
             prefix: prefix.to_string(),
+            timestamp_ms: None,
+            rng: None,
         }
     }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.rng = Some(rng);
+        self
+    }
+
+    /// Stamp every TypeID's UUIDv7 with `ms` (Unix milliseconds) instead of
+    /// the current time
+    pub fn with_timestamp(mut self, ms: u64) -> Result<Self> {
+        if ms >> 48 != 0 {
+            return Err(IdtError::InvalidArgument(format!(
+                "TypeID timestamp {} exceeds 48 bits",
+                ms
+            )));
+        }
+        self.timestamp_ms = Some(ms);
+        Ok(self)
+    }
 }
 
 impl IdGenerator for TypeIdGenerator {
     fn generate(&self) -> Result<String> {
         // Generate a UUIDv7
-        let uuid = uuid::Uuid::now_v7();
+        let uuid = match (self.timestamp_ms, &self.rng) {
+            (None, None) => uuid::Uuid::now_v7(),
+            (ms, rng) => random_v7(
+                rng.as_ref().unwrap_or(&IdRng::default()),
+                ms.unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64),
+            ),
+        };
         let bytes = uuid.as_bytes();
         let encoded = typeid_base32_encode(bytes);
 
@@ -41,7 +74,7 @@ impl IdGenerator for TypeIdGenerator {
 }
 
 /// Encode 16 bytes as 26-char modified Crockford Base32 (TypeID-specific)
-fn typeid_base32_encode(bytes: &[u8; 16]) -> String {
+pub(crate) fn typeid_base32_encode(bytes: &[u8; 16]) -> String {
     // 128 bits -> 26 base32 chars (26 * 5 = 130 bits, 2 padding)
     // Encode from MSB to LSB
     let mut result = [0u8; 26];
@@ -71,12 +104,17 @@ fn typeid_base32_decode(s: &str) -> Result<[u8; 16]> {
     }
 
     let mut val: u128 = 0;
-    for ch in s.chars() {
-        let v = typeid_char_value(ch).ok_or_else(|| {
-            IdtError::ParseError(format!("Invalid TypeID Base32 character: '{}'", ch))
-        })?;
+    for (offset, ch) in s.char_indices() {
+        let v = typeid_char_value(ch)
+            .ok_or_else(|| IdtError::invalid_character(s, offset, "TypeID Base32"))?;
         val = (val << 5) | (v as u128);
     }
+    // 26 digits hold 130 bits; the top two must be zero
+    if s.as_bytes()[0] > b'7' {
+        return Err(IdtError::ParseError(
+            "TypeID suffix overflows 128 bits (first character must be 0-7)".to_string(),
+        ));
+    }
 
     Ok(val.to_be_bytes())
 }
@@ -127,6 +165,11 @@ pub struct ParsedTypeId {
 }
 
 impl ParsedTypeId {
+    /// Type prefix, empty for a bare suffix
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
     pub fn parse(input: &str) -> Result<Self> {
         let input_trimmed = input.trim();
 
@@ -148,7 +191,9 @@ impl ParsedTypeId {
             (String::new(), input_trimmed)
         };
 
-        let uuid_bytes = typeid_base32_decode(suffix)?;
+        let suffix_start = input_trimmed.len() - suffix.len();
+        let uuid_bytes =
+            typeid_base32_decode(suffix).map_err(|e| e.in_input(input_trimmed, suffix_start))?;
 
         Ok(Self {
             prefix,
@@ -180,6 +225,14 @@ impl ParsedTypeId {
     }
 }
 
+impl std::str::FromStr for ParsedTypeId {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedTypeId {
     fn kind(&self) -> IdKind {
         IdKind::TypeId
@@ -202,6 +255,14 @@ impl ParsedId for ParsedTypeId {
         self.timestamp_ms().map(Timestamp::new)
     }
 
+    fn components(&self) -> Option<Components> {
+        Some(Components::TypeId(TypeIdComponents {
+            prefix: self.prefix.clone(),
+            uuid: self.uuid(),
+            timestamp_ms: self.timestamp_ms(),
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let timestamp = self.timestamp();
@@ -222,6 +283,9 @@ impl ParsedId for ParsedTypeId {
             timestamp,
             timestamp_iso: timestamp.as_ref().map(|ts| ts.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: Some(format!("UUIDv{}", uuid.get_version_num())),
             variant: Some(self.prefix.clone()),
             random_bits: Some(62),
@@ -280,6 +344,10 @@ impl ParsedId for ParsedTypeId {
         }
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        Some(u128::from_be_bytes(self.uuid_bytes))
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
@@ -289,12 +357,16 @@ impl ParsedId for ParsedTypeId {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => u128::from_be_bytes(self.uuid_bytes).to_string(),
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
@@ -308,6 +380,27 @@ pub fn is_typeid(input: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_rejects_overflow() {
+        assert!(ParsedTypeId::parse("user_7zzzzzzzzzzzzzzzzzzzzzzzzz").is_ok());
+        assert!(ParsedTypeId::parse("user_8zzzzzzzzzzzzzzzzzzzzzzzzz").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_character_offset() {
+        let err = ParsedTypeId::parse("user_01h455vb4pex5vsknk084sn0uq")
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            IdtError::InvalidCharacter {
+                offset: 29,
+                character: 'u',
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn test_generate_with_prefix() {
         let generator = TypeIdGenerator::new("user");
@@ -1,29 +1,140 @@
+use crate::core::case::canonical_case;
+use crate::core::components::{Components, UlidComponents};
 use crate::core::encoding::{
-    EncodingFormat, encode_base32, encode_base58, encode_base64, encode_base64_url, encode_bits,
-    encode_bytes_spaced, encode_hex, encode_hex_upper,
+    EncodingFormat, encode_base32, encode_base58, encode_base58check, encode_base64,
+    encode_base64_ordered, encode_base64_url, encode_bits, encode_bytes_spaced, encode_hex,
+    encode_hex_upper, encode_java_longs,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
+use crate::core::rng::{IdRng, RandomPool};
+use crate::core::sortkey::encode_sort_key;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::fmt;
+use std::sync::Mutex;
 use ulid::Ulid;
 
+/// Largest timestamp a ULID can hold (48 bits of milliseconds)
+pub const ULID_MAX_TIMESTAMP_MS: u64 = (1 << 48) - 1;
+
+/// Largest random component of a ULID (80 bits)
+pub const ULID_MAX_RANDOM: u128 = (1 << 80) - 1;
+
 /// ULID generator
 #[derive(Default)]
-pub struct UlidGenerator;
+pub struct UlidGenerator {
+    timestamp_ms: Option<u64>,
+    state: Option<Mutex<UlidState>>,
+    pool: RandomPool,
+}
+
+/// The last ULID issued, persisted between invocations so that a new
+/// process continues the monotonic sequence (see [`UlidGenerator::with_state`])
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UlidState {
+    /// Millisecond timestamp of the last ULID
+    pub last_timestamp: u64,
+    /// 80-bit random component of the last ULID
+    pub last_random: u128,
+}
+
+impl UlidState {
+    /// The (timestamp, random) pair after the last one.
+    ///
+    /// A later millisecond starts from fresh randomness. Within the same
+    /// millisecond, or if the clock is behind the stored timestamp, the
+    /// random part is incremented, as in the ULID spec's monotonic mode.
+    /// When it overflows, the timestamp moves one millisecond ahead.
+    fn advance(&mut self, now_ms: u64, pool: &RandomPool) -> Result<(u64, u128)> {
+        let (timestamp, random) = if now_ms > self.last_timestamp {
+            (now_ms, random_part(pool))
+        } else if self.last_random < ULID_MAX_RANDOM {
+            (self.last_timestamp, self.last_random + 1)
+        } else {
+            (self.last_timestamp + 1, random_part(pool))
+        };
+        if timestamp > ULID_MAX_TIMESTAMP_MS {
+            return Err(IdtError::GenerationError(
+                "ULID timestamp space exhausted".to_string(),
+            ));
+        }
+        self.last_timestamp = timestamp;
+        self.last_random = random;
+        Ok((timestamp, random))
+    }
+}
 
 impl UlidGenerator {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Stamp every ULID with `ms` (Unix milliseconds) instead of the current time
+    pub fn with_timestamp(mut self, ms: u64) -> Self {
+        self.timestamp_ms = Some(ms);
+        self
+    }
+
+    /// Continue strictly increasing from `state`, e.g. loaded from a state file.
+    /// Every ULID from this generator sorts after the one before it.
+    pub fn with_state(mut self, state: UlidState) -> Self {
+        self.state = Some(Mutex::new(state));
+        self
+    }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.pool = RandomPool::new(rng);
+        self
+    }
+
+    /// The state after the last ULID issued, if created [`with_state`](Self::with_state)
+    pub fn state(&self) -> Option<UlidState> {
+        let state = self.state.as_ref()?;
+        Some(state.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    fn next(&self) -> Result<Ulid> {
+        let now = self
+            .timestamp_ms
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64);
+        if let Some(ref state) = self.state {
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            let (timestamp, random) = state.advance(now, &self.pool)?;
+            return Ok(Ulid::from_parts(timestamp, random));
+        }
+        Ok(ParsedUlid::from_parts(now, random_part(&self.pool))?.ulid)
     }
 }
 
+/// `ulid` as text, in the case set by the ULID case policy
+fn ulid_text(ulid: &Ulid) -> String {
+    let mut text = ulid.to_string();
+    canonical_case(IdKind::Ulid).apply(&mut text);
+    text
+}
+
+/// 80 fresh random bits for a ULID
+fn random_part(pool: &RandomPool) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes[6..].copy_from_slice(&pool.take::<10>());
+    u128::from_be_bytes(bytes)
+}
+
 impl IdGenerator for UlidGenerator {
     fn generate(&self) -> Result<String> {
-        let ulid = Ulid::new();
-        Ok(ulid.to_string())
+        Ok(ulid_text(&self.next()?))
+    }
+
+    fn write_to(&self, writer: &mut dyn fmt::Write) -> Result<()> {
+        let mut buf = [0u8; ulid::ULID_LEN];
+        let text = self.next()?.array_to_str(&mut buf);
+        canonical_case(IdKind::Ulid).apply(text);
+        writer.write_str(text)?;
+        Ok(())
     }
 }
 
@@ -38,14 +149,62 @@ impl ParsedUlid {
         let input_trimmed = input.trim();
 
         // ULID is case-insensitive
-        let ulid = Ulid::from_string(input_trimmed)
-            .map_err(|e| IdtError::ParseError(format!("Invalid ULID: {}", e)))?;
+        let ulid = Ulid::from_string(input_trimmed).map_err(|e| {
+            match input_trimmed
+                .char_indices()
+                .find(|&(_, c)| !is_crockford_char(c))
+            {
+                Some((offset, _)) if e == ulid::DecodeError::InvalidChar => {
+                    IdtError::invalid_character(input_trimmed, offset, "Crockford Base32")
+                }
+                _ => IdtError::ParseError(format!("Invalid ULID: {}", e)),
+            }
+        })?;
+        // The largest ULID is 7ZZZZZZZZZZZZZZZZZZZZZZZZZ; the ulid crate
+        // silently drops the bits above 128
+        if input_trimmed.as_bytes()[0] > b'7' {
+            return Err(IdtError::ParseError(
+                "Invalid ULID: overflows 128 bits (first character must be 0-7)".to_string(),
+            ));
+        }
 
         Ok(Self {
             ulid,
             input: input_trimmed.to_string(),
         })
     }
+
+    /// Build a ULID from a 48-bit millisecond timestamp and 80 random bits.
+    ///
+    /// `from_parts(ms, 0)` and `from_parts(ms, ULID_MAX_RANDOM)` are the
+    /// smallest and largest ULIDs for that millisecond, handy as range bounds.
+    pub fn from_parts(timestamp_ms: u64, random: u128) -> Result<Self> {
+        if timestamp_ms > ULID_MAX_TIMESTAMP_MS {
+            return Err(IdtError::InvalidArgument(format!(
+                "ULID timestamp {} exceeds 48 bits",
+                timestamp_ms
+            )));
+        }
+        if random > ULID_MAX_RANDOM {
+            return Err(IdtError::InvalidArgument(
+                "ULID random component exceeds 80 bits".to_string(),
+            ));
+        }
+
+        let ulid = Ulid::from_parts(timestamp_ms, random);
+        Ok(Self {
+            ulid,
+            input: ulid.to_string(),
+        })
+    }
+}
+
+impl std::str::FromStr for ParsedUlid {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
 }
 
 impl ParsedId for ParsedUlid {
@@ -54,7 +213,7 @@ impl ParsedId for ParsedUlid {
     }
 
     fn canonical(&self) -> String {
-        self.ulid.to_string()
+        ulid_text(&self.ulid)
     }
 
     fn as_bytes(&self) -> Vec<u8> {
@@ -65,6 +224,13 @@ impl ParsedId for ParsedUlid {
         Some(Timestamp::new(self.ulid.timestamp_ms()))
     }
 
+    fn components(&self) -> Option<Components> {
+        Some(Components::Ulid(UlidComponents {
+            timestamp_ms: self.ulid.timestamp_ms(),
+            random: self.ulid.random(),
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let timestamp = self.timestamp().expect("ULID always has a timestamp");
@@ -83,6 +249,9 @@ impl ParsedId for ParsedUlid {
             timestamp: Some(timestamp),
             timestamp_iso: Some(timestamp.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: None,
             random_bits: Some(80), // 10 bytes * 8 bits
@@ -120,6 +289,10 @@ impl ParsedId for ParsedUlid {
         ValidationResult::valid("ulid")
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        Some(self.ulid.0)
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
@@ -129,20 +302,27 @@ impl ParsedId for ParsedUlid {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => {
-                u128::from_be_bytes(bytes.try_into().expect("ULID is 128-bit (16 bytes)"))
-                    .to_string()
-            }
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
 
 /// Check if a string can be parsed as ULID
+/// Characters the `ulid` crate decodes: Crockford Base32 (no I, L, O, U), either case
+fn is_crockford_char(c: char) -> bool {
+    c.is_ascii_digit()
+        || (c.is_ascii_alphabetic() && !matches!(c.to_ascii_uppercase(), 'I' | 'L' | 'O' | 'U'))
+}
+
 pub fn is_ulid(input: &str) -> bool {
     ParsedUlid::parse(input).is_ok()
 }
@@ -161,6 +341,48 @@ pub fn uuid_to_ulid(uuid: &uuid::Uuid) -> Ulid {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_state_advance() {
+        let mut state = UlidState::default();
+        let pool = RandomPool::default();
+        let (ts, first) = state.advance(1000, &pool).unwrap();
+        assert_eq!(ts, 1000);
+
+        // Same millisecond and a clock behind the state both increment
+        assert_eq!(state.advance(1000, &pool).unwrap(), (1000, first + 1));
+        assert_eq!(state.advance(900, &pool).unwrap(), (1000, first + 2));
+
+        // A full random part moves to the next millisecond
+        state.last_random = ULID_MAX_RANDOM;
+        assert_eq!(state.advance(1000, &pool).unwrap().0, 1001);
+
+        state.last_timestamp = ULID_MAX_TIMESTAMP_MS;
+        state.last_random = ULID_MAX_RANDOM;
+        assert!(state.advance(0, &pool).is_err());
+    }
+
+    #[test]
+    fn test_generator_with_state_is_monotonic() {
+        let generator = UlidGenerator::new()
+            .with_timestamp(1_700_000_000_000)
+            .with_state(UlidState::default());
+        let ids = generator.generate_many(100).unwrap();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // A second generator resumes after the first
+        let resumed = UlidGenerator::new()
+            .with_timestamp(1_700_000_000_000)
+            .with_state(generator.state().unwrap());
+        assert!(resumed.generate().unwrap() > ids[99]);
+    }
+
+    #[test]
+    fn test_write_to() {
+        let mut buf = String::new();
+        UlidGenerator::new().write_to(&mut buf).unwrap();
+        assert!(ParsedUlid::parse(&buf).is_ok());
+    }
+
     #[test]
     fn test_generate() {
         let generator = UlidGenerator::new();
@@ -188,6 +410,57 @@ mod tests {
         assert_eq!(parsed_upper.canonical(), parsed_lower.canonical());
     }
 
+    #[test]
+    fn test_parse_rejects_overflow() {
+        assert!(ParsedUlid::parse("7ZZZZZZZZZZZZZZZZZZZZZZZZZ").is_ok());
+        assert!(matches!(
+            ParsedUlid::parse("80000000000000000000000000"),
+            Err(IdtError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_invalid_character_offset() {
+        let err = ParsedUlid::parse("01ARZ3NDEKTSV4RRFFQ69G5FAU")
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            IdtError::InvalidCharacter {
+                offset: 25,
+                character: 'U',
+                ..
+            }
+        ));
+        let err = ParsedUlid::parse("01ARZ3NDEK").err().unwrap();
+        assert!(matches!(err, IdtError::ParseError(_)));
+    }
+
+    #[test]
+    fn test_with_timestamp() {
+        let generator = UlidGenerator::new().with_timestamp(1_469_922_850_259);
+        let id = generator.generate().unwrap();
+        let parsed = ParsedUlid::parse(&id).unwrap();
+        assert_eq!(parsed.timestamp().unwrap().millis, 1_469_922_850_259);
+        assert_ne!(id, generator.generate().unwrap());
+
+        let overflow = UlidGenerator::new().with_timestamp(ULID_MAX_TIMESTAMP_MS + 1);
+        assert!(overflow.generate().is_err());
+    }
+
+    #[test]
+    fn test_from_parts() {
+        let parsed = ParsedUlid::from_parts(1_469_922_850_259, 0).unwrap();
+        assert_eq!(parsed.canonical(), "01ARZ3NDEK0000000000000000");
+
+        let max = ParsedUlid::from_parts(ULID_MAX_TIMESTAMP_MS, ULID_MAX_RANDOM).unwrap();
+        assert_eq!(max.canonical(), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+        assert_eq!(max.as_u128(), Some(u128::MAX));
+
+        assert!(ParsedUlid::from_parts(ULID_MAX_TIMESTAMP_MS + 1, 0).is_err());
+        assert!(ParsedUlid::from_parts(0, ULID_MAX_RANDOM + 1).is_err());
+    }
+
     #[test]
     fn test_ulid_uuid_conversion() {
         let ulid = Ulid::new();
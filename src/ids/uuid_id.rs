@@ -1,29 +1,79 @@
+use crate::core::components::{Components, UuidComponents};
 use crate::core::encoding::{
-    EncodingFormat, encode_base32, encode_base58, encode_base64, encode_base64_url, encode_bits,
-    encode_bytes_spaced, encode_hex, encode_hex_upper,
+    EncodingFormat, decode_base64, encode_base32, encode_base58, encode_base58check, encode_base64,
+    encode_base64_ordered, encode_base64_url, encode_bits, encode_bytes_spaced, encode_hex,
+    encode_hex_upper, encode_java_longs,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
+use crate::core::rng::{IdRng, RandomPool};
+use crate::core::sortkey::encode_sort_key;
+use crate::ids::well_known::{
+    bluetooth_short, expand_bluetooth_short, format_bluetooth_short, well_known_uuid,
+};
+use hmac::{Hmac, KeyInit, Mac};
 use serde_json::json;
+use sha2::Sha256;
+use std::fmt;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU32, Ordering};
 use uuid::Uuid;
 
+/// Text form used when printing a UUID
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum UuidStyle {
+    /// 550e8400-e29b-41d4-a716-446655440000
+    #[default]
+    Hyphenated,
+    /// 550e8400e29b41d4a716446655440000
+    Simple,
+    /// {550e8400-e29b-41d4-a716-446655440000}
+    Braced,
+    /// urn:uuid:550e8400-e29b-41d4-a716-446655440000
+    Urn,
+    /// 550E8400-E29B-41D4-A716-446655440000
+    Upper,
+}
+
+impl UuidStyle {
+    /// Encode `uuid` in this style into `buf`
+    pub fn encode<'a>(self, uuid: &Uuid, buf: &'a mut [u8]) -> &'a str {
+        match self {
+            UuidStyle::Hyphenated => uuid.hyphenated().encode_lower(buf),
+            UuidStyle::Simple => uuid.simple().encode_lower(buf),
+            UuidStyle::Braced => uuid.braced().encode_lower(buf),
+            UuidStyle::Urn => uuid.urn().encode_lower(buf),
+            UuidStyle::Upper => uuid.hyphenated().encode_upper(buf),
+        }
+    }
+
+    pub fn format(self, uuid: &Uuid) -> String {
+        self.encode(uuid, &mut Uuid::encode_buffer()).to_string()
+    }
+}
+
 /// UUID generator with configurable version
 pub struct UuidGenerator {
     pub version: u8,
     pub namespace: Option<Uuid>,
     pub name: Option<String>,
+    pub style: UuidStyle,
+    /// Unix milliseconds for v1, v6, and v7 UUIDs; the current time if unset
+    pub timestamp_ms: Option<u64>,
+    /// Random bits for v4 and v7 UUIDs. If unset, v7 UUIDs use the uuid
+    /// crate's own source, along with its counter keeping them in order.
+    pub rng: Option<IdRng>,
+    /// Random bytes for v4 (and v7 with `rng`), drawn a chunk at a time
+    pool: RandomPool,
 }
 
 impl Default for UuidGenerator {
     fn default() -> Self {
-        Self {
-            version: 4,
-            namespace: None,
-            name: None,
-        }
+        Self::new(4)
     }
 }
 
@@ -33,6 +83,10 @@ impl UuidGenerator {
             version,
             namespace: None,
             name: None,
+            style: UuidStyle::default(),
+            timestamp_ms: None,
+            rng: None,
+            pool: RandomPool::default(),
         }
     }
 
@@ -52,6 +106,12 @@ impl UuidGenerator {
         Self::new(7)
     }
 
+    /// A v7 generator stamping every UUID with `ms` (Unix milliseconds),
+    /// e.g. to backfill rows whose creation time is known
+    pub fn v7_at(ms: u64) -> Self {
+        Self::v7().with_timestamp(ms)
+    }
+
     pub fn nil() -> Self {
         Self::new(0)
     }
@@ -69,27 +129,97 @@ impl UuidGenerator {
         self.name = Some(name);
         self
     }
+
+    pub fn with_style(mut self, style: UuidStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_timestamp(mut self, ms: u64) -> Self {
+        self.timestamp_ms = Some(ms);
+        self
+    }
+
+    pub fn with_rng(mut self, rng: IdRng) -> Self {
+        self.pool = RandomPool::new(rng.clone());
+        self.rng = Some(rng);
+        self
+    }
 }
 
-impl IdGenerator for UuidGenerator {
-    fn generate(&self) -> Result<String> {
+/// Node ID written into generated v1 and v6 UUIDs
+const NODE: [u8; 6] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+/// Last Unix millisecond the 60-bit v1/v6 timestamp can hold (in 5236)
+const MAX_GREGORIAN_MS: u64 = ((1 << 60) - 1) / 10_000 - 12_219_292_800_000;
+
+/// A v1/v6 timestamp at `ms` (Unix milliseconds). UUIDs made for the same
+/// millisecond step through its 100ns ticks and the clock sequence, so they
+/// still differ.
+fn gregorian_at(ms: u64) -> Result<uuid::Timestamp> {
+    static CLOCK_SEQUENCE: LazyLock<uuid::ContextV1> = LazyLock::new(uuid::ContextV1::new_random);
+    static TICKS: AtomicU32 = AtomicU32::new(0);
+    if ms > MAX_GREGORIAN_MS {
+        return Err(IdtError::InvalidArgument(format!(
+            "UUIDv1/v6 timestamp {} is past the end of the 60-bit range",
+            ms
+        )));
+    }
+    let tick = TICKS.fetch_add(1, Ordering::Relaxed) % 10_000;
+    Ok(uuid::Timestamp::from_unix(
+        &*CLOCK_SEQUENCE,
+        ms / 1000,
+        (ms % 1000) as u32 * 1_000_000 + tick * 100,
+    ))
+}
+
+impl UuidGenerator {
+    fn next_uuid(&self) -> Result<Uuid> {
         let uuid = match self.version {
             0 => Uuid::nil(),
             255 => Uuid::max(),
-            1 => Uuid::now_v1(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            1 => match self.timestamp_ms {
+                Some(ms) => Uuid::new_v1(gregorian_at(ms)?, &NODE),
+                None => Uuid::now_v1(&NODE),
+            },
             3 => {
                 let ns = self.namespace.unwrap_or(Uuid::NAMESPACE_DNS);
                 let name = self.name.as_deref().unwrap_or("example.com");
                 Uuid::new_v3(&ns, name.as_bytes())
             }
-            4 => Uuid::new_v4(),
+            4 => uuid::Builder::from_random_bytes(self.pool.take()).into_uuid(),
             5 => {
                 let ns = self.namespace.unwrap_or(Uuid::NAMESPACE_DNS);
                 let name = self.name.as_deref().unwrap_or("example.com");
                 Uuid::new_v5(&ns, name.as_bytes())
             }
-            6 => Uuid::now_v6(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
-            7 => Uuid::now_v7(),
+            6 => match self.timestamp_ms {
+                Some(ms) => Uuid::new_v6(gregorian_at(ms)?, &NODE),
+                None => Uuid::now_v6(&NODE),
+            },
+            7 => match (self.timestamp_ms, &self.rng) {
+                (Some(ms), _) if ms >= 1 << 48 => {
+                    // The v7 timestamp field holds 48 bits of milliseconds
+                    return Err(IdtError::InvalidArgument(format!(
+                        "UUIDv7 timestamp {} exceeds 48 bits",
+                        ms
+                    )));
+                }
+                (ms, Some(_)) => uuid::Builder::from_unix_timestamp_millis(
+                    ms.unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64),
+                    &self.pool.take(),
+                )
+                .into_uuid(),
+                (Some(ms), None) => {
+                    let ts = uuid::Timestamp::from_unix(
+                        uuid::NoContext,
+                        ms / 1000,
+                        (ms % 1000) as u32 * 1_000_000,
+                    );
+                    Uuid::new_v7(ts)
+                }
+                (None, None) => Uuid::now_v7(),
+            },
             _ => {
                 return Err(IdtError::InvalidArgument(format!(
                     "Unsupported UUID version: {}",
@@ -97,8 +227,212 @@ impl IdGenerator for UuidGenerator {
                 )));
             }
         };
-        Ok(uuid.to_string())
+        Ok(uuid)
+    }
+}
+
+impl IdGenerator for UuidGenerator {
+    fn generate(&self) -> Result<String> {
+        Ok(self.style.format(&self.next_uuid()?))
     }
+
+    fn write_to(&self, writer: &mut dyn fmt::Write) -> Result<()> {
+        let mut buf = Uuid::encode_buffer();
+        writer.write_str(self.style.encode(&self.next_uuid()?, &mut buf))?;
+        Ok(())
+    }
+}
+
+/// A v4 UUID with its 122 random bits drawn from `rng`
+pub fn random_v4(rng: &IdRng) -> Uuid {
+    let mut bytes = [0u8; 16];
+    rng.fill(&mut bytes);
+    uuid::Builder::from_random_bytes(bytes).into_uuid()
+}
+
+/// A v7 UUID at `ms` (Unix milliseconds) with its 74 random bits drawn from `rng`
+pub fn random_v7(rng: &IdRng, ms: u64) -> Uuid {
+    let mut random = [0u8; 10];
+    rng.fill(&mut random);
+    uuid::Builder::from_unix_timestamp_millis(ms, &random).into_uuid()
+}
+
+/// Resolve a well-known namespace name (dns, url, oid, x500) or a UUID literal
+pub fn parse_namespace(input: &str) -> Result<Uuid> {
+    match input.trim().to_ascii_lowercase().as_str() {
+        "dns" => Ok(Uuid::NAMESPACE_DNS),
+        "url" => Ok(Uuid::NAMESPACE_URL),
+        "oid" => Ok(Uuid::NAMESPACE_OID),
+        "x500" => Ok(Uuid::NAMESPACE_X500),
+        other => Uuid::parse_str(other).map_err(|_| {
+            IdtError::InvalidArgument(format!(
+                "Invalid namespace '{}'. Use dns, url, oid, x500, or a UUID.",
+                input
+            ))
+        }),
+    }
+}
+
+/// Build a name-based UUID (v3 = MD5, v5 = SHA-1) from arbitrary bytes
+pub fn name_based_uuid(version: u8, namespace: &Uuid, name: &[u8]) -> Result<Uuid> {
+    match version {
+        3 => Ok(Uuid::new_v3(namespace, name)),
+        5 => Ok(Uuid::new_v5(namespace, name)),
+        _ => Err(IdtError::InvalidArgument(format!(
+            "UUID version {} is not name-based. Use 3 or 5.",
+            version
+        ))),
+    }
+}
+
+/// Mint a v7 UUID at the instant of a v1 or v6 UUID.
+///
+/// This is lossy: the 100ns timestamp is truncated to milliseconds, and the
+/// clock sequence and node are dropped. With a `key`, the random bits are
+/// HMAC-SHA256 of the original UUID, so the same input always gives the same
+/// v7. Without one they are fresh random bits.
+pub fn upgrade_to_v7(uuid: &Uuid, key: Option<&[u8]>) -> Result<Uuid> {
+    let version = uuid.get_version_num();
+    if !matches!(version, 1 | 6) {
+        return Err(IdtError::InvalidArgument(format!(
+            "Only UUID v1 and v6 can be converted to v7, got version {}",
+            version
+        )));
+    }
+    let (ticks, _) = uuid
+        .get_timestamp()
+        .map(|ts| ts.to_gregorian())
+        .ok_or_else(|| IdtError::InvalidArgument("UUID has no timestamp".to_string()))?;
+    // Gregorian ticks are 100ns intervals since 1582-10-15; v7 can't go before 1970
+    let ms = ticks
+        .checked_sub(uuid::timestamp::UUID_TICKS_BETWEEN_EPOCHS)
+        .map(|unix_ticks| unix_ticks / 10_000)
+        .ok_or_else(|| {
+            IdtError::InvalidArgument(format!(
+                "{} predates the Unix epoch and has no UUIDv7 equivalent",
+                uuid
+            ))
+        })?;
+
+    let mut random = [0u8; 10];
+    match key {
+        Some(key) => {
+            let mut mac =
+                Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+            mac.update(uuid.as_bytes());
+            random.copy_from_slice(&mac.finalize().into_bytes()[..10]);
+        }
+        None => random.copy_from_slice(&Uuid::new_v4().as_bytes()[..10]),
+    }
+    Ok(uuid::Builder::from_unix_timestamp_millis(ms, &random).into_uuid())
+}
+
+/// Strip a `urn:uuid:` prefix or Windows-style `{...}` braces from a UUID.
+///
+/// Returns the input unchanged if it has neither wrapper.
+pub fn strip_uuid_wrapper(input: &str) -> &str {
+    const URN_PREFIX: &str = "urn:uuid:";
+    if input.len() > URN_PREFIX.len()
+        && input.is_char_boundary(URN_PREFIX.len())
+        && input[..URN_PREFIX.len()].eq_ignore_ascii_case(URN_PREFIX)
+    {
+        return &input[URN_PREFIX.len()..];
+    }
+    input
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .unwrap_or(input)
+}
+
+/// Byte order a legacy MongoDB driver used for UUIDs stored as BSON binary
+/// subtype 3, before subtype 4 fixed the layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum LegacyUuidOrder {
+    /// .NET driver: the first three fields little-endian, as `Guid.ToByteArray`
+    #[cfg_attr(feature = "cli", value(name = "csharp"))]
+    CSharp,
+    /// Java driver: each 8-byte half reversed
+    Java,
+    /// Python driver: standard byte order
+    Python,
+}
+
+impl LegacyUuidOrder {
+    /// Name as given to `--uuid-legacy`
+    pub fn name(self) -> &'static str {
+        match self {
+            LegacyUuidOrder::CSharp => "csharp",
+            LegacyUuidOrder::Java => "java",
+            LegacyUuidOrder::Python => "python",
+        }
+    }
+
+    /// Turn stored bytes into RFC 4122 byte order. Every shuffle is its own
+    /// inverse, so this also turns a UUID into the bytes the driver stores.
+    pub fn unshuffle(self, mut bytes: [u8; 16]) -> [u8; 16] {
+        match self {
+            LegacyUuidOrder::CSharp => {
+                bytes[0..4].reverse();
+                bytes[4..6].reverse();
+                bytes[6..8].reverse();
+            }
+            LegacyUuidOrder::Java => {
+                bytes[0..8].reverse();
+                bytes[8..16].reverse();
+            }
+            LegacyUuidOrder::Python => {}
+        }
+        bytes
+    }
+}
+
+/// Decode a legacy BSON binary UUID written by a driver using `order`.
+///
+/// Accepts the base64 payload on its own, in a mongosh `BinData(3, "...")`,
+/// or in Extended JSON (`{"$binary": {"base64": "...", "subType": "03"}}` or
+/// the older `{"$binary": "...", "$type": "03"}`).
+pub fn decode_legacy_uuid(input: &str, order: LegacyUuidOrder) -> Result<Uuid> {
+    let input = input.trim();
+    let payload = if input.starts_with('{') {
+        let json: serde_json::Value = serde_json::from_str(input)
+            .map_err(|e| IdtError::ParseError(format!("Invalid Extended JSON binary: {}", e)))?;
+        let binary = &json["$binary"];
+        binary["base64"]
+            .as_str()
+            .or_else(|| binary.as_str())
+            .ok_or_else(|| {
+                IdtError::ParseError("Extended JSON has no $binary payload".to_string())
+            })?
+            .to_string()
+    } else if let Some(args) = input
+        .strip_prefix("BinData(")
+        .and_then(|s| s.strip_suffix(')'))
+    {
+        let (subtype, data) = args.split_once(',').ok_or_else(|| {
+            IdtError::ParseError("BinData needs a subtype and a payload".to_string())
+        })?;
+        if subtype.trim() != "3" {
+            return Err(IdtError::ParseError(format!(
+                "BinData subtype {} is not a legacy UUID (subtype 3)",
+                subtype.trim()
+            )));
+        }
+        data.trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string()
+    } else {
+        input.to_string()
+    };
+
+    let bytes = decode_base64(&payload)?;
+    let bytes: [u8; 16] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+        IdtError::ParseError(format!(
+            "Legacy UUID payload is {} bytes, expected 16",
+            bytes.len()
+        ))
+    })?;
+    Ok(Uuid::from_bytes(order.unshuffle(bytes)))
 }
 
 /// Parsed UUID value
@@ -110,9 +444,10 @@ pub struct ParsedUuid {
 impl ParsedUuid {
     pub fn parse(input: &str) -> Result<Self> {
         let input_trimmed = input.trim();
+        let bare = strip_uuid_wrapper(input_trimmed);
 
         // Try parsing with dashes
-        if let Ok(uuid) = Uuid::parse_str(input_trimmed) {
+        if let Ok(uuid) = Uuid::parse_str(bare) {
             return Ok(Self {
                 uuid,
                 input: input_trimmed.to_string(),
@@ -120,7 +455,7 @@ impl ParsedUuid {
         }
 
         // Try parsing without dashes
-        let normalized = input_trimmed.replace('-', "");
+        let normalized = bare.replace('-', "");
         if normalized.len() == 32
             && let Ok(uuid) = Uuid::parse_str(&normalized)
         {
@@ -130,9 +465,48 @@ impl ParsedUuid {
             });
         }
 
+        // Bluetooth short form, e.g. 0x180D
+        if let Some(uuid) = expand_bluetooth_short(bare) {
+            return Ok(Self {
+                uuid,
+                input: input_trimmed.to_string(),
+            });
+        }
+
         Err(IdtError::ParseError(format!("Invalid UUID: {}", input)))
     }
 
+    /// Parse `input` and require it to be a UUID of `kind`: its version with
+    /// the RFC 4122 variant, or exactly nil or max. `IdKind::Uuid` accepts any UUID.
+    pub fn parse_kind(input: &str, kind: IdKind) -> Result<Self> {
+        let parsed = Self::parse(input)?;
+        let actual = parsed.kind();
+        if kind == IdKind::Uuid {
+            return Ok(parsed);
+        }
+        if actual != kind {
+            let found = match actual {
+                IdKind::Uuid => format!("UUID version {}", parsed.uuid.get_version_num()),
+                _ => actual.name().to_string(),
+            };
+            return Err(IdtError::ValidationError(format!(
+                "Expected {}, got {}",
+                kind.name(),
+                found
+            )));
+        }
+        if !matches!(kind, IdKind::UuidNil | IdKind::UuidMax)
+            && parsed.uuid.get_variant() != uuid::Variant::RFC4122
+        {
+            return Err(IdtError::ValidationError(format!(
+                "Expected {}, but the variant is {}, not RFC4122",
+                kind.name(),
+                parsed.get_variant()
+            )));
+        }
+        Ok(parsed)
+    }
+
     fn get_version(&self) -> Option<u8> {
         if self.uuid.is_nil() {
             return Some(0);
@@ -297,6 +671,14 @@ impl ParsedUuid {
     }
 }
 
+impl std::str::FromStr for ParsedUuid {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedUuid {
     fn kind(&self) -> IdKind {
         self.get_version()
@@ -315,6 +697,9 @@ impl ParsedId for ParsedUuid {
     fn timestamp(&self) -> Option<Timestamp> {
         let version = self.get_version()?;
         match version {
+            // The Bluetooth base UUID is v1, but its "timestamp" bits hold
+            // an assigned number
+            1 if bluetooth_short(&self.uuid).is_some() => None,
             1 | 6 => {
                 // UUID v1 and v6 use 100-nanosecond intervals since Oct 15, 1582
                 let ts = self.uuid.get_timestamp()?;
@@ -333,6 +718,14 @@ impl ParsedId for ParsedUuid {
         }
     }
 
+    fn components(&self) -> Option<Components> {
+        Some(Components::Uuid(UuidComponents {
+            version: self.get_version(),
+            variant: self.get_variant(),
+            timestamp_ms: self.timestamp().map(|ts| ts.millis),
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let version = self.get_version();
@@ -346,6 +739,18 @@ impl ParsedId for ParsedUuid {
         if let Some(ts) = &timestamp {
             components["timestamp_ms"] = json!(ts.millis);
         }
+        if let Some(known) = well_known_uuid(&self.uuid) {
+            components["well_known"] = json!({
+                "name": known.name,
+                "category": known.category,
+            });
+        }
+        if let Some(short) = bluetooth_short(&self.uuid) {
+            components["bluetooth"] = json!({
+                "short": format_bluetooth_short(short),
+                "bits": if short <= 0xffff { 16 } else { 32 },
+            });
+        }
 
         // Add random bits info based on version
         let random_bits = match version {
@@ -363,6 +768,9 @@ impl ParsedId for ParsedUuid {
             timestamp,
             timestamp_iso: timestamp.as_ref().map(|ts| ts.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: version.map(|v| format!("{}", v)),
             variant: Some(self.get_variant().to_string()),
             random_bits,
@@ -385,6 +793,10 @@ impl ParsedId for ParsedUuid {
         ValidationResult::valid(self.kind().name())
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        Some(self.uuid.as_u128())
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
@@ -394,15 +806,16 @@ impl ParsedId for ParsedUuid {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => {
-                u128::from_be_bytes(bytes.try_into().expect("UUID is 128-bit (16 bytes)"))
-                    .to_string()
-            }
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
@@ -415,6 +828,72 @@ pub fn is_uuid(input: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::rng::POOL_CHUNK_SIZE;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_upgrade_to_v7_keeps_instant() {
+        // 2024-01-01T00:00:00.123Z plus 4567 sub-millisecond ticks
+        let ticks = uuid::timestamp::UUID_TICKS_BETWEEN_EPOCHS + 1_704_067_200_123 * 10_000 + 4567;
+        let ts = uuid::Timestamp::from_gregorian_time(ticks, 42);
+        let node = [1, 2, 3, 4, 5, 6];
+        for old in [Uuid::new_v1(ts, &node), Uuid::new_v6(ts, &node)] {
+            let v7 = upgrade_to_v7(&old, None).unwrap();
+            assert_eq!(v7.get_version_num(), 7);
+            assert_eq!(v7.get_variant(), uuid::Variant::RFC4122);
+            let parsed = ParsedUuid::parse(&v7.to_string()).unwrap();
+            assert_eq!(parsed.timestamp().unwrap().millis, 1_704_067_200_123);
+        }
+    }
+
+    #[test]
+    fn test_upgrade_to_v7_keyed_is_deterministic() {
+        let old = Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+        let a = upgrade_to_v7(&old, Some(b"secret")).unwrap();
+        assert_eq!(a, upgrade_to_v7(&old, Some(b"secret")).unwrap());
+        assert_ne!(a, upgrade_to_v7(&old, Some(b"other")).unwrap());
+        assert_ne!(a, upgrade_to_v7(&old, None).unwrap());
+    }
+
+    #[test]
+    fn test_upgrade_to_v7_rejects_other_versions() {
+        let v4 = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert!(upgrade_to_v7(&v4, None).is_err());
+        // Gregorian tick 0 is 1582-10-15
+        let ancient = Uuid::new_v1(uuid::Timestamp::from_gregorian_time(0, 0), &[0; 6]);
+        let err = upgrade_to_v7(&ancient, None).unwrap_err();
+        assert!(err.to_string().contains("predates the Unix epoch"));
+    }
+
+    #[test]
+    fn test_generate_into_appends() {
+        let mut buf = String::from("id=");
+        UuidGenerator::v7().generate_into(&mut buf).unwrap();
+        assert!(buf.starts_with("id="));
+        let uuid = Uuid::parse_str(&buf[3..]).unwrap();
+        assert_eq!(uuid.get_version_num(), 7);
+    }
+
+    #[test]
+    fn test_parse_namespace() {
+        assert_eq!(parse_namespace("dns").unwrap(), Uuid::NAMESPACE_DNS);
+        assert_eq!(parse_namespace("URL").unwrap(), Uuid::NAMESPACE_URL);
+        assert_eq!(
+            parse_namespace("6ba7b812-9dad-11d1-80b4-00c04fd430c8").unwrap(),
+            Uuid::NAMESPACE_OID
+        );
+        assert!(parse_namespace("bogus").is_err());
+    }
+
+    #[test]
+    fn test_name_based_uuid_known_vectors() {
+        // RFC 9562 Appendix A.2 / A.4
+        let v3 = name_based_uuid(3, &Uuid::NAMESPACE_DNS, b"www.example.com").unwrap();
+        assert_eq!(v3.to_string(), "5df41881-3aed-3515-88a7-2f4a814cf09e");
+        let v5 = name_based_uuid(5, &Uuid::NAMESPACE_DNS, b"www.example.com").unwrap();
+        assert_eq!(v5.to_string(), "2ed6657d-e927-568b-95e1-2665a8aea6a2");
+        assert!(name_based_uuid(4, &Uuid::NAMESPACE_DNS, b"x").is_err());
+    }
 
     #[test]
     fn test_generate_v4() {
@@ -433,6 +912,34 @@ mod tests {
         assert!(parsed.timestamp().is_some());
     }
 
+    #[test]
+    fn test_generate_v7_at() {
+        let generator = UuidGenerator::v7_at(1_700_000_000_123);
+        let id = generator.generate().unwrap();
+        let parsed = ParsedUuid::parse(&id).unwrap();
+        assert_eq!(parsed.kind(), IdKind::UuidV7);
+        assert_eq!(parsed.timestamp().unwrap().millis, 1_700_000_000_123);
+        assert_ne!(id, generator.generate().unwrap());
+
+        assert!(UuidGenerator::v7_at(1 << 48).generate().is_err());
+    }
+
+    #[test]
+    fn test_generate_v1_v6_at() {
+        for generator in [UuidGenerator::v1(), UuidGenerator::v6()] {
+            let generator = generator.with_timestamp(1_700_000_000_123);
+            let ids: std::collections::HashSet<String> =
+                (0..100).map(|_| generator.generate().unwrap()).collect();
+            assert_eq!(ids.len(), 100);
+            for id in &ids {
+                let parsed = ParsedUuid::parse(id).unwrap();
+                assert_eq!(parsed.timestamp().unwrap().millis, 1_700_000_000_123);
+            }
+        }
+        let generator = UuidGenerator::v1().with_timestamp(MAX_GREGORIAN_MS + 1);
+        assert!(generator.generate().is_err());
+    }
+
     #[test]
     fn test_parse_uuid() {
         let input = "550e8400-e29b-41d4-a716-446655440000";
@@ -440,6 +947,73 @@ mod tests {
         assert_eq!(parsed.kind(), IdKind::UuidV4);
     }
 
+    #[test]
+    fn test_parse_urn_and_braced() {
+        for input in [
+            "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+            "URN:UUID:550e8400-e29b-41d4-a716-446655440000",
+            "{550E8400-E29B-41D4-A716-446655440000}",
+            "{550e8400e29b41d4a716446655440000}",
+        ] {
+            let parsed = ParsedUuid::parse(input).unwrap();
+            assert_eq!(parsed.kind(), IdKind::UuidV4, "{}", input);
+            assert_eq!(parsed.canonical(), "550e8400-e29b-41d4-a716-446655440000");
+        }
+        assert!(ParsedUuid::parse("{550e8400-e29b-41d4-a716-446655440000").is_err());
+        assert!(ParsedUuid::parse("urn:uuid:").is_err());
+    }
+
+    #[test]
+    fn test_uuid_styles() {
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(
+            UuidStyle::Hyphenated.format(&uuid),
+            "550e8400-e29b-41d4-a716-446655440000"
+        );
+        assert_eq!(
+            UuidStyle::Simple.format(&uuid),
+            "550e8400e29b41d4a716446655440000"
+        );
+        assert_eq!(
+            UuidStyle::Braced.format(&uuid),
+            "{550e8400-e29b-41d4-a716-446655440000}"
+        );
+        assert_eq!(
+            UuidStyle::Urn.format(&uuid),
+            "urn:uuid:550e8400-e29b-41d4-a716-446655440000"
+        );
+        assert_eq!(
+            UuidStyle::Upper.format(&uuid),
+            "550E8400-E29B-41D4-A716-446655440000"
+        );
+
+        // Every style parses back to the same UUID
+        for style in [
+            UuidStyle::Simple,
+            UuidStyle::Braced,
+            UuidStyle::Urn,
+            UuidStyle::Upper,
+        ] {
+            let parsed = ParsedUuid::parse(&style.format(&uuid)).unwrap();
+            assert_eq!(parsed.canonical(), uuid.to_string());
+        }
+    }
+
+    #[test]
+    fn test_generate_with_style() {
+        let id = UuidGenerator::v7()
+            .with_style(UuidStyle::Urn)
+            .generate()
+            .unwrap();
+        assert!(id.starts_with("urn:uuid:"));
+        let mut buf = String::new();
+        UuidGenerator::v4()
+            .with_style(UuidStyle::Braced)
+            .generate_into(&mut buf)
+            .unwrap();
+        assert!(buf.starts_with('{') && buf.ends_with('}'));
+    }
+
     #[test]
     fn test_nil_uuid() {
         let generator = UuidGenerator::nil();
@@ -504,6 +1078,38 @@ mod tests {
         assert!(inspection.version.is_some());
     }
 
+    #[test]
+    fn test_uuid_inspect_well_known() {
+        let parsed = ParsedUuid::parse("C12A7328-F81F-11D2-BA4B-00A0C93EC93B").unwrap();
+        let components = parsed.inspect().components.unwrap();
+        assert_eq!(components["well_known"]["name"], "EFI System Partition");
+        assert_eq!(components["well_known"]["category"], "GPT partition type");
+
+        let parsed = ParsedUuid::parse("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert!(
+            parsed
+                .inspect()
+                .components
+                .unwrap()
+                .get("well_known")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_uuid_bluetooth_short() {
+        let parsed = ParsedUuid::parse("0x180d").unwrap();
+        assert_eq!(parsed.canonical(), "0000180d-0000-1000-8000-00805f9b34fb");
+        assert!(parsed.timestamp().is_none());
+        let components = parsed.inspect().components.unwrap();
+        assert_eq!(components["bluetooth"]["short"], "0x180D");
+        assert_eq!(components["bluetooth"]["bits"], 16);
+
+        let expanded = ParsedUuid::parse("0000180d-0000-1000-8000-00805f9b34fb").unwrap();
+        let components = expanded.inspect().components.unwrap();
+        assert_eq!(components["bluetooth"]["short"], "0x180D");
+    }
+
     #[test]
     fn test_uuid_validate() {
         let input = "550e8400-e29b-41d4-a716-446655440000";
@@ -553,6 +1159,43 @@ mod tests {
         assert!(int_str.parse::<u128>().is_ok());
     }
 
+    #[test]
+    fn test_decode_legacy_uuid() {
+        // 00112233-4455-6677-8899-aabbccddeeff as each driver stores it
+        let expected = Uuid::parse_str("00112233-4455-6677-8899-aabbccddeeff").unwrap();
+        let cases = [
+            (LegacyUuidOrder::Python, "ABEiM0RVZneImaq7zN3u/w=="),
+            (LegacyUuidOrder::Java, "d2ZVRDMiEQD/7t3Mu6qZiA=="),
+            (LegacyUuidOrder::CSharp, "MyIRAFVEd2aImaq7zN3u/w=="),
+        ];
+        for (order, payload) in cases {
+            assert_eq!(decode_legacy_uuid(payload, order).unwrap(), expected);
+            let wrapped = format!("BinData(3, \"{}\")", payload);
+            assert_eq!(decode_legacy_uuid(&wrapped, order).unwrap(), expected);
+            let json = format!(
+                r#"{{"$binary": {{"base64": "{}", "subType": "03"}}}}"#,
+                payload
+            );
+            assert_eq!(decode_legacy_uuid(&json, order).unwrap(), expected);
+            let old = format!(r#"{{"$binary": "{}", "$type": "03"}}"#, payload);
+            assert_eq!(decode_legacy_uuid(&old, order).unwrap(), expected);
+        }
+
+        let bytes = *expected.as_bytes();
+        for order in [LegacyUuidOrder::CSharp, LegacyUuidOrder::Java] {
+            assert_ne!(order.unshuffle(bytes), bytes);
+            assert_eq!(order.unshuffle(order.unshuffle(bytes)), bytes);
+        }
+        assert!(
+            decode_legacy_uuid(
+                "BinData(4, \"ABEiM0RVZneImaq7zN3u/w==\")",
+                LegacyUuidOrder::Java
+            )
+            .is_err()
+        );
+        assert!(decode_legacy_uuid("AAEC", LegacyUuidOrder::Java).is_err());
+    }
+
     #[test]
     fn test_uuid_generate_many() {
         let generator = UuidGenerator::v4();
@@ -563,6 +1206,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_uuid_generate_many_across_pool_refills() {
+        // More UUIDs than fit in one chunk of the random pool
+        let count = POOL_CHUNK_SIZE / 16 * 2 + 1;
+        let ids = UuidGenerator::v4()
+            .with_rng(IdRng::seeded(9))
+            .generate_many(count)
+            .unwrap();
+        let again = UuidGenerator::v4()
+            .with_rng(IdRng::seeded(9))
+            .generate_many(count)
+            .unwrap();
+        assert_eq!(ids, again);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), count);
+        for id in &ids {
+            assert_eq!(Uuid::parse_str(id).unwrap().get_version_num(), 4);
+        }
+    }
+
     #[test]
     fn test_uuid_v7_timestamp() {
         let generator = UuidGenerator::v7();
@@ -0,0 +1,253 @@
+//! UUIDs with a fixed, published meaning: RFC 9562 namespaces, GPT partition
+//! types, the Bluetooth base UUID, and COM class and interface IDs.
+//! `inspect` names a UUID found here, and `idt info uuid --well-known` lists
+//! the table.
+//!
+//! Bluetooth also writes its assigned numbers as 16- or 32-bit short UUIDs
+//! (`0x180D`), which stand for the base UUID with the number in its first
+//! 32 bits.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+/// A UUID with a published meaning
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct WellKnownUuid {
+    /// Hyphenated, lowercase
+    pub uuid: &'static str,
+    pub name: &'static str,
+    pub category: &'static str,
+}
+
+const NAMESPACE: &str = "RFC 9562 namespace";
+const GPT: &str = "GPT partition type";
+const BLUETOOTH: &str = "Bluetooth";
+const COM: &str = "Microsoft COM";
+
+const fn entry(uuid: &'static str, name: &'static str, category: &'static str) -> WellKnownUuid {
+    WellKnownUuid {
+        uuid,
+        name,
+        category,
+    }
+}
+
+/// Every well-known UUID, grouped by category
+pub const WELL_KNOWN_UUIDS: &[WellKnownUuid] = &[
+    entry(
+        "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+        "NAMESPACE_DNS",
+        NAMESPACE,
+    ),
+    entry(
+        "6ba7b811-9dad-11d1-80b4-00c04fd430c8",
+        "NAMESPACE_URL",
+        NAMESPACE,
+    ),
+    entry(
+        "6ba7b812-9dad-11d1-80b4-00c04fd430c8",
+        "NAMESPACE_OID",
+        NAMESPACE,
+    ),
+    entry(
+        "6ba7b814-9dad-11d1-80b4-00c04fd430c8",
+        "NAMESPACE_X500",
+        NAMESPACE,
+    ),
+    entry(
+        "c12a7328-f81f-11d2-ba4b-00a0c93ec93b",
+        "EFI System Partition",
+        GPT,
+    ),
+    entry(
+        "21686148-6449-6e6f-744e-656564454649",
+        "BIOS boot partition",
+        GPT,
+    ),
+    entry(
+        "024dee41-33e7-11d3-9d69-0008c781f39f",
+        "MBR partition scheme",
+        GPT,
+    ),
+    entry(
+        "e3c9e316-0b5c-4db8-817d-f92df00215ae",
+        "Microsoft Reserved Partition",
+        GPT,
+    ),
+    entry(
+        "ebd0a0a2-b9e5-4433-87c0-68b6b72699c7",
+        "Microsoft basic data",
+        GPT,
+    ),
+    entry(
+        "de94bba4-06d1-4d40-a16a-bfd50179d6ac",
+        "Windows Recovery Environment",
+        GPT,
+    ),
+    entry(
+        "0fc63daf-8483-4772-8e79-3d69d8477de4",
+        "Linux filesystem data",
+        GPT,
+    ),
+    entry("0657fd6d-a4ab-43c4-84e5-0933c84b4f4f", "Linux swap", GPT),
+    entry("e6d6d379-f507-44c2-a23c-238f2a3df928", "Linux LVM", GPT),
+    entry("a19d880f-05fc-4d3b-a006-743f0f84911e", "Linux RAID", GPT),
+    entry(
+        "4f68bce3-e8cd-4db1-96e7-fbcaf984b709",
+        "Linux root (x86-64)",
+        GPT,
+    ),
+    entry("933ac7e1-2eb4-4f13-b844-0e14e2aef915", "Linux /home", GPT),
+    entry(
+        "bc13c2ff-59e6-4262-a352-b275fd6f7172",
+        "Linux extended boot (XBOOTLDR)",
+        GPT,
+    ),
+    entry("48465300-0000-11aa-aa11-00306543ecac", "Apple HFS+", GPT),
+    entry(
+        "7c3457ef-0000-11aa-aa11-00306543ecac",
+        "Apple APFS container",
+        GPT,
+    ),
+    entry("516e7cb6-6ecf-11d6-8ff8-00022d09712b", "FreeBSD UFS", GPT),
+    entry(
+        "6a898cc3-1dd2-11b2-99a6-080020736631",
+        "Solaris /usr or Apple ZFS",
+        GPT,
+    ),
+    entry(
+        "00000000-0000-1000-8000-00805f9b34fb",
+        "Bluetooth Base UUID",
+        BLUETOOTH,
+    ),
+    entry("00000000-0000-0000-c000-000000000046", "IID_IUnknown", COM),
+    entry(
+        "00000001-0000-0000-c000-000000000046",
+        "IID_IClassFactory",
+        COM,
+    ),
+    entry("00020400-0000-0000-c000-000000000046", "IID_IDispatch", COM),
+    entry(
+        "0002df01-0000-0000-c000-000000000046",
+        "CLSID_InternetExplorer",
+        COM,
+    ),
+    entry(
+        "20d04fe0-3aea-1069-a2d8-08002b30309d",
+        "CLSID This PC (My Computer)",
+        COM,
+    ),
+    entry(
+        "21ec2020-3aea-1069-a2dd-08002b30309d",
+        "CLSID Control Panel",
+        COM,
+    ),
+    entry(
+        "645ff040-5081-101b-9f08-00aa002f954e",
+        "CLSID Recycle Bin",
+        COM,
+    ),
+];
+
+/// `0000xxxx-0000-1000-8000-00805f9b34fb`, with a short UUID in the `x`s
+const BLUETOOTH_BASE: u128 = 0x0000_0000_0000_1000_8000_0080_5f9b_34fb;
+
+/// Expand a Bluetooth short UUID, `0x` and 4 or 8 hex digits, against the
+/// Bluetooth Base UUID
+pub fn expand_bluetooth_short(input: &str) -> Option<Uuid> {
+    let digits = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))?;
+    if !matches!(digits.len(), 4 | 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    Some(Uuid::from_u128(BLUETOOTH_BASE | (value as u128) << 96))
+}
+
+/// The assigned number of a UUID that expands a Bluetooth short UUID, or
+/// `None` for other UUIDs and the base UUID itself
+pub fn bluetooth_short(uuid: &Uuid) -> Option<u32> {
+    let value = uuid.as_u128();
+    let short = (value >> 96) as u32;
+    (short != 0 && value & !(0xffff_ffff << 96) == BLUETOOTH_BASE).then_some(short)
+}
+
+/// `0x180D` for a 16-bit assigned number, `0x0001180D` for a 32-bit one
+pub fn format_bluetooth_short(short: u32) -> String {
+    if short <= 0xffff {
+        format!("0x{:04X}", short)
+    } else {
+        format!("0x{:08X}", short)
+    }
+}
+
+/// The well-known UUID equal to `uuid`, if any
+pub fn well_known_uuid(uuid: &Uuid) -> Option<&'static WellKnownUuid> {
+    let text = uuid.hyphenated().to_string();
+    WELL_KNOWN_UUIDS.iter().find(|entry| entry.uuid == text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespaces_match_uuid_crate() {
+        for (uuid, name) in [
+            (Uuid::NAMESPACE_DNS, "NAMESPACE_DNS"),
+            (Uuid::NAMESPACE_URL, "NAMESPACE_URL"),
+            (Uuid::NAMESPACE_OID, "NAMESPACE_OID"),
+            (Uuid::NAMESPACE_X500, "NAMESPACE_X500"),
+        ] {
+            assert_eq!(well_known_uuid(&uuid).unwrap().name, name);
+        }
+    }
+
+    #[test]
+    fn test_entries_are_canonical_and_unique() {
+        for (i, entry) in WELL_KNOWN_UUIDS.iter().enumerate() {
+            let uuid = Uuid::parse_str(entry.uuid).unwrap();
+            assert_eq!(uuid.hyphenated().to_string(), entry.uuid);
+            assert!(
+                WELL_KNOWN_UUIDS[..i].iter().all(|e| e.uuid != entry.uuid),
+                "{}",
+                entry.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let esp = Uuid::parse_str("C12A7328-F81F-11D2-BA4B-00A0C93EC93B").unwrap();
+        assert_eq!(well_known_uuid(&esp).unwrap().name, "EFI System Partition");
+        assert!(well_known_uuid(&Uuid::from_u128(42)).is_none());
+    }
+
+    #[test]
+    fn test_bluetooth_short() {
+        let heart_rate = expand_bluetooth_short("0x180D").unwrap();
+        assert_eq!(
+            heart_rate.to_string(),
+            "0000180d-0000-1000-8000-00805f9b34fb"
+        );
+        assert_eq!(bluetooth_short(&heart_rate), Some(0x180d));
+        assert_eq!(format_bluetooth_short(0x180d), "0x180D");
+
+        let wide = expand_bluetooth_short("0x0001180d").unwrap();
+        assert_eq!(wide.to_string(), "0001180d-0000-1000-8000-00805f9b34fb");
+        assert_eq!(
+            format_bluetooth_short(bluetooth_short(&wide).unwrap()),
+            "0x0001180D"
+        );
+
+        for input in ["180D", "0x18D", "0x180G", "0x0000180D0"] {
+            assert!(expand_bluetooth_short(input).is_none(), "{}", input);
+        }
+        // The base UUID itself, and UUIDs off the base
+        let base = Uuid::from_u128(BLUETOOTH_BASE);
+        assert_eq!(bluetooth_short(&base), None);
+        let other = Uuid::parse_str("0000180d-0000-1000-8000-00805f9b34fc").unwrap();
+        assert_eq!(bluetooth_short(&other), None);
+    }
+}
@@ -1,65 +1,125 @@
+use crate::core::components::{Components, XidComponents};
+use crate::core::decode;
 use crate::core::encoding::{
-    EncodingFormat, encode_base32, encode_base58, encode_base64, encode_base64_url, encode_bits,
-    encode_bytes_spaced, encode_hex, encode_hex_upper,
+    EncodingFormat, bytes_to_u128, encode_base32, encode_base58, encode_base58check, encode_base64,
+    encode_base64_ordered, encode_base64_url, encode_bits, encode_bytes_spaced, encode_hex,
+    encode_hex_upper, encode_java_longs,
 };
 use crate::core::error::{IdtError, Result};
 use crate::core::id::{
     IdEncodings, IdGenerator, IdKind, InspectionResult, ParsedId, SizeUnit, StructureSegment,
     Timestamp, ValidationResult,
 };
-use rand::RngExt;
+use crate::core::rng::IdRng;
+use crate::core::sortkey::encode_sort_key;
 use serde_json::json;
-use std::sync::OnceLock;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, OnceLock};
 
 /// Xid base32hex alphabet
 const XID_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuv";
 
-/// 3-byte machine ID (random, fixed per process)
-static MACHINE_ID: OnceLock<[u8; 3]> = OnceLock::new();
-
-/// Counter starting from a random value
-static XID_COUNTER: AtomicU32 = AtomicU32::new(0);
-static XID_COUNTER_INIT: OnceLock<()> = OnceLock::new();
+/// Machine ID and counter mixed into generated Xids.
+///
+/// Generators created with [`XidGenerator::new`] share one process-wide
+/// state. Give a generator its own state with [`XidGenerator::with_state`],
+/// e.g. to use a specific machine ID.
+#[derive(Debug)]
+pub struct XidState {
+    /// 3-byte machine ID
+    machine_id: [u8; 3],
+    /// Counter starting from a random value
+    counter: AtomicU32,
+}
 
-fn machine_id() -> &'static [u8; 3] {
-    MACHINE_ID.get_or_init(|| {
-        let mut rng = rand::rng();
-        let mut buf = [0u8; 3];
-        rng.fill(&mut buf);
-        buf
-    })
+impl Default for XidState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-fn next_xid_counter() -> u32 {
-    XID_COUNTER_INIT.get_or_init(|| {
-        let mut rng = rand::rng();
-        XID_COUNTER.store(rng.random::<u32>() & 0xFF_FFFF, Ordering::SeqCst);
-    });
-    XID_COUNTER.fetch_add(1, Ordering::SeqCst) & 0xFF_FFFF
+impl XidState {
+    /// State with a random machine ID
+    pub fn new() -> Self {
+        Self::from_rng(&IdRng::default())
+    }
+
+    /// State with a machine ID and counter start drawn from `rng`
+    pub fn from_rng(rng: &IdRng) -> Self {
+        let mut machine_id = [0u8; 3];
+        rng.fill(&mut machine_id);
+        Self::with_machine_id_from(machine_id, rng)
+    }
+
+    pub fn with_machine_id(machine_id: [u8; 3]) -> Self {
+        Self::with_machine_id_from(machine_id, &IdRng::default())
+    }
+
+    fn with_machine_id_from(machine_id: [u8; 3], rng: &IdRng) -> Self {
+        Self {
+            machine_id,
+            counter: AtomicU32::new(rng.u64() as u32 & 0xFF_FFFF),
+        }
+    }
+
+    /// Process-wide state shared by default generators
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<XidState>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    fn next_counter(&self) -> u32 {
+        self.counter.fetch_add(1, Ordering::SeqCst) & 0xFF_FFFF
+    }
 }
 
 /// Xid generator
-pub struct XidGenerator;
+pub struct XidGenerator {
+    state: Arc<XidState>,
+    timestamp_secs: Option<u32>,
+}
 
 impl Default for XidGenerator {
     fn default() -> Self {
-        Self
+        Self {
+            state: XidState::shared(),
+            timestamp_secs: None,
+        }
     }
 }
 
 impl XidGenerator {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_state(mut self, state: Arc<XidState>) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Use a fresh state drawn from `rng` instead of the process-wide one
+    pub fn with_rng(self, rng: IdRng) -> Self {
+        self.with_state(Arc::new(XidState::from_rng(&rng)))
+    }
+
+    /// Stamp every Xid with `secs` (Unix seconds) instead of the current time
+    pub fn with_timestamp(mut self, secs: u64) -> Result<Self> {
+        self.timestamp_secs = Some(u32::try_from(secs).map_err(|_| {
+            IdtError::InvalidArgument(format!("Xid timestamp {} exceeds 32 bits", secs))
+        })?);
+        Ok(self)
     }
 }
 
 impl IdGenerator for XidGenerator {
     fn generate(&self) -> Result<String> {
-        let now = chrono::Utc::now().timestamp() as u32;
-        let mid = machine_id();
+        let now = self
+            .timestamp_secs
+            .unwrap_or_else(|| chrono::Utc::now().timestamp() as u32);
+        let mid = &self.state.machine_id;
         let pid = (std::process::id() & 0xFFFF) as u16;
-        let counter = next_xid_counter();
+        let counter = self.state.next_counter();
 
         let mut bytes = [0u8; 12];
         bytes[0..4].copy_from_slice(&now.to_be_bytes());
@@ -73,36 +133,17 @@ impl IdGenerator for XidGenerator {
     }
 }
 
-/// Xid-specific base32hex encoding (custom bit extraction, NOT standard base32)
-fn xid_encode(bytes: &[u8; 12]) -> String {
-    let mut dst = [0u8; 20];
+/// Encode 12 bytes as 20 lowercase base32hex characters, as rs/xid does.
+///
+/// The 96 bits are read most significant first, so the last character
+/// carries one data bit and four zero padding bits.
+pub(crate) fn xid_encode(bytes: &[u8; 12]) -> String {
+    let value = bytes.iter().fold(0u128, |acc, &b| acc << 8 | b as u128) << 4;
 
-    // Xid encodes 12 bytes (96 bits) into 20 base32hex chars (5 bits each = 100 bits, 4 padding)
-    // Using specific bit extraction pattern from the xid spec
-    dst[19] = XID_ALPHABET[(bytes[11] & 0x1f) as usize];
-    dst[18] = XID_ALPHABET[((bytes[11] >> 5) | (bytes[10] << 3)) as usize & 0x1f];
-    dst[17] = XID_ALPHABET[((bytes[10] >> 2) & 0x1f) as usize];
-    dst[16] = XID_ALPHABET[((bytes[10] >> 7) | (bytes[9] << 1)) as usize & 0x1f];
-    dst[15] = XID_ALPHABET[((bytes[9] >> 4) | (bytes[8] << 4)) as usize & 0x1f];
-
-    dst[14] = XID_ALPHABET[(bytes[8] >> 1 & 0x1f) as usize];
-    dst[13] = XID_ALPHABET[((bytes[8] >> 6) | (bytes[7] << 2)) as usize & 0x1f];
-    dst[12] = XID_ALPHABET[((bytes[7] >> 3) & 0x1f) as usize];
-
-    dst[11] = XID_ALPHABET[(bytes[6] & 0x1f) as usize];
-    dst[10] = XID_ALPHABET[((bytes[6] >> 5) | (bytes[5] << 3)) as usize & 0x1f];
-    dst[9] = XID_ALPHABET[((bytes[5] >> 2) & 0x1f) as usize];
-    dst[8] = XID_ALPHABET[((bytes[5] >> 7) | (bytes[4] << 1)) as usize & 0x1f];
-    dst[7] = XID_ALPHABET[((bytes[4] >> 4) | (bytes[3] << 4)) as usize & 0x1f];
-
-    dst[6] = XID_ALPHABET[((bytes[3] >> 1) & 0x1f) as usize];
-    dst[5] = XID_ALPHABET[((bytes[3] >> 6) | (bytes[2] << 2)) as usize & 0x1f];
-    dst[4] = XID_ALPHABET[((bytes[2] >> 3) & 0x1f) as usize];
-
-    dst[3] = XID_ALPHABET[(bytes[1] & 0x1f) as usize];
-    dst[2] = XID_ALPHABET[((bytes[1] >> 5) | (bytes[0] << 3)) as usize & 0x1f];
-    dst[1] = XID_ALPHABET[((bytes[0] >> 2) & 0x1f) as usize];
-    dst[0] = XID_ALPHABET[(bytes[0] >> 7) as usize];
+    let mut dst = [0u8; 20];
+    for (i, d) in dst.iter_mut().enumerate() {
+        *d = XID_ALPHABET[(value >> (5 * (19 - i)) & 0x1f) as usize];
+    }
 
     String::from_utf8(dst.to_vec()).expect("XID_ALPHABET is valid UTF-8")
 }
@@ -115,34 +156,26 @@ fn xid_decode(s: &str) -> Result<[u8; 12]> {
         ));
     }
 
-    let src: Vec<u8> = s
-        .chars()
-        .map(|c| {
-            xid_char_value(c)
-                .ok_or_else(|| IdtError::ParseError(format!("Invalid xid character: '{}'", c)))
-        })
-        .collect::<Result<Vec<u8>>>()?;
-
-    let mut bytes = [0u8; 12];
-
-    bytes[11] = (src[19]) | (src[18] << 5);
-    bytes[10] = (src[18] >> 3) | (src[17] << 2) | (src[16] << 7);
-    bytes[9] = (src[16] >> 1) | (src[15] << 4);
-    bytes[8] = (src[15] >> 4) | (src[14] << 1) | (src[13] << 6);
-    bytes[7] = (src[13] >> 2) | (src[12] << 3);
+    let src: [u8; 20] = decode::lookup(s.as_bytes(), &decode::BASE32_HEX).ok_or_else(|| {
+        let (offset, _) = decode::find_invalid(s, &decode::BASE32_HEX).unwrap_or((0, '?'));
+        IdtError::invalid_character(s, offset, "xid")
+    })?;
 
-    bytes[6] = (src[11]) | (src[10] << 5);
-    bytes[5] = (src[10] >> 3) | (src[9] << 2) | (src[8] << 7);
-    bytes[4] = (src[8] >> 1) | (src[7] << 4);
-    bytes[3] = (src[7] >> 4) | (src[6] << 1) | (src[5] << 6);
-    bytes[2] = (src[5] >> 2) | (src[4] << 3);
-
-    bytes[1] = (src[3]) | (src[2] << 5);
-    bytes[0] = (src[2] >> 3) | (src[1] << 2) | (src[0] << 7);
+    let value = src.iter().fold(0u128, |acc, &d| acc << 5 | d as u128);
+    // Nonzero padding would decode to an Xid that encodes differently
+    if value & 0xf != 0 {
+        return Err(IdtError::ParseError(
+            "Invalid Xid: last character must be 0 or g".to_string(),
+        ));
+    }
 
+    let mut bytes = [0u8; 12];
+    bytes.copy_from_slice(&(value >> 4).to_be_bytes()[4..]);
     Ok(bytes)
 }
 
+/// Scalar reference for [`decode::BASE32_HEX`]
+#[cfg(test)]
 fn xid_char_value(c: char) -> Option<u8> {
     match c {
         '0'..='9' => Some(c as u8 - b'0'),
@@ -184,6 +217,14 @@ impl ParsedXid {
     }
 }
 
+impl std::str::FromStr for ParsedXid {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl ParsedId for ParsedXid {
     fn kind(&self) -> IdKind {
         IdKind::Xid
@@ -201,6 +242,17 @@ impl ParsedId for ParsedXid {
         Some(Timestamp::from_secs(self.timestamp_secs() as u64))
     }
 
+    fn components(&self) -> Option<Components> {
+        Some(Components::Xid(XidComponents {
+            timestamp_secs: self.timestamp_secs(),
+            machine_id: self.bytes[4..7]
+                .try_into()
+                .expect("XID machine ID is 3 bytes"),
+            process_id: self.process_id(),
+            counter: self.counter(),
+        }))
+    }
+
     fn inspect(&self) -> InspectionResult {
         let bytes = self.as_bytes();
         let timestamp = self.timestamp().expect("XID always has a timestamp");
@@ -220,6 +272,9 @@ impl ParsedId for ParsedXid {
             timestamp: Some(timestamp),
             timestamp_iso: Some(timestamp.to_iso8601()),
             timestamp_local_iso: None,
+            age_ms: None,
+            age_human: None,
+            source: None,
             version: None,
             variant: None,
             random_bits: None,
@@ -274,6 +329,10 @@ impl ParsedId for ParsedXid {
         }
     }
 
+    fn as_u128(&self) -> Option<u128> {
+        bytes_to_u128(&self.bytes)
+    }
+
     fn encode(&self, format: EncodingFormat) -> String {
         let bytes = self.as_bytes();
         match format {
@@ -283,18 +342,16 @@ impl ParsedId for ParsedXid {
             EncodingFormat::Base32 => encode_base32(&bytes),
             EncodingFormat::Base32Hex => encode_base32(&bytes),
             EncodingFormat::Base58 => encode_base58(&bytes),
+            EncodingFormat::Base58Check => encode_base58check(&bytes),
             EncodingFormat::Base64 => encode_base64(&bytes),
             EncodingFormat::Base64Url => encode_base64_url(&bytes),
+            EncodingFormat::Base64Ordered => encode_base64_ordered(&bytes),
             EncodingFormat::Binary => String::from_utf8_lossy(&bytes).to_string(),
             EncodingFormat::Bits => encode_bits(&bytes),
-            EncodingFormat::Int => {
-                let mut val: u128 = 0;
-                for &b in &bytes {
-                    val = (val << 8) | b as u128;
-                }
-                val.to_string()
-            }
+            EncodingFormat::Int => self.as_u128().unwrap_or_default().to_string(),
             EncodingFormat::Bytes => encode_bytes_spaced(&bytes),
+            EncodingFormat::JavaLongs => encode_java_longs(&bytes),
+            EncodingFormat::SortKey => encode_sort_key(self),
         }
     }
 }
@@ -345,6 +402,20 @@ mod tests {
         assert!((now * 1000).abs_diff(ts.millis) < 10_000);
     }
 
+    #[test]
+    fn test_reference_encoding() {
+        // From rs/xid's README and tests
+        let bytes = [
+            0x4d, 0x88, 0xe1, 0x5b, 0x60, 0xf4, 0x86, 0xe4, 0x28, 0x41, 0x2d, 0xc9,
+        ];
+        assert_eq!(xid_encode(&bytes), "9m4e2mr0ui3e8a215n4g");
+        let parsed = ParsedXid::parse("9m4e2mr0ui3e8a215n4g").unwrap();
+        assert_eq!(parsed.as_bytes(), bytes);
+        assert_eq!(parsed.timestamp().unwrap().millis, 1_300_816_219_000);
+        // Padding bits set
+        assert!(ParsedXid::parse("9m4e2mr0ui3e8a215n4h").is_err());
+    }
+
     #[test]
     fn test_parse_error_wrong_length() {
         assert!(ParsedXid::parse("too_short").is_err());
@@ -445,6 +516,15 @@ mod tests {
         assert_eq!(xid_char_value('A'), None);
     }
 
+    #[test]
+    fn test_xid_table_matches_scalar() {
+        for b in 0..=255u8 {
+            let table = decode::BASE32_HEX[b as usize];
+            let expected = xid_char_value(b as char).unwrap_or(decode::INVALID);
+            assert_eq!(table, expected, "byte {:#04x}", b);
+        }
+    }
+
     #[test]
     fn test_components() {
         let generator = XidGenerator::new();
@@ -456,4 +536,12 @@ mod tests {
         let _ = parsed.process_id();
         let _ = parsed.counter();
     }
+
+    #[test]
+    fn test_state_machine_id() {
+        let generator =
+            XidGenerator::new().with_state(Arc::new(XidState::with_machine_id([1, 2, 3])));
+        let parsed = ParsedXid::parse(&generator.generate().unwrap()).unwrap();
+        assert_eq!(&parsed.as_bytes()[4..7], &[1, 2, 3]);
+    }
 }
@@ -10,6 +10,23 @@
 //!
 //! # Example
 //!
+//! The top-level functions cover the common cases without trait objects:
+//!
+//! ```rust
+//! use idt::IdKind;
+//! use idt::ids::ParsedUlid;
+//!
+//! let info = idt::inspect("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+//! assert_eq!(info.id_type, "ulid");
+//!
+//! assert_eq!(idt::detect("550e8400-e29b-41d4-a716-446655440000").unwrap(), IdKind::UuidV4);
+//!
+//! let id = idt::generate(IdKind::Ulid).unwrap();
+//! let ulid: ParsedUlid = id.parse().unwrap();
+//! ```
+//!
+//! Generators and parsed types can also be used directly:
+//!
 //! ```rust
 //! use idt::ids::{UuidGenerator, UlidGenerator};
 //! use idt::core::id::IdGenerator;
@@ -23,12 +40,37 @@
 //! println!("ULID: {}", ulid);
 //! ```
 
+#[cfg(feature = "cli")]
 pub mod cli;
+pub mod conformance;
 pub mod core;
 pub mod ids;
+#[cfg(feature = "tokio")]
+pub mod stream;
+pub mod typed;
 pub mod utils;
 
 // Re-export commonly used types
 pub use core::EncodingFormat;
 pub use core::error::{IdtError, Result};
 pub use core::id::{IdGenerator, IdKind, InspectionResult, ParsedId, Timestamp, ValidationResult};
+pub use typed::Id;
+
+/// Parse `input`, detecting its type, and describe what it contains
+pub fn inspect(input: &str) -> Result<InspectionResult> {
+    Ok(ids::parse_id(input, None)?.inspect())
+}
+
+/// The most likely type of `input`. See [`core::detect_id_type`] for every
+/// candidate with its confidence.
+pub fn detect(input: &str) -> Result<IdKind> {
+    core::detect_id_type(input)?
+        .first()
+        .map(|result| result.kind)
+        .ok_or(IdtError::DetectionFailed)
+}
+
+/// Generate one ID of `kind` with default settings
+pub fn generate(kind: IdKind) -> Result<String> {
+    ids::create_generator(kind)?.generate()
+}
@@ -1,44 +1,121 @@
-use clap::Parser;
-use idt::cli::commands;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use idt::cli::atomic_file::AtomicFile;
+use idt::cli::commands::{self, completions};
+use idt::cli::output::{finish_output_file, redirect_stdout};
 use idt::cli::{Cli, Commands, OutputFormat};
+use idt::cli::{logging, progress};
+use idt::core::error::IdtError;
+use idt::ids::custom_id::{self, CustomSchema};
 use std::process::ExitCode;
 
 fn main() -> ExitCode {
+    // Answers the script from `idt completions --dynamic` and exits
+    CompleteEnv::with_factory(Cli::command)
+        .var(completions::COMPLETE_VAR)
+        .complete();
+
     let cli = Cli::parse();
 
-    if cli.no_color {
+    if let Some(ref path) = cli.output_file {
+        match AtomicFile::create(path, cli.append) {
+            Ok(file) => redirect_stdout(file),
+            Err(e) => {
+                eprintln!("Error: cannot write {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // Files never get ANSI escapes
+    let no_color = cli.no_color || cli.output_file.is_some();
+    if no_color {
         colored::control::set_override(false);
     }
+    logging::init(cli.verbose, no_color);
+    if cli.no_progress {
+        progress::disable();
+    }
+
+    // Resolve effective output format: --output takes precedence, then -j/--json
+    let format = match cli.output {
+        Some(mode) => mode.structured(),
+        None if cli.json => Some(OutputFormat::Json),
+        None => None,
+    };
 
-    // Resolve effective output format: --format takes precedence, then -j/--json
-    let format = cli.output_format.or(if cli.json {
-        Some(OutputFormat::Json)
-    } else {
-        None
-    });
+    if let Some(ref path) = cli.schema
+        && let Err(e) = CustomSchema::load(path).and_then(custom_id::register)
+    {
+        eprintln!("Error: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    if let Err(e) = cli
+        .canonical_case
+        .iter()
+        .try_for_each(|policy| policy.apply())
+    {
+        eprintln!("Error: {}", e);
+        return ExitCode::FAILURE;
+    }
 
     let result = match &cli.command {
         Commands::Gen(args) => commands::generate::execute(args, format, cli.pretty),
-        Commands::Inspect(args) => {
-            commands::inspect::execute(args, format, cli.pretty, cli.no_color)
-        }
+        Commands::Inspect(args) => commands::inspect::execute(args, format, cli.pretty, no_color),
         Commands::Convert(args) => commands::convert::execute(args, format, cli.pretty),
-        Commands::Validate(args) => {
-            commands::validate::execute(args, format, cli.pretty, cli.no_color)
-        }
-        Commands::Compare(args) => {
-            commands::compare::execute(args, format, cli.pretty, cli.no_color)
+        Commands::Canon(args) => commands::canon::execute(args, format, cli.pretty),
+        Commands::Validate(args) => commands::validate::execute(args, format, cli.pretty, no_color),
+        Commands::Compare(args) => commands::compare::execute(args, format, cli.pretty, no_color),
+        Commands::Sort(args) => commands::sort::execute(args, format, cli.pretty, no_color),
+        Commands::Filter(args) => commands::filter::execute(args, format, cli.pretty),
+        Commands::Bucket(args) => commands::bucket::execute(args, format, cli.pretty),
+        Commands::Stats(args) => commands::stats::execute(args, format, cli.pretty, no_color),
+        Commands::Grep(args) => commands::grep::execute(args, format, cli.pretty),
+        Commands::Dedup(args) => commands::dedup::execute(args, format, cli.pretty),
+        Commands::Merge(args) => commands::merge::execute(args),
+        Commands::Set(args) => commands::set::execute(args),
+        Commands::Shard(args) => commands::shard::execute(args, format, cli.pretty),
+        Commands::Hash(args) => commands::hash::execute(args, format, cli.pretty),
+        Commands::Uuid5Find(args) => commands::uuid5_find::execute(args, format, cli.pretty),
+        Commands::Mask(args) => commands::mask::execute(args, format, cli.pretty),
+        Commands::Redact(args) => commands::redact::execute(args, format, cli.pretty),
+        Commands::Explain(args) => commands::explain::execute(args, format, cli.pretty, no_color),
+        Commands::Tui(args) => commands::tui::execute(args),
+        Commands::Grpc(args) => commands::grpc::execute(args),
+        Commands::Info(args) => commands::info::execute(args, format, cli.pretty, no_color),
+        Commands::Bench(args) => commands::bench::execute(args, format, cli.pretty, no_color),
+        Commands::Audit(args) => commands::audit::execute(args, format, cli.pretty, no_color),
+        Commands::Doctor(args) => commands::doctor::execute(args, format, cli.pretty, no_color),
+        Commands::Conformance(args) => {
+            commands::conformance::execute(args, format, cli.pretty, no_color)
         }
-        Commands::Sort(args) => commands::sort::execute(args, format, cli.pretty, cli.no_color),
-        Commands::Info(args) => commands::info::execute(args, format, cli.pretty, cli.no_color),
         Commands::Completions(args) => commands::completions::execute(args),
-        Commands::Manpage(args) => commands::manpage::execute(args),
+        Commands::SelfUpdate(args) => commands::self_update::execute(args, format, cli.pretty),
+        Commands::Man(args) => commands::manpage::execute(args),
+    };
+
+    // Publish --output-file once the command ran to completion, even when a
+    // check it made failed; I/O and argument errors leave the file alone
+    let completed = matches!(result, Ok(()) | Err(IdtError::CheckFailed(_)));
+    let result = match finish_output_file(completed) {
+        Ok(true) if !completed => {
+            if let Some(ref path) = cli.output_file {
+                eprintln!("note: {} was left unchanged", path.display());
+            }
+            result
+        }
+        Ok(_) => result,
+        Err(e) => Err(e),
     };
 
     match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
             eprintln!("Error: {}", e);
+            if let Some(caret) = e.caret() {
+                eprintln!("{}", caret);
+            }
             ExitCode::FAILURE
         }
     }
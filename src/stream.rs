@@ -0,0 +1,269 @@
+//! Async ID generation for tokio-based services (requires the `tokio` feature).
+//!
+//! Generation runs on tokio's blocking thread pool, so RNG and clock syscalls
+//! never stall the async runtime.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # async fn run() -> idt::Result<()> {
+//! use idt::ids::UlidGenerator;
+//! use idt::stream::{IdStream, generate};
+//! use std::sync::Arc;
+//!
+//! // One-off generation
+//! let id = generate(Arc::new(UlidGenerator::new())).await?;
+//!
+//! // 100 ULIDs per second, 1000 in total
+//! let stream = IdStream::new(UlidGenerator::new())
+//!     .with_rate(100)
+//!     .with_limit(1000);
+//! # let _ = (id, stream);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::core::error::{IdtError, Result};
+use crate::core::id::IdGenerator;
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{Interval, MissedTickBehavior};
+
+/// Generate a single ID without blocking the runtime
+pub async fn generate<G>(generator: Arc<G>) -> Result<String>
+where
+    G: IdGenerator + Send + Sync + ?Sized + 'static,
+{
+    tokio::task::spawn_blocking(move || generator.generate())
+        .await
+        .map_err(join_error)?
+}
+
+/// Generate `count` IDs without blocking the runtime
+pub async fn generate_many<G>(generator: Arc<G>, count: usize) -> Result<Vec<String>>
+where
+    G: IdGenerator + Send + Sync + ?Sized + 'static,
+{
+    tokio::task::spawn_blocking(move || generator.generate_many(count))
+        .await
+        .map_err(join_error)?
+}
+
+/// A [`Stream`] of freshly generated IDs.
+///
+/// The stream is unbounded and unthrottled by default. IDs are generated
+/// `batch_size` at a time on the blocking pool; keep the default of 1 when
+/// embedded timestamps should match the moment each ID is yielded.
+pub struct IdStream<G: ?Sized> {
+    generator: Arc<G>,
+    period: Option<Duration>,
+    interval: Option<Interval>,
+    ticked: bool,
+    remaining: Option<usize>,
+    batch_size: usize,
+    buffer: VecDeque<String>,
+    pending: Option<JoinHandle<Result<Vec<String>>>>,
+}
+
+impl<G> IdStream<G>
+where
+    G: IdGenerator + Send + Sync + 'static,
+{
+    pub fn new(generator: G) -> Self {
+        Self::from_arc(Arc::new(generator))
+    }
+}
+
+impl<G> IdStream<G>
+where
+    G: IdGenerator + Send + Sync + ?Sized + 'static,
+{
+    /// Stream from a generator that is shared with other tasks
+    pub fn from_arc(generator: Arc<G>) -> Self {
+        Self {
+            generator,
+            period: None,
+            interval: None,
+            ticked: false,
+            remaining: None,
+            batch_size: 1,
+            buffer: VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    /// Yield at most `per_second` IDs per second (0 = unthrottled)
+    pub fn with_rate(self, per_second: u32) -> Self {
+        if per_second == 0 {
+            return self.with_interval(Duration::ZERO);
+        }
+        self.with_interval(Duration::from_secs(1) / per_second)
+    }
+
+    /// Wait `period` between IDs (zero = unthrottled)
+    pub fn with_interval(mut self, period: Duration) -> Self {
+        self.period = (!period.is_zero()).then_some(period);
+        self.interval = None;
+        self
+    }
+
+    /// End the stream after `count` IDs
+    pub fn with_limit(mut self, count: usize) -> Self {
+        self.remaining = Some(count);
+        self
+    }
+
+    /// Number of IDs generated per trip to the blocking pool (minimum 1)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl<G> Stream for IdStream<G>
+where
+    G: IdGenerator + Send + Sync + ?Sized + 'static,
+{
+    type Item = Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.remaining == Some(0) {
+            return Poll::Ready(None);
+        }
+
+        if let Some(period) = this.period
+            && !this.ticked
+        {
+            // Created lazily: intervals need a running tokio runtime
+            let interval = this.interval.get_or_insert_with(|| {
+                let mut interval = tokio::time::interval(period);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                interval
+            });
+            ready!(interval.poll_tick(cx));
+            this.ticked = true;
+        }
+
+        if this.buffer.is_empty() {
+            let count = this
+                .remaining
+                .map_or(this.batch_size, |r| r.min(this.batch_size));
+            let handle = this.pending.get_or_insert_with(|| {
+                let generator = Arc::clone(&this.generator);
+                tokio::task::spawn_blocking(move || generator.generate_many(count))
+            });
+
+            let result = ready!(Pin::new(handle).poll(cx));
+            this.pending = None;
+            match result {
+                Ok(Ok(ids)) => this.buffer.extend(ids),
+                Ok(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Err(e) => return Poll::Ready(Some(Err(join_error(e)))),
+            }
+        }
+
+        let Some(id) = this.buffer.pop_front() else {
+            return Poll::Ready(Some(Err(IdtError::GenerationError(
+                "generator returned no IDs".to_string(),
+            ))));
+        };
+
+        this.ticked = false;
+        if let Some(ref mut remaining) = this.remaining {
+            *remaining -= 1;
+        }
+        Poll::Ready(Some(Ok(id)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (self.buffer.len().min(remaining), Some(remaining)),
+            None => (self.buffer.len(), None),
+        }
+    }
+}
+
+fn join_error(e: tokio::task::JoinError) -> IdtError {
+    IdtError::GenerationError(format!("generation task failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::{UlidGenerator, UuidGenerator};
+    use std::future::poll_fn;
+
+    async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn test_generate() {
+        let id = generate(Arc::new(UlidGenerator::new())).await.unwrap();
+        assert!(crate::ids::is_ulid(&id));
+    }
+
+    #[tokio::test]
+    async fn test_generate_many() {
+        let ids = generate_many(Arc::new(UuidGenerator::v7()), 5)
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_stream_limit() {
+        let mut stream = IdStream::new(UlidGenerator::new()).with_limit(3);
+        let mut ids = Vec::new();
+        while let Some(id) = next(&mut stream).await {
+            ids.push(id.unwrap());
+        }
+        assert_eq!(ids.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stream_batches() {
+        let mut stream = IdStream::new(UlidGenerator::new())
+            .with_batch_size(4)
+            .with_limit(6);
+        let mut count = 0;
+        while let Some(id) = next(&mut stream).await {
+            id.unwrap();
+            count += 1;
+        }
+        assert_eq!(count, 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_rate() {
+        let start = tokio::time::Instant::now();
+        let mut stream = IdStream::new(UlidGenerator::new())
+            .with_rate(10)
+            .with_limit(3);
+        while next(&mut stream).await.is_some() {}
+        // First tick is immediate, then two 100ms waits
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_stream_from_boxed_generator() {
+        let generator = crate::ids::create_generator(crate::core::id::IdKind::Ulid).unwrap();
+        let generator: Arc<dyn IdGenerator + Send + Sync> = Arc::from(generator);
+        let mut stream = IdStream::from_arc(generator).with_limit(1);
+        assert!(next(&mut stream).await.unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let stream = IdStream::new(UlidGenerator::new()).with_limit(5);
+        assert_eq!(stream.size_hint(), (0, Some(5)));
+    }
+}
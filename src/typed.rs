@@ -0,0 +1,388 @@
+//! Typed IDs for application structs.
+//!
+//! [`Id<K>`] holds an ID that is known to be of kind `K`. It is checked when
+//! it is parsed or deserialized, stored in canonical form, and serialized as
+//! a plain string, so a field of type `Id<Ulid>` can replace a `String`
+//! without changing the wire format.
+//!
+//! ```rust
+//! use idt::typed::{Id, TypeId, TypeIdPrefix, Ulid};
+//!
+//! struct User;
+//! impl TypeIdPrefix for User {
+//!     const PREFIX: &'static str = "user";
+//! }
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Account {
+//!     id: Id<TypeId<User>>,
+//!     last_event: Id<Ulid>,
+//! }
+//!
+//! let account: Account = serde_json::from_str(
+//!     r#"{"id": "user_01h455vb4pex5vsknk084sn02q", "last_event": "01ARZ3NDEKTSV4RRFFQ69G5FAV"}"#,
+//! )
+//! .unwrap();
+//! assert!(serde_json::from_str::<Id<Ulid>>(r#""user_01h455vb4pex5vsknk084sn02q""#).is_err());
+//! ```
+
+use crate::core::error::{IdtError, Result};
+use crate::core::id::{IdKind, ParsedId};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// An ID kind usable as the parameter of [`Id`]
+pub trait Kind {
+    /// Kind that parsed values must report. `IdKind::Uuid` accepts every
+    /// UUID version.
+    const KIND: IdKind;
+
+    type Parsed: ParsedId + FromStr<Err = IdtError>;
+
+    /// Parse `input` and check that it is this kind
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let parsed: Self::Parsed = input.parse()?;
+        let kind = parsed.kind();
+        let accepted = kind == Self::KIND || (Self::KIND == IdKind::Uuid && kind.is_uuid());
+        if !accepted {
+            return Err(IdtError::ValidationError(format!(
+                "expected {}, found {}",
+                Self::KIND.name(),
+                kind.name()
+            )));
+        }
+        Ok(parsed)
+    }
+
+    /// A new ID of this kind, as text
+    fn generate() -> Result<String> {
+        crate::ids::create_generator(Self::KIND)?.generate()
+    }
+}
+
+/// Any UUID version
+#[cfg(feature = "uuid")]
+pub struct Uuid;
+/// UUID version 4
+#[cfg(feature = "uuid")]
+pub struct UuidV4;
+/// UUID version 7
+#[cfg(feature = "uuid")]
+pub struct UuidV7;
+#[cfg(feature = "ulid")]
+pub struct Ulid;
+#[cfg(feature = "ksuid")]
+pub struct Ksuid;
+#[cfg(feature = "objectid")]
+pub struct ObjectId;
+/// Snowflake with the default (Twitter) layout
+#[cfg(feature = "snowflake")]
+pub struct Snowflake;
+#[cfg(feature = "nanoid")]
+pub struct NanoId;
+#[cfg(feature = "tsid")]
+pub struct Tsid;
+#[cfg(feature = "xid")]
+pub struct Xid;
+#[cfg(feature = "cuid")]
+pub struct Cuid;
+#[cfg(feature = "cuid2")]
+pub struct Cuid2;
+#[cfg(feature = "shortuuid")]
+pub struct ShortUuid;
+
+#[cfg(feature = "uuid")]
+impl Kind for Uuid {
+    const KIND: IdKind = IdKind::Uuid;
+    type Parsed = crate::ids::ParsedUuid;
+}
+
+#[cfg(feature = "uuid")]
+impl Kind for UuidV4 {
+    const KIND: IdKind = IdKind::UuidV4;
+    type Parsed = crate::ids::ParsedUuid;
+}
+
+#[cfg(feature = "uuid")]
+impl Kind for UuidV7 {
+    const KIND: IdKind = IdKind::UuidV7;
+    type Parsed = crate::ids::ParsedUuid;
+}
+
+#[cfg(feature = "ulid")]
+impl Kind for Ulid {
+    const KIND: IdKind = IdKind::Ulid;
+    type Parsed = crate::ids::ParsedUlid;
+}
+
+#[cfg(feature = "ksuid")]
+impl Kind for Ksuid {
+    const KIND: IdKind = IdKind::Ksuid;
+    type Parsed = crate::ids::ParsedKsuid;
+}
+
+#[cfg(feature = "objectid")]
+impl Kind for ObjectId {
+    const KIND: IdKind = IdKind::ObjectId;
+    type Parsed = crate::ids::ParsedObjectId;
+}
+
+#[cfg(feature = "snowflake")]
+impl Kind for Snowflake {
+    const KIND: IdKind = IdKind::Snowflake;
+    type Parsed = crate::ids::ParsedSnowflake;
+}
+
+#[cfg(feature = "nanoid")]
+impl Kind for NanoId {
+    const KIND: IdKind = IdKind::NanoId;
+    type Parsed = crate::ids::ParsedNanoId;
+}
+
+#[cfg(feature = "tsid")]
+impl Kind for Tsid {
+    const KIND: IdKind = IdKind::Tsid;
+    type Parsed = crate::ids::ParsedTsid;
+}
+
+#[cfg(feature = "xid")]
+impl Kind for Xid {
+    const KIND: IdKind = IdKind::Xid;
+    type Parsed = crate::ids::ParsedXid;
+}
+
+#[cfg(feature = "cuid")]
+impl Kind for Cuid {
+    const KIND: IdKind = IdKind::Cuid;
+    type Parsed = crate::ids::ParsedCuid;
+}
+
+#[cfg(feature = "cuid2")]
+impl Kind for Cuid2 {
+    const KIND: IdKind = IdKind::Cuid2;
+    type Parsed = crate::ids::ParsedCuid2;
+}
+
+#[cfg(feature = "shortuuid")]
+impl Kind for ShortUuid {
+    const KIND: IdKind = IdKind::ShortUuid;
+    type Parsed = crate::ids::ParsedShortUuid;
+}
+
+/// Prefix of a [`TypeId`] kind, e.g. `user` for `user_01h455vb4pex5vsknk084sn02q`
+#[cfg(feature = "typeid")]
+pub trait TypeIdPrefix {
+    const PREFIX: &'static str;
+}
+
+/// TypeID whose prefix must be `P::PREFIX`
+#[cfg(feature = "typeid")]
+pub struct TypeId<P>(PhantomData<P>);
+
+#[cfg(feature = "typeid")]
+impl<P: TypeIdPrefix> Kind for TypeId<P> {
+    const KIND: IdKind = IdKind::TypeId;
+    type Parsed = crate::ids::ParsedTypeId;
+
+    fn parse(input: &str) -> Result<crate::ids::ParsedTypeId> {
+        let parsed = crate::ids::ParsedTypeId::parse(input)?;
+        if parsed.prefix() != P::PREFIX {
+            return Err(IdtError::ValidationError(format!(
+                "expected TypeID prefix '{}', found '{}'",
+                P::PREFIX,
+                parsed.prefix()
+            )));
+        }
+        Ok(parsed)
+    }
+
+    fn generate() -> Result<String> {
+        use crate::core::id::IdGenerator;
+        crate::ids::TypeIdGenerator::new(P::PREFIX).generate()
+    }
+}
+
+/// An ID of kind `K`, held in canonical form
+pub struct Id<K> {
+    text: String,
+    kind: PhantomData<fn() -> K>,
+}
+
+impl<K: Kind> Id<K> {
+    /// Parse `input`, failing unless it is a valid `K`
+    pub fn parse(input: &str) -> Result<Self> {
+        Ok(Self::from_canonical(K::parse(input)?.canonical()))
+    }
+
+    /// A new ID of kind `K`
+    pub fn generate() -> Result<Self> {
+        Self::parse(&K::generate()?)
+    }
+
+    /// The parsed value, for timestamps, components, and encodings
+    pub fn parsed(&self) -> K::Parsed {
+        K::parse(&self.text).expect("Id holds a valid ID of its kind")
+    }
+}
+
+impl<K> Id<K> {
+    fn from_canonical(text: String) -> Self {
+        Self {
+            text,
+            kind: PhantomData,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+impl<K: Kind> FromStr for Id<K> {
+    type Err = IdtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl<K> fmt::Display for Id<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl<K: Kind> fmt::Debug for Id<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Id<{}>({:?})", K::KIND.name(), self.text)
+    }
+}
+
+impl<K> AsRef<str> for Id<K> {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl<K> Clone for Id<K> {
+    fn clone(&self) -> Self {
+        Self::from_canonical(self.text.clone())
+    }
+}
+
+impl<K> PartialEq for Id<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl<K> Eq for Id<K> {}
+
+impl<K> Hash for Id<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+/// Orders by canonical text, which is creation order for ULID, KSUID, TSID,
+/// and UUIDv7
+impl<K> PartialOrd for Id<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K> Ord for Id<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.text.cmp(&other.text)
+    }
+}
+
+impl<K> Serialize for Id<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de, K: Kind> Deserialize<'de> for Id<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Every test needs one ID family or another
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(feature = "typeid")]
+    struct User;
+    #[cfg(feature = "typeid")]
+    impl TypeIdPrefix for User {
+        const PREFIX: &'static str = "user";
+    }
+
+    #[test]
+    #[cfg(all(feature = "uuid", feature = "ulid"))]
+    fn test_parse_checks_kind() {
+        let id: Id<Ulid> = "01arz3ndektsv4rrffq69g5fav".parse().unwrap();
+        assert_eq!(id.as_str(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert!(
+            "550e8400-e29b-41d4-a716-446655440000"
+                .parse::<Id<Ulid>>()
+                .is_err()
+        );
+
+        assert!(Id::<Uuid>::parse("6ba7b810-9dad-11d1-80b4-00c04fd430c8").is_ok());
+        assert!(Id::<UuidV7>::parse("6ba7b810-9dad-11d1-80b4-00c04fd430c8").is_err());
+        assert!(Id::<UuidV4>::parse("550E8400-E29B-41D4-A716-446655440000").is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "typeid")]
+    fn test_typeid_prefix() {
+        assert!(Id::<TypeId<User>>::parse("user_01h455vb4pex5vsknk084sn02q").is_ok());
+        let err = Id::<TypeId<User>>::parse("post_01h455vb4pex5vsknk084sn02q").unwrap_err();
+        assert!(err.to_string().contains("expected TypeID prefix 'user'"));
+
+        let id = Id::<TypeId<User>>::generate().unwrap();
+        assert!(id.as_str().starts_with("user_"));
+        assert_eq!(id.parsed().prefix(), "user");
+    }
+
+    #[test]
+    #[cfg(all(feature = "uuid", feature = "ksuid"))]
+    fn test_generate() {
+        let a = Id::<UuidV7>::generate().unwrap();
+        assert_eq!(a.parsed().kind(), IdKind::UuidV7);
+        let b = Id::<Ksuid>::generate().unwrap();
+        assert_eq!(b.as_str().len(), 27);
+    }
+
+    #[test]
+    #[cfg(feature = "objectid")]
+    fn test_serde_roundtrip() {
+        let id: Id<ObjectId> = serde_json::from_str(r#""507f1f77bcf86cd799439011""#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&id).unwrap(),
+            r#""507f1f77bcf86cd799439011""#
+        );
+        assert_eq!(
+            format!("{:?}", id),
+            r#"Id<objectid>("507f1f77bcf86cd799439011")"#
+        );
+
+        let err = serde_json::from_str::<Id<ObjectId>>(r#""not-an-id""#).unwrap_err();
+        assert!(err.is_data());
+    }
+}
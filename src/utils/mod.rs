@@ -27,6 +27,55 @@ pub fn format_duration_ms(ms: u64) -> String {
     }
 }
 
+/// Hint naming the `candidates` closest to an unknown `input`, e.g.
+/// ` (did you mean 'ulid'?)`, or an empty string if none are close
+pub fn did_you_mean<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    // Allow roughly one typo per three characters
+    let max_distance = (input.chars().count() / 3).max(1);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|c| (edit_distance(input, c), c))
+        .filter(|&(d, _)| d <= max_distance)
+        .collect();
+    scored.sort();
+    scored.dedup_by_key(|&mut (_, c)| c);
+
+    let Some(&(best, _)) = scored.first() else {
+        return String::new();
+    };
+    let names: Vec<String> = scored
+        .iter()
+        .take_while(|&&(d, _)| d == best)
+        .take(3)
+        .map(|(_, c)| format!("'{}'", c))
+        .collect();
+    format!(" (did you mean {}?)", names.join(" or "))
+}
+
+/// Edit distance between `a` and `b` in chars, counting insertions,
+/// deletions, substitutions, and adjacent transpositions as one edit each
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,4 +122,24 @@ mod tests {
         assert_eq!(format_duration_ms(86_400_000), "1.00 days");
         assert_eq!(format_duration_ms(172_800_000), "2.00 days");
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("ulids", "ulid"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+        assert_eq!(edit_distance("base46", "base64"), 1);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let names = ["ulid", "uuid", "xid", "cuid", "cuid2"];
+        assert_eq!(did_you_mean("ulids", names), " (did you mean 'ulid'?)");
+        assert_eq!(
+            did_you_mean("uid", names),
+            " (did you mean 'cuid' or 'ulid' or 'uuid'?)"
+        );
+        assert_eq!(did_you_mean("snowflake", names), "");
+    }
 }
@@ -0,0 +1,36 @@
+#![cfg(feature = "cli")]
+
+use assert_cmd::Command;
+
+#[test]
+fn test_failed_check_still_writes_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("r.json");
+    Command::cargo_bin("idt")
+        .unwrap()
+        .args(["validate", "-o", "json", "--output-file"])
+        .arg(&path)
+        .args(["01ARZ3NDEKTSV4RRFFQ69G5FAV", "notanid"])
+        .assert()
+        .failure();
+
+    let report = std::fs::read_to_string(&path).unwrap();
+    assert!(report.contains("01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+    assert!(report.contains("notanid"));
+}
+
+#[test]
+fn test_argument_error_leaves_file_unchanged() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ids.txt");
+    std::fs::write(&path, "before\n").unwrap();
+    Command::cargo_bin("idt")
+        .unwrap()
+        .args(["gen", "snowflake", "--epoch", "nonsense", "--output-file"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("was left unchanged"));
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "before\n");
+}